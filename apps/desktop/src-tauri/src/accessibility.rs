@@ -0,0 +1,415 @@
+use crate::assets::AssetManifest;
+use crate::cache::{self, CacheCategory};
+use crate::hud::{UiElement, UiElementKind};
+use crate::{json_guard, spec};
+use crate::thumbnails::{self, parse_tint, Thumbnail};
+use crate::validation::Diagnostic;
+use image::{ImageFormat, Rgba, RgbaImage};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use tauri::AppHandle;
+
+/// WCAG 2.2 non-text contrast (1.4.11) for graphics players must make out
+const MIN_CONTRAST: f64 = 3.0;
+/// WCAG 2.2 target size minimum (2.5.8), in canvas pixels
+const MIN_TARGET_SIZE: f64 = 24.0;
+/// Pixels fainter than this don't count towards a texture's color
+const MIN_ALPHA: u8 = 128;
+/// Tags of entities a player has to recognize to play: threats, pickups and goals
+const CRITICAL_TAGS: &[&str] = &["enemy", "hazard", "collectible", "coin", "pickup", "goal", "exit", "checkpoint"];
+/// RGB distance under which two colors read as the same
+const MIN_COLOR_DISTANCE: f64 = 40.0;
+
+/// Color vision deficiencies previews can simulate
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ColorblindMode {
+    Protanopia,
+    Deuteranopia,
+    Tritanopia,
+}
+
+impl ColorblindMode {
+    pub const ALL: [ColorblindMode; 3] = [
+        ColorblindMode::Protanopia,
+        ColorblindMode::Deuteranopia,
+        ColorblindMode::Tritanopia,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            ColorblindMode::Protanopia => "protanopia",
+            ColorblindMode::Deuteranopia => "deuteranopia",
+            ColorblindMode::Tritanopia => "tritanopia",
+        }
+    }
+
+    /// Machado, Oliveira & Fernandes (2009) at full severity, applied to linear RGB
+    fn matrix(self) -> [[f64; 3]; 3] {
+        match self {
+            ColorblindMode::Protanopia => [
+                [0.152286, 1.052583, -0.204868],
+                [0.114503, 0.786281, 0.099216],
+                [-0.003882, -0.048116, 1.051998],
+            ],
+            ColorblindMode::Deuteranopia => [
+                [0.367322, 0.860646, -0.227968],
+                [0.280085, 0.672501, 0.047413],
+                [-0.011820, 0.042940, 0.968881],
+            ],
+            ColorblindMode::Tritanopia => [
+                [1.255528, -0.076749, -0.178779],
+                [-0.078411, 0.930809, 0.147602],
+                [0.004733, 0.691367, 0.303900],
+            ],
+        }
+    }
+}
+
+/// The accessibility section of a project's health report
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct AccessibilityReport {
+    pub checked_entities: usize,
+    pub findings: Vec<Diagnostic>,
+    /// Deficiencies under which critical entities become hard to tell apart; worth previewing
+    /// with `render_colorblind_preview`
+    pub colorblind_modes: Vec<ColorblindMode>,
+}
+
+fn to_linear(c: u8) -> f64 {
+    let c = c as f64 / 255.0;
+    if c <= 0.03928 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn to_srgb(c: f64) -> u8 {
+    let c = c.clamp(0.0, 1.0);
+    let c = if c <= 0.0031308 { c * 12.92 } else { 1.055 * c.powf(1.0 / 2.4) - 0.055 };
+    (c * 255.0).round() as u8
+}
+
+/// WCAG relative luminance of an sRGB color
+fn luminance(color: Rgba<u8>) -> f64 {
+    0.2126 * to_linear(color.0[0]) + 0.7152 * to_linear(color.0[1]) + 0.0722 * to_linear(color.0[2])
+}
+
+/// How `color` looks to someone with the given deficiency; alpha is kept
+pub fn simulate(mode: ColorblindMode, color: Rgba<u8>) -> Rgba<u8> {
+    let rgb = [to_linear(color.0[0]), to_linear(color.0[1]), to_linear(color.0[2])];
+    let m = mode.matrix();
+    let channel = |row: [f64; 3]| to_srgb(row[0] * rgb[0] + row[1] * rgb[1] + row[2] * rgb[2]);
+    Rgba([channel(m[0]), channel(m[1]), channel(m[2]), color.0[3]])
+}
+
+fn color_distance(a: Rgba<u8>, b: Rgba<u8>) -> f64 {
+    (0..3).map(|i| (a.0[i] as f64 - b.0[i] as f64).powi(2)).sum::<f64>().sqrt()
+}
+
+fn contrast_ratio(a: Rgba<u8>, b: Rgba<u8>) -> f64 {
+    let (a, b) = (luminance(a), luminance(b));
+    (a.max(b) + 0.05) / (a.min(b) + 0.05)
+}
+
+/// Average color of a texture's opaque pixels, or None for a fully transparent image
+fn average_color(image: &RgbaImage) -> Option<Rgba<u8>> {
+    let (mut sum, mut count) = ([0u64; 3], 0u64);
+    for pixel in image.pixels().filter(|p| p.0[3] >= MIN_ALPHA) {
+        for (total, channel) in sum.iter_mut().zip(pixel.0) {
+            *total += channel as u64;
+        }
+        count += 1;
+    }
+    (count > 0).then(|| Rgba([(sum[0] / count) as u8, (sum[1] / count) as u8, (sum[2] / count) as u8, 0xff]))
+}
+
+fn hex(color: Rgba<u8>) -> String {
+    format!("#{:02x}{:02x}{:02x}", color.0[0], color.0[1], color.0[2])
+}
+
+/// Entity lists with their spec paths, top level first
+fn entity_lists(spec: &Value) -> Vec<(String, &Vec<Value>)> {
+    let mut lists = Vec::new();
+    if let Some(entities) = spec.get("entities").and_then(Value::as_array) {
+        lists.push(("/entities".to_string(), entities));
+    }
+    for (i, scene) in spec.get("scenes").and_then(Value::as_array).into_iter().flatten().enumerate() {
+        if let Some(entities) = scene.get("entities").and_then(Value::as_array) {
+            lists.push((format!("/scenes/{}/entities", i), entities));
+        }
+    }
+    lists
+}
+
+/// A drawn sprite as the accessibility rules see it
+struct Sprite<'a> {
+    path: String,
+    name: &'a str,
+    texture: Option<&'a str>,
+    width: i64,
+    height: i64,
+    /// What the runtime draws: the texture's average color, or the tint for untextured sprites
+    color: Option<Rgba<u8>>,
+    /// First critical tag, if the entity is something players have to recognize
+    role: Option<&'a str>,
+}
+
+fn sprites<'a>(project_path: Option<&str>, spec: &'a Value) -> Vec<Sprite<'a>> {
+    let manifest = project_path.map(|p| AssetManifest::load(p).unwrap_or_default());
+    let mut textures: HashMap<&str, Option<Rgba<u8>>> = HashMap::new();
+    let mut sprites = Vec::new();
+
+    for (prefix, entities) in entity_lists(spec) {
+        for (i, entity) in entities.iter().enumerate() {
+            let Some(sprite) = entity.pointer("/components/sprite") else {
+                continue;
+            };
+            if sprite.get("visible") == Some(&Value::Bool(false)) {
+                continue;
+            }
+            let texture = sprite.get("texture").and_then(Value::as_str).filter(|t| !t.is_empty());
+            let color = match (texture, project_path, &manifest) {
+                (None, _, _) => Some(parse_tint(sprite.get("tint"))),
+                (Some(texture), Some(project_path), Some(manifest)) => *textures.entry(texture).or_insert_with(|| {
+                    thumbnails::load_texture(project_path, manifest, texture).and_then(|image| average_color(&image))
+                }),
+                // Without the project the texture can't be read, so its contrast is unknown
+                (Some(_), _, _) => None,
+            };
+            let tags: Vec<&str> = entity
+                .get("tags")
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+                .filter_map(Value::as_str)
+                .collect();
+            let size = |key: &str| sprite.get(key).and_then(Value::as_f64).unwrap_or(32.0).round() as i64;
+            sprites.push(Sprite {
+                path: format!("{}/{}/components/sprite", prefix, i),
+                name: entity.get("name").and_then(Value::as_str).unwrap_or_default(),
+                texture,
+                width: size("width"),
+                height: size("height"),
+                color,
+                role: CRITICAL_TAGS.iter().copied().find(|tag| tags.contains(tag)),
+            });
+        }
+    }
+    sprites
+}
+
+/// Sprites that blend into the background
+fn contrast(sprites: &[Sprite], background: Rgba<u8>) -> Vec<Diagnostic> {
+    sprites
+        .iter()
+        .filter_map(|sprite| {
+            let color = sprite.color?;
+            let ratio = contrast_ratio(color, background);
+            (ratio < MIN_CONTRAST).then(|| {
+                Diagnostic::warning(
+                    &sprite.path,
+                    format!(
+                        "'{}' ({}) has {:.1}:1 contrast against the {} background; aim for at least {:.0}:1",
+                        sprite.name,
+                        hex(color),
+                        ratio,
+                        hex(background),
+                        MIN_CONTRAST
+                    ),
+                )
+            })
+        })
+        .collect()
+}
+
+/// Critical entities that only differ from other kinds of entity by their color. Untextured
+/// sprites of the same size are plain boxes, so color is the only thing telling them apart.
+fn color_only(sprites: &[Sprite]) -> Vec<Diagnostic> {
+    let mut by_shape: BTreeMap<(i64, i64), Vec<&Sprite>> = BTreeMap::new();
+    for sprite in sprites.iter().filter(|s| s.texture.is_none()) {
+        by_shape.entry((sprite.width, sprite.height)).or_default().push(sprite);
+    }
+
+    let mut diagnostics = Vec::new();
+    for group in by_shape.values() {
+        let mut reported = Vec::new();
+        for sprite in group.iter().filter(|s| s.role.is_some()) {
+            let Some(other) = group.iter().find(|o| o.role != sprite.role && o.color != sprite.color) else {
+                continue;
+            };
+            let pair = (sprite.role, other.role);
+            if reported.contains(&pair) || reported.contains(&(pair.1, pair.0)) {
+                continue;
+            }
+            reported.push(pair);
+            diagnostics.push(Diagnostic::warning(
+                &sprite.path,
+                format!(
+                    "'{}' ({}) looks like '{}' apart from its color; give it a texture, shape or size of its own so color-blind players can tell them apart",
+                    sprite.name,
+                    sprite.role.unwrap_or_default(),
+                    other.name
+                ),
+            ));
+        }
+    }
+    diagnostics
+}
+
+/// Critical entities whose distinct colors collapse into one under a color vision deficiency
+fn colorblind_collisions(sprites: &[Sprite], mode: ColorblindMode) -> Vec<Diagnostic> {
+    let colored: Vec<(&Sprite, Rgba<u8>)> = sprites.iter().filter_map(|s| s.color.map(|c| (s, c))).collect();
+    let mut reported = Vec::new();
+    let mut diagnostics = Vec::new();
+    for (sprite, color) in colored.iter().filter(|(s, _)| s.role.is_some()) {
+        let Some((other, _)) = colored.iter().find(|(o, other_color)| {
+            o.role != sprite.role
+                && color_distance(*color, *other_color) >= MIN_COLOR_DISTANCE
+                && color_distance(simulate(mode, *color), simulate(mode, *other_color)) < MIN_COLOR_DISTANCE
+        }) else {
+            continue;
+        };
+        let pair = (sprite.role, other.role);
+        if reported.contains(&pair) || reported.contains(&(pair.1, pair.0)) {
+            continue;
+        }
+        reported.push(pair);
+        diagnostics.push(Diagnostic::warning(
+            &sprite.path,
+            format!(
+                "With {}, '{}' ({}) and '{}' look the same color; check the scene with a {} preview",
+                mode.name(),
+                sprite.name,
+                sprite.role.unwrap_or_default(),
+                other.name,
+                mode.name()
+            ),
+        ));
+    }
+    diagnostics
+}
+
+/// HUD buttons too small to hit reliably
+fn small_targets(spec: &Value) -> Vec<Diagnostic> {
+    let elements = spec.get("ui").and_then(Value::as_array).into_iter().flatten().enumerate();
+    elements
+        .filter_map(|(i, raw)| {
+            let element: UiElement = serde_json::from_value(raw.clone()).ok()?;
+            if element.kind != UiElementKind::Button || !element.visible {
+                return None;
+            }
+            // The runtime's default button size
+            let (width, height) = (element.width.unwrap_or(100.0), element.height.unwrap_or(24.0));
+            (width < MIN_TARGET_SIZE || height < MIN_TARGET_SIZE).then(|| {
+                Diagnostic::warning(
+                    format!("/ui/{}", i),
+                    format!(
+                        "Button '{}' is {}x{}px; make it at least {3}x{3}px so it's easy to hit",
+                        element.id, width, height, MIN_TARGET_SIZE
+                    ),
+                )
+            })
+        })
+        .collect()
+}
+
+/// Remapped controls with a single binding; the runtime defaults pair arrows with WASD
+fn single_bindings(spec: &Value) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    for (prefix, entities) in entity_lists(spec) {
+        for (i, entity) in entities.iter().enumerate() {
+            let Some(keys) = entity.pointer("/components/input/keys").and_then(Value::as_object) else {
+                continue;
+            };
+            for (action, binding) in keys {
+                let count = match binding {
+                    Value::String(_) => 1,
+                    Value::Array(bindings) => bindings.len(),
+                    _ => continue,
+                };
+                if count < 2 {
+                    diagnostics.push(Diagnostic::warning(
+                        format!("{}/{}/components/input/keys/{}", prefix, i, action),
+                        format!(
+                            "'{}' can only be done with one key; bind an alternative (e.g. arrows and WASD) for players who can't use it",
+                            action
+                        ),
+                    ));
+                }
+            }
+        }
+    }
+    diagnostics
+}
+
+/// Run the accessibility rules. With `project_path`, textures are read to judge their contrast;
+/// without it only untextured sprites are checked.
+pub fn audit(project_path: Option<&str>, spec: &Value) -> AccessibilityReport {
+    let sprites = sprites(project_path, spec);
+    let mut findings = contrast(&sprites, thumbnails::BACKGROUND);
+    findings.extend(color_only(&sprites));
+    let mut colorblind_modes = Vec::new();
+    for mode in ColorblindMode::ALL {
+        let collisions = colorblind_collisions(&sprites, mode);
+        if !collisions.is_empty() {
+            colorblind_modes.push(mode);
+        }
+        findings.extend(collisions);
+    }
+    findings.extend(small_targets(spec));
+    findings.extend(single_bindings(spec));
+    AccessibilityReport {
+        checked_entities: sprites.len(),
+        findings,
+        colorblind_modes,
+    }
+}
+
+/// Check a game spec for contrast, color-only cues, small targets and missing alternative controls
+#[tauri::command]
+pub async fn audit_accessibility(
+    game_spec_json: String,
+    project_path: Option<String>,
+) -> Result<AccessibilityReport, String> {
+    let spec: Value = json_guard::parse(game_spec_json.as_bytes(), "game spec", &json_guard::SPEC_LIMITS)?;
+    Ok(audit(project_path.as_deref(), &spec))
+}
+
+/// Render a scene thumbnail as seen with a color vision deficiency, next to the regular
+/// thumbnails in the cache
+#[tauri::command]
+pub async fn render_colorblind_preview(
+    app: AppHandle,
+    project_path: String,
+    scene: Option<String>,
+    mode: ColorblindMode,
+    width: Option<u32>,
+) -> Result<Thumbnail, String> {
+    let game_spec = spec::load_project_spec(&project_path)?;
+    if let Some(scene_id) = scene.as_deref().filter(|id| !spec::scene_exists(&game_spec, id)) {
+        return Err(format!("Scene '{}' not found", scene_id));
+    }
+
+    let width = width.unwrap_or(thumbnails::DEFAULT_THUMBNAIL_WIDTH).clamp(16, 2048);
+    let mut image = thumbnails::render_scene(Some(&project_path), &game_spec, scene.as_deref(), width);
+    for pixel in image.pixels_mut() {
+        *pixel = simulate(mode, *pixel);
+    }
+
+    let dir = cache::dir(&app, CacheCategory::Thumbnails)?.join(cache::project_key(&project_path));
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create thumbnail directory: {}", e))?;
+    let path = dir.join(format!("{}.{}.png", thumbnails::scene_file_stem(scene.as_deref()), mode.name()));
+    image
+        .save_with_format(&path, ImageFormat::Png)
+        .map_err(|e| format!("Failed to write preview: {}", e))?;
+
+    Ok(Thumbnail {
+        path: path.to_string_lossy().to_string(),
+        width: image.width(),
+        height: image.height(),
+    })
+}
@@ -1,10 +1,28 @@
+use crate::ai_providers::{self, ChatReply, ChatRequest, HttpRequest, Provider, ProviderSettings};
+use crate::components::ComponentRegistry;
+use crate::context_watchdog::{self, ContextWatchdogState};
+use crate::error::AppError;
+use crate::settings::AppSettings;
+use crate::{ai_context, feedback, health, palette, prompt_history, spec, thumbnails};
+use futures::StreamExt;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
-use std::sync::Arc;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+use tauri::Emitter;
 use tokio::sync::Mutex;
 
-const ANTHROPIC_API_URL: &str = "https://api.anthropic.com/v1/messages";
-const MODEL: &str = "claude-sonnet-4-20250514";
+/// Tool round-trips allowed per message before giving up
+const MAX_TOOL_ROUNDS: usize = 8;
+/// Event carrying each piece of a streamed reply's text as it arrives
+pub const STREAM_CHUNK_EVENT: &str = "ai-stream-chunk";
+/// Event sent once when a streamed request finishes, fails or is cancelled
+pub const STREAM_DONE_EVENT: &str = "ai-stream-done";
+/// How often a stream waiting on the network checks whether it was cancelled
+const CANCEL_POLL: Duration = Duration::from_millis(100);
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Message {
@@ -12,65 +30,181 @@ pub struct Message {
     pub content: String,
 }
 
-#[derive(Debug, Serialize)]
-struct AnthropicRequest {
-    model: String,
-    max_tokens: u32,
-    system: String,
-    messages: Vec<Message>,
+#[derive(Debug, Serialize, Clone)]
+pub struct AIResponse {
+    pub content: String,
+    pub success: bool,
+    pub error: Option<AppError>,
+    /// Prompt history entry for this request, to record whether its result was applied
+    pub prompt_id: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
-struct AnthropicResponse {
-    content: Vec<ContentBlock>,
-    #[serde(default)]
-    #[allow(dead_code)]
-    stop_reason: Option<String>,
+/// Where a streamed reply goes: `on_text` gets each text delta, and setting `cancel` aborts the
+/// request at the next chunk or poll
+pub struct StreamSink<'a> {
+    pub on_text: &'a (dyn Fn(&str) + Send + Sync),
+    pub cancel: &'a AtomicBool,
 }
 
-#[derive(Debug, Deserialize)]
-struct ContentBlock {
-    #[serde(rename = "type")]
-    content_type: String,
-    text: Option<String>,
+/// A streamed response rebuilt from its events, so tool rounds work the same as without streaming
+#[derive(Default)]
+struct StreamedReply {
+    response: ChatReply,
+    /// Tool input JSON per content block, which arrives in fragments
+    partial_json: Vec<String>,
 }
 
+impl StreamedReply {
+    /// Apply one server-sent event; true once the message is complete
+    fn apply(&mut self, event: &str, sink: &StreamSink<'_>) -> Result<bool, AppError> {
+        let data = event
+            .lines()
+            .filter_map(|line| line.strip_prefix("data:"))
+            .map(str::trim_start)
+            .collect::<Vec<_>>()
+            .join("\n");
+        if data.is_empty() {
+            return Ok(false);
+        }
+        let event: Value =
+            serde_json::from_str(&data).map_err(|e| AppError::from(format!("Failed to parse stream event: {}", e)))?;
+        let index = event.get("index").and_then(Value::as_u64).unwrap_or(0) as usize;
+        match event.get("type").and_then(Value::as_str) {
+            Some("content_block_start") => {
+                let block = serde_json::from_value(event["content_block"].clone())
+                    .map_err(|e| AppError::from(format!("Failed to parse stream event: {}", e)))?;
+                self.response.content.push(block);
+                self.partial_json.push(String::new());
+            }
+            Some("content_block_delta") => {
+                let delta = &event["delta"];
+                match delta.get("type").and_then(Value::as_str) {
+                    Some("text_delta") => {
+                        let text = delta.get("text").and_then(Value::as_str).unwrap_or_default();
+                        if let Some(block) = self.response.content.get_mut(index) {
+                            block.text.get_or_insert_with(String::new).push_str(text);
+                        }
+                        (sink.on_text)(text);
+                    }
+                    Some("input_json_delta") => {
+                        if let Some(json) = self.partial_json.get_mut(index) {
+                            json.push_str(delta.get("partial_json").and_then(Value::as_str).unwrap_or_default());
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            Some("content_block_stop") => {
+                if let (Some(block), Some(json)) = (self.response.content.get_mut(index), self.partial_json.get(index)) {
+                    if !json.is_empty() {
+                        block.input = Some(
+                            serde_json::from_str(json)
+                                .map_err(|e| AppError::from(format!("Failed to parse tool input: {}", e)))?,
+                        );
+                    }
+                }
+            }
+            Some("message_delta") => {
+                if let Some(reason) = event.pointer("/delta/stop_reason").and_then(Value::as_str) {
+                    self.response.stop_reason = Some(reason.to_string());
+                }
+            }
+            Some("message_stop") => return Ok(true),
+            Some("error") => {
+                let status = match event.pointer("/error/type").and_then(Value::as_str) {
+                    Some("rate_limit_error") => 429,
+                    Some("overloaded_error") => 529,
+                    _ => 500,
+                };
+                let detail = event.pointer("/error/message").and_then(Value::as_str).unwrap_or_default();
+                return Err(AppError::Api { status, detail: detail.to_string() });
+            }
+            _ => {}
+        }
+        Ok(false)
+    }
+}
+
+/// Cancel flags of the streamed requests in flight, by stream id
+#[derive(Default)]
+pub struct AIStreamState(std::sync::Mutex<HashMap<String, Arc<AtomicBool>>>);
+
 #[derive(Debug, Serialize, Clone)]
-pub struct AIResponse {
-    pub content: String,
-    pub success: bool,
-    pub error: Option<String>,
+#[serde(rename_all = "camelCase")]
+struct StreamChunk<'a> {
+    stream_id: &'a str,
+    text: &'a str,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct StreamDone<'a> {
+    stream_id: &'a str,
+    /// The same response the command resolves with; None when it failed before reaching the API
+    response: Option<&'a AIResponse>,
+    error: Option<&'a AppError>,
 }
 
 pub struct AIClient {
-    client: Client,
+    /// Built on first use; loading the TLS roots is slow on a cold disk
+    client: OnceLock<Client>,
     api_key: Option<String>,
+    provider: ProviderSettings,
 }
 
 impl AIClient {
     pub fn new() -> Self {
         Self {
-            client: Client::new(),
+            client: OnceLock::new(),
             api_key: std::env::var("ANTHROPIC_API_KEY").ok(),
+            provider: ProviderSettings::default(),
         }
     }
 
-    pub fn set_api_key(&mut self, key: String) {
-        self.api_key = Some(key);
+    /// The HTTP client, creating it if this is the first request
+    pub fn http(&self) -> &Client {
+        self.client.get_or_init(Client::new)
+    }
+
+    pub fn set_api_key(&mut self, key: Option<String>) {
+        self.api_key = key;
     }
 
     pub fn has_api_key(&self) -> bool {
-        self.api_key.is_some()
+        self.api_key.is_some() || !self.provider.kind.needs_api_key()
+    }
+
+    pub fn provider(&self) -> &ProviderSettings {
+        &self.provider
+    }
+
+    /// Switch providers; `api_key` replaces the previous provider's key
+    pub fn set_provider(&mut self, provider: ProviderSettings, api_key: Option<String>) {
+        self.provider = provider;
+        self.api_key = api_key;
+    }
+
+    /// The selected provider, ready to authenticate
+    fn backend(&self) -> Result<Box<dyn Provider>, AppError> {
+        if !self.has_api_key() {
+            return Err(AppError::ApiKeyMissing);
+        }
+        Ok(ai_providers::build(&self.provider, self.api_key.as_deref()))
     }
 
     pub async fn send_message(
         &self,
         messages: Vec<Message>,
         game_context: &str,
-    ) -> Result<String, String> {
-        let api_key = self.api_key.as_ref().ok_or("API key not set")?;
+        custom_components: &str,
+        project_notes: &str,
+        spec: Option<&Value>,
+        snapshot: Option<&str>,
+        stream: Option<&StreamSink<'_>>,
+    ) -> Result<String, AppError> {
+        let provider = self.backend()?;
 
-        let system_prompt = format!(
+        let mut system_prompt = format!(
             r#"You are an AI game development assistant for PromptPlay, a 2D & 3D game engine.
 You help users create and modify games by editing the game specification JSON.
 
@@ -83,60 +217,239 @@ When the user asks to modify the game, respond with:
 
 Important guidelines:
 - Preserve all existing entities unless explicitly asked to remove them
-- Use realistic coordinates (canvas is typically 800x600)
+- Every entity has a stable "id"; never change existing ids, omit "id" on new entities, and reference other entities (camera followTarget, aiBehavior targetEntity) by id
+- config.canvas {{width, height}} is the visible viewport (default 800x600) and config.worldBounds {{x, y, width, height}} the playable area (defaults to the canvas); keep entity positions inside the world bounds
 - Common entity types: player (with input component), platform (static), enemy (with aiBehavior), coin (collectible)
 - All entities need: transform (x, y, rotation, scaleX, scaleY), sprite (texture, width, height, tint)
 - Dynamic entities need: velocity (vx, vy), collider (type, width/height or radius)
+- Static colliders can also be "slope" (width, height, rise: left/right) for ramps or "polygon" with convex, clockwise vertices relative to the entity
+- Colliders may set material (default/ice/bouncy/sticky or a key of config.physicsMaterials), restitution and friction (0..1, override the material), and oneWay: true for jump-through platforms
 - Players need: input (moveSpeed, jumpForce)
 - Enemies can have: aiBehavior (type: patrol/chase/idle, speed, detectionRadius)
+- HUD elements go in the top-level "ui" array (screen space, not entities). Each has id, type (label/healthBar/button/minimap), anchor (topLeft/topCenter/topRight/centerLeft/center/centerRight/bottomLeft/bottomCenter/bottomRight), offsetX, offsetY, optional width/height/style
+- UI bindings: "gameState.score" (also highScore, lives, maxLives, level, timeRemaining, timeElapsed, combo, multiplier) or "entity.<name>.health" for health bars
+- A "minimap" element shows the baked minimap of its optional "scene" (default: top-level entities) with live player and collectible markers
+- Labels use text with a {{value}} placeholder, e.g. {{"id": "score", "type": "label", "anchor": "topLeft", "offsetX": 16, "offsetY": 16, "text": "Score: {{value}}", "binding": "gameState.score"}}
+- Buttons need an action: restart/pause/resume
+- Projectiles and waves use a spawner component (template: entity id, interval, lifetime, vx, vy, maxAlive); template entities aren't placed in the world. For fast spawners add a top-level "pooling" entry, e.g. {{"bullets": {{"template": "<id>", "size": 32, "maxSize": 64}}}}
 
 Be concise and helpful. If you can't fulfill a request, explain why and suggest alternatives."#,
             game_context
         );
 
-        let request = AnthropicRequest {
-            model: MODEL.to_string(),
+        if !custom_components.is_empty() {
+            system_prompt.push_str(&format!(
+                "\n\nThis project defines custom components (use them under \"components\" like built-ins):\n{}",
+                custom_components
+            ));
+        }
+
+        if !project_notes.is_empty() {
+            system_prompt.push_str("\n\n");
+            system_prompt.push_str(project_notes);
+        }
+
+        if spec.is_some() {
+            system_prompt.push_str(
+                "\n\nThe game context above is a summary, not the full spec. Use the get_entity, list_entities and get_spec_section tools to read anything you need before editing it.",
+            );
+        }
+
+        if snapshot.is_some() {
+            system_prompt.push_str(
+                "\n\nThe image attached to the latest message is a top-down snapshot of the current level layout; sprites without a texture are drawn as tinted boxes.",
+            );
+        }
+
+        // The snapshot rides along with the latest user message as an image block
+        let last_user = messages.iter().rposition(|m| m.role == "user");
+        let messages = messages
+            .into_iter()
+            .enumerate()
+            .map(|(i, m)| match snapshot {
+                Some(png) if Some(i) == last_user => json!({
+                    "role": m.role,
+                    "content": [
+                        { "type": "image", "source": { "type": "base64", "media_type": "image/png", "data": png } },
+                        { "type": "text", "text": m.content },
+                    ],
+                }),
+                _ => json!(m),
+            })
+            .collect();
+
+        let mut request = ChatRequest {
+            model: self.provider.model().to_string(),
             max_tokens: 4096,
             system: system_prompt,
             messages,
+            tools: spec.map(|_| ai_context::tool_definitions()).unwrap_or_default(),
+            stream: stream.is_some() && provider.streams(),
         };
 
-        let response = self
-            .client
-            .post(ANTHROPIC_API_URL)
-            .header("x-api-key", api_key)
-            .header("anthropic-version", "2023-06-01")
-            .header("content-type", "application/json")
-            .json(&request)
+        for _ in 0..MAX_TOOL_ROUNDS {
+            let result = match stream {
+                Some(sink) if request.stream => self.request_streaming(provider.as_ref(), &request, sink).await?,
+                // Providers without streaming still honour cancel, and the reply arrives as one chunk
+                Some(sink) => {
+                    let result = until_cancelled(sink.cancel, self.request(provider.as_ref(), &request)).await?;
+                    for text in result.content.iter().filter_map(|block| block.text.as_deref()) {
+                        (sink.on_text)(text);
+                    }
+                    result
+                }
+                None => self.request(provider.as_ref(), &request).await?,
+            };
+            let spec = match spec {
+                Some(spec) if result.stop_reason.as_deref() == Some("tool_use") => spec,
+                _ => {
+                    return Ok(result
+                        .content
+                        .into_iter()
+                        .filter_map(|block| {
+                            if block.content_type == "text" {
+                                block.text
+                            } else {
+                                None
+                            }
+                        })
+                        .collect::<Vec<_>>()
+                        .join(""));
+                }
+            };
+
+            let tool_results: Vec<Value> = result
+                .content
+                .iter()
+                .filter(|block| block.content_type == "tool_use")
+                .map(|block| {
+                    let input = block.input.clone().unwrap_or(Value::Null);
+                    let outcome = ai_context::run_tool(spec, block.name.as_deref().unwrap_or_default(), &input);
+                    json!({
+                        "type": "tool_result",
+                        "tool_use_id": block.id,
+                        "is_error": outcome.is_err(),
+                        "content": outcome.unwrap_or_else(|e| e),
+                    })
+                })
+                .collect();
+            request.messages.push(json!({ "role": "assistant", "content": result.content }));
+            request.messages.push(json!({ "role": "user", "content": tool_results }));
+        }
+
+        Err(AppError::ToolLoop { rounds: MAX_TOOL_ROUNDS })
+    }
+
+    /// Single-turn request with a task-specific system prompt; returns the text reply
+    pub async fn complete(&self, system: String, prompt: String) -> Result<String, AppError> {
+        let provider = self.backend()?;
+        let request = ChatRequest {
+            model: self.provider.model().to_string(),
+            max_tokens: 4096,
+            system,
+            messages: vec![json!({ "role": "user", "content": prompt })],
+            tools: Vec::new(),
+            stream: false,
+        };
+        let result = self.request(provider.as_ref(), &request).await?;
+        Ok(result
+            .content
+            .into_iter()
+            .filter_map(|block| block.text)
+            .collect::<Vec<_>>()
+            .join(""))
+    }
+
+    /// Send a request and check its status
+    async fn send(&self, request: HttpRequest) -> Result<reqwest::Response, AppError> {
+        let mut builder = match &request.body {
+            Some(body) => self.http().post(&request.url).json(body),
+            None => self.http().get(&request.url),
+        };
+        for (name, value) in &request.headers {
+            builder = builder.header(*name, value);
+        }
+        let response = builder
             .send()
             .await
-            .map_err(|e| format!("Request failed: {}", e))?;
+            .map_err(|e| AppError::Network { detail: e.to_string() })?;
 
         if !response.status().is_success() {
-            let status = response.status();
-            let error_text = response.text().await.unwrap_or_default();
-            return Err(format!("API error {}: {}", status, error_text));
+            let status = response.status().as_u16();
+            let detail = response.text().await.unwrap_or_default();
+            return Err(AppError::Api { status, detail });
         }
+        Ok(response)
+    }
 
-        let result: AnthropicResponse = response
+    async fn fetch_json(&self, request: HttpRequest) -> Result<Value, AppError> {
+        self.send(request)
+            .await?
             .json()
             .await
-            .map_err(|e| format!("Failed to parse response: {}", e))?;
+            .map_err(|e| AppError::from(format!("Failed to parse response: {}", e)))
+    }
 
-        let content = result
-            .content
-            .into_iter()
-            .filter_map(|block| {
-                if block.content_type == "text" {
-                    block.text
-                } else {
-                    None
+    async fn request(&self, provider: &dyn Provider, request: &ChatRequest) -> Result<ChatReply, AppError> {
+        provider.parse_chat(self.fetch_json(provider.chat_request(request)).await?)
+    }
+
+    /// Models the provider offers, as reported by its API
+    pub async fn list_models(&self, provider: &ProviderSettings, api_key: Option<&str>) -> Result<Vec<String>, AppError> {
+        let provider = ai_providers::build(provider, api_key);
+        let mut models = provider.parse_models(self.fetch_json(provider.models_request()).await?);
+        models.sort();
+        models.dedup();
+        Ok(models)
+    }
+
+    /// Like `request`, passing text to the sink as the server streams it
+    async fn request_streaming(
+        &self,
+        provider: &dyn Provider,
+        request: &ChatRequest,
+        sink: &StreamSink<'_>,
+    ) -> Result<ChatReply, AppError> {
+        let mut body = self.send(provider.chat_request(request)).await?.bytes_stream();
+        let mut buffer: Vec<u8> = Vec::new();
+        let mut reply = StreamedReply::default();
+        loop {
+            if sink.cancel.load(Ordering::Relaxed) {
+                return Err(AppError::Cancelled);
+            }
+            let chunk = match tokio::time::timeout(CANCEL_POLL, body.next()).await {
+                Err(_) => continue,
+                Ok(None) => break,
+                Ok(Some(chunk)) => chunk.map_err(|e| AppError::Network { detail: e.to_string() })?,
+            };
+            buffer.extend_from_slice(&chunk);
+            // Events end with a blank line; a chunk may hold several or stop mid-event
+            while let Some(end) = buffer.windows(2).position(|w| w == b"\n\n") {
+                let event: Vec<u8> = buffer.drain(..end + 2).collect();
+                if reply.apply(&String::from_utf8_lossy(&event), sink)? {
+                    return Ok(reply.response);
                 }
-            })
-            .collect::<Vec<_>>()
-            .join("");
+            }
+        }
+        Err(AppError::Network {
+            detail: "The response stream ended before the reply was complete".to_string(),
+        })
+    }
+}
 
-        Ok(content)
+/// Await `work`, giving up with `Cancelled` once `cancel` is set
+async fn until_cancelled<T>(
+    cancel: &AtomicBool,
+    work: impl std::future::Future<Output = Result<T, AppError>>,
+) -> Result<T, AppError> {
+    let mut work = std::pin::pin!(work);
+    loop {
+        if cancel.load(Ordering::Relaxed) {
+            return Err(AppError::Cancelled);
+        }
+        if let Ok(result) = tokio::time::timeout(CANCEL_POLL, work.as_mut()).await {
+            return result;
+        }
     }
 }
 
@@ -149,51 +462,283 @@ impl Default for AIClientState {
     }
 }
 
+/// Project-specific guidance for the system prompt: open playtester feedback and the palette
+fn project_notes(project_path: &str) -> String {
+    let mut sections = Vec::new();
+    let feedback = feedback::describe_open_for_prompt(project_path);
+    if !feedback.is_empty() {
+        sections.push(format!(
+            "Open playtester feedback (scene, position, note); consider it when it relates to the request:\n{}",
+            feedback
+        ));
+    }
+    let palette = palette::describe_for_prompt(project_path);
+    if !palette.is_empty() {
+        sections.push(format!(
+            "Project palette; pick sprite tints for new entities from it so they match the art:\n{}",
+            palette
+        ));
+    }
+    sections.join("\n\n")
+}
+
 // Tauri commands
 #[tauri::command]
 pub async fn ai_send_message(
+    app: tauri::AppHandle,
+    window: tauri::Window,
     state: tauri::State<'_, AIClientState>,
+    watchdog: tauri::State<'_, ContextWatchdogState>,
     messages: Vec<Message>,
     game_context: String,
-) -> Result<AIResponse, String> {
-    let client = state.0.lock().await;
+    project_path: Option<String>,
+    spec_context: Option<ai_context::SpecContext>,
+    conversation_id: Option<String>,
+    stream_id: Option<String>,
+    streams: tauri::State<'_, AIStreamState>,
+) -> Result<AIResponse, AppError> {
+    // With a stream id the reply arrives as STREAM_CHUNK_EVENTs while the command is pending,
+    // followed by one STREAM_DONE_EVENT, and ai_cancel_message can stop it. Both go only to the
+    // window that asked, so other project windows don't pick up its stream.
+    let cancel = match &stream_id {
+        Some(id) => {
+            let flag = Arc::new(AtomicBool::new(false));
+            let mut streams = streams.0.lock()?;
+            if streams.contains_key(id) {
+                return Err(AppError::from(format!("Stream {} is already running", id)));
+            }
+            streams.insert(id.clone(), flag.clone());
+            Some(flag)
+        }
+        None => None,
+    };
+    let on_text = |text: &str| {
+        if let Some(stream_id) = &stream_id {
+            let _ = app.emit_to(window.label(), STREAM_CHUNK_EVENT, StreamChunk { stream_id, text });
+        }
+    };
+    let sink = cancel.as_deref().map(|cancel| StreamSink { on_text: &on_text, cancel });
+
+    let result: Result<AIResponse, AppError> = async {
+        let client = state.0.lock().await;
+        let ai_context::SpecContext { game_spec, token_budget, context_scope, attach_snapshot, include_health } =
+            spec_context.unwrap_or_default();
 
-    if !client.has_api_key() {
-        return Ok(AIResponse {
-            content: String::new(),
-            success: false,
-            error: Some("API key not configured. Set ANTHROPIC_API_KEY environment variable or configure in settings.".to_string()),
-        });
+        let custom_components = project_path
+            .as_deref()
+            .and_then(|path| ComponentRegistry::load(path).ok())
+            .map(|registry| registry.describe_custom_for_prompt())
+            .unwrap_or_default();
+        let mut project_notes = project_path.as_deref().map(project_notes).unwrap_or_default();
+        let health_report = match project_path.clone().filter(|_| include_health) {
+            Some(path) => health::report_in_background(path).await.ok(),
+            None => None,
+        };
+        if let Some(report) = health_report {
+            if !project_notes.is_empty() {
+                project_notes.push_str("\n\n");
+            }
+            project_notes.push_str(&format!(
+                "Project health; mention relevant problems when they affect the request:\n{}",
+                health::describe_for_prompt(&report)
+            ));
+        }
+
+        // A scope without an in-editor spec falls back to the saved project spec
+        let game_spec = match (game_spec, &context_scope, project_path.as_deref()) {
+            (None, Some(_), Some(path)) => Some(spec::load_project_spec(path)?),
+            (game_spec, _, _) => game_spec,
+        };
+
+        // With the spec passed separately, the prompt gets a bounded summary of the requested scope
+        // instead of the whole document and the model fetches anything else through tools
+        let game_context = match &game_spec {
+            Some(spec) => {
+                let scope = context_scope.unwrap_or(ai_context::ContextScope::Project);
+                let summary = ai_context::assemble(spec, &scope, token_budget.unwrap_or(ai_context::DEFAULT_TOKEN_BUDGET))?;
+                format!("{}\n\n=== Spec Summary ===\n{}", game_context, summary.summary)
+            }
+            None => game_context,
+        };
+
+        let usage = context_watchdog::measure(
+            conversation_id.as_deref(),
+            &[&game_context, &custom_components, &project_notes],
+            &messages,
+        );
+        context_watchdog::check(&app, window.label(), &watchdog, &usage);
+
+        let snapshot = match &game_spec {
+            Some(spec) if attach_snapshot => Some(thumbnails::encode_png(&thumbnails::render_scene(
+                project_path.as_deref(),
+                spec,
+                thumbnails::default_scene(spec),
+                thumbnails::DEFAULT_THUMBNAIL_WIDTH,
+            ))?),
+            _ => None,
+        };
+
+        if !client.has_api_key() {
+            return Ok(AIResponse {
+                content: String::new(),
+                success: false,
+                error: Some(AppError::ApiKeyMissing),
+                prompt_id: None,
+            });
+        }
+
+        // History is a convenience; failing to write it must not block the request
+        let prompt_id = messages
+            .iter()
+            .rfind(|m| m.role == "user")
+            .and_then(|m| prompt_history::record(&app, &m.content, project_path.as_deref()).ok());
+
+        match client
+            .send_message(
+                messages,
+                &game_context,
+                &custom_components,
+                &project_notes,
+                game_spec.as_ref(),
+                snapshot.as_deref(),
+                sink.as_ref(),
+            )
+            .await
+        {
+            Ok(content) => Ok(AIResponse {
+                content,
+                success: true,
+                error: None,
+                prompt_id,
+            }),
+            Err(e) => {
+                if let Some(id) = prompt_id.as_ref().filter(|_| e != AppError::Cancelled) {
+                    let _ = prompt_history::record_failure(&app, id, &e.to_string());
+                }
+                Ok(AIResponse {
+                    content: String::new(),
+                    success: false,
+                    error: Some(e),
+                    prompt_id,
+                })
+            }
+        }
     }
+    .await;
 
-    match client.send_message(messages, &game_context).await {
-        Ok(content) => Ok(AIResponse {
-            content,
-            success: true,
-            error: None,
-        }),
-        Err(e) => Ok(AIResponse {
-            content: String::new(),
-            success: false,
-            error: Some(e),
-        }),
+    if let Some(stream_id) = &stream_id {
+        if let Ok(mut streams) = streams.0.lock() {
+            streams.remove(stream_id);
+        }
+        let done = StreamDone {
+            stream_id,
+            response: result.as_ref().ok(),
+            error: result.as_ref().err(),
+        };
+        let _ = app.emit_to(window.label(), STREAM_DONE_EVENT, done);
     }
+    result
 }
 
+/// Stop a streamed `ai_send_message`; it resolves with a `cancelled` error. False when no
+/// request with that stream id is running.
+#[tauri::command]
+pub async fn ai_cancel_message(streams: tauri::State<'_, AIStreamState>, stream_id: String) -> Result<bool, AppError> {
+    let streams = streams.0.lock()?;
+    let Some(cancel) = streams.get(&stream_id) else {
+        return Ok(false);
+    };
+    cancel.store(true, Ordering::Relaxed);
+    Ok(true)
+}
+
+/// Use a key for this session only; `ai_save_api_key` also keeps it for later launches
 #[tauri::command]
 pub async fn ai_set_api_key(
     state: tauri::State<'_, AIClientState>,
     api_key: String,
-) -> Result<(), String> {
+) -> Result<(), AppError> {
     let mut client = state.0.lock().await;
-    client.set_api_key(api_key);
+    client.set_api_key(Some(api_key));
     Ok(())
 }
 
+/// Save the selected provider's API key in the OS keychain and start using it. The key is loaded
+/// from there at startup and never sent back to the frontend.
+#[tauri::command]
+pub async fn ai_save_api_key(
+    state: tauri::State<'_, AIClientState>,
+    api_key: String,
+) -> Result<(), AppError> {
+    let api_key = api_key.trim();
+    if api_key.is_empty() {
+        return Err(AppError::from("API key is empty"));
+    }
+    let mut client = state.0.lock().await;
+    ai_providers::store_api_key(client.provider().kind, api_key)?;
+    client.set_api_key(Some(api_key.to_string()));
+    Ok(())
+}
+
+/// Remove the selected provider's key from the keychain. Returns whether a key is still
+/// available, e.g. from the provider's environment variable.
+#[tauri::command]
+pub async fn ai_delete_api_key(state: tauri::State<'_, AIClientState>) -> Result<bool, AppError> {
+    let mut client = state.0.lock().await;
+    let kind = client.provider().kind;
+    ai_providers::delete_api_key(kind)?;
+    client.set_api_key(ai_providers::load_api_key(kind));
+    Ok(client.has_api_key())
+}
+
 #[tauri::command]
 pub async fn ai_check_api_key(
     state: tauri::State<'_, AIClientState>,
-) -> Result<bool, String> {
+) -> Result<bool, AppError> {
     let client = state.0.lock().await;
     Ok(client.has_api_key())
 }
+
+#[tauri::command]
+pub async fn ai_get_provider(state: tauri::State<'_, AIClientState>) -> Result<ProviderSettings, AppError> {
+    Ok(state.0.lock().await.provider().clone())
+}
+
+/// Switch the AI provider for every later request and remember it. `api_key` is stored in the
+/// keychain under that provider (an empty one removes it); when omitted, the provider's saved key
+/// or environment variable is used.
+#[tauri::command]
+pub async fn ai_set_provider(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, AIClientState>,
+    provider: ProviderSettings,
+    api_key: Option<String>,
+) -> Result<(), AppError> {
+    if let Some(key) = &api_key {
+        ai_providers::store_api_key(provider.kind, key)?;
+    }
+    let mut app_settings = AppSettings::load(&app);
+    app_settings.ai_provider = Some(provider.clone());
+    app_settings.save(&app)?;
+
+    let api_key = ai_providers::load_api_key(provider.kind);
+    state.0.lock().await.set_provider(provider, api_key);
+    Ok(())
+}
+
+/// Models available from a provider, defaulting to the selected one; lets the settings screen
+/// offer a choice before switching
+#[tauri::command]
+pub async fn ai_list_models(
+    state: tauri::State<'_, AIClientState>,
+    provider: Option<ProviderSettings>,
+) -> Result<Vec<String>, AppError> {
+    let client = state.0.lock().await;
+    let provider = provider.unwrap_or_else(|| client.provider().clone());
+    let api_key = if provider.kind == client.provider().kind {
+        client.api_key.clone()
+    } else {
+        ai_providers::load_api_key(provider.kind)
+    };
+    client.list_models(&provider, api_key.as_deref()).await
+}
@@ -0,0 +1,399 @@
+use crate::{entity_locks, spec};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+
+/// Default prompt budget for the spec summary, in tokens
+pub const DEFAULT_TOKEN_BUDGET: usize = 4000;
+/// Rough chars-per-token ratio for JSON-heavy English text
+const CHARS_PER_TOKEN: usize = 4;
+const MAX_LIST_LIMIT: usize = 200;
+/// Components that mark an entity as worth listing before the crowd of platforms and coins
+const KEY_COMPONENTS: &[&str] = &["input", "camera", "aiBehavior", "health", "dialogue"];
+
+#[derive(Debug, Serialize)]
+pub struct SpecSummary {
+    pub summary: String,
+    pub estimated_tokens: usize,
+    pub entity_count: usize,
+    /// Entities described individually; the rest are only counted
+    pub listed_entities: usize,
+    pub truncated: bool,
+}
+
+pub fn estimate_tokens(text: &str) -> usize {
+    text.len().div_ceil(CHARS_PER_TOKEN)
+}
+
+fn compact(value: &Value, max_chars: usize) -> String {
+    let text = value.to_string();
+    if text.len() <= max_chars {
+        return text;
+    }
+    let mut end = max_chars;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    format!("{}…", &text[..end])
+}
+
+fn name_of(entity: &Value) -> &str {
+    entity.get("name").and_then(Value::as_str).unwrap_or("?")
+}
+
+fn position(entity: &Value) -> Option<(f64, f64)> {
+    let transform = entity.get("components")?.get("transform")?;
+    Some((transform.get("x")?.as_f64()?, transform.get("y")?.as_f64()?))
+}
+
+fn tags(entity: &Value) -> Vec<&str> {
+    entity
+        .get("tags")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_str)
+        .collect()
+}
+
+fn component_names(entity: &Value) -> Vec<&str> {
+    entity
+        .get("components")
+        .and_then(Value::as_object)
+        .map(|components| components.keys().map(String::as_str).collect())
+        .unwrap_or_default()
+}
+
+/// One line per entity: name, id, scene, position, tags and component names; locked entities are
+/// marked so the model leaves them alone
+fn describe_entity(scene: Option<&str>, entity: &Value) -> String {
+    let mut line = format!("- {}", name_of(entity));
+    if let Some(id) = entity.get("id").and_then(Value::as_str) {
+        line.push_str(&format!(" [{}]", id));
+    }
+    if let Some(scene) = scene {
+        line.push_str(&format!(" scene={}", scene));
+    }
+    if let Some((x, y)) = position(entity) {
+        line.push_str(&format!(" at ({:.0}, {:.0})", x, y));
+    }
+    let tags = tags(entity);
+    if !tags.is_empty() {
+        line.push_str(&format!(" tags: {}", tags.join(", ")));
+    }
+    line.push_str(&format!("; components: {}", component_names(entity).join(", ")));
+    if entity_locks::is_locked(entity) {
+        line.push_str(" (locked: keep unchanged)");
+    }
+    line
+}
+
+fn is_key_entity(entity: &Value) -> bool {
+    let components = component_names(entity);
+    KEY_COMPONENTS.iter().any(|key| components.contains(key)) || tags(entity).contains(&"player")
+}
+
+fn counts<'a>(items: impl Iterator<Item = &'a str>) -> String {
+    let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+    for item in items {
+        *counts.entry(item).or_default() += 1;
+    }
+    let mut sorted: Vec<_> = counts.into_iter().collect();
+    sorted.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+    sorted
+        .iter()
+        .map(|(name, count)| format!("{} {}", name, count))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Compact structured summary of the spec that fits in `token_budget`. Game-wide data is always
+/// included; individual entities are listed key entities first until the budget runs out.
+pub fn summarize(spec: &Value, token_budget: usize) -> SpecSummary {
+    let budget_chars = token_budget.max(200) * CHARS_PER_TOKEN;
+    let mut lines = Vec::new();
+
+    let metadata = spec.get("metadata").cloned().unwrap_or(Value::Null);
+    let title = metadata
+        .get("title")
+        .or_else(|| metadata.get("name"))
+        .and_then(Value::as_str)
+        .unwrap_or("Untitled");
+    let genre = metadata.get("genre").and_then(Value::as_str).unwrap_or("unspecified");
+    lines.push(format!(
+        "Game: {} ({}) spec {}",
+        title,
+        genre,
+        spec.get("version").and_then(Value::as_str).unwrap_or("?")
+    ));
+    if let Some(description) = metadata.get("description").and_then(Value::as_str) {
+        lines.push(format!("Description: {}", description));
+    }
+    for key in ["config", "settings"] {
+        if let Some(value) = spec.get(key) {
+            lines.push(format!("{}: {}", key, compact(value, budget_chars / 8)));
+        }
+    }
+    if let Some(systems) = spec.get("systems").and_then(Value::as_array) {
+        let systems: Vec<&str> = systems.iter().filter_map(Value::as_str).collect();
+        lines.push(format!("Systems: {}", systems.join(", ")));
+    }
+
+    let entities = spec::entities_with_scene(spec);
+    let scenes: Vec<&Value> = spec.get("scenes").and_then(Value::as_array).into_iter().flatten().collect();
+    if !scenes.is_empty() {
+        let described: Vec<String> = scenes
+            .iter()
+            .map(|scene| {
+                format!(
+                    "{} ({} entities)",
+                    scene.get("id").and_then(Value::as_str).unwrap_or("?"),
+                    scene.get("entities").and_then(Value::as_array).map_or(0, Vec::len)
+                )
+            })
+            .collect();
+        lines.push(format!("Scenes: {}", described.join(", ")));
+        if let Some(active) = spec.get("activeScene").and_then(Value::as_str) {
+            lines.push(format!("Active scene: {}", active));
+        }
+    }
+    if let Some(ui) = spec.get("ui").and_then(Value::as_array) {
+        lines.push(format!("UI elements: {}", counts(ui.iter().filter_map(|e| e.get("type").and_then(Value::as_str)))));
+    }
+    if let Some(tilemap) = spec.get("tilemap") {
+        lines.push(format!(
+            "Tilemap: {}x{} tiles of {}px, {} layers",
+            tilemap.get("width").and_then(Value::as_u64).unwrap_or(0),
+            tilemap.get("height").and_then(Value::as_u64).unwrap_or(0),
+            tilemap.get("tileSize").and_then(Value::as_u64).unwrap_or(0),
+            tilemap.get("layers").and_then(Value::as_array).map_or(0, Vec::len)
+        ));
+    }
+    lines.push(format!("Entities: {}", entities.len()));
+    lines.push(format!("By tag: {}", counts(entities.iter().flat_map(|(_, e)| tags(e)))));
+    lines.push(format!("By component: {}", counts(entities.iter().flat_map(|(_, e)| component_names(e)))));
+    lines.push("Entity list:".to_string());
+
+    let mut used = lines.iter().map(|line| line.len() + 1).sum::<usize>();
+    let (key, rest): (Vec<_>, Vec<_>) = entities.iter().partition(|(_, e)| is_key_entity(e));
+    let mut listed = 0;
+    for &(scene, entity) in key.into_iter().chain(rest) {
+        let line = describe_entity(scene, entity);
+        if used + line.len() + 1 > budget_chars {
+            break;
+        }
+        used += line.len() + 1;
+        lines.push(line);
+        listed += 1;
+    }
+    let truncated = listed < entities.len();
+    if truncated {
+        lines.push(format!(
+            "({} more entities not listed; use the list_entities and get_entity tools for details)",
+            entities.len() - listed
+        ));
+    }
+
+    let summary = lines.join("\n");
+    SpecSummary {
+        estimated_tokens: estimate_tokens(&summary),
+        entity_count: entities.len(),
+        listed_entities: listed,
+        truncated,
+        summary,
+    }
+}
+
+/// Tool definitions the model can call to fetch spec data left out of the summary
+pub fn tool_definitions() -> Vec<Value> {
+    vec![
+        json!({
+            "name": "get_entity",
+            "description": "Full JSON of one entity, looked up by name or id.",
+            "input_schema": {
+                "type": "object",
+                "properties": { "name": { "type": "string", "description": "Entity name or id" } },
+                "required": ["name"]
+            }
+        }),
+        json!({
+            "name": "list_entities",
+            "description": "One-line descriptions of entities, optionally filtered by scene, tag or component.",
+            "input_schema": {
+                "type": "object",
+                "properties": {
+                    "scene": { "type": "string", "description": "Scene id; \"main\" for top-level entities" },
+                    "tag": { "type": "string" },
+                    "component": { "type": "string" },
+                    "offset": { "type": "integer", "minimum": 0 },
+                    "limit": { "type": "integer", "minimum": 1, "maximum": MAX_LIST_LIMIT }
+                }
+            }
+        }),
+        json!({
+            "name": "get_spec_section",
+            "description": "Raw JSON at an RFC 6901 pointer into the spec, e.g. /config or /scenes/0/entities/3. An empty pointer returns the whole spec.",
+            "input_schema": {
+                "type": "object",
+                "properties": { "pointer": { "type": "string" } },
+                "required": ["pointer"]
+            }
+        }),
+    ]
+}
+
+/// Run one tool call against the spec; errors are returned as text for the model to read
+pub fn run_tool(spec: &Value, name: &str, input: &Value) -> Result<String, String> {
+    let arg = |key: &str| input.get(key).and_then(Value::as_str);
+    match name {
+        "get_entity" => {
+            let wanted = arg("name").ok_or("get_entity needs a name")?;
+            spec::all_entities(spec)
+                .find(|e| name_of(e) == wanted || e.get("id").and_then(Value::as_str) == Some(wanted))
+                .map(Value::to_string)
+                .ok_or_else(|| format!("No entity named {}", wanted))
+        }
+        "list_entities" => {
+            let offset = input.get("offset").and_then(Value::as_u64).unwrap_or(0) as usize;
+            let limit = input
+                .get("limit")
+                .and_then(Value::as_u64)
+                .map_or(50, |l| l as usize)
+                .clamp(1, MAX_LIST_LIMIT);
+            let matching: Vec<String> = spec::entities_with_scene(spec)
+                .into_iter()
+                .filter(|(scene, _)| arg("scene").is_none_or(|wanted| scene.unwrap_or("main") == wanted))
+                .filter(|(_, e)| arg("tag").is_none_or(|tag| tags(e).contains(&tag)))
+                .filter(|(_, e)| arg("component").is_none_or(|c| component_names(e).contains(&c)))
+                .map(|(scene, e)| describe_entity(scene, e))
+                .collect();
+            let total = matching.len();
+            let page: Vec<String> = matching.into_iter().skip(offset).take(limit).collect();
+            Ok(format!("{} of {} matching entities (offset {}):\n{}", page.len(), total, offset, page.join("\n")))
+        }
+        "get_spec_section" => {
+            let pointer = arg("pointer").unwrap_or("");
+            spec.pointer(pointer)
+                .map(Value::to_string)
+                .ok_or_else(|| format!("Nothing at {}", pointer))
+        }
+        _ => Err(format!("Unknown tool {}", name)),
+    }
+}
+
+/// How much of the project the AI prompt describes
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum ContextScope {
+    /// One scene's entities; defaults to the spec's activeScene, then the top-level entities
+    ActiveScene {
+        #[serde(default)]
+        scene: Option<String>,
+    },
+    /// Only the listed entities (names or ids), with their full JSON
+    Selection { entities: Vec<String> },
+    Project,
+}
+
+/// Spec and scope for `ai_send_message`; without a spec the prompt uses the caller's game context as is
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SpecContext {
+    pub game_spec: Option<Value>,
+    pub token_budget: Option<usize>,
+    pub context_scope: Option<ContextScope>,
+    /// Attach a snapshot of the spec's level layout to the latest user message
+    pub attach_snapshot: bool,
+    /// Add the project health score and top actions to the prompt
+    pub include_health: bool,
+}
+
+/// The spec with everything game-wide kept and the entity list replaced by `entities`
+fn with_entities(spec: &Value, entities: Vec<Value>) -> Value {
+    let mut scoped = spec.clone();
+    if let Some(object) = scoped.as_object_mut() {
+        object.remove("scenes");
+        object.remove("activeScene");
+        object.insert("entities".to_string(), Value::Array(entities));
+    }
+    scoped
+}
+
+/// Assemble the smallest context that covers `scope`, within `token_budget`
+pub fn assemble(spec: &Value, scope: &ContextScope, token_budget: usize) -> Result<SpecSummary, String> {
+    let total = spec::all_entities(spec).count();
+    match scope {
+        ContextScope::Project => Ok(summarize(spec, token_budget)),
+        ContextScope::ActiveScene { scene } => {
+            let scene = scene
+                .clone()
+                .or_else(|| spec.get("activeScene").and_then(Value::as_str).map(str::to_string));
+            let entities = match &scene {
+                Some(id) => spec
+                    .get("scenes")
+                    .and_then(Value::as_array)
+                    .into_iter()
+                    .flatten()
+                    .find(|s| s.get("id").and_then(Value::as_str) == Some(id.as_str()))
+                    .ok_or_else(|| format!("Scene {} not found", id))?
+                    .get("entities"),
+                None => spec.get("entities"),
+            };
+            let entities = entities.and_then(Value::as_array).cloned().unwrap_or_default();
+            let header = format!(
+                "Scope: scene {} ({} of {} entities in the project)",
+                scene.as_deref().unwrap_or("main"),
+                entities.len(),
+                total
+            );
+            let mut summary = summarize(&with_entities(spec, entities), token_budget);
+            summary.summary = format!("{}\n{}", header, summary.summary);
+            summary.estimated_tokens = estimate_tokens(&summary.summary);
+            Ok(summary)
+        }
+        ContextScope::Selection { entities: wanted } => {
+            let selected: Vec<Value> = spec::all_entities(spec)
+                .filter(|e| {
+                    wanted.iter().any(|w| {
+                        name_of(e) == w || e.get("id").and_then(Value::as_str) == Some(w.as_str())
+                    })
+                })
+                .cloned()
+                .collect();
+            if selected.is_empty() {
+                return Err("None of the selected entities exist in the spec".to_string());
+            }
+            let header = format!("Scope: {} selected of {} entities in the project", selected.len(), total);
+            let details: Vec<String> = selected.iter().map(Value::to_string).collect();
+            let mut summary = summarize(&with_entities(spec, selected), token_budget);
+            let mut text = format!("{}\n{}\nSelected entity JSON:", header, summary.summary);
+            let budget_chars = token_budget * CHARS_PER_TOKEN;
+            for detail in details {
+                if text.len() + detail.len() + 1 > budget_chars {
+                    text.push_str("\n(remaining selected entities omitted; use get_entity)");
+                    summary.truncated = true;
+                    break;
+                }
+                text.push('\n');
+                text.push_str(&detail);
+            }
+            summary.estimated_tokens = estimate_tokens(&text);
+            summary.summary = text;
+            Ok(summary)
+        }
+    }
+}
+
+/// Compact summary of an in-editor spec for the AI prompt, optionally narrowed to a scope
+#[tauri::command]
+pub async fn summarize_spec_for_ai(
+    spec: Value,
+    token_budget: Option<usize>,
+    context_scope: Option<ContextScope>,
+) -> Result<SpecSummary, String> {
+    assemble(
+        &spec,
+        &context_scope.unwrap_or(ContextScope::Project),
+        token_budget.unwrap_or(DEFAULT_TOKEN_BUDGET),
+    )
+}
@@ -0,0 +1,573 @@
+use crate::credentials;
+use crate::error::AppError;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+
+/// Services that can answer AI requests
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum ProviderKind {
+    #[default]
+    Anthropic,
+    /// OpenAI or any endpoint speaking its chat completions API (OpenRouter, LM Studio, vLLM, ...)
+    OpenAi,
+    /// A local Ollama server, through its OpenAI-compatible API
+    Ollama,
+    Gemini,
+}
+
+impl ProviderKind {
+    pub fn default_base_url(self) -> &'static str {
+        match self {
+            ProviderKind::Anthropic => "https://api.anthropic.com",
+            ProviderKind::OpenAi => "https://api.openai.com/v1",
+            ProviderKind::Ollama => "http://localhost:11434/v1",
+            ProviderKind::Gemini => "https://generativelanguage.googleapis.com/v1beta",
+        }
+    }
+
+    pub fn default_model(self) -> &'static str {
+        match self {
+            ProviderKind::Anthropic => "claude-sonnet-4-20250514",
+            ProviderKind::OpenAi => "gpt-4o",
+            ProviderKind::Ollama => "llama3.1",
+            ProviderKind::Gemini => "gemini-2.5-flash",
+        }
+    }
+
+    /// Local servers run without a key
+    pub fn needs_api_key(self) -> bool {
+        self != ProviderKind::Ollama
+    }
+
+    /// Keychain account holding this provider's key
+    fn api_key_account(self) -> &'static str {
+        match self {
+            ProviderKind::Anthropic => "ai-api-key-anthropic",
+            ProviderKind::OpenAi => "ai-api-key-openai",
+            ProviderKind::Ollama => "ai-api-key-ollama",
+            ProviderKind::Gemini => "ai-api-key-gemini",
+        }
+    }
+
+    fn env_var(self) -> Option<&'static str> {
+        match self {
+            ProviderKind::Anthropic => Some("ANTHROPIC_API_KEY"),
+            ProviderKind::OpenAi => Some("OPENAI_API_KEY"),
+            ProviderKind::Ollama => None,
+            ProviderKind::Gemini => Some("GEMINI_API_KEY"),
+        }
+    }
+}
+
+/// Selected AI provider (`aiProvider` in the app settings); keys live in the keychain
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderSettings {
+    pub kind: ProviderKind,
+    /// The provider's default model when unset
+    #[serde(default)]
+    pub model: Option<String>,
+    /// Endpoint override, e.g. another OpenAI-compatible server or a remote Ollama
+    #[serde(default)]
+    pub base_url: Option<String>,
+}
+
+impl ProviderSettings {
+    pub fn model(&self) -> &str {
+        self.model.as_deref().filter(|m| !m.is_empty()).unwrap_or(self.kind.default_model())
+    }
+
+    pub fn base_url(&self) -> &str {
+        self.base_url
+            .as_deref()
+            .filter(|u| !u.is_empty())
+            .unwrap_or(self.kind.default_base_url())
+            .trim_end_matches('/')
+    }
+}
+
+/// A provider's saved key, falling back to its environment variable
+pub fn load_api_key(kind: ProviderKind) -> Option<String> {
+    credentials::load(kind.api_key_account())
+        .ok()
+        .flatten()
+        .or_else(|| kind.env_var().and_then(|var| std::env::var(var).ok()))
+        .filter(|key| !key.is_empty())
+}
+
+/// Save (or with an empty key, forget) a provider's key in the keychain
+pub fn store_api_key(kind: ProviderKind, api_key: &str) -> Result<(), String> {
+    match api_key.trim() {
+        "" => delete_api_key(kind),
+        key => credentials::store(kind.api_key_account(), key),
+    }
+}
+
+/// Forget a provider's saved key
+pub fn delete_api_key(kind: ProviderKind) -> Result<(), String> {
+    credentials::delete(kind.api_key_account())
+}
+
+/// A chat request in Anthropic's shape, which is also how conversations are kept between tool
+/// rounds: messages hold text, image, tool_use and tool_result blocks
+#[derive(Debug, Serialize)]
+pub struct ChatRequest {
+    pub model: String,
+    pub max_tokens: u32,
+    pub system: String,
+    /// Plain messages plus tool_use/tool_result turns, hence raw JSON
+    pub messages: Vec<Value>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tools: Vec<Value>,
+    /// Ask for server-sent events instead of one JSON body
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub stream: bool,
+}
+
+#[derive(Debug, Deserialize, Default)]
+pub struct ChatReply {
+    pub content: Vec<ContentBlock>,
+    /// "tool_use" when the model is waiting for tool results
+    #[serde(default)]
+    pub stop_reason: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Default)]
+pub struct ContentBlock {
+    #[serde(rename = "type")]
+    pub content_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub input: Option<Value>,
+}
+
+impl ContentBlock {
+    fn text(text: String) -> Self {
+        Self {
+            content_type: "text".to_string(),
+            text: Some(text),
+            ..Default::default()
+        }
+    }
+
+    fn tool_use(id: String, name: String, input: Value) -> Self {
+        Self {
+            content_type: "tool_use".to_string(),
+            id: Some(id),
+            name: Some(name),
+            input: Some(input),
+            ..Default::default()
+        }
+    }
+}
+
+/// An HTTP call a provider wants made; GET without a body
+pub struct HttpRequest {
+    pub url: String,
+    pub headers: Vec<(&'static str, String)>,
+    pub body: Option<Value>,
+}
+
+/// One provider's wire format. Conversations stay in Anthropic's shape (`ChatRequest`,
+/// `ChatReply`) and each provider translates at the edge, so the tool loop and prompt building
+/// don't care who answers.
+pub trait Provider: Send + Sync {
+    fn chat_request(&self, request: &ChatRequest) -> HttpRequest;
+    fn parse_chat(&self, body: Value) -> Result<ChatReply, AppError>;
+    fn models_request(&self) -> HttpRequest;
+    fn parse_models(&self, body: Value) -> Vec<String>;
+    /// Whether `chat_request` honours `stream` with Anthropic server-sent events; other
+    /// providers answer in one piece
+    fn streams(&self) -> bool {
+        false
+    }
+}
+
+/// The provider for `settings`, authenticating with `api_key`
+pub fn build(settings: &ProviderSettings, api_key: Option<&str>) -> Box<dyn Provider> {
+    let base_url = settings.base_url().to_string();
+    let api_key = api_key.map(str::to_string);
+    match settings.kind {
+        ProviderKind::Anthropic => Box::new(Anthropic { base_url, api_key }),
+        ProviderKind::OpenAi | ProviderKind::Ollama => Box::new(OpenAiCompatible { base_url, api_key }),
+        ProviderKind::Gemini => Box::new(Gemini {
+            base_url,
+            api_key,
+            model: settings.model().to_string(),
+        }),
+    }
+}
+
+fn parse_error(what: &str, e: impl std::fmt::Display) -> AppError {
+    AppError::from(format!("Failed to parse {}: {}", what, e))
+}
+
+/// Text of a message's content, whether a plain string or blocks
+fn text_of(content: &Value) -> String {
+    match content {
+        Value::String(text) => text.clone(),
+        Value::Array(blocks) => blocks
+            .iter()
+            .filter(|b| b["type"] == "text")
+            .filter_map(|b| b["text"].as_str())
+            .collect::<Vec<_>>()
+            .join(""),
+        _ => String::new(),
+    }
+}
+
+/// Content blocks of a message; a plain string becomes one text block
+fn blocks_of(content: &Value) -> Vec<Value> {
+    match content {
+        Value::Array(blocks) => blocks.clone(),
+        other => vec![json!({ "type": "text", "text": text_of(other) })],
+    }
+}
+
+struct Anthropic {
+    base_url: String,
+    api_key: Option<String>,
+}
+
+impl Anthropic {
+    fn headers(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("x-api-key", self.api_key.clone().unwrap_or_default()),
+            ("anthropic-version", "2023-06-01".to_string()),
+        ]
+    }
+}
+
+impl Provider for Anthropic {
+    fn chat_request(&self, request: &ChatRequest) -> HttpRequest {
+        HttpRequest {
+            url: format!("{}/v1/messages", self.base_url),
+            headers: self.headers(),
+            body: Some(json!(request)),
+        }
+    }
+
+    fn parse_chat(&self, body: Value) -> Result<ChatReply, AppError> {
+        serde_json::from_value(body).map_err(|e| parse_error("response", e))
+    }
+
+    fn models_request(&self) -> HttpRequest {
+        HttpRequest {
+            url: format!("{}/v1/models?limit=100", self.base_url),
+            headers: self.headers(),
+            body: None,
+        }
+    }
+
+    fn parse_models(&self, body: Value) -> Vec<String> {
+        body["data"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|m| m["id"].as_str().map(str::to_string))
+            .collect()
+    }
+
+    fn streams(&self) -> bool {
+        true
+    }
+}
+
+/// OpenAI's chat completions API, which Ollama and most self-hosted servers also speak
+struct OpenAiCompatible {
+    base_url: String,
+    api_key: Option<String>,
+}
+
+impl OpenAiCompatible {
+    fn headers(&self) -> Vec<(&'static str, String)> {
+        self.api_key
+            .iter()
+            .map(|key| ("authorization", format!("Bearer {}", key)))
+            .collect()
+    }
+
+    fn messages(request: &ChatRequest) -> Vec<Value> {
+        let mut messages = vec![json!({ "role": "system", "content": request.system })];
+        for message in &request.messages {
+            let role = message["role"].as_str().unwrap_or("user");
+            let blocks = blocks_of(&message["content"]);
+            if role == "assistant" {
+                let tool_calls: Vec<Value> = blocks
+                    .iter()
+                    .filter(|b| b["type"] == "tool_use")
+                    .map(|b| {
+                        json!({
+                            "id": b["id"],
+                            "type": "function",
+                            "function": { "name": b["name"], "arguments": b["input"].to_string() },
+                        })
+                    })
+                    .collect();
+                let mut assistant = json!({ "role": "assistant", "content": text_of(&message["content"]) });
+                if !tool_calls.is_empty() {
+                    assistant["tool_calls"] = Value::Array(tool_calls);
+                }
+                messages.push(assistant);
+                continue;
+            }
+            // Tool results become "tool" messages; the rest of the turn stays a user message
+            let mut parts = Vec::new();
+            for block in &blocks {
+                match block["type"].as_str() {
+                    Some("tool_result") => messages.push(json!({
+                        "role": "tool",
+                        "tool_call_id": block["tool_use_id"],
+                        "content": block["content"].as_str().map_or_else(|| block["content"].to_string(), str::to_string),
+                    })),
+                    Some("image") => parts.push(json!({
+                        "type": "image_url",
+                        "image_url": {
+                            "url": format!(
+                                "data:{};base64,{}",
+                                block["source"]["media_type"].as_str().unwrap_or("image/png"),
+                                block["source"]["data"].as_str().unwrap_or_default()
+                            )
+                        },
+                    })),
+                    Some("text") => parts.push(json!({ "type": "text", "text": block["text"] })),
+                    _ => {}
+                }
+            }
+            match parts.as_slice() {
+                [] => {}
+                [only] if only["type"] == "text" => messages.push(json!({ "role": role, "content": only["text"] })),
+                _ => messages.push(json!({ "role": role, "content": parts })),
+            }
+        }
+        messages
+    }
+}
+
+impl Provider for OpenAiCompatible {
+    fn chat_request(&self, request: &ChatRequest) -> HttpRequest {
+        let mut body = json!({
+            "model": request.model,
+            "max_tokens": request.max_tokens,
+            "messages": Self::messages(request),
+        });
+        if !request.tools.is_empty() {
+            body["tools"] = request
+                .tools
+                .iter()
+                .map(|tool| {
+                    json!({
+                        "type": "function",
+                        "function": { "name": tool["name"], "description": tool["description"], "parameters": tool["input_schema"] },
+                    })
+                })
+                .collect();
+        }
+        HttpRequest {
+            url: format!("{}/chat/completions", self.base_url),
+            headers: self.headers(),
+            body: Some(body),
+        }
+    }
+
+    fn parse_chat(&self, body: Value) -> Result<ChatReply, AppError> {
+        let choice = body["choices"]
+            .get(0)
+            .ok_or_else(|| parse_error("response", "no choices returned"))?;
+        let message = &choice["message"];
+        let mut content = Vec::new();
+        if let Some(text) = message["content"].as_str().filter(|t| !t.is_empty()) {
+            content.push(ContentBlock::text(text.to_string()));
+        }
+        for (i, call) in message["tool_calls"].as_array().into_iter().flatten().enumerate() {
+            let arguments = &call["function"]["arguments"];
+            // Some servers send arguments as an object instead of a JSON string
+            let input = match arguments.as_str() {
+                Some(text) => serde_json::from_str(text).map_err(|e| parse_error("tool arguments", e))?,
+                None => arguments.clone(),
+            };
+            content.push(ContentBlock::tool_use(
+                call["id"].as_str().map_or_else(|| format!("call_{}", i), str::to_string),
+                call["function"]["name"].as_str().unwrap_or_default().to_string(),
+                input,
+            ));
+        }
+        let wants_tools = content.iter().any(|b| b.content_type == "tool_use");
+        Ok(ChatReply {
+            content,
+            stop_reason: Some(if wants_tools { "tool_use" } else { "end_turn" }.to_string()),
+        })
+    }
+
+    fn models_request(&self) -> HttpRequest {
+        HttpRequest {
+            url: format!("{}/models", self.base_url),
+            headers: self.headers(),
+            body: None,
+        }
+    }
+
+    fn parse_models(&self, body: Value) -> Vec<String> {
+        body["data"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|m| m["id"].as_str().map(str::to_string))
+            .collect()
+    }
+}
+
+/// Schema keywords Gemini's function declarations accept
+const GEMINI_SCHEMA_KEYS: &[&str] = &["type", "description", "properties", "required", "items", "enum", "format", "nullable"];
+
+struct Gemini {
+    base_url: String,
+    api_key: Option<String>,
+    model: String,
+}
+
+impl Gemini {
+    fn headers(&self) -> Vec<(&'static str, String)> {
+        vec![("x-goog-api-key", self.api_key.clone().unwrap_or_default())]
+    }
+
+    /// JSON Schema trimmed to the subset Gemini accepts
+    fn schema(schema: &Value) -> Value {
+        let Some(object) = schema.as_object() else {
+            return schema.clone();
+        };
+        let trimmed: Map<String, Value> = object
+            .iter()
+            .filter(|(key, _)| GEMINI_SCHEMA_KEYS.contains(&key.as_str()))
+            .map(|(key, value)| {
+                let value = match key.as_str() {
+                    "properties" => Value::Object(
+                        value
+                            .as_object()
+                            .into_iter()
+                            .flatten()
+                            .map(|(name, property)| (name.clone(), Self::schema(property)))
+                            .collect(),
+                    ),
+                    "items" => Self::schema(value),
+                    _ => value.clone(),
+                };
+                (key.clone(), value)
+            })
+            .collect();
+        Value::Object(trimmed)
+    }
+
+    fn contents(request: &ChatRequest) -> Vec<Value> {
+        // Function responses are matched by name, so remember which tool each call id ran
+        let mut tool_names: HashMap<String, Value> = HashMap::new();
+        let mut contents = Vec::new();
+        for message in &request.messages {
+            let role = if message["role"] == "assistant" { "model" } else { "user" };
+            let parts: Vec<Value> = blocks_of(&message["content"])
+                .iter()
+                .filter_map(|block| match block["type"].as_str()? {
+                    "text" => Some(json!({ "text": block["text"] })),
+                    "image" => Some(json!({
+                        "inlineData": { "mimeType": block["source"]["media_type"], "data": block["source"]["data"] }
+                    })),
+                    "tool_use" => {
+                        tool_names.insert(block["id"].as_str()?.to_string(), block["name"].clone());
+                        Some(json!({ "functionCall": { "name": block["name"], "args": block["input"] } }))
+                    }
+                    "tool_result" => Some(json!({
+                        "functionResponse": {
+                            "name": tool_names.get(block["tool_use_id"].as_str()?).cloned().unwrap_or(Value::Null),
+                            "response": { "content": block["content"] },
+                        }
+                    })),
+                    _ => None,
+                })
+                .collect();
+            if !parts.is_empty() {
+                contents.push(json!({ "role": role, "parts": parts }));
+            }
+        }
+        contents
+    }
+}
+
+impl Provider for Gemini {
+    fn chat_request(&self, request: &ChatRequest) -> HttpRequest {
+        let mut body = json!({
+            "systemInstruction": { "parts": [{ "text": request.system }] },
+            "contents": Self::contents(request),
+            "generationConfig": { "maxOutputTokens": request.max_tokens },
+        });
+        if !request.tools.is_empty() {
+            let declarations: Vec<Value> = request
+                .tools
+                .iter()
+                .map(|tool| {
+                    json!({
+                        "name": tool["name"],
+                        "description": tool["description"],
+                        "parameters": Self::schema(&tool["input_schema"]),
+                    })
+                })
+                .collect();
+            body["tools"] = json!([{ "functionDeclarations": declarations }]);
+        }
+        HttpRequest {
+            url: format!("{}/models/{}:generateContent", self.base_url, self.model),
+            headers: self.headers(),
+            body: Some(body),
+        }
+    }
+
+    fn parse_chat(&self, body: Value) -> Result<ChatReply, AppError> {
+        let Some(candidate) = body["candidates"].get(0) else {
+            let reason = body["promptFeedback"]["blockReason"].as_str().unwrap_or("no candidates returned");
+            return Err(AppError::from(format!("Gemini returned no answer: {}", reason)));
+        };
+        let mut content = Vec::new();
+        for (i, part) in candidate["content"]["parts"].as_array().into_iter().flatten().enumerate() {
+            if let Some(text) = part["text"].as_str() {
+                content.push(ContentBlock::text(text.to_string()));
+            } else if let Some(call) = part.get("functionCall") {
+                let name = call["name"].as_str().unwrap_or_default().to_string();
+                content.push(ContentBlock::tool_use(format!("call_{}_{}", i, name), name, call["args"].clone()));
+            }
+        }
+        let wants_tools = content.iter().any(|b| b.content_type == "tool_use");
+        Ok(ChatReply {
+            content,
+            stop_reason: Some(if wants_tools { "tool_use" } else { "end_turn" }.to_string()),
+        })
+    }
+
+    fn models_request(&self) -> HttpRequest {
+        HttpRequest {
+            url: format!("{}/models?pageSize=200", self.base_url),
+            headers: self.headers(),
+            body: None,
+        }
+    }
+
+    fn parse_models(&self, body: Value) -> Vec<String> {
+        body["models"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter(|m| {
+                m["supportedGenerationMethods"]
+                    .as_array()
+                    .is_some_and(|methods| methods.iter().any(|method| method == "generateContent"))
+            })
+            .filter_map(|m| m["name"].as_str())
+            .map(|name| name.trim_start_matches("models/").to_string())
+            .collect()
+    }
+}
@@ -0,0 +1,191 @@
+use crate::assets::AssetManifest;
+use crate::cache::{self, CacheCategory};
+use crate::{spec, thumbnails};
+use image::codecs::gif::{GifEncoder, Repeat};
+use image::imageops::{self, FilterType};
+use image::{Delay, Frame, RgbaImage};
+use serde::Serialize;
+use serde_json::Value;
+use std::fs;
+use std::io::BufWriter;
+use tauri::AppHandle;
+
+/// Longer clips are cut off; the inspector only needs to show the motion
+const MAX_FRAMES: usize = 256;
+/// Largest preview side after scaling
+const MAX_PREVIEW_SIZE: u32 = 512;
+/// GIF delays are in hundredths of a second, so shorter frames get rounded up by viewers anyway
+const MIN_FRAME_MILLIS: u32 = 20;
+
+#[derive(Debug, Serialize)]
+pub struct AnimationPreview {
+    pub path: String,
+    pub width: u32,
+    pub height: u32,
+    pub frames: usize,
+    pub duration_ms: u64,
+}
+
+/// The frames of one clip: an entity's whole animation, or one of its `states`
+struct Clip<'a> {
+    entity: &'a Value,
+    first: usize,
+    last: usize,
+    frame_duration: f64,
+    looping: bool,
+}
+
+/// Resolve `<entity>` or `<entity>/<state>`, where the entity is its stable id or its name
+fn find_clip<'a>(game_spec: &'a Value, clip_id: &str) -> Result<Clip<'a>, String> {
+    let find = |key: &str| {
+        spec::all_entities(game_spec).find(|e| {
+            e.get("id").and_then(Value::as_str) == Some(key) || e.get("name").and_then(Value::as_str) == Some(key)
+        })
+    };
+    let (entity, state) = match find(clip_id) {
+        Some(entity) => (entity, None),
+        None => match clip_id.rsplit_once('/') {
+            Some((entity, state)) => (find(entity).ok_or_else(|| format!("Entity '{}' not found", entity))?, Some(state)),
+            None => return Err(format!("Entity '{}' not found", clip_id)),
+        },
+    };
+    let animation = entity
+        .pointer("/components/animation")
+        .ok_or_else(|| format!("'{}' has no animation component", clip_id))?;
+    let number = |value: &Value, key: &str| value.get(key).and_then(Value::as_f64);
+
+    match state {
+        None => {
+            let count = number(animation, "frameCount").unwrap_or(1.0).max(1.0) as usize;
+            Ok(Clip {
+                entity,
+                first: 0,
+                last: count - 1,
+                frame_duration: number(animation, "frameDuration").unwrap_or(100.0),
+                looping: animation.get("loop").and_then(Value::as_bool).unwrap_or(true),
+            })
+        }
+        Some(state_name) => {
+            let state = animation
+                .get("states")
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+                .find(|s| s.get("name").and_then(Value::as_str) == Some(state_name))
+                .ok_or_else(|| format!("Animation state '{}' not found", state_name))?;
+            let first = number(state, "frameStart").unwrap_or(0.0).max(0.0) as usize;
+            let last = (number(state, "frameEnd").unwrap_or(first as f64).max(0.0) as usize).max(first);
+            Ok(Clip {
+                entity,
+                first,
+                last,
+                frame_duration: number(state, "frameDuration")
+                    .or_else(|| number(animation, "frameDuration"))
+                    .unwrap_or(100.0),
+                looping: state.get("loop").and_then(Value::as_bool).unwrap_or(true),
+            })
+        }
+    }
+}
+
+/// Cut frames `first..=last` out of a sheet laid out left to right, top to bottom. Without a frame
+/// size the sheet is one row of `frame_count` frames, as the runtime assumes.
+fn slice_frames(sheet: &RgbaImage, clip: &Clip) -> Result<Vec<RgbaImage>, String> {
+    let animation = &clip.entity["components"]["animation"];
+    let sprite = &clip.entity["components"]["sprite"];
+    let size = |key: &str| {
+        animation
+            .get(key)
+            .or_else(|| sprite.get(key))
+            .and_then(Value::as_u64)
+            .filter(|&v| v > 0)
+            .map(|v| v as u32)
+    };
+    let frame_count = animation.get("frameCount").and_then(Value::as_u64).unwrap_or(1).max(1) as u32;
+    let frame_width = size("frameWidth").unwrap_or(sheet.width() / frame_count).max(1);
+    let frame_height = size("frameHeight").unwrap_or(sheet.height()).max(1);
+    let columns = (sheet.width() / frame_width).max(1) as usize;
+    let rows = (sheet.height() / frame_height).max(1) as usize;
+
+    (clip.first..=clip.last)
+        .take(MAX_FRAMES)
+        .map(|index| {
+            let (column, row) = (index % columns, index / columns);
+            if row >= rows {
+                return Err(format!(
+                    "Frame {} is outside the {}x{} sprite sheet ({} frames of {}x{})",
+                    index,
+                    sheet.width(),
+                    sheet.height(),
+                    columns * rows,
+                    frame_width,
+                    frame_height
+                ));
+            }
+            let (x, y) = (column as u32 * frame_width, row as u32 * frame_height);
+            Ok(imageops::crop_imm(sheet, x, y, frame_width, frame_height).to_image())
+        })
+        .collect()
+}
+
+/// Assemble an animation clip into a GIF for the animation inspector. `clip_id` is an entity id
+/// or name, optionally followed by `/<state>`; `scale` enlarges pixel art with nearest-neighbor
+/// filtering.
+#[tauri::command]
+pub async fn render_animation_preview(
+    app: AppHandle,
+    project_path: String,
+    clip_id: String,
+    scale: Option<u32>,
+) -> Result<AnimationPreview, String> {
+    let game_spec = spec::load_project_spec(&project_path)?;
+    let clip = find_clip(&game_spec, &clip_id)?;
+    let sheet_name = ["/components/animation/spriteSheet", "/components/sprite/texture"]
+        .iter()
+        .find_map(|pointer| clip.entity.pointer(pointer).and_then(Value::as_str).filter(|s| !s.is_empty()))
+        .ok_or_else(|| format!("'{}' has no sprite sheet or texture", clip_id))?;
+    let manifest = AssetManifest::load(&project_path)?;
+    let sheet = thumbnails::load_texture(&project_path, &manifest, sheet_name)
+        .ok_or_else(|| format!("Failed to load sprite sheet '{}'", sheet_name))?;
+
+    let mut frames = slice_frames(&sheet, &clip)?;
+    let (width, height) = frames[0].dimensions();
+    let largest = width.max(height);
+    let scale = scale.unwrap_or(1).clamp(1, (MAX_PREVIEW_SIZE / largest.max(1)).max(1));
+    if scale > 1 {
+        frames = frames
+            .iter()
+            .map(|frame| imageops::resize(frame, width * scale, height * scale, FilterType::Nearest))
+            .collect();
+    }
+
+    let frame_millis = (clip.frame_duration.round() as u32).max(MIN_FRAME_MILLIS);
+    let frame_total = frames.len();
+    let dir = cache::dir(&app, CacheCategory::Thumbnails)?.join(cache::project_key(&project_path));
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create thumbnail directory: {}", e))?;
+    let stem: String = clip_id
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+        .collect();
+    let path = dir.join(format!("animation-{}.gif", stem));
+
+    let file = fs::File::create(&path).map_err(|e| format!("Failed to write preview: {}", e))?;
+    let mut encoder = GifEncoder::new(BufWriter::new(file));
+    let repeat = if clip.looping { Repeat::Infinite } else { Repeat::Finite(0) };
+    encoder.set_repeat(repeat).map_err(|e| format!("Failed to write preview: {}", e))?;
+    encoder
+        .encode_frames(
+            frames
+                .into_iter()
+                .map(|frame| Frame::from_parts(frame, 0, 0, Delay::from_numer_denom_ms(frame_millis, 1))),
+        )
+        .map_err(|e| format!("Failed to write preview: {}", e))?;
+
+    Ok(AnimationPreview {
+        path: path.to_string_lossy().to_string(),
+        width: width * scale,
+        height: height * scale,
+        frames: frame_total,
+        duration_ms: frame_total as u64 * frame_millis as u64,
+    })
+}
@@ -0,0 +1,438 @@
+use crate::assets::{self, AssetEntry, AssetKind, AssetLicense, AssetManifest, Dimensions, ASSETS_DIR};
+use crate::workers;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::{Component, Path, PathBuf};
+
+/// Single files beyond this are almost certainly not game assets
+const MAX_ENTRY_BYTES: u64 = 64 * 1024 * 1024;
+/// Extracted bytes held in memory at once; larger packs are imported in batches of this size
+const MAX_BATCH_BYTES: u64 = 256 * 1024 * 1024;
+/// Path words that mark an image as a tile rather than a sprite
+const TILE_WORDS: &[&str] = &["tile", "tiles", "tileset", "tilesheet", "terrain", "ground", "map"];
+/// Storefront images packs ship alongside the assets
+const PREVIEW_STEMS: &[&str] = &["preview", "sample", "cover", "thumbnail", "screenshot"];
+
+#[derive(Debug, Serialize)]
+pub struct ImportedAsset {
+    pub entry: AssetEntry,
+    /// "sprites", "tiles" or "audio"
+    pub category: String,
+    pub thumbnail: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DuplicateAsset {
+    pub archive_path: String,
+    /// The project asset with identical content that was kept instead
+    pub existing: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FailedAsset {
+    pub archive_path: String,
+    pub error: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AssetPackReport {
+    pub pack: String,
+    pub imported: Vec<ImportedAsset>,
+    pub duplicates: Vec<DuplicateAsset>,
+    /// Archive entries that aren't images or audio (docs, vector sources, previews)
+    pub skipped: Vec<String>,
+    /// Entries that could not be extracted or written; the rest of the pack is still imported
+    pub failed: Vec<FailedAsset>,
+    /// License applied to every imported asset, given or detected from the pack's license file
+    pub license: Option<AssetLicense>,
+}
+
+fn slug(name: &str) -> String {
+    let slug: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_lowercase() } else { '-' })
+        .collect();
+    slug.split('-').filter(|part| !part.is_empty()).collect::<Vec<_>>().join("-")
+}
+
+fn sha256_hex(bytes: &[u8]) -> String {
+    Sha256::digest(bytes).iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn is_ignored(path: &Path) -> bool {
+    path.components().any(|c| match c {
+        Component::Normal(part) => {
+            let part = part.to_string_lossy();
+            part.starts_with('.') || part == "__MACOSX"
+        }
+        _ => false,
+    })
+}
+
+/// Sort an image or audio file into sprites, tiles or audio by its kind and path words
+fn categorize(path: &Path) -> Option<&'static str> {
+    match AssetKind::from_path(path) {
+        AssetKind::Audio => Some("audio"),
+        AssetKind::Image => {
+            let stem = path.file_stem().map(|s| s.to_string_lossy().to_lowercase()).unwrap_or_default();
+            if PREVIEW_STEMS.contains(&stem.as_str()) {
+                return None;
+            }
+            let lower = path.to_string_lossy().to_lowercase();
+            let is_tile = lower
+                .split(|c: char| !c.is_ascii_alphanumeric())
+                .any(|word| TILE_WORDS.contains(&word) || word.starts_with("tile"));
+            Some(if is_tile { "tiles" } else { "sprites" })
+        }
+        AssetKind::Other => None,
+    }
+}
+
+fn is_license_file(path: &Path) -> bool {
+    let name = path.file_name().map(|n| n.to_string_lossy().to_lowercase()).unwrap_or_default();
+    (name.contains("license") || name.contains("licence")) && !matches!(AssetKind::from_path(path), AssetKind::Image | AssetKind::Audio)
+}
+
+/// Best-effort license from a pack's license text; Kenney and most itch packs state it plainly
+fn detect_license(text: &str) -> Option<AssetLicense> {
+    let lower = text.to_lowercase();
+    let license = if lower.contains("cc0") || lower.contains("creative commons zero") || lower.contains("public domain") {
+        "CC0-1.0"
+    } else if lower.contains("cc-by-sa") || lower.contains("attribution-sharealike") {
+        "CC-BY-SA-4.0"
+    } else if lower.contains("cc-by") || lower.contains("cc by") || lower.contains("attribution 4.0") {
+        "CC-BY-4.0"
+    } else if lower.contains("mit license") {
+        "MIT"
+    } else {
+        return None;
+    };
+    let source_url = text
+        .split_whitespace()
+        .find(|word| word.starts_with("http://") || word.starts_with("https://") || word.starts_with("www."))
+        .map(|url| url.trim_end_matches(|c: char| !c.is_ascii_alphanumeric() && c != '/').to_string());
+    let author = lower.contains("kenney").then(|| "Kenney".to_string());
+    Some(AssetLicense {
+        license: license.to_string(),
+        author,
+        source_url,
+        distributable: true,
+    })
+}
+
+/// The top folder every entry shares, e.g. "kenney_platformer-pack/"; stripped from destinations
+fn common_root(paths: &[PathBuf]) -> PathBuf {
+    let mut root: Option<PathBuf> = None;
+    for path in paths {
+        let parent = path.parent().map(Path::to_path_buf).unwrap_or_default();
+        root = Some(match root {
+            None => parent,
+            Some(current) => current
+                .components()
+                .zip(parent.components())
+                .take_while(|(a, b)| a == b)
+                .map(|(a, _)| a)
+                .collect(),
+        });
+    }
+    root.unwrap_or_default()
+}
+
+/// Pick `path`, or `path` with a numeric suffix, that doesn't exist yet and isn't `reserved` by a
+/// file of the same batch still being written
+fn unique_destination(path: PathBuf, reserved: &HashSet<PathBuf>) -> PathBuf {
+    let free = |candidate: &PathBuf| !candidate.exists() && !reserved.contains(candidate);
+    if free(&path) {
+        return path;
+    }
+    let stem = path.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+    let extension = path.extension().map(|e| format!(".{}", e.to_string_lossy())).unwrap_or_default();
+    (2..)
+        .map(|n| path.with_file_name(format!("{}-{}{}", stem, n, extension)))
+        .find(free)
+        .unwrap_or(path)
+}
+
+/// Content hashes of the project's existing image and audio assets
+async fn existing_hashes(project_path: &str, manifest: &AssetManifest) -> HashMap<String, String> {
+    let root = PathBuf::from(project_path);
+    let paths: Vec<String> = manifest
+        .assets
+        .iter()
+        .filter(|entry| entry.kind != AssetKind::Other)
+        .map(|entry| entry.path.clone())
+        .collect();
+    workers::run_blocking(paths, move |path| {
+        let bytes = fs::read(root.join(&path)).ok()?;
+        Some((sha256_hex(&bytes), path))
+    })
+    .await
+    .into_iter()
+    .filter_map(|outcome| outcome.ok().flatten())
+    .collect()
+}
+
+/// An extracted archive file on its way into the project
+struct PackFile {
+    relative: PathBuf,
+    bytes: Vec<u8>,
+}
+
+/// A file cleared for import: not a duplicate, with its destination reserved
+struct PlannedFile {
+    archive_path: String,
+    category: &'static str,
+    destination: PathBuf,
+    /// Project-relative destination, as recorded in assets.json
+    path: String,
+    hash: String,
+}
+
+/// Import state carried across batches
+struct PackImport {
+    project_path: String,
+    pack: String,
+    root: PathBuf,
+    license: Option<AssetLicense>,
+    manifest: AssetManifest,
+    known: HashMap<String, String>,
+    reserved: HashSet<PathBuf>,
+    imported: Vec<ImportedAsset>,
+    duplicates: Vec<DuplicateAsset>,
+    failed: Vec<FailedAsset>,
+}
+
+impl PackImport {
+    /// Hash and write one batch concurrently. Duplicate checks and destinations are settled in
+    /// between, in archive order, so the result matches a serial import.
+    async fn import_batch(&mut self, batch: Vec<PackFile>) {
+        let archive_paths: Vec<String> = batch
+            .iter()
+            .map(|file| file.relative.to_string_lossy().replace('\\', "/"))
+            .collect();
+        let hashed = workers::run_blocking(batch, |file| {
+            let hash = sha256_hex(&file.bytes);
+            (file, hash)
+        })
+        .await;
+
+        let mut planned = Vec::new();
+        let mut writes = Vec::new();
+        for (archive_path, outcome) in archive_paths.into_iter().zip(hashed) {
+            let (file, hash) = match outcome {
+                Ok(hashed) => hashed,
+                Err(error) => {
+                    self.failed.push(FailedAsset { archive_path, error });
+                    continue;
+                }
+            };
+            if let Some(existing) = self.known.get(&hash) {
+                self.duplicates.push(DuplicateAsset {
+                    archive_path,
+                    existing: existing.clone(),
+                });
+                continue;
+            }
+
+            let category = categorize(&file.relative).unwrap_or("sprites");
+            let inner = file.relative.strip_prefix(&self.root).unwrap_or(&file.relative);
+            let destination = unique_destination(
+                PathBuf::from(&self.project_path)
+                    .join(ASSETS_DIR)
+                    .join(category)
+                    .join(&self.pack)
+                    .join(inner),
+                &self.reserved,
+            );
+            let path = destination
+                .strip_prefix(&self.project_path)
+                .unwrap_or(&destination)
+                .to_string_lossy()
+                .replace('\\', "/");
+            self.reserved.insert(destination.clone());
+            // Claimed now so a later copy in the same batch counts as a duplicate of this one
+            self.known.insert(hash.clone(), path.clone());
+            writes.push((destination.clone(), hash.clone(), file.bytes));
+            planned.push(PlannedFile {
+                archive_path,
+                category,
+                destination,
+                path,
+                hash,
+            });
+        }
+
+        let project_path = self.project_path.clone();
+        let written = workers::run_blocking(writes, move |(destination, hash, bytes)| {
+            write_asset(&project_path, &destination, &hash, &bytes)
+        })
+        .await;
+
+        for (file, outcome) in planned.into_iter().zip(written) {
+            let details = match outcome.and_then(|written| written) {
+                Ok(details) => details,
+                Err(error) => {
+                    self.known.remove(&file.hash);
+                    self.failed.push(FailedAsset {
+                        archive_path: file.archive_path,
+                        error,
+                    });
+                    continue;
+                }
+            };
+            let (dimensions, thumbnail) = details.unzip();
+            let thumbnail_path = thumbnail
+                .as_ref()
+                .map(|relative| PathBuf::from(&self.project_path).join(relative).to_string_lossy().to_string());
+            let entry = AssetEntry {
+                dimensions,
+                thumbnail,
+                ..AssetEntry::new(file.path, self.license.clone())
+            };
+            self.manifest.upsert(entry.clone());
+            self.imported.push(ImportedAsset {
+                entry,
+                category: file.category.to_string(),
+                thumbnail: thumbnail_path,
+            });
+        }
+    }
+}
+
+/// Write one asset into the project and, for images, read its size and thumbnail it
+fn write_asset(
+    project_path: &str,
+    destination: &Path,
+    hash: &str,
+    bytes: &[u8],
+) -> Result<Option<(Dimensions, String)>, String> {
+    if let Some(parent) = destination.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create assets directory: {}", e))?;
+    }
+    fs::write(destination, bytes).map_err(|e| format!("Failed to write {}: {}", destination.display(), e))?;
+    Ok(match AssetKind::from_path(destination) {
+        AssetKind::Image => assets::image_details(project_path, destination, hash),
+        _ => None,
+    })
+}
+
+/// Unpack a .zip asset pack into assets/<sprites|tiles|audio>/<pack>/, skipping files already in
+/// the project, generating thumbnails and applying the pack's license to every imported file.
+/// Files are extracted in memory-bounded batches and written concurrently; a file that fails is
+/// reported without stopping the rest of the pack.
+#[tauri::command]
+pub async fn import_asset_pack(
+    project_path: String,
+    zip_path: String,
+    pack_name: Option<String>,
+    license: Option<AssetLicense>,
+) -> Result<AssetPackReport, String> {
+    let zip_file = Path::new(&zip_path);
+    let file = File::open(zip_file).map_err(|e| format!("Failed to open {}: {}", zip_path, e))?;
+    let mut archive = zip::ZipArchive::new(file).map_err(|e| format!("Failed to read asset pack: {}", e))?;
+    let pack = slug(
+        &pack_name.unwrap_or_else(|| zip_file.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default()),
+    );
+    if pack.is_empty() {
+        return Err("Asset pack needs a name".to_string());
+    }
+
+    // First pass reads only the central directory (and the license text), so the pack's common
+    // root and license are known before any bytes are extracted
+    let mut entries: Vec<(usize, PathBuf, u64)> = Vec::new();
+    let mut skipped = Vec::new();
+    let mut license_text = None;
+    for index in 0..archive.len() {
+        let mut entry = archive.by_index(index).map_err(|e| format!("Failed to read asset pack: {}", e))?;
+        // enclosed_name rejects absolute and `..` paths, so nothing escapes the project
+        let Some(relative) = entry.enclosed_name() else {
+            continue;
+        };
+        if entry.is_dir() || is_ignored(&relative) {
+            continue;
+        }
+        if entry.size() > MAX_ENTRY_BYTES {
+            skipped.push(relative.to_string_lossy().to_string());
+            continue;
+        }
+        if license_text.is_none() && is_license_file(&relative) {
+            let mut text = String::new();
+            if entry.read_to_string(&mut text).is_ok() {
+                license_text = Some(text);
+            }
+        }
+        if categorize(&relative).is_some() {
+            entries.push((index, relative, entry.size()));
+        } else {
+            skipped.push(relative.to_string_lossy().to_string());
+        }
+    }
+
+    let license = license.or_else(|| license_text.as_deref().and_then(detect_license));
+    let root = common_root(&entries.iter().map(|(_, path, _)| path.clone()).collect::<Vec<_>>());
+    let manifest = AssetManifest::load(&project_path)?;
+    let known = existing_hashes(&project_path, &manifest).await;
+    let mut import = PackImport {
+        project_path: project_path.clone(),
+        pack: pack.clone(),
+        root,
+        license: license.clone(),
+        manifest,
+        known,
+        reserved: HashSet::new(),
+        imported: Vec::new(),
+        duplicates: Vec::new(),
+        failed: Vec::new(),
+    };
+
+    let mut batch = Vec::new();
+    let mut batch_bytes = 0;
+    for (index, relative, size) in entries {
+        if !batch.is_empty() && batch_bytes + size > MAX_BATCH_BYTES {
+            import.import_batch(std::mem::take(&mut batch)).await;
+            batch_bytes = 0;
+        }
+        let extracted = archive
+            .by_index(index)
+            .map_err(|e| e.to_string())
+            .and_then(|mut entry| {
+                // Sizes in the zip headers can lie, so the cap is enforced on the bytes themselves
+                let mut bytes = Vec::with_capacity(size.min(MAX_ENTRY_BYTES) as usize);
+                (&mut entry).take(MAX_ENTRY_BYTES + 1).read_to_end(&mut bytes).map_err(|e| e.to_string())?;
+                if bytes.len() as u64 > MAX_ENTRY_BYTES {
+                    return Err(format!("larger than {} MB", MAX_ENTRY_BYTES / (1024 * 1024)));
+                }
+                Ok(bytes)
+            });
+        match extracted {
+            Ok(bytes) => {
+                batch_bytes += bytes.len() as u64;
+                batch.push(PackFile { relative, bytes });
+                if batch_bytes >= MAX_BATCH_BYTES {
+                    import.import_batch(std::mem::take(&mut batch)).await;
+                    batch_bytes = 0;
+                }
+            }
+            Err(e) => import.failed.push(FailedAsset {
+                archive_path: relative.to_string_lossy().replace('\\', "/"),
+                error: format!("Failed to extract {}: {}", relative.display(), e),
+            }),
+        }
+    }
+    if !batch.is_empty() {
+        import.import_batch(batch).await;
+    }
+
+    import.manifest.save(&project_path)?;
+    Ok(AssetPackReport {
+        pack,
+        imported: import.imported,
+        duplicates: import.duplicates,
+        skipped,
+        failed: import.failed,
+        license,
+    })
+}
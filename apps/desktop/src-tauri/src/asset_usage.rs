@@ -0,0 +1,109 @@
+use crate::assets::{AssetKind, AssetManifest, ASSETS_DIR};
+use crate::minimap::MINIMAP_DIR;
+use crate::{json_guard, paths, symlinks};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+/// A bundled asset and the spec strings that point at it
+#[derive(Debug, Serialize)]
+pub struct UsedAsset {
+    /// Project-relative path with forward slashes
+    pub path: String,
+    pub references: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Default)]
+pub struct AssetSelection {
+    pub included: Vec<UsedAsset>,
+    /// Project assets no part of the spec refers to
+    pub stripped: Vec<String>,
+}
+
+/// Every string anywhere in the spec: entity components, animation states, UI, audio, dialogues...
+fn spec_strings(value: &Value, out: &mut BTreeSet<String>) {
+    match value {
+        Value::String(s) => {
+            out.insert(crate::assets::normalize_relative(s));
+        }
+        Value::Array(items) => items.iter().for_each(|v| spec_strings(v, out)),
+        Value::Object(map) => map.values().for_each(|v| spec_strings(v, out)),
+        _ => {}
+    }
+}
+
+/// Ways a spec may name `assets/sprites/player.png`: the full path, the path inside assets/,
+/// the file name, or the bare stem used for textures
+pub fn aliases(relative: &str) -> Vec<String> {
+    let path = Path::new(relative);
+    let mut names = vec![relative.to_string()];
+    if let Some(inner) = relative.strip_prefix(&format!("{}/", ASSETS_DIR)) {
+        names.push(inner.to_string());
+    }
+    names.extend(path.file_name().map(|n| n.to_string_lossy().to_string()));
+    names.extend(path.file_stem().map(|n| n.to_string_lossy().to_string()));
+    names
+}
+
+/// Image and audio files in the project's assets folder and manifest; baked minimaps are
+/// shipped through the minimap payload instead
+pub fn project_assets(project_path: &str) -> Vec<String> {
+    let manifest = AssetManifest::load(project_path).unwrap_or_default();
+    let root = PathBuf::from(project_path);
+    let mut found = BTreeSet::new();
+
+    let mut descend = |dir: &Path| {
+        paths::relative_to(dir, &root)
+            .is_some_and(|r| paths::to_spec_path(&r) != format!("{}/{}", ASSETS_DIR, MINIMAP_DIR))
+    };
+    let mut visit = |file: &Path| {
+        if AssetKind::from_path(file) != AssetKind::Other {
+            if let Some(relative) = paths::relative_to(file, &root) {
+                found.insert(paths::to_spec_path(&relative));
+            }
+        }
+    };
+    symlinks::walk_files(&root.join(ASSETS_DIR), manifest.symlinks, &mut descend, &mut visit);
+
+    for entry in &manifest.assets {
+        if entry.kind != AssetKind::Other && root.join(&entry.path).is_file() {
+            found.insert(entry.path.clone());
+        }
+    }
+    found.into_iter().collect()
+}
+
+/// Split the project's assets into those the spec references and those a bundle can drop.
+/// With `include_all`, nothing is stripped.
+pub fn select(project_path: &str, spec: &Value, include_all: bool) -> AssetSelection {
+    let mut strings = BTreeSet::new();
+    spec_strings(spec, &mut strings);
+
+    let mut selection = AssetSelection::default();
+    for path in project_assets(project_path) {
+        let references: Vec<String> = aliases(&path)
+            .into_iter()
+            .filter(|alias| strings.contains(alias))
+            .collect();
+        if references.is_empty() && !include_all {
+            selection.stripped.push(path);
+        } else {
+            selection.included.push(UsedAsset { path, references });
+        }
+    }
+    selection
+}
+
+/// Report which project assets an export would include and which it would strip
+#[tauri::command]
+pub async fn analyze_asset_usage(
+    project_path: String,
+    game_spec_json: Option<String>,
+) -> Result<AssetSelection, String> {
+    let spec = match game_spec_json {
+        Some(json) => json_guard::parse(json.as_bytes(), "game spec", &json_guard::SPEC_LIMITS)?,
+        None => crate::spec::load_project_spec(&project_path)?,
+    };
+    Ok(select(&project_path, &spec, false))
+}
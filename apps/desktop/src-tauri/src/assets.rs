@@ -0,0 +1,409 @@
+use crate::encryption;
+use crate::symlinks::SymlinkPolicy;
+use crate::thumbnails::THUMBNAIL_DIR;
+use base64::Engine;
+use image::imageops::{self, FilterType};
+use image::ImageFormat;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const MANIFEST_FILE: &str = "assets.json";
+pub const ASSETS_DIR: &str = "assets";
+/// Asset thumbnails live beside the scene thumbnails
+const ASSET_THUMBNAIL_DIR: &str = "assets";
+const THUMBNAIL_SIZE: u32 = 64;
+/// Files up to this size are previewed inline as data URLs; larger images use their thumbnail
+const MAX_INLINE_PREVIEW_BYTES: u64 = 2 * 1024 * 1024;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum AssetKind {
+    Image,
+    Audio,
+    Other,
+}
+
+impl AssetKind {
+    pub fn from_path(path: &Path) -> Self {
+        let ext = path
+            .extension()
+            .map(|e| e.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        match ext.as_str() {
+            "png" | "jpg" | "jpeg" | "gif" | "webp" | "bmp" => AssetKind::Image,
+            "wav" | "mp3" | "ogg" | "flac" | "m4a" => AssetKind::Audio,
+            _ => AssetKind::Other,
+        }
+    }
+}
+
+/// License and attribution details for a single asset
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AssetLicense {
+    /// SPDX identifier (e.g. "CC0-1.0") or free-form license name
+    pub license: String,
+    pub author: Option<String>,
+    pub source_url: Option<String>,
+    #[serde(default = "default_distributable")]
+    pub distributable: bool,
+}
+
+fn default_distributable() -> bool {
+    true
+}
+
+/// Pixel size of an image asset
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub struct Dimensions {
+    pub width: u32,
+    pub height: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AssetEntry {
+    /// Project-relative path with forward slashes, e.g. "assets/player.png"
+    pub path: String,
+    pub kind: AssetKind,
+    pub license: Option<AssetLicense>,
+    /// Image size, when the image could be decoded
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dimensions: Option<Dimensions>,
+    /// Project-relative PNG thumbnail for the asset browser
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub thumbnail: Option<String>,
+}
+
+impl AssetEntry {
+    /// Entry for a project-relative path, its kind taken from the extension
+    pub fn new(path: String, license: Option<AssetLicense>) -> Self {
+        Self {
+            kind: AssetKind::from_path(Path::new(&path)),
+            path,
+            license,
+            dimensions: None,
+            thumbnail: None,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Default)]
+pub struct ImportReport {
+    pub imported: Vec<AssetEntry>,
+    /// One message per file that couldn't be imported
+    pub failed: Vec<String>,
+}
+
+/// How the frontend can show an asset: inline bytes, or a file to load by path
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AssetPreview {
+    pub kind: AssetKind,
+    pub mime_type: &'static str,
+    /// Base64 data URL of the file, for files small enough to send inline
+    pub data_url: Option<String>,
+    /// Absolute path of the thumbnail (or the file itself) for larger files
+    pub preview_path: Option<String>,
+    pub dimensions: Option<Dimensions>,
+    pub size_bytes: u64,
+}
+
+/// Contents of a project's assets.json
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct AssetManifest {
+    #[serde(default)]
+    pub assets: Vec<AssetEntry>,
+    /// Whether symlinked asset folders are followed when listing, watching and exporting
+    #[serde(default)]
+    pub symlinks: SymlinkPolicy,
+}
+
+impl AssetManifest {
+    pub fn load(project_path: &str) -> Result<Self, String> {
+        let path = PathBuf::from(project_path).join(MANIFEST_FILE);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = encryption::read_to_string(&path)?;
+        serde_json::from_str(&content).map_err(|e| format!("Failed to parse {}: {}", MANIFEST_FILE, e))
+    }
+
+    pub fn save(&self, project_path: &str) -> Result<(), String> {
+        let path = PathBuf::from(project_path).join(MANIFEST_FILE);
+        let content = serde_json::to_string_pretty(self)
+            .map_err(|e| format!("Failed to serialize {}: {}", MANIFEST_FILE, e))?;
+        encryption::write(&path, content.as_bytes())
+    }
+
+    pub fn find(&self, path: &str) -> Option<&AssetEntry> {
+        self.assets.iter().find(|a| a.path == path)
+    }
+
+    pub fn find_mut(&mut self, path: &str) -> Option<&mut AssetEntry> {
+        self.assets.iter_mut().find(|a| a.path == path)
+    }
+
+    /// Match a spec reference, which may be a full path or a bare texture name
+    pub fn resolve(&self, reference: &str) -> Option<&AssetEntry> {
+        let reference = normalize_relative(reference);
+        self.find(&reference).or_else(|| {
+            self.assets.iter().find(|a| {
+                Path::new(&a.path)
+                    .file_stem()
+                    .is_some_and(|stem| stem.to_string_lossy() == reference)
+            })
+        })
+    }
+
+    /// Insert or replace an entry by path
+    pub fn upsert(&mut self, entry: AssetEntry) {
+        match self.find_mut(&entry.path) {
+            Some(existing) => *existing = entry,
+            None => self.assets.push(entry),
+        }
+        self.assets.sort_by(|a, b| a.path.cmp(&b.path));
+    }
+}
+
+/// Normalize a project-relative path to forward slashes without a leading "./"
+pub fn normalize_relative(path: &str) -> String {
+    path.replace('\\', "/").trim_start_matches("./").to_string()
+}
+
+/// Lowercase file name with runs of anything but letters, digits, '_' and '.' turned into '-',
+/// so asset paths are the same on every platform, e.g. "Player Sprite (2).PNG" -> "player-sprite-2.png"
+pub fn normalized_file_name(name: &str) -> String {
+    let (stem, extension) = match name.rsplit_once('.') {
+        Some((stem, extension)) if !stem.is_empty() => (stem, Some(extension)),
+        _ => (name, None),
+    };
+    let clean = |part: &str| {
+        let mapped: String = part
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() || c == '_' { c.to_ascii_lowercase() } else { '-' })
+            .collect();
+        mapped.split('-').filter(|piece| !piece.is_empty()).collect::<Vec<_>>().join("-")
+    };
+    let stem = match clean(stem) {
+        stem if stem.is_empty() => "asset".to_string(),
+        stem => stem,
+    };
+    match extension.map(clean).filter(|e| !e.is_empty()) {
+        Some(extension) => format!("{}.{}", stem, extension),
+        None => stem,
+    }
+}
+
+/// `destination`, or `destination` with a numeric suffix, that is free or already holds the same
+/// bytes as `source`
+fn import_destination(source: &Path, destination: PathBuf) -> PathBuf {
+    let usable = |candidate: &PathBuf| match fs::read(candidate) {
+        Ok(existing) => fs::read(source).is_ok_and(|bytes| bytes == existing),
+        Err(_) => true,
+    };
+    if usable(&destination) {
+        return destination;
+    }
+    let stem = destination.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+    let extension = destination.extension().map(|e| format!(".{}", e.to_string_lossy())).unwrap_or_default();
+    (2..)
+        .map(|n| destination.with_file_name(format!("{}-{}{}", stem, n, extension)))
+        .find(usable)
+        .unwrap_or(destination)
+}
+
+/// Read an image's size and write its thumbnail into the project's thumbnail folder, named by
+/// `key` so identical images share one. Returns the size and the thumbnail's project-relative path;
+/// None when the image can't be decoded.
+pub fn image_details(project_path: &str, source: &Path, key: &str) -> Option<(Dimensions, String)> {
+    let image = image::open(source).ok()?.to_rgba8();
+    let (width, height) = image.dimensions();
+    let scale = THUMBNAIL_SIZE as f64 / width.max(height).max(1) as f64;
+    let (thumb_width, thumb_height) = if scale < 1.0 {
+        (((width as f64 * scale).round() as u32).max(1), ((height as f64 * scale).round() as u32).max(1))
+    } else {
+        (width, height)
+    };
+    // Nearest keeps pixel art crisp at thumbnail size
+    let thumbnail = imageops::resize(&image, thumb_width, thumb_height, FilterType::Nearest);
+    let relative = format!("{}/{}/{}.png", THUMBNAIL_DIR, ASSET_THUMBNAIL_DIR, &key[..key.len().min(16)]);
+    let path = PathBuf::from(project_path).join(&relative);
+    fs::create_dir_all(path.parent()?).ok()?;
+    thumbnail.save_with_format(&path, ImageFormat::Png).ok()?;
+    Some((Dimensions { width, height }, relative))
+}
+
+/// Fill in an image entry's size and thumbnail from the file it points at
+fn describe(project_path: &str, entry: &mut AssetEntry) {
+    if entry.kind != AssetKind::Image {
+        return;
+    }
+    let path = PathBuf::from(project_path).join(&entry.path);
+    let Ok(bytes) = fs::read(&path) else {
+        return;
+    };
+    let key: String = Sha256::digest(&bytes).iter().map(|b| format!("{:02x}", b)).collect();
+    if let Some((dimensions, thumbnail)) = image_details(project_path, &path, &key) {
+        entry.dimensions = Some(dimensions);
+        entry.thumbnail = Some(thumbnail);
+    }
+}
+
+/// Copy a file into `assets/<subdir>` under its normalized name and register it in the manifest,
+/// with size and thumbnail for images. A different file already using the name gets a numbered
+/// name instead of being overwritten.
+pub fn import_file(
+    project_path: &str,
+    source: &Path,
+    subdir: &str,
+    license: Option<AssetLicense>,
+) -> Result<AssetEntry, String> {
+    let file_name = source
+        .file_name()
+        .map(|name| normalized_file_name(&name.to_string_lossy()))
+        .ok_or_else(|| format!("Invalid asset path: {}", source.display()))?;
+
+    let relative_dir = [ASSETS_DIR, subdir.trim_matches('/')]
+        .iter()
+        .filter(|part| !part.is_empty())
+        .copied()
+        .collect::<Vec<_>>()
+        .join("/");
+    let assets_dir = PathBuf::from(project_path).join(&relative_dir);
+    fs::create_dir_all(&assets_dir)
+        .map_err(|e| format!("Failed to create assets directory: {}", e))?;
+
+    let destination = import_destination(source, assets_dir.join(file_name));
+    if !destination.exists() {
+        fs::copy(source, &destination)
+            .map_err(|e| format!("Failed to copy asset {}: {}", source.display(), e))?;
+    }
+
+    let file_name = destination.file_name().unwrap_or_default().to_string_lossy();
+    let mut entry = AssetEntry::new(format!("{}/{}", relative_dir, file_name), license);
+    describe(project_path, &mut entry);
+
+    let mut manifest = AssetManifest::load(project_path)?;
+    manifest.upsert(entry.clone());
+    manifest.save(project_path)?;
+
+    Ok(entry)
+}
+
+/// Copy a file into the project's assets directory and register it in the manifest
+#[tauri::command]
+pub async fn import_asset(
+    project_path: String,
+    source_path: String,
+    license: Option<AssetLicense>,
+) -> Result<AssetEntry, String> {
+    import_file(&project_path, Path::new(&source_path), "", license)
+}
+
+/// Import several images and sounds at once into `assets/<subdir>`; a file that fails is
+/// reported without stopping the rest
+#[tauri::command]
+pub async fn import_assets(
+    project_path: String,
+    source_paths: Vec<String>,
+    subdir: Option<String>,
+) -> Result<ImportReport, String> {
+    let subdir = subdir.unwrap_or_default();
+    let mut report = ImportReport::default();
+    for source in source_paths {
+        match import_file(&project_path, Path::new(&source), &subdir, None) {
+            Ok(entry) => report.imported.push(entry),
+            Err(e) => report.failed.push(e),
+        }
+    }
+    Ok(report)
+}
+
+/// Regenerate sizes and thumbnails for the manifest's images, e.g. after editing them outside
+/// the editor. Without `force`, only images missing a thumbnail are processed.
+#[tauri::command]
+pub async fn generate_asset_thumbnails(project_path: String, force: Option<bool>) -> Result<AssetManifest, String> {
+    let mut manifest = AssetManifest::load(&project_path)?;
+    let root = PathBuf::from(&project_path);
+    for entry in &mut manifest.assets {
+        let stale = entry.thumbnail.as_ref().is_none_or(|thumbnail| !root.join(thumbnail).is_file());
+        if force.unwrap_or(false) || stale {
+            describe(&project_path, entry);
+        }
+    }
+    manifest.save(&project_path)?;
+    Ok(manifest)
+}
+
+/// Pixel size of a project image
+#[tauri::command]
+pub async fn get_image_dimensions(project_path: String, asset_path: String) -> Result<Dimensions, String> {
+    let path = PathBuf::from(&project_path).join(normalize_relative(&asset_path));
+    let (width, height) =
+        image::image_dimensions(&path).map_err(|e| format!("Failed to read image {}: {}", asset_path, e))?;
+    Ok(Dimensions { width, height })
+}
+
+/// An asset's contents for display: a data URL for small files, or the path of its thumbnail
+/// (the file itself for sounds) for larger ones
+#[tauri::command]
+pub async fn read_asset_preview(project_path: String, asset_path: String) -> Result<AssetPreview, String> {
+    let relative = normalize_relative(&asset_path);
+    let root = PathBuf::from(&project_path);
+    let path = root.join(&relative);
+    let size_bytes = fs::metadata(&path)
+        .map_err(|e| format!("Failed to read asset {}: {}", asset_path, e))?
+        .len();
+    let kind = AssetKind::from_path(&path);
+    let extension = path.extension().map(|e| e.to_string_lossy().to_lowercase()).unwrap_or_default();
+    let mime_type = match extension.as_str() {
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "bmp" => "image/bmp",
+        "wav" => "audio/wav",
+        "mp3" => "audio/mpeg",
+        "ogg" => "audio/ogg",
+        "flac" => "audio/flac",
+        "m4a" => "audio/mp4",
+        _ => "application/octet-stream",
+    };
+    let entry = AssetManifest::load(&project_path)?.find(&relative).cloned();
+    let dimensions = entry
+        .as_ref()
+        .and_then(|e| e.dimensions)
+        .or_else(|| image::image_dimensions(&path).ok().map(|(width, height)| Dimensions { width, height }));
+
+    let (data_url, preview_path) = if size_bytes <= MAX_INLINE_PREVIEW_BYTES {
+        let bytes = fs::read(&path).map_err(|e| format!("Failed to read asset {}: {}", asset_path, e))?;
+        let encoded = base64::engine::general_purpose::STANDARD.encode(bytes);
+        (Some(format!("data:{};base64,{}", mime_type, encoded)), None)
+    } else {
+        let thumbnail = entry
+            .and_then(|e| e.thumbnail)
+            .map(|thumbnail| root.join(thumbnail))
+            .filter(|thumbnail| thumbnail.is_file());
+        let preview = thumbnail.unwrap_or(path);
+        (None, Some(preview.to_string_lossy().to_string()))
+    };
+
+    Ok(AssetPreview {
+        kind,
+        mime_type,
+        data_url,
+        preview_path,
+        dimensions,
+        size_bytes,
+    })
+}
+
+/// Get the project's asset manifest
+#[tauri::command]
+pub async fn get_asset_manifest(project_path: String) -> Result<AssetManifest, String> {
+    AssetManifest::load(&project_path)
+}
@@ -0,0 +1,218 @@
+use crate::ai_client::AIClientState;
+use crate::entity_ids;
+use crate::entity_locks;
+use crate::json_guard;
+use crate::history::{self, HistoryEntry};
+use crate::notifications::{self, NotificationCategory};
+use crate::rename;
+use crate::spec;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashSet;
+
+/// Entities per AI request; keeps each reply well inside the output token limit
+const BATCH_SIZE: usize = 40;
+/// Names editors and generators hand out before anyone picks a real one
+const GENERIC_BASES: &[&str] = &["", "entity", "newentity", "new_entity", "object", "gameobject", "node", "item", "sprite", "e"];
+/// Trailing numbers this long are timestamps (`entity_1767759446541`), not meaningful indices
+const TIMESTAMP_DIGITS: usize = 8;
+
+const SYSTEM_PROMPT: &str = r#"You name entities in 2D game levels so designers can find them in a hierarchy panel.
+For each entity you get its id, current name, scene, position, tags and components.
+Reply with only a JSON array, one object per entity: {"id": "<id>", "name": "<name>", "description": "<one short sentence>"}.
+Names: letters, digits, underscores or hyphens, starting with a letter; short and specific to role and location, e.g. "ledge_upper_left", "patrol_guard_bridge", "coin_secret_alcove"."#;
+
+#[derive(Debug, Serialize)]
+pub struct EntityLabel {
+    pub id: String,
+    pub old_name: String,
+    pub new_name: String,
+    pub description: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AutolabelResult {
+    pub labels: Vec<EntityLabel>,
+    /// Unnamed entities without an id, which can't be addressed safely
+    pub skipped: usize,
+    /// Entities in scope left alone because they're locked
+    pub locked: usize,
+    pub history: Option<HistoryEntry>,
+    /// Updated game.json for the editor
+    pub game_spec_json: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct SuggestedLabel {
+    id: String,
+    name: String,
+    #[serde(default)]
+    description: String,
+}
+
+/// Missing, empty or generator-style names such as `Entity_3` or `entity_1767759446541`
+fn is_unnamed(name: &str) -> bool {
+    let lower = name.trim().to_lowercase();
+    let base = lower.trim_end_matches(|c: char| c.is_ascii_digit() || c == '_' || c == '-' || c == ' ');
+    let digits = lower.len() - base.len();
+    GENERIC_BASES.contains(&base) || (digits >= TIMESTAMP_DIGITS && lower[base.len()..].chars().all(|c| c.is_ascii_digit() || c == '_'))
+}
+
+/// Force a suggestion into the schema's name pattern and make it unique among `taken`
+fn sanitize_name(raw: &str, taken: &HashSet<String>) -> Option<String> {
+    let cleaned: String = raw
+        .trim()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' { c } else { '_' })
+        .collect();
+    let cleaned = cleaned.trim_matches('_').trim_start_matches(|c: char| !c.is_ascii_alphabetic());
+    if cleaned.is_empty() {
+        return None;
+    }
+    let mut name = cleaned.to_string();
+    let mut suffix = 2;
+    while taken.contains(&name) {
+        name = format!("{}_{}", cleaned, suffix);
+        suffix += 1;
+    }
+    Some(name)
+}
+
+fn describe_for_prompt(scene: Option<&str>, entity: &Value) -> Value {
+    let mut components = entity.get("components").cloned().unwrap_or(Value::Null);
+    // Texture paths and tints add tokens without helping the model tell entities apart
+    if let Some(sprite) = components.get_mut("sprite").and_then(Value::as_object_mut) {
+        sprite.retain(|key, _| key == "width" || key == "height");
+    }
+    json!({
+        "id": entity_ids::entity_id(entity),
+        "name": entity.get("name").and_then(Value::as_str).unwrap_or_default(),
+        "scene": scene.unwrap_or("main"),
+        "tags": entity.get("tags").cloned().unwrap_or(Value::Null),
+        "components": components,
+    })
+}
+
+/// The JSON array in a reply, tolerating prose or a code fence around it
+fn parse_labels(reply: &str) -> Result<Vec<SuggestedLabel>, String> {
+    let start = reply.find('[').ok_or("The AI reply contained no labels")?;
+    let end = reply.rfind(']').ok_or("The AI reply contained no labels")?;
+    json_guard::parse(&reply.as_bytes()[start..=end], "AI labels", &json_guard::AI_REPLY_LIMITS)
+}
+
+/// Ask the AI to name and describe unnamed entities, then rename them (references included) as
+/// one undoable change. `overwrite` relabels every entity in scope, not just unnamed ones.
+#[tauri::command]
+pub async fn ai_autolabel_entities(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, AIClientState>,
+    project_path: String,
+    scene: Option<String>,
+    overwrite: Option<bool>,
+) -> Result<AutolabelResult, String> {
+    let before = spec::load_project_spec(&project_path)?;
+    let overwrite = overwrite.unwrap_or(false);
+
+    let mut skipped = 0;
+    let mut locked = 0;
+    let candidates: Vec<Value> = spec::entities_with_scene(&before)
+        .into_iter()
+        .filter(|(entity_scene, _)| scene.is_none() || scene.as_deref() == Some(entity_scene.unwrap_or("main")))
+        .filter(|(_, e)| overwrite || is_unnamed(e.get("name").and_then(Value::as_str).unwrap_or_default()))
+        .filter(|(_, e)| {
+            let is_locked = entity_locks::is_locked(e);
+            locked += is_locked as usize;
+            !is_locked
+        })
+        .filter(|(_, e)| {
+            let has_id = entity_ids::entity_id(e).is_some();
+            if !has_id {
+                skipped += 1;
+            }
+            has_id
+        })
+        .map(|(entity_scene, e)| describe_for_prompt(entity_scene, e))
+        .collect();
+    if candidates.is_empty() {
+        return Ok(AutolabelResult {
+            labels: Vec::new(),
+            skipped,
+            locked,
+            history: None,
+            game_spec_json: serde_json::to_string_pretty(&before).map_err(|e| format!("Failed to serialize game spec: {}", e))?,
+        });
+    }
+
+    let mut suggestions = Vec::new();
+    {
+        let client = state.0.lock().await;
+        if !client.has_api_key() {
+            return Err("API key not configured. Set ANTHROPIC_API_KEY environment variable or configure in settings.".to_string());
+        }
+        for batch in candidates.chunks(BATCH_SIZE) {
+            let prompt = serde_json::to_string(batch).map_err(|e| format!("Failed to serialize entities: {}", e))?;
+            let reply = client.complete(SYSTEM_PROMPT.to_string(), prompt).await?;
+            suggestions.extend(parse_labels(&reply)?);
+        }
+    }
+
+    let candidate_ids: HashSet<&str> = candidates.iter().filter_map(|c| c["id"].as_str()).collect();
+    let mut taken: HashSet<String> = spec::all_entities(&before)
+        .filter(|e| !entity_ids::entity_id(e).is_some_and(|id| candidate_ids.contains(id)))
+        .filter_map(|e| e.get("name").and_then(Value::as_str).map(str::to_string))
+        .collect();
+
+    let mut game_spec = before.clone();
+    let mut labels = Vec::new();
+    for suggestion in suggestions {
+        if !candidate_ids.contains(suggestion.id.as_str()) || labels.iter().any(|l: &EntityLabel| l.id == suggestion.id) {
+            continue;
+        }
+        let Some(new_name) = sanitize_name(&suggestion.name, &taken) else {
+            continue;
+        };
+        let Some(entity) = spec::find_entity_by_id_mut(&mut game_spec, &suggestion.id) else {
+            continue;
+        };
+        let old_name = entity.get("name").and_then(Value::as_str).unwrap_or_default().to_string();
+        let description = suggestion.description.trim().to_string();
+        if !description.is_empty() {
+            entity["description"] = Value::String(description.clone());
+        }
+        if old_name.is_empty() {
+            // Nothing can reference an empty name, so there are no references to rewrite
+            entity["name"] = Value::String(new_name.clone());
+        } else if old_name != new_name {
+            rename::apply_entity_rename(&mut game_spec, &suggestion.id, &old_name, &new_name);
+        }
+        taken.insert(new_name.clone());
+        labels.push(EntityLabel {
+            id: suggestion.id,
+            old_name,
+            new_name,
+            description,
+        });
+    }
+
+    let history = if labels.is_empty() {
+        None
+    } else {
+        let entry = history::record(&project_path, &format!("AI label {} entities", labels.len()), &before)?;
+        spec::save_project_spec(&project_path, &game_spec)?;
+        Some(entry)
+    };
+
+    notifications::task_finished(
+        &app,
+        NotificationCategory::AiBatch,
+        "AI labelling finished",
+        &format!("Named {} of {} entities", labels.len(), candidates.len()),
+    );
+    Ok(AutolabelResult {
+        labels,
+        skipped,
+        locked,
+        history,
+        game_spec_json: serde_json::to_string_pretty(&game_spec).map_err(|e| format!("Failed to serialize game spec: {}", e))?,
+    })
+}
@@ -0,0 +1,358 @@
+use crate::paths;
+use crate::workspace::{self, WorkspaceManager};
+use crate::settings::AppSettings;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Manager};
+use zip::write::SimpleFileOptions;
+
+/// Folder under the app data directory used when no backup directory is chosen
+const DEFAULT_BACKUP_DIR: &str = "backups";
+/// Generated state and dependency folders left out of backups
+const EXCLUDED_DIRS: &[&str] = &[".promptplay", "node_modules", ".git", ".cache"];
+/// How often the scheduler wakes to check for a stop request
+const POLL_SECONDS: u64 = 5;
+/// Archives with more entries than this are refused before anything is extracted
+const MAX_RESTORE_ENTRIES: usize = 50_000;
+/// Total bytes a restore may write, so a zip bomb stops instead of filling the disk
+const MAX_RESTORE_BYTES: u64 = 4 * 1024 * 1024 * 1024;
+
+/// Scheduled backups (`backups` in the app settings)
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase", default)]
+pub struct BackupSettings {
+    pub enabled: bool,
+    pub interval_minutes: u64,
+    /// Backups kept per project; older ones are deleted
+    pub retention: usize,
+    /// Where backups go; defaults to the app data directory
+    pub directory: Option<String>,
+}
+
+impl Default for BackupSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval_minutes: 30,
+            retention: 10,
+            directory: None,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct BackupInfo {
+    pub path: String,
+    pub file_name: String,
+    pub created_at: u64,
+    pub size: u64,
+}
+
+/// Stop flag of the running backup scheduler
+#[derive(Default)]
+pub struct BackupSchedulerState(Mutex<Option<Arc<AtomicBool>>>);
+
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// `<backup dir>/<project folder name>/`
+fn project_backup_dir(app: &AppHandle, settings: &BackupSettings, project: &Path) -> Result<PathBuf, String> {
+    let root = match &settings.directory {
+        Some(dir) => PathBuf::from(dir),
+        None => app
+            .path()
+            .app_data_dir()
+            .map(|dir| dir.join(DEFAULT_BACKUP_DIR))
+            .map_err(|e| format!("Failed to resolve app data directory: {}", e))?,
+    };
+    let name: String = project
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| "project".to_string())
+        .chars()
+        .map(|c| if c.is_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+        .collect();
+    Ok(root.join(name))
+}
+
+/// Project files to back up as (absolute path, archive name), skipping caches and the backup folder itself
+fn backup_files(project: &Path, backup_dir: &Path) -> Vec<(PathBuf, String)> {
+    let backup_dir = paths::canonical(backup_dir);
+    let mut files = Vec::new();
+    let mut pending = vec![project.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        for entry in fs::read_dir(&dir).into_iter().flatten().flatten() {
+            let path = entry.path();
+            let Ok(file_type) = entry.file_type() else { continue };
+            if file_type.is_dir() {
+                let name = entry.file_name().to_string_lossy().to_string();
+                if !EXCLUDED_DIRS.contains(&name.as_str()) && !paths::same_path(&path, &backup_dir) {
+                    pending.push(path);
+                }
+            } else if file_type.is_file() {
+                if let Some(relative) = paths::relative_to(&path, project) {
+                    files.push((path, paths::to_spec_path(&relative)));
+                }
+            }
+        }
+    }
+    files.sort_by(|a, b| a.1.cmp(&b.1));
+    files
+}
+
+fn list(dir: &Path) -> Vec<BackupInfo> {
+    let mut backups: Vec<BackupInfo> = fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter(|entry| entry.path().extension().is_some_and(|e| e == "zip"))
+        .filter_map(|entry| {
+            let metadata = entry.metadata().ok()?;
+            let created_at = metadata
+                .modified()
+                .ok()
+                .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+                .map(|d| d.as_millis() as u64)
+                .unwrap_or(0);
+            Some(BackupInfo {
+                path: paths::to_display(&entry.path()),
+                file_name: entry.file_name().to_string_lossy().to_string(),
+                created_at,
+                size: metadata.len(),
+            })
+        })
+        .collect();
+    // Newest first; file names carry the timestamp, so they sort too when mtimes tie
+    backups.sort_by(|a, b| b.created_at.cmp(&a.created_at).then(b.file_name.cmp(&a.file_name)));
+    backups
+}
+
+/// Files a backup or sync snapshot of the project contains, as (absolute path, archive name)
+pub fn project_files(app: &AppHandle, project: &Path) -> Result<Vec<(PathBuf, String)>, String> {
+    let dir = project_backup_dir(app, &AppSettings::load(app).backups, project)?;
+    Ok(backup_files(project, &dir))
+}
+
+/// Zip `files` into `target`, writing to a temporary file first so a failure leaves no partial archive
+pub fn write_archive(files: &[(PathBuf, String)], target: &Path) -> Result<(), String> {
+    let partial = target.with_extension("zip.part");
+    let write = || -> Result<(), String> {
+        let file = File::create(&partial).map_err(|e| format!("Failed to create archive: {}", e))?;
+        let mut zip = zip::ZipWriter::new(file);
+        let options = SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+        for (path, name) in files {
+            zip.start_file(name.as_str(), options)
+                .map_err(|e| format!("Failed to add {} to archive: {}", name, e))?;
+            let mut source = File::open(path).map_err(|e| format!("Failed to read {}: {}", name, e))?;
+            io::copy(&mut source, &mut zip).map_err(|e| format!("Failed to add {} to archive: {}", name, e))?;
+        }
+        zip.finish().map_err(|e| format!("Failed to finish archive: {}", e))?;
+        fs::rename(&partial, target).map_err(|e| format!("Failed to finish archive: {}", e))
+    };
+    write().inspect_err(|_| {
+        let _ = fs::remove_file(&partial);
+    })
+}
+
+/// Zip the project into its backup folder and prune backups beyond the retention count
+pub fn create(app: &AppHandle, project_path: &str, label: &str) -> Result<BackupInfo, String> {
+    let settings = AppSettings::load(app).backups;
+    let project = paths::project_root(project_path);
+    let dir = project_backup_dir(app, &settings, &project)?;
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create backup directory {}: {}", dir.display(), e))?;
+
+    let target = dir.join(format!("{}-{}.zip", label, now_millis()));
+    write_archive(&backup_files(&project, &dir), &target)?;
+
+    for old in list(&dir).iter().skip(settings.retention.max(1)) {
+        let _ = fs::remove_file(&old.path);
+    }
+    list(&dir)
+        .into_iter()
+        .find(|b| paths::same_path(Path::new(&b.path), &target))
+        .ok_or_else(|| "Backup was written but could not be listed".to_string())
+}
+
+/// Newest modification time of any backed-up file, to skip backups of unchanged projects
+fn last_change(project: &Path, backup_dir: &Path) -> Option<SystemTime> {
+    backup_files(project, backup_dir)
+        .iter()
+        .filter_map(|(path, _)| fs::metadata(path).and_then(|m| m.modified()).ok())
+        .max()
+}
+
+impl BackupSchedulerState {
+    /// Back the project up every configured interval while it's open and has changed
+    pub fn start(&self, app: &AppHandle, project_path: &str) -> Result<(), String> {
+        self.stop()?;
+        let settings = AppSettings::load(app).backups;
+        if !settings.enabled {
+            return Ok(());
+        }
+        let stop = Arc::new(AtomicBool::new(false));
+        *self.0.lock().map_err(|e| format!("Lock error: {}", e))? = Some(stop.clone());
+
+        let app = app.clone();
+        let project_path = project_path.to_string();
+        std::thread::spawn(move || {
+            let interval = Duration::from_secs(settings.interval_minutes.max(1) * 60);
+            let project = paths::project_root(&project_path);
+            let mut backed_up = None;
+            let mut elapsed = Duration::ZERO;
+            while !stop.load(Ordering::Relaxed) {
+                std::thread::sleep(Duration::from_secs(POLL_SECONDS));
+                elapsed += Duration::from_secs(POLL_SECONDS);
+                if elapsed < interval {
+                    continue;
+                }
+                elapsed = Duration::ZERO;
+
+                let changed = project_backup_dir(&app, &settings, &project)
+                    .map(|dir| last_change(&project, &dir))
+                    .unwrap_or(None);
+                if changed.is_some() && changed == backed_up {
+                    continue;
+                }
+                match create(&app, &project_path, "auto") {
+                    Ok(backup) => {
+                        backed_up = changed;
+                        workspace::emit_to_project(&app, &project, "backup-created", backup);
+                    }
+                    Err(error) => {
+                        workspace::emit_to_project(
+                            &app,
+                            &project,
+                            "backup-failed",
+                            serde_json::json!({ "projectPath": project_path, "error": error }),
+                        );
+                    }
+                }
+            }
+        });
+        Ok(())
+    }
+
+    pub fn stop(&self) -> Result<(), String> {
+        if let Some(stop) = self.0.lock().map_err(|e| format!("Lock error: {}", e))?.take() {
+            stop.store(true, Ordering::Relaxed);
+        }
+        Ok(())
+    }
+}
+
+/// Replace the project's files with an archive's, keeping caches; a safety backup is taken first
+pub fn restore(app: &AppHandle, backup_path: &Path, project_path: &str) -> Result<usize, String> {
+    let file = File::open(backup_path).map_err(|e| format!("Failed to open backup: {}", e))?;
+    let mut archive = zip::ZipArchive::new(file).map_err(|e| format!("Failed to read backup: {}", e))?;
+    let project = paths::project_root(project_path);
+    if archive.len() > MAX_RESTORE_ENTRIES {
+        return Err(format!(
+            "Backup has {} entries, over the {} entry limit",
+            archive.len(),
+            MAX_RESTORE_ENTRIES
+        ));
+    }
+    let declared: u64 = (0..archive.len())
+        .filter_map(|index| archive.by_index_raw(index).ok().map(|entry| entry.size()))
+        .sum();
+    if declared > MAX_RESTORE_BYTES {
+        return Err(format!(
+            "Backup would extract to {} MB, over the {} MB limit",
+            declared / (1024 * 1024),
+            MAX_RESTORE_BYTES / (1024 * 1024)
+        ));
+    }
+
+    create(app, project_path, "before-restore")?;
+    let backup_dir = project_backup_dir(app, &AppSettings::load(app).backups, &project)?;
+    for (path, _) in backup_files(&project, &backup_dir) {
+        fs::remove_file(&path).map_err(|e| format!("Failed to clear {}: {}", path.display(), e))?;
+    }
+
+    let mut restored = 0;
+    let mut written = 0;
+    for index in 0..archive.len() {
+        let mut entry = archive.by_index(index).map_err(|e| format!("Failed to read backup: {}", e))?;
+        // enclosed_name rejects absolute and `..` paths, so nothing escapes the project
+        let Some(relative) = entry.enclosed_name() else {
+            continue;
+        };
+        let target = project.join(relative);
+        if entry.is_dir() {
+            fs::create_dir_all(&target).map_err(|e| format!("Failed to restore {}: {}", target.display(), e))?;
+            continue;
+        }
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent).map_err(|e| format!("Failed to restore {}: {}", parent.display(), e))?;
+        }
+        let mut out = File::create(&target).map_err(|e| format!("Failed to restore {}: {}", target.display(), e))?;
+        // Sizes in the zip headers can lie, so the running total is enforced on the bytes themselves
+        written += io::copy(&mut (&mut entry).take(MAX_RESTORE_BYTES - written + 1), &mut out)
+            .map_err(|e| format!("Failed to restore {}: {}", target.display(), e))?;
+        if written > MAX_RESTORE_BYTES {
+            return Err(format!(
+                "Backup extracts to more than {} MB; restore stopped at {}",
+                MAX_RESTORE_BYTES / (1024 * 1024),
+                target.display()
+            ));
+        }
+        restored += 1;
+    }
+    Ok(restored)
+}
+
+/// Current backup schedule settings
+#[tauri::command]
+pub async fn get_backup_settings(app: AppHandle) -> Result<BackupSettings, String> {
+    Ok(AppSettings::load(&app).backups)
+}
+
+/// Change the backup schedule; the scheduler of an open project restarts with it
+#[tauri::command]
+pub async fn set_backup_settings(
+    app: AppHandle,
+    settings: BackupSettings,
+    project_path: Option<String>,
+    window: tauri::Window,
+    workspace: tauri::State<'_, WorkspaceManager>,
+) -> Result<(), String> {
+    if let Some(dir) = &settings.directory {
+        fs::create_dir_all(dir).map_err(|e| format!("Failed to create backup directory {}: {}", dir, e))?;
+    }
+    let mut app_settings = AppSettings::load(&app);
+    app_settings.backups = settings;
+    app_settings.save(&app)?;
+    match project_path {
+        Some(project_path) => workspace.services(window.label())?.backups.start(&app, &project_path),
+        None => Ok(()),
+    }
+}
+
+/// Back the project up now
+#[tauri::command]
+pub async fn create_backup(app: AppHandle, project_path: String) -> Result<BackupInfo, String> {
+    create(&app, &project_path, "manual")
+}
+
+/// Backups of a project, newest first
+#[tauri::command]
+pub async fn list_backups(app: AppHandle, project_path: String) -> Result<Vec<BackupInfo>, String> {
+    let settings = AppSettings::load(&app).backups;
+    let dir = project_backup_dir(&app, &settings, &paths::project_root(&project_path))?;
+    Ok(list(&dir))
+}
+
+/// Restore a backup over the project, returning how many files were restored
+#[tauri::command]
+pub async fn restore_backup(app: AppHandle, backup_path: String, project_path: String) -> Result<usize, String> {
+    restore(&app, Path::new(&backup_path), &project_path)
+}
@@ -0,0 +1,167 @@
+use crate::assets::AssetManifest;
+use crate::components::{self, ComponentRegistry};
+use crate::export::{self, ExportOptions};
+use crate::{compression, encryption, licensing, spec, validation};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use std::time::Instant;
+
+const BASELINE_FILE: &str = ".promptplay/benchmarks.json";
+const DEFAULT_ITERATIONS: usize = 5;
+/// Slowdowns beyond this are reported as regressions
+const REGRESSION_THRESHOLD_PERCENT: f64 = 20.0;
+
+#[derive(Debug, Serialize)]
+pub struct StageTiming {
+    pub stage: String,
+    pub median_ms: f64,
+    pub min_ms: f64,
+    pub max_ms: f64,
+    pub baseline_ms: Option<f64>,
+    /// Relative to the baseline; positive means slower
+    pub change_percent: Option<f64>,
+    pub regression: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BenchmarkReport {
+    pub spec_bytes: usize,
+    pub entity_count: usize,
+    pub iterations: usize,
+    pub stages: Vec<StageTiming>,
+    pub baseline_saved: bool,
+}
+
+/// Median per stage from a previous run, keyed by stage name
+#[derive(Debug, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+struct Baselines {
+    recorded_at: u64,
+    stages: BTreeMap<String, f64>,
+}
+
+fn baseline_path(project_path: &str) -> PathBuf {
+    PathBuf::from(project_path).join(BASELINE_FILE)
+}
+
+fn load_baselines(project_path: &str) -> Baselines {
+    fs::read_to_string(baseline_path(project_path))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_baselines(project_path: &str, baselines: &Baselines) -> Result<(), String> {
+    let path = baseline_path(project_path);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create benchmark directory: {}", e))?;
+    }
+    let content = serde_json::to_string_pretty(baselines)
+        .map_err(|e| format!("Failed to serialize benchmark baselines: {}", e))?;
+    fs::write(&path, content).map_err(|e| format!("Failed to write benchmark baselines: {}", e))
+}
+
+/// Run `f` repeatedly and return (median, min, max) in milliseconds
+fn time<T>(iterations: usize, mut f: impl FnMut() -> Result<T, String>) -> Result<(f64, f64, f64), String> {
+    let mut samples = Vec::with_capacity(iterations);
+    for _ in 0..iterations {
+        let start = Instant::now();
+        f()?;
+        samples.push(start.elapsed().as_secs_f64() * 1000.0);
+    }
+    samples.sort_by(|a, b| a.total_cmp(b));
+    Ok((samples[samples.len() / 2], samples[0], samples[samples.len() - 1]))
+}
+
+/// Time spec parsing, validation, lint and export for a project and compare with stored baselines
+#[tauri::command]
+pub async fn benchmark_project(
+    project_path: String,
+    iterations: Option<usize>,
+    save_baseline: Option<bool>,
+) -> Result<BenchmarkReport, String> {
+    let iterations = iterations.unwrap_or(DEFAULT_ITERATIONS).clamp(1, 100);
+    let game_json = PathBuf::from(&project_path).join("game.json");
+    let content = String::from_utf8(compression::decompress(encryption::read(&game_json)?)?)
+        .map_err(|e| format!("Failed to read game.json in {}: {}", project_path, e))?;
+
+    let game_spec = spec::load_project_spec(&project_path)?;
+    let registry = ComponentRegistry::load(&project_path)?;
+    let manifest = AssetManifest::load(&project_path)?;
+    let options = ExportOptions {
+        project_path: Some(project_path.clone()),
+        ..Default::default()
+    };
+
+    let stages: Vec<(&str, (f64, f64, f64))> = vec![
+        (
+            "parse",
+            time(iterations, || {
+                serde_json::from_str::<Value>(&content).map_err(|e| format!("Failed to parse game.json: {}", e))
+            })?,
+        ),
+        ("validate", time(iterations, || Ok(validation::validate_spec(&game_spec)))?),
+        (
+            "lint",
+            time(iterations, || {
+                let mut findings = components::validate_components(&game_spec, &registry).len();
+                findings += licensing::distribution_warnings(&manifest, &game_spec).len();
+                Ok(findings)
+            })?,
+        ),
+        (
+            "export",
+            time(iterations, || {
+                let prepared = export::prepare_spec(&content, &options)?;
+                let json = serde_json::to_string(&prepared)
+                    .map_err(|e| format!("Failed to serialize game spec: {}", e))?;
+                Ok(export::generate_standalone_html(&json, "benchmark").len())
+            })?,
+        ),
+    ];
+
+    let baselines = load_baselines(&project_path);
+    let timings: Vec<StageTiming> = stages
+        .iter()
+        .map(|(stage, (median, min, max))| {
+            let baseline = baselines.stages.get(*stage).copied();
+            let change = baseline
+                .filter(|b| *b > 0.0)
+                .map(|b| (median - b) / b * 100.0);
+            StageTiming {
+                stage: stage.to_string(),
+                median_ms: *median,
+                min_ms: *min,
+                max_ms: *max,
+                baseline_ms: baseline,
+                change_percent: change,
+                regression: change.is_some_and(|c| c > REGRESSION_THRESHOLD_PERCENT),
+            }
+        })
+        .collect();
+
+    let baseline_saved = save_baseline.unwrap_or(false);
+    if baseline_saved {
+        save_baselines(
+            &project_path,
+            &Baselines {
+                recorded_at: std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .map(|d| d.as_millis() as u64)
+                    .unwrap_or(0),
+                stages: timings.iter().map(|t| (t.stage.clone(), t.median_ms)).collect(),
+            },
+        )?;
+    }
+
+    Ok(BenchmarkReport {
+        spec_bytes: content.len(),
+        entity_count: spec::all_entities(&game_spec).count(),
+        iterations,
+        stages: timings,
+        baseline_saved,
+    })
+}
@@ -0,0 +1,161 @@
+use crate::paths;
+use crate::settings::AppSettings;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+
+/// Everything PromptPlay can regenerate, each in its own directory under the cache root
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "camelCase")]
+pub enum CacheCategory {
+    /// Rendered scene thumbnails, per project
+    Thumbnails,
+    /// Preview builds, parity pages and sync archives
+    Exports,
+    /// Dictation audio handed to speech-to-text
+    AiTranscripts,
+    /// Runtime libraries fetched for offline exports (Matter.js)
+    Downloads,
+}
+
+impl CacheCategory {
+    pub const ALL: [CacheCategory; 4] = [
+        CacheCategory::Thumbnails,
+        CacheCategory::Exports,
+        CacheCategory::AiTranscripts,
+        CacheCategory::Downloads,
+    ];
+
+    fn dir_name(self) -> &'static str {
+        match self {
+            CacheCategory::Thumbnails => "thumbnails",
+            CacheCategory::Exports => "exports",
+            CacheCategory::AiTranscripts => "ai-transcripts",
+            CacheCategory::Downloads => "downloads",
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct CategoryUsage {
+    pub category: CacheCategory,
+    pub path: String,
+    pub bytes: u64,
+    pub files: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CacheUsage {
+    pub root: String,
+    /// Whether `root` was chosen in the settings rather than the platform cache directory
+    pub custom: bool,
+    pub total_bytes: u64,
+    pub categories: Vec<CategoryUsage>,
+}
+
+/// The cache root: `cacheDir` from the settings, or the platform cache directory
+pub fn root(app: &AppHandle) -> Result<PathBuf, String> {
+    match AppSettings::load(app).cache_dir {
+        Some(dir) => Ok(PathBuf::from(dir)),
+        None => app
+            .path()
+            .app_cache_dir()
+            .map_err(|e| format!("Failed to resolve cache directory: {}", e)),
+    }
+}
+
+/// A category's directory, created if missing
+pub fn dir(app: &AppHandle, category: CacheCategory) -> Result<PathBuf, String> {
+    let dir = root(app)?.join(category.dir_name());
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create cache directory {}: {}", dir.display(), e))?;
+    Ok(dir)
+}
+
+/// A short stable key for a project folder, for per-project cache subdirectories
+pub fn project_key(project_path: &str) -> String {
+    let root = paths::to_display(&paths::canonical(&paths::project_root(project_path)));
+    Sha256::digest(root.as_bytes()).iter().take(8).map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Bytes and file count under a directory
+fn measure(dir: &Path) -> (u64, usize) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return (0, 0);
+    };
+    entries.flatten().fold((0, 0), |(bytes, files), entry| match entry.file_type() {
+        Ok(kind) if kind.is_dir() => {
+            let (b, f) = measure(&entry.path());
+            (bytes + b, files + f)
+        }
+        Ok(_) => (bytes + entry.metadata().map(|m| m.len()).unwrap_or(0), files + 1),
+        Err(_) => (bytes, files),
+    })
+}
+
+fn usage(app: &AppHandle) -> Result<CacheUsage, String> {
+    let root = root(app)?;
+    let categories: Vec<CategoryUsage> = CacheCategory::ALL
+        .iter()
+        .map(|&category| {
+            let path = root.join(category.dir_name());
+            let (bytes, files) = measure(&path);
+            CategoryUsage {
+                category,
+                path: paths::to_display(&path),
+                bytes,
+                files,
+            }
+        })
+        .collect();
+    Ok(CacheUsage {
+        root: paths::to_display(&root),
+        custom: AppSettings::load(app).cache_dir.is_some(),
+        total_bytes: categories.iter().map(|c| c.bytes).sum(),
+        categories,
+    })
+}
+
+fn clear(root: &Path, categories: &[CacheCategory]) -> Result<(), String> {
+    for category in categories {
+        let dir = root.join(category.dir_name());
+        match fs::remove_dir_all(&dir) {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(format!("Failed to clear {}: {}", dir.display(), e)),
+        }
+    }
+    Ok(())
+}
+
+/// Size of each cache category, for the storage settings page
+#[tauri::command]
+pub async fn get_cache_usage(app: AppHandle) -> Result<CacheUsage, String> {
+    usage(&app)
+}
+
+/// Delete the given cache categories, or all of them; everything in the cache is regenerated on demand
+#[tauri::command]
+pub async fn clear_cache(app: AppHandle, categories: Option<Vec<CacheCategory>>) -> Result<CacheUsage, String> {
+    let categories = categories.unwrap_or_else(|| CacheCategory::ALL.to_vec());
+    clear(&root(&app)?, &categories)?;
+    usage(&app)
+}
+
+/// Move the cache to `dir`, or back to the platform cache directory with None. The old cache is
+/// cleared rather than copied.
+#[tauri::command]
+pub async fn set_cache_dir(app: AppHandle, dir: Option<String>) -> Result<CacheUsage, String> {
+    if let Some(dir) = &dir {
+        fs::create_dir_all(dir).map_err(|e| format!("Failed to create cache directory {}: {}", dir, e))?;
+    }
+    let previous = root(&app)?;
+    let mut settings = AppSettings::load(&app);
+    settings.cache_dir = dir;
+    settings.save(&app)?;
+    if !paths::same_path(&previous, &root(&app)?) {
+        clear(&previous, &CacheCategory::ALL)?;
+    }
+    usage(&app)
+}
@@ -0,0 +1,214 @@
+use crate::assets::AssetManifest;
+use crate::history::{self, HistoryEntry};
+use crate::{paths, spec};
+use image::RgbaImage;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+
+const DEFAULT_ALPHA_THRESHOLD: u8 = 16;
+const DEFAULT_MAX_VERTICES: usize = 8;
+/// Matter.js and most 2D engines need at least a triangle
+const MIN_VERTICES: usize = 3;
+
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ColliderMode {
+    /// Tight axis-aligned box around the opaque pixels
+    Box,
+    /// Circle enclosing the opaque pixels
+    Circle,
+    /// Simplified convex hull as a polygon collider
+    Hull,
+}
+
+#[derive(Debug, Serialize)]
+pub struct GeneratedCollider {
+    pub entity_id: String,
+    /// The collider component as written to the entity
+    pub collider: Value,
+    pub history: HistoryEntry,
+    /// Updated game.json for the editor
+    pub game_spec_json: String,
+}
+
+/// Opaque pixel bounds as (min_x, min_y, max_x + 1, max_y + 1), or None for a fully clear image
+fn opaque_bounds(image: &RgbaImage, threshold: u8) -> Option<(u32, u32, u32, u32)> {
+    let (width, height) = image.dimensions();
+    let mut bounds: Option<(u32, u32, u32, u32)> = None;
+    for y in 0..height {
+        for x in 0..width {
+            if image.get_pixel(x, y).0[3] > threshold {
+                bounds = Some(match bounds {
+                    None => (x, y, x + 1, y + 1),
+                    Some((x0, y0, x1, y1)) => (x0.min(x), y0.min(y), x1.max(x + 1), y1.max(y + 1)),
+                });
+            }
+        }
+    }
+    bounds
+}
+
+/// Corners of the leftmost and rightmost opaque pixel on each row; their hull is the sprite's hull
+fn row_extremes(image: &RgbaImage, threshold: u8) -> Vec<(f64, f64)> {
+    let (width, height) = image.dimensions();
+    let mut points = Vec::new();
+    for y in 0..height {
+        let opaque = |x: &u32| image.get_pixel(*x, y).0[3] > threshold;
+        let (Some(left), Some(right)) = ((0..width).find(opaque), (0..width).rev().find(opaque)) else {
+            continue;
+        };
+        let (y, left, right) = (y as f64, left as f64, right as f64 + 1.0);
+        points.extend([(left, y), (left, y + 1.0), (right, y), (right, y + 1.0)]);
+    }
+    points
+}
+
+fn cross(o: (f64, f64), a: (f64, f64), b: (f64, f64)) -> f64 {
+    (a.0 - o.0) * (b.1 - o.1) - (a.1 - o.1) * (b.0 - o.0)
+}
+
+/// Andrew's monotone chain; counter-clockwise in y-down image space, without collinear points
+fn convex_hull(mut points: Vec<(f64, f64)>) -> Vec<(f64, f64)> {
+    points.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.1.total_cmp(&b.1)));
+    points.dedup();
+    if points.len() < MIN_VERTICES {
+        return points;
+    }
+    let mut hull: Vec<(f64, f64)> = Vec::with_capacity(points.len() * 2);
+    for pass in [points.clone(), points.into_iter().rev().collect()] {
+        let start = hull.len();
+        for point in pass {
+            while hull.len() >= start + 2 && cross(hull[hull.len() - 2], hull[hull.len() - 1], point) <= 0.0 {
+                hull.pop();
+            }
+            hull.push(point);
+        }
+        // The last point of each chain starts the other one
+        hull.pop();
+    }
+    hull
+}
+
+/// Drop the vertex whose removal loses the least area until `max_vertices` remain (Visvalingam)
+fn simplify(mut hull: Vec<(f64, f64)>, max_vertices: usize) -> Vec<(f64, f64)> {
+    while hull.len() > max_vertices.max(MIN_VERTICES) {
+        let n = hull.len();
+        let smallest = (0..n)
+            .min_by(|&a, &b| {
+                let area = |i: usize| cross(hull[(i + n - 1) % n], hull[i], hull[(i + 1) % n]).abs();
+                area(a).total_cmp(&area(b))
+            })
+            .unwrap_or(0);
+        hull.remove(smallest);
+    }
+    hull
+}
+
+fn round(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+/// Fit a collider to the opaque pixels of an entity's sprite and write it into the entity's
+/// collider component. Sizes are scaled from image pixels to the sprite's display size and
+/// offsets are relative to the sprite center; isSensor and layer are kept.
+#[tauri::command]
+pub async fn generate_collider_from_sprite(
+    project_path: String,
+    entity_id: String,
+    mode: ColliderMode,
+    path: Option<String>,
+    alpha_threshold: Option<u8>,
+    max_vertices: Option<usize>,
+) -> Result<GeneratedCollider, String> {
+    let before = spec::load_project_spec(&project_path)?;
+    let mut game_spec = before.clone();
+    let entity = spec::find_entity_by_id_mut(&mut game_spec, &entity_id)
+        .ok_or_else(|| format!("No entity with id {}", entity_id))?;
+    let name = entity.get("name").and_then(Value::as_str).unwrap_or(&entity_id).to_string();
+    let sprite = entity.get("components").and_then(|c| c.get("sprite")).cloned().unwrap_or(Value::Null);
+
+    // Default to the entity's own texture, resolved through the asset manifest
+    let reference = path
+        .or_else(|| sprite.get("texture").and_then(Value::as_str).map(str::to_string))
+        .ok_or_else(|| format!("Entity '{}' has no sprite texture", name))?;
+    let manifest = AssetManifest::load(&project_path)?;
+    let relative = manifest.resolve(&reference).map(|a| a.path.clone()).unwrap_or(reference);
+    let image_path = paths::project_root(&project_path).join(&relative);
+    let image = image::open(&image_path)
+        .map_err(|e| format!("Failed to open sprite {}: {}", image_path.display(), e))?
+        .to_rgba8();
+
+    let threshold = alpha_threshold.unwrap_or(DEFAULT_ALPHA_THRESHOLD);
+    let (x0, y0, x1, y1) =
+        opaque_bounds(&image, threshold).ok_or_else(|| format!("Sprite {} is fully transparent", relative))?;
+    let (image_width, image_height) = (image.width() as f64, image.height() as f64);
+    let scale_x = sprite.get("width").and_then(Value::as_f64).unwrap_or(image_width) / image_width;
+    let scale_y = sprite.get("height").and_then(Value::as_f64).unwrap_or(image_height) / image_height;
+    // Image pixel to sprite-local units, origin at the sprite center
+    let local = |(x, y): (f64, f64)| ((x - image_width / 2.0) * scale_x, (y - image_height / 2.0) * scale_y);
+
+    let (left, top) = local((x0 as f64, y0 as f64));
+    let (right, bottom) = local((x1 as f64, y1 as f64));
+    let (center_x, center_y) = ((left + right) / 2.0, (top + bottom) / 2.0);
+
+    let mut collider = Map::new();
+    match mode {
+        ColliderMode::Box | ColliderMode::Hull => {
+            let shape = if mode == ColliderMode::Hull { "polygon" } else { "box" };
+            collider.insert("type".to_string(), json!(shape));
+            collider.insert("width".to_string(), json!(round((right - left).max(1.0))));
+            collider.insert("height".to_string(), json!(round((bottom - top).max(1.0))));
+        }
+        ColliderMode::Circle => {
+            let radius = row_extremes(&image, threshold)
+                .into_iter()
+                .map(|point| {
+                    let (x, y) = local(point);
+                    ((x - center_x).powi(2) + (y - center_y).powi(2)).sqrt()
+                })
+                .fold(0.0, f64::max);
+            collider.insert("type".to_string(), json!("circle"));
+            collider.insert("radius".to_string(), json!(round(radius.max(1.0))));
+        }
+    }
+    if mode == ColliderMode::Hull {
+        let hull = simplify(convex_hull(row_extremes(&image, threshold)), max_vertices.unwrap_or(DEFAULT_MAX_VERTICES));
+        let vertices: Vec<Value> = hull
+            .into_iter()
+            .map(|point| {
+                let (x, y) = local(point);
+                json!({ "x": round(x - center_x), "y": round(y - center_y) })
+            })
+            .collect();
+        collider.insert("vertices".to_string(), Value::Array(vertices));
+    }
+    if center_x.abs() >= 0.5 || center_y.abs() >= 0.5 {
+        collider.insert("offsetX".to_string(), json!(round(center_x)));
+        collider.insert("offsetY".to_string(), json!(round(center_y)));
+    }
+
+    let components = entity
+        .get_mut("components")
+        .and_then(Value::as_object_mut)
+        .ok_or_else(|| format!("Entity '{}' has no components", name))?;
+    if let Some(existing) = components.get("collider").and_then(Value::as_object) {
+        for key in ["isSensor", "layer"] {
+            if let Some(value) = existing.get(key) {
+                collider.insert(key.to_string(), value.clone());
+            }
+        }
+    }
+    let collider = Value::Object(collider);
+    components.insert("collider".to_string(), collider.clone());
+
+    let entry = history::record(&project_path, &format!("Generate collider for '{}'", name), &before)?;
+    spec::save_project_spec(&project_path, &game_spec)?;
+
+    Ok(GeneratedCollider {
+        entity_id,
+        collider,
+        history: entry,
+        game_spec_json: serde_json::to_string_pretty(&game_spec)
+            .map_err(|e| format!("Failed to serialize game spec: {}", e))?,
+    })
+}
@@ -0,0 +1,196 @@
+use crate::validation::Diagnostic;
+use serde_json::Value;
+use std::f64::consts::TAU;
+
+/// Polygons need at least a triangle
+pub const MIN_VERTICES: usize = 3;
+/// Plenty for hand-drawn or generated hulls and keeps the per-frame checks cheap
+pub const MAX_VERTICES: usize = 32;
+const EPSILON: f64 = 1e-6;
+
+/// Twice the signed area; positive is the canonical winding, clockwise on screen (y-down), the
+/// order generate_collider_from_sprite writes hulls in
+pub fn signed_area(vertices: &[(f64, f64)]) -> f64 {
+    (0..vertices.len())
+        .map(|i| {
+            let (a, b) = (vertices[i], vertices[(i + 1) % vertices.len()]);
+            a.0 * b.1 - b.0 * a.1
+        })
+        .sum()
+}
+
+/// Convex and simple: every turn goes the same way and the edges wind around exactly once
+pub fn is_convex(vertices: &[(f64, f64)]) -> bool {
+    let n = vertices.len();
+    let mut sign = 0.0;
+    let mut turning = 0.0;
+    for i in 0..n {
+        let (a, b, c) = (vertices[i], vertices[(i + 1) % n], vertices[(i + 2) % n]);
+        let (e1, e2) = ((b.0 - a.0, b.1 - a.1), (c.0 - b.0, c.1 - b.1));
+        let cross = e1.0 * e2.1 - e1.1 * e2.0;
+        if cross.abs() < EPSILON {
+            continue;
+        }
+        if sign != 0.0 && cross.signum() != sign {
+            return false;
+        }
+        sign = cross.signum();
+        turning += cross.atan2(e1.0 * e2.0 + e1.1 * e2.1);
+    }
+    (turning.abs() - TAU).abs() < 1e-3
+}
+
+fn parse_vertices(value: &Value) -> Option<Vec<(f64, f64)>> {
+    value
+        .as_array()?
+        .iter()
+        .map(|v| Some((v.get("x")?.as_f64()?, v.get("y")?.as_f64()?)))
+        .collect()
+}
+
+/// Right triangle filling a `width` x `height` box, high on the `rise` side ("right" or "left")
+fn slope_vertices(width: f64, height: f64, rise: &str) -> Vec<(f64, f64)> {
+    let (hw, hh) = (width / 2.0, height / 2.0);
+    if rise == "left" {
+        vec![(-hw, hh), (-hw, -hh), (hw, hh)]
+    } else {
+        vec![(-hw, hh), (hw, -hh), (hw, hh)]
+    }
+}
+
+/// Outline of a polygon or slope collider relative to the entity position (offsets applied),
+/// in the canonical winding. None for boxes and circles, or malformed vertices.
+pub fn outline(collider: &Value, default_width: f64, default_height: f64) -> Option<Vec<(f64, f64)>> {
+    let number = |key: &str, default: f64| collider.get(key).and_then(Value::as_f64).unwrap_or(default);
+    let mut vertices = match collider.get("type").and_then(Value::as_str)? {
+        "polygon" => parse_vertices(collider.get("vertices")?)?,
+        "slope" => slope_vertices(
+            number("width", default_width),
+            number("height", default_height),
+            collider.get("rise").and_then(Value::as_str).unwrap_or("right"),
+        ),
+        _ => return None,
+    };
+    if vertices.len() < MIN_VERTICES {
+        return None;
+    }
+    if signed_area(&vertices) < 0.0 {
+        vertices.reverse();
+    }
+    let (offset_x, offset_y) = (number("offsetX", 0.0), number("offsetY", 0.0));
+    Some(vertices.into_iter().map(|(x, y)| (x + offset_x, y + offset_y)).collect())
+}
+
+/// Width and height of the smallest box centered on the entity that holds the outline
+pub fn extent(vertices: &[(f64, f64)]) -> (f64, f64) {
+    vertices
+        .iter()
+        .fold((0.0, 0.0), |(w, h), v| (f64::max(w, v.0.abs() * 2.0), f64::max(h, v.1.abs() * 2.0)))
+}
+
+/// Separating-axis test of a box (center and half size) against a convex polygon in world space.
+/// Returns the unit normal pointing from the polygon toward the box and the penetration depth.
+pub fn separate_box(center: (f64, f64), half: (f64, f64), polygon: &[(f64, f64)]) -> Option<((f64, f64), f64)> {
+    let n = polygon.len();
+    let mut axes = vec![(1.0, 0.0), (0.0, 1.0)];
+    for i in 0..n {
+        let (a, b) = (polygon[i], polygon[(i + 1) % n]);
+        let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+        let length = (dx * dx + dy * dy).sqrt();
+        if length > EPSILON {
+            axes.push((dy / length, -dx / length));
+        }
+    }
+
+    let centroid = polygon
+        .iter()
+        .fold((0.0, 0.0), |acc, v| (acc.0 + v.0 / n as f64, acc.1 + v.1 / n as f64));
+    let mut best: Option<((f64, f64), f64)> = None;
+    for axis in axes {
+        let box_center = center.0 * axis.0 + center.1 * axis.1;
+        let box_radius = half.0 * axis.0.abs() + half.1 * axis.1.abs();
+        let (min, max) = polygon
+            .iter()
+            .map(|v| v.0 * axis.0 + v.1 * axis.1)
+            .fold((f64::MAX, f64::MIN), |(lo, hi), p| (lo.min(p), hi.max(p)));
+        let depth = (box_center + box_radius - min).min(max - (box_center - box_radius));
+        if depth <= 0.0 {
+            return None;
+        }
+        if best.is_none_or(|(_, smallest)| depth < smallest) {
+            let toward_box = (center.0 - centroid.0) * axis.0 + (center.1 - centroid.1) * axis.1;
+            let normal = if toward_box < 0.0 { (-axis.0, -axis.1) } else { axis };
+            best = Some((normal, depth));
+        }
+    }
+    best
+}
+
+/// Check polygon and slope colliders: vertex count, convexity, winding and slope direction
+pub fn validate_shapes(game_spec: &Value) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+
+    let mut lists: Vec<(String, &Vec<Value>)> = Vec::new();
+    if let Some(entities) = game_spec.get("entities").and_then(Value::as_array) {
+        lists.push(("/entities".to_string(), entities));
+    }
+    for (i, scene) in game_spec.get("scenes").and_then(Value::as_array).into_iter().flatten().enumerate() {
+        if let Some(entities) = scene.get("entities").and_then(Value::as_array) {
+            lists.push((format!("/scenes/{}/entities", i), entities));
+        }
+    }
+
+    for (prefix, entities) in lists {
+        for (i, entity) in entities.iter().enumerate() {
+            let Some(collider) = entity.pointer("/components/collider") else {
+                continue;
+            };
+            let path = format!("{}/{}/components/collider", prefix, i);
+            match collider.get("type").and_then(Value::as_str) {
+                Some("polygon") => {
+                    let Some(vertices) = collider.get("vertices").and_then(parse_vertices) else {
+                        diagnostics.push(Diagnostic::error(
+                            format!("{}/vertices", path),
+                            "Polygon colliders need a vertices array of {x, y} points",
+                        ));
+                        continue;
+                    };
+                    let vertices_path = format!("{}/vertices", path);
+                    if vertices.len() < MIN_VERTICES || vertices.len() > MAX_VERTICES {
+                        diagnostics.push(Diagnostic::error(
+                            vertices_path,
+                            format!("Polygons need {} to {} vertices, got {}", MIN_VERTICES, MAX_VERTICES, vertices.len()),
+                        ));
+                    } else if signed_area(&vertices).abs() < EPSILON {
+                        diagnostics.push(Diagnostic::error(vertices_path, "Polygon has no area"));
+                    } else if !is_convex(&vertices) {
+                        diagnostics.push(Diagnostic::error(
+                            vertices_path,
+                            "Polygon must be convex; split concave shapes into several entities",
+                        ));
+                    } else if signed_area(&vertices) < 0.0 {
+                        diagnostics.push(Diagnostic::warning(
+                            vertices_path,
+                            "Vertices wind counter-clockwise on screen; they are reversed when loaded",
+                        ));
+                    }
+                }
+                Some("slope") => {
+                    if let Some(rise) = collider.get("rise") {
+                        if !matches!(rise.as_str(), Some("left" | "right")) {
+                            diagnostics.push(Diagnostic::error(format!("{}/rise", path), "Expected \"left\" or \"right\""));
+                        }
+                    }
+                    for key in ["width", "height"] {
+                        if collider.get(key).and_then(Value::as_f64).is_some_and(|v| v <= 0.0) {
+                            diagnostics.push(Diagnostic::error(format!("{}/{}", path, key), "Slope size must be positive"));
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    diagnostics
+}
@@ -0,0 +1,214 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::ipc::{Invoke, InvokeBody};
+use tauri::{Manager, State};
+
+/// Durations kept per command for the percentiles
+const MAX_SAMPLES: usize = 200;
+/// Calls slower than this count as slow unless the report asks for another threshold
+const DEFAULT_SLOW_MS: u64 = 100;
+/// Nesting shown in argument shapes; deeper values are summarized by type
+const SHAPE_DEPTH: usize = 2;
+/// The timing report itself, which would otherwise show up in every report
+const REPORT_COMMAND: &str = "record_command_timings";
+
+#[derive(Default)]
+struct CommandStats {
+    calls: u64,
+    /// Round-trip durations reported by the editor, newest last
+    samples: VecDeque<u64>,
+    max_ms: u64,
+    /// Argument shape and approximate size of the largest call seen
+    largest_args: Option<(String, usize)>,
+}
+
+/// Per-command call counts and durations since launch
+pub struct CommandTraceState {
+    started_at: u64,
+    commands: Mutex<HashMap<String, CommandStats>>,
+}
+
+impl Default for CommandTraceState {
+    fn default() -> Self {
+        Self {
+            started_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0),
+            commands: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+/// One round trip timed by the editor's invoke wrapper
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommandTiming {
+    pub command: String,
+    pub duration_ms: u64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CommandReport {
+    pub command: String,
+    pub calls: u64,
+    pub timed_calls: usize,
+    pub mean_ms: u64,
+    pub p95_ms: u64,
+    pub max_ms: u64,
+    /// Slow calls among the recent samples
+    pub slow_calls: usize,
+    /// Arguments of the largest call, e.g. `{gameSpecJson: string(48213)}`
+    pub largest_args: Option<String>,
+    pub largest_args_bytes: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SlowCommandsReport {
+    pub since: u64,
+    pub threshold_ms: u64,
+    /// Commands with at least one slow call, slowest p95 first
+    pub commands: Vec<CommandReport>,
+}
+
+/// Type and size of a JSON value without its contents, plus its approximate size in bytes
+fn shape(value: &Value, depth: usize) -> (String, usize) {
+    match value {
+        Value::Null => ("null".to_string(), 4),
+        Value::Bool(_) => ("bool".to_string(), 5),
+        Value::Number(_) => ("number".to_string(), 8),
+        Value::String(s) => (format!("string({})", s.len()), s.len() + 2),
+        Value::Array(items) => {
+            let bytes = items.iter().map(|item| shape(item, 0).1 + 1).sum::<usize>() + 2;
+            (format!("array({})", items.len()), bytes)
+        }
+        Value::Object(map) if depth == 0 => {
+            let bytes = map.iter().map(|(k, v)| k.len() + 3 + shape(v, 0).1).sum::<usize>() + 2;
+            (format!("object({})", map.len()), bytes)
+        }
+        Value::Object(map) => {
+            let fields: Vec<(String, usize)> = map
+                .iter()
+                .map(|(k, v)| {
+                    let (field, bytes) = shape(v, depth - 1);
+                    (format!("{}: {}", k, field), k.len() + 3 + bytes)
+                })
+                .collect();
+            let bytes = fields.iter().map(|(_, b)| b).sum::<usize>() + 2;
+            let names: Vec<String> = fields.into_iter().map(|(f, _)| f).collect();
+            (format!("{{{}}}", names.join(", ")), bytes)
+        }
+    }
+}
+
+fn percentile(sorted: &[u64], fraction: f64) -> u64 {
+    if sorted.is_empty() {
+        return 0;
+    }
+    let index = ((sorted.len() as f64 - 1.0) * fraction).round() as usize;
+    sorted[index.min(sorted.len() - 1)]
+}
+
+impl CommandTraceState {
+    fn dispatched(&self, command: &str, (shape, bytes): (String, usize)) {
+        let Ok(mut commands) = self.commands.lock() else {
+            return;
+        };
+        let stats = commands.entry(command.to_string()).or_default();
+        stats.calls += 1;
+        if stats.largest_args.as_ref().is_none_or(|(_, largest)| bytes > *largest) {
+            stats.largest_args = Some((shape, bytes));
+        }
+    }
+
+    fn timed(&self, timing: &CommandTiming) {
+        let Ok(mut commands) = self.commands.lock() else {
+            return;
+        };
+        let stats = commands.entry(timing.command.clone()).or_default();
+        if stats.samples.len() == MAX_SAMPLES {
+            stats.samples.pop_front();
+        }
+        stats.samples.push_back(timing.duration_ms);
+        stats.max_ms = stats.max_ms.max(timing.duration_ms);
+    }
+
+    fn report(&self, threshold_ms: u64) -> SlowCommandsReport {
+        let mut commands: Vec<CommandReport> = self
+            .commands
+            .lock()
+            .map(|commands| {
+                commands
+                    .iter()
+                    .filter(|(_, stats)| stats.max_ms >= threshold_ms)
+                    .map(|(command, stats)| {
+                        let mut sorted: Vec<u64> = stats.samples.iter().copied().collect();
+                        sorted.sort_unstable();
+                        CommandReport {
+                            command: command.clone(),
+                            calls: stats.calls,
+                            timed_calls: sorted.len(),
+                            mean_ms: sorted.iter().sum::<u64>() / (sorted.len().max(1) as u64),
+                            p95_ms: percentile(&sorted, 0.95),
+                            max_ms: stats.max_ms,
+                            slow_calls: sorted.iter().filter(|&&ms| ms >= threshold_ms).count(),
+                            largest_args: stats.largest_args.as_ref().map(|(shape, _)| shape.clone()),
+                            largest_args_bytes: stats.largest_args.as_ref().map_or(0, |(_, bytes)| *bytes),
+                        }
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        commands.sort_by(|a, b| b.p95_ms.cmp(&a.p95_ms).then(b.max_ms.cmp(&a.max_ms)));
+        SlowCommandsReport {
+            since: self.started_at,
+            threshold_ms,
+            commands,
+        }
+    }
+}
+
+/// Wrap the generated invoke handler so every command is counted with the shape of its
+/// arguments. Async commands finish after the handler returns, so durations come from the
+/// editor's invoke wrapper through record_command_timings.
+pub fn traced<F>(handler: F) -> impl Fn(Invoke) -> bool + Send + Sync + 'static
+where
+    F: Fn(Invoke) -> bool + Send + Sync + 'static,
+{
+    move |invoke| {
+        let command = invoke.message.command().to_string();
+        if command != REPORT_COMMAND {
+            let args = match invoke.message.payload() {
+                InvokeBody::Json(value) => shape(value, SHAPE_DEPTH),
+                InvokeBody::Raw(bytes) => (format!("raw({})", bytes.len()), bytes.len()),
+            };
+            invoke.message.webview().state::<CommandTraceState>().dispatched(&command, args);
+        }
+        handler(invoke)
+    }
+}
+
+/// Round-trip durations batched by the editor
+#[tauri::command]
+pub async fn record_command_timings(
+    state: State<'_, CommandTraceState>,
+    timings: Vec<CommandTiming>,
+) -> Result<(), String> {
+    for timing in &timings {
+        state.timed(timing);
+    }
+    Ok(())
+}
+
+/// Commands whose calls took at least `threshold_ms` (default 100), slowest first, with the
+/// argument shape of their largest call
+#[tauri::command]
+pub async fn get_slow_commands_report(
+    state: State<'_, CommandTraceState>,
+    threshold_ms: Option<u64>,
+) -> Result<SlowCommandsReport, String> {
+    Ok(state.report(threshold_ms.unwrap_or(DEFAULT_SLOW_MS)))
+}
@@ -0,0 +1,432 @@
+use crate::{encryption, json_guard};
+use crate::validation::Diagnostic;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+/// Project file declaring custom components
+pub const CUSTOM_COMPONENTS_FILE: &str = "components.d.json";
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum FieldType {
+    Number,
+    Integer,
+    String,
+    Boolean,
+    Color,
+    Asset,
+    Enum,
+    Object,
+    Array,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FieldDef {
+    #[serde(rename = "type")]
+    pub field_type: FieldType,
+    pub description: Option<String>,
+    pub default: Option<Value>,
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+    /// Allowed values for `enum` fields
+    #[serde(default)]
+    pub values: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ComponentDef {
+    pub description: Option<String>,
+    #[serde(default)]
+    pub fields: BTreeMap<String, FieldDef>,
+    #[serde(default)]
+    pub required: Vec<String>,
+    /// Set for components coming from components.d.json
+    #[serde(default)]
+    pub custom: bool,
+}
+
+/// Built-in plus project-defined components, keyed by spec key
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct ComponentRegistry {
+    pub components: BTreeMap<String, ComponentDef>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CustomComponentsFile {
+    #[serde(default)]
+    components: BTreeMap<String, ComponentDef>,
+}
+
+fn field(field_type: FieldType) -> FieldDef {
+    FieldDef {
+        field_type,
+        description: None,
+        default: None,
+        min: None,
+        max: None,
+        values: Vec::new(),
+    }
+}
+
+fn builtin(description: &str, fields: &[(&str, FieldType)], required: &[&str]) -> ComponentDef {
+    ComponentDef {
+        description: Some(description.to_string()),
+        fields: fields
+            .iter()
+            .map(|(name, t)| (name.to_string(), field(*t)))
+            .collect(),
+        required: required.iter().map(|r| r.to_string()).collect(),
+        custom: false,
+    }
+}
+
+/// Components the runtime understands natively
+pub fn builtin_components() -> BTreeMap<String, ComponentDef> {
+    use FieldType::*;
+
+    let mut components = BTreeMap::new();
+    components.insert(
+        "transform".to_string(),
+        builtin(
+            "Position, rotation and scale",
+            &[("x", Number), ("y", Number), ("rotation", Number), ("scaleX", Number), ("scaleY", Number)],
+            &["x", "y"],
+        ),
+    );
+    components.insert(
+        "velocity".to_string(),
+        builtin("Linear velocity in px/s", &[("vx", Number), ("vy", Number)], &["vx", "vy"]),
+    );
+    components.insert(
+        "sprite".to_string(),
+        builtin(
+            "Rendered image or colored box",
+            &[
+                ("texture", Asset),
+                ("width", Number),
+                ("height", Number),
+                ("tint", Color),
+                ("visible", Boolean),
+                ("zIndex", Integer),
+            ],
+            &["texture", "width", "height"],
+        ),
+    );
+    components.insert(
+        "collider".to_string(),
+        builtin(
+            "Physics collision shape",
+            &[
+                ("type", String),
+                ("width", Number),
+                ("height", Number),
+                ("radius", Number),
+                ("isSensor", Boolean),
+                ("isStatic", Boolean),
+                ("layer", Integer),
+                ("material", String),
+                ("restitution", Number),
+                ("friction", Number),
+                ("oneWay", Boolean),
+                ("vertices", Array),
+                ("rise", String),
+            ],
+            &["type"],
+        ),
+    );
+    components.insert(
+        "input".to_string(),
+        builtin(
+            "Player keyboard control",
+            &[("moveSpeed", Number), ("jumpForce", Number), ("canJump", Boolean), ("keys", Object), ("player", Integer)],
+            &["moveSpeed", "jumpForce"],
+        ),
+    );
+    components.insert(
+        "health".to_string(),
+        builtin("Hit points", &[("current", Number), ("max", Number)], &["current", "max"]),
+    );
+    components.insert(
+        "aiBehavior".to_string(),
+        builtin(
+            "Enemy behavior",
+            &[
+                ("type", String),
+                ("speed", Number),
+                ("detectionRadius", Number),
+                ("targetEntity", Integer),
+                ("patrolRange", Number),
+            ],
+            &["type", "speed"],
+        ),
+    );
+    components.insert(
+        "animation".to_string(),
+        builtin(
+            "Sprite sheet animation",
+            &[
+                ("frameCount", Integer),
+                ("frameDuration", Number),
+                ("loop", Boolean),
+                ("spriteSheet", Asset),
+                ("states", Array),
+            ],
+            &["frameCount", "frameDuration"],
+        ),
+    );
+    components.insert(
+        "camera".to_string(),
+        builtin(
+            "Camera follow and viewport",
+            &[("zoom", Number), ("followTarget", Integer), ("followSmoothing", Number), ("isActive", Boolean)],
+            &[],
+        ),
+    );
+    components.insert(
+        "particleEmitter".to_string(),
+        builtin(
+            "Particle effects",
+            &[("emitRate", Number), ("maxParticles", Integer), ("isEmitting", Boolean)],
+            &[],
+        ),
+    );
+    components.insert(
+        "spawner".to_string(),
+        builtin(
+            "Spawns copies of a template entity; pool the template under `pooling` for fast spawners",
+            &[
+                ("template", String),
+                ("interval", Number),
+                ("lifetime", Number),
+                ("vx", Number),
+                ("vy", Number),
+                ("maxAlive", Integer),
+            ],
+            &["template", "interval"],
+        ),
+    );
+    components.insert(
+        "audio".to_string(),
+        builtin(
+            "Sound source",
+            &[("source", Asset), ("volume", Number), ("loop", Boolean), ("isPlaying", Boolean)],
+            &["source"],
+        ),
+    );
+
+    // 3D components are validated by the 3D runtime; register them so they aren't flagged as unknown
+    for name in [
+        "transform3d",
+        "mesh",
+        "material",
+        "texture3d",
+        "light",
+        "collider3d",
+        "velocity3d",
+        "camera3d",
+        "input3d",
+        "rigidbody3d",
+        "model3d",
+    ] {
+        components.insert(name.to_string(), builtin("3D component", &[], &[]));
+    }
+
+    components
+}
+
+impl ComponentRegistry {
+    pub fn builtin() -> Self {
+        Self {
+            components: builtin_components(),
+        }
+    }
+
+    /// Built-ins merged with the project's components.d.json (custom names may not shadow built-ins)
+    pub fn load(project_path: &str) -> Result<Self, String> {
+        let mut registry = Self::builtin();
+        let path = PathBuf::from(project_path).join(CUSTOM_COMPONENTS_FILE);
+        if !path.exists() {
+            return Ok(registry);
+        }
+
+        let content = encryption::read_to_string(&path)?;
+        let file: CustomComponentsFile =
+            json_guard::parse(content.as_bytes(), CUSTOM_COMPONENTS_FILE, &json_guard::METADATA_LIMITS)?;
+
+        for (name, mut def) in file.components {
+            if registry.components.contains_key(&name) {
+                return Err(format!(
+                    "{} redefines built-in component '{}'",
+                    CUSTOM_COMPONENTS_FILE, name
+                ));
+            }
+            def.custom = true;
+            registry.components.insert(name, def);
+        }
+
+        Ok(registry)
+    }
+
+    pub fn custom(&self) -> impl Iterator<Item = (&String, &ComponentDef)> {
+        self.components.iter().filter(|(_, def)| def.custom)
+    }
+
+    /// One line per custom component, for the AI system prompt
+    pub fn describe_custom_for_prompt(&self) -> String {
+        self.custom()
+            .map(|(name, def)| {
+                let fields = def
+                    .fields
+                    .iter()
+                    .map(|(field, f)| format!("{}: {:?}", field, f.field_type).to_lowercase())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                format!(
+                    "- {} ({}): {}",
+                    name,
+                    fields,
+                    def.description.as_deref().unwrap_or("custom component")
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+fn check_field(path: &str, def: &FieldDef, value: &Value) -> Option<Diagnostic> {
+    let type_ok = match def.field_type {
+        FieldType::Number => value.is_number(),
+        FieldType::Integer => value.is_i64() || value.is_u64(),
+        FieldType::String | FieldType::Asset => value.is_string(),
+        FieldType::Boolean => value.is_boolean(),
+        FieldType::Color => value.is_string() || value.is_u64(),
+        FieldType::Enum => value.as_str().is_some_and(|v| def.values.iter().any(|a| a == v)),
+        FieldType::Object => value.is_object(),
+        FieldType::Array => value.is_array(),
+    };
+    if !type_ok {
+        let expected = if def.field_type == FieldType::Enum {
+            format!("one of {:?}", def.values)
+        } else {
+            format!("{:?}", def.field_type).to_lowercase()
+        };
+        return Some(Diagnostic::error(path, format!("Expected {}", expected)));
+    }
+
+    if let Some(n) = value.as_f64() {
+        if def.min.is_some_and(|min| n < min) || def.max.is_some_and(|max| n > max) {
+            return Some(Diagnostic::error(
+                path,
+                format!("Value {} is outside the allowed range", n),
+            ));
+        }
+    }
+
+    None
+}
+
+/// Edits needed to turn `a` into `b`
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut previous = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitute = previous + usize::from(!ca.eq_ignore_ascii_case(cb));
+            previous = row[j + 1];
+            row[j + 1] = substitute.min(previous + 1).min(row[j] + 1);
+        }
+    }
+    row[b.len()]
+}
+
+/// The registered component a misspelled key most likely meant, e.g. "Transform" or "aibehavior"
+fn closest_component<'a>(registry: &'a ComponentRegistry, name: &str) -> Option<&'a str> {
+    registry
+        .components
+        .keys()
+        .map(|known| (edit_distance(name, known), known.as_str()))
+        .filter(|(distance, known)| *distance <= (known.len() / 3).max(1))
+        .min()
+        .map(|(_, known)| known)
+}
+
+/// Check entity components against the registry: unknown keys and custom component fields
+pub fn validate_components(spec: &Value, registry: &ComponentRegistry) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+
+    let mut lists: Vec<(String, &Vec<Value>)> = Vec::new();
+    if let Some(entities) = spec.get("entities").and_then(Value::as_array) {
+        lists.push(("/entities".to_string(), entities));
+    }
+    for (i, scene) in spec.get("scenes").and_then(Value::as_array).into_iter().flatten().enumerate() {
+        if let Some(entities) = scene.get("entities").and_then(Value::as_array) {
+            lists.push((format!("/scenes/{}/entities", i), entities));
+        }
+    }
+
+    for (prefix, entities) in lists {
+        for (i, entity) in entities.iter().enumerate() {
+            let Some(components) = entity.get("components").and_then(Value::as_object) else {
+                continue;
+            };
+            for (name, value) in components {
+                let path = format!("{}/{}/components/{}", prefix, i, name);
+                let Some(def) = registry.components.get(name) else {
+                    let unknown = Diagnostic::warning(
+                        &path,
+                        format!(
+                            "Unknown component '{}'; declare it in {} to use it",
+                            name, CUSTOM_COMPONENTS_FILE
+                        ),
+                    );
+                    diagnostics.push(match closest_component(registry, name) {
+                        Some(known) => unknown.with_fix(format!("Rename it to '{}'", known)),
+                        None => unknown,
+                    });
+                    continue;
+                };
+                if !def.custom {
+                    continue;
+                }
+                for required in &def.required {
+                    if value.get(required).is_none() && def.fields.get(required).and_then(|f| f.default.as_ref()).is_none() {
+                        diagnostics.push(Diagnostic::error(
+                            &path,
+                            format!("Missing required field '{}'", required),
+                        ));
+                    }
+                }
+                for (field_name, field_value) in value.as_object().into_iter().flatten() {
+                    match def.fields.get(field_name) {
+                        Some(field_def) => diagnostics.extend(check_field(
+                            &format!("{}/{}", path, field_name),
+                            field_def,
+                            field_value,
+                        )),
+                        None => diagnostics.push(Diagnostic::warning(
+                            format!("{}/{}", path, field_name),
+                            format!("Field '{}' is not declared on '{}'", field_name, name),
+                        )),
+                    }
+                }
+            }
+        }
+    }
+
+    diagnostics
+}
+
+/// Get the component registry for a project (built-ins plus components.d.json)
+#[tauri::command]
+pub async fn get_component_registry(project_path: Option<String>) -> Result<ComponentRegistry, String> {
+    match project_path {
+        Some(path) => ComponentRegistry::load(&path),
+        None => Ok(ComponentRegistry::builtin()),
+    }
+}
@@ -0,0 +1,39 @@
+use crate::spec::MAX_SPEC_BYTES;
+use std::io::Read;
+
+/// zstd frame magic number; compressed specs are recognized by it, whatever their file name
+const ZSTD_MAGIC: &[u8] = &[0x28, 0xb5, 0x2f, 0xfd];
+/// Favors save speed; huge generated levels compress well even at low levels
+const COMPRESSION_LEVEL: i32 = 3;
+
+/// Whether `bytes` are a zstd-compressed file
+pub fn is_compressed(bytes: &[u8]) -> bool {
+    bytes.starts_with(ZSTD_MAGIC)
+}
+
+/// Decompress `bytes` when they're zstd-compressed; plain files pass through. Output is capped at
+/// [`MAX_SPEC_BYTES`] so a small file can't expand into an out-of-memory parse.
+pub fn decompress(bytes: Vec<u8>) -> Result<Vec<u8>, String> {
+    if !is_compressed(&bytes) {
+        return Ok(bytes);
+    }
+    let decoder = zstd::stream::Decoder::new(bytes.as_slice())
+        .map_err(|e| format!("Failed to decompress spec: {}", e))?;
+    let mut out = Vec::new();
+    decoder
+        .take(MAX_SPEC_BYTES + 1)
+        .read_to_end(&mut out)
+        .map_err(|e| format!("Failed to decompress spec: {}", e))?;
+    if out.len() as u64 > MAX_SPEC_BYTES {
+        return Err(format!(
+            "Compressed spec expands past the {} MB limit; split large tilemaps into separate scenes",
+            MAX_SPEC_BYTES / (1024 * 1024)
+        ));
+    }
+    Ok(out)
+}
+
+/// zstd-compress `bytes`
+pub fn compress(bytes: &[u8]) -> Result<Vec<u8>, String> {
+    zstd::encode_all(bytes, COMPRESSION_LEVEL).map_err(|e| format!("Failed to compress spec: {}", e))
+}
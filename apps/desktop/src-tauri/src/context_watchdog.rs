@@ -0,0 +1,144 @@
+use crate::ai_client::{AIClientState, Message};
+use crate::ai_context::estimate_tokens;
+use crate::error::AppError;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, State};
+
+/// Event sent when a conversation's context crosses a threshold
+pub const CONTEXT_WARNING_EVENT: &str = "ai-context-warning";
+/// Past this, requests get noticeably slower and pricier
+const WARNING_TOKENS: usize = 60_000;
+/// Close enough to the model's window that early turns risk being cut
+const CRITICAL_TOKENS: usize = 120_000;
+/// Recent messages `compact_ai_context` keeps verbatim by default
+const DEFAULT_KEEP_RECENT: usize = 4;
+
+const COMPACT_SYSTEM_PROMPT: &str = "You compact conversations between a game developer and an AI assistant for a 2D/3D game engine. \
+Summarize the conversation you are given so it can replace it as context: keep decisions, requested changes and their \
+outcome, entity names and ids, open questions and user preferences. Leave out pleasantries and any full game.json \
+listings. Reply with the summary only.";
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "camelCase")]
+pub enum ContextLevel {
+    Ok,
+    Warning,
+    Critical,
+}
+
+/// Estimated size of one request's context
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ContextUsage {
+    pub conversation_id: Option<String>,
+    pub estimated_tokens: usize,
+    pub message_count: usize,
+    pub level: ContextLevel,
+    /// Threshold of the current level, or the warning threshold while under it
+    pub threshold: usize,
+    /// Command the editor's "compact context" action calls
+    pub action: &'static str,
+}
+
+/// Last level reported per conversation, so each crossing warns once
+#[derive(Default)]
+pub struct ContextWatchdogState(Mutex<HashMap<String, ContextLevel>>);
+
+fn level(tokens: usize) -> (ContextLevel, usize) {
+    if tokens >= CRITICAL_TOKENS {
+        (ContextLevel::Critical, CRITICAL_TOKENS)
+    } else if tokens >= WARNING_TOKENS {
+        (ContextLevel::Warning, WARNING_TOKENS)
+    } else {
+        (ContextLevel::Ok, WARNING_TOKENS)
+    }
+}
+
+fn messages_tokens(messages: &[Message]) -> usize {
+    messages.iter().map(|m| estimate_tokens(&m.content)).sum()
+}
+
+/// Estimate a request's context from its prompt parts and messages
+pub fn measure(conversation_id: Option<&str>, prompt_parts: &[&str], messages: &[Message]) -> ContextUsage {
+    let estimated_tokens = prompt_parts.iter().map(|part| estimate_tokens(part)).sum::<usize>() + messages_tokens(messages);
+    let (level, threshold) = level(estimated_tokens);
+    ContextUsage {
+        conversation_id: conversation_id.map(str::to_string),
+        estimated_tokens,
+        message_count: messages.len(),
+        level,
+        threshold,
+        action: "compact_ai_context",
+    }
+}
+
+/// Record a request's context size and warn the window that sent it when the conversation moved
+/// up a level. Without a conversation id every request over the threshold warns.
+pub fn check(app: &AppHandle, window: &str, state: &ContextWatchdogState, usage: &ContextUsage) {
+    let previous = match (&usage.conversation_id, state.0.lock()) {
+        (Some(id), Ok(mut levels)) => levels.insert(id.clone(), usage.level),
+        _ => None,
+    };
+    if usage.level > previous.unwrap_or(ContextLevel::Ok) {
+        let _ = app.emit_to(window, CONTEXT_WARNING_EVENT, usage);
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CompactedContext {
+    /// A summary message followed by the recent messages, to continue the conversation with
+    pub messages: Vec<Message>,
+    pub tokens_before: usize,
+    pub tokens_after: usize,
+}
+
+/// Replace all but the most recent messages of a conversation with an AI-written summary
+#[tauri::command]
+pub async fn compact_ai_context(
+    client: State<'_, AIClientState>,
+    watchdog: State<'_, ContextWatchdogState>,
+    messages: Vec<Message>,
+    conversation_id: Option<String>,
+    keep_recent: Option<usize>,
+) -> Result<CompactedContext, AppError> {
+    let tokens_before = messages_tokens(&messages);
+    // Start the kept tail on a user turn so the conversation still reads in order
+    let mut split = messages.len().saturating_sub(keep_recent.unwrap_or(DEFAULT_KEEP_RECENT));
+    while split > 0 && messages.get(split).is_some_and(|m| m.role != "user") {
+        split -= 1;
+    }
+    if split == 0 {
+        return Ok(CompactedContext {
+            messages,
+            tokens_before,
+            tokens_after: tokens_before,
+        });
+    }
+
+    let transcript = messages[..split]
+        .iter()
+        .map(|m| format!("{}: {}", m.role, m.content))
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    let summary = client.0.lock().await.complete(COMPACT_SYSTEM_PROMPT.to_string(), transcript).await?;
+
+    let mut compacted = vec![Message {
+        role: "user".to_string(),
+        content: format!("Summary of our conversation so far:\n{}", summary),
+    }];
+    compacted.extend(messages.into_iter().skip(split));
+    let tokens_after = messages_tokens(&compacted);
+
+    if let (Some(id), Ok(mut levels)) = (conversation_id, watchdog.0.lock()) {
+        levels.insert(id, level(tokens_after).0);
+    }
+
+    Ok(CompactedContext {
+        messages: compacted,
+        tokens_before,
+        tokens_after,
+    })
+}
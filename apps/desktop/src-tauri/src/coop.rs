@@ -0,0 +1,338 @@
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::net::{IpAddr, Ipv4Addr, TcpListener, TcpStream, UdpSocket};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Manager, State};
+use tungstenite::{Message, WebSocket};
+
+/// First port tried; the next PORT_ATTEMPTS - 1 follow before letting the OS pick one
+const PREFERRED_PORT: u16 = 5190;
+const PORT_ATTEMPTS: u16 = 20;
+/// Players a relay seats; `input.player` in the spec picks which one controls an entity
+pub const MAX_PLAYERS: u8 = 4;
+const ACCEPT_POLL: Duration = Duration::from_millis(50);
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(5);
+/// How long a peer's thread waits for a message before forwarding what others sent it
+const PEER_POLL: Duration = Duration::from_millis(10);
+/// Messages larger than this are dropped; state updates are a few hundred bytes
+const MAX_MESSAGE_BYTES: usize = 64 * 1024;
+
+type Peers = Arc<Mutex<BTreeMap<u8, Sender<String>>>>;
+
+struct Relay {
+    port: u16,
+    started_at: u64,
+    peers: Peers,
+    relayed: Arc<AtomicU64>,
+    stop: Arc<AtomicBool>,
+}
+
+/// The app's relay; one per editor, shared by every window
+#[derive(Default)]
+pub struct CoopRelayState(Mutex<Option<Relay>>);
+
+#[derive(Debug, Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct CoopRelayStatus {
+    pub running: bool,
+    pub port: Option<u16>,
+    /// ws:// address other machines on the network connect to
+    pub url: Option<String>,
+    /// Query string to add to an exported game's URL so it joins this relay
+    pub join_query: Option<String>,
+    /// Player slots currently connected
+    pub players: Vec<u8>,
+    pub messages_relayed: u64,
+    pub started_at: Option<u64>,
+}
+
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// This machine's address on the local network. Connecting a UDP socket sends nothing; it
+/// only asks the OS which interface would route outward.
+fn lan_address() -> IpAddr {
+    UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0))
+        .and_then(|socket| {
+            socket.connect((Ipv4Addr::new(192, 0, 2, 1), 80))?;
+            socket.local_addr()
+        })
+        .map(|addr| addr.ip())
+        .unwrap_or(IpAddr::V4(Ipv4Addr::LOCALHOST))
+}
+
+/// Bind the first free port from PREFERRED_PORT on every interface, then any port the OS hands out
+fn bind_free_port() -> Result<TcpListener, String> {
+    (PREFERRED_PORT..PREFERRED_PORT + PORT_ATTEMPTS)
+        .chain(std::iter::once(0))
+        .find_map(|port| TcpListener::bind((Ipv4Addr::UNSPECIFIED, port)).ok())
+        .ok_or_else(|| "Failed to find a free port for the co-op relay".to_string())
+}
+
+fn message(kind: &str, fields: Value) -> String {
+    let mut message = serde_json::json!({ "type": kind, "timestamp": now_millis() });
+    if let (Some(message), Value::Object(fields)) = (message.as_object_mut(), fields) {
+        message.extend(fields);
+    }
+    message.to_string()
+}
+
+/// Send to every seated player except `except`
+fn broadcast(peers: &Peers, except: u8, text: &str) {
+    if let Ok(peers) = peers.lock() {
+        for (_, sender) in peers.iter().filter(|(slot, _)| **slot != except) {
+            let _ = sender.send(text.to_string());
+        }
+    }
+}
+
+/// Seat a new connection in the lowest free slot
+fn seat(peers: &Peers) -> Option<(u8, Receiver<String>)> {
+    let mut peers = peers.lock().ok()?;
+    let slot = (1..=MAX_PLAYERS).find(|slot| !peers.contains_key(slot))?;
+    let (sender, receiver) = mpsc::channel();
+    peers.insert(slot, sender);
+    Some((slot, receiver))
+}
+
+fn is_timeout(error: &tungstenite::Error) -> bool {
+    matches!(error, tungstenite::Error::Io(e)
+        if matches!(e.kind(), std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut))
+}
+
+/// Relay one player's messages until they leave or the relay stops. Each JSON message is
+/// stamped with the sender's slot as `from` and passed to everyone else unchanged otherwise.
+fn run_peer(
+    mut socket: WebSocket<TcpStream>,
+    slot: u8,
+    outbox: Receiver<String>,
+    peers: &Peers,
+    relayed: &AtomicU64,
+    stop: &AtomicBool,
+) {
+    let players: Vec<u8> = peers.lock().map(|p| p.keys().copied().collect()).unwrap_or_default();
+    let welcome = message(
+        "welcome",
+        serde_json::json!({ "playerId": slot.to_string(), "player": slot, "players": players, "serverTime": now_millis() }),
+    );
+    if socket.send(Message::Text(welcome)).is_err() {
+        return;
+    }
+    broadcast(peers, slot, &message("player_join", serde_json::json!({ "playerId": slot.to_string(), "player": slot })));
+
+    while !stop.load(Ordering::Relaxed) {
+        match socket.read() {
+            Ok(Message::Text(text)) if text.len() <= MAX_MESSAGE_BYTES => {
+                if let Ok(Value::Object(mut fields)) = serde_json::from_str::<Value>(&text) {
+                    fields.insert("from".to_string(), Value::from(slot));
+                    broadcast(peers, slot, &Value::Object(fields).to_string());
+                    relayed.fetch_add(1, Ordering::Relaxed);
+                }
+            }
+            Ok(Message::Close(_)) => break,
+            Ok(_) => {}
+            Err(e) if is_timeout(&e) => {}
+            Err(_) => break,
+        }
+        let mut failed = false;
+        for text in outbox.try_iter() {
+            failed |= socket.write(Message::Text(text)).is_err();
+        }
+        if failed || matches!(socket.flush(), Err(ref e) if !is_timeout(e)) {
+            break;
+        }
+    }
+    let _ = socket.close(None);
+    let _ = socket.flush();
+}
+
+fn handle(stream: TcpStream, peers: Peers, relayed: Arc<AtomicU64>, stop: Arc<AtomicBool>) {
+    let _ = stream.set_nonblocking(false);
+    let _ = stream.set_read_timeout(Some(HANDSHAKE_TIMEOUT));
+    let _ = stream.set_nodelay(true);
+    let Ok(mut socket) = tungstenite::accept(stream) else {
+        return;
+    };
+    let Some((slot, outbox)) = seat(&peers) else {
+        let full = message("error", serde_json::json!({ "code": "full", "message": format!("The relay seats {} players", MAX_PLAYERS) }));
+        let _ = socket.send(Message::Text(full));
+        let _ = socket.close(None);
+        return;
+    };
+    let _ = socket.get_ref().set_read_timeout(Some(PEER_POLL));
+    run_peer(socket, slot, outbox, &peers, &relayed, &stop);
+
+    if let Ok(mut seated) = peers.lock() {
+        seated.remove(&slot);
+    }
+    broadcast(&peers, slot, &message("player_leave", serde_json::json!({ "playerId": slot.to_string(), "player": slot })));
+}
+
+fn serve(listener: TcpListener, peers: Peers, relayed: Arc<AtomicU64>, stop: Arc<AtomicBool>) {
+    // Non-blocking accepts so the stop flag is seen without waiting for a connection
+    if listener.set_nonblocking(true).is_err() {
+        return;
+    }
+    while !stop.load(Ordering::Relaxed) {
+        match listener.accept() {
+            Ok((stream, _)) => {
+                let (peers, relayed, stop) = (peers.clone(), relayed.clone(), stop.clone());
+                std::thread::spawn(move || handle(stream, peers, relayed, stop));
+            }
+            Err(_) => std::thread::sleep(ACCEPT_POLL),
+        }
+    }
+}
+
+fn status_of(relay: Option<&Relay>) -> CoopRelayStatus {
+    let Some(relay) = relay else {
+        return CoopRelayStatus::default();
+    };
+    let url = format!("ws://{}:{}", lan_address(), relay.port);
+    CoopRelayStatus {
+        running: true,
+        port: Some(relay.port),
+        join_query: Some(format!("?relay={}", url)),
+        url: Some(url),
+        players: relay.peers.lock().map(|p| p.keys().copied().collect()).unwrap_or_default(),
+        messages_relayed: relay.relayed.load(Ordering::Relaxed),
+        started_at: Some(relay.started_at),
+    }
+}
+
+/// Stop the relay, disconnecting every player; called on app exit
+pub fn shutdown(app: &AppHandle) {
+    let state = app.state::<CoopRelayState>();
+    if let Ok(mut relay) = state.0.lock() {
+        if let Some(relay) = relay.take() {
+            relay.stop.store(true, Ordering::Relaxed);
+        }
+    }
+}
+
+/// Whether the spec has entities for a second player, so exports carry the co-op client
+fn has_coop_players(spec: &Value) -> bool {
+    crate::spec::all_entities(spec)
+        .filter_map(|entity| entity.pointer("/components/input/player")?.as_u64())
+        .any(|player| player > 1)
+}
+
+/// Co-op client appended to exports with a second player. The page joins the relay named by its
+/// `?relay=` parameter (or `config.coop.relayUrl`), controls the entities whose `input.player`
+/// matches the seat it's given, and mirrors the others from their owners' updates. Without a
+/// relay every player entity follows the keyboard, as in single-player exports.
+pub fn export_script(spec: &Value) -> Option<String> {
+    if !has_coop_players(spec) {
+        return None;
+    }
+    Some(
+        r#"
+
+        // Co-op: share player positions and game events through a PromptPlay LAN relay
+        if (typeof runtime !== 'undefined') {
+            const relayUrl = new URLSearchParams(location.search).get('relay') || runtime.spec.config.coop?.relayUrl;
+            const listeners = new Map();
+            const coop = {
+                player: null,
+                connected: false,
+                send(event, data) {
+                    if (coop.connected) socket.send(JSON.stringify({ type: 'rpc_call', callId: String(Date.now()), method: event, args: [data], target: 'others', timestamp: Date.now() }));
+                },
+                on(event, handler) {
+                    if (!listeners.has(event)) listeners.set(event, []);
+                    listeners.get(event).push(handler);
+                }
+            };
+            window.promptplay = Object.assign(window.promptplay || {}, { coop });
+            const owner = (e) => e.player || 1;
+            let socket = null;
+            if (relayUrl) {
+                socket = new WebSocket(relayUrl);
+                socket.onopen = () => { coop.connected = true; };
+                socket.onclose = () => { coop.connected = false; runtime.localPlayer = null; };
+                socket.onmessage = (event) => {
+                    let msg;
+                    try { msg = JSON.parse(event.data); } catch { return; }
+                    if (msg.type === 'welcome') {
+                        coop.player = msg.player;
+                        runtime.localPlayer = msg.player;
+                    } else if (msg.type === 'entity_update') {
+                        const e = runtime.entities.find(e => e.name === msg.entityId);
+                        const t = msg.components?.transform;
+                        if (!e || !t || !e.hasInput || owner(e) === coop.player) return;
+                        e.x = t.x; e.y = t.y;
+                        if (e.body) {
+                            Matter.Body.setPosition(e.body, { x: t.x + e.body.spriteOffset.x, y: t.y + e.body.spriteOffset.y });
+                            Matter.Body.setVelocity(e.body, { x: t.vx || 0, y: t.vy || 0 });
+                        }
+                    } else if (msg.type === 'rpc_call') {
+                        for (const handler of listeners.get(msg.method) || []) handler(msg.args?.[0], msg.from);
+                    } else if (msg.type === 'player_join' || msg.type === 'player_leave') {
+                        for (const handler of listeners.get(msg.type) || []) handler(msg.player);
+                    }
+                };
+                // Owned players' positions go out 20 times a second
+                setInterval(() => {
+                    if (!coop.connected || !runtime.isPlaying) return;
+                    for (const e of runtime.entities) {
+                        if (!e.hasInput || owner(e) !== coop.player) continue;
+                        const v = e.body ? e.body.velocity : { x: 0, y: 0 };
+                        socket.send(JSON.stringify({
+                            type: 'entity_update', entityId: e.name, timestamp: Date.now(),
+                            components: { transform: { x: e.x, y: e.y, vx: v.x, vy: v.y } }
+                        }));
+                    }
+                }, 50);
+            }
+        }"#
+        .to_string(),
+    )
+}
+
+/// Start a relay on the local network so exported games on two machines (or two tabs) can play
+/// together. Players take seats 1 to MAX_PLAYERS in the order they connect. A running relay is
+/// kept and its status returned.
+#[tauri::command]
+pub async fn start_coop_relay(state: State<'_, CoopRelayState>) -> Result<CoopRelayStatus, String> {
+    let mut relay = state.0.lock().map_err(|e| format!("Lock error: {}", e))?;
+    if relay.as_ref().is_some_and(|r| !r.stop.load(Ordering::Relaxed)) {
+        return Ok(status_of(relay.as_ref()));
+    }
+
+    let listener = bind_free_port()?;
+    let port = listener
+        .local_addr()
+        .map_err(|e| format!("Failed to read co-op relay address: {}", e))?
+        .port();
+    let started = Relay {
+        port,
+        started_at: now_millis() / 1000,
+        peers: Arc::new(Mutex::new(BTreeMap::new())),
+        relayed: Arc::new(AtomicU64::new(0)),
+        stop: Arc::new(AtomicBool::new(false)),
+    };
+    let (peers, relayed, stop) = (started.peers.clone(), started.relayed.clone(), started.stop.clone());
+    std::thread::spawn(move || serve(listener, peers, relayed, stop));
+    *relay = Some(started);
+    Ok(status_of(relay.as_ref()))
+}
+
+#[tauri::command]
+pub async fn stop_coop_relay(app: AppHandle) -> Result<(), String> {
+    shutdown(&app);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_coop_relay_status(state: State<'_, CoopRelayState>) -> Result<CoopRelayStatus, String> {
+    let relay = state.0.lock().map_err(|e| format!("Lock error: {}", e))?;
+    Ok(status_of(relay.as_ref()))
+}
@@ -0,0 +1,32 @@
+use keyring::Entry;
+
+/// Service name credentials are filed under in the OS keychain
+const SERVICE: &str = "PromptPlay";
+
+fn entry(account: &str) -> Result<Entry, String> {
+    Entry::new(SERVICE, account).map_err(|e| format!("Failed to open keychain entry {}: {}", account, e))
+}
+
+/// Save a secret in the OS keychain (Keychain, Credential Manager or Secret Service)
+pub fn store(account: &str, secret: &str) -> Result<(), String> {
+    entry(account)?
+        .set_password(secret)
+        .map_err(|e| format!("Failed to save {} to the keychain: {}", account, e))
+}
+
+/// The stored secret, or None when nothing is saved
+pub fn load(account: &str) -> Result<Option<String>, String> {
+    match entry(account)?.get_password() {
+        Ok(secret) => Ok(Some(secret)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(format!("Failed to read {} from the keychain: {}", account, e)),
+    }
+}
+
+/// Remove a stored secret; removing one that doesn't exist is not an error
+pub fn delete(account: &str) -> Result<(), String> {
+    match entry(account)?.delete_credential() {
+        Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+        Err(e) => Err(format!("Failed to remove {} from the keychain: {}", account, e)),
+    }
+}
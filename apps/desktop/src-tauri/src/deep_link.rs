@@ -0,0 +1,155 @@
+use crate::paths;
+use serde::Serialize;
+use std::path::Path;
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+/// URL scheme registered with the OS (`plugins.deep-link` in tauri.conf.json)
+pub const SCHEME: &str = "promptplay";
+/// Extension of project marker files associated with the app; opening one opens its folder
+pub const PROJECT_FILE_EXTENSION: &str = "promptplay";
+/// Longest template id a link may name; ids are short slugs like `platformer-basic`
+const MAX_TEMPLATE_ID: usize = 64;
+
+/// What an "open with PromptPlay" or a `promptplay://` link asks the editor to do
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[serde(tag = "action", rename_all = "camelCase")]
+pub enum LaunchRequest {
+    /// Open the project folder at `path`; links come from outside the app, so the editor asks first
+    #[serde(rename_all = "camelCase")]
+    OpenProject { path: String, from_link: bool },
+    /// Start the new-project flow from a bundled or saved template
+    #[serde(rename_all = "camelCase")]
+    InstallTemplate { id: String },
+    /// A link or file that could not be handled, with the reason to show the user
+    #[serde(rename_all = "camelCase")]
+    Rejected { target: String, reason: String },
+}
+
+/// Launch requests waiting for the frontend, which may not be listening yet at startup
+#[derive(Default)]
+pub struct LaunchQueue(Mutex<Vec<LaunchRequest>>);
+
+/// Whether `id` is a slug a link or template manifest may use
+pub fn is_template_id(id: &str) -> bool {
+    !id.is_empty()
+        && id.len() <= MAX_TEMPLATE_ID
+        && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+/// Decode %XX escapes and `+` (the form encoding links and file URLs use)
+pub fn percent_decode(text: &str) -> String {
+    let hex = |b: u8| (b as char).to_digit(16).map(|d| d as u8);
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'%' if i + 2 < bytes.len() => match (hex(bytes[i + 1]), hex(bytes[i + 2])) {
+                (Some(high), Some(low)) => {
+                    decoded.push(high * 16 + low);
+                    i += 2;
+                }
+                _ => decoded.push(b'%'),
+            },
+            b'+' => decoded.push(b' '),
+            byte => decoded.push(byte),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&decoded).to_string()
+}
+
+fn query_param(query: &str, key: &str) -> Option<String> {
+    query
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(k, _)| *k == key)
+        .map(|(_, value)| percent_decode(value))
+        .filter(|value| !value.is_empty())
+}
+
+/// The project folder a path stands for: the folder itself, or the folder holding a
+/// `.promptplay` marker file
+fn project_folder(path: &Path) -> Result<String, String> {
+    let folder = if path.extension().is_some_and(|e| e == PROJECT_FILE_EXTENSION) {
+        path.parent().ok_or("Project file has no parent folder")?
+    } else {
+        path
+    };
+    if !folder.join("game.json").is_file() {
+        return Err(format!("{} is not a PromptPlay project (no game.json)", paths::to_display(folder)));
+    }
+    Ok(paths::to_display(&paths::canonical(folder)))
+}
+
+/// Parse a launch target: a `promptplay://open?path=...` or `promptplay://install-template?id=...`
+/// link, a `file://` URL, or a plain path passed on the command line
+pub fn parse(target: &str) -> Result<LaunchRequest, String> {
+    if let Some(rest) = target.strip_prefix(&format!("{}://", SCHEME)) {
+        let (action, query) = rest.split_once('?').unwrap_or((rest, ""));
+        return match action.trim_end_matches('/') {
+            "open" => {
+                let path = query_param(query, "path").ok_or("Open links need a path parameter")?;
+                Ok(LaunchRequest::OpenProject {
+                    path: project_folder(Path::new(&path))?,
+                    from_link: true,
+                })
+            }
+            "install-template" => {
+                let id = query_param(query, "id").ok_or("Template links need an id parameter")?;
+                if !is_template_id(&id) {
+                    return Err(format!("'{}' is not a template id", id));
+                }
+                Ok(LaunchRequest::InstallTemplate { id })
+            }
+            other => Err(format!("Unknown link action '{}'; expected open or install-template", other)),
+        };
+    }
+
+    // File associations arrive as file:// URLs on macOS and as plain paths elsewhere
+    let path = match target.strip_prefix("file://") {
+        Some(rest) => percent_decode(rest.strip_prefix("localhost").unwrap_or(rest)),
+        None => target.to_string(),
+    };
+    Ok(LaunchRequest::OpenProject {
+        path: project_folder(Path::new(&path))?,
+        from_link: false,
+    })
+}
+
+/// Queue requests for the given targets and tell the main window to pick them up
+pub fn dispatch<I: IntoIterator<Item = String>>(app: &AppHandle, targets: I) {
+    let requests: Vec<LaunchRequest> = targets
+        .into_iter()
+        .map(|target| {
+            parse(&target).unwrap_or_else(|reason| LaunchRequest::Rejected { target, reason })
+        })
+        .collect();
+    if requests.is_empty() {
+        return;
+    }
+    if let Ok(mut queue) = app.state::<LaunchQueue>().0.lock() {
+        queue.extend(requests);
+    }
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.set_focus();
+    }
+    let _ = app.emit_to("main", "launch-requests", ());
+}
+
+/// Command-line arguments that are launch targets: file paths from "open with", not flags or
+/// `promptplay://` links (the deep-link plugin reports those itself)
+pub fn launch_arguments(args: &[String]) -> Vec<String> {
+    args.iter()
+        .skip(1)
+        .filter(|arg| !arg.starts_with('-') && !arg.starts_with(&format!("{}://", SCHEME)))
+        .cloned()
+        .collect()
+}
+
+/// Take the pending launch requests; the editor calls this on startup and on `launch-requests`
+#[tauri::command]
+pub async fn take_launch_requests(queue: State<'_, LaunchQueue>) -> Result<Vec<LaunchRequest>, String> {
+    Ok(std::mem::take(&mut *queue.0.lock().map_err(|e| format!("Lock error: {}", e))?))
+}
@@ -0,0 +1,279 @@
+use crate::ai_client::AIClientState;
+use crate::credentials;
+use notify::{Config, RecommendedWatcher, RecursiveMode, Watcher};
+use serde::Serialize;
+use std::path::Path;
+use std::process::Command;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager, State};
+
+/// Free space under which saving, exporting and backups start failing
+const DISK_ERROR_BYTES: u64 = 200 * 1024 * 1024;
+const DISK_WARNING_BYTES: u64 = 1024 * 1024 * 1024;
+/// inotify watch limit under which large projects stop reporting changes
+const INOTIFY_WARNING_WATCHES: u64 = 16384;
+const NETWORK_TIMEOUT: Duration = Duration::from_secs(5);
+/// Keychain account that is read but never written, to see whether the keychain answers
+const KEYCHAIN_PROBE_ACCOUNT: &str = "diagnostics-probe";
+/// WebGL renderers that mean drawing falls back to the CPU
+const SOFTWARE_RENDERERS: &[&str] = &["swiftshader", "llvmpipe", "softpipe", "software", "microsoft basic render"];
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    Ok,
+    Warning,
+    Error,
+    /// The check couldn't run here
+    Unknown,
+}
+
+#[derive(Debug, Serialize)]
+pub struct EnvironmentCheck {
+    /// Stable key: webview, gpu, disk, fileWatcher, aiEndpoint, apiKey, keychain
+    pub id: &'static str,
+    pub label: &'static str,
+    pub status: CheckStatus,
+    pub detail: String,
+    /// What to try when the check didn't pass
+    pub hint: Option<String>,
+}
+
+impl EnvironmentCheck {
+    fn new(id: &'static str, label: &'static str, status: CheckStatus, detail: impl Into<String>) -> Self {
+        Self {
+            id,
+            label,
+            status,
+            detail: detail.into(),
+            hint: None,
+        }
+    }
+
+    fn hint(mut self, hint: impl Into<String>) -> Self {
+        self.hint = Some(hint.into());
+        self
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiagnosticsReport {
+    pub app_version: &'static str,
+    pub os: &'static str,
+    pub arch: &'static str,
+    pub errors: usize,
+    pub warnings: usize,
+    pub checks: Vec<EnvironmentCheck>,
+}
+
+fn webview_check() -> EnvironmentCheck {
+    match tauri::webview_version() {
+        Ok(version) => EnvironmentCheck::new("webview", "Webview", CheckStatus::Ok, version),
+        Err(e) => EnvironmentCheck::new("webview", "Webview", CheckStatus::Error, e.to_string()).hint(
+            if cfg!(windows) {
+                "Install or repair the Microsoft Edge WebView2 Runtime."
+            } else if cfg!(target_os = "linux") {
+                "Install WebKitGTK (webkit2gtk-4.1) from your distribution's packages."
+            } else {
+                "Update the operating system; PromptPlay uses the system webview."
+            },
+        ),
+    }
+}
+
+/// Judged from the WebGL renderer string, which only the editor window can read
+fn gpu_check(renderer: Option<&str>) -> EnvironmentCheck {
+    let Some(renderer) = renderer.filter(|r| !r.is_empty()) else {
+        return EnvironmentCheck::new("gpu", "GPU", CheckStatus::Unknown, "The editor window didn't report a renderer");
+    };
+    let lower = renderer.to_lowercase();
+    if SOFTWARE_RENDERERS.iter().any(|software| lower.contains(software)) {
+        EnvironmentCheck::new("gpu", "GPU", CheckStatus::Warning, format!("Software rendering ({})", renderer))
+            .hint("Update the graphics driver, or check that hardware acceleration isn't disabled.")
+    } else {
+        EnvironmentCheck::new("gpu", "GPU", CheckStatus::Ok, renderer)
+    }
+}
+
+/// Available bytes on the volume holding `dir`
+fn free_bytes(dir: &Path) -> Result<u64, String> {
+    let output = if cfg!(windows) {
+        let script = format!(
+            "(Get-Item -LiteralPath '{}').PSDrive.Free",
+            dir.to_string_lossy().replace('\'', "''")
+        );
+        Command::new("powershell").args(["-NoProfile", "-Command", &script]).output()
+    } else {
+        Command::new("df").arg("-Pk").arg(dir).output()
+    }
+    .map_err(|e| format!("Failed to check free space: {}", e))?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let parsed = if cfg!(windows) {
+        stdout.trim().parse::<u64>().ok()
+    } else {
+        // POSIX df: one header line, then "filesystem blocks used available capacity mount" in KiB
+        stdout
+            .lines()
+            .nth(1)
+            .and_then(|line| line.split_whitespace().nth(3))
+            .and_then(|kib| kib.parse::<u64>().ok())
+            .map(|kib| kib * 1024)
+    };
+    parsed.ok_or_else(|| format!("Unexpected free space output: {}", stdout.trim()))
+}
+
+fn disk_check(app: &AppHandle, project_path: Option<&str>) -> EnvironmentCheck {
+    let mut dirs = Vec::new();
+    if let Ok(dir) = app.path().app_data_dir() {
+        dirs.push(("app data", dir.to_string_lossy().to_string()));
+    }
+    if let Some(project) = project_path {
+        dirs.push(("project", project.to_string()));
+    }
+
+    let mut status = CheckStatus::Ok;
+    let mut details = Vec::new();
+    for (name, dir) in dirs {
+        // The app data folder may not exist yet on a fresh install
+        let existing = Path::new(&dir).ancestors().find(|p| p.exists()).unwrap_or(Path::new(&dir));
+        match free_bytes(existing) {
+            Ok(free) => {
+                let level = match free {
+                    f if f < DISK_ERROR_BYTES => CheckStatus::Error,
+                    f if f < DISK_WARNING_BYTES => CheckStatus::Warning,
+                    _ => CheckStatus::Ok,
+                };
+                status = worst(status, level);
+                details.push(format!("{}: {:.1} GB free", name, free as f64 / 1e9));
+            }
+            Err(e) => {
+                status = worst(status, CheckStatus::Unknown);
+                details.push(format!("{}: {}", name, e));
+            }
+        }
+    }
+    let check = EnvironmentCheck::new("disk", "Disk space", status, details.join("; "));
+    match status {
+        CheckStatus::Error | CheckStatus::Warning => {
+            check.hint("Free up disk space; saving, exports and backups need room to write.")
+        }
+        _ => check,
+    }
+}
+
+fn worst(a: CheckStatus, b: CheckStatus) -> CheckStatus {
+    let rank = |s: CheckStatus| match s {
+        CheckStatus::Ok => 0,
+        CheckStatus::Unknown => 1,
+        CheckStatus::Warning => 2,
+        CheckStatus::Error => 3,
+    };
+    if rank(b) > rank(a) {
+        b
+    } else {
+        a
+    }
+}
+
+/// The platform watcher backend, and whether it can actually watch a folder
+fn file_watcher_check() -> EnvironmentCheck {
+    let backend = format!("{:?}", RecommendedWatcher::kind());
+    let probe = std::env::temp_dir();
+    let watching = RecommendedWatcher::new(|_: notify::Result<notify::Event>| {}, Config::default())
+        .and_then(|mut watcher| watcher.watch(&probe, RecursiveMode::NonRecursive));
+    if let Err(e) = watching {
+        return EnvironmentCheck::new("fileWatcher", "File watcher", CheckStatus::Error, format!("{}: {}", backend, e))
+            .hint("External edits won't reload automatically; on Linux, raise fs.inotify.max_user_watches and max_user_instances.");
+    }
+
+    let limit = std::fs::read_to_string("/proc/sys/fs/inotify/max_user_watches")
+        .ok()
+        .and_then(|text| text.trim().parse::<u64>().ok());
+    match limit {
+        Some(limit) if limit < INOTIFY_WARNING_WATCHES => EnvironmentCheck::new(
+            "fileWatcher",
+            "File watcher",
+            CheckStatus::Warning,
+            format!("{} (max_user_watches {})", backend, limit),
+        )
+        .hint("Large projects may stop reloading on change; raise fs.inotify.max_user_watches."),
+        Some(limit) => EnvironmentCheck::new(
+            "fileWatcher",
+            "File watcher",
+            CheckStatus::Ok,
+            format!("{} (max_user_watches {})", backend, limit),
+        ),
+        None => EnvironmentCheck::new("fileWatcher", "File watcher", CheckStatus::Ok, backend),
+    }
+}
+
+/// Any HTTP answer counts: the endpoint is reachable even if it rejects an unauthenticated GET
+async fn ai_endpoint_check(client: reqwest::Client, url: String) -> EnvironmentCheck {
+    let started = Instant::now();
+    match client.get(&url).timeout(NETWORK_TIMEOUT).send().await {
+        Ok(response) => EnvironmentCheck::new(
+            "aiEndpoint",
+            "AI endpoint",
+            CheckStatus::Ok,
+            format!("{} answered HTTP {} in {} ms", url, response.status().as_u16(), started.elapsed().as_millis()),
+        ),
+        Err(e) => {
+            let reason = if e.is_timeout() { "timed out".to_string() } else { e.to_string() };
+            EnvironmentCheck::new("aiEndpoint", "AI endpoint", CheckStatus::Error, format!("{}: {}", url, reason))
+                .hint("Check the internet connection, proxy and firewall, or the provider's base URL in settings.")
+        }
+    }
+}
+
+fn keychain_check() -> EnvironmentCheck {
+    match credentials::load(KEYCHAIN_PROBE_ACCOUNT) {
+        Ok(_) => EnvironmentCheck::new("keychain", "Keychain", CheckStatus::Ok, "Available"),
+        Err(e) => EnvironmentCheck::new("keychain", "Keychain", CheckStatus::Error, e).hint(if cfg!(target_os = "linux") {
+            "Saved API keys and tokens need a Secret Service provider such as GNOME Keyring or KWallet running."
+        } else {
+            "Unlock the system keychain, then try again."
+        }),
+    }
+}
+
+/// Check the things support usually asks about first: webview, GPU, disk space, file watching,
+/// reaching the AI provider and the keychain. `gpu_renderer` is the WebGL renderer string from
+/// the editor window; `project_path` adds the project's volume to the disk check.
+#[tauri::command]
+pub async fn run_diagnostics(
+    app: AppHandle,
+    ai: State<'_, AIClientState>,
+    gpu_renderer: Option<String>,
+    project_path: Option<String>,
+) -> Result<DiagnosticsReport, String> {
+    let (http, provider, has_api_key) = {
+        let client = ai.0.lock().await;
+        (client.http().clone(), client.provider().clone(), client.has_api_key())
+    };
+
+    let mut checks = vec![
+        webview_check(),
+        gpu_check(gpu_renderer.as_deref()),
+        disk_check(&app, project_path.as_deref()),
+        file_watcher_check(),
+        ai_endpoint_check(http, provider.base_url().to_string()).await,
+    ];
+    let model = format!("{:?}, model {}", provider.kind, provider.model());
+    checks.push(if has_api_key {
+        EnvironmentCheck::new("apiKey", "AI provider", CheckStatus::Ok, model)
+    } else {
+        EnvironmentCheck::new("apiKey", "AI provider", CheckStatus::Warning, format!("{}; no API key", model))
+            .hint("Add an API key in the AI panel's settings.")
+    });
+    checks.push(keychain_check());
+
+    Ok(DiagnosticsReport {
+        app_version: env!("CARGO_PKG_VERSION"),
+        os: std::env::consts::OS,
+        arch: std::env::consts::ARCH,
+        errors: checks.iter().filter(|c| c.status == CheckStatus::Error).count(),
+        warnings: checks.iter().filter(|c| c.status == CheckStatus::Warning).count(),
+        checks,
+    })
+}
@@ -0,0 +1,101 @@
+use crate::assets::{AssetKind, ASSETS_DIR};
+use crate::settings::AppSettings;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use tauri::AppHandle;
+
+const IMAGE_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "gif", "webp", "bmp"];
+
+/// Run a native dialog off the async runtime, as pick_directory does
+pub fn run_dialog<T: Send + 'static>(show: impl FnOnce() -> T + Send + 'static) -> Result<T, String> {
+    let (tx, rx) = mpsc::channel();
+    std::thread::spawn(move || {
+        let _ = tx.send(show());
+    });
+    rx.recv().map_err(|e| format!("Dialog error: {}", e))
+}
+
+/// Last directory used for `purpose`, if it still exists
+fn last_directory(app: &AppHandle, purpose: &str) -> Option<PathBuf> {
+    AppSettings::load(app)
+        .last_directories
+        .get(purpose)
+        .map(PathBuf::from)
+        .filter(|dir| dir.is_dir())
+}
+
+fn remember_directory(app: &AppHandle, purpose: &str, dir: &Path) -> Result<(), String> {
+    let mut settings = AppSettings::load(app);
+    settings
+        .last_directories
+        .insert(purpose.to_string(), dir.to_string_lossy().to_string());
+    settings.save(app)
+}
+
+fn folder_dialog(title: &str, start: Option<PathBuf>) -> rfd::FileDialog {
+    let dialog = rfd::FileDialog::new().set_title(title);
+    match start {
+        Some(dir) => dialog.set_directory(dir),
+        None => dialog,
+    }
+}
+
+/// Pick a project folder; the folder must contain a game.json
+#[tauri::command]
+pub async fn pick_project_folder(app: AppHandle) -> Result<Option<String>, String> {
+    let dialog = folder_dialog("Open Project", last_directory(&app, "project"));
+    let Some(folder) = run_dialog(move || dialog.pick_folder())? else {
+        return Ok(None);
+    };
+
+    if !folder.join("game.json").is_file() {
+        return Err(format!("{} is not a PromptPlay project (no game.json)", folder.display()));
+    }
+    // Projects usually sit side by side, so start next time from the parent
+    remember_directory(&app, "project", folder.parent().unwrap_or(&folder))?;
+    Ok(Some(folder.to_string_lossy().to_string()))
+}
+
+/// Pick one or more image files, starting in the project's assets folder the first time
+#[tauri::command]
+pub async fn pick_image_assets(app: AppHandle, project_path: Option<String>) -> Result<Vec<String>, String> {
+    let start = last_directory(&app, "images").or_else(|| {
+        project_path
+            .map(|p| PathBuf::from(p).join(ASSETS_DIR))
+            .filter(|dir| dir.is_dir())
+    });
+    let dialog = folder_dialog("Import Images", start).add_filter("Images", IMAGE_EXTENSIONS);
+    let Some(files) = run_dialog(move || dialog.pick_files())? else {
+        return Ok(Vec::new());
+    };
+
+    // Filters are advisory on some platforms ("All files" is still selectable)
+    if let Some(bad) = files
+        .iter()
+        .find(|f| !f.is_file() || AssetKind::from_path(f) != AssetKind::Image)
+    {
+        return Err(format!("{} is not a supported image", bad.display()));
+    }
+    if let Some(dir) = files.first().and_then(|f| f.parent()) {
+        remember_directory(&app, "images", dir)?;
+    }
+    Ok(files.iter().map(|f| f.to_string_lossy().to_string()).collect())
+}
+
+/// Pick an export destination; the folder must be writable
+#[tauri::command]
+pub async fn pick_export_dir(app: AppHandle) -> Result<Option<String>, String> {
+    let dialog = folder_dialog("Export To", last_directory(&app, "export"));
+    let Some(folder) = run_dialog(move || dialog.pick_folder())? else {
+        return Ok(None);
+    };
+
+    let readonly = std::fs::metadata(&folder)
+        .map(|m| m.permissions().readonly())
+        .map_err(|e| format!("Failed to read {}: {}", folder.display(), e))?;
+    if readonly {
+        return Err(format!("{} is not writable", folder.display()));
+    }
+    remember_directory(&app, "export", &folder)?;
+    Ok(Some(folder.to_string_lossy().to_string()))
+}
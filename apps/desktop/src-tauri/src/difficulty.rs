@@ -0,0 +1,200 @@
+use crate::{json_guard, spec};
+use crate::validation::Diagnostic;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Scale one numeric component field, optionally only on entities with a tag
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Multiplier {
+    pub component: String,
+    pub field: String,
+    pub factor: f64,
+    pub tag: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DifficultyPreset {
+    pub name: String,
+    #[serde(default)]
+    pub multipliers: Vec<Multiplier>,
+}
+
+/// The spec's `difficulty` section
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct DifficultyConfig {
+    pub default: Option<String>,
+    #[serde(default)]
+    pub presets: Vec<DifficultyPreset>,
+}
+
+impl DifficultyConfig {
+    pub fn from_spec(spec: &Value) -> Result<Option<Self>, String> {
+        match spec.get("difficulty") {
+            None => Ok(None),
+            Some(raw) => serde_json::from_value(raw.clone())
+                .map(Some)
+                .map_err(|e| format!("Invalid difficulty section: {}", e)),
+        }
+    }
+
+    pub fn preset(&self, name: &str) -> Option<&DifficultyPreset> {
+        self.presets.iter().find(|p| p.name == name)
+    }
+}
+
+/// Built-in presets used when a spec has no `difficulty` section
+pub fn builtin_presets() -> Vec<DifficultyPreset> {
+    let preset = |name: &str, enemy_speed: f64, spawn_rate: f64, player_health: f64| {
+        DifficultyPreset {
+            name: name.to_string(),
+            multipliers: vec![
+                Multiplier {
+                    component: "aiBehavior".to_string(),
+                    field: "speed".to_string(),
+                    factor: enemy_speed,
+                    tag: None,
+                },
+                Multiplier {
+                    component: "particleEmitter".to_string(),
+                    field: "emitRate".to_string(),
+                    factor: spawn_rate,
+                    tag: Some("spawner".to_string()),
+                },
+                Multiplier {
+                    component: "health".to_string(),
+                    field: "max".to_string(),
+                    factor: player_health,
+                    tag: Some("player".to_string()),
+                },
+                Multiplier {
+                    component: "health".to_string(),
+                    field: "current".to_string(),
+                    factor: player_health,
+                    tag: Some("player".to_string()),
+                },
+            ],
+        }
+    };
+
+    vec![
+        preset("easy", 0.75, 0.6, 1.5),
+        preset("normal", 1.0, 1.0, 1.0),
+        preset("hard", 1.3, 1.5, 0.75),
+    ]
+}
+
+fn has_tag(entity: &Value, tag: &str) -> bool {
+    entity
+        .get("tags")
+        .and_then(Value::as_array)
+        .is_some_and(|tags| tags.iter().any(|t| t.as_str() == Some(tag)))
+}
+
+fn apply_multiplier(entity: &mut Value, multiplier: &Multiplier) {
+    if let Some(tag) = &multiplier.tag {
+        if !has_tag(entity, tag) {
+            return;
+        }
+    }
+
+    let Some(field) = entity
+        .get_mut("components")
+        .and_then(|c| c.get_mut(&multiplier.component))
+        .and_then(|c| c.get_mut(&multiplier.field))
+    else {
+        return;
+    };
+
+    // Keep integer fields (health, counts) integral after scaling
+    if let Some(n) = field.as_i64() {
+        *field = Value::from((n as f64 * multiplier.factor).round() as i64);
+    } else if let Some(n) = field.as_f64() {
+        *field = Value::from(n * multiplier.factor);
+    }
+}
+
+/// Produce a derived spec with a preset's multipliers applied to every entity
+pub fn apply_preset(spec: &Value, preset: &DifficultyPreset) -> Value {
+    let mut derived = spec.clone();
+
+    spec::for_each_entity_mut(&mut derived, |entity| {
+        for multiplier in &preset.multipliers {
+            apply_multiplier(entity, multiplier);
+        }
+    });
+
+    if let Some(root) = derived.as_object_mut() {
+        root.insert("activeDifficulty".to_string(), Value::from(preset.name.clone()));
+    }
+
+    derived
+}
+
+/// Resolve a preset by name from the spec, falling back to the built-in presets
+pub fn resolve_preset(spec: &Value, name: &str) -> Result<DifficultyPreset, String> {
+    let config = DifficultyConfig::from_spec(spec)?;
+
+    config
+        .as_ref()
+        .and_then(|c| c.preset(name).cloned())
+        .or_else(|| builtin_presets().into_iter().find(|p| p.name == name))
+        .ok_or_else(|| format!("Unknown difficulty preset '{}'", name))
+}
+
+/// Validate the optional `difficulty` section of a spec
+pub fn validate_difficulty(spec: &Value) -> Vec<Diagnostic> {
+    let config = match DifficultyConfig::from_spec(spec) {
+        Ok(Some(config)) => config,
+        Ok(None) => return Vec::new(),
+        Err(e) => return vec![Diagnostic::error("/difficulty", e)],
+    };
+
+    let mut diagnostics = Vec::new();
+
+    if let Some(default) = &config.default {
+        if config.preset(default).is_none() {
+            diagnostics.push(Diagnostic::error(
+                "/difficulty/default",
+                format!("Default preset '{}' is not defined", default),
+            ));
+        }
+    }
+
+    for (i, preset) in config.presets.iter().enumerate() {
+        for (j, multiplier) in preset.multipliers.iter().enumerate() {
+            if !multiplier.factor.is_finite() || multiplier.factor <= 0.0 {
+                diagnostics.push(Diagnostic::error(
+                    format!("/difficulty/presets/{}/multipliers/{}/factor", i, j),
+                    "Multiplier factor must be a positive number",
+                ));
+            }
+        }
+    }
+
+    diagnostics
+}
+
+/// List the difficulty presets available to a spec
+#[tauri::command]
+pub async fn list_difficulty_presets(game_spec_json: String) -> Result<Vec<DifficultyPreset>, String> {
+    let spec: Value = json_guard::parse(game_spec_json.as_bytes(), "game spec", &json_guard::SPEC_LIMITS)?;
+
+    Ok(DifficultyConfig::from_spec(&spec)?
+        .map(|c| c.presets)
+        .filter(|presets| !presets.is_empty())
+        .unwrap_or_else(builtin_presets))
+}
+
+/// Apply a difficulty preset and return the derived spec JSON
+#[tauri::command]
+pub async fn apply_difficulty(game_spec_json: String, preset: String) -> Result<String, String> {
+    let spec: Value = json_guard::parse(game_spec_json.as_bytes(), "game spec", &json_guard::SPEC_LIMITS)?;
+
+    let preset = resolve_preset(&spec, &preset)?;
+
+    serde_json::to_string_pretty(&apply_preset(&spec, &preset))
+        .map_err(|e| format!("Failed to serialize game spec: {}", e))
+}
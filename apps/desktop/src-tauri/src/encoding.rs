@@ -0,0 +1,65 @@
+use encoding_rs::{Encoding, UTF_16BE, UTF_16LE, UTF_8, WINDOWS_1252};
+use serde::Serialize;
+use std::borrow::Cow;
+
+/// Text decoded from disk together with what it was stored as
+#[derive(Debug, Serialize)]
+pub struct DecodedText {
+    pub content: String,
+    /// WHATWG encoding name, e.g. "UTF-8", "UTF-16LE", "windows-1252"
+    pub encoding: String,
+    pub had_bom: bool,
+    /// Whether any bytes could not be decoded and were replaced
+    pub lossy: bool,
+}
+
+/// BOM-less UTF-16 from Windows editors: ASCII text leaves every other byte zero
+fn sniff_utf16(bytes: &[u8]) -> Option<&'static Encoding> {
+    let sample = &bytes[..bytes.len().min(512) & !1];
+    if sample.len() < 4 {
+        return None;
+    }
+    let pairs = sample.len() / 2;
+    let zero_even = sample.iter().step_by(2).filter(|b| **b == 0).count();
+    let zero_odd = sample.iter().skip(1).step_by(2).filter(|b| **b == 0).count();
+    if zero_odd * 10 >= pairs * 9 && zero_even == 0 {
+        Some(UTF_16LE)
+    } else if zero_even * 10 >= pairs * 9 && zero_odd == 0 {
+        Some(UTF_16BE)
+    } else {
+        None
+    }
+}
+
+/// Decode bytes as text: BOMs first, then UTF-8, BOM-less UTF-16, and finally Windows-1252
+pub fn decode(bytes: &[u8]) -> DecodedText {
+    let (encoding, bom_length) = match Encoding::for_bom(bytes) {
+        Some((encoding, length)) => (encoding, length),
+        None if std::str::from_utf8(bytes).is_ok() => (UTF_8, 0),
+        None => (sniff_utf16(bytes).unwrap_or(WINDOWS_1252), 0),
+    };
+
+    let (content, lossy) = encoding.decode_without_bom_handling(&bytes[bom_length..]);
+    DecodedText {
+        content: content.into_owned(),
+        encoding: encoding.name().to_string(),
+        had_bom: bom_length > 0,
+        lossy,
+    }
+}
+
+/// Borrow bytes that are already plain UTF-8, transcoding anything else
+pub fn to_utf8(bytes: &[u8]) -> Cow<'_, [u8]> {
+    if Encoding::for_bom(bytes).is_none() && std::str::from_utf8(bytes).is_ok() {
+        Cow::Borrowed(bytes)
+    } else {
+        Cow::Owned(decode(bytes).content.into_bytes())
+    }
+}
+
+/// Read a text file in any common encoding and report the encoding that was detected
+#[tauri::command]
+pub async fn read_text_file(path: String) -> Result<DecodedText, String> {
+    let bytes = std::fs::read(&path).map_err(|e| format!("Failed to read file {}: {}", path, e))?;
+    Ok(decode(&bytes))
+}
@@ -0,0 +1,310 @@
+use crate::{backup, cache, credentials, history, paths};
+use argon2::Argon2;
+use base64::Engine;
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{Key, XChaCha20Poly1305, XNonce};
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use tauri::AppHandle;
+
+/// Marks a project as encrypted and holds what's needed to re-derive its key. It sits in the
+/// project root rather than .promptplay so backups and sync snapshots carry it.
+pub const MANIFEST_FILE: &str = ".promptplay-encryption.json";
+/// Prefix of every encrypted file, followed by the nonce and the ciphertext
+const MAGIC: &[u8] = b"PPENC1\n";
+const NONCE_LEN: usize = 24;
+const SALT_LEN: usize = 16;
+/// Encrypted with the key so a wrong passphrase is caught before any file is touched
+const CHECK_TEXT: &[u8] = b"promptplay-encryption-check";
+/// Specs, scripts and notes; assets stay readable so exports and thumbnails keep working
+const ENCRYPTED_EXTENSIONS: &[&str] = &["json", "js", "ts", "md", "txt", "yaml", "yml"];
+const MIN_PASSPHRASE_CHARS: usize = 8;
+
+/// `.promptplay-encryption.json`
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct EncryptionManifest {
+    version: u32,
+    /// Always "argon2id" for now
+    kdf: String,
+    salt: String,
+    check: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct EncryptionStatus {
+    pub encrypted: bool,
+    /// The key is in the keychain, so files open without asking for the passphrase
+    pub unlocked: bool,
+}
+
+/// Keys read from the keychain this session, by project root
+fn key_cache() -> &'static Mutex<HashMap<PathBuf, [u8; 32]>> {
+    static KEYS: OnceLock<Mutex<HashMap<PathBuf, [u8; 32]>>> = OnceLock::new();
+    KEYS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn keychain_account(project: &Path) -> String {
+    format!("project-key:{}", cache::project_key(&project.to_string_lossy()))
+}
+
+fn b64() -> base64::engine::GeneralPurpose {
+    base64::engine::general_purpose::STANDARD
+}
+
+/// The encrypted project `path` belongs to, if any
+fn project_for(path: &Path) -> Option<PathBuf> {
+    let path = paths::canonical(path);
+    path.ancestors().find(|dir| dir.join(MANIFEST_FILE).is_file()).map(Path::to_path_buf)
+}
+
+fn should_encrypt(project: &Path, path: &Path) -> bool {
+    let Some(relative) = paths::relative_to(&paths::canonical(path), project) else {
+        return false;
+    };
+    let relative = paths::to_spec_path(&relative);
+    let history = format!("{}/", history::HISTORY_DIR);
+    relative != MANIFEST_FILE
+        && (!relative.starts_with(".promptplay/") || relative.starts_with(&history))
+        && Path::new(&relative)
+            .extension()
+            .and_then(|e| e.to_str())
+            .is_some_and(|e| ENCRYPTED_EXTENSIONS.contains(&e.to_ascii_lowercase().as_str()))
+}
+
+fn load_manifest(project: &Path) -> Result<EncryptionManifest, String> {
+    let content = fs::read_to_string(project.join(MANIFEST_FILE))
+        .map_err(|e| format!("Failed to read {}: {}", MANIFEST_FILE, e))?;
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse {}: {}", MANIFEST_FILE, e))
+}
+
+fn derive_key(passphrase: &str, salt: &[u8]) -> Result<[u8; 32], String> {
+    let mut key = [0u8; 32];
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| format!("Failed to derive key: {}", e))?;
+    Ok(key)
+}
+
+fn seal(key: &[u8; 32], plaintext: &[u8]) -> Result<Vec<u8>, String> {
+    let cipher = XChaCha20Poly1305::new(Key::from_slice(key));
+    let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+    let ciphertext = cipher.encrypt(&nonce, plaintext).map_err(|e| format!("Failed to encrypt: {}", e))?;
+    Ok([MAGIC, nonce.as_slice(), &ciphertext].concat())
+}
+
+fn open(key: &[u8; 32], sealed: &[u8]) -> Result<Vec<u8>, String> {
+    let body = sealed.strip_prefix(MAGIC).ok_or("File is not encrypted")?;
+    if body.len() < NONCE_LEN {
+        return Err("Encrypted file is truncated".to_string());
+    }
+    let (nonce, ciphertext) = body.split_at(NONCE_LEN);
+    XChaCha20Poly1305::new(Key::from_slice(key))
+        .decrypt(XNonce::from_slice(nonce), ciphertext)
+        .map_err(|_| "Failed to decrypt: wrong key or the file was modified".to_string())
+}
+
+/// The project's key from this session's cache or the keychain
+fn key_for(project: &Path) -> Result<[u8; 32], String> {
+    if let Some(key) = key_cache().lock().ok().and_then(|keys| keys.get(project).copied()) {
+        return Ok(key);
+    }
+    let stored = credentials::load(&keychain_account(project))?.ok_or_else(|| {
+        format!("{} is encrypted and locked; unlock it with its passphrase", project.display())
+    })?;
+    let key: [u8; 32] = b64()
+        .decode(stored)
+        .ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or("The stored project key is corrupt; unlock the project again")?;
+    if let Ok(mut keys) = key_cache().lock() {
+        keys.insert(project.to_path_buf(), key);
+    }
+    Ok(key)
+}
+
+fn remember_key(project: &Path, key: [u8; 32]) -> Result<(), String> {
+    credentials::store(&keychain_account(project), &b64().encode(key))?;
+    if let Ok(mut keys) = key_cache().lock() {
+        keys.insert(project.to_path_buf(), key);
+    }
+    Ok(())
+}
+
+fn forget_key(project: &Path) -> Result<(), String> {
+    if let Ok(mut keys) = key_cache().lock() {
+        keys.remove(project);
+    }
+    credentials::delete(&keychain_account(project))
+}
+
+/// Whether `bytes` are an encrypted file
+pub fn is_encrypted(bytes: &[u8]) -> bool {
+    bytes.starts_with(MAGIC)
+}
+
+/// Decrypt `bytes` read from `path` when they're encrypted; plain files pass through
+pub fn decrypt(path: &Path, bytes: Vec<u8>) -> Result<Vec<u8>, String> {
+    if !bytes.starts_with(MAGIC) {
+        return Ok(bytes);
+    }
+    let project = project_for(path).ok_or_else(|| {
+        format!("{} is encrypted but isn't inside an encrypted project", path.display())
+    })?;
+    open(&key_for(&project)?, &bytes)
+}
+
+/// What to write to `path`: encrypted when it's a spec, script or note in an encrypted project
+pub fn encrypt_for<'a>(path: &Path, bytes: &'a [u8]) -> Result<Cow<'a, [u8]>, String> {
+    match project_for(path).filter(|project| should_encrypt(project, path)) {
+        Some(project) => Ok(Cow::Owned(seal(&key_for(&project)?, bytes)?)),
+        None => Ok(Cow::Borrowed(bytes)),
+    }
+}
+
+fn file_label(path: &Path) -> String {
+    path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_else(|| path.display().to_string())
+}
+
+/// Read a project file, decrypted when it's encrypted. Anything that reads specs, scripts or
+/// project metadata such as assets.json goes through here rather than `fs::read`.
+pub fn read(path: &Path) -> Result<Vec<u8>, String> {
+    let bytes = fs::read(path).map_err(|e| format!("Failed to read {}: {}", file_label(path), e))?;
+    decrypt(path, bytes)
+}
+
+pub fn read_to_string(path: &Path) -> Result<String, String> {
+    String::from_utf8(read(path)?).map_err(|e| format!("Failed to read {}: {}", file_label(path), e))
+}
+
+/// Write a project file, encrypted when `encrypt_for` says it should be
+pub fn write(path: &Path, bytes: &[u8]) -> Result<(), String> {
+    fs::write(path, encrypt_for(path, bytes)?).map_err(|e| format!("Failed to write {}: {}", file_label(path), e))
+}
+
+/// Re-write every eligible project file with `transform`, returning how many changed. Undo
+/// snapshots aren't part of backups but hold whole specs, so they're included.
+fn rewrite_files(
+    app: &AppHandle,
+    project: &Path,
+    transform: impl Fn(&[u8]) -> Result<Option<Vec<u8>>, String>,
+) -> Result<usize, String> {
+    let mut files = backup::project_files(app, project)?;
+    let history = project.join(history::HISTORY_DIR);
+    for entry in fs::read_dir(&history).into_iter().flatten().flatten() {
+        let path = entry.path();
+        if path.is_file() {
+            let name = format!("{}/{}", history::HISTORY_DIR, entry.file_name().to_string_lossy());
+            files.push((path, name));
+        }
+    }
+
+    let mut changed = 0;
+    for (path, name) in files {
+        if !should_encrypt(project, &path) {
+            continue;
+        }
+        let bytes = fs::read(&path).map_err(|e| format!("Failed to read {}: {}", name, e))?;
+        if let Some(output) = transform(&bytes)? {
+            fs::write(&path, output).map_err(|e| format!("Failed to write {}: {}", name, e))?;
+            changed += 1;
+        }
+    }
+    Ok(changed)
+}
+
+/// Whether the project is encrypted and, if so, whether its key is available
+#[tauri::command]
+pub async fn get_project_encryption(project_path: String) -> Result<EncryptionStatus, String> {
+    let project = paths::project_root(&project_path);
+    let encrypted = project.join(MANIFEST_FILE).is_file();
+    Ok(EncryptionStatus {
+        encrypted,
+        unlocked: encrypted && key_for(&project).is_ok(),
+    })
+}
+
+/// Encrypt the project's specs, scripts and notes with a key derived from `passphrase`. The key
+/// is kept in the OS keychain, so files open transparently until the project is locked.
+#[tauri::command]
+pub async fn enable_project_encryption(app: AppHandle, project_path: String, passphrase: String) -> Result<usize, String> {
+    let project = paths::project_root(&project_path);
+    if project.join(MANIFEST_FILE).exists() {
+        return Err("This project is already encrypted".to_string());
+    }
+    if passphrase.chars().count() < MIN_PASSPHRASE_CHARS {
+        return Err(format!("Use a passphrase of at least {} characters", MIN_PASSPHRASE_CHARS));
+    }
+
+    let mut salt = [0u8; SALT_LEN];
+    OsRng.fill_bytes(&mut salt);
+    let key = derive_key(&passphrase, &salt)?;
+    let manifest = EncryptionManifest {
+        version: 1,
+        kdf: "argon2id".to_string(),
+        salt: b64().encode(salt),
+        check: b64().encode(seal(&key, CHECK_TEXT)?),
+    };
+    remember_key(&project, key)?;
+    let content = serde_json::to_string_pretty(&manifest).map_err(|e| format!("Failed to serialize {}: {}", MANIFEST_FILE, e))?;
+    fs::write(project.join(MANIFEST_FILE), content).map_err(|e| format!("Failed to write {}: {}", MANIFEST_FILE, e))?;
+
+    rewrite_files(&app, &project, |bytes| {
+        if bytes.starts_with(MAGIC) {
+            Ok(None)
+        } else {
+            seal(&key, bytes).map(Some)
+        }
+    })
+}
+
+/// Check the passphrase against the project's manifest and keep its key in the keychain
+#[tauri::command]
+pub async fn unlock_encrypted_project(project_path: String, passphrase: String) -> Result<EncryptionStatus, String> {
+    let project = paths::project_root(&project_path);
+    let manifest = load_manifest(&project)?;
+    let salt = b64().decode(&manifest.salt).map_err(|e| format!("Failed to read {}: {}", MANIFEST_FILE, e))?;
+    let check = b64().decode(&manifest.check).map_err(|e| format!("Failed to read {}: {}", MANIFEST_FILE, e))?;
+    let key = derive_key(&passphrase, &salt)?;
+    if open(&key, &check).ok().as_deref() != Some(CHECK_TEXT) {
+        return Err("Wrong passphrase".to_string());
+    }
+    remember_key(&project, key)?;
+    Ok(EncryptionStatus {
+        encrypted: true,
+        unlocked: true,
+    })
+}
+
+/// Remove the project's key from the keychain; its files need the passphrase again to open
+#[tauri::command]
+pub async fn lock_encrypted_project(project_path: String) -> Result<EncryptionStatus, String> {
+    let project = paths::project_root(&project_path);
+    forget_key(&project)?;
+    Ok(EncryptionStatus {
+        encrypted: project.join(MANIFEST_FILE).is_file(),
+        unlocked: false,
+    })
+}
+
+/// Decrypt every file and stop encrypting the project; it must be unlocked first
+#[tauri::command]
+pub async fn disable_project_encryption(app: AppHandle, project_path: String) -> Result<usize, String> {
+    let project = paths::project_root(&project_path);
+    let key = key_for(&project)?;
+    let decrypted = rewrite_files(&app, &project, |bytes| {
+        if bytes.starts_with(MAGIC) {
+            open(&key, bytes).map(Some)
+        } else {
+            Ok(None)
+        }
+    })?;
+    fs::remove_file(project.join(MANIFEST_FILE)).map_err(|e| format!("Failed to remove {}: {}", MANIFEST_FILE, e))?;
+    forget_key(&project)?;
+    Ok(decrypted)
+}
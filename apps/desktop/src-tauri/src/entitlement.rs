@@ -0,0 +1,321 @@
+use crate::dialogs::run_dialog;
+use crate::{cache, credentials, paths};
+use base64::Engine;
+use chacha20poly1305::aead::OsRng;
+use ed25519_dalek::{Signer, SigningKey};
+use rfd::{MessageButtons, MessageDialog, MessageDialogResult, MessageLevel};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Identifies the project across moves, renames and fresh clones; its signing key is stored in
+/// the keychain under this id. It sits in the project root so backups and sync snapshots carry it.
+pub const PROJECT_ID_FILE: &str = ".promptplay-id";
+/// Extension of signing key backups
+const KEY_BACKUP_EXTENSION: &str = "ppsigningkey";
+const REPLACE: &str = "Replace Key";
+const KEEP: &str = "Keep Current Key";
+
+/// Ids and dates of the keys handed out, so creators can look them up; the keys themselves
+/// aren't kept
+const LEDGER_FILE: &str = ".promptplay/issued-keys.json";
+/// Prefix of every key, bumped if the format changes
+const KEY_VERSION: &str = "PPK1";
+const MAX_KEYS_PER_BATCH: usize = 1000;
+
+/// What a key vouches for; signed by the project's signing key
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct KeyPayload {
+    id: String,
+    issued_at: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    licensee: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct IssuedKey {
+    pub id: String,
+    pub issued_at: u64,
+    #[serde(default)]
+    pub licensee: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct GameKey {
+    pub id: String,
+    /// The text to hand the buyer, usually saved as a `.key` file
+    pub key: String,
+}
+
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+fn b64url() -> base64::engine::GeneralPurpose {
+    base64::engine::general_purpose::URL_SAFE_NO_PAD
+}
+
+/// Signing key backup written by `export_signing_key`
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SigningKeyBackup {
+    version: u32,
+    project_id: String,
+    public_key: String,
+    signing_key: String,
+}
+
+/// The project's id from its id file, created on first use
+fn project_id(project_path: &str) -> Result<String, String> {
+    let path = paths::project_root(project_path).join(PROJECT_ID_FILE);
+    match fs::read_to_string(&path) {
+        Ok(content) => {
+            let id = content.trim();
+            return match uuid::Uuid::parse_str(id) {
+                Ok(_) => Ok(id.to_string()),
+                Err(_) => Err(format!("{} is corrupt; restore it from a backup", PROJECT_ID_FILE)),
+            };
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+        Err(e) => return Err(format!("Failed to read {}: {}", PROJECT_ID_FILE, e)),
+    }
+    let id = uuid::Uuid::new_v4().to_string();
+    fs::write(&path, &id).map_err(|e| format!("Failed to write {}: {}", PROJECT_ID_FILE, e))?;
+    Ok(id)
+}
+
+fn keychain_account(project_id: &str) -> String {
+    format!("entitlement-signing:{}", project_id)
+}
+
+/// Where keys were stored before projects had an id: under a hash of the project's path
+fn legacy_keychain_account(project_path: &str) -> String {
+    format!("entitlement-signing:{}", cache::project_key(project_path))
+}
+
+fn decode_key(encoded: &str) -> Option<SigningKey> {
+    let bytes: [u8; 32] = b64url().decode(encoded.trim()).ok()?.try_into().ok()?;
+    Some(SigningKey::from_bytes(&bytes))
+}
+
+fn stored_key(account: &str) -> Result<Option<SigningKey>, String> {
+    match credentials::load(account)? {
+        Some(stored) => decode_key(&stored).map(Some).ok_or_else(|| "The stored signing key is corrupt".to_string()),
+        None => Ok(None),
+    }
+}
+
+fn ledger_path(project_path: &str) -> PathBuf {
+    paths::project_root(project_path).join(LEDGER_FILE)
+}
+
+/// The project's signing key from the keychain, created on first use. Losing it invalidates
+/// every key issued so far; `export_signing_key` makes a backup.
+fn signing_key(project_path: &str) -> Result<SigningKey, String> {
+    let account = keychain_account(&project_id(project_path)?);
+    if let Some(key) = stored_key(&account)? {
+        return Ok(key);
+    }
+    // A key made before the project had an id keeps working, so keys already sold stay valid
+    let key = match stored_key(&legacy_keychain_account(project_path))? {
+        Some(key) => key,
+        None => SigningKey::generate(&mut OsRng),
+    };
+    credentials::store(&account, &b64url().encode(key.to_bytes()))?;
+    Ok(key)
+}
+
+/// Base64url public key that exports embed to check keys against
+pub fn public_key(project_path: &str) -> Result<String, String> {
+    Ok(b64url().encode(signing_key(project_path)?.verifying_key().to_bytes()))
+}
+
+fn load_ledger(project_path: &str) -> Result<Vec<IssuedKey>, String> {
+    match fs::read_to_string(ledger_path(project_path)) {
+        Ok(content) => serde_json::from_str(&content).map_err(|e| format!("Failed to parse {}: {}", LEDGER_FILE, e)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(format!("Failed to read {}: {}", LEDGER_FILE, e)),
+    }
+}
+
+fn save_ledger(project_path: &str, ledger: &[IssuedKey]) -> Result<(), String> {
+    let path = ledger_path(project_path);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create .promptplay directory: {}", e))?;
+    }
+    let content = serde_json::to_string_pretty(ledger).map_err(|e| format!("Failed to serialize key ledger: {}", e))?;
+    fs::write(&path, content).map_err(|e| format!("Failed to write {}: {}", LEDGER_FILE, e))
+}
+
+/// Appended to the runtime module of exports that need a license key. Play stays disabled until
+/// a key signed by the project's key is loaded from a file or pasted; a verified key is
+/// remembered in localStorage. Verification uses WebCrypto Ed25519 and works offline.
+pub fn gate_script(public_key: &str) -> String {
+    format!(
+        r#"
+
+        // License key gate: play unlocks once a key signed by the creator verifies
+        if (typeof runtime !== 'undefined') (() => {{
+            const publicKey = '{public_key}';
+            const storageKey = 'promptplay-license:' + publicKey;
+            const playButton = document.getElementById('play-btn');
+            const fromB64 = (text) => Uint8Array.from(atob(text.replace(/-/g, '+').replace(/_/g, '/')), c => c.charCodeAt(0));
+            const verify = async (text) => {{
+                const parts = text.trim().split('.');
+                if (parts.length !== 3 || parts[0] !== '{version}') return false;
+                try {{
+                    const key = await crypto.subtle.importKey('raw', fromB64(publicKey), {{ name: 'Ed25519' }}, false, ['verify']);
+                    const signed = new TextEncoder().encode(parts[0] + '.' + parts[1]);
+                    return await crypto.subtle.verify({{ name: 'Ed25519' }}, key, fromB64(parts[2]), signed);
+                }} catch (e) {{
+                    return false;
+                }}
+            }};
+            const baseStart = runtime.start.bind(runtime);
+            let unlocked = false;
+            runtime.start = () => {{ if (unlocked) baseStart(); }};
+            playButton.disabled = true;
+
+            const overlay = document.createElement('div');
+            overlay.style.cssText = 'position:absolute;inset:0;display:flex;flex-direction:column;align-items:center;justify-content:center;gap:10px;background:rgba(10,10,25,0.92);color:#fff;font-size:14px;z-index:10;';
+            overlay.innerHTML = '<div style="font-weight:600">Enter your license key</div>'
+                + '<input type="file" accept=".key,.txt">'
+                + '<textarea rows="3" cols="40" placeholder="or paste the key here" style="font-family:monospace;font-size:11px"></textarea>'
+                + '<button style="padding:6px 14px">Unlock</button><div class="license-error" style="color:#f88"></div>';
+            const unlock = async (text, remember) => {{
+                if (!(await verify(text))) {{
+                    overlay.querySelector('.license-error').textContent = 'That key is not valid for this game.';
+                    return;
+                }}
+                unlocked = true;
+                playButton.disabled = false;
+                overlay.remove();
+                if (remember) localStorage.setItem(storageKey, text.trim());
+            }};
+            overlay.querySelector('input').addEventListener('change', async (e) => {{
+                const file = e.target.files[0];
+                if (file) unlock(await file.text(), true);
+            }});
+            overlay.querySelector('button').addEventListener('click', () => unlock(overlay.querySelector('textarea').value, true));
+            document.getElementById('game-container').appendChild(overlay);
+
+            const saved = localStorage.getItem(storageKey);
+            if (saved) unlock(saved, false);
+        }})();"#,
+        public_key = public_key,
+        version = KEY_VERSION
+    )
+}
+
+/// Sign `count` license keys for the project's exports, optionally noting who they're for.
+/// Exports made with `requireLicenseKey` accept any key from this project.
+#[tauri::command]
+pub async fn generate_game_keys(
+    project_path: String,
+    count: usize,
+    licensee: Option<String>,
+) -> Result<Vec<GameKey>, String> {
+    if count == 0 || count > MAX_KEYS_PER_BATCH {
+        return Err(format!("Generate between 1 and {} keys at a time", MAX_KEYS_PER_BATCH));
+    }
+    let signing_key = signing_key(&project_path)?;
+    let mut ledger = load_ledger(&project_path)?;
+    let issued_at = now_millis();
+
+    let mut keys = Vec::with_capacity(count);
+    for _ in 0..count {
+        let payload = KeyPayload {
+            id: uuid::Uuid::new_v4().to_string(),
+            issued_at,
+            licensee: licensee.clone(),
+        };
+        let json = serde_json::to_string(&payload).map_err(|e| format!("Failed to serialize key: {}", e))?;
+        let signed = format!("{}.{}", KEY_VERSION, b64url().encode(json));
+        let signature = signing_key.sign(signed.as_bytes());
+        keys.push(GameKey {
+            id: payload.id.clone(),
+            key: format!("{}.{}", signed, b64url().encode(signature.to_bytes())),
+        });
+        ledger.push(IssuedKey {
+            id: payload.id,
+            issued_at,
+            licensee: payload.licensee,
+        });
+    }
+    save_ledger(&project_path, &ledger)?;
+    Ok(keys)
+}
+
+/// Save a backup of the project's signing key where the user picks in a native dialog, so it can
+/// be restored on another machine or after losing the keychain. Returns where it was saved, or
+/// None when the dialog was cancelled. The key never passes through the webview.
+#[tauri::command]
+pub async fn export_signing_key(project_path: String) -> Result<Option<String>, String> {
+    let key = signing_key(&project_path)?;
+    let backup = SigningKeyBackup {
+        version: 1,
+        project_id: project_id(&project_path)?,
+        public_key: b64url().encode(key.verifying_key().to_bytes()),
+        signing_key: b64url().encode(key.to_bytes()),
+    };
+    let dialog = rfd::FileDialog::new()
+        .set_title("Back Up Signing Key")
+        .set_file_name(format!("signing-key.{}", KEY_BACKUP_EXTENSION))
+        .add_filter("Signing key", &[KEY_BACKUP_EXTENSION]);
+    let Some(target) = run_dialog(move || dialog.save_file())? else {
+        return Ok(None);
+    };
+    let content = serde_json::to_string_pretty(&backup).map_err(|e| format!("Failed to serialize signing key: {}", e))?;
+    fs::write(&target, content).map_err(|e| format!("Failed to write {}: {}", target.display(), e))?;
+    Ok(Some(target.to_string_lossy().to_string()))
+}
+
+/// Restore the project's signing key from a backup picked in a native dialog. Replacing a
+/// different key invalidates the keys it signed, so that asks first. Returns the public key, or
+/// None when nothing was restored.
+#[tauri::command]
+pub async fn import_signing_key(project_path: String) -> Result<Option<String>, String> {
+    let dialog = rfd::FileDialog::new()
+        .set_title("Restore Signing Key")
+        .add_filter("Signing key", &[KEY_BACKUP_EXTENSION]);
+    let Some(source) = run_dialog(move || dialog.pick_file())? else {
+        return Ok(None);
+    };
+    let content = fs::read_to_string(&source).map_err(|e| format!("Failed to read {}: {}", source.display(), e))?;
+    let backup: SigningKeyBackup =
+        serde_json::from_str(&content).map_err(|e| format!("{} isn't a signing key backup: {}", source.display(), e))?;
+    let key = decode_key(&backup.signing_key).ok_or("The signing key in the backup is corrupt")?;
+    let public_key = b64url().encode(key.verifying_key().to_bytes());
+    if public_key != backup.public_key {
+        return Err("The backup's signing key doesn't match its public key".to_string());
+    }
+
+    let account = keychain_account(&project_id(&project_path)?);
+    if let Some(current) = stored_key(&account)?.filter(|current| current.to_bytes() != key.to_bytes()) {
+        let description = format!(
+            "This project already has a different signing key (public key {:.12}…). Keys it signed stop working if it's replaced.",
+            b64url().encode(current.verifying_key().to_bytes())
+        );
+        let answer = run_dialog(move || {
+            MessageDialog::new()
+                .set_title("Replace Signing Key?")
+                .set_description(description)
+                .set_level(MessageLevel::Warning)
+                .set_buttons(MessageButtons::OkCancelCustom(REPLACE.to_string(), KEEP.to_string()))
+                .show()
+        })?;
+        let replace = matches!(&answer, MessageDialogResult::Custom(label) if label == REPLACE)
+            || matches!(answer, MessageDialogResult::Ok);
+        if !replace {
+            return Ok(None);
+        }
+    }
+    credentials::store(&account, &b64url().encode(key.to_bytes()))?;
+    Ok(Some(public_key))
+}
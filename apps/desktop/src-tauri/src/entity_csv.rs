@@ -0,0 +1,493 @@
+use crate::history::{self, HistoryEntry};
+use crate::{csv, entity_ids, entity_locks, spec};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+use std::fs;
+
+/// How CSV columns map onto entity fields
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ColumnMapping {
+    /// Column holding stable entity ids; matching rows update the existing entity in place, and
+    /// rows with an unknown id are rejected. Rows with an empty id create entities.
+    pub id: Option<String>,
+    pub x: String,
+    pub y: String,
+    /// Column naming the entity type (template) for each row
+    pub entity_type: Option<String>,
+    pub name: Option<String>,
+    /// Extra columns mapped to "component.field", e.g. { "speed": "aiBehavior.speed" }
+    #[serde(default)]
+    pub params: HashMap<String, String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CsvPreview {
+    pub headers: Vec<String>,
+    pub sample_rows: Vec<Vec<String>>,
+    pub row_count: usize,
+    pub suggested_mapping: ColumnMapping,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RowError {
+    pub row: usize,
+    pub message: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CsvImportReport {
+    pub created: Vec<String>,
+    pub updated: Vec<String>,
+    pub errors: Vec<RowError>,
+    pub applied: bool,
+    pub history: Option<HistoryEntry>,
+}
+
+/// Minimal templates for common placement types when the spec has no matching entity
+fn builtin_template(entity_type: &str) -> Option<Value> {
+    let template = match entity_type.to_lowercase().as_str() {
+        "coin" | "collectible" => json!({
+            "components": {
+                "transform": { "x": 0, "y": 0, "rotation": 0, "scaleX": 1, "scaleY": 1 },
+                "sprite": { "texture": "coin", "width": 16, "height": 16, "tint": "#f1c40f" },
+                "collider": { "type": "circle", "radius": 8, "isSensor": true }
+            },
+            "tags": ["collectible"]
+        }),
+        "enemy" => json!({
+            "components": {
+                "transform": { "x": 0, "y": 0, "rotation": 0, "scaleX": 1, "scaleY": 1 },
+                "sprite": { "texture": "enemy", "width": 32, "height": 32, "tint": "#e74c3c" },
+                "velocity": { "vx": 0, "vy": 0 },
+                "collider": { "type": "box", "width": 32, "height": 32 },
+                "aiBehavior": { "type": "patrol", "speed": 60, "detectionRadius": 150 }
+            },
+            "tags": ["enemy"]
+        }),
+        "platform" => json!({
+            "components": {
+                "transform": { "x": 0, "y": 0, "rotation": 0, "scaleX": 1, "scaleY": 1 },
+                "sprite": { "texture": "platform", "width": 128, "height": 16, "tint": "#7f8c8d" },
+                "collider": { "type": "box", "width": 128, "height": 16 }
+            },
+            "tags": ["platform"]
+        }),
+        _ => return None,
+    };
+    Some(template)
+}
+
+/// Guess a mapping from conventional header names
+fn suggest_mapping(headers: &[String]) -> ColumnMapping {
+    let find = |candidates: &[&str]| {
+        headers
+            .iter()
+            .find(|h| candidates.iter().any(|c| h.eq_ignore_ascii_case(c)))
+            .cloned()
+    };
+
+    let x = find(&["x", "posx", "pos_x"]).unwrap_or_else(|| "x".to_string());
+    let y = find(&["y", "posy", "pos_y"]).unwrap_or_else(|| "y".to_string());
+    let entity_type = find(&["type", "kind", "prefab", "template"]);
+    let name = find(&["name", "label"]);
+    let id = find(&["id", "uuid"]);
+
+    let params = headers
+        .iter()
+        .filter(|h| h.contains('.'))
+        .map(|h| (h.clone(), h.clone()))
+        .collect();
+
+    ColumnMapping {
+        id,
+        x,
+        y,
+        entity_type,
+        name,
+        params,
+    }
+}
+
+fn parse_cell(raw: &str) -> Value {
+    let raw = raw.trim();
+    if let Ok(i) = raw.parse::<i64>() {
+        return Value::from(i);
+    }
+    if let Ok(f) = raw.parse::<f64>() {
+        return Value::from(f);
+    }
+    match raw.to_lowercase().as_str() {
+        "true" => Value::from(true),
+        "false" => Value::from(false),
+        _ => Value::from(raw),
+    }
+}
+
+fn set_component_field(entity: &mut Value, path: &str, value: Value) -> Result<(), String> {
+    let (component, field) = path
+        .split_once('.')
+        .ok_or_else(|| format!("Parameter target '{}' must be 'component.field'", path))?;
+
+    let components = entity
+        .as_object_mut()
+        .ok_or("Entity must be an object")?
+        .entry("components")
+        .or_insert_with(|| Value::Object(Map::new()));
+    let component = components
+        .as_object_mut()
+        .ok_or("Entity components must be an object")?
+        .entry(component)
+        .or_insert_with(|| Value::Object(Map::new()));
+    component
+        .as_object_mut()
+        .ok_or_else(|| format!("Component in '{}' is not an object", path))?
+        .insert(field.to_string(), value);
+    Ok(())
+}
+
+/// The entity list of a scene, or the top-level list when `scene` is None
+pub fn target_entities<'a>(game_spec: &'a mut Value, scene: Option<&str>) -> Result<&'a mut Vec<Value>, String> {
+    let container = match scene {
+        None => game_spec,
+        Some(scene_id) => game_spec
+            .get_mut("scenes")
+            .and_then(Value::as_array_mut)
+            .and_then(|scenes| {
+                scenes
+                    .iter_mut()
+                    .find(|s| s.get("id").and_then(Value::as_str) == Some(scene_id))
+            })
+            .ok_or_else(|| format!("Scene '{}' not found", scene_id))?,
+    };
+
+    container
+        .as_object_mut()
+        .ok_or("Game spec must be a JSON object")?
+        .entry("entities")
+        .or_insert_with(|| Value::Array(Vec::new()))
+        .as_array_mut()
+        .ok_or_else(|| "entities must be an array".to_string())
+}
+
+/// Read a CSV's headers and first rows, with a suggested column mapping
+#[tauri::command]
+pub async fn preview_entities_csv(path: String) -> Result<CsvPreview, String> {
+    let text = fs::read_to_string(&path).map_err(|e| format!("Failed to read file {}: {}", path, e))?;
+    let (headers, records) = csv::parse_with_headers(&text)?;
+
+    Ok(CsvPreview {
+        suggested_mapping: suggest_mapping(&headers),
+        sample_rows: records.iter().take(5).cloned().collect(),
+        row_count: records.len(),
+        headers,
+    })
+}
+
+/// Create entities from CSV rows (x, y, type, params) in the project's game.json or one scene.
+/// Rows with an id update that entity; ids matching no entity are reported as errors, as are rows
+/// that would update a locked entity unless `include_locked` is set.
+#[tauri::command]
+pub async fn import_entities_csv(
+    project_path: String,
+    path: String,
+    mapping: ColumnMapping,
+    scene: Option<String>,
+    dry_run: Option<bool>,
+    include_locked: Option<bool>,
+) -> Result<CsvImportReport, String> {
+    let text = fs::read_to_string(&path).map_err(|e| format!("Failed to read file {}: {}", path, e))?;
+    let (headers, records) = csv::parse_with_headers(&text)?;
+
+    let column = |name: &str| -> Result<usize, String> {
+        headers
+            .iter()
+            .position(|h| h == name)
+            .ok_or_else(|| format!("Column '{}' not found in CSV", name))
+    };
+    let x_col = column(&mapping.x)?;
+    let y_col = column(&mapping.y)?;
+    let id_col = mapping.id.as_deref().map(column).transpose()?;
+    let type_col = mapping.entity_type.as_deref().map(column).transpose()?;
+    let name_col = mapping.name.as_deref().map(column).transpose()?;
+    let param_cols = mapping
+        .params
+        .iter()
+        .map(|(col, target)| Ok((column(col)?, target.clone())))
+        .collect::<Result<Vec<_>, String>>()?;
+
+    let mut game_spec = spec::load_project_spec(&project_path)?;
+    let mut existing_names: HashMap<String, Value> = spec::all_entities(&game_spec)
+        .filter_map(|e| Some((e.get("name")?.as_str()?.to_string(), e.clone())))
+        .collect();
+
+    let mut new_entities = Vec::new();
+    let mut report = CsvImportReport {
+        created: Vec::new(),
+        updated: Vec::new(),
+        errors: Vec::new(),
+        applied: false,
+        history: None,
+    };
+
+    for (i, record) in records.iter().enumerate() {
+        let row = i + 2; // 1-based, after the header
+        let cell = |col: usize| record.get(col).map(|v| v.trim()).unwrap_or_default();
+
+        let (Ok(x), Ok(y)) = (cell(x_col).parse::<f64>(), cell(y_col).parse::<f64>()) else {
+            report.errors.push(RowError {
+                row,
+                message: format!("Invalid coordinates '{}', '{}'", cell(x_col), cell(y_col)),
+            });
+            continue;
+        };
+
+        let params = || {
+            [("transform.x", Value::from(x)), ("transform.y", Value::from(y))]
+                .into_iter()
+                .chain(param_cols.iter().filter_map(|(col, target)| {
+                    let raw = cell(*col);
+                    (!raw.is_empty()).then(|| (target.as_str(), parse_cell(raw)))
+                }))
+                .collect::<Vec<_>>()
+        };
+
+        // Rows carrying a known id update that entity, keeping every other component
+        if let Some(id) = id_col.map(cell).filter(|id| !id.is_empty()) {
+            if let Some(existing) = spec::find_entity_by_id_mut(&mut game_spec, id) {
+                if entity_locks::is_locked(existing) && !include_locked.unwrap_or(false) {
+                    let name = existing.get("name").and_then(Value::as_str).unwrap_or(id);
+                    report.errors.push(RowError {
+                        row,
+                        message: format!("Entity '{}' is locked; unlock it or allow updating locked entities", name),
+                    });
+                    continue;
+                }
+                // Edit a copy so a failing row leaves the entity untouched
+                let mut updated = existing.clone();
+                if let Some(name) = name_col.map(cell).filter(|n| !n.is_empty()) {
+                    updated["name"] = Value::from(name);
+                }
+                match params()
+                    .into_iter()
+                    .try_for_each(|(target, value)| set_component_field(&mut updated, target, value))
+                {
+                    Ok(()) => {
+                        report.updated.push(
+                            updated
+                                .get("name")
+                                .and_then(Value::as_str)
+                                .unwrap_or(id)
+                                .to_string(),
+                        );
+                        *existing = updated;
+                    }
+                    Err(message) => report.errors.push(RowError { row, message }),
+                }
+                continue;
+            }
+            // A stale or mistyped id would otherwise silently duplicate the entity it meant
+            report.errors.push(RowError {
+                row,
+                message: format!("No entity with id '{}'; clear the id cell to create a new entity", id),
+            });
+            continue;
+        }
+
+        let entity_type = type_col.map(cell).filter(|t| !t.is_empty());
+        let template = match entity_type {
+            Some(t) => match existing_names.get(t).cloned().or_else(|| builtin_template(t)) {
+                Some(template) => template,
+                None => {
+                    report.errors.push(RowError {
+                        row,
+                        message: format!("Unknown entity type '{}'", t),
+                    });
+                    continue;
+                }
+            },
+            None => json!({ "components": {} }),
+        };
+
+        let mut entity = template;
+        let obj = entity.as_object_mut().ok_or("Template must be an object")?;
+        let base = name_col
+            .map(cell)
+            .filter(|n| !n.is_empty())
+            .or(entity_type)
+            .unwrap_or("entity")
+            .to_string();
+        let mut name = base.clone();
+        let mut suffix = 1;
+        while existing_names.contains_key(&name) {
+            suffix += 1;
+            name = format!("{}_{}", base, suffix);
+        }
+        obj.insert("name".to_string(), Value::from(name.clone()));
+        obj.insert("id".to_string(), Value::from(entity_ids::new_entity_id()));
+        // Copies of a locked entity start unlocked
+        obj.remove(entity_locks::LOCKED_KEY);
+
+        if let Err(message) = params()
+            .into_iter()
+            .try_for_each(|(target, value)| set_component_field(&mut entity, target, value))
+        {
+            report.errors.push(RowError { row, message });
+            continue;
+        }
+
+        existing_names.insert(name.clone(), entity.clone());
+        report.created.push(name);
+        new_entities.push(entity);
+    }
+
+    if dry_run.unwrap_or(false) || (new_entities.is_empty() && report.updated.is_empty()) {
+        return Ok(report);
+    }
+
+    target_entities(&mut game_spec, scene.as_deref())?.extend(new_entities);
+    let label = format!(
+        "Import CSV: {} created, {} updated",
+        report.created.len(),
+        report.updated.len()
+    );
+    report.history = Some(history::save_with_history(&project_path, &label, &game_spec)?);
+    report.applied = true;
+
+    Ok(report)
+}
+
+/// Filter for exported rows; every set field must match
+#[derive(Debug, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct EntityCsvFilter {
+    pub tag: Option<String>,
+    pub component: Option<String>,
+    pub name_contains: Option<String>,
+}
+
+impl EntityCsvFilter {
+    fn matches(&self, entity: &Value) -> bool {
+        let has_tag = |tag: &str| {
+            entity
+                .get("tags")
+                .and_then(Value::as_array)
+                .is_some_and(|tags| tags.iter().any(|t| t.as_str() == Some(tag)))
+        };
+        let name = entity.get("name").and_then(Value::as_str).unwrap_or_default();
+
+        self.tag.as_deref().is_none_or(has_tag)
+            && self
+                .component
+                .as_deref()
+                .is_none_or(|c| entity.pointer(&format!("/components/{}", c)).is_some())
+            && self
+                .name_contains
+                .as_deref()
+                .is_none_or(|n| name.to_lowercase().contains(&n.to_lowercase()))
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct CsvExportReport {
+    pub rows: usize,
+    pub columns: Vec<String>,
+}
+
+fn cell_text(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+/// Scalar "component.field" columns present on any of the entities, transform x/y excluded
+fn scalar_columns(entities: &[&Value]) -> Vec<String> {
+    let mut columns = std::collections::BTreeSet::new();
+    for entity in entities {
+        for (component, fields) in entity
+            .get("components")
+            .and_then(Value::as_object)
+            .into_iter()
+            .flatten()
+        {
+            for (field, value) in fields.as_object().into_iter().flatten() {
+                let column = format!("{}.{}", component, field);
+                if matches!(value, Value::Number(_) | Value::String(_) | Value::Bool(_))
+                    && column != "transform.x"
+                    && column != "transform.y"
+                {
+                    columns.insert(column);
+                }
+            }
+        }
+    }
+    columns.into_iter().collect()
+}
+
+/// Write entity placements to CSV with id/name/type/x/y plus scalar component columns.
+/// Re-importing the file with the id column mapped updates entities in place.
+#[tauri::command]
+pub async fn export_entities_csv(
+    project_path: String,
+    output_path: String,
+    scene: Option<String>,
+    filter: Option<EntityCsvFilter>,
+    columns: Option<Vec<String>>,
+) -> Result<CsvExportReport, String> {
+    let game_spec = spec::load_project_spec(&project_path)?;
+    let filter = filter.unwrap_or_default();
+
+    let entities: Vec<&Value> = spec::entities_with_scene(&game_spec)
+        .into_iter()
+        .filter(|(entity_scene, _)| scene.is_none() || *entity_scene == scene.as_deref())
+        .map(|(_, entity)| entity)
+        .filter(|entity| filter.matches(entity))
+        .collect();
+
+    let param_columns = columns.unwrap_or_else(|| scalar_columns(&entities));
+
+    let mut header = vec![
+        "id".to_string(),
+        "name".to_string(),
+        "type".to_string(),
+        "x".to_string(),
+        "y".to_string(),
+    ];
+    header.extend(param_columns.iter().cloned());
+
+    let mut out = csv::write_row(&header);
+    for entity in &entities {
+        let get = |pointer: &str| entity.pointer(pointer).map(cell_text).unwrap_or_default();
+        // The first tag doubles as the row type so re-imported new rows pick a sensible template
+        let entity_type = entity
+            .get("tags")
+            .and_then(Value::as_array)
+            .and_then(|tags| tags.first())
+            .map(cell_text)
+            .unwrap_or_default();
+
+        let mut row = vec![
+            get("/id"),
+            get("/name"),
+            entity_type,
+            get("/components/transform/x"),
+            get("/components/transform/y"),
+        ];
+        for column in &param_columns {
+            let pointer = format!("/components/{}", column.replacen('.', "/", 1));
+            row.push(get(&pointer));
+        }
+        out.push_str(&csv::write_row(&row));
+    }
+
+    fs::write(&output_path, out)
+        .map_err(|e| format!("Failed to write file {}: {}", output_path, e))?;
+
+    Ok(CsvExportReport {
+        rows: entities.len(),
+        columns: header,
+    })
+}
@@ -0,0 +1,201 @@
+use crate::json_guard;
+use crate::validation::Diagnostic;
+use serde::Serialize;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+use uuid::{Builder, Uuid};
+
+/// Component fields that point at another entity
+pub const REFERENCE_FIELDS: &[(&str, &str)] = &[
+    ("camera", "followTarget"),
+    ("aiBehavior", "targetEntity"),
+    ("spawner", "template"),
+    ("trigger", "target"),
+];
+
+#[derive(Debug, Serialize, Default)]
+pub struct MigrationReport {
+    pub ids_assigned: usize,
+    pub references_rewritten: usize,
+    pub unresolved_references: Vec<String>,
+}
+
+pub fn entity_id(entity: &Value) -> Option<&str> {
+    entity.get("id").and_then(Value::as_str)
+}
+
+pub fn new_entity_id() -> String {
+    Uuid::new_v4().to_string()
+}
+
+/// Id for a legacy entity, derived from where it sits so every load of an unsaved legacy spec
+/// agrees on it: the editor, backend reloads and the file watcher all see the same ids
+fn legacy_entity_id(scene: Option<&str>, index: usize, entity: &Value) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(scene.unwrap_or_default().as_bytes());
+    hasher.update([0]);
+    hasher.update(index.to_le_bytes());
+    hasher.update(entity.get("name").and_then(Value::as_str).unwrap_or_default().as_bytes());
+    let mut bytes = [0u8; 16];
+    bytes.copy_from_slice(&hasher.finalize()[..16]);
+    Builder::from_random_bytes(bytes).into_uuid().to_string()
+}
+
+/// Give every entity in one list an id and rewrite index/name references within it
+fn migrate_list(
+    scene: Option<&str>,
+    entities: &mut [Value],
+    all_ids: &HashSet<String>,
+    report: &mut MigrationReport,
+) {
+    for (index, entity) in entities.iter_mut().enumerate() {
+        if entity_id(entity).is_none() {
+            let id = legacy_entity_id(scene, index, entity);
+            if let Some(obj) = entity.as_object_mut() {
+                obj.insert("id".to_string(), Value::from(id));
+                report.ids_assigned += 1;
+            }
+        }
+    }
+
+    // Legacy references are either list indices or entity names
+    let by_index: Vec<Option<String>> = entities
+        .iter()
+        .map(|e| entity_id(e).map(str::to_string))
+        .collect();
+    let by_name: HashMap<String, String> = entities
+        .iter()
+        .filter_map(|e| {
+            Some((
+                e.get("name")?.as_str()?.to_string(),
+                entity_id(e)?.to_string(),
+            ))
+        })
+        .collect();
+
+    for entity in entities.iter_mut() {
+        let owner = entity
+            .get("name")
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string();
+        for (component, field) in REFERENCE_FIELDS {
+            let Some(reference) = entity
+                .get_mut("components")
+                .and_then(|c| c.get_mut(*component))
+                .and_then(|c| c.get_mut(*field))
+            else {
+                continue;
+            };
+
+            let resolved = match &*reference {
+                Value::Number(n) => n
+                    .as_u64()
+                    .and_then(|i| by_index.get(i as usize).cloned().flatten()),
+                Value::String(s) if all_ids.contains(s) => continue,
+                Value::String(s) => by_name.get(s).cloned(),
+                _ => continue,
+            };
+
+            match resolved {
+                Some(id) => {
+                    *reference = Value::from(id);
+                    report.references_rewritten += 1;
+                }
+                None => report
+                    .unresolved_references
+                    .push(format!("{}.{}.{} = {}", owner, component, field, reference)),
+            }
+        }
+    }
+}
+
+/// Assign stable ids to entities and convert index/name references to ids. Ids are derived from
+/// each entity's scene, position and name, so migrating the same file twice gives the same ids.
+pub fn migrate(spec: &mut Value) -> MigrationReport {
+    let mut report = MigrationReport::default();
+    let existing: HashSet<String> = crate::spec::all_entities(spec)
+        .filter_map(|e| entity_id(e).map(str::to_string))
+        .collect();
+
+    if let Some(entities) = spec.get_mut("entities").and_then(Value::as_array_mut) {
+        migrate_list(None, entities, &existing, &mut report);
+    }
+    if let Some(scenes) = spec.get_mut("scenes").and_then(Value::as_array_mut) {
+        for (index, scene) in scenes.iter_mut().enumerate() {
+            let scene_id = scene
+                .get("id")
+                .and_then(Value::as_str)
+                .map_or_else(|| format!("#{}", index), str::to_string);
+            if let Some(entities) = scene.get_mut("entities").and_then(Value::as_array_mut) {
+                migrate_list(Some(&scene_id), entities, &existing, &mut report);
+            }
+        }
+    }
+
+    report
+}
+
+/// Referential integrity: unique ids and references that resolve to existing entities
+pub fn validate_references(spec: &Value) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    let mut ids = HashSet::new();
+
+    for entity in crate::spec::all_entities(spec) {
+        let name = entity.get("name").and_then(Value::as_str).unwrap_or("<unnamed>");
+        match entity_id(entity) {
+            Some(id) if !ids.insert(id.to_string()) => diagnostics.push(Diagnostic::error(
+                "/entities",
+                format!("Duplicate entity id '{}' on '{}'", id, name),
+            )),
+            Some(_) => {}
+            None => diagnostics.push(Diagnostic::warning(
+                "/entities",
+                format!("Entity '{}' has no stable id", name),
+            )),
+        }
+    }
+
+    for entity in crate::spec::all_entities(spec) {
+        let name = entity.get("name").and_then(Value::as_str).unwrap_or("<unnamed>");
+        for (component, field) in REFERENCE_FIELDS {
+            let Some(reference) = entity
+                .get("components")
+                .and_then(|c| c.get(*component))
+                .and_then(|c| c.get(*field))
+            else {
+                continue;
+            };
+            match reference {
+                Value::String(id) if ids.contains(id) => {}
+                Value::String(id) => diagnostics.push(Diagnostic::error(
+                    "/entities",
+                    format!(
+                        "'{}' {}.{} references missing entity '{}'",
+                        name, component, field, id
+                    ),
+                )),
+                other => diagnostics.push(Diagnostic::warning(
+                    "/entities",
+                    format!(
+                        "'{}' {}.{} uses a legacy index reference ({}); reload to migrate it",
+                        name, component, field, other
+                    ),
+                )),
+            }
+        }
+    }
+
+    diagnostics
+}
+
+/// Assign stable entity ids and migrate references in a spec JSON string
+#[tauri::command]
+pub async fn migrate_entity_ids(game_spec_json: String) -> Result<(String, MigrationReport), String> {
+    let mut spec: Value = json_guard::parse(game_spec_json.as_bytes(), "game spec", &json_guard::SPEC_LIMITS)?;
+    let report = migrate(&mut spec);
+    let json = serde_json::to_string_pretty(&spec)
+        .map_err(|e| format!("Failed to serialize game spec: {}", e))?;
+    Ok((json, report))
+}
@@ -0,0 +1,197 @@
+use crate::history::{self, HistoryEntry};
+use crate::spec;
+use crate::spec_changes::{self, EntityChangeKind};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashSet;
+
+/// Entity key that marks hand-tuned content: bulk operations and AI changes leave a locked entity
+/// alone unless the caller explicitly overrides
+pub const LOCKED_KEY: &str = "locked";
+
+pub fn is_locked(entity: &Value) -> bool {
+    entity.get(LOCKED_KEY).and_then(Value::as_bool).unwrap_or(false)
+}
+
+/// A change to a locked entity that was refused or undone
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LockedEdit {
+    pub id: String,
+    pub name: String,
+    /// None for the top-level entity list
+    pub scene: Option<String>,
+    pub kind: EntityChangeKind,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProtectedSpec {
+    /// `after` with every locked entity as it was in `before`
+    pub spec: Value,
+    pub reverted: Vec<LockedEdit>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SetLockedResult {
+    /// Ids whose lock changed
+    pub changed: Vec<String>,
+    pub history: Option<HistoryEntry>,
+}
+
+/// Remove an entity by id from every entity list
+fn remove_entity(game_spec: &mut Value, id: &str) {
+    let same_id = |entity: &Value| entity.get("id").and_then(Value::as_str) == Some(id);
+    if let Some(entities) = game_spec.get_mut("entities").and_then(Value::as_array_mut) {
+        entities.retain(|e| !same_id(e));
+    }
+    for scene in game_spec.get_mut("scenes").and_then(Value::as_array_mut).into_iter().flatten() {
+        if let Some(entities) = scene.get_mut("entities").and_then(Value::as_array_mut) {
+            entities.retain(|e| !same_id(e));
+        }
+    }
+}
+
+/// The scene's entity list in `spec` (None for the top-level list), if it exists
+fn entity_list_mut<'a>(game_spec: &'a mut Value, scene: Option<&str>) -> Option<&'a mut Vec<Value>> {
+    let holder = match scene {
+        None => game_spec,
+        Some(id) => game_spec
+            .get_mut("scenes")?
+            .as_array_mut()?
+            .iter_mut()
+            .find(|s| s.get("id").and_then(Value::as_str) == Some(id))?,
+    };
+    holder.get_mut("entities")?.as_array_mut()
+}
+
+/// Edits and removals `after` makes to entities that are locked in `before`. Entities without ids
+/// can't be matched between versions and aren't checked.
+pub fn locked_edits(before: &Value, after: &Value) -> Vec<LockedEdit> {
+    let locked: Vec<(Option<&str>, &Value)> = spec::entities_with_scene(before)
+        .into_iter()
+        .filter(|(_, entity)| is_locked(entity))
+        .collect();
+    if locked.is_empty() {
+        return Vec::new();
+    }
+    spec_changes::diff(before, after)
+        .entities
+        .into_iter()
+        .filter(|change| change.kind != EntityChangeKind::Added)
+        .filter_map(|change| {
+            let (_, entity) = locked.iter().find(|(scene, entity)| {
+                *scene == change.scene.as_deref() && entity.get("id").and_then(Value::as_str) == Some(change.id.as_str())
+            })?;
+            Some(LockedEdit {
+                name: entity.get("name").and_then(Value::as_str).unwrap_or_default().to_string(),
+                id: change.id,
+                scene: change.scene,
+                kind: change.kind,
+            })
+        })
+        .collect()
+}
+
+/// Undo every change `after` makes to entities locked in `before`: edited ones get their old
+/// value back, removed ones are put back at the end of their list, and a locked entity moved to
+/// another scene is moved back. Everything else in `after` is kept.
+pub fn protect(before: &Value, after: &Value) -> ProtectedSpec {
+    let reverted = locked_edits(before, after);
+    let mut protected = after.clone();
+    for edit in &reverted {
+        let Some(original) = spec::entities_with_scene(before)
+            .into_iter()
+            .find(|(scene, entity)| {
+                *scene == edit.scene.as_deref() && entity.get("id").and_then(Value::as_str) == Some(edit.id.as_str())
+            })
+            .map(|(_, entity)| entity.clone())
+        else {
+            continue;
+        };
+        match edit.kind {
+            EntityChangeKind::Modified => {
+                if let Some(entity) = entity_list_mut(&mut protected, edit.scene.as_deref()).and_then(|entities| {
+                    entities
+                        .iter_mut()
+                        .find(|e| e.get("id").and_then(Value::as_str) == Some(edit.id.as_str()))
+                }) {
+                    *entity = original;
+                }
+            }
+            EntityChangeKind::Removed => {
+                remove_entity(&mut protected, &edit.id);
+                if let Some(entities) = entity_list_mut(&mut protected, edit.scene.as_deref()) {
+                    entities.push(original);
+                }
+            }
+            EntityChangeKind::Added => {}
+        }
+    }
+    ProtectedSpec {
+        spec: protected,
+        reverted,
+    }
+}
+
+/// Locked edits `after` would make to `before`, so the editor can warn before applying changes
+/// such as an AI patch
+#[tauri::command]
+pub async fn check_entity_locks(before: Value, after: Value) -> Result<Vec<LockedEdit>, String> {
+    Ok(locked_edits(&before, &after))
+}
+
+/// `after` with changes to locked entities undone, for applying an AI patch or other bulk edit
+/// without touching hand-tuned content
+#[tauri::command]
+pub async fn protect_locked_entities(before: Value, after: Value) -> Result<ProtectedSpec, String> {
+    Ok(protect(&before, &after))
+}
+
+/// Lock or unlock entities by id as one undoable change; returns the ids that changed
+#[tauri::command]
+pub async fn set_entities_locked(
+    project_path: String,
+    ids: Vec<String>,
+    locked: bool,
+) -> Result<SetLockedResult, String> {
+    let mut game_spec = spec::load_project_spec(&project_path)?;
+    let wanted: HashSet<&str> = ids.iter().map(String::as_str).collect();
+    let mut changed = Vec::new();
+    spec::for_each_entity_mut(&mut game_spec, |entity| {
+        let Some(id) = entity
+            .get("id")
+            .and_then(Value::as_str)
+            .filter(|id| wanted.contains(id))
+            .map(str::to_string)
+        else {
+            return;
+        };
+        if is_locked(entity) == locked {
+            return;
+        }
+        changed.push(id);
+        if let Some(entity) = entity.as_object_mut() {
+            if locked {
+                entity.insert(LOCKED_KEY.to_string(), Value::Bool(true));
+            } else {
+                entity.remove(LOCKED_KEY);
+            }
+        }
+    });
+    if changed.is_empty() {
+        return Ok(SetLockedResult { changed, history: None });
+    }
+
+    let label = match (locked, changed.len()) {
+        (true, 1) => "Lock entity".to_string(),
+        (false, 1) => "Unlock entity".to_string(),
+        (true, n) => format!("Lock {} entities", n),
+        (false, n) => format!("Unlock {} entities", n),
+    };
+    let entry = history::save_with_history(&project_path, &label, &game_spec)?;
+    Ok(SetLockedResult {
+        changed,
+        history: Some(entry),
+    })
+}
@@ -0,0 +1,148 @@
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+use std::fmt;
+
+/// Error returned by commands. It reaches the frontend as `{ code, message, hint }`, so the UI
+/// can branch on `code` instead of matching message text.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AppError {
+    /// A file or folder the command needs is missing
+    NotFound { path: String },
+    /// The OS refused access to a path
+    PermissionDenied { path: String },
+    /// Any other filesystem failure; `action` reads like "read file"
+    Io { action: String, path: String, detail: String },
+    /// The folder has no game.json
+    NotAProject { path: String },
+    /// game.json, or a spec sent by the editor, isn't valid
+    InvalidSpec { detail: String },
+    /// A native dialog failed to report its result
+    Dialog { detail: String },
+    /// Shared state was left poisoned by a panicking thread
+    Lock,
+    /// No Anthropic API key is configured
+    ApiKeyMissing,
+    /// The AI service could not be reached
+    Network { detail: String },
+    /// The AI service answered with an error status
+    Api { status: u16, detail: String },
+    /// The model kept calling tools without giving an answer
+    ToolLoop { rounds: usize },
+    /// The user stopped the request before it finished
+    Cancelled,
+    /// Errors passed through from modules that still report plain strings
+    Other { message: String },
+}
+
+impl AppError {
+    /// A filesystem error, classified by its kind
+    pub fn io(action: &str, path: &str, error: std::io::Error) -> Self {
+        match error.kind() {
+            std::io::ErrorKind::NotFound => AppError::NotFound { path: path.to_string() },
+            std::io::ErrorKind::PermissionDenied => AppError::PermissionDenied { path: path.to_string() },
+            _ => AppError::Io {
+                action: action.to_string(),
+                path: path.to_string(),
+                detail: error.to_string(),
+            },
+        }
+    }
+
+    /// Stable machine-readable code, camelCase like the rest of the IPC payloads
+    pub fn code(&self) -> &'static str {
+        match self {
+            AppError::NotFound { .. } => "notFound",
+            AppError::PermissionDenied { .. } => "permissionDenied",
+            AppError::Io { .. } => "io",
+            AppError::NotAProject { .. } => "notAProject",
+            AppError::InvalidSpec { .. } => "invalidSpec",
+            AppError::Dialog { .. } => "dialog",
+            AppError::Lock => "lock",
+            AppError::ApiKeyMissing => "apiKeyMissing",
+            AppError::Network { .. } => "network",
+            AppError::Api { status: 401 | 403, .. } => "apiUnauthorized",
+            AppError::Api { status: 429, .. } => "rateLimited",
+            AppError::Api { .. } => "api",
+            AppError::ToolLoop { .. } => "toolLoop",
+            AppError::Cancelled => "cancelled",
+            AppError::Other { .. } => "other",
+        }
+    }
+
+    /// What the user can do about it, when there is something
+    pub fn hint(&self) -> Option<&'static str> {
+        match self {
+            AppError::NotFound { .. } => Some("It may have been moved or deleted; refresh the file tree and try again."),
+            AppError::PermissionDenied { .. } => Some("Check the file's permissions or choose a different location."),
+            AppError::Io { .. } => Some("Check that the disk isn't full or disconnected, then try again."),
+            AppError::NotAProject { .. } => Some("Select a folder that contains game.json, or create a new project."),
+            AppError::InvalidSpec { .. } => Some("Fix the JSON in game.json, or restore it from a backup."),
+            AppError::Dialog { .. } | AppError::Lock => Some("Try again; restart PromptPlay if it keeps happening."),
+            AppError::ApiKeyMissing => {
+                Some("Add an API key for the selected AI provider in settings, or set its environment variable (e.g. ANTHROPIC_API_KEY).")
+            }
+            AppError::Network { .. } => Some("Check your internet connection and try again."),
+            AppError::Api { status: 401 | 403, .. } => Some("Check that your API key is correct and still active."),
+            AppError::Api { status: 429, .. } => Some("The AI service is rate limiting requests; wait a minute and retry."),
+            AppError::Api { status, .. } if *status >= 500 => Some("The AI service is having trouble; try again shortly."),
+            AppError::ToolLoop { .. } => Some("Rephrase the request or narrow it to fewer entities."),
+            AppError::Api { .. } | AppError::Cancelled | AppError::Other { .. } => None,
+        }
+    }
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AppError::NotFound { path } => write!(f, "Path does not exist: {}", path),
+            AppError::PermissionDenied { path } => write!(f, "Permission denied: {}", path),
+            AppError::Io { action, path, detail } => write!(f, "Failed to {} {}: {}", action, path, detail),
+            AppError::NotAProject { path } => write!(f, "game.json not found in {}", path),
+            AppError::InvalidSpec { detail } => write!(f, "Invalid game spec: {}", detail),
+            AppError::Dialog { detail } => write!(f, "Dialog error: {}", detail),
+            AppError::Lock => write!(f, "Lock error: shared state is poisoned"),
+            AppError::ApiKeyMissing => write!(f, "API key not configured"),
+            AppError::Network { detail } => write!(f, "Request failed: {}", detail),
+            AppError::Api { status, detail } => write!(f, "API error {}: {}", status, detail),
+            AppError::ToolLoop { rounds } => write!(f, "Stopped after {} tool calls without an answer", rounds),
+            AppError::Cancelled => write!(f, "Request cancelled"),
+            AppError::Other { message } => f.write_str(message),
+        }
+    }
+}
+
+impl std::error::Error for AppError {}
+
+impl Serialize for AppError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("AppError", 3)?;
+        state.serialize_field("code", self.code())?;
+        state.serialize_field("message", &self.to_string())?;
+        state.serialize_field("hint", &self.hint())?;
+        state.end()
+    }
+}
+
+impl From<String> for AppError {
+    fn from(message: String) -> Self {
+        AppError::Other { message }
+    }
+}
+
+impl From<&str> for AppError {
+    fn from(message: &str) -> Self {
+        AppError::Other { message: message.to_string() }
+    }
+}
+
+impl<T> From<std::sync::PoisonError<T>> for AppError {
+    fn from(_: std::sync::PoisonError<T>) -> Self {
+        AppError::Lock
+    }
+}
+
+/// Lets helpers that still return `Result<_, String>` call typed functions with `?`
+impl From<AppError> for String {
+    fn from(error: AppError) -> Self {
+        error.to_string()
+    }
+}
@@ -0,0 +1,1329 @@
+use crate::assets::AssetManifest;
+use crate::cache::{self, CacheCategory};
+use crate::components::ComponentRegistry;
+use crate::hooks::{self, HookEvent};
+use crate::notifications::{self, NotificationCategory};
+use crate::{asset_usage, coop, difficulty, json_guard, licensing, minimap, paths, physics_materials, runtime, seed, services, spec, symlinks, workers};
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+
+const MATTER_JS_URL: &str = "https://cdnjs.cloudflare.com/ajax/libs/matter-js/0.19.0/matter.min.js";
+/// Cached copy of Matter.js for exports that must run offline
+const MATTER_JS_CACHE_FILE: &str = "matter-0.19.0.min.js";
+/// SHA-256 of matter.min.js 0.19.0; the download and the cached copy must both match before the
+/// library is inlined into an export. Replace with the digest of a verified copy when bumping the
+/// version.
+const MATTER_JS_SHA256: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+/// Default ceiling for single-file exports, small enough for forum and mail attachments
+pub const DEFAULT_SINGLE_FILE_LIMIT: u64 = 10 * 1024 * 1024;
+
+/// Options controlling how a game is exported
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportOptions {
+    /// Difficulty preset baked into the export; defaults to the spec's default preset
+    pub difficulty: Option<String>,
+    /// Gameplay seed override; otherwise `config.seed` or a freshly rolled seed
+    pub seed: Option<u32>,
+    /// Project the spec belongs to; enables asset-aware checks such as licensing
+    pub project_path: Option<String>,
+    /// Inline Matter.js and every referenced asset so the export is one self-contained index.html
+    #[serde(default)]
+    pub single_file: bool,
+    /// Size ceiling for single-file exports; defaults to DEFAULT_SINGLE_FILE_LIMIT
+    pub max_size_bytes: Option<u64>,
+    /// Bundle every project asset instead of stripping ones the spec never references
+    #[serde(default)]
+    pub include_all_assets: bool,
+    /// Collider overlay, FPS counter, `pp` console inspector and a source map for the runtime
+    #[serde(default)]
+    pub debug: bool,
+    /// Fixed-timestep, delta-time movement at the spec's px/s; defaults to `config.deltaTime`
+    pub delta_time: Option<bool>,
+    /// Lock play behind a license key from `generate_game_keys`; needs `project_path`
+    #[serde(default)]
+    pub require_license_key: bool,
+}
+
+/// Result of an export, including non-fatal warnings for the user
+#[derive(Debug, Serialize)]
+pub struct ExportReport {
+    pub output_path: String,
+    pub manifest_path: String,
+    pub warnings: Vec<String>,
+    /// Unreferenced project assets left out of a bundled export
+    pub stripped_assets: Vec<String>,
+}
+
+/// Written next to every export so builds can be reproduced and compared
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportManifest {
+    pub title: String,
+    pub engine_version: String,
+    /// Game runtime bundled into the page; None for exports made without a project
+    pub runtime_version: Option<String>,
+    #[serde(default)]
+    pub debug: bool,
+    pub exported_at: u64,
+    pub seed: u32,
+    pub difficulty: Option<String>,
+    /// Size of the exported page in bytes
+    #[serde(default)]
+    pub output_bytes: u64,
+    /// Hash of the exported spec without inlined asset data, to tell content changes from rebuilds
+    #[serde(default)]
+    pub spec_hash: String,
+    #[serde(default)]
+    pub entity_count: usize,
+    /// Project assets the build uses, keyed by project-relative path
+    #[serde(default)]
+    pub assets: BTreeMap<String, ExportedAsset>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct ExportedAsset {
+    pub size: u64,
+    pub hash: String,
+}
+
+fn sha256_hex(bytes: &[u8]) -> String {
+    Sha256::digest(bytes).iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Hash of the spec's content, skipping the keys a single-file export adds for inlined assets
+fn spec_hash(spec: &Value) -> String {
+    let mut hasher = Sha256::new();
+    for (key, value) in spec.as_object().into_iter().flatten() {
+        if key != "assetData" && key != "assetAliases" {
+            hasher.update(key.as_bytes());
+            hasher.update(value.to_string().as_bytes());
+        }
+    }
+    hasher.finalize().iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+impl ExportManifest {
+    pub fn for_spec(spec: &Value, title: &str) -> Self {
+        Self {
+            title: title.to_string(),
+            engine_version: env!("CARGO_PKG_VERSION").to_string(),
+            runtime_version: None,
+            debug: false,
+            exported_at: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_millis() as u64)
+                .unwrap_or(0),
+            seed: seed::get(spec),
+            difficulty: spec
+                .get("activeDifficulty")
+                .and_then(Value::as_str)
+                .map(str::to_string),
+            output_bytes: 0,
+            spec_hash: spec_hash(spec),
+            entity_count: spec::all_entities(spec).count(),
+            assets: BTreeMap::new(),
+        }
+    }
+
+    /// `game.html` -> `game.manifest.json`
+    pub fn path_for(output_path: &Path) -> PathBuf {
+        let stem = output_path
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_else(|| "export".to_string());
+        output_path.with_file_name(format!("{}.manifest.json", stem))
+    }
+
+    pub fn write(&self, output_path: &Path) -> Result<(), String> {
+        let path = Self::path_for(output_path);
+        let content = serde_json::to_string_pretty(self)
+            .map_err(|e| format!("Failed to serialize export manifest: {}", e))?;
+        fs::write(&path, content)
+            .map_err(|e| format!("Failed to write export manifest {}: {}", path.display(), e))
+    }
+}
+
+fn minimap_elements(spec: &Value) -> impl Iterator<Item = &Value> {
+    spec.get("ui")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter(|el| el.get("type").and_then(Value::as_str) == Some("minimap"))
+}
+
+fn has_minimap_ui(spec: &Value) -> bool {
+    minimap_elements(spec).next().is_some()
+}
+
+fn mime_type(path: &Path) -> &'static str {
+    let ext = path
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    match ext.as_str() {
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "bmp" => "image/bmp",
+        "wav" => "audio/wav",
+        "mp3" => "audio/mpeg",
+        "ogg" => "audio/ogg",
+        "flac" => "audio/flac",
+        "m4a" => "audio/mp4",
+        _ => "application/octet-stream",
+    }
+}
+
+/// Assets carried inside a bundled export
+pub struct EmbeddedAssets {
+    /// Data URLs keyed by project-relative path
+    pub data: Map<String, Value>,
+    /// Spec references (bare names, file names) mapped to the path they resolve to
+    pub aliases: Map<String, Value>,
+    pub stripped: Vec<String>,
+    pub warnings: Vec<String>,
+    /// Size and hash of every embedded asset, for the export manifest
+    pub fingerprints: BTreeMap<String, ExportedAsset>,
+}
+
+/// Inline the project's referenced assets, dropping unreferenced ones unless `include_all`.
+/// Assets are read and encoded concurrently; one that can't be read becomes a warning.
+pub async fn embedded_assets(project_path: &str, spec: &Value, include_all: bool) -> EmbeddedAssets {
+    let selection = asset_usage::select(project_path, spec, include_all);
+    let mut embedded = EmbeddedAssets {
+        data: Map::new(),
+        aliases: Map::new(),
+        stripped: selection.stripped,
+        warnings: Vec::new(),
+        fingerprints: BTreeMap::new(),
+    };
+
+    let root = PathBuf::from(project_path);
+    let relative_paths: Vec<String> = selection.included.iter().map(|asset| asset.path.clone()).collect();
+    let encoded = workers::run_blocking(relative_paths, move |relative| {
+        let path = root.join(relative);
+        fs::read(paths::for_io(&path)).map_err(|e| e.to_string()).map(|bytes| {
+            let fingerprint = ExportedAsset {
+                size: bytes.len() as u64,
+                hash: sha256_hex(&bytes),
+            };
+            let url = format!(
+                "data:{};base64,{}",
+                mime_type(&path),
+                base64::engine::general_purpose::STANDARD.encode(bytes)
+            );
+            (url, fingerprint)
+        })
+    })
+    .await;
+
+    for (asset, url) in selection.included.into_iter().zip(encoded) {
+        match url.and_then(|url| url) {
+            Ok((url, fingerprint)) => {
+                embedded.data.insert(asset.path.clone(), Value::from(url));
+                embedded.fingerprints.insert(asset.path.clone(), fingerprint);
+                for reference in asset.references.into_iter().filter(|r| *r != asset.path) {
+                    embedded.aliases.insert(reference, Value::from(asset.path.clone()));
+                }
+            }
+            Err(e) => embedded
+                .warnings
+                .push(format!("Asset '{}' could not be read and is not embedded: {}", asset.path, e)),
+        }
+    }
+    embedded
+}
+
+/// Size and hash of the project assets a linked (not single-file) export refers to
+async fn asset_fingerprints(project_path: &str, spec: &Value, include_all: bool) -> BTreeMap<String, ExportedAsset> {
+    let root = PathBuf::from(project_path);
+    let relative_paths: Vec<String> = asset_usage::select(project_path, spec, include_all)
+        .included
+        .into_iter()
+        .map(|asset| asset.path)
+        .collect();
+    workers::run_blocking(relative_paths, move |relative| {
+        let bytes = fs::read(paths::for_io(&root.join(&relative))).ok()?;
+        Some((
+            relative,
+            ExportedAsset {
+                size: bytes.len() as u64,
+                hash: sha256_hex(&bytes),
+            },
+        ))
+    })
+    .await
+    .into_iter()
+    .filter_map(|outcome| outcome.ok().flatten())
+    .collect()
+}
+
+/// Matter.js source, downloaded once into the app cache so later exports work offline. Both the
+/// download and the cached copy are checked against [`MATTER_JS_SHA256`]; a cached copy that
+/// doesn't match is discarded and fetched again.
+async fn matter_js_source(app: &AppHandle) -> Result<String, String> {
+    let cache = cache::dir(app, CacheCategory::Downloads)?.join(MATTER_JS_CACHE_FILE);
+    if let Ok(bytes) = fs::read(&cache) {
+        if sha256_hex(&bytes) == MATTER_JS_SHA256 {
+            if let Ok(source) = String::from_utf8(bytes) {
+                return Ok(source);
+            }
+        }
+        let _ = fs::remove_file(&cache);
+    }
+
+    let bytes = reqwest::get(MATTER_JS_URL)
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| format!("Failed to download Matter.js for single-file export: {}", e))?
+        .bytes()
+        .await
+        .map_err(|e| format!("Failed to download Matter.js for single-file export: {}", e))?;
+    let digest = sha256_hex(&bytes);
+    if digest != MATTER_JS_SHA256 {
+        return Err(format!(
+            "Downloaded Matter.js does not match the pinned checksum (expected {}, got {}); not inlining it",
+            MATTER_JS_SHA256, digest
+        ));
+    }
+    let source = String::from_utf8(bytes.to_vec())
+        .map_err(|e| format!("Downloaded Matter.js is not valid UTF-8: {}", e))?;
+    if let Some(parent) = cache.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+    let _ = fs::write(&cache, &source);
+    Ok(source)
+}
+
+/// Keep inlined text from closing its <script> element early; `<\/` means `</` in JS and JSON strings
+fn script_safe(text: &str) -> String {
+    text.replace("</script", "<\\/script").replace("</SCRIPT", "<\\/SCRIPT")
+}
+
+/// Derive the spec that actually ships, applying export-time transforms
+pub fn prepare_spec(game_spec_json: &str, options: &ExportOptions) -> Result<Value, String> {
+    let mut spec: Value = json_guard::parse(game_spec_json.as_bytes(), "game spec", &json_guard::SPEC_LIMITS)?;
+
+    // Every export is pinned to a seed so replays of the build are reproducible
+    let seed = options
+        .seed
+        .or_else(|| seed::spec_seed(&spec))
+        .unwrap_or_else(seed::random_seed);
+    seed::set_spec_seed(&mut spec, seed)?;
+
+    if let Some(delta_time) = options.delta_time {
+        if let Some(config) = spec.get_mut("config").and_then(Value::as_object_mut) {
+            config.insert("deltaTime".to_string(), Value::Bool(delta_time));
+        }
+    }
+
+    let preset_name = match &options.difficulty {
+        Some(name) => Some(name.clone()),
+        None => difficulty::DifficultyConfig::from_spec(&spec)?.and_then(|c| c.default),
+    };
+
+    // Custom component data already rides along in each entity; ship the definitions
+    // too so runtime scripts can read declared defaults
+    if let Some(project_path) = &options.project_path {
+        let registry = ComponentRegistry::load(project_path)?;
+        let custom: serde_json::Map<String, Value> = registry
+            .custom()
+            .filter_map(|(name, def)| serde_json::to_value(def).ok().map(|v| (name.clone(), v)))
+            .collect();
+        if !custom.is_empty() {
+            if let Some(root) = spec.as_object_mut() {
+                root.insert("componentDefinitions".to_string(), Value::Object(custom));
+            }
+        }
+    }
+
+    // The runtime reads plain restitution/friction, so resolve collider materials up front
+    physics_materials::bake(&mut spec);
+
+    // Minimap HUD elements draw the baked images, so inline them into the export
+    if let Some(project_path) = &options.project_path {
+        if has_minimap_ui(&spec) {
+            let minimaps = minimap::export_payload(project_path);
+            if let Some(root) = spec.as_object_mut() {
+                root.insert("minimaps".to_string(), Value::Object(minimaps));
+            }
+        }
+    }
+
+    match preset_name {
+        Some(name) => {
+            let preset = difficulty::resolve_preset(&spec, &name)?;
+            Ok(difficulty::apply_preset(&spec, &preset))
+        }
+        None => Ok(spec),
+    }
+}
+
+/// Result of an embeddable export
+#[derive(Debug, Serialize)]
+pub struct EmbedReport {
+    pub export: ExportReport,
+    /// Ready-to-paste iframe markup, also written to embed.html
+    pub snippet: String,
+    pub snippet_path: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum EmbedSizing {
+    /// Iframe scales with its container, keeping the canvas aspect ratio
+    #[default]
+    Responsive,
+    /// Iframe at the canvas's pixel size
+    Fixed,
+}
+
+/// How an embedded game fits its iframe
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase", default)]
+pub struct EmbedOptions {
+    pub sizing: EmbedSizing,
+    /// Keep the aspect ratio with bars (true) or stretch to fill the frame (false)
+    pub letterbox: bool,
+    /// Bar color behind a letterboxed game; defaults to the page background
+    pub background: Option<String>,
+    /// Widest the responsive iframe grows, in CSS pixels
+    pub max_width: Option<u32>,
+    /// Where the bundle will be hosted; the snippet's iframe src (defaults to "index.html")
+    pub url: Option<String>,
+}
+
+impl Default for EmbedOptions {
+    fn default() -> Self {
+        Self {
+            sizing: EmbedSizing::Responsive,
+            letterbox: true,
+            background: None,
+            max_width: None,
+            url: None,
+        }
+    }
+}
+
+/// Page chrome for an export: a standalone page, or an iframe-filling embed
+enum Layout<'a> {
+    Page,
+    Embed(&'a EmbedOptions),
+}
+
+fn html_attribute(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('"', "&quot;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// Extra CSS and script that make the page fill its iframe and scale the canvas
+fn embed_chrome(embed: &EmbedOptions) -> (String, String) {
+    let background = embed.background.as_deref().unwrap_or("#1a1a2e");
+    let style = format!(
+        r#"<style>
+        html, body {{ width: 100%; height: 100%; overflow: hidden; min-height: 0; background: {background}; }}
+        #game-container {{ border-radius: 0; box-shadow: none; }}
+        .game-title, .credits {{ display: none; }}
+    </style>"#,
+        background = html_attribute(background)
+    );
+    let script = format!(
+        r#"
+        // Scale the canvas to the iframe; the runtime maps clicks back to canvas pixels
+        function fitToFrame() {{
+            const sx = window.innerWidth / canvas.width, sy = window.innerHeight / canvas.height;
+            const letterbox = {letterbox};
+            const scaleX = letterbox ? Math.min(sx, sy) : sx;
+            const scaleY = letterbox ? Math.min(sx, sy) : sy;
+            canvas.style.width = canvas.width * scaleX + 'px';
+            canvas.style.height = canvas.height * scaleY + 'px';
+        }}
+        window.addEventListener('resize', fitToFrame);
+        fitToFrame();"#,
+        letterbox = embed.letterbox
+    );
+    (style, script)
+}
+
+/// Iframe markup for a bundle exported with `embed`
+pub fn embed_snippet(spec: &Value, title: &str, embed: &EmbedOptions) -> String {
+    let canvas = crate::world::canvas(spec);
+    let src = html_attribute(embed.url.as_deref().unwrap_or("index.html"));
+    let title = html_attribute(title);
+    match embed.sizing {
+        EmbedSizing::Fixed => format!(
+            r#"<iframe src="{src}" title="{title}" width="{width}" height="{height}" style="border: 0;" allow="fullscreen; gamepad; autoplay" loading="lazy"></iframe>"#,
+            width = canvas.width,
+            height = canvas.height
+        ),
+        EmbedSizing::Responsive => format!(
+            r#"<div style="position: relative; width: 100%; max-width: {max_width}px; aspect-ratio: {width} / {height};">
+  <iframe src="{src}" title="{title}" style="position: absolute; inset: 0; width: 100%; height: 100%; border: 0;" allow="fullscreen; gamepad; autoplay" loading="lazy"></iframe>
+</div>"#,
+            max_width = embed.max_width.unwrap_or(canvas.width as u32),
+            width = canvas.width,
+            height = canvas.height
+        ),
+    }
+}
+
+/// Prepare, check and write an HTML export with the given page layout
+async fn write_export(
+    app: &AppHandle,
+    game_spec_json: &str,
+    output_path: String,
+    game_title: &str,
+    options: ExportOptions,
+    layout: Layout<'_>,
+) -> Result<(ExportReport, Value), String> {
+    let mut spec = prepare_spec(game_spec_json, &options)?;
+
+    let mut warnings = Vec::new();
+    let mut pinned_runtime = None;
+    if let Some(project_path) = &options.project_path {
+        pinned_runtime = Some(runtime::runtime_for_export(app, project_path, &spec)?);
+        let manifest = AssetManifest::load(project_path)?;
+        warnings.extend(licensing::distribution_warnings(&manifest, &spec));
+        warnings.extend(symlinks::export_warnings(project_path, &manifest, &spec));
+    }
+    for element in minimap_elements(&spec) {
+        let scene = element.get("scene").and_then(Value::as_str).filter(|s| !s.is_empty());
+        let baked = spec
+            .get("minimaps")
+            .and_then(|m| m.get(minimap::payload_key(scene)))
+            .is_some();
+        if !baked {
+            warnings.push(format!(
+                "Minimap '{}' has no baked minimap for scene '{}'; bake it before exporting",
+                element.get("id").and_then(Value::as_str).unwrap_or_default(),
+                scene.unwrap_or("main")
+            ));
+        }
+    }
+
+    let mut output_path = output_path;
+    let mut stripped_assets = Vec::new();
+    let mut fingerprints = BTreeMap::new();
+    let physics_script = if options.single_file {
+        if let Some(project_path) = &options.project_path {
+            let assets = embedded_assets(project_path, &spec, options.include_all_assets).await;
+            warnings.extend(assets.warnings);
+            stripped_assets = assets.stripped;
+            fingerprints = assets.fingerprints;
+            if let Some(root) = spec.as_object_mut() {
+                root.insert("assetData".to_string(), Value::Object(assets.data));
+                root.insert("assetAliases".to_string(), Value::Object(assets.aliases));
+            }
+        }
+        if Path::new(&output_path).is_dir() {
+            output_path = Path::new(&output_path).join("index.html").to_string_lossy().to_string();
+        }
+        format!("<script>{}</script>", script_safe(&matter_js_source(app).await?))
+    } else {
+        if let Some(project_path) = &options.project_path {
+            fingerprints = asset_fingerprints(project_path, &spec, options.include_all_assets).await;
+        }
+        format!(r#"<script src="{}"></script>"#, MATTER_JS_URL)
+    };
+
+    let spec_json = serde_json::to_string(&spec)
+        .map_err(|e| format!("Failed to serialize game spec: {}", e))?;
+
+    let (embed_style, mut embed_script) = match layout {
+        Layout::Page => (String::new(), String::new()),
+        Layout::Embed(embed) => embed_chrome(embed),
+    };
+    if options.debug {
+        embed_script.push_str(DEBUG_SCRIPT);
+        warnings.push("Debug export: the inspector exposes the whole spec in the console; don't publish this build".to_string());
+    }
+    if options.require_license_key {
+        let project_path = options
+            .project_path
+            .as_deref()
+            .ok_or("License-key exports need the project path to find its signing key")?;
+        embed_script.push_str(&crate::entitlement::gate_script(&crate::entitlement::public_key(project_path)?));
+    }
+    if let Some(project_path) = &options.project_path {
+        if let Some(script) = services::export_script(project_path, game_title)? {
+            embed_script.push_str(&script);
+        }
+    }
+    if let Some(script) = coop::export_script(&spec) {
+        embed_script.push_str(&script);
+    }
+    let mut html_content = generate_html(
+        &script_safe(&spec_json),
+        game_title,
+        &physics_script,
+        &embed_style,
+        &embed_script,
+    );
+    if let Some(script) = pinned_runtime.as_ref().map(runtime::RuntimeVersion::script).transpose()?.flatten() {
+        html_content = with_runtime_module(&html_content, &format!("{}{}", script, embed_script));
+    }
+    if options.debug {
+        html_content = with_source_map(&html_content);
+    }
+    if options.single_file {
+        let limit = options.max_size_bytes.unwrap_or(DEFAULT_SINGLE_FILE_LIMIT);
+        let size = html_content.len() as u64;
+        if size > limit {
+            return Err(format!(
+                "Single-file export is {:.1} MB, over the {:.1} MB limit ({:.1} MB of it is the spec and assets); shrink the assets or raise the limit",
+                size as f64 / 1048576.0,
+                limit as f64 / 1048576.0,
+                spec_json.len() as f64 / 1048576.0
+            ));
+        }
+    }
+    let output_bytes = html_content.len() as u64;
+    fs::write(&output_path, html_content)
+        .map_err(|e| format!("Failed to write export file {}: {}", output_path, e))?;
+
+    let output = Path::new(&output_path);
+    let mut manifest = ExportManifest::for_spec(&spec, game_title);
+    manifest.debug = options.debug;
+    manifest.output_bytes = output_bytes;
+    manifest.assets = fingerprints;
+    manifest.runtime_version = pinned_runtime.map(|r| format!("{}@{:?}", r.version, r.channel).to_lowercase());
+    manifest.write(output)?;
+    if let Some(project_path) = &options.project_path {
+        hooks::fire(app, project_path, HookEvent::OnExport);
+    }
+
+    let report = ExportReport {
+        manifest_path: ExportManifest::path_for(output).to_string_lossy().to_string(),
+        output_path,
+        warnings,
+        stripped_assets,
+    };
+    Ok((report, spec))
+}
+
+
+/// Export game as a standalone HTML file. With `singleFile`, Matter.js and the assets are inlined
+/// and a directory output gets an index.html.
+#[tauri::command]
+pub async fn export_game_html(
+    app: AppHandle,
+    game_spec_json: String,
+    output_path: String,
+    game_title: String,
+    options: Option<ExportOptions>,
+) -> Result<ExportReport, String> {
+    let report = export_html(&app, &game_spec_json, output_path, &game_title, options.unwrap_or_default()).await?;
+    notifications::task_finished(
+        &app,
+        NotificationCategory::Export,
+        "Export finished",
+        &format!("{} was exported to {}", game_title, report.output_path),
+    );
+    Ok(report)
+}
+
+/// The standalone HTML export without the completion notification, for background re-exports
+pub async fn export_html(
+    app: &AppHandle,
+    game_spec_json: &str,
+    output_path: String,
+    game_title: &str,
+    options: ExportOptions,
+) -> Result<ExportReport, String> {
+    write_export(app, game_spec_json, output_path, game_title, options, Layout::Page)
+        .await
+        .map(|(report, _)| report)
+}
+
+/// Export an iframe-ready bundle (index.html with assets inlined) into `output_dir`,
+/// plus embed.html holding the snippet to paste into a blog or portfolio page
+#[tauri::command]
+pub async fn export_game_embed(
+    app: AppHandle,
+    game_spec_json: String,
+    output_dir: String,
+    game_title: String,
+    options: Option<ExportOptions>,
+    embed: Option<EmbedOptions>,
+) -> Result<EmbedReport, String> {
+    let embed = embed.unwrap_or_default();
+    let mut options = options.unwrap_or_default();
+    // Hosts serve the bundle as-is, so assets travel inside the page
+    options.single_file = true;
+
+    let dir = PathBuf::from(&output_dir);
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create export directory {}: {}", output_dir, e))?;
+    let index = dir.join("index.html").to_string_lossy().to_string();
+    let (export, spec) =
+        write_export(&app, &game_spec_json, index, &game_title, options, Layout::Embed(&embed)).await?;
+
+    let snippet = embed_snippet(&spec, &game_title, &embed);
+    let snippet_path = dir.join("embed.html");
+    fs::write(&snippet_path, format!("{}\n", snippet))
+        .map_err(|e| format!("Failed to write embed snippet: {}", e))?;
+
+    notifications::task_finished(
+        &app,
+        NotificationCategory::Export,
+        "Embed export finished",
+        &format!("{} was exported to {}", game_title, output_dir),
+    );
+    Ok(EmbedReport {
+        export,
+        snippet,
+        snippet_path: snippet_path.to_string_lossy().to_string(),
+    })
+}
+
+/// Byte range of the runtime module's body in an export page
+fn module_range(html: &str) -> Option<(usize, usize)> {
+    const OPEN: &str = r#"<script type="module">"#;
+    const CLOSE: &str = "</script>\n</body>";
+    let start = html.find(OPEN)? + OPEN.len();
+    let end = html.rfind(CLOSE)?;
+    (start <= end).then_some((start, end))
+}
+
+/// Swap the built-in runtime module for a pinned runtime's runtime.js. The replacement reads the
+/// spec from #game-spec and must declare `canvas` for the embed chrome.
+fn with_runtime_module(html: &str, module: &str) -> String {
+    match module_range(html) {
+        Some((start, end)) => format!("{}\n{}\n    {}", &html[..start], script_safe(module), &html[end..]),
+        None => html.to_string(),
+    }
+}
+
+/// Name the runtime module and attach an inline source map, so devtools show it as
+/// promptplay-runtime.js with its own line numbers instead of an anonymous slice of the page
+fn with_source_map(html: &str) -> String {
+    let Some((start, end)) = module_range(html) else {
+        return html.to_string();
+    };
+    let body = &html[start..end];
+    // One segment per line at column 0, each mapping to the same line of the source
+    let lines = body.lines().count().max(1);
+    let mappings = std::iter::once("AAAA").chain(std::iter::repeat_n("AACA", lines - 1)).collect::<Vec<_>>().join(";");
+    let map = serde_json::json!({
+        "version": 3,
+        "file": "promptplay-runtime.js",
+        "sources": ["promptplay://runtime/game-runtime.js"],
+        "sourcesContent": [body],
+        "names": [],
+        "mappings": mappings,
+    });
+    format!(
+        "{}    //# sourceURL=promptplay-runtime.js\n    //# sourceMappingURL=data:application/json;base64,{}\n    {}",
+        &html[..end].trim_end_matches(' '),
+        base64::engine::general_purpose::STANDARD.encode(map.to_string()),
+        &html[end..]
+    )
+}
+
+/// Appended to the runtime module of debug exports. F3 toggles the collider overlay; `pp` in the
+/// console lists and inspects entities.
+const DEBUG_SCRIPT: &str = r#"
+
+        // Debug build: collider overlay, FPS counter and the `pp` console inspector
+        if (typeof runtime !== 'undefined') {
+            const debug = { overlay: true, fps: 0, frames: 0, since: performance.now() };
+            const baseRender = runtime.render.bind(runtime);
+            runtime.render = () => {
+                baseRender();
+                const now = performance.now();
+                debug.frames++;
+                if (now - debug.since >= 500) {
+                    debug.fps = Math.round(debug.frames * 1000 / (now - debug.since));
+                    debug.frames = 0;
+                    debug.since = now;
+                }
+                if (!debug.overlay) return;
+                const ctx = runtime.ctx, cam = runtime.camera || { x: 0, y: 0 };
+                ctx.save();
+                ctx.lineWidth = 1;
+                ctx.strokeStyle = 'rgba(52, 152, 219, 0.8)';
+                for (const e of runtime.entities) {
+                    ctx.strokeRect(e.x - cam.x - e.width / 2, e.y - cam.y - e.height / 2, e.width, e.height);
+                }
+                for (const body of Matter.Composite.allBodies(runtime.engine.world)) {
+                    ctx.strokeStyle = body.isStatic ? 'rgba(46, 204, 113, 0.9)' : 'rgba(231, 76, 60, 0.9)';
+                    ctx.beginPath();
+                    body.vertices.forEach((v, i) => i ? ctx.lineTo(v.x - cam.x, v.y - cam.y) : ctx.moveTo(v.x - cam.x, v.y - cam.y));
+                    ctx.closePath();
+                    ctx.stroke();
+                }
+                ctx.fillStyle = 'rgba(0, 0, 0, 0.6)';
+                ctx.fillRect(runtime.canvas.width - 124, 4, 120, 20);
+                ctx.fillStyle = '#0f0';
+                ctx.font = '12px monospace';
+                ctx.textAlign = 'left';
+                ctx.textBaseline = 'middle';
+                ctx.fillText(debug.fps + ' FPS  ' + runtime.entities.length + ' entities', runtime.canvas.width - 120, 14);
+                ctx.restore();
+            };
+            window.addEventListener('keydown', (e) => {
+                if (e.code === 'F3') { debug.overlay = !debug.overlay; runtime.render(); }
+            });
+            window.pp = {
+                runtime,
+                spec: runtime.spec,
+                state: () => ({ ...runtime.gameState }),
+                entities: () => console.table(runtime.entities.map(e => ({
+                    name: e.name, x: Math.round(e.x), y: Math.round(e.y), width: e.width, height: e.height,
+                    grounded: e.isGrounded, tags: e.tags.join(', ')
+                }))),
+                inspect: (name) => {
+                    const entity = runtime.entities.find(e => e.name === name);
+                    const body = runtime.bodies.get(name);
+                    console.log(entity, body && { velocity: body.velocity, angle: body.angle, isStatic: body.isStatic });
+                    return entity;
+                },
+                overlay: (on) => { debug.overlay = on ?? !debug.overlay; runtime.render(); }
+            };
+            console.info('PromptPlay debug build: F3 toggles the overlay; try pp.entities(), pp.inspect(name), pp.state()');
+            runtime.render();
+        }"#;
+
+/// Self-contained export page for headless runs: Matter.js inlined and `driver` appended to the
+/// runtime module, where it can reach `runtime`
+pub async fn headless_page(app: &AppHandle, spec: &Value, driver: &str) -> Result<String, String> {
+    let spec_json = serde_json::to_string(spec)
+        .map_err(|e| format!("Failed to serialize game spec: {}", e))?;
+    let physics_script = format!("<script>{}</script>", script_safe(&matter_js_source(app).await?));
+    Ok(generate_html(&script_safe(&spec_json), "PromptPlay headless run", &physics_script, "", driver))
+}
+
+/// HTML export that loads Matter.js from the CDN
+pub fn generate_standalone_html(game_spec_json: &str, title: &str) -> String {
+    let physics_script = format!(r#"<script src="{}"></script>"#, MATTER_JS_URL);
+    generate_html(game_spec_json, title, &physics_script, "", "")
+}
+
+/// The export page; `physics_script` is the <script> element providing Matter.js, and the
+/// embed parts are extra head markup and trailing module code (empty for standalone pages)
+fn generate_html(
+    game_spec_json: &str,
+    title: &str,
+    physics_script: &str,
+    embed_style: &str,
+    embed_script: &str,
+) -> String {
+    format!(r##"<!DOCTYPE html>
+<html lang="en">
+<head>
+    <meta charset="UTF-8">
+    <meta name="viewport" content="width=device-width, initial-scale=1.0">
+    <title>{title}</title>
+    <style>
+        * {{ margin: 0; padding: 0; box-sizing: border-box; }}
+        body {{
+            background: #1a1a2e;
+            display: flex;
+            justify-content: center;
+            align-items: center;
+            min-height: 100vh;
+            font-family: -apple-system, BlinkMacSystemFont, 'Segoe UI', Roboto, sans-serif;
+        }}
+        #game-container {{
+            position: relative;
+            border-radius: 8px;
+            overflow: hidden;
+            box-shadow: 0 20px 60px rgba(0,0,0,0.5);
+        }}
+        canvas {{ display: block; }}
+        .controls {{
+            position: absolute;
+            bottom: 10px;
+            left: 50%;
+            transform: translateX(-50%);
+            display: flex;
+            gap: 8px;
+            opacity: 0;
+            transition: opacity 0.3s;
+        }}
+        #game-container:hover .controls {{ opacity: 1; }}
+        .controls button {{
+            padding: 8px 16px;
+            border: none;
+            border-radius: 4px;
+            background: rgba(255,255,255,0.9);
+            color: #333;
+            font-size: 14px;
+            cursor: pointer;
+        }}
+        .controls button:hover {{ background: #fff; }}
+        .game-title {{
+            position: absolute;
+            top: 10px;
+            left: 10px;
+            color: white;
+            font-size: 14px;
+            font-weight: 600;
+            opacity: 0.7;
+        }}
+        .credits {{
+            position: fixed;
+            bottom: 10px;
+            right: 10px;
+            color: rgba(255,255,255,0.4);
+            font-size: 12px;
+        }}
+        .credits a {{ color: rgba(255,255,255,0.6); text-decoration: none; }}
+    </style>
+    {embed_style}
+</head>
+<body>
+    <div id="game-container">
+        <div class="game-title">{title}</div>
+        <canvas id="game-canvas" width="800" height="600"></canvas>
+        <div class="controls">
+            <button id="play-btn">Play</button>
+            <button id="reset-btn">Reset</button>
+        </div>
+    </div>
+    <div class="credits">Made with <a href="https://promptplay.dev" target="_blank">PromptPlay</a></div>
+
+    <script id="game-spec" type="application/json">{game_spec}</script>
+    {physics_script}
+    <script type="module">
+        const gameSpec = JSON.parse(document.getElementById('game-spec').textContent);
+
+        // Seeded PRNG (mulberry32) so every run of this build plays out identically
+        function mulberry32(seed) {{
+            let a = seed >>> 0;
+            return function() {{
+                a = (a + 0x6D2B79F5) >>> 0;
+                let t = a;
+                t = Math.imul(t ^ (t >>> 15), t | 1);
+                t ^= t + Math.imul(t ^ (t >>> 7), t | 61);
+                return ((t ^ (t >>> 14)) >>> 0) / 4294967296;
+            }};
+        }}
+        const gameSeed = (gameSpec.config && gameSpec.config.seed) || 0;
+        Math.random = mulberry32(gameSeed);
+
+        class GameRuntime {{
+            constructor(canvas, spec) {{
+                this.canvas = canvas;
+                this.ctx = canvas.getContext('2d');
+                this.spec = spec;
+                const view = spec.config.canvas || {{ width: 800, height: 600 }};
+                canvas.width = view.width;
+                canvas.height = view.height;
+                this.bounds = Object.assign({{ x: 0, y: 0 }}, spec.config.worldBounds || view);
+                this.entities = [];
+                this.isPlaying = false;
+                // Seat taken in a co-op relay; null plays every player entity from this keyboard
+                this.localPlayer = null;
+                this.keys = new Map();
+                this.lastTime = 0;
+                this.engine = Matter.Engine.create({{ gravity: {{ x: spec.config.gravity.x, y: spec.config.gravity.y }} }});
+                // Delta-time builds step physics at a fixed 60 Hz and convert px/s to px per step;
+                // legacy builds step once per rendered frame with speeds scaled by 0.01
+                this.fixedStep = !!spec.config.deltaTime;
+                this.velocityScale = this.fixedStep ? 1 / 60 : 0.01;
+                this.accumulator = 0;
+                this.bodies = new Map();
+                this.ui = spec.ui || [];
+                this.uiButtons = [];
+                this.images = {{}};
+                this.assetAliases = spec.assetAliases || {{}};
+                for (const [key, url] of Object.entries(spec.assetData || {{}})) {{
+                    const img = new Image();
+                    img.src = url;
+                    this.images[key] = img;
+                }}
+                this.minimaps = {{}};
+                for (const [key, mm] of Object.entries(spec.minimaps || {{}})) {{
+                    const img = new Image();
+                    img.src = mm.image;
+                    this.minimaps[key] = {{ ...mm, img }};
+                }}
+                this.resetGameState();
+                this.setupInput();
+                this.loadEntities();
+            }}
+
+            setupInput() {{
+                window.addEventListener('keydown', (e) => this.keys.set(e.code, true));
+                window.addEventListener('keyup', (e) => this.keys.set(e.code, false));
+                this.canvas.addEventListener('click', (e) => {{
+                    const rect = this.canvas.getBoundingClientRect();
+                    // The canvas may be drawn scaled (embeds), so map back to canvas pixels
+                    const x = (e.clientX - rect.left) * this.canvas.width / rect.width;
+                    const y = (e.clientY - rect.top) * this.canvas.height / rect.height;
+                    const hit = this.uiButtons.find(b => x >= b.x && x <= b.x + b.w && y >= b.y && y <= b.y + b.h);
+                    if (hit) this.runUIAction(hit.action);
+                }});
+            }}
+
+            resetGameState() {{
+                this.gameState = Object.assign({{
+                    score: 0, highScore: 0, lives: 3, maxLives: 3, level: 1,
+                    timeRemaining: 0, timeElapsed: 0, combo: 0, multiplier: 1
+                }}, this.spec.gameState || {{}});
+            }}
+
+            runUIAction(action) {{
+                if (action === 'restart') {{ this.reset(); this.render(); }}
+                else if (action === 'pause') this.pause();
+                else if (action === 'resume' && !this.isPlaying) this.start();
+            }}
+
+            resolveBinding(binding) {{
+                if (!binding) return undefined;
+                if (binding.startsWith('gameState.')) return this.gameState[binding.slice(10)];
+                const m = binding.match(/^entity\.(.+)\.health$/);
+                if (m) return this.entities.find(e => e.name === m[1])?.health;
+                return undefined;
+            }}
+
+            renderUI() {{
+                const ctx = this.ctx;
+                const cw = this.canvas.width, ch = this.canvas.height;
+                this.uiButtons = [];
+                for (const el of this.ui) {{
+                    if (el.visible === false) continue;
+                    const mm = el.type === 'minimap' ? this.minimaps[el.scene || ''] : null;
+                    const w = el.width || (mm ? mm.width : el.type === 'healthBar' ? 120 : 100);
+                    const h = el.height || (mm ? mm.height : el.type === 'healthBar' ? 12 : 24);
+                    const anchor = el.anchor || 'topLeft';
+                    let x = anchor.endsWith('Right') ? cw - w : anchor.endsWith('Center') || anchor === 'center' ? (cw - w) / 2 : 0;
+                    let y = anchor.startsWith('bottom') ? ch - h : anchor.startsWith('center') ? (ch - h) / 2 : 0;
+                    x += el.offsetX || 0;
+                    y += el.offsetY || 0;
+                    const style = el.style || {{}};
+                    const value = this.resolveBinding(el.binding);
+                    if (el.type === 'healthBar') {{
+                        const ratio = value && value.max ? Math.max(0, Math.min(1, value.current / value.max)) : 0;
+                        ctx.fillStyle = style.background || 'rgba(0,0,0,0.5)';
+                        ctx.fillRect(x, y, w, h);
+                        ctx.fillStyle = style.color || '#e74c3c';
+                        ctx.fillRect(x, y, w * ratio, h);
+                        continue;
+                    }}
+                    if (el.type === 'minimap') {{
+                        if (!mm) continue;
+                        ctx.drawImage(mm.img, x, y, w, h);
+                        const sx = w / mm.worldWidth, sy = h / mm.worldHeight;
+                        const dot = (ex, ey, color) => {{
+                            ctx.fillStyle = color;
+                            ctx.fillRect(x + (ex - (mm.worldX || 0)) * sx - 2, y + (ey - (mm.worldY || 0)) * sy - 2, 4, 4);
+                        }};
+                        const names = new Set(mm.collectibles.map(c => c.name));
+                        for (const e of this.entities) {{
+                            if (names.has(e.name)) dot(e.x, e.y, style.background || '#f1c40f');
+                        }}
+                        const player = this.entities.find(e => e.hasInput);
+                        if (player) dot(player.x, player.y, style.color || '#2ecc71');
+                        continue;
+                    }}
+                    if (el.type === 'button') {{
+                        ctx.fillStyle = style.background || 'rgba(255,255,255,0.9)';
+                        ctx.fillRect(x, y, w, h);
+                        this.uiButtons.push({{ x, y, w, h, action: el.action }});
+                    }}
+                    const text = (el.text ?? '{{value}}').replace('{{value}}', value ?? '');
+                    ctx.fillStyle = style.color || (el.type === 'button' ? '#333' : '#fff');
+                    ctx.font = (style.fontSize || 16) + 'px sans-serif';
+                    ctx.textBaseline = 'middle';
+                    ctx.textAlign = el.type === 'button' ? 'center' : 'left';
+                    ctx.fillText(text, el.type === 'button' ? x + w / 2 : x, y + h / 2);
+                }}
+            }}
+
+            createEntity(es, spawned) {{
+                const e = {{
+                    name: es.name,
+                    x: es.components.transform?.x || 0,
+                    y: es.components.transform?.y || 0,
+                    rotation: es.components.transform?.rotation || 0,
+                    width: es.components.sprite?.width || 32,
+                    height: es.components.sprite?.height || 32,
+                    color: '#' + (es.components.sprite?.tint || 0x808080).toString(16).padStart(6, '0'),
+                    texture: es.components.sprite?.texture,
+                    hasInput: !!es.components.input,
+                    moveSpeed: es.components.input?.moveSpeed || 200,
+                    jumpForce: es.components.input?.jumpForce || -400,
+                    player: es.components.input?.player || 1,
+                    tags: es.tags || [],
+                    isGrounded: false,
+                    health: es.components.health ? {{ ...es.components.health }} : null,
+                    spawner: es.components.spawner ? {{ ...es.components.spawner, timer: 0, alive: 0 }} : null,
+                    vx: 0, vy: 0, ttl: Infinity, body: null
+                }};
+
+                if (es.components.collider) {{
+                    // Spawned copies always move, even when their template is placed like scenery
+                    const isStatic = !spawned && !es.components.velocity && !es.components.input;
+                    const c = es.components.collider;
+                    const options = {{ isStatic, label: es.name, restitution: c.restitution ?? 0, friction: c.friction ?? 0.1 }};
+                    const w = c.width || e.width, h = c.height || e.height;
+                    const ox = c.offsetX || 0, oy = c.offsetY || 0;
+                    let outline = c.type === 'polygon' && c.vertices?.length >= 3 ? c.vertices : null;
+                    if (c.type === 'slope') {{
+                        outline = c.rise === 'left'
+                            ? [{{ x: -w/2, y: h/2 }}, {{ x: -w/2, y: -h/2 }}, {{ x: w/2, y: h/2 }}]
+                            : [{{ x: -w/2, y: h/2 }}, {{ x: w/2, y: -h/2 }}, {{ x: w/2, y: h/2 }}];
+                    }}
+                    let body;
+                    if (outline) {{
+                        // fromVertices centers the body on the outline's centroid; remember the
+                        // shift so the sprite stays where the spec puts it
+                        const centre = Matter.Vertices.centre(outline);
+                        body = Matter.Bodies.fromVertices(e.x + ox + centre.x, e.y + oy + centre.y, [outline], options);
+                        body.spriteOffset = {{ x: ox + centre.x, y: oy + centre.y }};
+                    }} else if (c.type === 'circle') {{
+                        body = Matter.Bodies.circle(e.x + ox, e.y + oy, c.radius || Math.max(w, h) / 2, options);
+                        body.spriteOffset = {{ x: ox, y: oy }};
+                    }} else {{
+                        body = Matter.Bodies.rectangle(e.x + ox, e.y + oy, w, h, options);
+                        body.spriteOffset = {{ x: ox, y: oy }};
+                    }}
+                    body.oneWay = isStatic && !!c.oneWay;
+                    e.body = body;
+                }}
+                return e;
+            }}
+
+            // Pools from spec.pooling pre-build their template's entities and bodies once, so
+            // spawning and despawning reuse them instead of leaving garbage behind
+            createPools() {{
+                this.pools = new Map();
+                for (const [name, cfg] of Object.entries(this.spec.pooling || {{}})) {{
+                    const template = this.templates.get(cfg.template);
+                    if (!template) continue;
+                    const pool = {{ name, template, free: [], size: 0, maxSize: cfg.maxSize || cfg.size }};
+                    for (let i = 0; i < cfg.size; i++) {{
+                        const e = this.createEntity(template, true);
+                        e.pool = pool;
+                        pool.free.push(e);
+                    }}
+                    pool.size = cfg.size;
+                    this.pools.set(cfg.template, pool);
+                }}
+            }}
+
+            spawn(templateId, x, y, vx, vy, ttl) {{
+                const pool = this.pools.get(templateId);
+                let e;
+                if (pool) {{
+                    e = pool.free.pop();
+                    if (!e) {{
+                        if (pool.size >= pool.maxSize) return null;
+                        e = this.createEntity(pool.template, true);
+                        e.pool = pool;
+                        pool.size++;
+                    }}
+                }} else {{
+                    const template = this.templates.get(templateId);
+                    if (!template) return null;
+                    e = this.createEntity(template, true);
+                }}
+                e.x = x; e.y = y; e.vx = vx; e.vy = vy; e.ttl = ttl; e.rotation = 0;
+                if (e.body) {{
+                    Matter.Body.setPosition(e.body, {{ x: x + e.body.spriteOffset.x, y: y + e.body.spriteOffset.y }});
+                    Matter.Body.setAngle(e.body, 0);
+                    Matter.Body.setVelocity(e.body, {{ x: vx * this.velocityScale, y: vy * this.velocityScale }});
+                    Matter.Composite.add(this.engine.world, e.body);
+                }}
+                this.entities.push(e);
+                return e;
+            }}
+
+            despawn(index) {{
+                const e = this.entities[index];
+                // Swap-remove keeps the entity list from reallocating every despawn
+                this.entities[index] = this.entities[this.entities.length - 1];
+                this.entities.pop();
+                if (e.body) Matter.Composite.remove(this.engine.world, e.body);
+                if (e.spawnedBy) {{ e.spawnedBy.spawner.alive--; e.spawnedBy = null; }}
+                if (e.pool) e.pool.free.push(e);
+            }}
+
+            loadEntities() {{
+                this.entities = [];
+                this.bodies.clear();
+                Matter.Composite.clear(this.engine.world);
+
+                // Pool and spawner templates are prototypes, not placed in the world
+                const templateIds = new Set(Object.values(this.spec.pooling || {{}}).map(p => p.template));
+                for (const es of this.spec.entities) {{
+                    if (es.components.spawner?.template) templateIds.add(es.components.spawner.template);
+                }}
+                this.templates = new Map();
+                for (const es of this.spec.entities) {{
+                    if (es.id && templateIds.has(es.id)) {{
+                        this.templates.set(es.id, es);
+                        continue;
+                    }}
+                    const e = this.createEntity(es, false);
+                    if (e.body) {{
+                        Matter.Composite.add(this.engine.world, e.body);
+                        this.bodies.set(es.name, e.body);
+                    }}
+                    this.entities.push(e);
+                }}
+                this.createPools();
+
+                Matter.Events.on(this.engine, 'collisionStart', (ev) => {{
+                    for (const p of ev.pairs) {{
+                        // One-way platforms only catch bodies falling onto them from above. The
+                        // disabled pair restarts next step, so this is re-checked every frame.
+                        const platform = p.bodyA.oneWay ? p.bodyA : p.bodyB.oneWay ? p.bodyB : null;
+                        if (platform) {{
+                            const other = platform === p.bodyA ? p.bodyB : p.bodyA;
+                            if (other.velocity.y < 0 || other.bounds.max.y - other.velocity.y > platform.bounds.min.y + 2) {{
+                                p.isActive = false;
+                                continue;
+                            }}
+                        }}
+                        const a = this.entities.find(e => e.name === p.bodyA.label);
+                        const b = this.entities.find(e => e.name === p.bodyB.label);
+                        if (a?.hasInput && (b?.tags?.includes('ground') || b?.tags?.includes('platform'))) a.isGrounded = true;
+                        if (b?.hasInput && (a?.tags?.includes('ground') || a?.tags?.includes('platform'))) b.isGrounded = true;
+                    }}
+                }});
+
+                Matter.Events.on(this.engine, 'collisionEnd', (ev) => {{
+                    for (const p of ev.pairs) {{
+                        const a = this.entities.find(e => e.name === p.bodyA.label);
+                        const b = this.entities.find(e => e.name === p.bodyB.label);
+                        if (a?.hasInput && (b?.tags?.includes('ground') || b?.tags?.includes('platform'))) a.isGrounded = false;
+                        if (b?.hasInput && (a?.tags?.includes('ground') || a?.tags?.includes('platform'))) b.isGrounded = false;
+                    }}
+                }});
+            }}
+
+            start() {{ this.isPlaying = true; this.lastTime = performance.now(); this.loop(); }}
+            pause() {{ this.isPlaying = false; }}
+            reset() {{ Math.random = mulberry32(gameSeed); this.resetGameState(); this.loadEntities(); }}
+
+            loop() {{
+                if (!this.isPlaying) return;
+                const now = performance.now();
+                const dt = Math.min((now - this.lastTime) / 1000, 0.1);
+                this.lastTime = now;
+                if (this.fixedStep) {{
+                    this.accumulator += dt;
+                    let steps = 0;
+                    while (this.accumulator >= 1 / 60 && steps < 5) {{
+                        this.update(1 / 60);
+                        this.accumulator -= 1 / 60;
+                        steps++;
+                    }}
+                    // Drop the backlog after a long stall instead of fast-forwarding
+                    if (steps === 5) this.accumulator = 0;
+                }} else {{
+                    this.update(dt);
+                }}
+                this.render();
+                requestAnimationFrame(() => this.loop());
+            }}
+
+            update(dt) {{
+                for (const e of this.entities) {{
+                    if (!e.hasInput) continue;
+                    // In a co-op session other players' entities follow the relay, not the keyboard
+                    if (this.localPlayer && e.player !== this.localPlayer) continue;
+                    const body = e.body;
+                    if (!body) continue;
+                    let vx = 0;
+                    if (this.keys.get('ArrowLeft') || this.keys.get('KeyA')) vx = -e.moveSpeed;
+                    if (this.keys.get('ArrowRight') || this.keys.get('KeyD')) vx = e.moveSpeed;
+                    Matter.Body.setVelocity(body, {{ x: vx * this.velocityScale, y: body.velocity.y }});
+                    if ((this.keys.get('Space') || this.keys.get('ArrowUp') || this.keys.get('KeyW')) && e.isGrounded) {{
+                        Matter.Body.setVelocity(body, {{ x: body.velocity.x, y: e.jumpForce * this.velocityScale }});
+                        e.isGrounded = false;
+                    }}
+                }}
+                // Iterate a snapshot length; spawns append to the list while we walk it
+                for (let i = 0, n = this.entities.length; i < n; i++) {{
+                    const e = this.entities[i];
+                    const sp = e.spawner;
+                    if (!sp || !sp.template || !(sp.interval > 0)) continue;
+                    sp.timer += dt;
+                    while (sp.timer >= sp.interval) {{
+                        sp.timer -= sp.interval;
+                        if (sp.maxAlive && sp.alive >= sp.maxAlive) continue;
+                        const s = this.spawn(sp.template, e.x, e.y, sp.vx || 0, sp.vy || 0, sp.lifetime || Infinity);
+                        if (s) {{ s.spawnedBy = e; sp.alive++; }}
+                    }}
+                }}
+                Matter.Engine.update(this.engine, dt * 1000);
+                const b = this.bounds;
+                for (let i = this.entities.length - 1; i >= 0; i--) {{
+                    const e = this.entities[i];
+                    const body = e.body;
+                    if (body) {{ e.x = body.position.x - body.spriteOffset.x; e.y = body.position.y - body.spriteOffset.y; e.rotation = body.angle; }}
+                    else if (e.spawnedBy) {{ e.x += e.vx * this.velocityScale * 60 * dt; e.y += e.vy * this.velocityScale * 60 * dt; }}
+                    if (!e.spawnedBy) continue;
+                    e.ttl -= dt;
+                    const outside = e.x < b.x - e.width || e.x > b.x + b.width + e.width || e.y < b.y - e.height || e.y > b.y + b.height + e.height;
+                    if (e.ttl <= 0 || outside) this.despawn(i);
+                }}
+            }}
+
+            render() {{
+                const ctx = this.ctx;
+                ctx.fillStyle = '#1a1a2e';
+                ctx.fillRect(0, 0, this.canvas.width, this.canvas.height);
+                // Follow the player, keeping the view inside the world bounds
+                const b = this.bounds, cw = this.canvas.width, ch = this.canvas.height;
+                const player = this.entities.find(e => e.hasInput && (!this.localPlayer || e.player === this.localPlayer));
+                const clamp = (v, lo, hi) => hi < lo ? lo : Math.max(lo, Math.min(hi, v));
+                const camX = clamp((player ? player.x : b.x) - cw / 2, b.x, b.x + b.width - cw);
+                const camY = clamp((player ? player.y : b.y) - ch / 2, b.y, b.y + b.height - ch);
+                this.camera = {{ x: camX, y: camY }};
+                for (const e of this.entities) {{
+                    ctx.save();
+                    ctx.translate(e.x - camX, e.y - camY);
+                    ctx.rotate(e.rotation);
+                    const img = this.images[this.assetAliases[e.texture] || e.texture];
+                    if (img && img.complete && img.naturalWidth) {{
+                        ctx.drawImage(img, -e.width/2, -e.height/2, e.width, e.height);
+                    }} else {{
+                        ctx.fillStyle = e.color;
+                        ctx.fillRect(-e.width/2, -e.height/2, e.width, e.height);
+                    }}
+                    ctx.restore();
+                }}
+                this.renderUI();
+            }}
+        }}
+
+        const canvas = document.getElementById('game-canvas');
+        const runtime = new GameRuntime(canvas, gameSpec);
+        let isPlaying = false;
+
+        document.getElementById('play-btn').addEventListener('click', () => {{
+            if (isPlaying) {{ runtime.pause(); document.getElementById('play-btn').textContent = 'Play'; }}
+            else {{ runtime.start(); document.getElementById('play-btn').textContent = 'Pause'; }}
+            isPlaying = !isPlaying;
+        }});
+
+        document.getElementById('reset-btn').addEventListener('click', () => {{
+            runtime.reset();
+            if (!isPlaying) runtime.render();
+        }});
+
+        runtime.render();{embed_script}
+    </script>
+</body>
+</html>"##,
+        title = title,
+        game_spec = game_spec_json,
+        physics_script = physics_script,
+        embed_style = embed_style,
+        embed_script = embed_script
+    )
+}
@@ -0,0 +1,194 @@
+use crate::export::ExportManifest;
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const MANIFEST_SUFFIX: &str = ".manifest.json";
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct VersionChange {
+    pub from: Option<String>,
+    pub to: Option<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AssetDelta {
+    pub path: String,
+    /// None when the asset is new in build B
+    pub size_before: Option<u64>,
+    /// None when the asset was dropped from build B
+    pub size_after: Option<u64>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportDiff {
+    pub manifest_a: String,
+    pub manifest_b: String,
+    pub engine: Option<VersionChange>,
+    pub runtime: Option<VersionChange>,
+    pub added_assets: Vec<AssetDelta>,
+    pub removed_assets: Vec<AssetDelta>,
+    /// Same path, different content
+    pub changed_assets: Vec<AssetDelta>,
+    /// Build B's page size minus build A's, in bytes
+    pub size_delta: i64,
+    pub spec_changed: bool,
+    /// Older manifests don't record assets or sizes; their diffs only cover versions and settings
+    pub partial: bool,
+    /// Markdown summary for publish notes
+    pub changelog: String,
+}
+
+/// The manifest for an export: accepts the exported file or its `.manifest.json`
+fn manifest_path(build: &str) -> PathBuf {
+    let path = Path::new(build);
+    if build.ends_with(MANIFEST_SUFFIX) {
+        path.to_path_buf()
+    } else {
+        ExportManifest::path_for(path)
+    }
+}
+
+fn load(build: &str) -> Result<(PathBuf, ExportManifest), String> {
+    let path = manifest_path(build);
+    let content = fs::read_to_string(&path)
+        .map_err(|e| format!("No export manifest at {}: {}", path.display(), e))?;
+    let manifest = serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse {}: {}", path.display(), e))?;
+    Ok((path, manifest))
+}
+
+fn version_change(from: Option<&str>, to: Option<&str>) -> Option<VersionChange> {
+    (from != to).then(|| VersionChange {
+        from: from.map(str::to_string),
+        to: to.map(str::to_string),
+    })
+}
+
+/// "1.2 MB", "340 KB" or "12 B"
+fn human_size(bytes: u64) -> String {
+    match bytes {
+        b if b >= 1024 * 1024 => format!("{:.1} MB", b as f64 / (1024.0 * 1024.0)),
+        b if b >= 1024 => format!("{:.0} KB", b as f64 / 1024.0),
+        b => format!("{} B", b),
+    }
+}
+
+fn signed_size(delta: i64) -> String {
+    let sign = if delta < 0 { "-" } else { "+" };
+    format!("{}{}", sign, human_size(delta.unsigned_abs()))
+}
+
+/// Compare two export manifests
+pub fn diff(a: &ExportManifest, b: &ExportManifest) -> ExportDiff {
+    let mut added_assets = Vec::new();
+    let mut removed_assets = Vec::new();
+    let mut changed_assets = Vec::new();
+    for (path, before) in &a.assets {
+        match b.assets.get(path) {
+            None => removed_assets.push(AssetDelta {
+                path: path.clone(),
+                size_before: Some(before.size),
+                size_after: None,
+            }),
+            Some(after) if after != before => changed_assets.push(AssetDelta {
+                path: path.clone(),
+                size_before: Some(before.size),
+                size_after: Some(after.size),
+            }),
+            Some(_) => {}
+        }
+    }
+    for (path, after) in &b.assets {
+        if !a.assets.contains_key(path) {
+            added_assets.push(AssetDelta {
+                path: path.clone(),
+                size_before: None,
+                size_after: Some(after.size),
+            });
+        }
+    }
+
+    let mut diff = ExportDiff {
+        manifest_a: String::new(),
+        manifest_b: String::new(),
+        engine: version_change(Some(&a.engine_version), Some(&b.engine_version)),
+        runtime: version_change(a.runtime_version.as_deref(), b.runtime_version.as_deref()),
+        added_assets,
+        removed_assets,
+        changed_assets,
+        size_delta: b.output_bytes as i64 - a.output_bytes as i64,
+        spec_changed: a.spec_hash != b.spec_hash,
+        partial: a.spec_hash.is_empty() || b.spec_hash.is_empty(),
+        changelog: String::new(),
+    };
+    diff.changelog = changelog(a, b, &diff);
+    diff
+}
+
+fn changelog(a: &ExportManifest, b: &ExportManifest, diff: &ExportDiff) -> String {
+    let mut lines = vec![format!("## {} changes", b.title)];
+    let version = |v: &Option<String>| v.clone().unwrap_or_else(|| "built-in".to_string());
+
+    if let Some(engine) = &diff.engine {
+        lines.push(format!("- Engine {} → {}", version(&engine.from), version(&engine.to)));
+    }
+    if let Some(runtime) = &diff.runtime {
+        lines.push(format!("- Runtime {} → {}", version(&runtime.from), version(&runtime.to)));
+    }
+    if a.title != b.title {
+        lines.push(format!("- Renamed from \"{}\"", a.title));
+    }
+    if diff.spec_changed {
+        let entities = b.entity_count as i64 - a.entity_count as i64;
+        lines.push(match entities {
+            0 => "- Game content changed".to_string(),
+            n => format!("- Game content changed ({:+} entities, {} total)", n, b.entity_count),
+        });
+    }
+    if a.seed != b.seed {
+        lines.push(format!("- Random seed {} → {}", a.seed, b.seed));
+    }
+    if a.difficulty != b.difficulty {
+        lines.push(format!("- Difficulty {} → {}", version(&a.difficulty), version(&b.difficulty)));
+    }
+    if a.debug != b.debug {
+        lines.push(if b.debug { "- Debug build" } else { "- Release build (debug tools removed)" }.to_string());
+    }
+    for asset in &diff.added_assets {
+        lines.push(format!("- Added {} ({})", asset.path, human_size(asset.size_after.unwrap_or(0))));
+    }
+    for asset in &diff.changed_assets {
+        let delta = asset.size_after.unwrap_or(0) as i64 - asset.size_before.unwrap_or(0) as i64;
+        lines.push(format!("- Updated {} ({})", asset.path, signed_size(delta)));
+    }
+    for asset in &diff.removed_assets {
+        lines.push(format!("- Removed {}", asset.path));
+    }
+    if !diff.partial && diff.size_delta != 0 {
+        lines.push(format!("- Build size {} ({})", human_size(b.output_bytes), signed_size(diff.size_delta)));
+    }
+    if lines.len() == 1 {
+        lines.push("- No changes".to_string());
+    }
+    if diff.partial {
+        lines.push(String::new());
+        lines.push("_One of the builds predates asset tracking; asset and size changes aren't listed._".to_string());
+    }
+    lines.join("\n")
+}
+
+/// Compare two exports by their manifests: version bumps, asset changes and size, plus a
+/// changelog. Each build is an exported file or its `.manifest.json`.
+#[tauri::command]
+pub async fn diff_exports(build_a: String, build_b: String) -> Result<ExportDiff, String> {
+    let (path_a, a) = load(&build_a)?;
+    let (path_b, b) = load(&build_b)?;
+    let mut diff = diff(&a, &b);
+    diff.manifest_a = path_a.to_string_lossy().to_string();
+    diff.manifest_b = path_b.to_string_lossy().to_string();
+    Ok(diff)
+}
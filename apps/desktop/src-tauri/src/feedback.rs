@@ -0,0 +1,157 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+const FEEDBACK_FILE: &str = ".promptplay/feedback.json";
+/// Keeps the AI prompt short on projects with long-running playtests
+const MAX_PROMPT_NOTES: usize = 20;
+
+/// A playtester note pinned to a position in a scene
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FeedbackNote {
+    pub id: String,
+    /// Scene name; None for the top-level entities
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scene: Option<String>,
+    pub x: f64,
+    pub y: f64,
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    pub created_at: u64,
+    #[serde(default)]
+    pub resolved: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resolved_at: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resolution: Option<String>,
+}
+
+fn feedback_path(project_path: &str) -> PathBuf {
+    PathBuf::from(project_path).join(FEEDBACK_FILE)
+}
+
+fn now_millis() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+fn load(project_path: &str) -> Result<Vec<FeedbackNote>, String> {
+    let path = feedback_path(project_path);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = fs::read_to_string(&path).map_err(|e| format!("Failed to read feedback: {}", e))?;
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse feedback: {}", e))
+}
+
+fn save(project_path: &str, notes: &[FeedbackNote]) -> Result<(), String> {
+    let path = feedback_path(project_path);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create feedback directory: {}", e))?;
+    }
+    let content = serde_json::to_string_pretty(notes).map_err(|e| format!("Failed to serialize feedback: {}", e))?;
+    fs::write(&path, content).map_err(|e| format!("Failed to write feedback: {}", e))
+}
+
+/// Open notes formatted for the AI system prompt; empty when there are none
+pub fn describe_open_for_prompt(project_path: &str) -> String {
+    let notes = load(project_path).unwrap_or_default();
+    let open: Vec<&FeedbackNote> = notes.iter().filter(|note| !note.resolved).collect();
+    // Newest first, so the cap drops the oldest notes
+    open.iter()
+        .rev()
+        .take(MAX_PROMPT_NOTES)
+        .map(|note| {
+            format!(
+                "- [{}] {} at ({:.0}, {:.0}): {}",
+                note.id,
+                note.scene.as_deref().unwrap_or("main"),
+                note.x,
+                note.y,
+                note.message
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// File a playtester note; called by the share server's feedback endpoint and the editor
+#[tauri::command]
+pub async fn add_feedback(
+    project_path: String,
+    scene: Option<String>,
+    x: f64,
+    y: f64,
+    message: String,
+    author: Option<String>,
+) -> Result<FeedbackNote, String> {
+    let message = message.trim().to_string();
+    if message.is_empty() {
+        return Err("Feedback message is empty".to_string());
+    }
+    if !x.is_finite() || !y.is_finite() {
+        return Err("Feedback position must be a finite number".to_string());
+    }
+    let mut notes = load(&project_path)?;
+    let note = FeedbackNote {
+        id: uuid::Uuid::new_v4().to_string(),
+        scene,
+        x,
+        y,
+        message,
+        author: author.filter(|a| !a.trim().is_empty()),
+        created_at: now_millis(),
+        resolved: false,
+        resolved_at: None,
+        resolution: None,
+    };
+    notes.push(note.clone());
+    save(&project_path, &notes)?;
+    Ok(note)
+}
+
+/// Notes in filing order; resolved ones only when `include_resolved` is set
+#[tauri::command]
+pub async fn list_feedback(
+    project_path: String,
+    scene: Option<String>,
+    include_resolved: Option<bool>,
+) -> Result<Vec<FeedbackNote>, String> {
+    let include_resolved = include_resolved.unwrap_or(false);
+    Ok(load(&project_path)?
+        .into_iter()
+        .filter(|note| include_resolved || !note.resolved)
+        .filter(|note| scene.is_none() || note.scene == scene)
+        .collect())
+}
+
+/// Mark a note resolved, or reopen it with `resolved: false`
+#[tauri::command]
+pub async fn resolve_feedback(
+    project_path: String,
+    id: String,
+    resolution: Option<String>,
+    resolved: Option<bool>,
+) -> Result<FeedbackNote, String> {
+    let mut notes = load(&project_path)?;
+    let note = notes
+        .iter_mut()
+        .find(|note| note.id == id)
+        .ok_or_else(|| format!("Feedback {} not found", id))?;
+    if resolved.unwrap_or(true) {
+        note.resolved = true;
+        note.resolved_at = Some(now_millis());
+        note.resolution = resolution;
+    } else {
+        note.resolved = false;
+        note.resolved_at = None;
+        note.resolution = None;
+    }
+    let note = note.clone();
+    save(&project_path, &notes)?;
+    Ok(note)
+}
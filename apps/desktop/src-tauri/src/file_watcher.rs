@@ -1,8 +1,17 @@
 use notify::{Config, Event, RecommendedWatcher, RecursiveMode, Watcher};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::mpsc::channel;
 use std::time::Duration;
-use tauri::{AppHandle, Emitter};
+use crate::assets::AssetManifest;
+use crate::components::CUSTOM_COMPONENTS_FILE;
+use crate::symlinks::SymlinkPolicy;
+use crate::notifications::{self, NotificationCategory};
+use crate::spec_changes::{self, SPEC_CHANGED_EVENT};
+use crate::{minimap, paths, spec};
+use crate::search_index::{self, SearchIndexState};
+use crate::tray::TrayState;
+use crate::workspace;
+use tauri::{AppHandle, Manager};
 
 pub struct FileWatcherState {
     pub watcher: Option<RecommendedWatcher>,
@@ -26,29 +35,64 @@ pub fn start_watching(
     let (tx, rx) = channel();
 
     let app_handle_clone = app_handle.clone();
+    let watched_root = path.clone();
+    let follow_symlinks = AssetManifest::load(&path.to_string_lossy())
+        .map(|m| m.symlinks == SymlinkPolicy::Follow)
+        .unwrap_or(true);
 
     // Create watcher with debounce
+    let error_app = app_handle.clone();
+    let mut reported_error = false;
     let mut watcher = RecommendedWatcher::new(
-        move |res: Result<Event, notify::Error>| {
-            if let Ok(event) = res {
+        move |res: Result<Event, notify::Error>| match res {
+            Ok(event) => {
                 // Send event to channel
                 let _ = tx.send(event);
             }
+            // One notification per watcher; the errors tend to repeat until it is restarted
+            Err(e) if !reported_error => {
+                reported_error = true;
+                notifications::task_finished(
+                    &error_app,
+                    NotificationCategory::Watcher,
+                    "File watching stopped",
+                    &format!("Changes on disk may not reload: {}", e),
+                );
+            }
+            Err(_) => {}
         },
         Config::default()
             .with_poll_interval(Duration::from_millis(500))
-            .with_compare_contents(false),
+            .with_compare_contents(false)
+            .with_follow_symlinks(follow_symlinks),
     )
     .map_err(|e| format!("Failed to create watcher: {}", e))?;
 
     // Start watching the path
     watcher
-        .watch(&path, RecursiveMode::Recursive)
+        .watch(&paths::for_io(&path), RecursiveMode::Recursive)
         .map_err(|e| format!("Failed to watch path: {}", e))?;
 
     // Spawn a thread to handle events
     std::thread::spawn(move || {
+        // Last spec seen on disk, diffed against each game.json write for granular change events
+        let mut last_spec = spec::load_project_spec(&watched_root.to_string_lossy()).ok();
+
         while let Ok(event) = rx.recv() {
+            // Keep the search index in sync, including deletions
+            if matches!(
+                event.kind,
+                notify::EventKind::Modify(_)
+                    | notify::EventKind::Create(_)
+                    | notify::EventKind::Remove(_)
+            ) {
+                let index_state = app_handle_clone.state::<SearchIndexState>();
+                for changed in &event.paths {
+                    search_index::notify_changed(&index_state, changed);
+                    minimap::notify_changed(&watched_root, changed);
+                }
+            }
+
             // Filter out non-modify events
             if !matches!(
                 event.kind,
@@ -59,10 +103,15 @@ pub fn start_watching(
 
             // Get the changed file path
             if let Some(path) = event.paths.first() {
-                let path_str = path.to_string_lossy().to_string();
+                let path_str = paths::to_display(path);
+                let hidden = paths::relative_to(path, &watched_root).is_some_and(|relative| {
+                    relative
+                        .components()
+                        .any(|c| c.as_os_str().to_string_lossy().starts_with('.'))
+                });
 
                 // Ignore hidden files, temp files, and directories
-                if path_str.contains("/.")
+                if hidden
                     || path_str.ends_with('~')
                     || path_str.ends_with(".tmp")
                     || path.is_dir()
@@ -70,8 +119,33 @@ pub fn start_watching(
                     continue;
                 }
 
+                // Paused from the tray: the index stays current but the editor isn't reloaded
+                if app_handle_clone.state::<TrayState>().watching_paused() {
+                    continue;
+                }
+
+                if paths::relative_to(path, &watched_root).is_some_and(|r| r == Path::new("game.json")) {
+                    if let Ok(current) = spec::load_project_spec(&watched_root.to_string_lossy()) {
+                        let changes = match &last_spec {
+                            Some(previous) => spec_changes::diff(previous, &current),
+                            None => spec_changes::SpecChangeSet {
+                                full_reload: true,
+                                ..Default::default()
+                            },
+                        };
+                        if !changes.is_empty() {
+                            workspace::emit_to_project(&app_handle_clone, &watched_root, SPEC_CHANGED_EVENT, changes);
+                        }
+                        last_spec = Some(current);
+                    }
+                }
+
+                if path.file_name().is_some_and(|n| n == CUSTOM_COMPONENTS_FILE) {
+                    workspace::emit_to_project(&app_handle_clone, &watched_root, "component-registry-changed", &path_str);
+                }
+
                 // Emit event to frontend
-                let _ = app_handle_clone.emit("file-changed", path_str);
+                workspace::emit_to_project(&app_handle_clone, &watched_root, "file-changed", path_str);
             }
         }
     });
@@ -0,0 +1,225 @@
+use crate::{encryption, json_guard};
+use crate::rounding::RoundingPolicy;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::path::PathBuf;
+
+/// Project file holding the spec formatting style
+pub const FORMAT_CONFIG_FILE: &str = "spec-format.json";
+
+/// Most decimal places `coordinatePrecision` may ask for; f64 holds about 15 significant digits
+pub const MAX_COORDINATE_PRECISION: u32 = 15;
+
+/// Components whose x/y/z fields count as coordinates
+const COORDINATE_COMPONENTS: &[&str] = &["transform", "transform3d"];
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum KeyOrder {
+    /// Well-known keys first (id, name, components, transform x/y...), the rest alphabetical
+    #[default]
+    Canonical,
+    Alphabetical,
+}
+
+/// On-disk encoding of game.json; readers accept either regardless of the setting
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum SpecStorage {
+    #[default]
+    Json,
+    /// zstd-compressed JSON, for huge generated levels; smaller and faster to load, but not diffable
+    Zstd,
+}
+
+/// How game.json is written; shared by the UI, the AI and backend edits so diffs stay clean
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase", default)]
+pub struct FormatConfig {
+    pub indent: usize,
+    pub key_order: KeyOrder,
+    /// Decimal places kept for entity coordinates; None leaves values untouched
+    pub coordinate_precision: Option<u32>,
+    /// Snapping applied to coordinates and sizes before saving
+    pub rounding: RoundingPolicy,
+    pub trailing_newline: bool,
+    pub storage: SpecStorage,
+}
+
+impl Default for FormatConfig {
+    fn default() -> Self {
+        Self {
+            indent: 2,
+            key_order: KeyOrder::Canonical,
+            coordinate_precision: None,
+            rounding: RoundingPolicy::None,
+            trailing_newline: true,
+            storage: SpecStorage::Json,
+        }
+    }
+}
+
+impl FormatConfig {
+    /// The project's spec-format.json, or the defaults when it doesn't exist
+    pub fn load(project_path: &str) -> Result<Self, String> {
+        let path = PathBuf::from(project_path).join(FORMAT_CONFIG_FILE);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = encryption::read_to_string(&path)?;
+        let config: Self = serde_json::from_str(&content)
+            .map_err(|e| format!("Failed to parse {}: {}", FORMAT_CONFIG_FILE, e))?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        match self.coordinate_precision {
+            Some(places) if places > MAX_COORDINATE_PRECISION => Err(format!(
+                "{}: coordinatePrecision is {}, but at most {} decimal places are supported",
+                FORMAT_CONFIG_FILE, places, MAX_COORDINATE_PRECISION
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    pub fn save(&self, project_path: &str) -> Result<(), String> {
+        self.validate()?;
+        let path = PathBuf::from(project_path).join(FORMAT_CONFIG_FILE);
+        let content = serde_json::to_string_pretty(self)
+            .map_err(|e| format!("Failed to serialize {}: {}", FORMAT_CONFIG_FILE, e))?;
+        encryption::write(&path, content.as_bytes())
+    }
+}
+
+/// Preferred leading keys for an object, chosen by the key it sits under
+fn priority_keys(parent: Option<&str>) -> &'static [&'static str] {
+    match parent {
+        None => &[
+            "version",
+            "metadata",
+            "config",
+            "activeDifficulty",
+            "difficulty",
+            "levels",
+            "ui",
+            "entities",
+            "scenes",
+        ],
+        Some("entities") => &["id", "name", "tags", "components"],
+        Some("components") => &["transform", "sprite", "velocity", "collider", "input"],
+        Some("transform") | Some("transform3d") => &["x", "y", "z", "rotation", "scaleX", "scaleY"],
+        Some("metadata") => &["title", "genre", "description"],
+        _ => &["id", "name", "type"],
+    }
+}
+
+fn ordered_keys<'a>(object: &'a Map<String, Value>, parent: Option<&str>, order: KeyOrder) -> Vec<&'a String> {
+    // Map is sorted, so alphabetical order is the iteration order
+    let mut keys: Vec<&String> = object.keys().collect();
+    if order == KeyOrder::Canonical {
+        let priority = priority_keys(parent);
+        keys.sort_by_key(|k| priority.iter().position(|p| p == k).unwrap_or(priority.len()));
+    }
+    keys
+}
+
+/// Print numbers the way JSON.stringify does, so UI and backend saves agree
+fn write_number(out: &mut String, value: &Value, precision: Option<u32>) {
+    let Some(f) = value.as_f64().filter(|_| value.is_f64()) else {
+        out.push_str(&value.to_string());
+        return;
+    };
+    let f = match precision {
+        Some(places) => {
+            let factor = 10f64.powi(places.min(MAX_COORDINATE_PRECISION) as i32);
+            // Huge values overflow when scaled; they have no decimals to drop anyway
+            Some((f * factor).round() / factor).filter(|rounded| rounded.is_finite()).unwrap_or(f)
+        }
+        None => f,
+    };
+    if f.fract() == 0.0 && f.abs() < 1e15 {
+        out.push_str(&format!("{}", f as i64));
+    } else {
+        out.push_str(&format!("{}", f));
+    }
+}
+
+fn write_value(out: &mut String, value: &Value, parent: Option<&str>, depth: usize, config: &FormatConfig) {
+    let pad = |depth: usize| " ".repeat(depth * config.indent);
+
+    match value {
+        Value::Object(object) if object.is_empty() => out.push_str("{}"),
+        Value::Object(object) => {
+            out.push_str("{\n");
+            let keys = ordered_keys(object, parent, config.key_order);
+            let coordinates = parent.is_some_and(|p| COORDINATE_COMPONENTS.contains(&p));
+            for (i, key) in keys.iter().enumerate() {
+                out.push_str(&pad(depth + 1));
+                out.push_str(&Value::from(key.as_str()).to_string());
+                out.push_str(": ");
+                let child = &object[key.as_str()];
+                if coordinates && matches!(key.as_str(), "x" | "y" | "z") && child.is_number() {
+                    write_number(out, child, config.coordinate_precision);
+                } else {
+                    write_value(out, child, Some(key), depth + 1, config);
+                }
+                out.push_str(if i + 1 < keys.len() { ",\n" } else { "\n" });
+            }
+            out.push_str(&pad(depth));
+            out.push('}');
+        }
+        Value::Array(items) if items.is_empty() => out.push_str("[]"),
+        Value::Array(items) => {
+            out.push_str("[\n");
+            for (i, item) in items.iter().enumerate() {
+                out.push_str(&pad(depth + 1));
+                // Array elements inherit the array's key so entity lists get entity ordering
+                write_value(out, item, parent, depth + 1, config);
+                out.push_str(if i + 1 < items.len() { ",\n" } else { "\n" });
+            }
+            out.push_str(&pad(depth));
+            out.push(']');
+        }
+        Value::Number(_) => write_number(out, value, None),
+        other => out.push_str(&other.to_string()),
+    }
+}
+
+/// Serialize a spec with the given style
+pub fn format_spec(spec: &Value, config: &FormatConfig) -> String {
+    let mut out = String::new();
+    write_value(&mut out, spec, None, 0, config);
+    if config.trailing_newline {
+        out.push('\n');
+    }
+    out
+}
+
+/// Format a game spec JSON string with the project's style (or an explicit one)
+#[tauri::command]
+pub async fn format_game_spec(
+    game_spec_json: String,
+    project_path: Option<String>,
+    config: Option<FormatConfig>,
+) -> Result<String, String> {
+    let spec: Value = json_guard::parse(game_spec_json.as_bytes(), "game spec", &json_guard::SPEC_LIMITS)?;
+    let config = match (config, project_path) {
+        (Some(config), _) => config,
+        (None, Some(path)) => FormatConfig::load(&path)?,
+        (None, None) => FormatConfig::default(),
+    };
+    Ok(format_spec(&spec, &config))
+}
+
+/// Get a project's spec formatting style
+#[tauri::command]
+pub async fn get_format_config(project_path: String) -> Result<FormatConfig, String> {
+    FormatConfig::load(&project_path)
+}
+
+/// Set a project's spec formatting style (written to spec-format.json)
+#[tauri::command]
+pub async fn set_format_config(project_path: String, config: FormatConfig) -> Result<(), String> {
+    config.save(&project_path)
+}
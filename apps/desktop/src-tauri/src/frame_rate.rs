@@ -0,0 +1,196 @@
+use crate::history::{self, HistoryEntry};
+use crate::simulator::{InputEvent, Simulation};
+use crate::spec;
+use crate::validation::Diagnostic;
+use serde::Serialize;
+use serde_json::Value;
+
+/// Speed fields in px/s, as (component, field)
+const SPEED_FIELDS: &[(&str, &str)] = &[
+    ("velocity", "vx"),
+    ("velocity", "vy"),
+    ("input", "moveSpeed"),
+    ("input", "jumpForce"),
+    ("aiBehavior", "speed"),
+    ("spawner", "vx"),
+    ("spawner", "vy"),
+];
+/// Speeds below this many px/s read as pixels per frame; even a slow walk is 30+ px/s
+const PER_FRAME_LIMIT: f64 = 20.0;
+const REFERENCE_FPS: f64 = 60.0;
+/// The legacy runtime sets Matter velocities to speed * 0.01 px per 60 Hz step, i.e. 0.6x of
+/// the px/s the spec and simulator use; delta-time exports move at the true px/s
+const LEGACY_VELOCITY_SCALE: f64 = 0.01 * REFERENCE_FPS;
+/// Frame rates the simulation audit compares against 60 fps
+const AUDIT_FPS: &[f64] = &[30.0, 144.0];
+const DEFAULT_AUDIT_SECONDS: f64 = 5.0;
+/// Positions may differ this much between rates before an entity counts as drifting
+const DRIFT_TOLERANCE: f64 = 4.0;
+
+#[derive(Debug, Serialize)]
+pub struct EntityDrift {
+    pub entity: String,
+    pub fps: f64,
+    /// Distance from the 60 fps position at the end of the run, in px
+    pub distance: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FrameRateAudit {
+    /// Speed values that look tuned per frame
+    pub findings: Vec<Diagnostic>,
+    /// Entities that end up in different places when simulated at other frame rates
+    pub drift: Vec<EntityDrift>,
+    /// Whether the spec already uses delta-time movement (`config.deltaTime`)
+    pub delta_time: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RescaledField {
+    pub entity: String,
+    /// "component.field"
+    pub field: String,
+    pub from: f64,
+    pub to: f64,
+    /// Rescaled from px per frame rather than from legacy-runtime tuning
+    pub per_frame: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DeltaTimeMigration {
+    pub rescaled: Vec<RescaledField>,
+    pub history: HistoryEntry,
+    /// Updated game.json for the editor
+    pub game_spec_json: String,
+}
+
+fn looks_per_frame(value: f64) -> bool {
+    value != 0.0 && value.abs() < PER_FRAME_LIMIT
+}
+
+/// Warn about speeds that only make sense as pixels per 60 fps frame
+pub fn validate_frame_rate(game_spec: &Value) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    for entity in spec::all_entities(game_spec) {
+        let name = entity.get("name").and_then(Value::as_str).unwrap_or("<unnamed>");
+        for (component, field) in SPEED_FIELDS {
+            let Some(value) = entity.pointer(&format!("/components/{}/{}", component, field)).and_then(Value::as_f64) else {
+                continue;
+            };
+            if looks_per_frame(value) {
+                diagnostics.push(Diagnostic::warning(
+                    "/entities",
+                    format!(
+                        "'{}' {}.{} is {}, which looks like px per frame ({} px/s at 60 fps, {} at 30); speeds are px/s",
+                        name,
+                        component,
+                        field,
+                        value,
+                        value * REFERENCE_FPS,
+                        value * 30.0
+                    ),
+                ));
+            }
+        }
+    }
+    diagnostics
+}
+
+/// Per-frame speed findings plus a headless run at 30, 60 and 144 fps comparing where each
+/// entity ends up
+#[tauri::command]
+pub async fn audit_frame_rate(
+    project_path: String,
+    seconds: Option<f64>,
+    inputs: Option<Vec<InputEvent>>,
+    scene: Option<String>,
+) -> Result<FrameRateAudit, String> {
+    let game_spec = spec::load_project_spec(&project_path)?;
+    let seconds = seconds.unwrap_or(DEFAULT_AUDIT_SECONDS).clamp(0.1, 600.0);
+    let inputs = inputs.unwrap_or_default();
+    let positions = |fps: f64| -> Vec<(String, f64, f64)> {
+        let mut simulation = Simulation::new(&game_spec, scene.as_deref()).with_frame_rate(fps);
+        simulation.run(seconds, &inputs);
+        spec::all_entities(&game_spec)
+            .filter_map(|e| e.get("name").and_then(Value::as_str))
+            .filter_map(|name| {
+                let x = simulation.read(&format!("entity.{}.x", name)).ok()?.as_f64()?;
+                let y = simulation.read(&format!("entity.{}.y", name)).ok()?.as_f64()?;
+                Some((name.to_string(), x, y))
+            })
+            .collect()
+    };
+
+    let reference = positions(REFERENCE_FPS);
+    let mut drift = Vec::new();
+    for &fps in AUDIT_FPS {
+        for ((entity, x, y), (_, rx, ry)) in positions(fps).into_iter().zip(&reference) {
+            let distance = ((x - rx).powi(2) + (y - ry).powi(2)).sqrt();
+            if distance > DRIFT_TOLERANCE {
+                drift.push(EntityDrift {
+                    entity,
+                    fps,
+                    distance: (distance * 10.0).round() / 10.0,
+                });
+            }
+        }
+    }
+
+    Ok(FrameRateAudit {
+        findings: validate_frame_rate(&game_spec),
+        drift,
+        delta_time: game_spec.pointer("/config/deltaTime").and_then(Value::as_bool).unwrap_or(false),
+    })
+}
+
+/// Switch a spec to delta-time movement: speeds that look per frame become px/s (x60) and
+/// the rest are scaled to keep the feel they had under the legacy runtime. Sets
+/// `config.deltaTime`, which exports then honor.
+#[tauri::command]
+pub async fn migrate_to_delta_time(project_path: String) -> Result<DeltaTimeMigration, String> {
+    let before = spec::load_project_spec(&project_path)?;
+    if before.pointer("/config/deltaTime").and_then(Value::as_bool) == Some(true) {
+        return Err("This spec already uses delta-time movement".to_string());
+    }
+    let mut game_spec = before.clone();
+
+    let mut rescaled = Vec::new();
+    spec::for_each_entity_mut(&mut game_spec, |entity| {
+        let name = entity.get("name").and_then(Value::as_str).unwrap_or("<unnamed>").to_string();
+        for (component, field) in SPEED_FIELDS {
+            let Some(slot) = entity.pointer_mut(&format!("/components/{}/{}", component, field)) else {
+                continue;
+            };
+            let Some(from) = slot.as_f64().filter(|v| *v != 0.0) else {
+                continue;
+            };
+            let per_frame = looks_per_frame(from);
+            let scale = if per_frame { REFERENCE_FPS } else { LEGACY_VELOCITY_SCALE };
+            let to = (from * scale * 100.0).round() / 100.0;
+            *slot = Value::from(to);
+            rescaled.push(RescaledField {
+                entity: name.clone(),
+                field: format!("{}.{}", component, field),
+                from,
+                to,
+                per_frame,
+            });
+        }
+    });
+
+    let config = game_spec
+        .get_mut("config")
+        .and_then(Value::as_object_mut)
+        .ok_or("Game spec has no config object")?;
+    config.insert("deltaTime".to_string(), Value::Bool(true));
+
+    let entry = history::record(&project_path, "Migrate to delta-time movement", &before)?;
+    spec::save_project_spec(&project_path, &game_spec)?;
+
+    Ok(DeltaTimeMigration {
+        rescaled,
+        history: entry,
+        game_spec_json: serde_json::to_string_pretty(&game_spec)
+            .map_err(|e| format!("Failed to serialize game spec: {}", e))?,
+    })
+}
@@ -0,0 +1,282 @@
+use crate::spec;
+use crate::validation::Diagnostic;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, HashSet};
+
+/// Typed view of game.json. Sections other modules own (ui, levels, pooling, dialogues...) stay
+/// raw JSON in `rest`, so a spec round-trips through these structs without losing anything.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct GameSpec {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub config: Option<GameConfig>,
+    #[serde(default)]
+    pub entities: Vec<Entity>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub scenes: Vec<Scene>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub active_scene: Option<String>,
+    #[serde(flatten)]
+    pub rest: Map<String, Value>,
+}
+
+impl GameSpec {
+    pub fn from_value(value: &Value) -> Result<Self, String> {
+        serde_json::from_value(value.clone()).map_err(|e| format!("Invalid game spec: {}", e))
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub struct Size {
+    pub width: f64,
+    pub height: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub struct Vec2 {
+    pub x: f64,
+    pub y: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct GameConfig {
+    /// Visible viewport; 800x600 when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub canvas: Option<Size>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gravity: Option<Vec2>,
+    #[serde(flatten)]
+    pub rest: Map<String, Value>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Scene {
+    pub id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub entities: Vec<Entity>,
+    #[serde(flatten)]
+    pub rest: Map<String, Value>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Entity {
+    /// Stable id; older specs may not have one yet
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    pub name: String,
+    pub components: Components,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    #[serde(flatten)]
+    pub rest: Map<String, Value>,
+}
+
+/// The components the physics and rendering core read, typed; the rest by name
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct Components {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transform: Option<Transform>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub velocity: Option<Velocity>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sprite: Option<Sprite>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub collider: Option<Collider>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub input: Option<Input>,
+    #[serde(flatten)]
+    pub other: BTreeMap<String, Value>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Transform {
+    pub x: f64,
+    pub y: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rotation: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scale_x: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scale_y: Option<f64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Velocity {
+    pub vx: f64,
+    pub vy: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Sprite {
+    /// Asset path or bare texture name; empty draws a tinted box
+    #[serde(default)]
+    pub texture: String,
+    pub width: f64,
+    pub height: f64,
+    /// "#rrggbb" or a 0xRRGGBB number
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tint: Option<Value>,
+    #[serde(flatten)]
+    pub rest: Map<String, Value>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Collider {
+    /// box, circle, polygon or slope
+    #[serde(rename = "type")]
+    pub kind: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub width: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub height: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub radius: Option<f64>,
+    #[serde(flatten)]
+    pub rest: Map<String, Value>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Input {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub move_speed: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub jump_force: Option<f64>,
+    /// Co-op seat; 1 when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub player: Option<u32>,
+    #[serde(flatten)]
+    pub rest: Map<String, Value>,
+}
+
+/// Example value for each typed component, offered as the fix when one is missing or malformed
+const COMPONENT_EXAMPLES: &[(&str, &str)] = &[
+    ("transform", r#"{"x": 0, "y": 0, "rotation": 0, "scaleX": 1, "scaleY": 1}"#),
+    ("velocity", r#"{"vx": 0, "vy": 0}"#),
+    ("sprite", r##"{"texture": "", "width": 32, "height": 32, "tint": "#ffffff"}"##),
+    ("collider", r#"{"type": "box", "width": 32, "height": 32}"#),
+    ("input", r#"{"moveSpeed": 200, "jumpForce": 400}"#),
+];
+
+fn example(component: &str) -> &'static str {
+    COMPONENT_EXAMPLES
+        .iter()
+        .find(|(name, _)| *name == component)
+        .map_or("{}", |(_, example)| example)
+}
+
+/// Deserialize one component to see whether it has the shape the runtime expects
+fn check_typed<T: DeserializeOwned>(name: &str, value: &Value, path: &str) -> Option<Diagnostic> {
+    let error = serde_json::from_value::<T>(value.clone()).err()?;
+    Some(
+        Diagnostic::error(path, format!("Invalid {} component: {}", name, error))
+            .with_fix(format!("Use the shape \"{}\": {}", name, example(name))),
+    )
+}
+
+/// Ids of entities that spawners and pools copy; they get a position when spawned
+fn template_ids(spec: &Value) -> HashSet<&str> {
+    let mut ids: HashSet<&str> = spec::all_entities(spec)
+        .filter_map(|e| e.pointer("/components/spawner/template").and_then(Value::as_str))
+        .collect();
+    ids.extend(
+        spec.get("pooling")
+            .and_then(Value::as_object)
+            .into_iter()
+            .flat_map(|pools| pools.values())
+            .filter_map(|pool| pool.get("template").and_then(Value::as_str)),
+    );
+    ids
+}
+
+fn check_entity(entity: &Value, path: &str, templates: &HashSet<&str>) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    let Some(components) = entity.get("components").and_then(Value::as_object) else {
+        return diagnostics;
+    };
+    let name = entity.get("name").and_then(Value::as_str).unwrap_or("(unnamed)");
+
+    for (component, value) in components {
+        let component_path = format!("{}/components/{}", path, component);
+        diagnostics.extend(match component.as_str() {
+            "transform" => check_typed::<Transform>(component, value, &component_path),
+            "velocity" => check_typed::<Velocity>(component, value, &component_path),
+            "sprite" => check_typed::<Sprite>(component, value, &component_path),
+            "collider" => check_typed::<Collider>(component, value, &component_path),
+            "input" => check_typed::<Input>(component, value, &component_path),
+            _ => None,
+        });
+    }
+
+    let has = |component: &str| components.contains_key(component);
+    let template = entity.get("id").and_then(Value::as_str).is_some_and(|id| templates.contains(id));
+    if !has("transform") && !template {
+        diagnostics.push(
+            Diagnostic::error(
+                format!("{}/components", path),
+                format!("Entity '{}' has no transform, so it has no position", name),
+            )
+            .with_fix(format!("Add \"transform\": {}", example("transform"))),
+        );
+    }
+    if has("velocity") && !has("collider") {
+        diagnostics.push(
+            Diagnostic::warning(
+                format!("{}/components", path),
+                format!("Entity '{}' moves but has no collider, so it passes through everything", name),
+            )
+            .with_fix(format!("Add \"collider\": {} sized like the sprite", example("collider"))),
+        );
+    }
+    if has("input") && !has("velocity") {
+        diagnostics.push(
+            Diagnostic::warning(
+                format!("{}/components", path),
+                format!("Entity '{}' takes input but has no velocity, so it can't move", name),
+            )
+            .with_fix(format!("Add \"velocity\": {}", example("velocity"))),
+        );
+    }
+    diagnostics
+}
+
+/// Check every entity, top-level and in scenes, against the typed component shapes and the
+/// components the runtime needs together
+pub fn validate_entities(spec: &Value) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    let templates = template_ids(spec);
+    let mut lists: Vec<(String, &Vec<Value>)> = Vec::new();
+    if let Some(entities) = spec.get("entities").and_then(Value::as_array) {
+        lists.push(("/entities".to_string(), entities));
+    }
+    for (i, scene) in spec.get("scenes").and_then(Value::as_array).into_iter().flatten().enumerate() {
+        if let Some(entities) = scene.get("entities").and_then(Value::as_array) {
+            lists.push((format!("/scenes/{}/entities", i), entities));
+        }
+    }
+    for (prefix, entities) in lists {
+        for (i, entity) in entities.iter().enumerate() {
+            diagnostics.extend(check_entity(entity, &format!("{}/{}", prefix, i), &templates));
+        }
+    }
+    diagnostics
+}
+
+/// Load a project's spec through the typed model, failing up front with the first shape problem
+/// instead of handing the frontend JSON the renderer can't use. `validate_game_spec` lists every
+/// problem; this is for callers that just need a spec they can trust.
+#[tauri::command]
+pub async fn load_typed_game_spec(project_path: String) -> Result<GameSpec, String> {
+    GameSpec::from_value(&spec::load_project_spec(&project_path)?)
+}
@@ -0,0 +1,268 @@
+use crate::credentials;
+use crate::notifications::{self, NotificationCategory};
+use crate::tools::{self, ToolId};
+use base64::Engine;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+
+const GITHUB_API: &str = "https://api.github.com";
+/// Keychain account holding the GitHub token
+const GITHUB_TOKEN_ACCOUNT: &str = "github-token";
+const DEFAULT_BRANCH: &str = "main";
+/// Generated per-machine state that never belongs in a repository
+const GITIGNORE_ENTRIES: &[&str] = &[".promptplay/", "node_modules/"];
+
+#[derive(Debug, Deserialize)]
+struct GitHubUser {
+    id: u64,
+    login: String,
+    name: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GitHubRepo {
+    html_url: String,
+    clone_url: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct GitHubPublishResult {
+    pub html_url: String,
+    pub clone_url: String,
+    pub branch: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct GitResult {
+    pub output: String,
+}
+
+/// Run git in the project through the tool allowlist, so the project's own git config can't
+/// run commands. The token, when given, is passed as an auth header through environment config
+/// so it never lands in the remote URL, argv or .git/config.
+fn git(project: &Path, args: &[&str], token: Option<&str>) -> Result<String, String> {
+    git_with_env(project, args, token, Vec::new())
+}
+
+fn git_with_env(
+    project: &Path,
+    args: &[&str],
+    token: Option<&str>,
+    mut env: Vec<(&str, String)>,
+) -> Result<String, String> {
+    if let Some(token) = token {
+        let basic = base64::engine::general_purpose::STANDARD.encode(format!("x-access-token:{}", token));
+        env.extend([
+            ("GIT_CONFIG_COUNT", "1".to_string()),
+            ("GIT_CONFIG_KEY_0", "http.https://github.com/.extraheader".to_string()),
+            ("GIT_CONFIG_VALUE_0", format!("Authorization: Basic {}", basic)),
+        ]);
+    }
+    let run = tools::run_for_app(project, ToolId::Git, args, &env)?;
+
+    let stdout = run.stdout.trim().to_string();
+    let stderr = run.stderr.trim().to_string();
+    if run.exit_code != Some(0) {
+        return Err(format!("git {} failed: {}", args.first().unwrap_or(&""), if stderr.is_empty() { stdout } else { stderr }));
+    }
+    // git reports push/pull progress on stderr
+    Ok([stdout, stderr].into_iter().filter(|s| !s.is_empty()).collect::<Vec<_>>().join("\n"))
+}
+
+/// Working-tree state of a project repository
+#[derive(Debug, Serialize, Clone)]
+pub struct GitStatus {
+    pub branch: String,
+    /// Changed and untracked files not yet committed
+    pub uncommitted: usize,
+    /// Commits not pushed to / not pulled from the upstream; 0 without an upstream
+    pub ahead: usize,
+    pub behind: usize,
+}
+
+/// Status of the project's repository, from the last fetch; None when it isn't one. This runs
+/// whenever the health report is built, including for projects opened from someone else, so it
+/// goes through the tool wrapper and skips submodules, whose own config the wrapper doesn't vet.
+pub fn status(project: &Path) -> Option<GitStatus> {
+    let porcelain = git(project, &["status", "--porcelain", "--branch", "--ignore-submodules"], None).ok()?;
+    let mut lines = porcelain.lines();
+    // "## main...origin/main [ahead 1, behind 2]"
+    let header = lines.next()?.trim_start_matches("## ");
+    let branch = header.split("...").next().unwrap_or(header).split(' ').next().unwrap_or_default();
+    let count = |label: &str| {
+        header
+            .split_once(label)
+            .and_then(|(_, rest)| rest.split([',', ']']).next())
+            .and_then(|n| n.trim().parse().ok())
+            .unwrap_or(0)
+    };
+    Some(GitStatus {
+        branch: branch.to_string(),
+        uncommitted: lines.count(),
+        ahead: count("ahead "),
+        behind: count("behind "),
+    })
+}
+
+/// Add the generated-state folders to .gitignore
+fn ensure_gitignore(project: &Path) -> Result<(), String> {
+    let path = project.join(".gitignore");
+    let existing = fs::read_to_string(&path).unwrap_or_default();
+    let missing: Vec<&str> = GITIGNORE_ENTRIES
+        .iter()
+        .filter(|entry| !existing.lines().any(|line| line.trim() == **entry))
+        .copied()
+        .collect();
+    if missing.is_empty() {
+        return Ok(());
+    }
+    let mut content = existing;
+    if !content.is_empty() && !content.ends_with('\n') {
+        content.push('\n');
+    }
+    content.push_str(&missing.join("\n"));
+    content.push('\n');
+    fs::write(&path, content).map_err(|e| format!("Failed to write .gitignore: {}", e))
+}
+
+fn github_token() -> Result<String, String> {
+    credentials::load(GITHUB_TOKEN_ACCOUNT)?
+        .ok_or_else(|| "No GitHub token saved; publish the project to GitHub first".to_string())
+}
+
+/// Token to push/pull with, only for GitHub HTTPS remotes
+fn remote_token(project: &Path) -> Result<Option<String>, String> {
+    let remote = git(project, &["remote", "get-url", "origin"], None)?;
+    if remote.starts_with("https://github.com/") {
+        github_token().map(Some)
+    } else {
+        Ok(None)
+    }
+}
+
+async fn github_get_user(client: &Client, token: &str) -> Result<GitHubUser, String> {
+    let response = client
+        .get(format!("{}/user", GITHUB_API))
+        .bearer_auth(token)
+        .header("User-Agent", "PromptPlay")
+        .header("Accept", "application/vnd.github+json")
+        .send()
+        .await
+        .map_err(|e| format!("Failed to reach GitHub: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!("GitHub rejected the token: HTTP {}", response.status()));
+    }
+    response.json().await.map_err(|e| format!("Failed to parse GitHub user: {}", e))
+}
+
+/// Create a GitHub repository for the project, commit it, set `origin` and push. The token is
+/// saved in the OS keychain for later `git_push`/`git_pull`.
+#[tauri::command]
+pub async fn publish_to_github(
+    app: AppHandle,
+    project_path: String,
+    token: String,
+    repo_name: String,
+    private: Option<bool>,
+) -> Result<GitHubPublishResult, String> {
+    let project = PathBuf::from(&project_path);
+    let client = Client::new();
+    let user = github_get_user(&client, &token).await?;
+
+    let response = client
+        .post(format!("{}/user/repos", GITHUB_API))
+        .bearer_auth(&token)
+        .header("User-Agent", "PromptPlay")
+        .header("Accept", "application/vnd.github+json")
+        .json(&serde_json::json!({ "name": repo_name, "private": private.unwrap_or(true) }))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to reach GitHub: {}", e))?;
+    if response.status() == reqwest::StatusCode::UNPROCESSABLE_ENTITY {
+        return Err(format!("A repository named {} already exists on {}'s account", repo_name, user.login));
+    }
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        return Err(format!("Failed to create GitHub repository: HTTP {} {}", status, body));
+    }
+    let repo: GitHubRepo = response.json().await.map_err(|e| format!("Failed to parse GitHub repository: {}", e))?;
+    credentials::store(GITHUB_TOKEN_ACCOUNT, &token)?;
+
+    if !project.join(".git").exists() {
+        git(&project, &["init"], None)?;
+    }
+    ensure_gitignore(&project)?;
+    git(&project, &["add", "-A"], None)?;
+    let has_changes = !git(&project, &["status", "--porcelain"], None)?.is_empty();
+    let has_commits = git(&project, &["rev-parse", "--verify", "HEAD"], None).is_ok();
+    if has_changes || !has_commits {
+        // Fall back to the GitHub identity when git has none configured
+        let name = user.name.clone().unwrap_or_else(|| user.login.clone());
+        let email = format!("{}+{}@users.noreply.github.com", user.id, user.login);
+        let identity = git(&project, &["config", "user.email"], None).is_ok();
+        let env = if identity {
+            Vec::new()
+        } else {
+            vec![
+                ("GIT_AUTHOR_NAME", name.clone()),
+                ("GIT_COMMITTER_NAME", name),
+                ("GIT_AUTHOR_EMAIL", email.clone()),
+                ("GIT_COMMITTER_EMAIL", email),
+            ]
+        };
+        git_with_env(&project, &["commit", "--allow-empty", "-m", "Publish PromptPlay project"], None, env)?;
+    }
+    git(&project, &["branch", "-M", DEFAULT_BRANCH], None)?;
+    if git(&project, &["remote", "get-url", "origin"], None).is_ok() {
+        git(&project, &["remote", "set-url", "origin", &repo.clone_url], None)?;
+    } else {
+        git(&project, &["remote", "add", "origin", &repo.clone_url], None)?;
+    }
+    git(&project, &["push", "-u", "origin", DEFAULT_BRANCH], Some(&token))?;
+
+    notifications::task_finished(
+        &app,
+        NotificationCategory::Publish,
+        "Published to GitHub",
+        &format!("{} is live at {}", repo_name, repo.html_url),
+    );
+    Ok(GitHubPublishResult {
+        html_url: repo.html_url,
+        clone_url: repo.clone_url,
+        branch: DEFAULT_BRANCH.to_string(),
+    })
+}
+
+/// Commit pending changes (when `message` is given) and push the current branch to origin
+#[tauri::command]
+pub async fn git_push(project_path: String, message: Option<String>) -> Result<GitResult, String> {
+    let project = PathBuf::from(&project_path);
+    let token = remote_token(&project)?;
+    if let Some(message) = message {
+        git(&project, &["add", "-A"], None)?;
+        if !git(&project, &["status", "--porcelain"], None)?.is_empty() {
+            git(&project, &["commit", "-m", &message], None)?;
+        }
+    }
+    let output = git(&project, &["push", "origin", "HEAD"], token.as_deref())?;
+    Ok(GitResult { output })
+}
+
+/// Fast-forward the current branch from origin; diverged histories are reported, not merged
+#[tauri::command]
+pub async fn git_pull(project_path: String) -> Result<GitResult, String> {
+    let project = PathBuf::from(&project_path);
+    let token = remote_token(&project)?;
+    let output = git(&project, &["pull", "--ff-only", "origin"], token.as_deref())?;
+    Ok(GitResult { output })
+}
+
+/// Forget the saved GitHub token
+#[tauri::command]
+pub async fn disconnect_github() -> Result<(), String> {
+    credentials::delete(GITHUB_TOKEN_ACCOUNT)
+}
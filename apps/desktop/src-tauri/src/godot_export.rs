@@ -0,0 +1,440 @@
+use crate::assets::{AssetKind, AssetManifest};
+use crate::scene_import::Untranslated;
+use crate::thumbnails::{parse_tint, scene_entities, scene_file_stem};
+use crate::{spec, world};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashSet};
+use std::fmt::Write as _;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Godot's default gravity, which a spec gravity of 1 maps to
+const GODOT_GRAVITY: f64 = 980.0;
+const PLAYER_SCRIPT: &str = "scripts/player.gd";
+const PATROL_SCRIPT: &str = "scripts/patrol.gd";
+/// Components the exporter turns into nodes; anything else is listed in the report
+const EXPORTED_COMPONENTS: &[&str] = &["transform", "sprite", "collider", "velocity", "input", "aiBehavior"];
+
+const PLAYER_GD: &str = r#"extends CharacterBody2D
+## Left/right movement and jumping, exported from a PromptPlay entity with an input component
+
+@export var move_speed := 200.0
+@export var jump_force := 400.0
+
+func _physics_process(delta: float) -> void:
+	if not is_on_floor():
+		velocity += get_gravity() * delta
+	if Input.is_action_just_pressed("ui_accept") and is_on_floor():
+		velocity.y = -jump_force
+	velocity.x = Input.get_axis("ui_left", "ui_right") * move_speed
+	move_and_slide()
+"#;
+
+const PATROL_GD: &str = r#"extends CharacterBody2D
+## Walks back and forth around its start, exported from a PromptPlay patrol behavior
+
+@export var speed := 60.0
+@export var patrol_range := 100.0
+
+var _origin_x := 0.0
+var _direction := 1.0
+
+func _ready() -> void:
+	_origin_x = position.x
+
+func _physics_process(delta: float) -> void:
+	if not is_on_floor():
+		velocity += get_gravity() * delta
+	velocity.x = speed * _direction
+	move_and_slide()
+	if is_on_wall() or absf(position.x - _origin_x) > patrol_range:
+		_direction = -_direction
+"#;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GodotExportReport {
+    pub output_dir: String,
+    /// res:// paths of the generated scenes; the first is the main scene
+    pub scenes: Vec<String>,
+    pub scripts: Vec<String>,
+    pub assets: Vec<String>,
+    /// Components and settings that have no counterpart in the generated project
+    pub untranslated: Vec<Untranslated>,
+}
+
+/// Godot `Color(...)` for a spec tint
+fn godot_color(tint: Option<&Value>) -> String {
+    let rgba = parse_tint(tint);
+    format!(
+        "Color({:.3}, {:.3}, {:.3}, 1)",
+        rgba[0] as f64 / 255.0,
+        rgba[1] as f64 / 255.0,
+        rgba[2] as f64 / 255.0
+    )
+}
+
+fn quoted(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Node names can't contain `. : @ / " %` and must be unique among siblings
+fn node_name(name: &str, taken: &mut HashSet<String>) -> String {
+    let clean: String = name
+        .chars()
+        .map(|c| if ".:@/\"%".contains(c) { '_' } else { c })
+        .collect::<String>()
+        .trim()
+        .to_string();
+    let base = if clean.is_empty() { "Entity".to_string() } else { clean };
+    let mut unique = base.clone();
+    let mut suffix = 1;
+    while !taken.insert(unique.clone()) {
+        suffix += 1;
+        unique = format!("{}{}", base, suffix);
+    }
+    unique
+}
+
+fn number(value: Option<&Value>, default: f64) -> f64 {
+    value.and_then(Value::as_f64).unwrap_or(default)
+}
+
+/// Runtime input speeds are per frame at 60 fps (values over 50 are legacy px/s scaled by 40)
+fn per_second(raw: f64) -> f64 {
+    let per_frame = if raw.abs() > 50.0 { raw / 40.0 } else { raw };
+    per_frame * 60.0
+}
+
+/// Project-relative path of an image texture
+fn texture_asset(manifest: &AssetManifest, texture: &str) -> Option<String> {
+    let relative = match manifest.resolve(texture) {
+        Some(entry) if entry.kind == AssetKind::Image => entry.path.clone(),
+        Some(_) => return None,
+        None => texture.to_string(),
+    };
+    (AssetKind::from_path(Path::new(&relative)) == AssetKind::Image).then_some(relative)
+}
+
+/// Shared state while writing one project
+struct Exporter<'a> {
+    project_path: &'a str,
+    output: PathBuf,
+    manifest: AssetManifest,
+    /// Copied textures: project path -> size in pixels, if readable
+    assets: BTreeMap<String, Option<(u32, u32)>>,
+    scripts: HashSet<&'static str>,
+    untranslated: Vec<Untranslated>,
+}
+
+impl Exporter<'_> {
+    fn note(&mut self, node: &str, kind: &str, reason: impl Into<String>) {
+        self.untranslated.push(Untranslated {
+            node: node.to_string(),
+            kind: kind.to_string(),
+            reason: reason.into(),
+        });
+    }
+
+    /// Copy a texture into the Godot project once; returns its res:// path and pixel size
+    fn copy_texture(&mut self, entity: &str, texture: &str) -> Option<(String, Option<(u32, u32)>)> {
+        let Some(relative) = texture_asset(&self.manifest, texture) else {
+            self.note(entity, "sprite", format!("Texture '{}' isn't an image asset; a colored box is used", texture));
+            return None;
+        };
+        if !self.assets.contains_key(&relative) {
+            let source = PathBuf::from(self.project_path).join(&relative);
+            let target = self.output.join(&relative);
+            let copied = target
+                .parent()
+                .map_or(Ok(()), fs::create_dir_all)
+                .and_then(|_| fs::copy(&source, &target));
+            if let Err(e) = copied {
+                self.note(entity, "sprite", format!("Couldn't copy {}: {}; a colored box is used", relative, e));
+                return None;
+            }
+            self.assets.insert(relative.clone(), image::image_dimensions(&source).ok());
+        }
+        let size = self.assets.get(&relative).copied().flatten();
+        Some((format!("res://{}", relative), size))
+    }
+
+    /// Write one scene file holding `entities` under a Node2D root
+    fn write_scene(&mut self, root_name: &str, file: &str, entities: &[&Value]) -> Result<(), String> {
+        let mut ext_resources: Vec<(String, String, String)> = Vec::new();
+        let mut sub_resources = String::new();
+        let mut nodes = String::new();
+        let mut taken = HashSet::new();
+        let ext_id = |resources: &mut Vec<(String, String, String)>, kind: &str, path: String| {
+            if let Some((_, _, id)) = resources.iter().find(|(_, p, _)| *p == path) {
+                return id.clone();
+            }
+            let id = format!("{}_{}", resources.len() + 1, kind.to_lowercase());
+            resources.push((kind.to_string(), path, id.clone()));
+            id
+        };
+        let mut shape_count = 0;
+
+        let _ = writeln!(nodes, "[node name={} type=\"Node2D\"]\n", quoted(root_name));
+        for entity in entities {
+            let entity_name = entity.get("name").and_then(Value::as_str).unwrap_or("Entity");
+            let name = node_name(entity_name, &mut taken);
+            let components = entity.get("components").cloned().unwrap_or(Value::Null);
+            let component = |key: &str| components.get(key).filter(|c| c.is_object());
+            for key in components.as_object().into_iter().flat_map(|c| c.keys()) {
+                if !EXPORTED_COMPONENTS.contains(&key.as_str()) {
+                    self.note(entity_name, key, "No Godot equivalent was generated; rebuild it in the editor or a script");
+                }
+            }
+
+            let collider = component("collider");
+            let input = component("input");
+            let ai = component("aiBehavior");
+            let velocity = component("velocity");
+            let sensor = collider.and_then(|c| c.get("isSensor")).and_then(Value::as_bool).unwrap_or(false);
+            let (node_type, script) = if input.is_some() {
+                ("CharacterBody2D", Some(PLAYER_SCRIPT))
+            } else if let Some(ai) = ai {
+                let patrol = ai.get("type").and_then(Value::as_str) == Some("patrol");
+                if !patrol {
+                    self.note(entity_name, "aiBehavior", "Only patrol behaviors get a script");
+                }
+                ("CharacterBody2D", patrol.then_some(PATROL_SCRIPT))
+            } else if collider.is_some() && sensor {
+                ("Area2D", None)
+            } else if collider.is_some() && velocity.is_some() {
+                ("RigidBody2D", None)
+            } else if collider.is_some() {
+                ("StaticBody2D", None)
+            } else {
+                ("Node2D", None)
+            };
+
+            let transform = component("transform");
+            let field = |key: &str, default: f64| number(transform.and_then(|t| t.get(key)), default);
+            let _ = writeln!(nodes, "[node name={} type=\"{}\" parent=\".\"]", quoted(&name), node_type);
+            let _ = writeln!(nodes, "position = Vector2({}, {})", field("x", 0.0), field("y", 0.0));
+            if field("rotation", 0.0) != 0.0 {
+                let _ = writeln!(nodes, "rotation = {}", field("rotation", 0.0));
+            }
+            let (scale_x, scale_y) = (field("scaleX", 1.0), field("scaleY", 1.0));
+            if (scale_x, scale_y) != (1.0, 1.0) {
+                let _ = writeln!(nodes, "scale = Vector2({}, {})", scale_x, scale_y);
+            }
+            if let Some(script) = script {
+                self.scripts.insert(script);
+                let id = ext_id(&mut ext_resources, "Script", format!("res://{}", script));
+                let _ = writeln!(nodes, "script = ExtResource(\"{}\")", id);
+                if script == PLAYER_SCRIPT {
+                    let input = input.unwrap_or(&Value::Null);
+                    let _ = writeln!(nodes, "move_speed = {}", per_second(number(input.get("moveSpeed"), 5.0)));
+                    let _ = writeln!(nodes, "jump_force = {}", per_second(number(input.get("jumpForce"), 10.0).abs()));
+                } else {
+                    let ai = ai.unwrap_or(&Value::Null);
+                    let _ = writeln!(nodes, "speed = {}", number(ai.get("speed"), 60.0));
+                    let _ = writeln!(nodes, "patrol_range = {}", number(ai.get("patrolRange"), 100.0));
+                }
+            }
+            if node_type == "RigidBody2D" {
+                let velocity = velocity.unwrap_or(&Value::Null);
+                let _ = writeln!(
+                    nodes,
+                    "linear_velocity = Vector2({}, {})",
+                    number(velocity.get("vx"), 0.0),
+                    number(velocity.get("vy"), 0.0)
+                );
+            }
+            nodes.push('\n');
+
+            let child_parent = quoted(&name);
+            if let Some(sprite) = component("sprite") {
+                let width = number(sprite.get("width"), 32.0);
+                let height = number(sprite.get("height"), 32.0);
+                let texture = sprite
+                    .get("texture")
+                    .and_then(Value::as_str)
+                    .filter(|t| !t.is_empty())
+                    .and_then(|t| self.copy_texture(entity_name, t));
+                match texture {
+                    Some((path, size)) => {
+                        let id = ext_id(&mut ext_resources, "Texture2D", path);
+                        let _ = writeln!(nodes, "[node name=\"Sprite2D\" type=\"Sprite2D\" parent={}]", child_parent);
+                        let _ = writeln!(nodes, "texture = ExtResource(\"{}\")", id);
+                        if let Some((w, h)) = size.filter(|(w, h)| *w > 0 && *h > 0) {
+                            let _ = writeln!(nodes, "scale = Vector2({}, {})", width / w as f64, height / h as f64);
+                        }
+                        if sprite.get("tint").is_some() {
+                            let _ = writeln!(nodes, "modulate = {}", godot_color(sprite.get("tint")));
+                        }
+                    }
+                    None => {
+                        let (hw, hh) = (width / 2.0, height / 2.0);
+                        let _ = writeln!(nodes, "[node name=\"Shape\" type=\"Polygon2D\" parent={}]", child_parent);
+                        let _ = writeln!(nodes, "color = {}", godot_color(sprite.get("tint")));
+                        let _ = writeln!(
+                            nodes,
+                            "polygon = PackedVector2Array({}, {}, {}, {}, {}, {}, {}, {})",
+                            -hw, -hh, hw, -hh, hw, hh, -hw, hh
+                        );
+                    }
+                }
+                if sprite.get("visible").and_then(Value::as_bool) == Some(false) {
+                    let _ = writeln!(nodes, "visible = false");
+                }
+                if let Some(z) = sprite.get("zIndex").and_then(Value::as_i64) {
+                    let _ = writeln!(nodes, "z_index = {}", z);
+                }
+                nodes.push('\n');
+            }
+
+            if let Some(collider) = collider {
+                let shape = match collider.get("type").and_then(Value::as_str).unwrap_or("box") {
+                    "circle" => Some(format!(
+                        "[sub_resource type=\"CircleShape2D\" id=\"shape_{}\"]\nradius = {}\n",
+                        shape_count + 1,
+                        number(collider.get("radius"), 16.0)
+                    )),
+                    "box" => Some(format!(
+                        "[sub_resource type=\"RectangleShape2D\" id=\"shape_{}\"]\nsize = Vector2({}, {})\n",
+                        shape_count + 1,
+                        number(collider.get("width"), 32.0),
+                        number(collider.get("height"), 32.0)
+                    )),
+                    other => {
+                        self.note(entity_name, "collider", format!("'{}' colliders aren't exported; add a shape by hand", other));
+                        None
+                    }
+                };
+                if let Some(shape) = shape {
+                    shape_count += 1;
+                    let _ = writeln!(sub_resources, "{}", shape);
+                    let _ = writeln!(
+                        nodes,
+                        "[node name=\"CollisionShape2D\" type=\"CollisionShape2D\" parent={}]",
+                        child_parent
+                    );
+                    let _ = writeln!(nodes, "shape = SubResource(\"shape_{}\")\n", shape_count);
+                }
+                if collider.get("oneWay").and_then(Value::as_bool) == Some(true) {
+                    self.note(entity_name, "collider", "One-way platforms need one_way_collision set on the shape");
+                }
+            }
+        }
+
+        let mut scene = format!(
+            "[gd_scene load_steps={} format=3]\n\n",
+            ext_resources.len() + shape_count + 1
+        );
+        for (kind, path, id) in &ext_resources {
+            let _ = writeln!(scene, "[ext_resource type=\"{}\" path={} id=\"{}\"]", kind, quoted(path), id);
+        }
+        if !ext_resources.is_empty() {
+            scene.push('\n');
+        }
+        scene.push_str(&sub_resources);
+        scene.push_str(nodes.trim_end());
+        scene.push('\n');
+
+        let path = self.output.join(file);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+        }
+        fs::write(&path, scene).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+    }
+}
+
+fn project_godot(game_spec: &Value, title: &str, main_scene: &str) -> String {
+    let canvas = world::canvas(game_spec);
+    let mut file = format!(
+        "; Generated by PromptPlay\n\nconfig_version=5\n\n[application]\n\nconfig/name={}\nrun/main_scene={}\nconfig/features=PackedStringArray(\"4.2\")\n\n[display]\n\nwindow/size/viewport_width={}\nwindow/size/viewport_height={}\n",
+        quoted(title),
+        quoted(main_scene),
+        canvas.width.round(),
+        canvas.height.round()
+    );
+    if let Some(gravity) = game_spec.pointer("/config/gravity/y").and_then(Value::as_f64) {
+        let _ = write!(file, "\n[physics]\n\n2d/default_gravity={}\n", gravity * GODOT_GRAVITY);
+    }
+    if let Some(background) = game_spec.pointer("/config/backgroundColor") {
+        let _ = write!(
+            file,
+            "\n[rendering]\n\nenvironment/defaults/default_clear_color={}\n",
+            godot_color(Some(background))
+        );
+    }
+    file
+}
+
+/// Generate a Godot 4 project from the spec: one scene per PromptPlay scene with entities as
+/// nodes (names and layout kept), sprites and box/circle colliders, copied textures, and starter
+/// scripts for player input and patrol behavior. `output_dir` must be empty or missing.
+#[tauri::command]
+pub async fn export_godot_project(project_path: String, output_dir: String) -> Result<GodotExportReport, String> {
+    let game_spec = spec::load_project_spec(&project_path)?;
+    let output = PathBuf::from(&output_dir);
+    if fs::read_dir(&output).is_ok_and(|mut entries| entries.next().is_some()) {
+        return Err(format!("{} is not empty; choose an empty folder for the Godot project", output_dir));
+    }
+    fs::create_dir_all(&output).map_err(|e| format!("Failed to create {}: {}", output_dir, e))?;
+
+    let mut exporter = Exporter {
+        project_path: &project_path,
+        output: output.clone(),
+        manifest: AssetManifest::load(&project_path)?,
+        assets: BTreeMap::new(),
+        scripts: HashSet::new(),
+        untranslated: Vec::new(),
+    };
+
+    let scene_ids: Vec<String> = game_spec
+        .get("scenes")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|s| s.get("id")?.as_str().map(str::to_string))
+        .collect();
+    let mut groups: Vec<Option<&str>> = scene_ids.iter().map(|id| Some(id.as_str())).collect();
+    if !scene_entities(&game_spec, None).is_empty() || groups.is_empty() {
+        groups.insert(0, None);
+    }
+    let active = game_spec.get("activeScene").and_then(Value::as_str);
+    if let Some(position) = groups.iter().position(|g| g.is_some() && *g == active) {
+        let main = groups.remove(position);
+        groups.insert(0, main);
+    }
+
+    let mut scenes = Vec::new();
+    for scene in groups {
+        let file = format!("scenes/{}.tscn", scene_file_stem(scene));
+        let root = scene.unwrap_or("Main");
+        exporter.write_scene(root, &file, &scene_entities(&game_spec, scene))?;
+        scenes.push(format!("res://{}", file));
+    }
+
+    let mut scripts: Vec<&str> = exporter.scripts.iter().copied().collect();
+    scripts.sort_unstable();
+    for script in &scripts {
+        let source = if *script == PLAYER_SCRIPT { PLAYER_GD } else { PATROL_GD };
+        let path = output.join(script);
+        fs::create_dir_all(output.join("scripts")).map_err(|e| format!("Failed to create scripts folder: {}", e))?;
+        fs::write(&path, source).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    }
+
+    let title = game_spec
+        .pointer("/metadata/title")
+        .and_then(Value::as_str)
+        .unwrap_or("PromptPlay Game");
+    let main_scene = scenes.first().cloned().unwrap_or_default();
+    fs::write(output.join("project.godot"), project_godot(&game_spec, title, &main_scene))
+        .map_err(|e| format!("Failed to write project.godot: {}", e))?;
+    fs::write(output.join(".gitignore"), ".godot/\n").map_err(|e| format!("Failed to write .gitignore: {}", e))?;
+
+    Ok(GodotExportReport {
+        output_dir,
+        scenes,
+        scripts: scripts.iter().map(|s| format!("res://{}", s)).collect(),
+        assets: exporter.assets.into_keys().collect(),
+        untranslated: exporter.untranslated,
+    })
+}
@@ -0,0 +1,365 @@
+use crate::accessibility::{self, AccessibilityReport};
+use crate::assets::{AssetManifest, ASSETS_DIR, MANIFEST_FILE};
+use crate::components::{ComponentRegistry, CUSTOM_COMPONENTS_FILE};
+use crate::formatter::{FormatConfig, SpecStorage};
+use crate::git::{self, GitStatus};
+use crate::validation::{self, Diagnostic, Severity};
+use crate::lint_rules::{self, LINT_RULES_DIR};
+use crate::{asset_usage, paths, spec, workspace};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tauri::AppHandle;
+
+const CHECK_INTERVAL_SECONDS: u64 = 30;
+/// Files touched more recently than this are mid-edit; wait for the user to pause
+const IDLE_SECONDS: u64 = 10;
+/// Entities in one scene past which the editor and runtime start to slow down
+const LARGE_SCENE_ENTITIES: usize = 5000;
+/// Uncompressed game.json size worth switching to zstd storage for
+const LARGE_SPEC_BYTES: u64 = 16 * 1024 * 1024;
+/// Single assets past this bloat exports and load times
+const LARGE_ASSET_BYTES: u64 = 4 * 1024 * 1024;
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ProjectHealth {
+    pub project_path: String,
+    pub checked_at: u64,
+    pub errors: usize,
+    pub warnings: usize,
+    pub diagnostics: Vec<Diagnostic>,
+    /// Kept apart from `diagnostics` and the counts: these don't break the game
+    pub accessibility: AccessibilityReport,
+}
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum ActionPriority {
+    High,
+    Medium,
+    Low,
+}
+
+/// Something the user can do to raise the score, with the command that does or starts it
+#[derive(Debug, Serialize, Clone)]
+pub struct HealthAction {
+    pub priority: ActionPriority,
+    pub title: String,
+    pub detail: String,
+    pub command: Option<&'static str>,
+}
+
+/// `ProjectHealth` plus asset, performance and git findings, scored 0-100 with ranked actions
+#[derive(Debug, Serialize, Clone)]
+pub struct ProjectHealthReport {
+    pub score: u32,
+    pub health: ProjectHealth,
+    /// Project assets nothing in the spec references
+    pub unused_assets: Vec<String>,
+    pub performance: Vec<Diagnostic>,
+    /// None when the project isn't a git repository
+    pub git: Option<GitStatus>,
+    /// Most important first
+    pub actions: Vec<HealthAction>,
+}
+
+/// Stop flag of the running background checker
+#[derive(Default)]
+pub struct HealthMonitorState(Mutex<Option<Arc<AtomicBool>>>);
+
+/// Referenced asset files that are registered or path-like but absent on disk.
+/// Bare names with no manifest entry are left alone; the runtime draws them as placeholders.
+fn missing_assets(project_path: &str, game_spec: &serde_json::Value) -> Vec<Diagnostic> {
+    let manifest = AssetManifest::load(project_path).unwrap_or_default();
+    let root = PathBuf::from(project_path);
+    let mut seen = HashSet::new();
+    let mut diagnostics = Vec::new();
+
+    for reference in spec::asset_references(game_spec) {
+        if !seen.insert(reference.asset.clone()) {
+            continue;
+        }
+        let relative = match manifest.resolve(&reference.asset) {
+            Some(entry) => entry.path.clone(),
+            None if reference.asset.contains(['/', '.']) => reference.asset.clone(),
+            None => continue,
+        };
+        if !paths::for_io(&root.join(&relative)).is_file() {
+            diagnostics.push(Diagnostic::error(
+                "",
+                format!("Asset '{}' used by '{}' is missing from the project", reference.asset, reference.entity),
+            ));
+        }
+    }
+    diagnostics
+}
+
+/// Validate the project's spec and asset references as the export would see them
+pub fn check(project_path: &str) -> ProjectHealth {
+    let (diagnostics, accessibility) = match spec::load_project_spec(project_path) {
+        Ok(game_spec) => {
+            let registry = ComponentRegistry::load(project_path).unwrap_or_else(|_| ComponentRegistry::builtin());
+            let mut diagnostics = validation::validate_spec_with_registry(&game_spec, &registry);
+            diagnostics.extend(missing_assets(project_path, &game_spec));
+            diagnostics.extend(lint_rules::run(&lint_rules::project_rules(project_path), &game_spec));
+            (diagnostics, accessibility::audit(Some(project_path), &game_spec))
+        }
+        Err(e) => (vec![Diagnostic::error("", e)], AccessibilityReport::default()),
+    };
+
+    let count = |severity: Severity| diagnostics.iter().filter(|d| d.severity == severity).count();
+    ProjectHealth {
+        project_path: project_path.to_string(),
+        checked_at: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0),
+        errors: count(Severity::Error),
+        warnings: count(Severity::Warning),
+        diagnostics,
+        accessibility,
+    }
+}
+
+/// Heuristics for what makes large projects slow: crowded scenes, huge specs and assets
+fn performance(project_path: &str, game_spec: &Value, used_assets: &[String]) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    let mut per_scene: Vec<(Option<&str>, usize)> = Vec::new();
+    for (scene, _) in spec::entities_with_scene(game_spec) {
+        match per_scene.iter_mut().find(|(s, _)| *s == scene) {
+            Some((_, count)) => *count += 1,
+            None => per_scene.push((scene, 1)),
+        }
+    }
+    for (scene, count) in per_scene.into_iter().filter(|(_, count)| *count > LARGE_SCENE_ENTITIES) {
+        diagnostics.push(Diagnostic::warning(
+            "/entities",
+            format!(
+                "Scene '{}' has {} entities; split it or merge static tiles into a tilemap",
+                scene.unwrap_or("main"),
+                count
+            ),
+        ));
+    }
+
+    let spec_bytes = std::fs::metadata(paths::for_io(&PathBuf::from(project_path).join("game.json")))
+        .map(|m| m.len())
+        .unwrap_or(0);
+    let storage = FormatConfig::load(project_path).map(|c| c.storage).unwrap_or_default();
+    if spec_bytes > LARGE_SPEC_BYTES && storage == SpecStorage::Json {
+        diagnostics.push(Diagnostic::warning(
+            "",
+            format!(
+                "game.json is {:.1} MB; compressed storage would load faster",
+                spec_bytes as f64 / (1024.0 * 1024.0)
+            ),
+        ));
+    }
+
+    for asset in used_assets {
+        let size = std::fs::metadata(paths::for_io(&PathBuf::from(project_path).join(asset)))
+            .map(|m| m.len())
+            .unwrap_or(0);
+        if size > LARGE_ASSET_BYTES {
+            diagnostics.push(Diagnostic::warning(
+                "",
+                format!("Asset '{}' is {:.1} MB; every export carries it", asset, size as f64 / (1024.0 * 1024.0)),
+            ));
+        }
+    }
+    diagnostics
+}
+
+/// 100 minus penalties: errors weigh most, unused assets least, each kind capped so one noisy
+/// category can't zero the score on its own
+fn score(health: &ProjectHealth, unused_assets: usize, performance: usize, git: Option<&GitStatus>) -> u32 {
+    let penalty = (health.errors * 15).min(60)
+        + (health.warnings * 3).min(20)
+        + unused_assets.min(10)
+        + (performance * 5).min(15)
+        + git.map_or(0, |g| (if g.behind > 0 { 5 } else { 0 }) + (if g.uncommitted > 0 { 2 } else { 0 }));
+    100u32.saturating_sub(penalty as u32)
+}
+
+fn plural(count: usize, one: &str, many: &str) -> String {
+    format!("{} {}", count, if count == 1 { one } else { many })
+}
+
+fn actions(health: &ProjectHealth, unused_assets: &[String], performance: &[Diagnostic], git: Option<&GitStatus>) -> Vec<HealthAction> {
+    let mut actions = Vec::new();
+    let first = |severity: Severity| {
+        health
+            .diagnostics
+            .iter()
+            .find(|d| d.severity == severity)
+            .map(|d| d.message.clone())
+            .unwrap_or_default()
+    };
+    if health.errors > 0 {
+        actions.push(HealthAction {
+            priority: ActionPriority::High,
+            title: format!("Fix {}", plural(health.errors, "error", "errors")),
+            detail: first(Severity::Error),
+            command: Some("validate_game_spec"),
+        });
+    }
+    if let Some(git) = git.filter(|g| g.behind > 0) {
+        actions.push(HealthAction {
+            priority: ActionPriority::High,
+            title: format!("Pull {}", plural(git.behind, "commit", "commits")),
+            detail: format!("'{}' is behind its upstream; pull before editing to avoid conflicts", git.branch),
+            command: Some("git_pull"),
+        });
+    }
+    if health.warnings > 0 {
+        actions.push(HealthAction {
+            priority: ActionPriority::Medium,
+            title: format!("Review {}", plural(health.warnings, "warning", "warnings")),
+            detail: first(Severity::Warning),
+            command: Some("validate_game_spec"),
+        });
+    }
+    for finding in performance {
+        actions.push(HealthAction {
+            priority: ActionPriority::Medium,
+            title: "Improve performance".to_string(),
+            detail: finding.message.clone(),
+            command: None,
+        });
+    }
+    if !unused_assets.is_empty() {
+        actions.push(HealthAction {
+            priority: ActionPriority::Low,
+            title: format!("Remove {}", plural(unused_assets.len(), "unused asset", "unused assets")),
+            detail: unused_assets.iter().take(5).cloned().collect::<Vec<_>>().join(", "),
+            command: Some("analyze_asset_usage"),
+        });
+    }
+    if let Some(git) = git.filter(|g| g.uncommitted > 0 || g.ahead > 0) {
+        actions.push(HealthAction {
+            priority: ActionPriority::Low,
+            title: "Commit and push".to_string(),
+            detail: format!(
+                "{} uncommitted, {} unpushed",
+                plural(git.uncommitted, "file", "files"),
+                plural(git.ahead, "commit", "commits")
+            ),
+            command: Some("git_push"),
+        });
+    }
+    // Stable, so actions of equal priority keep the order above
+    actions.sort_by_key(|a| a.priority);
+    actions
+}
+
+/// The full scored report behind the project dashboard
+pub fn report(project_path: &str) -> ProjectHealthReport {
+    let health = check(project_path);
+    let (unused_assets, performance) = match spec::load_project_spec(project_path) {
+        Ok(game_spec) => {
+            let selection = asset_usage::select(project_path, &game_spec, false);
+            let used: Vec<String> = selection.included.into_iter().map(|asset| asset.path).collect();
+            (selection.stripped, performance(project_path, &game_spec, &used))
+        }
+        Err(_) => (Vec::new(), Vec::new()),
+    };
+    let git = git::status(Path::new(project_path));
+    ProjectHealthReport {
+        score: score(&health, unused_assets.len(), performance.len(), git.as_ref()),
+        actions: actions(&health, &unused_assets, &performance, git.as_ref()),
+        health,
+        unused_assets,
+        performance,
+        git,
+    }
+}
+
+/// `report` on a blocking thread, since it runs the project's lint rules
+pub async fn report_in_background(project_path: String) -> Result<ProjectHealthReport, String> {
+    tauri::async_runtime::spawn_blocking(move || report(&project_path))
+        .await
+        .map_err(|e| format!("Health check failed: {}", e))
+}
+
+/// Short health summary for the AI prompt: the score and the top actions
+pub fn describe_for_prompt(report: &ProjectHealthReport) -> String {
+    let mut lines = vec![format!("Score {}/100", report.score)];
+    lines.extend(
+        report
+            .actions
+            .iter()
+            .take(5)
+            .map(|a| format!("- {} ({:?} priority): {}", a.title, a.priority, a.detail)),
+    );
+    lines.join("\n")
+}
+
+/// Modification times of everything a check reads; unchanged fingerprints skip the check
+pub fn fingerprint(project: &Path) -> Vec<Option<SystemTime>> {
+    ["game.json", MANIFEST_FILE, CUSTOM_COMPONENTS_FILE, ASSETS_DIR, LINT_RULES_DIR]
+        .iter()
+        .map(|name| {
+            std::fs::metadata(paths::for_io(&project.join(name)))
+                .and_then(|m| m.modified())
+                .ok()
+        })
+        .collect()
+}
+
+pub fn recently_modified(stamps: &[Option<SystemTime>]) -> bool {
+    stamps.iter().flatten().any(|t| {
+        t.elapsed()
+            .is_ok_and(|age| age < Duration::from_secs(IDLE_SECONDS))
+    })
+}
+
+impl HealthMonitorState {
+    /// Start re-checking `project_path` in the background, replacing any previous monitor
+    pub fn start(&self, app: &AppHandle, project_path: &str) -> Result<(), String> {
+        self.stop()?;
+        let stop = Arc::new(AtomicBool::new(false));
+        *self.0.lock().map_err(|e| format!("Lock error: {}", e))? = Some(stop.clone());
+
+        let app = app.clone();
+        let project_path = project_path.to_string();
+        std::thread::spawn(move || {
+            let project = PathBuf::from(&project_path);
+            let mut last_checked = None;
+            while !stop.load(Ordering::Relaxed) {
+                let stamps = fingerprint(&project);
+                if last_checked.as_ref() != Some(&stamps) && !recently_modified(&stamps) {
+                    workspace::emit_to_project(&app, &project, "project-health", check(&project_path));
+                    last_checked = Some(stamps);
+                }
+                std::thread::sleep(Duration::from_secs(CHECK_INTERVAL_SECONDS));
+            }
+        });
+        Ok(())
+    }
+
+    pub fn stop(&self) -> Result<(), String> {
+        if let Some(stop) = self.0.lock().map_err(|e| format!("Lock error: {}", e))?.take() {
+            stop.store(true, Ordering::Relaxed);
+        }
+        Ok(())
+    }
+}
+
+/// Run the background health check immediately
+#[tauri::command]
+pub async fn check_project_health(project_path: String) -> Result<ProjectHealth, String> {
+    tauri::async_runtime::spawn_blocking(move || check(&project_path))
+        .await
+        .map_err(|e| format!("Health check failed: {}", e))
+}
+
+/// Score the project and list what to fix first, combining the health check with unused assets,
+/// performance heuristics and git status
+#[tauri::command]
+pub async fn get_project_health(project_path: String) -> Result<ProjectHealthReport, String> {
+    report_in_background(project_path).await
+}
@@ -0,0 +1,256 @@
+use crate::simulator::{InputEvent, Simulation, FRAME_SECONDS};
+use crate::thumbnails::{encode_png, render_scene, scene_entities};
+use crate::{spec, world};
+use image::{imageops, Rgba, RgbaImage};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+
+const DEFAULT_HEATMAP_WIDTH: u32 = 480;
+/// Replayed input traces stop here if the player never dies
+const DEFAULT_REPLAY_SECONDS: f64 = 30.0;
+const MAX_REPLAY_SECONDS: f64 = 600.0;
+/// Splat radius in world pixels; deaths are rarer, so each spreads wider
+const POSITION_RADIUS: f64 = 24.0;
+const DEATH_RADIUS: f64 = 48.0;
+/// Deaths within one cell of this size count toward the same hotspot
+const HOTSPOT_CELL: f64 = 64.0;
+const MAX_HOTSPOTS: usize = 5;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+pub struct TracePoint {
+    pub x: f64,
+    pub y: f64,
+}
+
+/// One playtest run: positions recorded while playing, or an input recording replayed in the
+/// headless simulator
+#[derive(Debug, Deserialize, Clone)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum PlayTrace {
+    /// Player positions sampled during a session, and where the player died
+    Recorded {
+        positions: Vec<TracePoint>,
+        #[serde(default)]
+        deaths: Vec<TracePoint>,
+    },
+    /// Keys held over time. The player's position is sampled every frame; losing a life, running
+    /// out of health or leaving the world counts as a death and ends the replay.
+    Inputs {
+        inputs: Vec<InputEvent>,
+        seconds: Option<f64>,
+    },
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HeatmapLayer {
+    /// Transparent PNG sized like the scene render, as a data URL, for overlaying in the editor
+    pub data_url: String,
+    pub samples: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Hotspot {
+    pub x: f64,
+    pub y: f64,
+    pub deaths: usize,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Heatmap {
+    pub scene: Option<String>,
+    pub width: u32,
+    pub height: u32,
+    pub positions: HeatmapLayer,
+    pub deaths: HeatmapLayer,
+    /// Busiest death areas, most deaths first
+    pub death_hotspots: Vec<Hotspot>,
+    /// Both layers over the scene render, for sharing
+    pub composite_data_url: String,
+    /// Traces that couldn't be used, with the reason
+    pub skipped: Vec<String>,
+}
+
+/// Replay an input trace and collect the player's positions and deaths
+fn replay(
+    game_spec: &Value,
+    scene: Option<&str>,
+    player: &str,
+    inputs: &[InputEvent],
+    seconds: f64,
+) -> (Vec<TracePoint>, Vec<TracePoint>) {
+    let mut simulation = Simulation::new(game_spec, scene);
+    let read = |simulation: &Simulation, target: &str| simulation.read(target).ok();
+    let number = |simulation: &Simulation, target: &str| read(simulation, target).and_then(|v| v.as_f64());
+    let lives = |simulation: &Simulation| number(simulation, "gameState.lives");
+    let start_lives = lives(&simulation);
+
+    let mut positions = Vec::new();
+    let frames = (seconds / FRAME_SECONDS).round() as u64;
+    for _ in 0..frames {
+        simulation.run(FRAME_SECONDS, inputs);
+        let (Some(x), Some(y)) = (
+            number(&simulation, &format!("entity.{}.x", player)),
+            number(&simulation, &format!("entity.{}.y", player)),
+        ) else {
+            break;
+        };
+        let point = TracePoint { x, y };
+        positions.push(point);
+
+        let lost_life = matches!((start_lives, lives(&simulation)), (Some(start), Some(now)) if now < start);
+        let out_of_health = number(&simulation, &format!("entity.{}.health", player)).is_some_and(|h| h <= 0.0);
+        let fell_out = read(&simulation, &format!("entity.{}.inBounds", player)) == Some(Value::Bool(false));
+        if lost_life || out_of_health || fell_out {
+            return (positions, vec![point]);
+        }
+    }
+    (positions, Vec::new())
+}
+
+/// Density of points on an image-sized grid, each spread over a cone of `radius` world pixels
+fn density(points: &[TracePoint], bounds: &world::WorldBounds, scale: f64, size: (u32, u32), radius: f64) -> Vec<f32> {
+    let (width, height) = size;
+    let mut grid = vec![0.0f32; (width * height) as usize];
+    let r = (radius * scale).max(1.0);
+    for point in points {
+        let cx = (point.x - bounds.x) * scale;
+        let cy = (point.y - bounds.y) * scale;
+        let x0 = (cx - r).floor().max(0.0) as u32;
+        let y0 = (cy - r).floor().max(0.0) as u32;
+        let x1 = ((cx + r).ceil().max(0.0) as u32).min(width);
+        let y1 = ((cy + r).ceil().max(0.0) as u32).min(height);
+        for y in y0..y1 {
+            for x in x0..x1 {
+                let distance = ((x as f64 - cx).powi(2) + (y as f64 - cy).powi(2)).sqrt();
+                if distance < r {
+                    grid[(y * width + x) as usize] += (1.0 - distance / r) as f32;
+                }
+            }
+        }
+    }
+    grid
+}
+
+/// Cold-to-hot color for a normalized density; faint areas stay transparent
+fn ramp(t: f32) -> Rgba<u8> {
+    if t < 0.02 {
+        return Rgba([0, 0, 0, 0]);
+    }
+    const STOPS: [(f32, [f32; 3]); 4] = [
+        (0.0, [0.0, 64.0, 255.0]),
+        (0.35, [0.0, 220.0, 120.0]),
+        (0.65, [255.0, 220.0, 0.0]),
+        (1.0, [255.0, 32.0, 0.0]),
+    ];
+    let upper = STOPS.iter().position(|(stop, _)| *stop >= t).unwrap_or(STOPS.len() - 1).max(1);
+    let ((a, from), (b, to)) = (STOPS[upper - 1], STOPS[upper]);
+    let f = ((t - a) / (b - a)).clamp(0.0, 1.0);
+    let mix = |i: usize| (from[i] + (to[i] - from[i]) * f).round() as u8;
+    Rgba([mix(0), mix(1), mix(2), (70.0 + 170.0 * t).round() as u8])
+}
+
+fn overlay(grid: &[f32], size: (u32, u32)) -> RgbaImage {
+    let peak = grid.iter().copied().fold(0.0f32, f32::max);
+    RgbaImage::from_fn(size.0, size.1, |x, y| {
+        let value = grid[(y * size.0 + x) as usize];
+        if peak > 0.0 {
+            ramp(value / peak)
+        } else {
+            Rgba([0, 0, 0, 0])
+        }
+    })
+}
+
+fn hotspots(deaths: &[TracePoint]) -> Vec<Hotspot> {
+    let mut cells: HashMap<(i64, i64), Vec<TracePoint>> = HashMap::new();
+    for death in deaths {
+        let cell = ((death.x / HOTSPOT_CELL).floor() as i64, (death.y / HOTSPOT_CELL).floor() as i64);
+        cells.entry(cell).or_default().push(*death);
+    }
+    let mut hotspots: Vec<Hotspot> = cells
+        .into_values()
+        .map(|points| Hotspot {
+            x: (points.iter().map(|p| p.x).sum::<f64>() / points.len() as f64).round(),
+            y: (points.iter().map(|p| p.y).sum::<f64>() / points.len() as f64).round(),
+            deaths: points.len(),
+        })
+        .collect();
+    hotspots.sort_by(|a, b| b.deaths.cmp(&a.deaths).then(a.x.total_cmp(&b.x)));
+    hotspots.truncate(MAX_HOTSPOTS);
+    hotspots
+}
+
+/// Turn playtest traces into heatmaps of where players spend their time and where they die.
+/// Traces are either recorded positions or input recordings, which are replayed in the headless
+/// simulator against the saved spec. The scene defaults to the top-level entities.
+#[tauri::command]
+pub async fn generate_heatmap(
+    project_path: String,
+    scene: Option<String>,
+    traces: Vec<PlayTrace>,
+    width: Option<u32>,
+) -> Result<Heatmap, String> {
+    let game_spec = spec::load_project_spec(&project_path)?;
+    if let Some(scene_id) = scene.as_deref().filter(|id| !spec::scene_exists(&game_spec, id)) {
+        return Err(format!("Scene '{}' not found", scene_id));
+    }
+    let player = scene_entities(&game_spec, scene.as_deref())
+        .into_iter()
+        .find(|e| e.pointer("/components/input").is_some())
+        .and_then(|e| e.get("name").and_then(Value::as_str))
+        .map(str::to_string);
+
+    let mut positions = Vec::new();
+    let mut deaths = Vec::new();
+    let mut skipped = Vec::new();
+    for (i, trace) in traces.iter().enumerate() {
+        match trace {
+            PlayTrace::Recorded { positions: p, deaths: d } => {
+                positions.extend_from_slice(p);
+                deaths.extend_from_slice(d);
+            }
+            PlayTrace::Inputs { inputs, seconds } => {
+                let Some(player) = &player else {
+                    skipped.push(format!("Trace {}: the scene has no entity with an input component to replay", i + 1));
+                    continue;
+                };
+                let seconds = seconds.unwrap_or(DEFAULT_REPLAY_SECONDS).clamp(FRAME_SECONDS, MAX_REPLAY_SECONDS);
+                let (p, d) = replay(&game_spec, scene.as_deref(), player, inputs, seconds);
+                positions.extend(p);
+                deaths.extend(d);
+            }
+        }
+    }
+
+    let width = width.unwrap_or(DEFAULT_HEATMAP_WIDTH).clamp(16, 2048);
+    let mut composite = render_scene(Some(&project_path), &game_spec, scene.as_deref(), width);
+    let size = composite.dimensions();
+    let bounds = world::world_bounds(&game_spec);
+    // render_scene may narrow a tall world to keep the height in bounds
+    let scale = size.0 as f64 / bounds.width;
+    let position_layer = overlay(&density(&positions, &bounds, scale, size, POSITION_RADIUS), size);
+    let death_layer = overlay(&density(&deaths, &bounds, scale, size, DEATH_RADIUS), size);
+    imageops::overlay(&mut composite, &position_layer, 0, 0);
+    imageops::overlay(&mut composite, &death_layer, 0, 0);
+
+    let data_url = |image: &RgbaImage| encode_png(image).map(|png| format!("data:image/png;base64,{}", png));
+    Ok(Heatmap {
+        scene,
+        width: size.0,
+        height: size.1,
+        positions: HeatmapLayer {
+            data_url: data_url(&position_layer)?,
+            samples: positions.len(),
+        },
+        deaths: HeatmapLayer {
+            data_url: data_url(&death_layer)?,
+            samples: deaths.len(),
+        },
+        death_hotspots: hotspots(&deaths),
+        composite_data_url: data_url(&composite)?,
+        skipped,
+    })
+}
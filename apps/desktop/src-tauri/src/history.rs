@@ -0,0 +1,147 @@
+use crate::assets::AssetManifest;
+use crate::{encryption, spec};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs;
+use std::path::PathBuf;
+
+/// Undo snapshots; they hold whole specs, so encrypted projects encrypt them too
+pub const HISTORY_DIR: &str = ".promptplay/history";
+const INDEX_FILE: &str = "index.json";
+const MAX_ENTRIES: usize = 50;
+
+/// A project file an operation moved; undo moves it back
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct FileMove {
+    /// Project-relative paths with forward slashes
+    pub from: String,
+    pub to: String,
+}
+
+/// One undoable backend operation: the spec as it was before the change
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct HistoryEntry {
+    pub id: String,
+    pub label: String,
+    pub timestamp: u64,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub moved_files: Vec<FileMove>,
+}
+
+fn history_dir(project_path: &str) -> PathBuf {
+    PathBuf::from(project_path).join(HISTORY_DIR)
+}
+
+fn now_millis() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+fn load_index(project_path: &str) -> Result<Vec<HistoryEntry>, String> {
+    let path = history_dir(project_path).join(INDEX_FILE);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = encryption::read_to_string(&path)?;
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse history index: {}", e))
+}
+
+fn save_index(project_path: &str, entries: &[HistoryEntry]) -> Result<(), String> {
+    let path = history_dir(project_path).join(INDEX_FILE);
+    let content = serde_json::to_string_pretty(entries)
+        .map_err(|e| format!("Failed to serialize history index: {}", e))?;
+    encryption::write(&path, content.as_bytes())
+}
+
+/// Store the pre-change spec so the operation can be undone as a single step
+pub fn record(project_path: &str, label: &str, before: &Value) -> Result<HistoryEntry, String> {
+    record_with_moves(project_path, label, before, Vec::new())
+}
+
+/// Like [`record`], for operations that also moved files in the project
+pub fn record_with_moves(
+    project_path: &str,
+    label: &str,
+    before: &Value,
+    moved_files: Vec<FileMove>,
+) -> Result<HistoryEntry, String> {
+    let dir = history_dir(project_path);
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create history directory: {}", e))?;
+
+    // Several records can land in the same millisecond, so the id alone names the snapshot file
+    let entry = HistoryEntry {
+        id: uuid::Uuid::new_v4().to_string(),
+        label: label.to_string(),
+        timestamp: now_millis(),
+        moved_files,
+    };
+
+    let snapshot = serde_json::to_string(before)
+        .map_err(|e| format!("Failed to serialize history snapshot: {}", e))?;
+    encryption::write(&dir.join(format!("{}.json", entry.id)), snapshot.as_bytes())?;
+
+    let mut entries = load_index(project_path)?;
+    entries.push(entry.clone());
+
+    // Drop the oldest snapshots beyond the retention limit
+    while entries.len() > MAX_ENTRIES {
+        let old = entries.remove(0);
+        let _ = fs::remove_file(dir.join(format!("{}.json", old.id)));
+    }
+
+    save_index(project_path, &entries)?;
+    Ok(entry)
+}
+
+/// Snapshot the current game.json, then save the new spec, as one undo entry
+pub fn save_with_history(project_path: &str, label: &str, after: &Value) -> Result<HistoryEntry, String> {
+    let before = spec::load_project_spec(project_path)?;
+    let entry = record(project_path, label, &before)?;
+    spec::save_project_spec(project_path, after)?;
+    Ok(entry)
+}
+
+/// List undoable backend operations, newest last
+#[tauri::command]
+pub async fn list_history(project_path: String) -> Result<Vec<HistoryEntry>, String> {
+    load_index(&project_path)
+}
+
+/// Revert game.json to the state before the most recent backend operation
+#[tauri::command]
+pub async fn undo_last_change(project_path: String) -> Result<Option<HistoryEntry>, String> {
+    let mut entries = load_index(&project_path)?;
+    let Some(entry) = entries.pop() else {
+        return Ok(None);
+    };
+
+    let snapshot_path = history_dir(&project_path).join(format!("{}.json", entry.id));
+    let content = encryption::read_to_string(&snapshot_path)?;
+    let before: Value = serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse history snapshot: {}", e))?;
+
+    // Put moved files back before the spec that names their old paths returns
+    let root = PathBuf::from(&project_path);
+    for moved in entry.moved_files.iter().rev() {
+        let (from, to) = (root.join(&moved.from), root.join(&moved.to));
+        if to.exists() && !from.exists() {
+            if let Some(parent) = from.parent() {
+                fs::create_dir_all(parent).map_err(|e| format!("Failed to restore {}: {}", moved.from, e))?;
+            }
+            fs::rename(&to, &from).map_err(|e| format!("Failed to move {} back to {}: {}", moved.to, moved.from, e))?;
+        }
+        let mut manifest = AssetManifest::load(&project_path)?;
+        if let Some(asset) = manifest.find_mut(&moved.to) {
+            asset.path = moved.from.clone();
+            manifest.save(&project_path)?;
+        }
+    }
+
+    spec::save_project_spec(&project_path, &before)?;
+    let _ = fs::remove_file(&snapshot_path);
+    save_index(&project_path, &entries)?;
+
+    Ok(Some(entry))
+}
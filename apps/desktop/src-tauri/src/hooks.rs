@@ -0,0 +1,343 @@
+use crate::notifications::{self, NotificationCategory};
+use crate::tools::{self, ToolId};
+use crate::workspace::{self, WorkspaceManager};
+use crate::{backup, health, permissions};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Manager, State};
+
+const HOOKS_FILE: &str = ".promptplay/hooks.toml";
+pub const LOG_FILE: &str = ".promptplay/hooks.log";
+/// Runs kept in the log; older ones are dropped as new ones come in
+const MAX_LOG_ENTRIES: usize = 500;
+/// Output kept per run in the log
+const MAX_OUTPUT_CHARS: usize = 4000;
+/// Event sent to the editor after each hook action
+pub const HOOK_RUN_EVENT: &str = "hook-run";
+
+/// Editor events a hook can be attached to
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "kebab-case")]
+pub enum HookEvent {
+    OnSave,
+    OnExport,
+    OnAiApply,
+}
+
+/// What a hook does. Tools go through the same allowlist as `run_tool`.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(tag = "action", rename_all = "kebab-case")]
+pub enum HookAction {
+    /// Validation, asset checks and the project's lint rules
+    Lint,
+    /// A project backup labelled with the event
+    Snapshot,
+    Tool {
+        tool: ToolId,
+        #[serde(default)]
+        args: Vec<String>,
+    },
+}
+
+/// One `[[hook]]` table in hooks.toml
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Hook {
+    pub on: HookEvent,
+    #[serde(flatten)]
+    pub action: HookAction,
+    /// Later hooks for the event still run when this one fails, unless this is set
+    #[serde(default, rename = "stop-on-failure")]
+    pub stop_on_failure: bool,
+}
+
+#[derive(Debug, Deserialize, Default)]
+struct HooksFile {
+    #[serde(default)]
+    hook: Vec<Hook>,
+}
+
+/// A finished hook action, as logged and sent to the editor
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct HookRun {
+    pub event: HookEvent,
+    /// None when the hooks couldn't be run at all, e.g. an invalid hooks.toml
+    pub action: Option<HookAction>,
+    pub ok: bool,
+    pub summary: String,
+    pub output: String,
+    pub started_at: u64,
+    pub duration_ms: u64,
+}
+
+/// Events with hooks running per project, so a burst of saves doesn't queue a run per save
+#[derive(Default)]
+pub struct HooksState(Mutex<HashSet<(String, HookEvent)>>);
+
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// The project's hooks and the hooks.toml they came from; an absent file means none
+fn load_with_source(project_path: &str) -> Result<(Vec<Hook>, String), String> {
+    let path = PathBuf::from(project_path).join(HOOKS_FILE);
+    let content = match fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok((Vec::new(), String::new())),
+        Err(e) => return Err(format!("Failed to read {}: {}", HOOKS_FILE, e)),
+    };
+    let file: HooksFile = toml::from_str(&content).map_err(|e| format!("Invalid {}: {}", HOOKS_FILE, e))?;
+    Ok((file.hook, content))
+}
+
+/// The project's hooks; an absent hooks.toml means none
+pub fn load(project_path: &str) -> Result<Vec<Hook>, String> {
+    load_with_source(project_path).map(|(hooks, _)| hooks)
+}
+
+fn describe(hook: &Hook) -> String {
+    let event = serde_json::to_value(hook.on)
+        .ok()
+        .and_then(|v| v.as_str().map(str::to_string))
+        .unwrap_or_default();
+    match &hook.action {
+        HookAction::Lint => format!("{}: lint", event),
+        HookAction::Snapshot => format!("{}: snapshot", event),
+        HookAction::Tool { tool, args } => {
+            let tool = serde_json::to_value(tool)
+                .ok()
+                .and_then(|v| v.as_str().map(str::to_string))
+                .unwrap_or_default();
+            format!("{}: {} {}", event, tool, args.join(" "))
+        }
+    }
+}
+
+/// Whether the user lets this version of the project's hooks.toml run, asking the first time.
+/// Lint and snapshot hooks only run the app's own code, so they need no trust.
+fn trusted(app: &AppHandle, project_path: &str, hooks: &[Hook], source: &str) -> Result<bool, String> {
+    let commands: Vec<String> = hooks
+        .iter()
+        .filter(|h| matches!(h.action, HookAction::Tool { .. }))
+        .map(describe)
+        .collect();
+    if commands.is_empty() {
+        return Ok(true);
+    }
+    permissions::check_project_trust(app, project_path, HOOKS_FILE, &commands.join("\n"), source)
+}
+
+fn truncate(output: String) -> String {
+    match output.char_indices().nth(MAX_OUTPUT_CHARS) {
+        Some((end, _)) => format!("{}…", &output[..end]),
+        None => output,
+    }
+}
+
+/// Run one action; Ok carries (passed, summary, output)
+fn run_action(
+    app: &AppHandle,
+    project_path: &str,
+    event: HookEvent,
+    action: &HookAction,
+) -> Result<(bool, String, String), String> {
+    match action {
+        HookAction::Lint => {
+            let report = health::check(project_path);
+            let output = report
+                .diagnostics
+                .iter()
+                .map(|d| format!("{:?} {}: {}", d.severity, d.path, d.message))
+                .collect::<Vec<_>>()
+                .join("\n");
+            Ok((
+                report.errors == 0,
+                format!("{} errors, {} warnings", report.errors, report.warnings),
+                output,
+            ))
+        }
+        HookAction::Snapshot => {
+            let label = serde_json::to_value(event)
+                .ok()
+                .and_then(|v| v.as_str().map(str::to_string))
+                .unwrap_or_else(|| "hook".to_string());
+            let backup = backup::create(app, project_path, &label)?;
+            Ok((true, format!("Snapshot {}", backup.file_name), backup.path))
+        }
+        HookAction::Tool { tool, args } => {
+            let run = tools::run_unattended(app, project_path, *tool, args)?;
+            let output = [run.stdout, run.stderr]
+                .into_iter()
+                .filter(|s| !s.is_empty())
+                .collect::<Vec<_>>()
+                .join("\n");
+            let summary = match run.exit_code {
+                Some(code) => format!("Exited with {} in {} ms", code, run.duration_ms),
+                None => "Stopped by a signal".to_string(),
+            };
+            Ok((run.exit_code == Some(0), summary, output))
+        }
+    }
+}
+
+fn append_log(project_path: &str, run: &HookRun) -> Result<(), String> {
+    let path = PathBuf::from(project_path).join(LOG_FILE);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create .promptplay directory: {}", e))?;
+    }
+    let existing = fs::read_to_string(&path).unwrap_or_default();
+    let mut lines: Vec<&str> = existing.lines().filter(|l| !l.trim().is_empty()).collect();
+    let entry = serde_json::to_string(run).map_err(|e| format!("Failed to serialize hook run: {}", e))?;
+    lines.drain(..lines.len().saturating_sub(MAX_LOG_ENTRIES - 1));
+    lines.push(&entry);
+    fs::write(&path, format!("{}\n", lines.join("\n"))).map_err(|e| format!("Failed to write {}: {}", LOG_FILE, e))
+}
+
+/// Log a run and send it to the project's windows; a run that couldn't be logged says so
+fn record(app: &AppHandle, project_path: &str, mut run: HookRun) -> HookRun {
+    if let Err(e) = append_log(project_path, &run) {
+        run.summary = format!("{} (not logged: {})", run.summary, e);
+    }
+    workspace::emit_to_project(app, Path::new(project_path), HOOK_RUN_EVENT, &run);
+    run
+}
+
+/// Run every hook for `event` in order, logging each action
+fn run_hooks(app: &AppHandle, project_path: &str, event: HookEvent, hooks: &[Hook]) -> Vec<HookRun> {
+    let mut runs = Vec::new();
+    for hook in hooks.iter().filter(|h| h.on == event) {
+        let started_at = now_millis();
+        let started = Instant::now();
+        let (ok, summary, output) = match run_action(app, project_path, event, &hook.action) {
+            Ok(result) => result,
+            Err(e) => (false, e, String::new()),
+        };
+        let run = record(
+            app,
+            project_path,
+            HookRun {
+                event,
+                action: Some(hook.action.clone()),
+                ok,
+                summary,
+                output: truncate(output),
+                started_at,
+                duration_ms: started.elapsed().as_millis() as u64,
+            },
+        );
+        if !ok {
+            notifications::task_finished(app, NotificationCategory::Hooks, "Hook failed", &run.summary);
+        }
+        runs.push(run);
+        if !ok && hook.stop_on_failure {
+            break;
+        }
+    }
+    runs
+}
+
+/// Run the project's hooks for `event` in the background. Skipped while the same event's hooks
+/// are still running for the project, for projects opened read-only, and until the user trusts
+/// the project's hooks. An unreadable hooks.toml is logged as a failed run.
+pub fn fire(app: &AppHandle, project_path: &str, event: HookEvent) {
+    if app.state::<WorkspaceManager>().is_read_only(Path::new(project_path)) {
+        return;
+    }
+    let (hooks, source) = match load_with_source(project_path) {
+        Ok((hooks, source)) if hooks.iter().any(|h| h.on == event) => (hooks, source),
+        Ok(_) => return,
+        Err(e) => {
+            record(
+                app,
+                project_path,
+                HookRun {
+                    event,
+                    action: None,
+                    ok: false,
+                    summary: e,
+                    output: String::new(),
+                    started_at: now_millis(),
+                    duration_ms: 0,
+                },
+            );
+            return;
+        }
+    };
+    let key = (project_path.to_string(), event);
+    let state = app.state::<HooksState>();
+    match state.0.lock() {
+        Ok(mut running) if running.insert(key.clone()) => {}
+        _ => return,
+    }
+
+    let app = app.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        // The prompt blocks, so it's asked here rather than on the save that fired the hooks
+        if trusted(&app, &key.0, &hooks, &source).unwrap_or(false) {
+            run_hooks(&app, &key.0, event, &hooks);
+        }
+        if let Ok(mut running) = app.state::<HooksState>().0.lock() {
+            running.remove(&key);
+        }
+    });
+}
+
+/// The hooks configured in the project's .promptplay/hooks.toml
+#[tauri::command]
+pub async fn list_hooks(project_path: String) -> Result<Vec<Hook>, String> {
+    load(&project_path)
+}
+
+/// Run the hooks for an event now and wait for them. The editor calls this with `on-ai-apply`
+/// after applying an AI change, and from the "run hooks" action.
+#[tauri::command]
+pub async fn run_project_hooks(
+    app: AppHandle,
+    project_path: String,
+    event: HookEvent,
+    state: State<'_, HooksState>,
+) -> Result<Vec<HookRun>, String> {
+    let (hooks, source) = load_with_source(&project_path)?;
+    let key = (project_path.clone(), event);
+    if !state.0.lock().map_err(|e| format!("Lock error: {}", e))?.insert(key.clone()) {
+        return Err("These hooks are already running".to_string());
+    }
+    let handle = app.clone();
+    let runs = tauri::async_runtime::spawn_blocking(move || {
+        if trusted(&handle, &project_path, &hooks, &source)? {
+            Ok(run_hooks(&handle, &project_path, event, &hooks))
+        } else {
+            Err(format!("The hooks in {} aren't trusted, so they weren't run", HOOKS_FILE))
+        }
+    })
+    .await
+    .map_err(|e| format!("Hook task failed: {}", e))
+    .and_then(|runs| runs);
+    if let Ok(mut running) = state.0.lock() {
+        running.remove(&key);
+    }
+    runs
+}
+
+/// The most recent hook runs, newest first
+#[tauri::command]
+pub async fn get_hook_log(project_path: String, limit: Option<usize>) -> Result<Vec<HookRun>, String> {
+    let content = match fs::read_to_string(PathBuf::from(&project_path).join(LOG_FILE)) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(format!("Failed to read {}: {}", LOG_FILE, e)),
+    };
+    Ok(content
+        .lines()
+        .rev()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .take(limit.unwrap_or(MAX_LOG_ENTRIES))
+        .collect())
+}
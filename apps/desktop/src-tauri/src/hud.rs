@@ -0,0 +1,247 @@
+use crate::spec;
+use crate::validation::Diagnostic;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashSet;
+
+/// Game state fields a UI element may bind to via `gameState.<field>`
+pub const GAME_STATE_FIELDS: &[&str] = &[
+    "score",
+    "highScore",
+    "lives",
+    "maxLives",
+    "level",
+    "timeRemaining",
+    "timeElapsed",
+    "combo",
+    "multiplier",
+];
+
+/// Actions a button element can trigger in the runtime
+pub const BUTTON_ACTIONS: &[&str] = &["restart", "pause", "resume"];
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum UiElementKind {
+    Label,
+    HealthBar,
+    Button,
+    /// Baked minimap image with live player and collectible markers
+    Minimap,
+}
+
+/// Screen-space anchor; offsets are applied relative to this point
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum UiAnchor {
+    #[default]
+    TopLeft,
+    TopCenter,
+    TopRight,
+    CenterLeft,
+    Center,
+    CenterRight,
+    BottomLeft,
+    BottomCenter,
+    BottomRight,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct UiStyle {
+    pub color: Option<String>,
+    pub background: Option<String>,
+    pub font_size: Option<f64>,
+}
+
+/// One entry of the spec's `ui` section
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct UiElement {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub kind: UiElementKind,
+    #[serde(default)]
+    pub anchor: UiAnchor,
+    #[serde(default)]
+    pub offset_x: f64,
+    #[serde(default)]
+    pub offset_y: f64,
+    pub width: Option<f64>,
+    pub height: Option<f64>,
+    /// Label text; `{value}` is replaced with the bound value
+    pub text: Option<String>,
+    /// `gameState.<field>` or `entity.<name>.health`
+    pub binding: Option<String>,
+    /// Button action, one of [`BUTTON_ACTIONS`]
+    pub action: Option<String>,
+    /// Minimap scene id; defaults to the top-level entity list
+    pub scene: Option<String>,
+    #[serde(default)]
+    pub style: UiStyle,
+    #[serde(default = "default_visible")]
+    pub visible: bool,
+}
+
+fn default_visible() -> bool {
+    true
+}
+
+/// JSON description of the `ui` section, shared with the frontend and the AI prompt
+pub fn ui_schema() -> Value {
+    json!({
+        "type": "array",
+        "items": {
+            "type": "object",
+            "required": ["id", "type"],
+            "properties": {
+                "id": { "type": "string" },
+                "type": { "enum": ["label", "healthBar", "button", "minimap"] },
+                "anchor": {
+                    "enum": [
+                        "topLeft", "topCenter", "topRight",
+                        "centerLeft", "center", "centerRight",
+                        "bottomLeft", "bottomCenter", "bottomRight"
+                    ]
+                },
+                "offsetX": { "type": "number" },
+                "offsetY": { "type": "number" },
+                "width": { "type": "number" },
+                "height": { "type": "number" },
+                "text": { "type": "string" },
+                "binding": { "type": "string", "pattern": "^(gameState\\.[A-Za-z]+|entity\\..+\\.health)$" },
+                "action": { "enum": BUTTON_ACTIONS },
+                "scene": { "type": "string" },
+                "style": {
+                    "type": "object",
+                    "properties": {
+                        "color": { "type": "string" },
+                        "background": { "type": "string" },
+                        "fontSize": { "type": "number" }
+                    }
+                },
+                "visible": { "type": "boolean" }
+            }
+        }
+    })
+}
+
+/// Check that a binding points at a known game state field or an entity with health
+fn validate_binding(spec: &Value, binding: &str) -> Result<(), String> {
+    if let Some(field) = binding.strip_prefix("gameState.") {
+        if GAME_STATE_FIELDS.contains(&field) {
+            return Ok(());
+        }
+        return Err(format!("Unknown game state field '{}'", field));
+    }
+
+    if let Some(name) = binding
+        .strip_prefix("entity.")
+        .and_then(|rest| rest.strip_suffix(".health"))
+    {
+        let entity = spec::find_entity(spec, name)
+            .ok_or_else(|| format!("Binding references unknown entity '{}'", name))?;
+        if entity.pointer("/components/health").is_none() {
+            return Err(format!("Entity '{}' has no health component", name));
+        }
+        return Ok(());
+    }
+
+    Err(format!(
+        "Binding '{}' must be 'gameState.<field>' or 'entity.<name>.health'",
+        binding
+    ))
+}
+
+/// Validate the optional `ui` section of a spec
+pub fn validate_ui(spec: &Value) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+
+    let elements = match spec.get("ui") {
+        None => return diagnostics,
+        Some(Value::Array(elements)) => elements,
+        Some(_) => {
+            diagnostics.push(Diagnostic::error("/ui", "ui must be an array of elements"));
+            return diagnostics;
+        }
+    };
+
+    let mut seen_ids = HashSet::new();
+
+    for (i, raw) in elements.iter().enumerate() {
+        let path = format!("/ui/{}", i);
+
+        let element: UiElement = match serde_json::from_value(raw.clone()) {
+            Ok(element) => element,
+            Err(e) => {
+                diagnostics.push(Diagnostic::error(&path, format!("Invalid UI element: {}", e)));
+                continue;
+            }
+        };
+
+        if !seen_ids.insert(element.id.clone()) {
+            diagnostics.push(Diagnostic::error(
+                format!("{}/id", path),
+                format!("Duplicate UI element id '{}'", element.id),
+            ));
+        }
+
+        if let Some(binding) = &element.binding {
+            if let Err(message) = validate_binding(spec, binding) {
+                diagnostics.push(Diagnostic::error(format!("{}/binding", path), message));
+            }
+        }
+
+        match element.kind {
+            UiElementKind::Label => {
+                if element.text.is_none() && element.binding.is_none() {
+                    diagnostics.push(Diagnostic::warning(
+                        &path,
+                        "Label has neither text nor a binding and will render empty",
+                    ));
+                }
+            }
+            UiElementKind::HealthBar => {
+                if element.binding.is_none() {
+                    diagnostics.push(Diagnostic::error(
+                        format!("{}/binding", path),
+                        "Health bar requires a binding",
+                    ));
+                }
+            }
+            UiElementKind::Button => match element.action.as_deref() {
+                None => diagnostics.push(Diagnostic::error(
+                    format!("{}/action", path),
+                    "Button requires an action",
+                )),
+                Some(action) if !BUTTON_ACTIONS.contains(&action) => {
+                    diagnostics.push(Diagnostic::error(
+                        format!("{}/action", path),
+                        format!("Unknown button action '{}'", action),
+                    ))
+                }
+                Some(_) => {}
+            },
+            UiElementKind::Minimap => {
+                if let Some(scene) = element.scene.as_deref().filter(|s| !spec::scene_exists(spec, s)) {
+                    diagnostics.push(Diagnostic::error(
+                        format!("{}/scene", path),
+                        format!("Minimap references unknown scene '{}'", scene),
+                    ));
+                }
+            }
+        }
+
+        if element.width.is_some_and(|w| w <= 0.0) || element.height.is_some_and(|h| h <= 0.0) {
+            diagnostics.push(Diagnostic::error(&path, "UI element size must be positive"));
+        }
+    }
+
+    diagnostics
+}
+
+/// Get the schema of the `ui` spec section
+#[tauri::command]
+pub async fn get_ui_schema() -> Result<Value, String> {
+    Ok(ui_schema())
+}
@@ -0,0 +1,210 @@
+use crate::ai_client::AIClientState;
+use crate::settings::AppSettings;
+use crate::{ai_context, json_guard, paths, spec, workspace};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, ShortcutState};
+
+/// The project's idea backlog
+const IDEAS_FILE: &str = ".promptplay/ideas.json";
+/// Quick-capture shortcut when the settings don't name one
+pub const DEFAULT_CAPTURE_SHORTCUT: &str = "CmdOrCtrl+Shift+Space";
+const MAX_IDEA_CHARS: usize = 4000;
+/// Spec context sent with a proposal request
+const PROPOSAL_CONTEXT_TOKENS: usize = 3000;
+
+const PROPOSAL_PROMPT: &str = r#"You turn a game designer's quick note into a proposed change to their PromptPlay game spec (game.json).
+You get a summary of the spec and the note. Reply with only a JSON object:
+{"summary": "<one sentence describing the change>", "operations": [<RFC 6902 JSON Patch operations against game.json>]}.
+Keep the patch minimal and use paths that exist in the summary; entities are under /entities by index. If the note is not actionable as a spec change, reply with an empty operations array and say why in the summary."#;
+
+/// A spec change the AI drafted from an idea; kept for review, never applied automatically
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PatchProposal {
+    pub summary: String,
+    /// RFC 6902 operations against game.json
+    pub operations: Vec<Value>,
+}
+
+/// One captured note in `.promptplay/ideas.json`
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Idea {
+    pub id: String,
+    pub text: String,
+    pub created_at: u64,
+    /// A proposal was requested and hasn't come back yet
+    #[serde(default)]
+    pub proposal_pending: bool,
+    #[serde(default)]
+    pub proposal: Option<PatchProposal>,
+    #[serde(default)]
+    pub proposal_error: Option<String>,
+}
+
+fn ideas_path(project_path: &str) -> PathBuf {
+    paths::project_root(project_path).join(IDEAS_FILE)
+}
+
+fn load_ideas(project_path: &str) -> Result<Vec<Idea>, String> {
+    match fs::read_to_string(ideas_path(project_path)) {
+        Ok(content) => serde_json::from_str(&content).map_err(|e| format!("Failed to parse {}: {}", IDEAS_FILE, e)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(format!("Failed to read {}: {}", IDEAS_FILE, e)),
+    }
+}
+
+fn save_ideas(project_path: &str, ideas: &[Idea]) -> Result<(), String> {
+    let path = ideas_path(project_path);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create .promptplay directory: {}", e))?;
+    }
+    let content = serde_json::to_string_pretty(ideas).map_err(|e| format!("Failed to serialize ideas: {}", e))?;
+    fs::write(&path, content).map_err(|e| format!("Failed to write {}: {}", IDEAS_FILE, e))
+}
+
+/// The JSON object in a reply, tolerating prose or a code fence around it
+fn parse_proposal(reply: &str) -> Result<PatchProposal, String> {
+    let start = reply.find('{').ok_or("The AI reply contained no proposal")?;
+    let end = reply.rfind('}').ok_or("The AI reply contained no proposal")?;
+    let proposal: PatchProposal =
+        json_guard::parse(&reply.as_bytes()[start..=end], "AI proposal", &json_guard::AI_REPLY_LIMITS)?;
+    if let Some(bad) = proposal.operations.iter().find(|op| {
+        !matches!(op.get("op").and_then(Value::as_str), Some("add" | "remove" | "replace" | "move" | "copy" | "test"))
+            || op.get("path").and_then(Value::as_str).is_none()
+    }) {
+        return Err(format!("The AI proposed an invalid patch operation: {}", bad));
+    }
+    Ok(proposal)
+}
+
+async fn draft_proposal(app: &AppHandle, project_path: &str, text: &str) -> Result<PatchProposal, String> {
+    let game_spec = spec::load_project_spec(project_path)?;
+    let context = ai_context::summarize(&game_spec, PROPOSAL_CONTEXT_TOKENS);
+    let prompt = format!("Spec summary:\n{}\n\nNote:\n{}", context.summary, text);
+    let state = app.state::<AIClientState>();
+    let client = state.0.lock().await;
+    if !client.has_api_key() {
+        return Err("API key not configured".to_string());
+    }
+    parse_proposal(&client.complete(PROPOSAL_PROMPT.to_string(), prompt).await?)
+}
+
+/// Fill in the proposal of a captured idea and tell the windows showing the project
+async fn attach_proposal(app: AppHandle, project_path: String, id: String, text: String) {
+    let result = draft_proposal(&app, &project_path, &text).await;
+    let Ok(mut ideas) = load_ideas(&project_path) else {
+        return;
+    };
+    let Some(idea) = ideas.iter_mut().find(|idea| idea.id == id) else {
+        return;
+    };
+    idea.proposal_pending = false;
+    match result {
+        Ok(proposal) => idea.proposal = Some(proposal),
+        Err(error) => idea.proposal_error = Some(error),
+    }
+    let idea = idea.clone();
+    if save_ideas(&project_path, &ideas).is_ok() {
+        workspace::emit_to_project(&app, &paths::project_root(&project_path), "idea-updated", idea);
+    }
+}
+
+/// Register the quick-capture shortcut, replacing the previous one
+pub fn register_capture_shortcut(app: &AppHandle, shortcut: &str) -> Result<(), String> {
+    let shortcuts = app.global_shortcut();
+    shortcuts
+        .unregister_all()
+        .map_err(|e| format!("Failed to clear global shortcuts: {}", e))?;
+    shortcuts
+        .register(shortcut)
+        .map_err(|e| format!("Failed to register shortcut {}: {}", shortcut, e))
+}
+
+/// Global shortcut handler: bring the editor forward and open its quick-capture prompt
+pub fn on_capture_shortcut(app: &AppHandle, state: ShortcutState) {
+    if state != ShortcutState::Pressed {
+        return;
+    }
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+    let _ = app.emit_to("main", "quick-capture", ());
+}
+
+/// The configured shortcut, registered at startup
+pub fn capture_shortcut(app: &AppHandle) -> String {
+    AppSettings::load(app)
+        .capture_shortcut
+        .unwrap_or_else(|| DEFAULT_CAPTURE_SHORTCUT.to_string())
+}
+
+/// Append a note to the project's idea backlog. With `propose`, the AI drafts a spec patch for it
+/// in the background; the idea is updated and `idea-updated` emitted when it arrives.
+#[tauri::command]
+pub async fn capture_idea(
+    app: AppHandle,
+    project_path: String,
+    text: String,
+    propose: Option<bool>,
+) -> Result<Idea, String> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Err("The idea is empty".to_string());
+    }
+    if text.chars().count() > MAX_IDEA_CHARS {
+        return Err(format!("Ideas are limited to {} characters", MAX_IDEA_CHARS));
+    }
+
+    let propose = propose.unwrap_or(false);
+    let idea = Idea {
+        id: uuid::Uuid::new_v4().to_string(),
+        text: text.to_string(),
+        created_at: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0),
+        proposal_pending: propose,
+        proposal: None,
+        proposal_error: None,
+    };
+    let mut ideas = load_ideas(&project_path)?;
+    ideas.push(idea.clone());
+    save_ideas(&project_path, &ideas)?;
+
+    if propose {
+        tauri::async_runtime::spawn(attach_proposal(app, project_path, idea.id.clone(), idea.text.clone()));
+    }
+    Ok(idea)
+}
+
+/// The project's idea backlog, oldest first
+#[tauri::command]
+pub async fn list_ideas(project_path: String) -> Result<Vec<Idea>, String> {
+    load_ideas(&project_path)
+}
+
+#[tauri::command]
+pub async fn get_capture_shortcut(app: AppHandle) -> Result<String, String> {
+    Ok(capture_shortcut(&app))
+}
+
+/// Change the quick-capture shortcut, e.g. "CmdOrCtrl+Alt+N"; None restores the default
+#[tauri::command]
+pub async fn set_capture_shortcut(app: AppHandle, shortcut: Option<String>) -> Result<(), String> {
+    let effective = shortcut.clone().unwrap_or_else(|| DEFAULT_CAPTURE_SHORTCUT.to_string());
+    if let Err(e) = register_capture_shortcut(&app, &effective) {
+        // Keep the old shortcut working when the new one is taken or malformed
+        let _ = register_capture_shortcut(&app, &capture_shortcut(&app));
+        return Err(e);
+    }
+    let mut settings = AppSettings::load(&app);
+    settings.capture_shortcut = shortcut;
+    settings.save(&app)
+}
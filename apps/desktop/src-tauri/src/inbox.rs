@@ -0,0 +1,220 @@
+use crate::assets::{self, AssetEntry, ASSETS_DIR};
+use crate::paths;
+use crate::workspace::{self, WorkspaceManager};
+use crate::project_settings::ProjectSettings;
+use notify::{Config, Event, RecommendedWatcher, RecursiveMode, Watcher};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{channel, RecvTimeoutError};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, State};
+
+/// Art tools write exports in several passes; import once the inbox has been quiet this long
+const SETTLE_MILLIS: u64 = 1000;
+
+/// Art inbox of a project (`inbox` in project.json)
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct InboxSettings {
+    /// Folder Aseprite/Krita export scripts write into; relative paths resolve against the project
+    pub path: String,
+    /// Delete files from the inbox once they're filed into assets/
+    #[serde(default = "default_remove_after_import")]
+    pub remove_after_import: bool,
+}
+
+fn default_remove_after_import() -> bool {
+    true
+}
+
+/// Payload of the `inbox-imported` event
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct InboxReport {
+    pub imported: Vec<AssetEntry>,
+    pub failed: Vec<String>,
+}
+
+/// Watcher of the open project's inbox
+#[derive(Default)]
+pub struct InboxState(Mutex<Option<RecommendedWatcher>>);
+
+fn inbox_dir(project_path: &str, inbox: &InboxSettings) -> PathBuf {
+    let path = PathBuf::from(&inbox.path);
+    if path.is_absolute() {
+        path
+    } else {
+        PathBuf::from(project_path).join(path)
+    }
+}
+
+/// Half-written or editor scratch files that shouldn't be imported
+fn is_partial(path: &Path) -> bool {
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    name.starts_with('.') || name.ends_with('~') || name.ends_with(".tmp") || name.ends_with(".part")
+}
+
+/// Whether `destination` already holds this version of `source`
+fn already_filed(source: &Path, destination: &Path) -> bool {
+    let (Ok(src), Ok(dst)) = (fs::metadata(source), fs::metadata(destination)) else {
+        return false;
+    };
+    src.len() == dst.len() && matches!((src.modified(), dst.modified()), (Ok(s), Ok(d)) if s <= d)
+}
+
+/// File the given inbox files into assets/, keeping their folder layout under the inbox
+fn import_files(project_path: &str, inbox: &InboxSettings, files: &BTreeSet<PathBuf>) -> InboxReport {
+    let root = paths::canonical(&inbox_dir(project_path, inbox));
+    let mut report = InboxReport::default();
+
+    for file in files {
+        if !file.is_file() || is_partial(file) {
+            continue;
+        }
+        let Some(relative) = paths::relative_to(&paths::canonical(file), &root) else {
+            continue;
+        };
+        let subdir = relative
+            .parent()
+            .map(paths::to_spec_path)
+            .unwrap_or_default();
+        let destination = PathBuf::from(project_path)
+            .join(ASSETS_DIR)
+            .join(&subdir)
+            .join(assets::normalized_file_name(&relative.file_name().unwrap_or_default().to_string_lossy()));
+        if already_filed(file, &destination) {
+            continue;
+        }
+
+        match assets::import_file(project_path, file, &subdir, None) {
+            Ok(entry) => {
+                if inbox.remove_after_import {
+                    if let Err(e) = fs::remove_file(file) {
+                        report.failed.push(format!("Imported {} but failed to remove it from the inbox: {}", entry.path, e));
+                    }
+                }
+                report.imported.push(entry);
+            }
+            Err(e) => report.failed.push(e),
+        }
+    }
+    report
+}
+
+fn inbox_files(dir: &Path) -> BTreeSet<PathBuf> {
+    let mut files = BTreeSet::new();
+    let mut pending = vec![dir.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        for entry in fs::read_dir(&dir).into_iter().flatten().flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                pending.push(path);
+            } else {
+                files.insert(path);
+            }
+        }
+    }
+    files
+}
+
+fn emit_report(app: &AppHandle, project_path: &str, report: &InboxReport) {
+    if !report.imported.is_empty() || !report.failed.is_empty() {
+        workspace::emit_to_project(app, Path::new(project_path), "inbox-imported", report);
+    }
+}
+
+impl InboxState {
+    /// Watch the project's configured inbox, first filing anything dropped there while it was closed
+    pub fn start(&self, app: &AppHandle, project_path: &str) -> Result<Option<PathBuf>, String> {
+        self.stop()?;
+        let Some(inbox) = ProjectSettings::load(project_path)?.inbox else {
+            return Ok(None);
+        };
+        let dir = inbox_dir(project_path, &inbox);
+        let assets_root = paths::canonical(&PathBuf::from(project_path).join(ASSETS_DIR));
+        fs::create_dir_all(&dir).map_err(|e| format!("Failed to create inbox folder {}: {}", dir.display(), e))?;
+        if paths::relative_to(&paths::canonical(&dir), &assets_root).is_some() {
+            return Err(format!("The inbox can't be inside {}/", ASSETS_DIR));
+        }
+
+        let (tx, rx) = channel();
+        let mut watcher = RecommendedWatcher::new(
+            move |res: Result<Event, notify::Error>| {
+                if let Ok(event) = res {
+                    let _ = tx.send(event);
+                }
+            },
+            Config::default(),
+        )
+        .map_err(|e| format!("Failed to create inbox watcher: {}", e))?;
+        watcher
+            .watch(&paths::for_io(&dir), RecursiveMode::Recursive)
+            .map_err(|e| format!("Failed to watch inbox folder {}: {}", dir.display(), e))?;
+
+        let app = app.clone();
+        let project_path = project_path.to_string();
+        let existing = inbox_files(&dir);
+        std::thread::spawn(move || {
+            emit_report(&app, &project_path, &import_files(&project_path, &inbox, &existing));
+
+            // Collect changes until the inbox goes quiet; the loop ends when the watcher is dropped
+            let mut pending = BTreeSet::new();
+            loop {
+                match rx.recv_timeout(Duration::from_millis(SETTLE_MILLIS)) {
+                    Ok(event) => {
+                        if matches!(event.kind, notify::EventKind::Create(_) | notify::EventKind::Modify(_)) {
+                            pending.extend(event.paths);
+                        }
+                    }
+                    Err(RecvTimeoutError::Timeout) => {
+                        if !pending.is_empty() {
+                            emit_report(&app, &project_path, &import_files(&project_path, &inbox, &pending));
+                            pending.clear();
+                        }
+                    }
+                    Err(RecvTimeoutError::Disconnected) => break,
+                }
+            }
+        });
+
+        *self.0.lock().map_err(|e| format!("Lock error: {}", e))? = Some(watcher);
+        Ok(Some(dir))
+    }
+
+    pub fn stop(&self) -> Result<(), String> {
+        self.0.lock().map_err(|e| format!("Lock error: {}", e))?.take();
+        Ok(())
+    }
+}
+
+/// Set (or clear) the project's art inbox and restart its watcher; returns the watched folder
+#[tauri::command]
+pub async fn set_inbox_folder(
+    app: AppHandle,
+    project_path: String,
+    inbox: Option<InboxSettings>,
+    window: tauri::Window,
+    workspace: State<'_, WorkspaceManager>,
+) -> Result<Option<String>, String> {
+    let mut settings = ProjectSettings::load(&project_path)?;
+    settings.inbox = inbox;
+    settings.save(&project_path)?;
+    let services = workspace.services(window.label())?;
+    Ok(services.inbox.start(&app, &project_path)?.map(|dir| paths::to_display(&dir)))
+}
+
+/// File everything currently in the project's inbox without waiting for the watcher
+#[tauri::command]
+pub async fn import_inbox(app: AppHandle, project_path: String) -> Result<InboxReport, String> {
+    let inbox = ProjectSettings::load(&project_path)?
+        .inbox
+        .ok_or("This project has no inbox folder configured")?;
+    let report = import_files(&project_path, &inbox, &inbox_files(&inbox_dir(&project_path, &inbox)));
+    emit_report(&app, &project_path, &report);
+    Ok(report)
+}
@@ -0,0 +1,134 @@
+use crate::spec::MAX_SPEC_BYTES;
+use serde::de::DeserializeOwned;
+use std::collections::HashSet;
+
+/// Bounds for JSON from files, archives or the AI, checked before anything is deserialized
+pub struct JsonLimits {
+    pub max_bytes: usize,
+    /// Containers nested inside each other; serde_json gives up at 128 with a vaguer error
+    pub max_depth: usize,
+}
+
+/// game.json and specs sent by the editor
+pub const SPEC_LIMITS: JsonLimits = JsonLimits {
+    max_bytes: MAX_SPEC_BYTES as usize,
+    max_depth: 100,
+};
+
+/// Patches and labels parsed out of AI replies
+pub const AI_REPLY_LIMITS: JsonLimits = JsonLimits {
+    max_bytes: 1024 * 1024,
+    max_depth: 32,
+};
+
+/// Small project and sync metadata files such as components.d.json or the sync lineage
+pub const METADATA_LIMITS: JsonLimits = JsonLimits {
+    max_bytes: 16 * 1024 * 1024,
+    max_depth: 64,
+};
+
+/// 1-based line and column of a byte offset
+fn location(bytes: &[u8], offset: usize) -> (usize, usize) {
+    let before = &bytes[..offset.min(bytes.len())];
+    let line = before.iter().filter(|b| **b == b'\n').count() + 1;
+    let line_start = before.iter().rposition(|b| *b == b'\n').map_or(0, |p| p + 1);
+    (line, offset - line_start + 1)
+}
+
+fn located(bytes: &[u8], offset: usize, label: &str, problem: String) -> String {
+    let (line, column) = location(bytes, offset);
+    format!("{} {} at line {}, column {}", label, problem, line, column)
+}
+
+/// Offset of the quote closing the string that opens at `start`
+fn string_end(bytes: &[u8], start: usize) -> Option<usize> {
+    let mut i = start + 1;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i += 2,
+            b'"' => return Some(i),
+            _ => i += 1,
+        }
+    }
+    None
+}
+
+/// Scan `bytes` for input serde_json would accept silently or choke on: oversized documents,
+/// deep nesting, duplicate object keys (serde keeps the last) and NaN/Infinity literals.
+/// Other syntax errors are left for the parser to report.
+pub fn check(bytes: &[u8], label: &str, limits: &JsonLimits) -> Result<(), String> {
+    if bytes.len() > limits.max_bytes {
+        return Err(format!(
+            "{} is {:.1} MB, over the {:.1} MB limit",
+            label,
+            bytes.len() as f64 / (1024.0 * 1024.0),
+            limits.max_bytes as f64 / (1024.0 * 1024.0)
+        ));
+    }
+
+    // One entry per open container: the keys seen so far for objects, None for arrays
+    let mut stack: Vec<Option<HashSet<String>>> = Vec::new();
+    let mut expect_key = false;
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'"' => {
+                let Some(end) = string_end(bytes, i) else {
+                    return Ok(());
+                };
+                if let Some(Some(keys)) = stack.last_mut().filter(|_| expect_key) {
+                    let raw = &bytes[i..=end];
+                    let key = if raw.contains(&b'\\') {
+                        serde_json::from_slice::<String>(raw)
+                            .unwrap_or_else(|_| String::from_utf8_lossy(raw).into_owned())
+                    } else {
+                        String::from_utf8_lossy(&raw[1..raw.len() - 1]).into_owned()
+                    };
+                    if keys.contains(&key) {
+                        return Err(located(bytes, i, label, format!("repeats the key \"{}\"", key)));
+                    }
+                    keys.insert(key);
+                    expect_key = false;
+                }
+                i = end;
+            }
+            open @ (b'{' | b'[') => {
+                if stack.len() >= limits.max_depth {
+                    return Err(located(
+                        bytes,
+                        i,
+                        label,
+                        format!("nests deeper than {} levels", limits.max_depth),
+                    ));
+                }
+                stack.push((open == b'{').then(HashSet::new));
+                expect_key = open == b'{';
+            }
+            b'}' | b']' => {
+                stack.pop();
+                expect_key = false;
+            }
+            b',' => expect_key = matches!(stack.last(), Some(Some(_))),
+            b'N' | b'I' | b'-' if bytes[i..].starts_with(b"NaN")
+                || bytes[i..].starts_with(b"Infinity")
+                || bytes[i..].starts_with(b"-Infinity") =>
+            {
+                return Err(located(
+                    bytes,
+                    i,
+                    label,
+                    "contains NaN or Infinity, which JSON doesn't allow; use null or a finite number".to_string(),
+                ));
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    Ok(())
+}
+
+/// Check `bytes` against `limits`, then deserialize them
+pub fn parse<T: DeserializeOwned>(bytes: &[u8], label: &str, limits: &JsonLimits) -> Result<T, String> {
+    check(bytes, label, limits)?;
+    serde_json::from_slice(bytes).map_err(|e| format!("Failed to parse {}: {}", label, e))
+}
@@ -0,0 +1,544 @@
+use crate::templates::{self, TemplateManifest};
+use crate::{entity_ids, history, json_guard, paths, spec};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeSet, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::AppHandle;
+
+/// Kits installed into the project, with the version and content of everything they brought in
+const KITS_FILE: &str = ".promptplay/kits.json";
+
+/// A kit file as it was installed
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PinnedFile {
+    pub path: String,
+    pub sha256: String,
+}
+
+/// A kit entity as it was installed
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PinnedEntity {
+    /// Id, or name for entities without one, in the kit's game.json
+    pub source: String,
+    /// Id of the copy in the project
+    pub id: String,
+    /// The entity as installed; the base the upgrade diff is taken against
+    pub fragment: Value,
+}
+
+/// An entry of `.promptplay/kits.json`
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct InstalledKit {
+    pub id: String,
+    pub name: String,
+    pub version: String,
+    /// Registry id, or the template folder it was installed from
+    pub source: String,
+    pub installed_at: u64,
+    pub files: Vec<PinnedFile>,
+    pub entities: Vec<PinnedEntity>,
+}
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ChangeStatus {
+    /// Changed upstream and untouched locally; upgrading takes the new version
+    Updated,
+    Added,
+    /// Gone upstream; upgrading stops tracking it but leaves the project copy alone
+    Removed,
+    /// Changed both upstream and locally; upgrading needs `force` and overwrites the local edit
+    Conflict,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FileUpdate {
+    pub path: String,
+    pub status: ChangeStatus,
+}
+
+/// One changed value of an entity, by JSON pointer into the entity
+#[derive(Debug, Serialize)]
+pub struct FieldChange {
+    pub pointer: String,
+    pub installed: Option<Value>,
+    pub upstream: Option<Value>,
+    pub local: Option<Value>,
+    pub conflict: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct EntityUpdate {
+    pub source: String,
+    /// Entity id in the project, or the id an added entity will get
+    pub id: String,
+    pub status: ChangeStatus,
+    pub changes: Vec<FieldChange>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct KitUpdate {
+    pub kit_id: String,
+    pub name: String,
+    pub installed_version: String,
+    pub available_version: Option<String>,
+    /// The template is no longer where the kit was installed from
+    pub source_missing: bool,
+    pub files: Vec<FileUpdate>,
+    pub entities: Vec<EntityUpdate>,
+    pub conflicts: usize,
+    pub up_to_date: bool,
+}
+
+/// The kit as it is upstream now, filled in for this project
+struct Upstream {
+    manifest: TemplateManifest,
+    files: Vec<(String, Vec<u8>)>,
+    /// Entities by source key, already carrying their project ids
+    entities: Vec<(String, Value)>,
+}
+
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+fn sha256_hex(bytes: &[u8]) -> String {
+    Sha256::digest(bytes).iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn kits_path(project_path: &str) -> PathBuf {
+    paths::project_root(project_path).join(KITS_FILE)
+}
+
+fn load_kits(project_path: &str) -> Result<Vec<InstalledKit>, String> {
+    match fs::read(kits_path(project_path)) {
+        Ok(bytes) => json_guard::parse(&bytes, KITS_FILE, &json_guard::METADATA_LIMITS),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(format!("Failed to read {}: {}", KITS_FILE, e)),
+    }
+}
+
+fn save_kits(project_path: &str, kits: &[InstalledKit]) -> Result<(), String> {
+    let path = kits_path(project_path);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create .promptplay directory: {}", e))?;
+    }
+    let content = serde_json::to_string_pretty(kits).map_err(|e| format!("Failed to serialize {}: {}", KITS_FILE, e))?;
+    fs::write(&path, content).map_err(|e| format!("Failed to write {}: {}", KITS_FILE, e))
+}
+
+/// What `{{projectName}}` stands for in this project
+fn project_name(project: &Path, game_spec: &Value) -> String {
+    game_spec
+        .pointer("/metadata/title")
+        .and_then(Value::as_str)
+        .map(str::to_string)
+        .or_else(|| project.file_name().map(|n| n.to_string_lossy().to_string()))
+        .unwrap_or_default()
+}
+
+fn fill(value: &mut Value, name: &str) {
+    match value {
+        Value::String(s) => *s = s.replace("{{projectName}}", name),
+        Value::Array(items) => items.iter_mut().for_each(|item| fill(item, name)),
+        Value::Object(map) => map.values_mut().for_each(|item| fill(item, name)),
+        _ => {}
+    }
+}
+
+/// Read a valid template and fill it in for the project. Entities keep their template id unless
+/// it is already taken in the project by something other than `pinned`.
+fn read_upstream(
+    dir: &Path,
+    name: &str,
+    game_spec: &Value,
+    pinned: &[PinnedEntity],
+) -> Result<Upstream, String> {
+    let validation = templates::validate(dir);
+    let manifest = match validation.manifest {
+        Some(manifest) if validation.valid => manifest,
+        _ => {
+            let reasons: Vec<String> = validation
+                .diagnostics
+                .iter()
+                .filter(|d| d.severity == crate::validation::Severity::Error)
+                .map(|d| format!("{}: {}", d.path, d.message))
+                .collect();
+            return Err(format!("{} isn't a valid template: {}", dir.display(), reasons.join("; ")));
+        }
+    };
+
+    let mut files = Vec::new();
+    for file in manifest.files.iter().filter(|f| *f != "game.json") {
+        let bytes = fs::read(dir.join(file)).map_err(|e| format!("Failed to read {}: {}", file, e))?;
+        let bytes = match templates::is_text_file(file).then(|| String::from_utf8(bytes.clone()).ok()).flatten() {
+            Some(text) => text.replace("{{projectName}}", name).into_bytes(),
+            None => bytes,
+        };
+        files.push((file.clone(), bytes));
+    }
+
+    let taken: HashSet<&str> = spec::all_entities(game_spec)
+        .filter_map(entity_ids::entity_id)
+        .filter(|id| !pinned.iter().any(|p| p.id == *id))
+        .collect();
+    let kit_spec = spec::read_spec_file(&dir.join("game.json"))?;
+    let mut entities = Vec::new();
+    for entity in kit_spec.get("entities").and_then(Value::as_array).into_iter().flatten() {
+        let Some(source) = entity_ids::entity_id(entity).or_else(|| entity.get("name").and_then(Value::as_str)) else {
+            continue;
+        };
+        let mut entity = entity.clone();
+        fill(&mut entity, name);
+        let id = match pinned.iter().find(|p| p.source == source) {
+            Some(pin) => pin.id.clone(),
+            None => match entity_ids::entity_id(&entity) {
+                Some(id) if !taken.contains(id) => id.to_string(),
+                _ => entity_ids::new_entity_id(),
+            },
+        };
+        if let Some(object) = entity.as_object_mut() {
+            object.insert("id".to_string(), Value::String(id));
+            entities.push((source.to_string(), entity));
+        }
+    }
+    Ok(Upstream { manifest, files, entities })
+}
+
+fn escape_pointer(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
+
+fn unescape_pointer(key: &str) -> String {
+    key.replace("~1", "/").replace("~0", "~")
+}
+
+/// Leaf-level differences between two values; arrays compare as a whole
+fn diff(before: &Value, after: &Value, pointer: &str, out: &mut Vec<(String, Option<Value>, Option<Value>)>) {
+    match (before, after) {
+        (Value::Object(a), Value::Object(b)) => {
+            let keys: BTreeSet<&String> = a.keys().chain(b.keys()).collect();
+            for key in keys {
+                let child = format!("{}/{}", pointer, escape_pointer(key));
+                match (a.get(key), b.get(key)) {
+                    (Some(x), Some(y)) => diff(x, y, &child, out),
+                    (x, y) => out.push((child, x.cloned(), y.cloned())),
+                }
+            }
+        }
+        _ if before != after => out.push((pointer.to_string(), Some(before.clone()), Some(after.clone()))),
+        _ => {}
+    }
+}
+
+/// Set (or with None, remove) the value at a JSON pointer, creating parent objects as needed
+fn set_pointer(target: &mut Value, pointer: &str, value: Option<Value>) {
+    let Some((parent, key)) = pointer.rsplit_once('/') else {
+        if let Some(value) = value {
+            *target = value;
+        }
+        return;
+    };
+    let mut current = target;
+    for part in parent.split('/').skip(1) {
+        if !current.is_object() {
+            *current = json!({});
+        }
+        let Value::Object(map) = current else {
+            return;
+        };
+        current = map.entry(unescape_pointer(part)).or_insert_with(|| json!({}));
+    }
+    if !current.is_object() {
+        *current = json!({});
+    }
+    if let Value::Object(map) = current {
+        match value {
+            Some(value) => map.insert(unescape_pointer(key), value),
+            None => map.remove(&unescape_pointer(key)),
+        };
+    }
+}
+
+fn source_dir(app: &AppHandle, kit: &InstalledKit) -> Option<PathBuf> {
+    templates::template_dir(app, &kit.source).ok()
+}
+
+/// Compare an installed kit with its template as it is now
+fn plan(
+    app: &AppHandle,
+    project_path: &str,
+    kit: &InstalledKit,
+    game_spec: &Value,
+) -> Result<(KitUpdate, Option<Upstream>), String> {
+    let project = paths::project_root(project_path);
+    let mut update = KitUpdate {
+        kit_id: kit.id.clone(),
+        name: kit.name.clone(),
+        installed_version: kit.version.clone(),
+        available_version: None,
+        source_missing: false,
+        files: Vec::new(),
+        entities: Vec::new(),
+        conflicts: 0,
+        up_to_date: false,
+    };
+    let Some(dir) = source_dir(app, kit) else {
+        update.source_missing = true;
+        return Ok((update, None));
+    };
+    let upstream = read_upstream(&dir, &project_name(&project, game_spec), game_spec, &kit.entities)?;
+    update.available_version = Some(upstream.manifest.version.clone());
+
+    let local_hash = |path: &str| fs::read(project.join(path)).ok().map(|bytes| sha256_hex(&bytes));
+    for (path, bytes) in &upstream.files {
+        let hash = sha256_hex(bytes);
+        let status = match kit.files.iter().find(|f| &f.path == path) {
+            Some(pin) if pin.sha256 == hash => continue,
+            Some(pin) if local_hash(path).as_deref() == Some(pin.sha256.as_str()) => ChangeStatus::Updated,
+            Some(_) => ChangeStatus::Conflict,
+            None => match local_hash(path) {
+                Some(local) if local != hash => ChangeStatus::Conflict,
+                Some(_) => continue,
+                None => ChangeStatus::Added,
+            },
+        };
+        update.files.push(FileUpdate { path: path.clone(), status });
+    }
+    for pin in kit.files.iter().filter(|pin| !upstream.files.iter().any(|(path, _)| *path == pin.path)) {
+        update.files.push(FileUpdate {
+            path: pin.path.clone(),
+            status: ChangeStatus::Removed,
+        });
+    }
+
+    for (source, entity) in &upstream.entities {
+        let id = entity_ids::entity_id(entity).unwrap_or_default().to_string();
+        let Some(pin) = kit.entities.iter().find(|p| &p.source == source) else {
+            update.entities.push(EntityUpdate {
+                source: source.clone(),
+                id,
+                status: ChangeStatus::Added,
+                changes: Vec::new(),
+            });
+            continue;
+        };
+        let mut differences = Vec::new();
+        diff(&pin.fragment, entity, "", &mut differences);
+        if differences.is_empty() {
+            continue;
+        }
+        let local = spec::all_entities(game_spec).find(|e| entity_ids::entity_id(e) == Some(pin.id.as_str()));
+        let changes: Vec<FieldChange> = differences
+            .into_iter()
+            .map(|(pointer, installed, upstream)| {
+                let current = local.and_then(|l| l.pointer(&pointer)).cloned();
+                FieldChange {
+                    conflict: local.is_none() || (current != installed && current != upstream),
+                    pointer,
+                    installed,
+                    upstream,
+                    local: current,
+                }
+            })
+            .collect();
+        let status = if changes.iter().any(|c| c.conflict) {
+            ChangeStatus::Conflict
+        } else {
+            ChangeStatus::Updated
+        };
+        update.entities.push(EntityUpdate {
+            source: source.clone(),
+            id,
+            status,
+            changes,
+        });
+    }
+    for pin in kit.entities.iter().filter(|pin| !upstream.entities.iter().any(|(source, _)| *source == pin.source)) {
+        update.entities.push(EntityUpdate {
+            source: pin.source.clone(),
+            id: pin.id.clone(),
+            status: ChangeStatus::Removed,
+            changes: Vec::new(),
+        });
+    }
+
+    update.conflicts = update.files.iter().filter(|f| f.status == ChangeStatus::Conflict).count()
+        + update.entities.iter().filter(|e| e.status == ChangeStatus::Conflict).count();
+    update.up_to_date = update.files.is_empty() && update.entities.is_empty() && upstream.manifest.version == kit.version;
+    Ok((update, Some(upstream)))
+}
+
+fn pins(upstream: &Upstream) -> (Vec<PinnedFile>, Vec<PinnedEntity>) {
+    let files = upstream
+        .files
+        .iter()
+        .map(|(path, bytes)| PinnedFile {
+            path: path.clone(),
+            sha256: sha256_hex(bytes),
+        })
+        .collect();
+    let entities = upstream
+        .entities
+        .iter()
+        .map(|(source, entity)| PinnedEntity {
+            source: source.clone(),
+            id: entity_ids::entity_id(entity).unwrap_or_default().to_string(),
+            fragment: entity.clone(),
+        })
+        .collect();
+    (files, entities)
+}
+
+fn write_file(project: &Path, path: &str, bytes: &[u8]) -> Result<(), String> {
+    let destination = project.join(path);
+    if let Some(parent) = destination.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    fs::write(&destination, bytes).map_err(|e| format!("Failed to write {}: {}", path, e))
+}
+
+/// Install a template's assets and entities into the project as a kit, recording the version and
+/// content of each so later releases can be told apart from local edits
+#[tauri::command]
+pub async fn install_kit(app: AppHandle, project_path: String, template: String) -> Result<InstalledKit, String> {
+    let dir = templates::template_dir(&app, &template)?;
+    let project = paths::project_root(&project_path);
+    let mut kits = load_kits(&project_path)?;
+    let mut game_spec = spec::load_project_spec(&project_path)?;
+    let upstream = read_upstream(&dir, &project_name(&project, &game_spec), &game_spec, &[])?;
+    if kits.iter().any(|k| k.id == upstream.manifest.id) {
+        return Err(format!("'{}' is already installed; check it for updates instead", upstream.manifest.name));
+    }
+
+    let clashes: Vec<&str> = upstream
+        .files
+        .iter()
+        .filter(|(path, bytes)| fs::read(project.join(path)).is_ok_and(|existing| existing != *bytes))
+        .map(|(path, _)| path.as_str())
+        .collect();
+    if !clashes.is_empty() {
+        return Err(format!("The kit would overwrite project files: {}", clashes.join(", ")));
+    }
+
+    for (path, bytes) in &upstream.files {
+        write_file(&project, path, bytes)?;
+    }
+    if !upstream.entities.is_empty() {
+        let entities = game_spec
+            .get_mut("entities")
+            .and_then(Value::as_array_mut)
+            .ok_or("The project's game.json has no entities array")?;
+        entities.extend(upstream.entities.iter().map(|(_, entity)| entity.clone()));
+        history::save_with_history(&project_path, &format!("Install kit {}", upstream.manifest.name), &game_spec)?;
+    }
+
+    // Registry templates are tracked by id so they're still found if the app data folder moves
+    let in_registry = templates::template_dir(&app, &upstream.manifest.id)
+        .is_ok_and(|registered| paths::canonical(&registered) == paths::canonical(&dir));
+    let (files, entities) = pins(&upstream);
+    let kit = InstalledKit {
+        id: upstream.manifest.id.clone(),
+        name: upstream.manifest.name.clone(),
+        version: upstream.manifest.version.clone(),
+        source: if in_registry { upstream.manifest.id.clone() } else { paths::to_display(&dir) },
+        installed_at: now_millis(),
+        files,
+        entities,
+    };
+    kits.push(kit.clone());
+    save_kits(&project_path, &kits)?;
+    Ok(kit)
+}
+
+/// Compare every installed kit with its template: new versions, changed files and field-level
+/// changes to its entities, flagging the ones also edited in the project
+#[tauri::command]
+pub async fn check_kit_updates(app: AppHandle, project_path: String) -> Result<Vec<KitUpdate>, String> {
+    let game_spec = spec::load_project_spec(&project_path)?;
+    load_kits(&project_path)?
+        .iter()
+        .map(|kit| plan(&app, &project_path, kit, &game_spec).map(|(update, _)| update))
+        .collect()
+}
+
+/// Upgrade a kit to its template's current release. Refuses while local edits conflict with
+/// upstream changes unless `force` is set, which lets upstream win; the spec change is one undo step.
+#[tauri::command]
+pub async fn upgrade_kit(
+    app: AppHandle,
+    project_path: String,
+    kit_id: String,
+    force: Option<bool>,
+) -> Result<KitUpdate, String> {
+    let force = force.unwrap_or(false);
+    let project = paths::project_root(&project_path);
+    let mut kits = load_kits(&project_path)?;
+    let index = kits
+        .iter()
+        .position(|k| k.id == kit_id)
+        .ok_or_else(|| format!("Kit '{}' is not installed", kit_id))?;
+    let mut game_spec = spec::load_project_spec(&project_path)?;
+    let (update, upstream) = plan(&app, &project_path, &kits[index], &game_spec)?;
+    let upstream = upstream.ok_or_else(|| format!("The template '{}' was installed from is gone", kits[index].source))?;
+    if update.conflicts > 0 && !force {
+        return Err(format!(
+            "{} has {} change(s) that conflict with local edits; review them or upgrade with force",
+            update.name, update.conflicts
+        ));
+    }
+
+    for file in update.files.iter().filter(|f| f.status != ChangeStatus::Removed) {
+        if let Some((_, bytes)) = upstream.files.iter().find(|(path, _)| *path == file.path) {
+            write_file(&project, &file.path, bytes)?;
+        }
+    }
+
+    let mut spec_changed = false;
+    for change in &update.entities {
+        let Some((_, upstream_entity)) = upstream.entities.iter().find(|(source, _)| *source == change.source) else {
+            continue;
+        };
+        match spec::find_entity_by_id_mut(&mut game_spec, &change.id) {
+            Some(local) if change.status != ChangeStatus::Added => {
+                for field in change.changes.iter().filter(|c| force || !c.conflict) {
+                    set_pointer(local, &field.pointer, field.upstream.clone());
+                }
+            }
+            Some(_) => continue,
+            None => {
+                if change.status == ChangeStatus::Conflict && !force {
+                    continue;
+                }
+                let entities = game_spec
+                    .get_mut("entities")
+                    .and_then(Value::as_array_mut)
+                    .ok_or("The project's game.json has no entities array")?;
+                entities.push(upstream_entity.clone());
+            }
+        }
+        spec_changed = true;
+    }
+    if spec_changed {
+        history::save_with_history(&project_path, &format!("Upgrade kit {}", update.name), &game_spec)?;
+    }
+
+    let (files, entities) = pins(&upstream);
+    let kit = &mut kits[index];
+    kit.version = upstream.manifest.version.clone();
+    kit.name = upstream.manifest.name.clone();
+    kit.files = files;
+    kit.entities = entities;
+    save_kits(&project_path, &kits)?;
+    Ok(update)
+}
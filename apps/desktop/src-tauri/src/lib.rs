@@ -1,10 +1,108 @@
 // Tauri library entry point
 // This file is required for the library crate
 
+pub mod accessibility;
 pub mod ai_client;
+pub mod ai_context;
+pub mod ai_providers;
+pub mod animation_preview;
+pub mod asset_pack;
+pub mod asset_usage;
+pub mod assets;
+pub mod autolabel;
+pub mod backup;
+pub mod benchmark;
+pub mod cache;
+pub mod collider_gen;
+pub mod collision_shapes;
+pub mod command_trace;
 pub mod commands;
+pub mod components;
+pub mod compression;
+pub mod context_watchdog;
+pub mod coop;
+pub mod credentials;
+pub mod csv;
+pub mod deep_link;
+pub mod diagnostics;
+pub mod dialogs;
+pub mod difficulty;
+pub mod encoding;
+pub mod encryption;
+pub mod entitlement;
+pub mod entity_csv;
+pub mod entity_ids;
+pub mod entity_locks;
+pub mod error;
+pub mod export;
+pub mod export_diff;
+pub mod feedback;
 pub mod file_watcher;
+pub mod formatter;
+pub mod frame_rate;
+pub mod game_spec;
+pub mod git;
+pub mod godot_export;
+pub mod health;
+pub mod heatmap;
+pub mod history;
+pub mod hooks;
+pub mod hud;
+pub mod ideas;
+pub mod json_guard;
+pub mod inbox;
+pub mod kits;
+pub mod licensing;
+pub mod lint_rules;
+pub mod minimap;
+pub mod notifications;
+pub mod palette;
+pub mod parity;
+pub mod paths;
+pub mod permissions;
+pub mod physics_materials;
+pub mod pooling;
+pub mod preview_server;
+pub mod progression;
+pub mod project_lock;
+pub mod project_settings;
+pub mod projects;
+pub mod prompt_history;
+pub mod read_only;
+pub mod references;
+pub mod rename;
+pub mod replace;
+pub mod repro;
+pub mod rounding;
+pub mod runtime;
+pub mod safe_delete;
+pub mod save_conflicts;
+pub mod scene_import;
+pub mod scene_query;
+pub mod search_index;
+pub mod seed;
+pub mod services;
+pub mod settings;
+pub mod simulator;
+pub mod spec;
+pub mod spec_changes;
+pub mod spec_tests;
+pub mod spec_types;
+pub mod startup;
+pub mod stt;
+pub mod symlinks;
+pub mod sync;
+pub mod templates;
+pub mod thumbnails;
+pub mod tools;
+pub mod tray;
+pub mod tts;
+pub mod tutorial;
+pub mod validation;
 pub mod watcher_commands;
+pub mod workers;
+pub mod workspace;
+pub mod world;
 
 pub fn init() {
     // Library initialization code
@@ -0,0 +1,179 @@
+use crate::assets::{normalize_relative, AssetEntry, AssetLicense, AssetManifest};
+use crate::{csv, spec};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+pub const CREDITS_FILE: &str = "CREDITS.txt";
+
+#[derive(Debug, Serialize)]
+pub struct LicenseImportReport {
+    pub updated: usize,
+    pub created: usize,
+    pub errors: Vec<String>,
+}
+
+fn upsert_license(manifest: &mut AssetManifest, asset_path: &str, license: AssetLicense) -> bool {
+    let path = normalize_relative(asset_path);
+    match manifest.find_mut(&path) {
+        Some(entry) => {
+            entry.license = Some(license);
+            false
+        }
+        None => {
+            manifest.upsert(AssetEntry::new(path, Some(license)));
+            true
+        }
+    }
+}
+
+/// Warnings for referenced assets that are unlicensed or marked non-distributable
+pub fn distribution_warnings(manifest: &AssetManifest, game_spec: &Value) -> Vec<String> {
+    let mut warnings = Vec::new();
+    let mut seen = std::collections::HashSet::new();
+
+    for reference in spec::asset_references(game_spec) {
+        if !seen.insert(reference.asset.clone()) {
+            continue;
+        }
+        match manifest.resolve(&reference.asset).and_then(|a| a.license.as_ref()) {
+            Some(license) if !license.distributable => warnings.push(format!(
+                "Asset '{}' ({}) is marked non-distributable but is used by '{}'",
+                reference.asset, license.license, reference.entity
+            )),
+            Some(_) => {}
+            None => warnings.push(format!(
+                "Asset '{}' used by '{}' has no license information",
+                reference.asset, reference.entity
+            )),
+        }
+    }
+
+    warnings
+}
+
+/// Render a plain-text credits listing grouped by author
+pub fn render_credits(manifest: &AssetManifest, title: &str) -> String {
+    let mut by_author: BTreeMap<String, Vec<(&AssetEntry, &AssetLicense)>> = BTreeMap::new();
+
+    for entry in &manifest.assets {
+        if let Some(license) = &entry.license {
+            let author = license.author.clone().unwrap_or_else(|| "Unknown author".to_string());
+            by_author.entry(author).or_default().push((entry, license));
+        }
+    }
+
+    let mut out = format!("{}\nCREDITS\n\n", title);
+    for (author, entries) in by_author {
+        out.push_str(&format!("{}\n", author));
+        for (entry, license) in entries {
+            out.push_str(&format!("  - {} ({})", entry.path, license.license));
+            if let Some(url) = &license.source_url {
+                out.push_str(&format!(" <{}>", url));
+            }
+            out.push('\n');
+        }
+        out.push('\n');
+    }
+    out.push_str("Made with PromptPlay\n");
+    out
+}
+
+/// Set license metadata for one asset
+#[tauri::command]
+pub async fn set_asset_license(
+    project_path: String,
+    asset_path: String,
+    license: AssetLicense,
+) -> Result<(), String> {
+    let mut manifest = AssetManifest::load(&project_path)?;
+    upsert_license(&mut manifest, &asset_path, license);
+    manifest.save(&project_path)
+}
+
+/// Bulk-import license metadata from a CSV with columns: path, license, author, source, distributable
+#[tauri::command]
+pub async fn import_asset_licenses_csv(
+    project_path: String,
+    csv_path: String,
+) -> Result<LicenseImportReport, String> {
+    let text = fs::read_to_string(&csv_path)
+        .map_err(|e| format!("Failed to read file {}: {}", csv_path, e))?;
+    let (headers, records) = csv::parse_with_headers(&text)?;
+
+    let column = |name: &str| headers.iter().position(|h| h.eq_ignore_ascii_case(name));
+    let path_col = column("path").ok_or("CSV must have a 'path' column")?;
+    let license_col = column("license").ok_or("CSV must have a 'license' column")?;
+    let author_col = column("author");
+    let source_col = column("source");
+    let distributable_col = column("distributable");
+
+    let mut manifest = AssetManifest::load(&project_path)?;
+    let mut report = LicenseImportReport {
+        updated: 0,
+        created: 0,
+        errors: Vec::new(),
+    };
+
+    for (i, record) in records.iter().enumerate() {
+        let cell = |col: Option<usize>| {
+            col.and_then(|c| record.get(c))
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty())
+        };
+
+        let (Some(path), Some(license)) = (cell(Some(path_col)), cell(Some(license_col))) else {
+            report.errors.push(format!("Row {}: missing path or license", i + 2));
+            continue;
+        };
+
+        let distributable = match cell(distributable_col).map(|v| v.to_lowercase()) {
+            None => true,
+            Some(v) if ["yes", "true", "1", "y"].contains(&v.as_str()) => true,
+            Some(v) if ["no", "false", "0", "n"].contains(&v.as_str()) => false,
+            Some(v) => {
+                report
+                    .errors
+                    .push(format!("Row {}: invalid distributable value '{}'", i + 2, v));
+                continue;
+            }
+        };
+
+        let created = upsert_license(
+            &mut manifest,
+            &path,
+            AssetLicense {
+                license,
+                author: cell(author_col),
+                source_url: cell(source_col),
+                distributable,
+            },
+        );
+        if created {
+            report.created += 1;
+        } else {
+            report.updated += 1;
+        }
+    }
+
+    manifest.save(&project_path)?;
+    Ok(report)
+}
+
+/// Generate CREDITS.txt in the project root from asset license metadata
+#[tauri::command]
+pub async fn generate_credits(project_path: String) -> Result<String, String> {
+    let manifest = AssetManifest::load(&project_path)?;
+    let title = spec::load_project_spec(&project_path)
+        .ok()
+        .and_then(|s| s.pointer("/metadata/title").and_then(Value::as_str).map(str::to_string))
+        .unwrap_or_else(|| "Untitled Game".to_string());
+
+    let credits = render_credits(&manifest, &title);
+    let path = PathBuf::from(&project_path).join(CREDITS_FILE);
+    fs::write(&path, &credits).map_err(|e| format!("Failed to write {}: {}", CREDITS_FILE, e))?;
+
+    Ok(credits)
+}
@@ -0,0 +1,129 @@
+use crate::json_guard;
+use crate::validation::{Diagnostic, Severity};
+use rhai::{Dynamic, Engine, Scope, AST};
+use serde::Deserialize;
+use serde_json::Value;
+use std::fs;
+use std::path::PathBuf;
+
+/// Project folder holding custom lint rules, one `.rhai` script per rule
+pub const LINT_RULES_DIR: &str = "lint-rules";
+/// Operations a rule may run per spec before it is stopped, so a runaway loop can't hang a lint
+const MAX_OPERATIONS: u64 = 5_000_000;
+const MAX_CALL_LEVELS: usize = 64;
+/// Size caps on values a rule builds. Rules come with shared projects and run unattended, so a
+/// rule that keeps doubling a string or array must fail before it exhausts memory.
+const MAX_STRING_SIZE: usize = 1024 * 1024;
+const MAX_ARRAY_SIZE: usize = 100_000;
+const MAX_MAP_SIZE: usize = 100_000;
+const MAX_EXPR_DEPTH: usize = 64;
+const MAX_FUNCTION_EXPR_DEPTH: usize = 32;
+/// Findings kept per rule; a rule that flags everything is a broken rule
+const MAX_FINDINGS: usize = 200;
+
+/// A lint rule script. It defines `fn lint(spec)`, gets the spec as a map and returns an array of
+/// `#{ message, path, severity }` maps; `path` defaults to "" and `severity` to "warning".
+#[derive(Debug, Deserialize, Clone)]
+pub struct LintRule {
+    /// File stem for project rules, `<plugin id>:<rule id>` for rules registered by plugins
+    pub id: String,
+    pub source: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct RuleFinding {
+    message: String,
+    #[serde(default)]
+    path: String,
+    #[serde(default)]
+    severity: Option<Severity>,
+}
+
+fn engine() -> Engine {
+    let mut engine = Engine::new();
+    engine.set_max_operations(MAX_OPERATIONS);
+    engine.set_max_call_levels(MAX_CALL_LEVELS);
+    engine.set_max_string_size(MAX_STRING_SIZE);
+    engine.set_max_array_size(MAX_ARRAY_SIZE);
+    engine.set_max_map_size(MAX_MAP_SIZE);
+    engine.set_max_expr_depths(MAX_EXPR_DEPTH, MAX_FUNCTION_EXPR_DEPTH);
+    engine
+}
+
+/// The project's own rules from lint-rules/*.rhai, sorted by id
+pub fn project_rules(project_path: &str) -> Vec<LintRule> {
+    let dir = PathBuf::from(project_path).join(LINT_RULES_DIR);
+    let mut rules: Vec<LintRule> = fs::read_dir(&dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|e| e == "rhai"))
+        .filter_map(|path| {
+            Some(LintRule {
+                id: path.file_stem()?.to_string_lossy().to_string(),
+                source: fs::read_to_string(&path).ok()?,
+            })
+        })
+        .collect();
+    rules.sort_by(|a, b| a.id.cmp(&b.id));
+    rules
+}
+
+fn run_rule(engine: &Engine, rule: &LintRule, spec: &Dynamic) -> Result<Vec<Diagnostic>, String> {
+    let ast: AST = engine.compile(&rule.source).map_err(|e| e.to_string())?;
+    let result: Dynamic = engine
+        .call_fn(&mut Scope::new(), &ast, "lint", (spec.clone(),))
+        .map_err(|e| e.to_string())?;
+    let findings: Vec<RuleFinding> = rhai::serde::from_dynamic(&result)
+        .map_err(|e| format!("lint() must return an array of #{{ message, path, severity }}: {}", e))?;
+    Ok(findings
+        .into_iter()
+        .take(MAX_FINDINGS)
+        .map(|finding| Diagnostic {
+            path: finding.path,
+            severity: finding.severity.unwrap_or(Severity::Warning),
+            message: format!("[{}] {}", rule.id, finding.message),
+            fix: None,
+        })
+        .collect())
+}
+
+/// Run lint rules against a spec. A rule that fails to compile or run is reported as a warning
+/// instead of stopping the others. Rules can take a while, so async callers run this on a
+/// blocking thread.
+pub fn run(rules: &[LintRule], spec: &Value) -> Vec<Diagnostic> {
+    if rules.is_empty() {
+        return Vec::new();
+    }
+    let spec = match rhai::serde::to_dynamic(spec) {
+        Ok(spec) => spec,
+        Err(e) => return vec![Diagnostic::warning("", format!("Lint rules could not read the spec: {}", e))],
+    };
+    let engine = engine();
+    rules
+        .iter()
+        .flat_map(|rule| {
+            run_rule(&engine, rule, &spec).unwrap_or_else(|e| {
+                vec![Diagnostic::warning("", format!("Lint rule '{}' failed: {}", rule.id, e))]
+            })
+        })
+        .collect()
+}
+
+/// Run the project's lint-rules/*.rhai plus any rules registered by editor plugins
+#[tauri::command]
+pub async fn run_lint_rules(
+    game_spec_json: String,
+    project_path: Option<String>,
+    plugin_rules: Option<Vec<LintRule>>,
+) -> Result<Vec<Diagnostic>, String> {
+    let spec: Value = json_guard::parse(game_spec_json.as_bytes(), "game spec", &json_guard::SPEC_LIMITS)?;
+    tauri::async_runtime::spawn_blocking(move || {
+        let mut rules = project_path.as_deref().map(project_rules).unwrap_or_default();
+        rules.extend(plugin_rules.unwrap_or_default());
+        run(&rules, &spec)
+    })
+    .await
+    .map_err(|e| format!("Lint task failed: {}", e))
+}
@@ -1,27 +1,205 @@
 // Prevents additional console window on Windows in release builds
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+mod accessibility;
 mod ai_client;
+mod ai_context;
+mod ai_providers;
+mod animation_preview;
+mod asset_pack;
+mod asset_usage;
+mod assets;
+mod autolabel;
+mod backup;
+mod benchmark;
+mod cache;
+mod collider_gen;
+mod collision_shapes;
+mod command_trace;
 mod commands;
+mod components;
+mod compression;
+mod context_watchdog;
+mod coop;
+mod credentials;
+mod csv;
+mod deep_link;
+mod diagnostics;
+mod dialogs;
+mod difficulty;
+mod encoding;
+mod encryption;
+mod entitlement;
+mod entity_csv;
+mod entity_ids;
+mod entity_locks;
+mod error;
+mod export;
+mod export_diff;
+mod feedback;
 mod file_watcher;
+mod formatter;
+mod frame_rate;
+mod game_spec;
+mod git;
+mod godot_export;
+mod health;
+mod heatmap;
+mod history;
+mod hooks;
+mod hud;
+mod ideas;
+mod json_guard;
+mod inbox;
+mod kits;
+mod licensing;
+mod lint_rules;
+mod minimap;
+mod notifications;
+mod palette;
+mod parity;
+mod paths;
+mod permissions;
+mod physics_materials;
+mod pooling;
+mod preview_server;
+mod progression;
+mod project_lock;
+mod project_settings;
+mod projects;
+mod prompt_history;
+mod read_only;
+mod references;
+mod rename;
+mod replace;
+mod repro;
+mod rounding;
+mod runtime;
+mod safe_delete;
+mod save_conflicts;
+mod scene_import;
+mod scene_query;
+mod search_index;
+mod seed;
+mod services;
+mod settings;
+mod simulator;
+mod spec;
+mod spec_changes;
+mod spec_tests;
+mod spec_types;
+mod startup;
+mod stt;
+mod symlinks;
+mod sync;
+mod templates;
+mod thumbnails;
+mod tools;
+mod tray;
+mod tts;
+mod tutorial;
+mod validation;
 mod watcher_commands;
+mod workers;
+mod workspace;
+mod world;
 
-use ai_client::AIClientState;
-use file_watcher::FileWatcherState;
-use std::sync::Mutex;
+use ai_client::{AIClientState, AIStreamState};
+use command_trace::CommandTraceState;
+use context_watchdog::ContextWatchdogState;
+use coop::CoopRelayState;
+use deep_link::LaunchQueue;
+use hooks::HooksState;
+use preview_server::PreviewServerState;
+use scene_query::SpecCacheState;
+use search_index::SearchIndexState;
+use startup::StartupState;
+use tray::TrayState;
+use workspace::WorkspaceManager;
 use tauri::{
     menu::{Menu, MenuItem, PredefinedMenuItem, Submenu},
-    Emitter, Manager,
+    AppHandle, Emitter, Manager,
 };
+use tauri_plugin_deep_link::DeepLinkExt;
+use std::time::Instant;
+
+/// Startup work that can wait until the main window is showing
+fn start_deferred(app: &AppHandle) {
+    startup::in_background(app, "preview server cleanup", preview_server::reclaim_on_startup);
+    startup::in_background(app, "AI client", |app| {
+        let state = app.state::<AIClientState>();
+        let mut client = tauri::async_runtime::block_on(state.0.lock());
+        // The saved key comes from the keychain so it doesn't have to be entered every launch
+        let provider = settings::AppSettings::load(app).ai_provider.unwrap_or_default();
+        let api_key = ai_providers::load_api_key(provider.kind);
+        client.set_provider(provider, api_key);
+        client.http();
+    });
+}
 
 fn main() {
+    // Headless subcommands run without starting the app
+    let args: Vec<String> = std::env::args().collect();
+    if args.get(1).map(String::as_str) == Some("test") {
+        std::process::exit(spec_tests::run_cli(&args[2..]));
+    }
+    if args.get(1).map(String::as_str) == Some("types") {
+        std::process::exit(spec_types::run_cli(&args[2..]));
+    }
+
     tauri::Builder::default()
+        .manage(StartupState::default())
+        .manage(CommandTraceState::default())
         .plugin(tauri_plugin_fs::init())
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_shell::init())
-        .manage(Mutex::new(FileWatcherState::default()))
+        .plugin(tauri_plugin_deep_link::init())
+        .plugin(tauri_plugin_notification::init())
+        .plugin(
+            tauri_plugin_global_shortcut::Builder::new()
+                .with_handler(|app, _shortcut, event| ideas::on_capture_shortcut(app, event.state()))
+                .build(),
+        )
         .manage(AIClientState::default())
-        .setup(|app| {
+        .manage(AIStreamState::default())
+        .manage(ContextWatchdogState::default())
+        .manage(SearchIndexState::default())
+        .manage(SpecCacheState::default())
+        .manage(HooksState::default())
+        .manage(WorkspaceManager::default())
+        .manage(LaunchQueue::default())
+        .manage(TrayState::default())
+        .manage(PreviewServerState::default())
+        .manage(CoopRelayState::default())
+        .on_window_event(|window, event| match event {
+            // Background exports keep running in the tray; the window comes back from its menu
+            tauri::WindowEvent::CloseRequested { api, .. }
+                if window.label() == "main" && window.state::<TrayState>().keeps_running() =>
+            {
+                api.prevent_close();
+                let _ = window.hide();
+            }
+            // Leave no lock behind when the window goes away
+            tauri::WindowEvent::Destroyed => {
+                let _ = window.state::<WorkspaceManager>().close(window.label());
+                preview_server::stop_for_window(window.app_handle(), window.label());
+            }
+            _ => {}
+        })
+        .on_page_load(|webview, payload| match payload.event() {
+            // A reloaded page has lost its handle to the window's preview server
+            tauri::webview::PageLoadEvent::Started => {
+                preview_server::stop_for_window(webview.app_handle(), webview.label());
+            }
+            tauri::webview::PageLoadEvent::Finished => {
+                if webview.label() == "main" && startup::window_shown(webview.app_handle()) {
+                    start_deferred(webview.app_handle());
+                }
+            }
+        })
+        .setup(move |app| {
+            let startup = app.state::<StartupState>();
+            let menus_started = Instant::now();
             // ==================== FILE MENU ====================
             let new_project = MenuItem::with_id(app, "new_project", "New Project", true, Some("CmdOrCtrl+Shift+N"))?;
             let open_project = MenuItem::with_id(app, "open_project", "Open Project...", true, Some("CmdOrCtrl+O"))?;
@@ -235,77 +413,104 @@ fn main() {
 
             app.set_menu(menu)?;
 
-            // Handle menu events
+            // Handle menu events; they act on the focused project window only
             app.on_menu_event(|app, event| {
-                let window = app.get_webview_window("main").unwrap();
+                let Some(label) = workspace::focused_window(app) else {
+                    return;
+                };
                 match event.id().as_ref() {
                     // File menu
-                    "new_project" => { let _ = window.emit("menu-event", "new_project"); }
-                    "open_project" => { let _ = window.emit("menu-event", "open_project"); }
-                    "close_project" => { let _ = window.emit("menu-event", "close_project"); }
-                    "save" => { let _ = window.emit("menu-event", "save"); }
-                    "save_as" => { let _ = window.emit("menu-event", "save_as"); }
-                    "save_as_template" => { let _ = window.emit("menu-event", "save_as_template"); }
-                    "import_game" => { let _ = window.emit("menu-event", "import_game"); }
-                    "export_html" => { let _ = window.emit("menu-event", "export_html"); }
-                    "export_zip" => { let _ = window.emit("menu-event", "export_zip"); }
-                    "publish" => { let _ = window.emit("menu-event", "publish"); }
+                    "new_project" => { let _ = app.emit_to(label.as_str(), "menu-event", "new_project"); }
+                    "open_project" => { let _ = app.emit_to(label.as_str(), "menu-event", "open_project"); }
+                    "close_project" => { let _ = app.emit_to(label.as_str(), "menu-event", "close_project"); }
+                    "save" => { let _ = app.emit_to(label.as_str(), "menu-event", "save"); }
+                    "save_as" => { let _ = app.emit_to(label.as_str(), "menu-event", "save_as"); }
+                    "save_as_template" => { let _ = app.emit_to(label.as_str(), "menu-event", "save_as_template"); }
+                    "import_game" => { let _ = app.emit_to(label.as_str(), "menu-event", "import_game"); }
+                    "export_html" => { let _ = app.emit_to(label.as_str(), "menu-event", "export_html"); }
+                    "export_zip" => { let _ = app.emit_to(label.as_str(), "menu-event", "export_zip"); }
+                    "publish" => { let _ = app.emit_to(label.as_str(), "menu-event", "publish"); }
                     // Edit menu
-                    "undo" => { let _ = window.emit("menu-event", "undo"); }
-                    "redo" => { let _ = window.emit("menu-event", "redo"); }
-                    "duplicate" => { let _ = window.emit("menu-event", "duplicate"); }
-                    "delete" => { let _ = window.emit("menu-event", "delete"); }
-                    "select_all_entities" => { let _ = window.emit("menu-event", "select_all_entities"); }
-                    "deselect_all" => { let _ = window.emit("menu-event", "deselect_all"); }
-                    "preferences" => { let _ = window.emit("menu-event", "preferences"); }
+                    "undo" => { let _ = app.emit_to(label.as_str(), "menu-event", "undo"); }
+                    "redo" => { let _ = app.emit_to(label.as_str(), "menu-event", "redo"); }
+                    "duplicate" => { let _ = app.emit_to(label.as_str(), "menu-event", "duplicate"); }
+                    "delete" => { let _ = app.emit_to(label.as_str(), "menu-event", "delete"); }
+                    "select_all_entities" => { let _ = app.emit_to(label.as_str(), "menu-event", "select_all_entities"); }
+                    "deselect_all" => { let _ = app.emit_to(label.as_str(), "menu-event", "deselect_all"); }
+                    "preferences" => { let _ = app.emit_to(label.as_str(), "menu-event", "preferences"); }
                     // View menu
-                    "toggle_grid" => { let _ = window.emit("menu-event", "toggle_grid"); }
-                    "toggle_debug" => { let _ = window.emit("menu-event", "toggle_debug"); }
-                    "toggle_2d_3d" => { let _ = window.emit("menu-event", "toggle_2d_3d"); }
-                    "zoom_in" => { let _ = window.emit("menu-event", "zoom_in"); }
-                    "zoom_out" => { let _ = window.emit("menu-event", "zoom_out"); }
-                    "zoom_reset" => { let _ = window.emit("menu-event", "zoom_reset"); }
-                    "fit_view" => { let _ = window.emit("menu-event", "fit_view"); }
-                    "show_scene_tree" => { let _ = window.emit("menu-event", "show_scene_tree"); }
-                    "show_inspector" => { let _ = window.emit("menu-event", "show_inspector"); }
-                    "show_assets" => { let _ = window.emit("menu-event", "show_assets"); }
-                    "show_animation" => { let _ = window.emit("menu-event", "show_animation"); }
-                    "show_code" => { let _ = window.emit("menu-event", "show_code"); }
-                    "show_visual_scripts" => { let _ = window.emit("menu-event", "show_visual_scripts"); }
-                    "show_shaders" => { let _ = window.emit("menu-event", "show_shaders"); }
-                    "show_behavior_trees" => { let _ = window.emit("menu-event", "show_behavior_trees"); }
-                    "show_state_machines" => { let _ = window.emit("menu-event", "show_state_machines"); }
-                    "show_ai" => { let _ = window.emit("menu-event", "show_ai"); }
+                    "toggle_grid" => { let _ = app.emit_to(label.as_str(), "menu-event", "toggle_grid"); }
+                    "toggle_debug" => { let _ = app.emit_to(label.as_str(), "menu-event", "toggle_debug"); }
+                    "toggle_2d_3d" => { let _ = app.emit_to(label.as_str(), "menu-event", "toggle_2d_3d"); }
+                    "zoom_in" => { let _ = app.emit_to(label.as_str(), "menu-event", "zoom_in"); }
+                    "zoom_out" => { let _ = app.emit_to(label.as_str(), "menu-event", "zoom_out"); }
+                    "zoom_reset" => { let _ = app.emit_to(label.as_str(), "menu-event", "zoom_reset"); }
+                    "fit_view" => { let _ = app.emit_to(label.as_str(), "menu-event", "fit_view"); }
+                    "show_scene_tree" => { let _ = app.emit_to(label.as_str(), "menu-event", "show_scene_tree"); }
+                    "show_inspector" => { let _ = app.emit_to(label.as_str(), "menu-event", "show_inspector"); }
+                    "show_assets" => { let _ = app.emit_to(label.as_str(), "menu-event", "show_assets"); }
+                    "show_animation" => { let _ = app.emit_to(label.as_str(), "menu-event", "show_animation"); }
+                    "show_code" => { let _ = app.emit_to(label.as_str(), "menu-event", "show_code"); }
+                    "show_visual_scripts" => { let _ = app.emit_to(label.as_str(), "menu-event", "show_visual_scripts"); }
+                    "show_shaders" => { let _ = app.emit_to(label.as_str(), "menu-event", "show_shaders"); }
+                    "show_behavior_trees" => { let _ = app.emit_to(label.as_str(), "menu-event", "show_behavior_trees"); }
+                    "show_state_machines" => { let _ = app.emit_to(label.as_str(), "menu-event", "show_state_machines"); }
+                    "show_ai" => { let _ = app.emit_to(label.as_str(), "menu-event", "show_ai"); }
                     // Game menu
-                    "play_game" => { let _ = window.emit("menu-event", "play_game"); }
-                    "stop_game" => { let _ = window.emit("menu-event", "stop_game"); }
-                    "restart_game" => { let _ = window.emit("menu-event", "restart_game"); }
-                    "ai_playtest" => { let _ = window.emit("menu-event", "ai_playtest"); }
-                    "game_settings" => { let _ = window.emit("menu-event", "game_settings"); }
-                    "restore_demo" => { let _ = window.emit("menu-event", "restore_demo"); }
+                    "play_game" => { let _ = app.emit_to(label.as_str(), "menu-event", "play_game"); }
+                    "stop_game" => { let _ = app.emit_to(label.as_str(), "menu-event", "stop_game"); }
+                    "restart_game" => { let _ = app.emit_to(label.as_str(), "menu-event", "restart_game"); }
+                    "ai_playtest" => { let _ = app.emit_to(label.as_str(), "menu-event", "ai_playtest"); }
+                    "game_settings" => { let _ = app.emit_to(label.as_str(), "menu-event", "game_settings"); }
+                    "restore_demo" => { let _ = app.emit_to(label.as_str(), "menu-event", "restore_demo"); }
                     // Window menu
-                    "community_gallery" => { let _ = window.emit("menu-event", "community_gallery"); }
-                    "marketplace" => { let _ = window.emit("menu-event", "marketplace"); }
+                    "community_gallery" => { let _ = app.emit_to(label.as_str(), "menu-event", "community_gallery"); }
+                    "marketplace" => { let _ = app.emit_to(label.as_str(), "menu-event", "marketplace"); }
                     // Help menu
-                    "getting_started" => { let _ = window.emit("menu-event", "getting_started"); }
-                    "keyboard_shortcuts" => { let _ = window.emit("menu-event", "keyboard_shortcuts"); }
-                    "documentation" => { let _ = window.emit("menu-event", "documentation"); }
-                    "report_issue" => { let _ = window.emit("menu-event", "report_issue"); }
-                    "about" => { let _ = window.emit("menu-event", "about"); }
+                    "getting_started" => { let _ = app.emit_to(label.as_str(), "menu-event", "getting_started"); }
+                    "keyboard_shortcuts" => { let _ = app.emit_to(label.as_str(), "menu-event", "keyboard_shortcuts"); }
+                    "documentation" => { let _ = app.emit_to(label.as_str(), "menu-event", "documentation"); }
+                    "report_issue" => { let _ = app.emit_to(label.as_str(), "menu-event", "report_issue"); }
+                    "about" => { let _ = app.emit_to(label.as_str(), "menu-event", "about"); }
                     _ => {}
                 }
             });
 
+            startup.record("menus", menus_started);
+
+            startup.time("tray", || tray::setup(app.handle()))?;
+            // A shortcut taken by another app shouldn't keep the editor from starting
+            startup.time("global shortcut", || {
+                let _ = ideas::register_capture_shortcut(app.handle(), &ideas::capture_shortcut(app.handle()));
+            });
+
+            // ==================== DEEP LINKS ====================
+            // promptplay:// links and "open with" files; the ones that launched the app are queued
+            // until the editor asks for them
+            let deep_links_started = Instant::now();
+            let handle = app.handle().clone();
+            app.deep_link().on_open_url(move |event| {
+                deep_link::dispatch(&handle, event.urls().iter().map(|url| url.to_string()));
+            });
+            #[cfg(any(windows, target_os = "linux"))]
+            let _ = app.deep_link().register_all();
+            let mut launch = deep_link::launch_arguments(&args);
+            if let Ok(Some(urls)) = app.deep_link().get_current() {
+                launch.extend(urls.iter().map(|url| url.to_string()));
+            }
+            deep_link::dispatch(app.handle(), launch);
+            startup.record("deep links", deep_links_started);
+
             Ok(())
         })
-        .invoke_handler(tauri::generate_handler![
+        .invoke_handler(command_trace::traced(read_only::guarded(tauri::generate_handler![
             commands::read_file,
             commands::write_file,
             commands::list_directory,
             commands::load_game_spec,
             commands::path_exists,
             commands::create_directory,
-            commands::export_game_html,
+            export::export_game_html,
             commands::read_binary_file,
             commands::write_binary_file,
             commands::delete_path,
@@ -316,9 +521,195 @@ fn main() {
             watcher_commands::stop_file_watcher,
             watcher_commands::get_watched_path,
             ai_client::ai_send_message,
+            ai_client::ai_cancel_message,
             ai_client::ai_set_api_key,
+            ai_client::ai_save_api_key,
+            ai_client::ai_delete_api_key,
+            ai_client::ai_get_provider,
+            ai_client::ai_set_provider,
+            ai_client::ai_list_models,
+            context_watchdog::compact_ai_context,
             ai_client::ai_check_api_key,
-        ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+            validation::validate_game_spec,
+            game_spec::load_typed_game_spec,
+            lint_rules::run_lint_rules,
+            hud::get_ui_schema,
+            progression::get_progression_graph,
+            difficulty::list_difficulty_presets,
+            difficulty::apply_difficulty,
+            seed::get_seed,
+            seed::reroll_seed,
+            assets::import_asset,
+            assets::get_asset_manifest,
+            assets::import_assets,
+            assets::generate_asset_thumbnails,
+            assets::get_image_dimensions,
+            assets::read_asset_preview,
+            licensing::set_asset_license,
+            licensing::import_asset_licenses_csv,
+            licensing::generate_credits,
+            history::list_history,
+            history::undo_last_change,
+            replace::replace_in_spec,
+            search_index::rebuild_search_index,
+            search_index::search_project,
+            search_index::query_entities,
+            scene_query::get_scene_summary,
+            scene_query::get_entities_page,
+            spec_changes::diff_spec_changes,
+            entity_locks::check_entity_locks,
+            entity_locks::protect_locked_entities,
+            entity_locks::set_entities_locked,
+            components::get_component_registry,
+            entity_ids::migrate_entity_ids,
+            entity_csv::preview_entities_csv,
+            entity_csv::import_entities_csv,
+            entity_csv::export_entities_csv,
+            scene_import::import_godot_scene,
+            scene_import::import_unity_scene_yaml,
+            godot_export::export_godot_project,
+            repro::export_repro_bundle,
+            thumbnails::render_scene_thumbnail,
+            thumbnails::render_spec_snapshot,
+            minimap::bake_minimap,
+            minimap::list_minimaps,
+            commands::save_game_spec,
+            save_conflicts::get_conflict_policy,
+            save_conflicts::set_conflict_policy,
+            formatter::format_game_spec,
+            formatter::get_format_config,
+            formatter::set_format_config,
+            rounding::round_game_spec,
+            world::get_world_bounds,
+            world::set_world_bounds,
+            spec_tests::run_spec_tests,
+            benchmark::benchmark_project,
+            encoding::read_text_file,
+            dialogs::pick_project_folder,
+            dialogs::pick_image_assets,
+            dialogs::pick_export_dir,
+            symlinks::list_symlinks,
+            symlinks::set_symlink_policy,
+            permissions::request_permission,
+            permissions::get_permissions,
+            permissions::set_permission,
+            permissions::reset_project_trust,
+            permissions::open_external,
+            project_lock::open_project,
+            project_lock::close_project,
+            projects::create_project,
+            projects::list_recent_projects,
+            projects::add_recent_project,
+            read_only::open_project_readonly,
+            health::check_project_health,
+            health::get_project_health,
+            hooks::list_hooks,
+            hooks::run_project_hooks,
+            hooks::get_hook_log,
+            export::export_game_embed,
+            export_diff::diff_exports,
+            asset_usage::analyze_asset_usage,
+            runtime::list_runtime_versions,
+            runtime::set_runtime_pin,
+            inbox::set_inbox_folder,
+            inbox::import_inbox,
+            references::get_reference_graph,
+            references::find_usages,
+            safe_delete::safe_delete,
+            rename::rename_entity,
+            rename::rename_asset,
+            backup::get_backup_settings,
+            backup::set_backup_settings,
+            backup::create_backup,
+            backup::list_backups,
+            backup::restore_backup,
+            sync::get_sync_settings,
+            sync::set_sync_settings,
+            sync::sync_status,
+            sync::sync_push,
+            sync::sync_pull,
+            git::publish_to_github,
+            git::git_push,
+            git::git_pull,
+            git::disconnect_github,
+            feedback::add_feedback,
+            feedback::list_feedback,
+            feedback::resolve_feedback,
+            ai_context::summarize_spec_for_ai,
+            autolabel::ai_autolabel_entities,
+            prompt_history::get_prompt_history,
+            prompt_history::set_prompt_outcome,
+            prompt_history::favorite_prompt,
+            prompt_history::search_prompts,
+            stt::get_stt_settings,
+            stt::set_stt_settings,
+            stt::transcribe_audio,
+            tts::get_tts_settings,
+            tts::set_tts_settings,
+            tts::synthesize_dialogue_line,
+            asset_pack::import_asset_pack,
+            palette::extract_palette,
+            collider_gen::generate_collider_from_sprite,
+            physics_materials::list_physics_materials,
+            frame_rate::audit_frame_rate,
+            frame_rate::migrate_to_delta_time,
+            parity::verify_runtime_parity,
+            heatmap::generate_heatmap,
+            workspace::open_project_window,
+            workspace::get_window_project,
+            deep_link::take_launch_requests,
+            tray::start_continuous_export,
+            tray::stop_continuous_export,
+            tray::get_background_status,
+            notifications::get_notification_settings,
+            notifications::set_notification_settings,
+            ideas::capture_idea,
+            ideas::list_ideas,
+            ideas::get_capture_shortcut,
+            ideas::set_capture_shortcut,
+            preview_server::start_preview_server,
+            preview_server::stop_preview_server,
+            preview_server::get_preview_server_status,
+            coop::start_coop_relay,
+            coop::stop_coop_relay,
+            coop::get_coop_relay_status,
+            cache::get_cache_usage,
+            cache::clear_cache,
+            cache::set_cache_dir,
+            startup::get_startup_report,
+            diagnostics::run_diagnostics,
+            command_trace::record_command_timings,
+            command_trace::get_slow_commands_report,
+            templates::create_template_from_project,
+            templates::validate_template,
+            tutorial::get_tutorial_state,
+            tutorial::verify_tutorial_step,
+            tools::run_tool,
+            encryption::get_project_encryption,
+            encryption::enable_project_encryption,
+            encryption::unlock_encrypted_project,
+            encryption::lock_encrypted_project,
+            encryption::disable_project_encryption,
+            entitlement::generate_game_keys,
+            entitlement::export_signing_key,
+            entitlement::import_signing_key,
+            services::get_service_settings,
+            services::set_service_settings,
+            services::test_service_connection,
+            accessibility::audit_accessibility,
+            accessibility::render_colorblind_preview,
+            animation_preview::render_animation_preview,
+            kits::install_kit,
+            kits::check_kit_updates,
+            kits::upgrade_kit,
+            spec_types::generate_spec_types,
+        ])))
+        .build(tauri::generate_context!())
+        .expect("error while running tauri application")
+        .run(|app, event| {
+            if let tauri::RunEvent::Exit = event {
+                preview_server::shutdown(app);
+                coop::shutdown(app);
+            }
+        });
 }
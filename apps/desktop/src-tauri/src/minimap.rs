@@ -0,0 +1,242 @@
+use crate::assets::ASSETS_DIR;
+use crate::thumbnails::{fill_rect, parse_tint, scene_entities, scene_file_stem};
+use crate::{paths, spec, world};
+use base64::Engine;
+use image::{ImageFormat, Rgba, RgbaImage};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Baked minimaps live under assets/minimap as `<scene>.png` plus `<scene>.json`
+pub const MINIMAP_DIR: &str = "minimap";
+const DEFAULT_MINIMAP_WIDTH: u32 = 160;
+/// Largest side of a baked minimap
+const MAX_MINIMAP_SIZE: u32 = 1024;
+const BACKGROUND: Rgba<u8> = Rgba([0, 0, 0, 0x99]);
+const COLLECTIBLE_TAGS: &[&str] = &["collectible", "coin", "pickup"];
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+pub struct MinimapPoint {
+    pub x: f64,
+    pub y: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MinimapMarker {
+    pub name: String,
+    pub x: f64,
+    pub y: f64,
+}
+
+/// Sidecar describing a baked minimap image
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MinimapMetadata {
+    pub scene: Option<String>,
+    /// Project-relative path of the PNG
+    pub image: String,
+    pub width: u32,
+    pub height: u32,
+    #[serde(default)]
+    pub world_x: f64,
+    #[serde(default)]
+    pub world_y: f64,
+    pub world_width: f64,
+    pub world_height: f64,
+    pub player_start: Option<MinimapPoint>,
+    pub collectibles: Vec<MinimapMarker>,
+}
+
+fn minimap_dir(project_path: &str) -> PathBuf {
+    PathBuf::from(project_path).join(ASSETS_DIR).join(MINIMAP_DIR)
+}
+
+fn position(entity: &Value) -> Option<MinimapPoint> {
+    Some(MinimapPoint {
+        x: entity.pointer("/components/transform/x")?.as_f64()?,
+        y: entity.pointer("/components/transform/y")?.as_f64()?,
+    })
+}
+
+fn has_tag(entity: &Value, tags: &[&str]) -> bool {
+    entity
+        .get("tags")
+        .and_then(Value::as_array)
+        .is_some_and(|t| t.iter().filter_map(Value::as_str).any(|t| tags.contains(&t)))
+}
+
+/// Static level geometry: sprites that neither move nor take input
+fn is_static(entity: &Value) -> bool {
+    let components = entity.get("components");
+    components.and_then(|c| c.get("sprite")).is_some()
+        && components.and_then(|c| c.get("velocity")).is_none()
+        && components.and_then(|c| c.get("input")).is_none()
+        && !has_tag(entity, COLLECTIBLE_TAGS)
+}
+
+/// Downsample a scene's static geometry and collect marker positions; a world taller than wide is
+/// baked narrower than `width` so the height stays within MAX_MINIMAP_SIZE
+pub fn bake(game_spec: &Value, scene: Option<&str>, width: u32) -> (RgbaImage, MinimapMetadata) {
+    let bounds = world::world_bounds(game_spec);
+    let scale = (width.min(MAX_MINIMAP_SIZE) as f64 / bounds.width).min(MAX_MINIMAP_SIZE as f64 / bounds.height);
+    let width = ((bounds.width * scale).round() as u32).clamp(1, MAX_MINIMAP_SIZE);
+    let height = ((bounds.height * scale).round() as u32).clamp(1, MAX_MINIMAP_SIZE);
+    let mut image = RgbaImage::from_pixel(width, height, BACKGROUND);
+
+    let entities = scene_entities(game_spec, scene);
+
+    for entity in entities.iter().filter(|e| is_static(e)) {
+        let Some(center) = position(entity) else {
+            continue;
+        };
+        let number = |pointer: &str, default: f64| {
+            entity.pointer(pointer).and_then(Value::as_f64).unwrap_or(default)
+        };
+        let w = number("/components/sprite/width", 32.0) * number("/components/transform/scaleX", 1.0).abs();
+        let h = number("/components/sprite/height", 32.0) * number("/components/transform/scaleY", 1.0).abs();
+        fill_rect(
+            &mut image,
+            ((center.x - w / 2.0 - bounds.x) * scale).round() as i64,
+            ((center.y - h / 2.0 - bounds.y) * scale).round() as i64,
+            ((w * scale).round() as i64).max(1),
+            ((h * scale).round() as i64).max(1),
+            parse_tint(entity.pointer("/components/sprite/tint")),
+        );
+    }
+
+    let player_start = entities
+        .iter()
+        .find(|e| e.pointer("/components/input").is_some() || has_tag(e, &["player"]))
+        .and_then(|e| position(e));
+
+    let collectibles = entities
+        .iter()
+        .filter(|e| has_tag(e, COLLECTIBLE_TAGS))
+        .filter_map(|e| {
+            let point = position(e)?;
+            Some(MinimapMarker {
+                name: e.get("name").and_then(Value::as_str).unwrap_or_default().to_string(),
+                x: point.x,
+                y: point.y,
+            })
+        })
+        .collect();
+
+    let metadata = MinimapMetadata {
+        scene: scene.map(str::to_string),
+        image: format!("{}/{}/{}.png", ASSETS_DIR, MINIMAP_DIR, scene_file_stem(scene)),
+        width,
+        height,
+        world_x: bounds.x,
+        world_y: bounds.y,
+        world_width: bounds.width,
+        world_height: bounds.height,
+        player_start,
+        collectibles,
+    };
+
+    (image, metadata)
+}
+
+/// Bake and write one scene's minimap image and metadata into the project's assets
+pub fn bake_to_project(
+    project_path: &str,
+    game_spec: &Value,
+    scene: Option<&str>,
+    width: u32,
+) -> Result<MinimapMetadata, String> {
+    let (image, metadata) = bake(game_spec, scene, width);
+
+    let dir = minimap_dir(project_path);
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create minimap directory: {}", e))?;
+
+    image
+        .save_with_format(PathBuf::from(project_path).join(&metadata.image), ImageFormat::Png)
+        .map_err(|e| format!("Failed to write minimap image: {}", e))?;
+
+    let content = serde_json::to_string_pretty(&metadata)
+        .map_err(|e| format!("Failed to serialize minimap metadata: {}", e))?;
+    fs::write(dir.join(format!("{}.json", scene_file_stem(scene))), content)
+        .map_err(|e| format!("Failed to write minimap metadata: {}", e))?;
+
+    Ok(metadata)
+}
+
+/// Every minimap previously baked for the project
+pub fn load_all(project_path: &str) -> Vec<MinimapMetadata> {
+    let Ok(entries) = fs::read_dir(minimap_dir(project_path)) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|e| e == "json"))
+        .filter_map(|path| fs::read_to_string(path).ok())
+        .filter_map(|content| serde_json::from_str(&content).ok())
+        .collect()
+}
+
+/// Re-bake every existing minimap after game.json changes, keeping each one's width
+pub fn rebake_existing(project_path: &str) -> Result<usize, String> {
+    let existing = load_all(project_path);
+    if existing.is_empty() {
+        return Ok(0);
+    }
+
+    let game_spec = spec::load_project_spec(project_path)?;
+    for metadata in &existing {
+        bake_to_project(project_path, &game_spec, metadata.scene.as_deref(), metadata.width)?;
+    }
+    Ok(existing.len())
+}
+
+/// Called by the file watcher; only game.json edits can change a minimap
+pub fn notify_changed(project_path: &Path, changed: &Path) {
+    if paths::same_path(changed, &project_path.join("game.json")) {
+        let _ = rebake_existing(&project_path.to_string_lossy());
+    }
+}
+
+/// Key of a scene's minimap in the export payload: the scene id as the runtime's UI elements name
+/// it, and "" for the legacy top-level list
+pub fn payload_key(scene: Option<&str>) -> String {
+    scene.unwrap_or_default().to_string()
+}
+
+/// Baked minimaps keyed by [`payload_key`], with the image inlined as a data URL for exports
+pub fn export_payload(project_path: &str) -> Map<String, Value> {
+    load_all(project_path)
+        .into_iter()
+        .filter_map(|metadata| {
+            let bytes = fs::read(PathBuf::from(project_path).join(&metadata.image)).ok()?;
+            let mut value = serde_json::to_value(&metadata).ok()?;
+            value["image"] = Value::from(format!(
+                "data:image/png;base64,{}",
+                base64::engine::general_purpose::STANDARD.encode(bytes)
+            ));
+            Some((payload_key(metadata.scene.as_deref()), value))
+        })
+        .collect()
+}
+
+/// Bake a scene's minimap (image + player start and collectible markers) into assets/minimap
+#[tauri::command]
+pub async fn bake_minimap(
+    project_path: String,
+    scene: Option<String>,
+    width: Option<u32>,
+) -> Result<MinimapMetadata, String> {
+    let game_spec = spec::load_project_spec(&project_path)?;
+    if let Some(scene_id) = scene.as_deref().filter(|id| !spec::scene_exists(&game_spec, id)) {
+        return Err(format!("Scene '{}' not found", scene_id));
+    }
+    let width = width.unwrap_or(DEFAULT_MINIMAP_WIDTH).clamp(16, MAX_MINIMAP_SIZE);
+    bake_to_project(&project_path, &game_spec, scene.as_deref(), width)
+}
+
+/// List the minimaps baked for a project
+#[tauri::command]
+pub async fn list_minimaps(project_path: String) -> Result<Vec<MinimapMetadata>, String> {
+    Ok(load_all(&project_path))
+}
@@ -0,0 +1,58 @@
+use crate::settings::AppSettings;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_notification::NotificationExt;
+
+/// Kinds of long-running work that end in an OS notification
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "camelCase")]
+pub enum NotificationCategory {
+    /// An HTML or embed export finished (continuous exports only notify on failure)
+    Export,
+    /// A project was published
+    Publish,
+    /// A batch of AI requests finished
+    AiBatch,
+    /// The file watcher stopped reporting changes
+    Watcher,
+    /// An automation hook from .promptplay/hooks.toml failed
+    Hooks,
+}
+
+/// Which notifications are shown (`notifications` in the app settings); all are on by default
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct NotificationSettings {
+    /// Categories the user opted out of
+    pub disabled: BTreeSet<NotificationCategory>,
+    /// Also notify while a PromptPlay window has focus
+    pub when_focused: bool,
+}
+
+fn app_focused(app: &AppHandle) -> bool {
+    app.webview_windows().values().any(|window| window.is_focused().unwrap_or(false))
+}
+
+/// Report the end of a long-running task with an OS notification, unless the user opted out of
+/// the category or is looking at the app anyway
+pub fn task_finished(app: &AppHandle, category: NotificationCategory, title: &str, body: &str) {
+    let settings = AppSettings::load(app).notifications;
+    if settings.disabled.contains(&category) || (!settings.when_focused && app_focused(app)) {
+        return;
+    }
+    let _ = app.notification().builder().title(title).body(body).show();
+}
+
+#[tauri::command]
+pub async fn get_notification_settings(app: AppHandle) -> Result<NotificationSettings, String> {
+    Ok(AppSettings::load(&app).notifications)
+}
+
+/// Change which task notifications are shown
+#[tauri::command]
+pub async fn set_notification_settings(app: AppHandle, settings: NotificationSettings) -> Result<(), String> {
+    let mut app_settings = AppSettings::load(&app);
+    app_settings.notifications = settings;
+    app_settings.save(&app)
+}
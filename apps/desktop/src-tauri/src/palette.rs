@@ -0,0 +1,163 @@
+use crate::paths;
+use crate::project_settings::ProjectSettings;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+const DEFAULT_COLORS: usize = 8;
+const MAX_COLORS: usize = 32;
+/// Pixels sampled per image; plenty for dominant colors and fast on large sheets
+const MAX_SAMPLES: usize = 20_000;
+const KMEANS_ITERATIONS: usize = 12;
+/// Mostly transparent pixels are sprite padding, not art
+const MIN_ALPHA: u8 = 128;
+
+#[derive(Debug, Serialize)]
+pub struct PaletteColor {
+    /// "#rrggbb"
+    pub hex: String,
+    /// The same color as a sprite tint value
+    pub tint: u32,
+    /// Fraction of sampled pixels nearest to this color
+    pub share: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Palette {
+    pub colors: Vec<PaletteColor>,
+    /// The project palette after merging, when `save` was set
+    pub project_palette: Option<Vec<String>>,
+}
+
+fn distance(a: [f64; 3], b: [f64; 3]) -> f64 {
+    (0..3).map(|i| (a[i] - b[i]).powi(2)).sum()
+}
+
+fn nearest(centers: &[[f64; 3]], pixel: [f64; 3]) -> usize {
+    (0..centers.len())
+        .min_by(|&a, &b| distance(centers[a], pixel).total_cmp(&distance(centers[b], pixel)))
+        .unwrap_or(0)
+}
+
+/// k-means over the samples, seeded deterministically with farthest-point picks so the same
+/// image always gives the same palette
+fn cluster(samples: &[[f64; 3]], k: usize) -> Vec<([f64; 3], usize)> {
+    let mut centers = vec![samples[0]];
+    while centers.len() < k {
+        let farthest = samples
+            .iter()
+            .max_by(|a, b| {
+                let da = distance(centers[nearest(&centers, **a)], **a);
+                let db = distance(centers[nearest(&centers, **b)], **b);
+                da.total_cmp(&db)
+            })
+            .copied()
+            .unwrap_or(samples[0]);
+        if centers.contains(&farthest) {
+            // Fewer distinct colors than requested
+            break;
+        }
+        centers.push(farthest);
+    }
+
+    let mut counts = vec![0; centers.len()];
+    for _ in 0..KMEANS_ITERATIONS {
+        let mut sums = vec![[0.0; 3]; centers.len()];
+        counts = vec![0; centers.len()];
+        for &sample in samples {
+            let index = nearest(&centers, sample);
+            for channel in 0..3 {
+                sums[index][channel] += sample[channel];
+            }
+            counts[index] += 1;
+        }
+        for (index, center) in centers.iter_mut().enumerate() {
+            if counts[index] > 0 {
+                *center = sums[index].map(|sum| sum / counts[index] as f64);
+            }
+        }
+    }
+    centers.into_iter().zip(counts).filter(|(_, count)| *count > 0).collect()
+}
+
+fn to_hex(rgb: [f64; 3]) -> (String, u32) {
+    let [r, g, b] = rgb.map(|c| c.round().clamp(0.0, 255.0) as u32);
+    let tint = (r << 16) | (g << 8) | b;
+    (format!("#{:06x}", tint), tint)
+}
+
+/// The project palette for the AI system prompt; empty when none is set
+pub fn describe_for_prompt(project_path: &str) -> String {
+    ProjectSettings::load(project_path)
+        .map(|settings| {
+            settings
+                .palette
+                .iter()
+                .filter_map(|hex| u32::from_str_radix(hex.trim_start_matches('#'), 16).ok().map(|tint| format!("{} (tint {})", hex, tint)))
+                .collect::<Vec<_>>()
+                .join(", ")
+        })
+        .unwrap_or_default()
+}
+
+/// Dominant colors of an image, most common first. With `save` they are merged into the
+/// project palette in project.json, which the AI uses to pick tints for new entities.
+#[tauri::command]
+pub async fn extract_palette(
+    project_path: Option<String>,
+    image_path: String,
+    n_colors: Option<usize>,
+    save: Option<bool>,
+) -> Result<Palette, String> {
+    let n_colors = n_colors.unwrap_or(DEFAULT_COLORS).clamp(1, MAX_COLORS);
+    // Project-relative paths such as "assets/hero.png" resolve against the project
+    let path = match &project_path {
+        Some(project) if Path::new(&image_path).is_relative() => paths::project_root(project).join(&image_path),
+        _ => PathBuf::from(&image_path),
+    };
+    let image = image::open(&path)
+        .map_err(|e| format!("Failed to open image {}: {}", path.display(), e))?
+        .to_rgba8();
+
+    let (width, height) = image.dimensions();
+    let step = ((width as usize * height as usize) / MAX_SAMPLES).max(1);
+    let samples: Vec<[f64; 3]> = (0..width as usize * height as usize)
+        .step_by(step)
+        .map(|i| image.get_pixel((i % width as usize) as u32, (i / width as usize) as u32))
+        .filter(|pixel| pixel.0[3] >= MIN_ALPHA)
+        .map(|pixel| [pixel.0[0] as f64, pixel.0[1] as f64, pixel.0[2] as f64])
+        .collect();
+    if samples.is_empty() {
+        return Err("Image has no opaque pixels".to_string());
+    }
+
+    let mut clusters = cluster(&samples, n_colors);
+    clusters.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+    let colors: Vec<PaletteColor> = clusters
+        .into_iter()
+        .map(|(center, count)| {
+            let (hex, tint) = to_hex(center);
+            PaletteColor {
+                hex,
+                tint,
+                share: count as f64 / samples.len() as f64,
+            }
+        })
+        .collect();
+
+    let project_palette = match (&project_path, save.unwrap_or(false)) {
+        (Some(project), true) => {
+            let mut settings = ProjectSettings::load(project)?;
+            for color in &colors {
+                if !settings.palette.contains(&color.hex) && settings.palette.len() < MAX_COLORS {
+                    settings.palette.push(color.hex.clone());
+                }
+            }
+            settings.save(project)?;
+            Some(settings.palette)
+        }
+        (None, true) => return Err("Saving a palette requires a project".to_string()),
+        _ => None,
+    };
+
+    Ok(Palette { colors, project_palette })
+}
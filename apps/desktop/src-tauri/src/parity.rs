@@ -0,0 +1,241 @@
+use crate::cache::{self, CacheCategory};
+use crate::export::{self, ExportOptions};
+use crate::simulator::{InputEvent, Simulation};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::process::Command;
+use tauri::AppHandle;
+
+/// Both sides step at the runtime's fixed 60 Hz tick
+const STEP_SECONDS: f64 = 1.0 / 60.0;
+const DEFAULT_SECONDS: f64 = 5.0;
+/// Positions may differ this much before an entity counts as diverged; Matter's solver and the
+/// simulator's resolver don't settle contacts to the same sub-pixel
+const DEFAULT_TOLERANCE: f64 = 2.0;
+/// Frames between trajectory samples
+const DEFAULT_SAMPLE_EVERY: u64 = 6;
+/// Chromium-family binaries tried in order when no browser is given
+const BROWSER_CANDIDATES: &[&str] = &[
+    "chromium",
+    "chromium-browser",
+    "google-chrome",
+    "google-chrome-stable",
+    "microsoft-edge",
+    "/Applications/Google Chrome.app/Contents/MacOS/Google Chrome",
+    "C:\\Program Files\\Google\\Chrome\\Application\\chrome.exe",
+    "C:\\Program Files (x86)\\Microsoft\\Edge\\Application\\msedge.exe",
+];
+const RESULT_MARKER: &str = r#"<script type="application/json" id="parity-result">"#;
+
+/// Replays the trace on the export runtime, bypassing requestAnimationFrame, and leaves the
+/// sampled positions in a JSON script element for --dump-dom to print
+const DRIVER_SCRIPT: &str = r#"
+        {
+            const parity = __PARITY__;
+            let result;
+            try {
+                runtime.reset();
+                const samples = [];
+                for (let frame = 0; frame < parity.frames; frame++) {
+                    const now = frame * parity.step;
+                    runtime.keys.clear();
+                    for (const input of parity.trace) {
+                        const at = input.at || 0;
+                        if (now >= at && now < at + (input.for ?? parity.step)) {
+                            for (const key of input.press) runtime.keys.set(key, true);
+                        }
+                    }
+                    runtime.update(parity.step);
+                    if ((frame + 1) % parity.every === 0 || frame + 1 === parity.frames) {
+                        samples.push({ frame: frame + 1, entities: runtime.entities.filter(e => !e.spawnedBy).map(e => [e.name, e.x, e.y]) });
+                    }
+                }
+                result = { samples };
+            } catch (err) {
+                result = { error: String(err && err.stack || err) };
+            }
+            const out = document.createElement('script');
+            out.type = 'application/json';
+            out.id = 'parity-result';
+            out.textContent = JSON.stringify(result).replace(/</g, '\\u003c');
+            document.body.appendChild(out);
+        }"#;
+
+#[derive(Debug, Deserialize)]
+struct RuntimeSample {
+    frame: u64,
+    entities: Vec<(String, f64, f64)>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RuntimeResult {
+    #[serde(default)]
+    samples: Vec<RuntimeSample>,
+    error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct EntityParity {
+    pub entity: String,
+    /// Largest distance between the two trajectories, in px
+    pub max_distance: f64,
+    /// First sampled frame past the tolerance
+    pub first_divergence_frame: Option<u64>,
+    pub simulator_end: (f64, f64),
+    pub runtime_end: (f64, f64),
+}
+
+#[derive(Debug, Serialize)]
+pub struct ParityReport {
+    /// No entity diverged past the tolerance
+    pub passed: bool,
+    pub frames: u64,
+    pub tolerance: f64,
+    /// Per-entity comparison, most diverged first
+    pub entities: Vec<EntityParity>,
+    /// Entities only one side tracks, e.g. pool and spawner templates the runtime doesn't place
+    pub unmatched: Vec<String>,
+    /// The browser that ran the export
+    pub browser: String,
+    pub notes: Vec<String>,
+}
+
+fn round(value: f64) -> f64 {
+    (value * 10.0).round() / 10.0
+}
+
+/// Run the page in a headless browser and return its DOM, trying each candidate until one starts
+fn dump_dom(page: &std::path::Path, browser: Option<&str>, budget_ms: u64) -> Result<(String, String), String> {
+    let candidates: Vec<&str> = match browser.filter(|b| !b.is_empty()) {
+        Some(browser) => vec![browser],
+        None => BROWSER_CANDIDATES.to_vec(),
+    };
+    for candidate in candidates {
+        let output = Command::new(candidate)
+            .arg("--headless=new")
+            .arg("--disable-gpu")
+            .arg("--no-first-run")
+            .arg("--disable-extensions")
+            .arg("--allow-file-access-from-files")
+            .arg(format!("--virtual-time-budget={}", budget_ms))
+            .arg("--dump-dom")
+            .arg(format!("file://{}", page.to_string_lossy().replace('\\', "/")))
+            .output();
+        match output {
+            Ok(output) if output.status.success() => {
+                return Ok((candidate.to_string(), String::from_utf8_lossy(&output.stdout).to_string()))
+            }
+            Ok(output) => {
+                return Err(format!(
+                    "Headless browser {} failed: {}",
+                    candidate,
+                    String::from_utf8_lossy(&output.stderr).trim()
+                ))
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(format!("Failed to run headless browser {}: {}", candidate, e)),
+        }
+    }
+    Err("No headless browser found; install Chrome or Chromium, or pass its path as `browser`".to_string())
+}
+
+/// Run the same input trace through the headless simulator and the exported JS runtime (in a
+/// headless Chromium) and compare entity trajectories, catching engine/runtime drift that would
+/// break replays and spec tests
+#[tauri::command]
+pub async fn verify_runtime_parity(
+    app: AppHandle,
+    game_spec_json: String,
+    trace: Vec<InputEvent>,
+    seconds: Option<f64>,
+    tolerance: Option<f64>,
+    sample_every: Option<u64>,
+    browser: Option<String>,
+) -> Result<ParityReport, String> {
+    // Compare against exactly what ships: seed pinned, materials baked
+    let spec = export::prepare_spec(&game_spec_json, &ExportOptions::default())?;
+    let seconds = seconds.unwrap_or(DEFAULT_SECONDS).clamp(0.1, 600.0);
+    let frames = (seconds / STEP_SECONDS).round() as u64;
+    let every = sample_every.unwrap_or(DEFAULT_SAMPLE_EVERY).clamp(1, frames.max(1));
+    let tolerance = tolerance.unwrap_or(DEFAULT_TOLERANCE).max(0.0);
+
+    let config = json!({ "trace": trace, "frames": frames, "every": every, "step": STEP_SECONDS });
+    let driver = DRIVER_SCRIPT.replace("__PARITY__", &config.to_string().replace('<', "\\u003c"));
+    let page = cache::dir(&app, CacheCategory::Exports)?.join(format!("parity-{}.html", uuid::Uuid::new_v4()));
+    fs::write(&page, export::headless_page(&app, &spec, &driver).await?)
+        .map_err(|e| format!("Failed to write parity page: {}", e))?;
+    let dumped = dump_dom(&page, browser.as_deref(), (seconds * 1000.0) as u64 + 5000);
+    let _ = fs::remove_file(&page);
+    let (browser, dom) = dumped?;
+
+    let start = dom
+        .find(RESULT_MARKER)
+        .map(|i| i + RESULT_MARKER.len())
+        .ok_or("The exported runtime produced no trajectory; it likely failed to load")?;
+    let end = dom[start..].find("</script>").map(|i| start + i).unwrap_or(dom.len());
+    let result: RuntimeResult = serde_json::from_str(&dom[start..end])
+        .map_err(|e| format!("Failed to parse runtime trajectory: {}", e))?;
+    if let Some(error) = result.error {
+        return Err(format!("Exported runtime threw while replaying the trace: {}", error));
+    }
+
+    let mut simulation = Simulation::new(&spec, None);
+    let mut compared: BTreeMap<String, EntityParity> = BTreeMap::new();
+    let mut unmatched: BTreeSet<String> = BTreeSet::new();
+    let mut frame = 0;
+    for sample in &result.samples {
+        simulation.run((sample.frame - frame) as f64 * STEP_SECONDS, &trace);
+        frame = sample.frame;
+        for (name, rx, ry) in &sample.entities {
+            let read = |axis: &str| simulation.read(&format!("entity.{}.{}", name, axis)).ok()?.as_f64();
+            let (Some(sx), Some(sy)) = (read("x"), read("y")) else {
+                unmatched.insert(name.clone());
+                continue;
+            };
+            let distance = ((sx - rx).powi(2) + (sy - ry).powi(2)).sqrt();
+            let entry = compared.entry(name.clone()).or_insert_with(|| EntityParity {
+                entity: name.clone(),
+                max_distance: 0.0,
+                first_divergence_frame: None,
+                simulator_end: (0.0, 0.0),
+                runtime_end: (0.0, 0.0),
+            });
+            entry.max_distance = entry.max_distance.max(round(distance));
+            if distance > tolerance && entry.first_divergence_frame.is_none() {
+                entry.first_divergence_frame = Some(sample.frame);
+            }
+            entry.simulator_end = (round(sx), round(sy));
+            entry.runtime_end = (round(*rx), round(*ry));
+        }
+    }
+    for entity in crate::thumbnails::scene_entities(&spec, None) {
+        if let Some(name) = entity.get("name").and_then(Value::as_str) {
+            if !compared.contains_key(name) {
+                unmatched.insert(name.to_string());
+            }
+        }
+    }
+
+    let mut entities: Vec<EntityParity> = compared.into_values().collect();
+    entities.sort_by(|a, b| b.max_distance.total_cmp(&a.max_distance));
+    let passed = entities.iter().all(|e| e.first_divergence_frame.is_none());
+    let mut notes = Vec::new();
+    if !passed && spec.pointer("/config/deltaTime").and_then(Value::as_bool) != Some(true) {
+        notes.push(
+            "The legacy runtime moves bodies at 0.6x the spec's px/s; migrate_to_delta_time makes both sides use the same units"
+                .to_string(),
+        );
+    }
+
+    Ok(ParityReport {
+        passed,
+        frames,
+        tolerance,
+        entities,
+        unmatched: unmatched.into_iter().collect(),
+        browser,
+        notes,
+    })
+}
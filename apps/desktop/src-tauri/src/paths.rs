@@ -0,0 +1,96 @@
+use std::path::{Component, Path, PathBuf};
+
+/// Longest path the classic Win32 APIs accept without the `\\?\` prefix
+const MAX_PATH: usize = 260;
+const VERBATIM_PREFIX: &str = r"\\?\";
+const VERBATIM_UNC_PREFIX: &str = r"\\?\UNC\";
+
+/// Strip verbatim prefixes so paths from canonicalize and the watcher match what the user picked:
+/// `\\?\C:\game` becomes `C:\game` and `\\?\UNC\server\share` becomes `\\server\share`
+pub fn normalize(path: &Path) -> PathBuf {
+    let text = path.to_string_lossy();
+    if let Some(rest) = text.strip_prefix(VERBATIM_UNC_PREFIX) {
+        return PathBuf::from(format!(r"\\{}", rest));
+    }
+    match text.strip_prefix(VERBATIM_PREFIX) {
+        // Only drive paths; volume GUID paths have no non-verbatim form
+        Some(rest) if rest.as_bytes().get(1) == Some(&b':') => PathBuf::from(rest),
+        _ => path.to_path_buf(),
+    }
+}
+
+/// Path to hand to std::fs: on Windows, long absolute paths get the `\\?\` prefix.
+/// Verbatim paths skip `..` handling, so the path is cleaned lexically first.
+pub fn for_io(path: &Path) -> PathBuf {
+    if !cfg!(windows) || !path.is_absolute() || path.as_os_str().len() < MAX_PATH {
+        return path.to_path_buf();
+    }
+    let text = path.to_string_lossy();
+    if text.starts_with(VERBATIM_PREFIX) {
+        return path.to_path_buf();
+    }
+
+    let mut clean = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                clean.pop();
+            }
+            other => clean.push(other),
+        }
+    }
+    let clean = clean.to_string_lossy().replace('/', "\\");
+    match clean.strip_prefix(r"\\") {
+        Some(share) => PathBuf::from(format!("{}{}", VERBATIM_UNC_PREFIX, share)),
+        None => PathBuf::from(format!("{}{}", VERBATIM_PREFIX, clean)),
+    }
+}
+
+/// Canonical form of a path, falling back to the given path when it can't be resolved
+/// (e.g. a network share that is briefly unreachable)
+pub fn canonical(path: &Path) -> PathBuf {
+    std::fs::canonicalize(for_io(path))
+        .map(|p| normalize(&p))
+        .unwrap_or_else(|_| normalize(path))
+}
+
+pub fn project_root(project_path: &str) -> PathBuf {
+    canonical(Path::new(project_path))
+}
+
+/// Windows paths compare case-insensitively; elsewhere they are exact
+fn component_eq(a: Component, b: Component) -> bool {
+    if cfg!(windows) {
+        a.as_os_str().to_string_lossy().to_lowercase() == b.as_os_str().to_string_lossy().to_lowercase()
+    } else {
+        a == b
+    }
+}
+
+/// `path` relative to `root`, tolerating verbatim prefixes and (on Windows) case differences
+pub fn relative_to(path: &Path, root: &Path) -> Option<PathBuf> {
+    let path = normalize(path);
+    let root = normalize(root);
+    let mut rest = path.components();
+    for expected in root.components() {
+        if !component_eq(rest.next()?, expected) {
+            return None;
+        }
+    }
+    Some(rest.as_path().to_path_buf())
+}
+
+pub fn same_path(a: &Path, b: &Path) -> bool {
+    relative_to(a, b).is_some_and(|rest| rest.as_os_str().is_empty())
+}
+
+/// Forward-slash relative path as stored in specs and manifests
+pub fn to_spec_path(relative: &Path) -> String {
+    relative.to_string_lossy().replace('\\', "/")
+}
+
+/// Path string for the frontend, without verbatim prefixes
+pub fn to_display(path: &Path) -> String {
+    normalize(path).to_string_lossy().to_string()
+}
@@ -0,0 +1,210 @@
+use crate::dialogs::run_dialog;
+use crate::paths;
+use crate::settings::AppSettings;
+use rfd::{MessageButtons, MessageDialog, MessageDialogResult, MessageLevel};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use tauri::AppHandle;
+use tauri_plugin_shell::ShellExt;
+
+const ALLOW_ONCE: &str = "Allow Once";
+const ALWAYS_ALLOW: &str = "Always Allow";
+const DENY: &str = "Deny";
+const CANCEL: &str = "Cancel";
+const TRUST: &str = "Run Them";
+const DISTRUST: &str = "Don't Run";
+
+/// Operations that can destroy data or reach outside the app, and so need the user's consent
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "camelCase")]
+pub enum Operation {
+    DeleteOutsideProject,
+    OpenExternal,
+    PublishUpload,
+    /// Running an allowlisted tool that pushes or uploads
+    RunTool,
+}
+
+impl Operation {
+    fn prompt(&self) -> &'static str {
+        match self {
+            Operation::DeleteOutsideProject => "Delete a file outside the current project?",
+            Operation::OpenExternal => "Open this link or file with another application?",
+            Operation::PublishUpload => "Upload this game to the public gallery?",
+            Operation::RunTool => "Run this command? It sends project files to another service.",
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum PermissionChoice {
+    Allow,
+    Deny,
+}
+
+/// Whether a project's own commands (its hooks.toml) may run, tied to the file it was given for
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectTrust {
+    pub choice: PermissionChoice,
+    /// SHA-256 of the file the answer covers; any change to it asks again
+    pub digest: String,
+}
+
+fn trust_key(project_path: &str) -> String {
+    paths::to_display(&paths::project_root(project_path))
+}
+
+/// Ask the user (or reuse a remembered answer) before performing `operation` on `detail`
+pub fn check(app: &AppHandle, operation: Operation, detail: &str) -> Result<bool, String> {
+    match AppSettings::load(app).permissions.get(&operation) {
+        Some(PermissionChoice::Allow) => return Ok(true),
+        Some(PermissionChoice::Deny) => return Ok(false),
+        None => {}
+    }
+
+    // Commands can be triggered by AI-suggested actions, so show exactly what is affected
+    let description = format!("{}\n\n{}", operation.prompt(), detail);
+    let answer = run_dialog(move || {
+        MessageDialog::new()
+            .set_title("Permission Required")
+            .set_description(description)
+            .set_level(MessageLevel::Warning)
+            .set_buttons(MessageButtons::YesNoCancelCustom(
+                ALLOW_ONCE.to_string(),
+                ALWAYS_ALLOW.to_string(),
+                DENY.to_string(),
+            ))
+            .show()
+    })?;
+
+    match answer {
+        MessageDialogResult::Custom(label) if label == ALWAYS_ALLOW => {
+            let mut settings = AppSettings::load(app);
+            settings.permissions.insert(operation, PermissionChoice::Allow);
+            settings.save(app)?;
+            Ok(true)
+        }
+        MessageDialogResult::Custom(label) => Ok(label == ALLOW_ONCE),
+        MessageDialogResult::Yes | MessageDialogResult::Ok => Ok(true),
+        _ => Ok(false),
+    }
+}
+
+/// Like `check`, but as an error for commands that should stop when refused
+pub fn require(app: &AppHandle, operation: Operation, detail: &str) -> Result<(), String> {
+    if check(app, operation, detail)? {
+        Ok(())
+    } else {
+        Err(format!("Permission denied: {}", detail))
+    }
+}
+
+/// Ask before running commands a project configures for itself, such as the hooks in `file`.
+/// Projects travel between people, so nothing they define runs until the user agrees. Both
+/// answers are remembered for this version of `content`; editing the file asks again.
+pub fn check_project_trust(
+    app: &AppHandle,
+    project_path: &str,
+    file: &str,
+    summary: &str,
+    content: &str,
+) -> Result<bool, String> {
+    let key = trust_key(project_path);
+    let digest: String = Sha256::digest(content.as_bytes()).iter().map(|b| format!("{:02x}", b)).collect();
+    if let Some(trust) = AppSettings::load(app).trusted_projects.get(&key) {
+        if trust.digest == digest {
+            return Ok(trust.choice == PermissionChoice::Allow);
+        }
+    }
+
+    let description = format!(
+        "This project defines commands in {} that run automatically:\n\n{}\n\nOnly run them if you trust where {} came from.",
+        file, summary, key
+    );
+    let answer = run_dialog(move || {
+        MessageDialog::new()
+            .set_title("Run Project Hooks?")
+            .set_description(description)
+            .set_level(MessageLevel::Warning)
+            .set_buttons(MessageButtons::OkCancelCustom(TRUST.to_string(), DISTRUST.to_string()))
+            .show()
+    })?;
+    let trusted = matches!(&answer, MessageDialogResult::Custom(label) if label == TRUST)
+        || matches!(answer, MessageDialogResult::Ok);
+
+    let mut settings = AppSettings::load(app);
+    let choice = if trusted { PermissionChoice::Allow } else { PermissionChoice::Deny };
+    settings.trusted_projects.insert(key, ProjectTrust { choice, digest });
+    settings.save(app)?;
+    Ok(trusted)
+}
+
+/// Forget whether a project's hooks may run, so the next hook asks again
+#[tauri::command]
+pub async fn reset_project_trust(app: AppHandle, project_path: String) -> Result<(), String> {
+    let mut settings = AppSettings::load(&app);
+    if settings.trusted_projects.remove(&trust_key(&project_path)).is_some() {
+        settings.save(&app)?;
+    }
+    Ok(())
+}
+
+/// Confirm a sensitive frontend-side operation (e.g. a gallery upload) before it runs
+#[tauri::command]
+pub async fn request_permission(app: AppHandle, operation: Operation, detail: String) -> Result<bool, String> {
+    check(&app, operation, &detail)
+}
+
+/// Remembered permission choices
+#[tauri::command]
+pub async fn get_permissions(app: AppHandle) -> Result<BTreeMap<Operation, PermissionChoice>, String> {
+    Ok(AppSettings::load(&app).permissions)
+}
+
+/// Remember a choice for an operation, or forget it (None) so the next use prompts again.
+/// Denying and forgetting take effect directly; always allowing is confirmed in a native
+/// dialog, so webview code (including AI-driven actions) can't grant itself an operation.
+#[tauri::command]
+pub async fn set_permission(
+    app: AppHandle,
+    operation: Operation,
+    choice: Option<PermissionChoice>,
+) -> Result<(), String> {
+    if choice == Some(PermissionChoice::Allow) {
+        let description = format!("{}\n\nAlways allow this without asking?", operation.prompt());
+        let answer = run_dialog(move || {
+            MessageDialog::new()
+                .set_title("Permission Required")
+                .set_description(description)
+                .set_level(MessageLevel::Warning)
+                .set_buttons(MessageButtons::OkCancelCustom(ALWAYS_ALLOW.to_string(), CANCEL.to_string()))
+                .show()
+        })?;
+        let confirmed = matches!(&answer, MessageDialogResult::Custom(label) if label == ALWAYS_ALLOW)
+            || matches!(answer, MessageDialogResult::Ok);
+        if !confirmed {
+            return Err(format!("Permission denied: always allow {:?}", operation));
+        }
+    }
+
+    let mut settings = AppSettings::load(&app);
+    match choice {
+        Some(choice) => settings.permissions.insert(operation, choice),
+        None => settings.permissions.remove(&operation),
+    };
+    settings.save(&app)
+}
+
+/// Open a URL or file with the system handler, after the user agrees
+#[tauri::command]
+pub async fn open_external(app: AppHandle, target: String) -> Result<(), String> {
+    require(&app, Operation::OpenExternal, &target)?;
+    // The shell plugin's open is deprecated in favour of the opener plugin, which isn't a dependency yet
+    #[allow(deprecated)]
+    app.shell()
+        .open(target.clone(), None)
+        .map_err(|e| format!("Failed to open {}: {}", target, e))
+}
@@ -0,0 +1,201 @@
+use crate::spec;
+use crate::validation::Diagnostic;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// Matter.js body defaults, used for colliders without a material
+pub const DEFAULT_RESTITUTION: f64 = 0.0;
+pub const DEFAULT_FRICTION: f64 = 0.1;
+
+/// Surface response of a collider (`config.physicsMaterials.<name>` or a bundled material)
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PhysicsMaterial {
+    /// Bounciness, 0 keeps nothing of the impact speed and 1 keeps all of it
+    #[serde(default)]
+    pub restitution: f64,
+    /// Surface friction, 0 is frictionless
+    #[serde(default = "default_friction")]
+    pub friction: f64,
+}
+
+fn default_friction() -> f64 {
+    DEFAULT_FRICTION
+}
+
+/// Resolved physics properties of one collider
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SurfaceProperties {
+    pub restitution: f64,
+    pub friction: f64,
+    /// Only blocks bodies landing on it from above
+    pub one_way: bool,
+}
+
+/// Materials every project can use without declaring them
+pub fn bundled_materials() -> BTreeMap<String, PhysicsMaterial> {
+    [
+        ("default", DEFAULT_RESTITUTION, DEFAULT_FRICTION),
+        ("ice", 0.05, 0.02),
+        ("bouncy", 0.9, 0.1),
+        ("sticky", 0.0, 1.0),
+    ]
+    .into_iter()
+    .map(|(name, restitution, friction)| (name.to_string(), PhysicsMaterial { restitution, friction }))
+    .collect()
+}
+
+/// Bundled materials plus the spec's `config.physicsMaterials`, which may override them
+pub fn materials(game_spec: &Value) -> BTreeMap<String, PhysicsMaterial> {
+    let mut materials = bundled_materials();
+    if let Some(custom) = game_spec.pointer("/config/physicsMaterials").and_then(Value::as_object) {
+        for (name, value) in custom {
+            if let Ok(material) = serde_json::from_value::<PhysicsMaterial>(value.clone()) {
+                materials.insert(name.clone(), material);
+            }
+        }
+    }
+    materials
+}
+
+/// Properties of a collider: explicit restitution/friction win over its material, which wins
+/// over the defaults. Out-of-range values are clamped; validation reports them.
+pub fn resolve(materials: &BTreeMap<String, PhysicsMaterial>, collider: &Value) -> SurfaceProperties {
+    let material = collider
+        .get("material")
+        .and_then(Value::as_str)
+        .and_then(|name| materials.get(name))
+        .copied()
+        .unwrap_or(PhysicsMaterial {
+            restitution: DEFAULT_RESTITUTION,
+            friction: DEFAULT_FRICTION,
+        });
+    let number = |key: &str, fallback: f64| collider.get(key).and_then(Value::as_f64).unwrap_or(fallback).clamp(0.0, 1.0);
+    SurfaceProperties {
+        restitution: number("restitution", material.restitution),
+        friction: number("friction", material.friction),
+        one_way: collider.get("oneWay").and_then(Value::as_bool).unwrap_or(false),
+    }
+}
+
+fn check_range(diagnostics: &mut Vec<Diagnostic>, path: String, value: Option<&Value>) {
+    let Some(value) = value else {
+        return;
+    };
+    match value.as_f64() {
+        Some(n) if (0.0..=1.0).contains(&n) => {}
+        Some(n) => diagnostics.push(Diagnostic::error(path, format!("Value {} must be between 0 and 1", n))),
+        None => diagnostics.push(Diagnostic::error(path, "Expected number")),
+    }
+}
+
+/// Check material declarations and collider material references, ranges and one-way shapes
+pub fn validate_materials(game_spec: &Value) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+
+    if let Some(custom) = game_spec.pointer("/config/physicsMaterials") {
+        match custom.as_object() {
+            Some(custom) => {
+                for (name, material) in custom {
+                    let path = format!("/config/physicsMaterials/{}", name);
+                    if !material.is_object() {
+                        diagnostics.push(Diagnostic::error(path, "Physics material must be an object"));
+                        continue;
+                    }
+                    for key in ["restitution", "friction"] {
+                        check_range(&mut diagnostics, format!("{}/{}", path, key), material.get(key));
+                    }
+                }
+            }
+            None => diagnostics.push(Diagnostic::error("/config/physicsMaterials", "physicsMaterials must be an object")),
+        }
+    }
+
+    let materials = materials(game_spec);
+    let mut lists: Vec<(String, &Vec<Value>)> = Vec::new();
+    if let Some(entities) = game_spec.get("entities").and_then(Value::as_array) {
+        lists.push(("/entities".to_string(), entities));
+    }
+    for (i, scene) in game_spec.get("scenes").and_then(Value::as_array).into_iter().flatten().enumerate() {
+        if let Some(entities) = scene.get("entities").and_then(Value::as_array) {
+            lists.push((format!("/scenes/{}/entities", i), entities));
+        }
+    }
+
+    for (prefix, entities) in lists {
+        for (i, entity) in entities.iter().enumerate() {
+            let Some(collider) = entity.pointer("/components/collider") else {
+                continue;
+            };
+            let path = format!("{}/{}/components/collider", prefix, i);
+            if let Some(material) = collider.get("material") {
+                match material.as_str() {
+                    Some(name) if materials.contains_key(name) => {}
+                    Some(name) => diagnostics.push(Diagnostic::error(
+                        format!("{}/material", path),
+                        format!(
+                            "Unknown physics material '{}'; use one of {:?} or declare it in config.physicsMaterials",
+                            name,
+                            materials.keys().collect::<Vec<_>>()
+                        ),
+                    )),
+                    None => diagnostics.push(Diagnostic::error(format!("{}/material", path), "Expected string")),
+                }
+            }
+            for key in ["restitution", "friction"] {
+                check_range(&mut diagnostics, format!("{}/{}", path, key), collider.get(key));
+            }
+            if collider.get("oneWay").and_then(Value::as_bool) == Some(true) {
+                if collider.get("type").and_then(Value::as_str) != Some("box") {
+                    diagnostics.push(Diagnostic::warning(
+                        format!("{}/oneWay", path),
+                        "One-way platforms need a box collider; the flag is ignored",
+                    ));
+                } else if entity.pointer("/components/velocity").is_some() || entity.pointer("/components/input").is_some() {
+                    diagnostics.push(Diagnostic::warning(
+                        format!("{}/oneWay", path),
+                        "One-way colliders only apply to static platforms",
+                    ));
+                }
+            }
+        }
+    }
+
+    diagnostics
+}
+
+/// Write each collider's resolved restitution and friction into it, so runtimes without the
+/// material table (the HTML export) see the same surfaces
+pub fn bake(game_spec: &mut Value) {
+    let materials = materials(game_spec);
+    let mut lists: Vec<&mut Value> = Vec::new();
+    let root = match game_spec.as_object_mut() {
+        Some(root) => root,
+        None => return,
+    };
+    for (key, value) in root.iter_mut() {
+        match key.as_str() {
+            "entities" => lists.push(value),
+            "scenes" => lists.extend(value.as_array_mut().into_iter().flatten().filter_map(|scene| scene.get_mut("entities"))),
+            _ => {}
+        }
+    }
+    for entity in lists.into_iter().filter_map(Value::as_array_mut).flatten() {
+        let Some(collider) = entity.pointer_mut("/components/collider").and_then(Value::as_object_mut) else {
+            continue;
+        };
+        let surface = resolve(&materials, &Value::Object(collider.clone()));
+        collider.insert("restitution".to_string(), Value::from(surface.restitution));
+        collider.insert("friction".to_string(), Value::from(surface.friction));
+    }
+}
+
+/// Physics materials available to a project: the bundled ones plus its config.physicsMaterials
+#[tauri::command]
+pub async fn list_physics_materials(project_path: Option<String>) -> Result<BTreeMap<String, PhysicsMaterial>, String> {
+    match project_path {
+        Some(path) => Ok(materials(&spec::load_project_spec(&path)?)),
+        None => Ok(bundled_materials()),
+    }
+}
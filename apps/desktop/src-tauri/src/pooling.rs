@@ -0,0 +1,130 @@
+use crate::entity_ids;
+use crate::spec;
+use crate::validation::Diagnostic;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// Upper bound on preallocated entities per pool
+pub const MAX_POOL_SIZE: usize = 4096;
+/// Spawners at or above this many entities per second churn enough to hitch the GC
+const CHURN_RATE: f64 = 4.0;
+/// ...as do spawners that keep this many copies alive at once
+const CHURN_ALIVE: f64 = 16.0;
+/// Headroom over the steady-state count when suggesting a pool size
+const SIZE_HEADROOM: f64 = 1.25;
+
+/// One object pool (`pooling.<name>`): copies of a template entity built once and reused
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PoolConfig {
+    /// Entity id of the prototype; templates aren't placed in the world themselves
+    pub template: String,
+    /// Entities built when the game loads
+    pub size: usize,
+    /// Hard cap the pool may grow to; spawns beyond it are dropped. Defaults to `size`
+    #[serde(default)]
+    pub max_size: Option<usize>,
+}
+
+/// Spawns per second and steady-state live count of a spawner component
+fn churn(spawner: &Value) -> Option<(f64, f64)> {
+    let interval = spawner.get("interval").and_then(Value::as_f64).filter(|i| *i > 0.0)?;
+    let rate = 1.0 / interval;
+    let lifetime_alive = spawner.get("lifetime").and_then(Value::as_f64).map(|lifetime| lifetime * rate);
+    let max_alive = spawner.get("maxAlive").and_then(Value::as_f64);
+    let alive = match (lifetime_alive, max_alive) {
+        (Some(a), Some(b)) => a.min(b),
+        (Some(a), None) | (None, Some(a)) => a,
+        // Without either, copies only despawn when they leave the world
+        (None, None) => f64::INFINITY,
+    };
+    Some((rate, alive))
+}
+
+/// Pool configuration errors, plus warnings for high-churn spawners whose template isn't pooled
+pub fn validate_pooling(game_spec: &Value) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    let ids: HashMap<&str, &Value> = spec::all_entities(game_spec)
+        .filter_map(|entity| Some((entity_ids::entity_id(entity)?, entity)))
+        .collect();
+
+    let mut pooled: HashMap<String, String> = HashMap::new();
+    match game_spec.get("pooling") {
+        None => {}
+        Some(Value::Object(pooling)) => {
+            for (name, value) in pooling {
+                let path = format!("/pooling/{}", name);
+                let pool: PoolConfig = match serde_json::from_value(value.clone()) {
+                    Ok(pool) => pool,
+                    Err(e) => {
+                        diagnostics.push(Diagnostic::error(path, format!("Invalid pool: {}", e)));
+                        continue;
+                    }
+                };
+                if !ids.contains_key(pool.template.as_str()) {
+                    diagnostics.push(Diagnostic::error(
+                        format!("{}/template", path),
+                        format!("Pool template '{}' is not an entity id", pool.template),
+                    ));
+                }
+                if pool.size == 0 || pool.size > MAX_POOL_SIZE {
+                    diagnostics.push(Diagnostic::error(
+                        format!("{}/size", path),
+                        format!("Pool size must be between 1 and {}", MAX_POOL_SIZE),
+                    ));
+                }
+                if let Some(max_size) = pool.max_size {
+                    if max_size < pool.size || max_size > MAX_POOL_SIZE {
+                        diagnostics.push(Diagnostic::error(
+                            format!("{}/maxSize", path),
+                            format!("maxSize must be between size ({}) and {}", pool.size, MAX_POOL_SIZE),
+                        ));
+                    }
+                }
+                if let Some(other) = pooled.insert(pool.template.clone(), name.clone()) {
+                    diagnostics.push(Diagnostic::warning(
+                        format!("{}/template", path),
+                        format!("Template '{}' is already pooled by '{}'; only one pool is used", pool.template, other),
+                    ));
+                }
+            }
+        }
+        Some(_) => diagnostics.push(Diagnostic::error("/pooling", "pooling must be an object of pools")),
+    }
+
+    for entity in spec::all_entities(game_spec) {
+        let Some(spawner) = entity.pointer("/components/spawner") else {
+            continue;
+        };
+        let name = entity.get("name").and_then(Value::as_str).unwrap_or("<unnamed>");
+        let Some(template) = spawner.get("template").and_then(Value::as_str) else {
+            continue;
+        };
+        let Some((rate, alive)) = churn(spawner) else {
+            continue;
+        };
+        if pooled.contains_key(template) || (rate < CHURN_RATE && alive < CHURN_ALIVE) {
+            continue;
+        }
+        let template_name = ids
+            .get(template)
+            .and_then(|t| t.get("name"))
+            .and_then(Value::as_str)
+            .unwrap_or(template);
+        let suggestion = if alive.is_finite() {
+            format!("a pool of about {}", ((alive * SIZE_HEADROOM).ceil() as usize).clamp(1, MAX_POOL_SIZE))
+        } else {
+            "a pool with a maxSize (or a lifetime/maxAlive on the spawner)".to_string()
+        };
+        diagnostics.push(Diagnostic::warning(
+            "/entities",
+            format!(
+                "Spawner '{}' creates {:.1} '{}' per second; add {} under `pooling` to avoid garbage-collection hitches",
+                name, rate, template_name, suggestion
+            ),
+        ));
+    }
+
+    diagnostics
+}
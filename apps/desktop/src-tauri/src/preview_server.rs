@@ -0,0 +1,457 @@
+use crate::assets::ASSETS_DIR;
+use crate::export::{self, ExportOptions};
+use crate::cache::{self, CacheCategory};
+use crate::{deep_link, encryption, paths};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{Ipv4Addr, SocketAddr, TcpListener, TcpStream};
+use std::path::{Component, Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Manager, State};
+
+/// First port tried; the next PORT_ATTEMPTS - 1 follow before letting the OS pick one
+const PREFERRED_PORT: u16 = 5180;
+const PORT_ATTEMPTS: u16 = 20;
+/// Running servers of this and earlier sessions, in the app data directory
+const RECORDS_FILE: &str = "preview-servers.json";
+/// Path a server answers shutdown requests on; the token from the records file is required
+const SHUTDOWN_PATH: &str = "/__promptplay/shutdown";
+const ACCEPT_POLL: Duration = Duration::from_millis(50);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+const RECLAIM_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// A server written to the records file, so a later session can find it if this one crashes
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct ServerRecord {
+    pid: u32,
+    port: u16,
+    token: String,
+    window: String,
+}
+
+struct PreviewServer {
+    port: u16,
+    token: String,
+    /// Export written for the preview; served first
+    root: PathBuf,
+    /// Project folder; only its assets folder is served, for assets the export references relatively
+    project: Option<PathBuf>,
+    started_at: u64,
+    requests: Arc<AtomicU64>,
+    stop: Arc<AtomicBool>,
+}
+
+/// One preview server per window, replaced when the window starts another
+#[derive(Default)]
+pub struct PreviewServerState(Mutex<HashMap<String, PreviewServer>>);
+
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct PreviewServerStatus {
+    pub running: bool,
+    pub url: Option<String>,
+    pub port: Option<u16>,
+    pub root_dir: Option<String>,
+    pub started_at: Option<u64>,
+    pub requests_served: u64,
+    /// Servers left by crashed sessions or closed windows that were shut down on the way
+    pub reclaimed: usize,
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+fn records_path(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map(|dir| dir.join(RECORDS_FILE))
+        .map_err(|e| format!("Failed to resolve app data directory: {}", e))
+}
+
+fn load_records(app: &AppHandle) -> Vec<ServerRecord> {
+    records_path(app)
+        .ok()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// Whether a process with this id is running. A reused id reads as alive, which only delays
+/// reclaiming the servers recorded under it.
+fn process_alive(pid: u32) -> bool {
+    if cfg!(windows) {
+        Command::new("tasklist")
+            .args(["/FI", &format!("PID eq {}", pid), "/NH", "/FO", "CSV"])
+            .stdin(Stdio::null())
+            .output()
+            .is_ok_and(|output| String::from_utf8_lossy(&output.stdout).contains(&format!("\"{}\"", pid)))
+    } else {
+        Command::new("kill")
+            .args(["-0", &pid.to_string()])
+            .stdin(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .is_ok_and(|status| status.success())
+    }
+}
+
+/// Replace this process's records with `servers`, keeping those of other running instances
+fn save_records(app: &AppHandle, servers: &HashMap<String, PreviewServer>) {
+    let own_pid = std::process::id();
+    let mut records: Vec<ServerRecord> = load_records(app)
+        .into_iter()
+        .filter(|record| record.pid != own_pid && process_alive(record.pid))
+        .collect();
+    records.extend(servers.iter().map(|(window, server)| ServerRecord {
+        pid: own_pid,
+        port: server.port,
+        token: server.token.clone(),
+        window: window.clone(),
+    }));
+    let Ok(path) = records_path(app) else {
+        return;
+    };
+    if records.is_empty() {
+        let _ = fs::remove_file(path);
+        return;
+    }
+    if let Some(parent) = path.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+    if let Ok(content) = serde_json::to_string_pretty(&records) {
+        let _ = fs::write(path, content);
+    }
+}
+
+/// Ask a server from another session to let go of its port
+fn request_shutdown(record: &ServerRecord) -> bool {
+    let addr = SocketAddr::from((Ipv4Addr::LOCALHOST, record.port));
+    let Ok(mut stream) = TcpStream::connect_timeout(&addr, RECLAIM_TIMEOUT) else {
+        return false;
+    };
+    let _ = stream.set_read_timeout(Some(RECLAIM_TIMEOUT));
+    let request = format!(
+        "POST {}?token={} HTTP/1.1\r\nHost: 127.0.0.1:{}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+        SHUTDOWN_PATH, record.token, record.port
+    );
+    let mut response = String::new();
+    stream.write_all(request.as_bytes()).is_ok()
+        && stream.read_to_string(&mut response).is_ok()
+        && response.starts_with("HTTP/1.1 200")
+}
+
+/// Shut down servers nobody can reach anymore: those recorded by a process that is no longer
+/// running and those of this instance's windows that have closed or reloaded. Servers of another
+/// running instance are left alone. Returns how many were stopped.
+fn reclaim_orphans(app: &AppHandle, servers: &mut HashMap<String, PreviewServer>) -> usize {
+    let own_pid = std::process::id();
+    let mut reclaimed = load_records(app)
+        .iter()
+        .filter(|record| record.pid != own_pid && !process_alive(record.pid))
+        .filter(|record| request_shutdown(record))
+        .count();
+
+    let orphaned: Vec<String> = servers
+        .iter()
+        .filter(|(window, server)| server.stop.load(Ordering::Relaxed) || app.get_webview_window(window).is_none())
+        .map(|(window, _)| window.clone())
+        .collect();
+    for window in orphaned {
+        if let Some(server) = servers.remove(&window) {
+            server.stop.store(true, Ordering::Relaxed);
+            reclaimed += 1;
+        }
+    }
+    save_records(app, servers);
+    reclaimed
+}
+
+/// Bind the first free port from PREFERRED_PORT, then any port the OS hands out
+fn bind_free_port() -> Result<TcpListener, String> {
+    (PREFERRED_PORT..PREFERRED_PORT + PORT_ATTEMPTS)
+        .chain(std::iter::once(0))
+        .find_map(|port| TcpListener::bind((Ipv4Addr::LOCALHOST, port)).ok())
+        .ok_or_else(|| "Failed to find a free port for the preview server".to_string())
+}
+
+fn content_type(path: &Path) -> &'static str {
+    match path.extension().and_then(|e| e.to_str()).map(str::to_ascii_lowercase).as_deref() {
+        Some("html") => "text/html; charset=utf-8",
+        Some("js" | "mjs") => "text/javascript; charset=utf-8",
+        Some("json") => "application/json",
+        Some("css") => "text/css; charset=utf-8",
+        Some("png") => "image/png",
+        Some("jpg" | "jpeg") => "image/jpeg",
+        Some("gif") => "image/gif",
+        Some("svg") => "image/svg+xml",
+        Some("webp") => "image/webp",
+        Some("mp3") => "audio/mpeg",
+        Some("ogg") => "audio/ogg",
+        Some("wav") => "audio/wav",
+        Some("woff2") => "font/woff2",
+        _ => "application/octet-stream",
+    }
+}
+
+/// The file a request path names under `root`, refusing anything that climbs out of it
+fn resolve(root: &Path, request_path: &str) -> Option<PathBuf> {
+    let relative = request_path.trim_start_matches('/');
+    let relative = if relative.is_empty() { "index.html" } else { relative };
+    let relative = Path::new(relative);
+    if !relative.components().all(|c| matches!(c, Component::Normal(_))) {
+        return None;
+    }
+    let path = root.join(relative);
+    path.is_file().then_some(path)
+}
+
+/// What a server hands out: the preview export, and from the project only its assets folder
+struct Site {
+    port: u16,
+    export: PathBuf,
+    project: Option<PathBuf>,
+}
+
+impl Site {
+    fn resolve(&self, request_path: &str) -> Option<PathBuf> {
+        resolve(&self.export, request_path).or_else(|| {
+            let project = self.project.as_ref()?;
+            let in_assets = request_path
+                .trim_start_matches('/')
+                .strip_prefix(ASSETS_DIR)
+                .is_some_and(|rest| rest.starts_with('/'));
+            in_assets.then(|| resolve(project, request_path)).flatten()
+        })
+    }
+
+    /// Whether a Host header names this server. Any other name is a web page reaching the port
+    /// through DNS rebinding, which must not read the project.
+    fn allows_host(&self, host: &str) -> bool {
+        let host = host.trim();
+        ["127.0.0.1", "localhost"]
+            .iter()
+            .any(|name| host.eq_ignore_ascii_case(&format!("{}:{}", name, self.port)))
+    }
+}
+
+fn respond(stream: &mut TcpStream, status: &str, content_type: &str, body: &[u8]) {
+    let head = format!(
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nCache-Control: no-store\r\nConnection: close\r\n\r\n",
+        status,
+        content_type,
+        body.len()
+    );
+    let _ = stream.write_all(head.as_bytes());
+    let _ = stream.write_all(body);
+}
+
+fn handle(mut stream: TcpStream, site: &Site, token: &str, stop: &AtomicBool) {
+    let _ = stream.set_nonblocking(false);
+    let _ = stream.set_read_timeout(Some(REQUEST_TIMEOUT));
+    let Ok(reader) = stream.try_clone() else {
+        return;
+    };
+    let mut lines = BufReader::new(reader).lines();
+    let Some(Ok(request_line)) = lines.next() else {
+        return;
+    };
+    // Drain the headers; only Host matters
+    let mut host = None;
+    for line in lines.by_ref() {
+        match line {
+            Ok(line) if !line.is_empty() => {
+                if let Some((name, value)) = line.split_once(':') {
+                    if name.trim().eq_ignore_ascii_case("host") {
+                        host = Some(value.trim().to_string());
+                    }
+                }
+            }
+            _ => break,
+        }
+    }
+    if !host.is_some_and(|host| site.allows_host(&host)) {
+        respond(&mut stream, "403 Forbidden", "text/plain", b"forbidden");
+        return;
+    }
+
+    let mut parts = request_line.split_whitespace();
+    let (method, target) = (parts.next().unwrap_or(""), parts.next().unwrap_or("/"));
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+
+    if path == SHUTDOWN_PATH {
+        if method == "POST" && query.split('&').any(|pair| pair == format!("token={}", token)) {
+            stop.store(true, Ordering::Relaxed);
+            respond(&mut stream, "200 OK", "text/plain", b"stopping");
+        } else {
+            respond(&mut stream, "403 Forbidden", "text/plain", b"forbidden");
+        }
+        return;
+    }
+    if method != "GET" && method != "HEAD" {
+        respond(&mut stream, "405 Method Not Allowed", "text/plain", b"method not allowed");
+        return;
+    }
+    let path = deep_link::percent_decode(path);
+    match site.resolve(&path) {
+        // Specs and scripts in an encrypted project are served decrypted, as the export would read them
+        Some(file) => match encryption::read(&file) {
+            Ok(body) => {
+                let body = if method == "HEAD" { Vec::new() } else { body };
+                respond(&mut stream, "200 OK", content_type(&file), &body);
+            }
+            Err(_) => respond(&mut stream, "500 Internal Server Error", "text/plain", b"unreadable file"),
+        },
+        None => respond(&mut stream, "404 Not Found", "text/plain", b"not found"),
+    }
+}
+
+fn serve(listener: TcpListener, site: Site, token: String, stop: Arc<AtomicBool>, requests: Arc<AtomicU64>) {
+    // Non-blocking accepts so the stop flag is seen without waiting for a connection
+    if listener.set_nonblocking(true).is_err() {
+        return;
+    }
+    let site = Arc::new(site);
+    while !stop.load(Ordering::Relaxed) {
+        match listener.accept() {
+            Ok((stream, _)) => {
+                requests.fetch_add(1, Ordering::Relaxed);
+                let (site, token, stop) = (site.clone(), token.clone(), stop.clone());
+                std::thread::spawn(move || handle(stream, &site, &token, &stop));
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => std::thread::sleep(ACCEPT_POLL),
+            Err(_) => std::thread::sleep(ACCEPT_POLL),
+        }
+    }
+}
+
+fn status_of(server: Option<&PreviewServer>, reclaimed: usize) -> PreviewServerStatus {
+    match server {
+        Some(server) => PreviewServerStatus {
+            running: true,
+            url: Some(format!("http://127.0.0.1:{}/", server.port)),
+            port: Some(server.port),
+            root_dir: Some(paths::to_display(&server.root)),
+            started_at: Some(server.started_at),
+            requests_served: server.requests.load(Ordering::Relaxed),
+            reclaimed,
+        },
+        None => PreviewServerStatus {
+            reclaimed,
+            ..Default::default()
+        },
+    }
+}
+
+/// Stop the window's server; called when the window closes or its page reloads
+pub fn stop_for_window(app: &AppHandle, window: &str) {
+    let state = app.state::<PreviewServerState>();
+    let Ok(mut servers) = state.0.lock() else {
+        return;
+    };
+    if let Some(server) = servers.remove(window) {
+        server.stop.store(true, Ordering::Relaxed);
+        save_records(app, &servers);
+    }
+}
+
+/// Stop every server this session started; called on app exit
+pub fn shutdown(app: &AppHandle) {
+    let state = app.state::<PreviewServerState>();
+    let Ok(mut servers) = state.0.lock() else {
+        return;
+    };
+    for (_, server) in servers.drain() {
+        server.stop.store(true, Ordering::Relaxed);
+    }
+    save_records(app, &servers);
+}
+
+/// Reclaim servers left by a crashed session; called once from setup
+pub fn reclaim_on_startup(app: &AppHandle) {
+    let state = app.state::<PreviewServerState>();
+    let Ok(mut servers) = state.0.lock() else {
+        return;
+    };
+    reclaim_orphans(app, &mut servers);
+}
+
+/// Export the spec into the app cache and serve it (and the project's assets) on a free
+/// localhost port, for playtesting in an external browser. A running server for this window
+/// keeps its port and serves the fresh export.
+#[tauri::command]
+pub async fn start_preview_server(
+    app: AppHandle,
+    window: tauri::Window,
+    state: State<'_, PreviewServerState>,
+    game_spec_json: String,
+    game_title: String,
+    options: Option<ExportOptions>,
+) -> Result<PreviewServerStatus, String> {
+    let label = window.label().to_string();
+    let root = cache::dir(&app, CacheCategory::Exports)?.join("preview").join(&label);
+    fs::create_dir_all(&root).map_err(|e| format!("Failed to create preview directory: {}", e))?;
+    let mut options = options.unwrap_or_default();
+    // Assets are served from the project folder, so the page stays small and quick to rebuild
+    options.single_file = false;
+    let project = options.project_path.as_deref().map(paths::project_root);
+    let index = root.join("index.html").to_string_lossy().to_string();
+    export::export_html(&app, &game_spec_json, index, &game_title, options).await?;
+
+    let mut servers = state.0.lock().map_err(|e| format!("Lock error: {}", e))?;
+    let reclaimed = reclaim_orphans(&app, &mut servers);
+    if servers.get(&label).is_some_and(|server| server.project == project) {
+        return Ok(status_of(servers.get(&label), reclaimed));
+    }
+    if let Some(previous) = servers.remove(&label) {
+        previous.stop.store(true, Ordering::Relaxed);
+    }
+
+    let listener = bind_free_port()?;
+    let port = listener
+        .local_addr()
+        .map_err(|e| format!("Failed to read preview server address: {}", e))?
+        .port();
+    let server = PreviewServer {
+        port,
+        token: uuid::Uuid::new_v4().to_string(),
+        root: root.clone(),
+        project: project.clone(),
+        started_at: now(),
+        requests: Arc::new(AtomicU64::new(0)),
+        stop: Arc::new(AtomicBool::new(false)),
+    };
+    let site = Site { port, export: root, project };
+    let (token, stop, requests) = (server.token.clone(), server.stop.clone(), server.requests.clone());
+    std::thread::spawn(move || serve(listener, site, token, stop, requests));
+
+    servers.insert(label.clone(), server);
+    save_records(&app, &servers);
+    Ok(status_of(servers.get(&label), reclaimed))
+}
+
+#[tauri::command]
+pub async fn stop_preview_server(app: AppHandle, window: tauri::Window) -> Result<(), String> {
+    stop_for_window(&app, window.label());
+    Ok(())
+}
+
+/// The window's preview server, after shutting down any orphaned ones
+#[tauri::command]
+pub async fn get_preview_server_status(
+    app: AppHandle,
+    window: tauri::Window,
+    state: State<'_, PreviewServerState>,
+) -> Result<PreviewServerStatus, String> {
+    let mut servers = state.0.lock().map_err(|e| format!("Lock error: {}", e))?;
+    let reclaimed = reclaim_orphans(&app, &mut servers);
+    Ok(status_of(servers.get(window.label()), reclaimed))
+}
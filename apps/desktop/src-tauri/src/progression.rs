@@ -0,0 +1,234 @@
+use crate::spec;
+use crate::validation::Diagnostic;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+
+/// How a level becomes playable
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum UnlockCondition {
+    /// Unlocked from the start
+    Start,
+    /// Unlocked once another level is completed
+    #[serde(rename_all = "camelCase")]
+    CompleteLevel { level: String },
+    /// Unlocked once every listed level is completed
+    #[serde(rename_all = "camelCase")]
+    CompleteAll { levels: Vec<String> },
+    /// Unlocked once a level is completed with at least `min_score`
+    #[serde(rename_all = "camelCase")]
+    Score { level: String, min_score: i64 },
+}
+
+impl UnlockCondition {
+    /// Levels that must be completed before this condition can be met
+    pub fn prerequisites(&self) -> Vec<&str> {
+        match self {
+            UnlockCondition::Start => Vec::new(),
+            UnlockCondition::CompleteLevel { level } | UnlockCondition::Score { level, .. } => {
+                vec![level.as_str()]
+            }
+            UnlockCondition::CompleteAll { levels } => levels.iter().map(String::as_str).collect(),
+        }
+    }
+}
+
+/// Entry of the spec's `levels` section
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LevelSpec {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub order: u32,
+    /// Scene id this level plays; defaults to the level id
+    pub scene: Option<String>,
+    pub unlock: UnlockCondition,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ProgressionNode {
+    pub id: String,
+    pub name: String,
+    pub order: u32,
+    pub scene: Option<String>,
+    pub reachable: bool,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ProgressionEdge {
+    pub from: String,
+    pub to: String,
+    pub condition: UnlockCondition,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ProgressionGraph {
+    pub nodes: Vec<ProgressionNode>,
+    pub edges: Vec<ProgressionEdge>,
+    pub start_levels: Vec<String>,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+/// Parse the `levels` section, reporting entries that fail to deserialize
+pub fn parse_levels(spec: &Value) -> (Vec<LevelSpec>, Vec<Diagnostic>) {
+    let mut levels = Vec::new();
+    let mut diagnostics = Vec::new();
+
+    match spec.get("levels") {
+        None => {}
+        Some(Value::Array(entries)) => {
+            for (i, entry) in entries.iter().enumerate() {
+                match serde_json::from_value::<LevelSpec>(entry.clone()) {
+                    Ok(level) => levels.push(level),
+                    Err(e) => diagnostics.push(Diagnostic::error(
+                        format!("/levels/{}", i),
+                        format!("Invalid level: {}", e),
+                    )),
+                }
+            }
+        }
+        Some(_) => diagnostics.push(Diagnostic::error("/levels", "levels must be an array")),
+    }
+
+    levels.sort_by_key(|l| l.order);
+    (levels, diagnostics)
+}
+
+/// Compute which levels can eventually be unlocked, starting from `Start` levels
+fn reachable_levels(levels: &[LevelSpec]) -> HashSet<String> {
+    let mut reachable: HashSet<String> = HashSet::new();
+
+    // Iterate to a fixpoint: CompleteAll needs every prerequisite reachable first
+    loop {
+        let before = reachable.len();
+        for level in levels {
+            if reachable.contains(&level.id) {
+                continue;
+            }
+            let unlocked = match &level.unlock {
+                UnlockCondition::Start => true,
+                other => other.prerequisites().iter().all(|p| reachable.contains(*p)),
+            };
+            if unlocked {
+                reachable.insert(level.id.clone());
+            }
+        }
+        if reachable.len() == before {
+            return reachable;
+        }
+    }
+}
+
+/// Build the unlock graph and its diagnostics from a spec
+pub fn build_graph(spec: &Value) -> ProgressionGraph {
+    let (levels, mut diagnostics) = parse_levels(spec);
+
+    let scene_ids: HashSet<&str> = spec
+        .get("scenes")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|s| s.get("id").and_then(Value::as_str))
+        .collect();
+
+    let mut index: HashMap<&str, usize> = HashMap::new();
+    for (i, level) in levels.iter().enumerate() {
+        if index.insert(level.id.as_str(), i).is_some() {
+            diagnostics.push(Diagnostic::error(
+                "/levels",
+                format!("Duplicate level id '{}'", level.id),
+            ));
+        }
+    }
+
+    let mut edges = Vec::new();
+    for level in &levels {
+        for prerequisite in level.unlock.prerequisites() {
+            if !index.contains_key(prerequisite) {
+                diagnostics.push(Diagnostic::error(
+                    "/levels",
+                    format!(
+                        "Level '{}' unlocks after unknown level '{}'",
+                        level.id, prerequisite
+                    ),
+                ));
+                continue;
+            }
+            if prerequisite == level.id {
+                diagnostics.push(Diagnostic::error(
+                    "/levels",
+                    format!("Level '{}' depends on itself", level.id),
+                ));
+            }
+            edges.push(ProgressionEdge {
+                from: prerequisite.to_string(),
+                to: level.id.clone(),
+                condition: level.unlock.clone(),
+            });
+        }
+
+        if let Some(scene) = &level.scene {
+            if !scene_ids.is_empty() && !scene_ids.contains(scene.as_str()) {
+                diagnostics.push(Diagnostic::error(
+                    "/levels",
+                    format!("Level '{}' references unknown scene '{}'", level.id, scene),
+                ));
+            }
+        }
+    }
+
+    let start_levels: Vec<String> = levels
+        .iter()
+        .filter(|l| l.unlock == UnlockCondition::Start)
+        .map(|l| l.id.clone())
+        .collect();
+
+    if !levels.is_empty() && start_levels.is_empty() {
+        diagnostics.push(Diagnostic::error(
+            "/levels",
+            "No level is unlocked at the start of the game",
+        ));
+    }
+
+    let reachable = reachable_levels(&levels);
+    for level in &levels {
+        if !reachable.contains(&level.id) {
+            diagnostics.push(Diagnostic::error(
+                "/levels",
+                format!("Level '{}' can never be unlocked", level.id),
+            ));
+        }
+    }
+
+    let nodes = levels
+        .iter()
+        .map(|l| ProgressionNode {
+            id: l.id.clone(),
+            name: l.name.clone(),
+            order: l.order,
+            scene: l.scene.clone(),
+            reachable: reachable.contains(&l.id),
+        })
+        .collect();
+
+    ProgressionGraph {
+        nodes,
+        edges,
+        start_levels,
+        diagnostics,
+    }
+}
+
+/// Validate the optional `levels` section of a spec
+pub fn validate_levels(spec: &Value) -> Vec<Diagnostic> {
+    build_graph(spec).diagnostics
+}
+
+/// Get the level unlock graph for a project
+#[tauri::command]
+pub async fn get_progression_graph(project_path: String) -> Result<ProgressionGraph, String> {
+    let spec = spec::load_project_spec(&project_path)?;
+    Ok(build_graph(&spec))
+}
@@ -0,0 +1,277 @@
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use crate::workspace::{self, WorkspaceManager};
+use tauri::{AppHandle, State};
+
+const LOCK_FILE: &str = ".promptplay/lock";
+const HEARTBEAT_SECONDS: u64 = 10;
+/// A lock whose heartbeat is older than this belongs to an instance that crashed or hung
+const STALE_SECONDS: u64 = 30;
+/// Tries at creating the lock when other instances keep racing for it
+const TAKEOVER_ATTEMPTS: usize = 3;
+
+/// Contents of `.promptplay/lock`
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LockInfo {
+    pub pid: u32,
+    /// Random per-process id, so a reused PID is not mistaken for this instance
+    pub instance: String,
+    pub acquired_at: u64,
+    pub heartbeat: u64,
+}
+
+impl LockInfo {
+    pub fn is_stale(&self) -> bool {
+        now().saturating_sub(self.heartbeat) > STALE_SECONDS
+    }
+}
+
+struct HeldLock {
+    project: PathBuf,
+    info: LockInfo,
+    stop: Arc<AtomicBool>,
+}
+
+/// The lock this instance holds, if any
+#[derive(Default)]
+pub struct ProjectLockState(Mutex<Option<HeldLock>>);
+
+#[derive(Debug, Serialize)]
+pub struct OpenProjectResult {
+    /// None when the project is locked by another instance and `force` was not set
+    pub game_spec_json: Option<String>,
+    pub conflict: Option<LockInfo>,
+}
+
+fn instance_id() -> &'static str {
+    static INSTANCE: OnceLock<String> = OnceLock::new();
+    INSTANCE.get_or_init(|| uuid::Uuid::new_v4().to_string())
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+fn lock_path(project: &Path) -> PathBuf {
+    project.join(LOCK_FILE)
+}
+
+/// Each instance refreshes its own heartbeat file; the lock file itself is written once by
+/// `create_new` and never rewritten, so a heartbeat can't clobber a lock another instance took over
+fn heartbeat_path(project: &Path, instance: &str) -> PathBuf {
+    project.join(format!("{}-heartbeat-{}", LOCK_FILE, instance))
+}
+
+pub fn read_lock(project: &Path) -> Option<LockInfo> {
+    let content = fs::read_to_string(lock_path(project)).ok()?;
+    let mut info: LockInfo = serde_json::from_str(&content).ok()?;
+    if let Some(heartbeat) = fs::read_to_string(heartbeat_path(project, &info.instance))
+        .ok()
+        .and_then(|h| h.trim().parse::<u64>().ok())
+    {
+        info.heartbeat = info.heartbeat.max(heartbeat);
+    }
+    Some(info)
+}
+
+/// Create the lock file; false when one already exists
+fn create_lock(project: &Path, info: &LockInfo) -> Result<bool, String> {
+    let path = lock_path(project);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create .promptplay directory: {}", e))?;
+    }
+    let content = serde_json::to_string(info).map_err(|e| format!("Failed to serialize lock: {}", e))?;
+    let mut file = match OpenOptions::new().write(true).create_new(true).open(&path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == ErrorKind::AlreadyExists => return Ok(false),
+        Err(e) => return Err(format!("Failed to write project lock: {}", e)),
+    };
+    if let Err(e) = file.write_all(content.as_bytes()) {
+        drop(file);
+        let _ = fs::remove_file(&path);
+        return Err(format!("Failed to write project lock: {}", e));
+    }
+    Ok(true)
+}
+
+/// Seconds since the lock file was last modified
+fn lock_age(project: &Path) -> Option<u64> {
+    let modified = fs::metadata(lock_path(project)).ok()?.modified().ok()?;
+    Some(SystemTime::now().duration_since(modified).ok()?.as_secs())
+}
+
+fn write_heartbeat(project: &Path, instance: &str) -> Result<(), String> {
+    fs::write(heartbeat_path(project, instance), now().to_string())
+        .map_err(|e| format!("Failed to write project lock heartbeat: {}", e))
+}
+
+/// Remove the lock file if it still belongs to `owner`. The file is first renamed aside, which only
+/// one instance can do, and put back if it turns out to be a newer lock than the one inspected.
+fn remove_lock_of(project: &Path, owner: &LockInfo) -> bool {
+    let path = lock_path(project);
+    let aside = project.join(format!("{}-removing-{}", LOCK_FILE, uuid::Uuid::new_v4()));
+    if fs::rename(&path, &aside).is_err() {
+        return false;
+    }
+    let moved = fs::read_to_string(&aside)
+        .ok()
+        .and_then(|content| serde_json::from_str::<LockInfo>(&content).ok());
+    let ours = moved
+        .as_ref()
+        .is_some_and(|m| m.instance == owner.instance && m.acquired_at == owner.acquired_at);
+    if !ours {
+        if let Some(moved) = moved {
+            let _ = create_lock(project, &moved);
+        }
+        let _ = fs::remove_file(&aside);
+        return false;
+    }
+    let _ = fs::remove_file(&aside);
+    let _ = fs::remove_file(heartbeat_path(project, &owner.instance));
+    true
+}
+
+/// Refresh the heartbeat until stopped; if another instance takes the lock over, tell the frontend
+fn spawn_heartbeat(app: AppHandle, project: PathBuf, info: LockInfo, stop: Arc<AtomicBool>) {
+    std::thread::spawn(move || loop {
+        std::thread::sleep(Duration::from_secs(HEARTBEAT_SECONDS));
+        if stop.load(Ordering::Relaxed) {
+            break;
+        }
+        match read_lock(&project) {
+            Some(current) if current.instance != info.instance => {
+                workspace::emit_to_project(&app, &project, "project-lock-lost", current);
+                break;
+            }
+            _ => {
+                let _ = write_heartbeat(&project, &info.instance);
+            }
+        }
+    });
+}
+
+impl ProjectLockState {
+    /// Take the project's lock, or report the live lock of another instance unless `force` is set.
+    /// Stale and forced locks are taken over only after removing exactly the lock that was inspected,
+    /// so two instances can't both win.
+    pub fn acquire(&self, app: &AppHandle, project: &Path, force: bool) -> Result<Option<LockInfo>, String> {
+        self.release()?;
+
+        let timestamp = now();
+        let info = LockInfo {
+            pid: std::process::id(),
+            instance: instance_id().to_string(),
+            acquired_at: timestamp,
+            heartbeat: timestamp,
+        };
+        let mut acquired = false;
+        for _ in 0..TAKEOVER_ATTEMPTS {
+            if create_lock(project, &info)? {
+                acquired = true;
+                break;
+            }
+            let Some(existing) = read_lock(project) else {
+                // Being written by another instance, or left unreadable long ago
+                if force || lock_age(project).is_some_and(|age| age > STALE_SECONDS) {
+                    let _ = fs::remove_file(lock_path(project));
+                } else {
+                    std::thread::sleep(Duration::from_millis(100));
+                }
+                continue;
+            };
+            if existing.instance != instance_id() && !existing.is_stale() && !force {
+                return Ok(Some(existing));
+            }
+            remove_lock_of(project, &existing);
+        }
+        if !acquired {
+            return match read_lock(project) {
+                Some(existing) => Ok(Some(existing)),
+                None => Err("Failed to take the project lock; another instance is opening it".to_string()),
+            };
+        }
+        write_heartbeat(project, &info.instance)?;
+
+        let stop = Arc::new(AtomicBool::new(false));
+        spawn_heartbeat(app.clone(), project.to_path_buf(), info.clone(), stop.clone());
+        *self.0.lock().map_err(|e| format!("Lock error: {}", e))? = Some(HeldLock {
+            project: project.to_path_buf(),
+            info,
+            stop,
+        });
+        Ok(None)
+    }
+
+    /// Stop the heartbeat and remove the lock file if it is still ours
+    pub fn release(&self) -> Result<(), String> {
+        let Some(held) = self.0.lock().map_err(|e| format!("Lock error: {}", e))?.take() else {
+            return Ok(());
+        };
+        held.stop.store(true, Ordering::Relaxed);
+        if !remove_lock_of(&held.project, &held.info) {
+            let _ = fs::remove_file(heartbeat_path(&held.project, &held.info.instance));
+        }
+        Ok(())
+    }
+}
+
+/// Load a project into the calling window and take its session lock; a project open in another
+/// instance is reported as a conflict so the user can choose to open it anyway (`force`)
+#[tauri::command]
+pub async fn open_project(
+    app: AppHandle,
+    window: tauri::Window,
+    project_path: String,
+    force: Option<bool>,
+    workspace: State<'_, WorkspaceManager>,
+) -> Result<OpenProjectResult, String> {
+    // Load first so a folder without a valid game.json is never locked
+    let game_spec_json = crate::commands::load_game_spec(project_path.clone()).await?;
+
+    let project = crate::paths::project_root(&project_path);
+    if workspace.owners(&project).iter().any(|label| label != window.label()) {
+        return Err("This project is already open in another window of this instance".to_string());
+    }
+    let services = workspace.services(window.label())?;
+    match services.lock.acquire(&app, &project, force.unwrap_or(false))? {
+        Some(conflict) => Ok(OpenProjectResult {
+            game_spec_json: None,
+            conflict: Some(conflict),
+        }),
+        None => {
+            workspace.bind(window.label(), Some(&project_path))?;
+            let _ = crate::projects::add_recent(&app, &project_path);
+            services.health.start(&app, &project_path)?;
+            services.backups.start(&app, &project_path)?;
+            // A bad inbox setting shouldn't keep the project from opening; report it instead
+            if let Err(e) = services.inbox.start(&app, &project_path) {
+                let report = crate::inbox::InboxReport {
+                    failed: vec![format!("Inbox watcher not started: {}", e)],
+                    ..Default::default()
+                };
+                workspace::emit_to_project(&app, &project, "inbox-imported", report);
+            }
+            Ok(OpenProjectResult {
+                game_spec_json: Some(game_spec_json),
+                conflict: None,
+            })
+        }
+    }
+}
+
+/// Release the session lock of the calling window's project and stop its background checks,
+/// inbox watcher, backup schedule and file watcher
+#[tauri::command]
+pub async fn close_project(window: tauri::Window, workspace: State<'_, WorkspaceManager>) -> Result<(), String> {
+    workspace.services(window.label())?.stop()?;
+    workspace.bind(window.label(), None)
+}
@@ -0,0 +1,42 @@
+use crate::encryption;
+use crate::inbox::InboxSettings;
+use crate::runtime::RuntimePin;
+use crate::services::ServiceSettings;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// Project file for settings that travel with the project (unlike the app-wide settings.json)
+pub const PROJECT_SETTINGS_FILE: &str = "project.json";
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ProjectSettings {
+    /// Game runtime bundled into exports
+    pub runtime: RuntimePin,
+    /// Folder watched for art tool exports to file into assets/
+    pub inbox: Option<InboxSettings>,
+    /// Art palette as "#rrggbb" colors, seeded from imported art
+    pub palette: Vec<String>,
+    /// Score backend exported games submit to
+    pub services: Option<ServiceSettings>,
+}
+
+impl ProjectSettings {
+    /// The project's project.json, or the defaults when it doesn't exist
+    pub fn load(project_path: &str) -> Result<Self, String> {
+        let path = PathBuf::from(project_path).join(PROJECT_SETTINGS_FILE);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = encryption::read_to_string(&path)?;
+        serde_json::from_str(&content)
+            .map_err(|e| format!("Failed to parse {}: {}", PROJECT_SETTINGS_FILE, e))
+    }
+
+    pub fn save(&self, project_path: &str) -> Result<(), String> {
+        let path = PathBuf::from(project_path).join(PROJECT_SETTINGS_FILE);
+        let content = serde_json::to_string_pretty(self)
+            .map_err(|e| format!("Failed to serialize {}: {}", PROJECT_SETTINGS_FILE, e))?;
+        encryption::write(&path, content.as_bytes())
+    }
+}
@@ -0,0 +1,254 @@
+use crate::settings::AppSettings;
+use crate::{entity_ids, paths, spec, tray};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Manager};
+
+/// Recent projects list in the app data directory
+const RECENT_FILE: &str = "recent-projects.json";
+/// Recent projects kept, newest first
+const MAX_RECENT: usize = 8;
+/// Folders every new project starts with
+const PROJECT_DIRS: &[&str] = &["assets", "scripts"];
+
+/// Built-in starters for new projects
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum ProjectTemplate {
+    /// Player with jump on a ground platform, under gravity
+    #[default]
+    Platformer,
+    /// Player moving in four directions in a walled room, no gravity
+    TopDown,
+    /// No entities
+    Empty,
+}
+
+/// A project folder and what its game.json says about it
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectInfo {
+    pub path: String,
+    /// metadata.title, or the folder name
+    pub name: String,
+    /// Unix milliseconds; None for projects never opened
+    pub last_opened: Option<u64>,
+    /// App version that created the project (metadata.engineVersion)
+    pub engine_version: Option<String>,
+    /// Spec format version (version in game.json)
+    pub spec_version: Option<String>,
+    /// Entities across the top-level list and every scene
+    pub entity_count: usize,
+    /// False when the folder or its game.json is gone
+    pub exists: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct RecentProject {
+    path: String,
+    last_opened: u64,
+}
+
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+fn folder_name(project_path: &str) -> String {
+    Path::new(project_path)
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| project_path.to_string())
+}
+
+fn box_entity(name: &str, x: f64, y: f64, width: f64, height: f64, tint: u32, tags: &[&str]) -> Value {
+    json!({
+        "name": name,
+        "components": {
+            "transform": { "x": x, "y": y, "rotation": 0, "scaleX": 1, "scaleY": 1 },
+            "sprite": { "texture": "default", "width": width, "height": height, "tint": tint },
+            "collider": { "type": "box", "width": width, "height": height },
+        },
+        "tags": tags,
+    })
+}
+
+fn player(x: f64, y: f64, input: Value) -> Value {
+    let mut player = box_entity("player", x, y, 32.0, 32.0, 0x4488ff, &["player"]);
+    player["components"]["velocity"] = json!({ "vx": 0, "vy": 0 });
+    player["components"]["input"] = input;
+    player
+}
+
+/// game.json for a new project from a built-in template
+fn template_spec(template: ProjectTemplate, name: &str) -> Value {
+    let (genre, gravity, entities) = match template {
+        ProjectTemplate::Platformer => (
+            "platformer",
+            json!({ "x": 0, "y": 1 }),
+            vec![
+                player(400.0, 300.0, json!({ "moveSpeed": 150, "jumpForce": -300 })),
+                box_entity("ground", 400.0, 580.0, 800.0, 40.0, 0x664422, &["ground", "platform"]),
+            ],
+        ),
+        ProjectTemplate::TopDown => (
+            "adventure",
+            json!({ "x": 0, "y": 0 }),
+            vec![
+                player(400.0, 300.0, json!({ "moveSpeed": 150 })),
+                box_entity("wall_top", 400.0, 10.0, 800.0, 20.0, 0x555555, &["wall"]),
+                box_entity("wall_bottom", 400.0, 590.0, 800.0, 20.0, 0x555555, &["wall"]),
+                box_entity("wall_left", 10.0, 300.0, 20.0, 600.0, 0x555555, &["wall"]),
+                box_entity("wall_right", 790.0, 300.0, 20.0, 600.0, 0x555555, &["wall"]),
+            ],
+        ),
+        ProjectTemplate::Empty => ("platformer", json!({ "x": 0, "y": 1 }), Vec::new()),
+    };
+    json!({
+        "version": "1.0.0",
+        "metadata": {
+            "title": name,
+            "genre": genre,
+            "description": "A new game created with PromptPlay",
+            "engineVersion": env!("CARGO_PKG_VERSION"),
+        },
+        "config": {
+            "gravity": gravity,
+            "worldBounds": { "width": 800, "height": 600 },
+        },
+        "entities": entities,
+        "systems": ["input", "physics", "collision", "render"],
+    })
+}
+
+/// Read what a project's game.json says about it; a missing or unreadable spec gives an entry
+/// with only the folder name
+fn project_info(project_path: &str, last_opened: Option<u64>) -> ProjectInfo {
+    let game_spec = spec::load_project_spec(project_path).ok();
+    let metadata = |field: &str| {
+        game_spec
+            .as_ref()
+            .and_then(|s| s.get("metadata")?.get(field)?.as_str())
+            .map(str::to_string)
+    };
+    ProjectInfo {
+        path: project_path.to_string(),
+        name: metadata("title").unwrap_or_else(|| folder_name(project_path)),
+        last_opened,
+        engine_version: metadata("engineVersion"),
+        spec_version: game_spec
+            .as_ref()
+            .and_then(|s| s.get("version")?.as_str())
+            .map(str::to_string),
+        entity_count: game_spec.as_ref().map_or(0, |s| spec::all_entities(s).count()),
+        exists: game_spec.is_some(),
+    }
+}
+
+fn recent_path(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map(|dir| dir.join(RECENT_FILE))
+        .map_err(|e| format!("Failed to resolve app data directory: {}", e))
+}
+
+/// The recent list, newest first. Before the list had its own file it lived in the app
+/// settings without open times; that list is picked up until the first project is opened.
+fn load_recent(app: &AppHandle) -> Vec<RecentProject> {
+    let saved = recent_path(app)
+        .ok()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str(&content).ok());
+    saved.unwrap_or_else(|| {
+        AppSettings::load(app)
+            .recent_projects
+            .into_iter()
+            .map(|path| RecentProject { path, last_opened: 0 })
+            .collect()
+    })
+}
+
+fn save_recent(app: &AppHandle, recent: &[RecentProject]) -> Result<(), String> {
+    let path = recent_path(app)?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("Failed to create app data directory: {}", e))?;
+    }
+    let content =
+        serde_json::to_string_pretty(recent).map_err(|e| format!("Failed to serialize recent projects: {}", e))?;
+    fs::write(&path, content).map_err(|e| format!("Failed to write {}: {}", RECENT_FILE, e))
+}
+
+/// Recent project folders, newest first, for the tray menu
+pub fn recent_paths(app: &AppHandle) -> Vec<String> {
+    load_recent(app).into_iter().map(|entry| entry.path).collect()
+}
+
+/// Put a project at the top of the recent list, stamped with the current time
+pub fn add_recent(app: &AppHandle, project_path: &str) -> Result<(), String> {
+    let display = paths::to_display(&paths::project_root(project_path));
+    let mut recent = load_recent(app);
+    recent.retain(|entry| entry.path != display);
+    recent.insert(
+        0,
+        RecentProject {
+            path: display,
+            last_opened: now_millis(),
+        },
+    );
+    recent.truncate(MAX_RECENT);
+    save_recent(app, &recent)?;
+    tray::refresh(app);
+    Ok(())
+}
+
+/// Create a project folder at `path` from a built-in template (platformer when not given), with
+/// game.json, assets/ and scripts/, and add it to the recent list. `name` becomes the game's
+/// title and defaults to the folder name. An existing project at `path` is never overwritten.
+#[tauri::command]
+pub async fn create_project(
+    app: AppHandle,
+    path: String,
+    template: Option<ProjectTemplate>,
+    name: Option<String>,
+) -> Result<ProjectInfo, String> {
+    let root = paths::for_io(Path::new(&path));
+    if root.join("game.json").exists() {
+        return Err(format!("{} already contains a project", path));
+    }
+    if root.is_dir() && fs::read_dir(&root).is_ok_and(|mut entries| entries.next().is_some()) {
+        return Err(format!("{} is not empty; choose an empty or new folder", path));
+    }
+    for dir in PROJECT_DIRS {
+        fs::create_dir_all(root.join(dir)).map_err(|e| format!("Failed to create {} in {}: {}", dir, path, e))?;
+    }
+
+    let name = name.filter(|n| !n.trim().is_empty()).unwrap_or_else(|| folder_name(&path));
+    let mut game_spec = template_spec(template.unwrap_or_default(), name.trim());
+    entity_ids::migrate(&mut game_spec);
+    spec::save_project_spec(&path, &game_spec)?;
+
+    add_recent(&app, &path)?;
+    Ok(project_info(&path, Some(now_millis())))
+}
+
+/// Recently opened projects, newest first, with what each game.json says about it
+#[tauri::command]
+pub async fn list_recent_projects(app: AppHandle) -> Result<Vec<ProjectInfo>, String> {
+    Ok(load_recent(&app)
+        .into_iter()
+        .map(|entry| project_info(&entry.path, Some(entry.last_opened).filter(|t| *t > 0)))
+        .collect())
+}
+
+/// Record a project as just opened and return the updated recent list
+#[tauri::command]
+pub async fn add_recent_project(app: AppHandle, project_path: String) -> Result<Vec<ProjectInfo>, String> {
+    add_recent(&app, &project_path)?;
+    list_recent_projects(app).await
+}
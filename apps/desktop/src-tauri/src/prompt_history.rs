@@ -0,0 +1,177 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use tauri::{AppHandle, Manager};
+
+/// App-wide prompt store in the platform data directory
+const HISTORY_FILE: &str = "prompt_history.json";
+/// Oldest non-favorite prompts are dropped beyond this
+const MAX_ENTRIES: usize = 2000;
+const DEFAULT_LIMIT: usize = 50;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum PromptOutcome {
+    /// Sent, but the user hasn't applied or discarded the result yet
+    Pending,
+    Applied,
+    Discarded,
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptEntry {
+    pub id: String,
+    pub prompt: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project_path: Option<String>,
+    pub created_at: u64,
+    pub outcome: PromptOutcome,
+    #[serde(default)]
+    pub favorite: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+fn history_path(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map(|dir| dir.join(HISTORY_FILE))
+        .map_err(|e| format!("Failed to resolve data directory: {}", e))
+}
+
+fn now_millis() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+fn load(app: &AppHandle) -> Result<Vec<PromptEntry>, String> {
+    let path = history_path(app)?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = fs::read_to_string(&path).map_err(|e| format!("Failed to read prompt history: {}", e))?;
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse prompt history: {}", e))
+}
+
+fn save(app: &AppHandle, entries: &[PromptEntry]) -> Result<(), String> {
+    let path = history_path(app)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create data directory: {}", e))?;
+    }
+    let content = serde_json::to_string_pretty(entries).map_err(|e| format!("Failed to serialize prompt history: {}", e))?;
+    fs::write(&path, content).map_err(|e| format!("Failed to write prompt history: {}", e))
+}
+
+fn update<F: FnOnce(&mut PromptEntry)>(app: &AppHandle, id: &str, f: F) -> Result<PromptEntry, String> {
+    let mut entries = load(app)?;
+    let entry = entries
+        .iter_mut()
+        .find(|entry| entry.id == id)
+        .ok_or_else(|| format!("Prompt {} not found", id))?;
+    f(entry);
+    let entry = entry.clone();
+    save(app, &entries)?;
+    Ok(entry)
+}
+
+/// Store a sent prompt; returns its id so the outcome can be filled in later
+pub fn record(app: &AppHandle, prompt: &str, project_path: Option<&str>) -> Result<String, String> {
+    let mut entries = load(app)?;
+    let id = uuid::Uuid::new_v4().to_string();
+    entries.push(PromptEntry {
+        id: id.clone(),
+        prompt: prompt.to_string(),
+        project_path: project_path.map(str::to_string),
+        created_at: now_millis(),
+        outcome: PromptOutcome::Pending,
+        favorite: false,
+        error: None,
+    });
+    let mut excess = entries.len().saturating_sub(MAX_ENTRIES);
+    entries.retain(|entry| {
+        if excess > 0 && !entry.favorite {
+            excess -= 1;
+            return false;
+        }
+        true
+    });
+    save(app, &entries)?;
+    Ok(id)
+}
+
+/// Mark a prompt as failed with the error the AI call returned
+pub fn record_failure(app: &AppHandle, id: &str, error: &str) -> Result<(), String> {
+    update(app, id, |entry| {
+        entry.outcome = PromptOutcome::Failed;
+        entry.error = Some(error.to_string());
+    })
+    .map(|_| ())
+}
+
+/// Every prompt sent from a project, oldest first
+pub fn for_project(app: &AppHandle, project_path: &str) -> Result<Vec<PromptEntry>, String> {
+    Ok(load(app)?
+        .into_iter()
+        .filter(|entry| entry.project_path.as_deref() == Some(project_path))
+        .collect())
+}
+
+/// Prompts newest first, optionally limited to one project or to favorites
+#[tauri::command]
+pub async fn get_prompt_history(
+    app: AppHandle,
+    project_path: Option<String>,
+    favorites_only: Option<bool>,
+    limit: Option<usize>,
+) -> Result<Vec<PromptEntry>, String> {
+    let favorites_only = favorites_only.unwrap_or(false);
+    Ok(load(&app)?
+        .into_iter()
+        .rev()
+        .filter(|entry| project_path.is_none() || entry.project_path == project_path)
+        .filter(|entry| !favorites_only || entry.favorite)
+        .take(limit.unwrap_or(DEFAULT_LIMIT))
+        .collect())
+}
+
+/// Record whether the result of a prompt was applied or discarded
+#[tauri::command]
+pub async fn set_prompt_outcome(app: AppHandle, id: String, outcome: PromptOutcome) -> Result<PromptEntry, String> {
+    update(&app, &id, |entry| entry.outcome = outcome)
+}
+
+#[tauri::command]
+pub async fn favorite_prompt(app: AppHandle, id: String, favorite: Option<bool>) -> Result<PromptEntry, String> {
+    update(&app, &id, |entry| entry.favorite = favorite.unwrap_or(true))
+}
+
+/// Prompts containing every word of `query`, favorites and applied prompts first, then newest
+#[tauri::command]
+pub async fn search_prompts(
+    app: AppHandle,
+    query: String,
+    project_path: Option<String>,
+    limit: Option<usize>,
+) -> Result<Vec<PromptEntry>, String> {
+    let terms: Vec<String> = query.split_whitespace().map(str::to_lowercase).collect();
+    let mut matches: Vec<PromptEntry> = load(&app)?
+        .into_iter()
+        .filter(|entry| project_path.is_none() || entry.project_path == project_path)
+        .filter(|entry| {
+            let prompt = entry.prompt.to_lowercase();
+            terms.iter().all(|term| prompt.contains(term.as_str()))
+        })
+        .collect();
+    matches.sort_by(|a, b| {
+        b.favorite
+            .cmp(&a.favorite)
+            .then((b.outcome == PromptOutcome::Applied).cmp(&(a.outcome == PromptOutcome::Applied)))
+            .then(b.created_at.cmp(&a.created_at))
+    });
+    matches.truncate(limit.unwrap_or(DEFAULT_LIMIT));
+    Ok(matches)
+}
@@ -0,0 +1,195 @@
+use crate::error::AppError;
+use crate::paths;
+use crate::project_lock::OpenProjectResult;
+use crate::workspace::WorkspaceManager;
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use tauri::ipc::{Invoke, InvokeBody};
+use tauri::{AppHandle, Manager, State};
+
+/// How a command may touch the project of a read-only window
+struct Access {
+    name: &'static str,
+    /// Arguments holding the write target; the first one passed is used. Empty for commands
+    /// that only read the project.
+    targets: &'static [&'static str],
+    /// Boolean argument and the value at which the command writes; absent counts as false
+    when: Option<(&'static str, bool)>,
+}
+
+const fn reads(name: &'static str) -> Access {
+    Access { name, targets: &[], when: None }
+}
+
+const fn writes(name: &'static str, targets: &'static [&'static str]) -> Access {
+    Access { name, targets, when: None }
+}
+
+const PROJECT: &[&str] = &["projectPath"];
+
+/// Arguments that can name where a command writes. A command missing from `COMMAND_ACCESS` is
+/// refused in a read-only window when any of these points into the project, so a new command
+/// that writes there is blocked until it's listed here.
+const PATH_ARGS: &[&str] = &["projectPath", "path", "outputPath", "outputDir", "newPath", "dir"];
+
+/// Commands a read-only window may run against its project: ones that only read it, and ones
+/// that write only to their target arguments (exports) or only under a flag. Everything else
+/// that names a path in the project is refused.
+const COMMAND_ACCESS: &[Access] = &[
+    reads("read_file"),
+    reads("read_binary_file"),
+    reads("read_text_file"),
+    reads("list_directory"),
+    reads("path_exists"),
+    reads("get_file_info"),
+    reads("start_file_watcher"),
+    reads("load_game_spec"),
+    reads("load_typed_game_spec"),
+    reads("open_project_readonly"),
+    reads("add_recent_project"),
+    reads("validate_game_spec"),
+    reads("format_game_spec"),
+    reads("get_format_config"),
+    reads("round_game_spec"),
+    reads("get_component_registry"),
+    reads("get_asset_manifest"),
+    reads("get_image_dimensions"),
+    reads("read_asset_preview"),
+    reads("analyze_asset_usage"),
+    reads("pick_image_assets"),
+    reads("preview_entities_csv"),
+    reads("list_history"),
+    reads("list_backups"),
+    reads("list_hooks"),
+    reads("get_hook_log"),
+    reads("list_feedback"),
+    reads("list_ideas"),
+    reads("list_minimaps"),
+    reads("list_symlinks"),
+    reads("list_physics_materials"),
+    reads("list_runtime_versions"),
+    reads("check_kit_updates"),
+    reads("check_project_health"),
+    reads("get_project_health"),
+    reads("run_diagnostics"),
+    reads("run_lint_rules"),
+    reads("run_spec_tests"),
+    reads("audit_accessibility"),
+    reads("audit_frame_rate"),
+    reads("generate_heatmap"),
+    reads("get_reference_graph"),
+    reads("find_usages"),
+    reads("get_progression_graph"),
+    reads("get_scene_summary"),
+    reads("get_entities_page"),
+    reads("rebuild_search_index"),
+    reads("search_project"),
+    reads("query_entities"),
+    reads("get_seed"),
+    reads("get_world_bounds"),
+    reads("get_service_settings"),
+    reads("test_service_connection"),
+    reads("get_sync_settings"),
+    reads("sync_status"),
+    reads("get_tutorial_state"),
+    reads("get_prompt_history"),
+    reads("search_prompts"),
+    reads("ai_send_message"),
+    reads("get_project_encryption"),
+    reads("unlock_encrypted_project"),
+    reads("lock_encrypted_project"),
+    reads("reset_project_trust"),
+    reads("create_template_from_project"),
+    writes("export_game_html", &["outputPath"]),
+    writes("export_game_embed", &["outputDir"]),
+    writes("export_entities_csv", &["outputPath"]),
+    writes("export_godot_project", &["outputDir"]),
+    writes("export_repro_bundle", &["outputPath"]),
+    writes("start_continuous_export", &["outputPath"]),
+    writes("generate_spec_types", &["outputDir", "projectPath"]),
+    Access { name: "import_entities_csv", targets: PROJECT, when: Some(("dryRun", false)) },
+    Access { name: "import_godot_scene", targets: PROJECT, when: Some(("dryRun", false)) },
+    Access { name: "import_unity_scene_yaml", targets: PROJECT, when: Some(("dryRun", false)) },
+    Access { name: "benchmark_project", targets: PROJECT, when: Some(("saveBaseline", true)) },
+    Access { name: "extract_palette", targets: PROJECT, when: Some(("save", true)) },
+];
+
+/// Canonical form of a write target that may not exist yet
+fn resolve(path: &Path) -> PathBuf {
+    if path.exists() {
+        return paths::canonical(path);
+    }
+    match (path.parent(), path.file_name()) {
+        (Some(parent), Some(name)) if !parent.as_os_str().is_empty() => resolve(parent).join(name),
+        _ => paths::normalize(path),
+    }
+}
+
+/// The target of a listed command that falls inside `root`, if the call writes there
+fn blocked_target(command: &Access, args: &Value, root: &Path) -> Option<String> {
+    if let Some((flag, writes_when)) = command.when {
+        if args.get(flag).and_then(Value::as_bool).unwrap_or(false) != writes_when {
+            return None;
+        }
+    }
+    let target = command.targets.iter().find_map(|key| args.get(*key)?.as_str())?;
+    paths::relative_to(&resolve(Path::new(target)), root).map(|_| target.to_string())
+}
+
+/// The first path argument of an unlisted command that falls inside `root`
+fn unlisted_target(args: &Value, root: &Path) -> Option<String> {
+    PATH_ARGS
+        .iter()
+        .filter_map(|key| args.get(*key)?.as_str())
+        .find(|target| paths::relative_to(&resolve(Path::new(target)), root).is_some())
+        .map(str::to_string)
+}
+
+/// Wrap the invoke handler so windows showing a read-only project can't write into it.
+/// Refused calls reject with `permissionDenied` before the command runs.
+pub fn guarded<F>(handler: F) -> impl Fn(Invoke) -> bool + Send + Sync + 'static
+where
+    F: Fn(Invoke) -> bool + Send + Sync + 'static,
+{
+    move |invoke| {
+        let webview = invoke.message.webview();
+        let root = webview.state::<WorkspaceManager>().read_only_project(webview.label());
+        if let (Some(root), InvokeBody::Json(args)) = (root, invoke.message.payload()) {
+            let blocked = match COMMAND_ACCESS.iter().find(|c| c.name == invoke.message.command()) {
+                Some(command) => blocked_target(command, args, &root),
+                None => unlisted_target(args, &root),
+            };
+            if let Some(path) = blocked {
+                invoke.resolver.reject(AppError::PermissionDenied { path });
+                return true;
+            }
+        }
+        handler(invoke)
+    }
+}
+
+/// Open a project for review without the risk of changing it: the calling window can preview and
+/// export it elsewhere, but every command that would write into it is refused. No session lock
+/// is taken and the backup schedule and inbox watcher stay off.
+#[tauri::command]
+pub async fn open_project_readonly(
+    app: AppHandle,
+    window: tauri::Window,
+    project_path: String,
+    workspace: State<'_, WorkspaceManager>,
+) -> Result<OpenProjectResult, String> {
+    let game_spec_json = crate::commands::load_game_spec(project_path.clone()).await?;
+
+    let project = paths::project_root(&project_path);
+    if workspace.owners(&project).iter().any(|label| label != window.label()) {
+        return Err("This project is already open in another window of this instance".to_string());
+    }
+    let services = workspace.services(window.label())?;
+    services.stop()?;
+    workspace.bind_read_only(window.label(), &project_path)?;
+    services.health.start(&app, &project_path)?;
+    Ok(OpenProjectResult {
+        game_spec_json: Some(game_spec_json),
+        conflict: None,
+    })
+}
@@ -0,0 +1,350 @@
+use crate::{asset_usage, json_guard};
+use crate::assets::normalize_relative;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+/// Node id of the spec's own top-level fields (ui, audio, config...)
+const GAME_NODE: &str = "game";
+/// Entity and prefab fields that name a prefab
+const PREFAB_KEYS: &[&str] = &["prefab", "prefabId"];
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum NodeKind {
+    Game,
+    Scene,
+    Entity,
+    Prefab,
+    Dialogue,
+    Asset,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct GraphNode {
+    /// `scene:<id>`, `entity:<id>`, `prefab:<id>`, `dialogue:<id>`, `asset:<path>` or `game`
+    pub id: String,
+    pub kind: NodeKind,
+    pub label: String,
+    /// Scene an entity belongs to; None for the flat entity list and non-entities
+    pub scene: Option<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct GraphEdge {
+    pub from: String,
+    pub to: String,
+    /// Field holding the reference, e.g. `components.sprite.texture`; `contains` for scene membership
+    pub via: String,
+    /// JSON pointer to the referencing string in the spec (to the entity for `contains`)
+    pub pointer: String,
+}
+
+#[derive(Debug, Serialize, Default)]
+pub struct ReferenceGraph {
+    pub nodes: Vec<GraphNode>,
+    pub edges: Vec<GraphEdge>,
+    /// Asset nodes nothing points at
+    pub unused_assets: Vec<String>,
+}
+
+/// Who uses a scene, entity, prefab, dialogue or asset
+#[derive(Debug, Serialize)]
+pub struct Usages {
+    pub target: String,
+    pub references: Vec<GraphEdge>,
+    /// Entities using the target directly or through a prefab
+    pub entities: Vec<String>,
+    pub scenes: Vec<String>,
+    /// e.g. "Used by 12 entities in 3 scenes"
+    pub summary: String,
+}
+
+/// Targets a string may resolve to, keyed by the exact text found in the spec
+struct Lookup {
+    assets: HashMap<String, String>,
+    prefabs: HashMap<String, String>,
+    dialogues: HashMap<String, String>,
+}
+
+struct Builder<'a> {
+    lookup: &'a Lookup,
+    graph: ReferenceGraph,
+}
+
+impl Builder<'_> {
+    fn node(&mut self, id: String, kind: NodeKind, label: &str, scene: Option<&str>) {
+        self.graph.nodes.push(GraphNode {
+            id,
+            kind,
+            label: label.to_string(),
+            scene: scene.map(str::to_string),
+        });
+    }
+
+    fn edge(&mut self, from: &str, to: String, via: String, pointer: String) {
+        self.graph.edges.push(GraphEdge {
+            from: from.to_string(),
+            to,
+            via,
+            pointer,
+        });
+    }
+
+    /// Record an edge for every string under `value` that names a known target
+    fn scan(&mut self, from: &str, value: &Value, via: &str, key: &str, pointer: &str) {
+        match value {
+            Value::String(s) => {
+                let text = normalize_relative(s);
+                let key_lower = key.to_lowercase();
+                let target = if PREFAB_KEYS.contains(&key) {
+                    self.lookup.prefabs.get(&text).map(|id| format!("prefab:{}", id))
+                } else if key_lower.contains("dialogue") || key_lower.contains("dialog") {
+                    self.lookup.dialogues.get(&text).map(|id| format!("dialogue:{}", id))
+                } else {
+                    None
+                };
+                if let Some(to) = target.or_else(|| self.lookup.assets.get(&text).map(|p| format!("asset:{}", p))) {
+                    self.edge(from, to, via.to_string(), pointer.to_string());
+                }
+            }
+            Value::Array(items) => {
+                for (i, item) in items.iter().enumerate() {
+                    self.scan(from, item, &format!("{}[{}]", via, i), key, &format!("{}/{}", pointer, i));
+                }
+            }
+            Value::Object(map) => {
+                for (k, v) in map {
+                    let via = if via.is_empty() { k.clone() } else { format!("{}.{}", via, k) };
+                    self.scan(from, v, &via, k, &child_pointer(pointer, k));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Scan an object's fields except the listed ones (nested lists the caller walks itself)
+    fn scan_fields(&mut self, from: &str, value: &Value, skip: &[&str], pointer: &str) {
+        for (key, field) in value.as_object().into_iter().flatten() {
+            if !skip.contains(&key.as_str()) {
+                self.scan(from, field, key, key, &child_pointer(pointer, key));
+            }
+        }
+    }
+
+    fn entity(&mut self, entity: &Value, scene: Option<&str>, index: usize, pointer: &str) -> String {
+        let name = entity.get("name").and_then(Value::as_str).unwrap_or_default();
+        let key = entity
+            .get("id")
+            .and_then(Value::as_str)
+            .map(str::to_string)
+            .unwrap_or_else(|| format!("{}#{}", scene.unwrap_or(""), index));
+        let id = format!("entity:{}", key);
+        self.node(id.clone(), NodeKind::Entity, name, scene);
+        self.scan_fields(&id, entity, &["id", "name"], pointer);
+        id
+    }
+}
+
+/// `pointer` extended by an object key, escaped per RFC 6901
+fn child_pointer(pointer: &str, key: &str) -> String {
+    format!("{}/{}", pointer, key.replace('~', "~0").replace('/', "~1"))
+}
+
+fn id_or_name(value: &Value) -> Option<&str> {
+    value
+        .get("id")
+        .and_then(Value::as_str)
+        .or_else(|| value.get("name").and_then(Value::as_str))
+}
+
+/// Build the graph of scenes → entities → assets/prefabs/dialogues; `assets` are project-relative
+/// paths, matched by any of the names a spec may use for them
+pub fn build(spec: &Value, assets: &[String]) -> ReferenceGraph {
+    let list = |key: &str| spec.get(key).and_then(Value::as_array).cloned().unwrap_or_default();
+    let prefabs = list("prefabs");
+    let dialogues = list("dialogues");
+
+    let mut lookup = Lookup {
+        assets: HashMap::new(),
+        prefabs: HashMap::new(),
+        dialogues: HashMap::new(),
+    };
+    for path in assets {
+        for alias in asset_usage::aliases(path) {
+            lookup.assets.entry(alias).or_insert_with(|| path.clone());
+        }
+    }
+    for (items, map) in [(&prefabs, &mut lookup.prefabs), (&dialogues, &mut lookup.dialogues)] {
+        for item in items {
+            let Some(id) = id_or_name(item) else { continue };
+            map.insert(id.to_string(), id.to_string());
+            if let Some(name) = item.get("name").and_then(Value::as_str) {
+                map.entry(name.to_string()).or_insert_with(|| id.to_string());
+            }
+        }
+    }
+
+    let mut builder = Builder {
+        lookup: &lookup,
+        graph: ReferenceGraph::default(),
+    };
+    let title = spec
+        .get("metadata")
+        .and_then(|m| m.get("title"))
+        .and_then(Value::as_str)
+        .unwrap_or("Game");
+    builder.node(GAME_NODE.to_string(), NodeKind::Game, title, None);
+    builder.scan_fields(GAME_NODE, spec, &["entities", "scenes", "prefabs", "dialogues"], "");
+
+    for (index, entity) in spec.get("entities").and_then(Value::as_array).into_iter().flatten().enumerate() {
+        builder.entity(entity, None, index, &format!("/entities/{}", index));
+    }
+    for (scene_index, scene) in spec.get("scenes").and_then(Value::as_array).into_iter().flatten().enumerate() {
+        let scene_pointer = format!("/scenes/{}", scene_index);
+        let scene_id = scene.get("id").and_then(Value::as_str).unwrap_or_default();
+        let id = format!("scene:{}", scene_id);
+        let label = scene.get("name").and_then(Value::as_str).unwrap_or(scene_id);
+        builder.node(id.clone(), NodeKind::Scene, label, None);
+        builder.scan_fields(&id, scene, &["id", "name", "entities"], &scene_pointer);
+        for (index, entity) in scene.get("entities").and_then(Value::as_array).into_iter().flatten().enumerate() {
+            let pointer = format!("{}/entities/{}", scene_pointer, index);
+            let entity_id = builder.entity(entity, Some(scene_id), index, &pointer);
+            builder.edge(&id, entity_id, "contains".to_string(), pointer);
+        }
+    }
+    for (kind, prefix, items) in [(NodeKind::Prefab, "prefab", &prefabs), (NodeKind::Dialogue, "dialogue", &dialogues)] {
+        for (index, item) in items.iter().enumerate() {
+            let Some(key) = id_or_name(item) else { continue };
+            let id = format!("{}:{}", prefix, key);
+            builder.node(id.clone(), kind, item.get("name").and_then(Value::as_str).unwrap_or(key), None);
+            builder.scan_fields(&id, item, &["id", "name"], &format!("/{}s/{}", prefix, index));
+        }
+    }
+
+    let mut graph = builder.graph;
+    let referenced: BTreeSet<&str> = graph.edges.iter().map(|e| e.to.as_str()).collect();
+    let unused: Vec<String> = assets
+        .iter()
+        .filter(|path| !referenced.contains(format!("asset:{}", path).as_str()))
+        .cloned()
+        .collect();
+    for path in assets {
+        graph.nodes.push(GraphNode {
+            id: format!("asset:{}", path),
+            kind: NodeKind::Asset,
+            label: path.clone(),
+            scene: None,
+        });
+    }
+    graph.unused_assets = unused;
+    graph
+}
+
+/// Resolve what the user asked about: a node id, an asset path or alias, or a prefab/dialogue/scene id
+fn resolve_target<'a>(graph: &'a ReferenceGraph, target: &str) -> Option<&'a GraphNode> {
+    let normalized = normalize_relative(target);
+    graph
+        .nodes
+        .iter()
+        .find(|n| n.id == target)
+        .or_else(|| graph.nodes.iter().find(|n| n.kind == NodeKind::Asset && asset_usage::aliases(&n.label).contains(&normalized)))
+        .or_else(|| {
+            graph
+                .nodes
+                .iter()
+                .find(|n| n.kind != NodeKind::Entity && n.id.split_once(':').is_some_and(|(_, key)| key == target))
+        })
+}
+
+fn count(n: usize, one: &str, many: &str) -> String {
+    format!("{} {}", n, if n == 1 { one } else { many })
+}
+
+/// Reference edges pointing at a node, leaving out scene membership
+fn incoming<'a>(graph: &'a ReferenceGraph, id: &'a str) -> impl Iterator<Item = &'a GraphEdge> {
+    graph.edges.iter().filter(move |e| e.to == id && e.via != "contains")
+}
+
+/// Everything that points at `target`, with entities reached through prefabs counted too
+pub fn usages(graph: &ReferenceGraph, target: &str) -> Result<Usages, String> {
+    let node = resolve_target(graph, target).ok_or_else(|| format!("Nothing named '{}' in the project", target))?;
+    let nodes: BTreeMap<&str, &GraphNode> = graph.nodes.iter().map(|n| (n.id.as_str(), n)).collect();
+
+    let references: Vec<GraphEdge> = incoming(graph, &node.id).cloned().collect();
+    let mut entities = BTreeSet::new();
+    let mut scenes = BTreeSet::new();
+    let mut other = BTreeSet::new();
+    for edge in &references {
+        let Some(source) = nodes.get(edge.from.as_str()) else { continue };
+        let mut users = vec![*source];
+        if source.kind == NodeKind::Prefab {
+            users.extend(incoming(graph, &source.id).filter_map(|e| nodes.get(e.from.as_str()).copied()));
+        }
+        for user in users {
+            match user.kind {
+                NodeKind::Entity => {
+                    entities.insert(user.id.clone());
+                    scenes.extend(user.scene.clone());
+                }
+                NodeKind::Scene => {
+                    scenes.insert(user.id.trim_start_matches("scene:").to_string());
+                }
+                _ => {
+                    other.insert(user.label.clone());
+                }
+            }
+        }
+    }
+
+    let summary = if references.is_empty() {
+        "Not used anywhere".to_string()
+    } else {
+        let mut summary = format!("Used by {}", count(entities.len(), "entity", "entities"));
+        if !scenes.is_empty() {
+            summary.push_str(&format!(" in {}", count(scenes.len(), "scene", "scenes")));
+        }
+        if !other.is_empty() {
+            summary.push_str(&format!(" and {}", other.into_iter().collect::<Vec<_>>().join(", ")));
+        }
+        summary
+    };
+
+    Ok(Usages {
+        target: node.id.clone(),
+        references,
+        entities: entities
+            .iter()
+            .map(|id| nodes.get(id.as_str()).map(|n| n.label.clone()).unwrap_or_else(|| id.clone()))
+            .collect(),
+        scenes: scenes.into_iter().collect(),
+        summary,
+    })
+}
+
+fn project_graph(project_path: &str, game_spec_json: Option<String>) -> Result<ReferenceGraph, String> {
+    let spec = match game_spec_json {
+        Some(json) => json_guard::parse(json.as_bytes(), "game spec", &json_guard::SPEC_LIMITS)?,
+        None => crate::spec::load_project_spec(project_path)?,
+    };
+    Ok(build(&spec, &asset_usage::project_assets(project_path)))
+}
+
+/// Graph of scenes, entities, prefabs, dialogues and the assets they reference
+#[tauri::command]
+pub async fn get_reference_graph(
+    project_path: String,
+    game_spec_json: Option<String>,
+) -> Result<ReferenceGraph, String> {
+    project_graph(&project_path, game_spec_json)
+}
+
+/// Find usages of an asset, prefab, dialogue or scene, e.g. before deleting it
+#[tauri::command]
+pub async fn find_usages(
+    project_path: String,
+    target: String,
+    game_spec_json: Option<String>,
+) -> Result<Usages, String> {
+    usages(&project_graph(&project_path, game_spec_json)?, &target)
+}
@@ -0,0 +1,206 @@
+use crate::assets::{normalize_relative, AssetManifest, ASSETS_DIR};
+use crate::entity_ids::{self, REFERENCE_FIELDS};
+use crate::history::{self, FileMove, HistoryEntry};
+use crate::references;
+use crate::spec;
+use serde::Serialize;
+use serde_json::Value;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+/// Fields outside the entity reference components that name an entity: trigger and dialogue data
+const NAME_KEYS: &[&str] = &["entity", "entityName", "speaker", "npc"];
+
+#[derive(Debug, Serialize)]
+pub struct RenameResult {
+    pub old_name: String,
+    pub new_name: String,
+    pub references_updated: usize,
+    pub history: HistoryEntry,
+    /// Updated game.json for the editor
+    pub game_spec_json: String,
+}
+
+/// Rewrite strings that name entity `old` by field, and `entity.<old>.` bindings anywhere
+fn rewrite_entity_name(value: &mut Value, key: &str, old: &str, new: &str) -> usize {
+    match value {
+        Value::String(s) => {
+            let by_field = NAME_KEYS.contains(&key) || REFERENCE_FIELDS.iter().any(|(_, field)| *field == key);
+            if by_field && s == old {
+                *s = new.to_string();
+                return 1;
+            }
+            let binding = format!("entity.{}.", old);
+            if s.contains(&binding) {
+                *s = s.replace(&binding, &format!("entity.{}.", new));
+                return 1;
+            }
+            0
+        }
+        Value::Array(items) => items.iter_mut().map(|v| rewrite_entity_name(v, key, old, new)).sum(),
+        Value::Object(map) => map
+            .iter_mut()
+            .map(|(k, v)| {
+                // Entity names themselves are renamed by id, never by matching text
+                if k == "name" {
+                    0
+                } else {
+                    rewrite_entity_name(v, k, old, new)
+                }
+            })
+            .sum(),
+        _ => 0,
+    }
+}
+
+fn to_json(game_spec: &Value) -> Result<String, String> {
+    serde_json::to_string_pretty(game_spec).map_err(|e| format!("Failed to serialize game spec: {}", e))
+}
+
+/// Set the name of entity `id` and rewrite references to `old`; returns the references updated
+pub fn apply_entity_rename(game_spec: &mut Value, id: &str, old: &str, new: &str) -> usize {
+    if let Some(entity) = spec::find_entity_by_id_mut(game_spec, id) {
+        entity["name"] = Value::String(new.to_string());
+    }
+    rewrite_entity_name(game_spec, "", old, new)
+}
+
+/// Rename an entity by id and update every name reference to it in scenes, prefabs, triggers,
+/// dialogues and UI bindings, as one undoable change
+#[tauri::command]
+pub async fn rename_entity(project_path: String, id: String, new_name: String) -> Result<RenameResult, String> {
+    let new_name = new_name.trim().to_string();
+    if new_name.is_empty() {
+        return Err("Entity name must not be empty".to_string());
+    }
+
+    let before = spec::load_project_spec(&project_path)?;
+    let mut game_spec = before.clone();
+    let old_name = spec::all_entities(&game_spec)
+        .find(|e| entity_ids::entity_id(e) == Some(id.as_str()))
+        .and_then(|e| e.get("name").and_then(Value::as_str))
+        .map(str::to_string)
+        .ok_or_else(|| format!("No entity with id {}", id))?;
+    if old_name == new_name {
+        return Err(format!("The entity is already named '{}'", new_name));
+    }
+    if spec::find_entity(&game_spec, &new_name).is_some() {
+        return Err(format!("An entity named '{}' already exists", new_name));
+    }
+
+    let references_updated = apply_entity_rename(&mut game_spec, &id, &old_name, &new_name);
+
+    let label = format!("Rename entity '{}' to '{}'", old_name, new_name);
+    let entry = history::record(&project_path, &label, &before)?;
+    spec::save_project_spec(&project_path, &game_spec)?;
+
+    Ok(RenameResult {
+        old_name,
+        new_name,
+        references_updated,
+        history: entry,
+        game_spec_json: to_json(&game_spec)?,
+    })
+}
+
+/// Project-relative path with no `..` or absolute parts
+fn checked_relative(path: &str) -> Result<String, String> {
+    let normalized = normalize_relative(path);
+    let valid = !normalized.is_empty()
+        && Path::new(&normalized).components().all(|c| matches!(c, Component::Normal(_)));
+    if valid {
+        Ok(normalized)
+    } else {
+        Err(format!("'{}' is not a path inside the project", path))
+    }
+}
+
+/// The reference rewritten in the same style it was written: full path, path inside assets/,
+/// file name, or bare stem
+fn restyle(reference: &str, old_path: &str, new_path: &str) -> String {
+    let reference = normalize_relative(reference);
+    let inner = |p: &str| p.strip_prefix(&format!("{}/", ASSETS_DIR)).map(str::to_string);
+    let name = |p: &str| Path::new(p).file_name().map(|n| n.to_string_lossy().to_string());
+    let stem = |p: &str| Path::new(p).file_stem().map(|n| n.to_string_lossy().to_string());
+
+    if reference == old_path {
+        return new_path.to_string();
+    }
+    for form in [inner, name, stem] {
+        if form(old_path).as_deref() == Some(reference.as_str()) {
+            if let Some(new_form) = form(new_path) {
+                return new_form;
+            }
+        }
+    }
+    // A form the new path doesn't have (e.g. moved out of assets/): use the full path
+    new_path.to_string()
+}
+
+/// Move an asset and rewrite every reference to it, keeping each reference's naming style,
+/// as one undoable change (undo moves the file back too)
+#[tauri::command]
+pub async fn rename_asset(project_path: String, path: String, new_path: String) -> Result<RenameResult, String> {
+    let old_path = checked_relative(&path)?;
+    let new_path = checked_relative(&new_path)?;
+    let root = PathBuf::from(&project_path);
+    let (from, to) = (root.join(&old_path), root.join(&new_path));
+    if !from.is_file() {
+        return Err(format!("Asset not found: {}", old_path));
+    }
+    if to.exists() {
+        return Err(format!("{} already exists", new_path));
+    }
+
+    let before = spec::load_project_spec(&project_path)?;
+    let mut game_spec = before.clone();
+    let graph = references::build(&game_spec, std::slice::from_ref(&old_path));
+    let usages = references::usages(&graph, &format!("asset:{}", old_path))?;
+    let mut references_updated = 0;
+    for edge in &usages.references {
+        if let Some(slot) = game_spec.pointer_mut(&edge.pointer) {
+            if let Some(reference) = slot.as_str() {
+                *slot = Value::String(restyle(reference, &old_path, &new_path));
+                references_updated += 1;
+            }
+        }
+    }
+
+    if let Some(parent) = to.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    fs::rename(&from, &to).map_err(|e| format!("Failed to move {} to {}: {}", old_path, new_path, e))?;
+
+    let commit = || -> Result<HistoryEntry, String> {
+        let label = format!("Rename asset '{}' to '{}'", old_path, new_path);
+        let moved = vec![FileMove {
+            from: old_path.clone(),
+            to: new_path.clone(),
+        }];
+        let entry = history::record_with_moves(&project_path, &label, &before, moved)?;
+        spec::save_project_spec(&project_path, &game_spec)?;
+        let mut manifest = AssetManifest::load(&project_path)?;
+        if let Some(asset) = manifest.find_mut(&old_path) {
+            asset.path = new_path.clone();
+            manifest.save(&project_path)?;
+        }
+        Ok(entry)
+    };
+    // All or nothing: a failed save leaves the file where the spec still expects it
+    let entry = match commit() {
+        Ok(entry) => entry,
+        Err(e) => {
+            let _ = fs::rename(&to, &from);
+            let _ = spec::save_project_spec(&project_path, &before);
+            return Err(e);
+        }
+    };
+
+    Ok(RenameResult {
+        old_name: old_path,
+        new_name: new_path,
+        references_updated,
+        history: entry,
+        game_spec_json: to_json(&game_spec)?,
+    })
+}
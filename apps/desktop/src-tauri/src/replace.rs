@@ -0,0 +1,211 @@
+use crate::entity_locks;
+use crate::history::{self, HistoryEntry};
+use crate::spec;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Spec values that find-and-replace can touch
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ReplaceField {
+    EntityName,
+    TexturePath,
+    Tag,
+}
+
+const ALL_FIELDS: &[ReplaceField] = &[
+    ReplaceField::EntityName,
+    ReplaceField::TexturePath,
+    ReplaceField::Tag,
+];
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ReplaceMatch {
+    pub scene: Option<String>,
+    pub entity: String,
+    pub field: ReplaceField,
+    pub before: String,
+    pub after: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReplaceResult {
+    pub matches: Vec<ReplaceMatch>,
+    /// Matches in locked entities, left unchanged unless `include_locked` was set
+    pub locked: Vec<ReplaceMatch>,
+    pub applied: bool,
+    pub history: Option<HistoryEntry>,
+}
+
+fn replace_value(
+    value: &mut Value,
+    query: &str,
+    replacement: &str,
+    whole_value: bool,
+) -> Option<(String, String)> {
+    let current = value.as_str()?;
+    let replaced = if whole_value {
+        if current != query {
+            return None;
+        }
+        replacement.to_string()
+    } else {
+        if !current.contains(query) {
+            return None;
+        }
+        current.replace(query, replacement)
+    };
+
+    let before = current.to_string();
+    *value = Value::from(replaced.clone());
+    Some((before, replaced))
+}
+
+/// Apply a replacement to one entity in place, returning what changed
+fn replace_in_entity(
+    entity: &mut Value,
+    scene: Option<&str>,
+    query: &str,
+    replacement: &str,
+    fields: &[ReplaceField],
+    whole_value: bool,
+) -> Vec<ReplaceMatch> {
+    let mut matches = Vec::new();
+    let entity_name = entity
+        .get("name")
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .to_string();
+
+    let mut push = |field, (before, after)| {
+        matches.push(ReplaceMatch {
+            scene: scene.map(str::to_string),
+            entity: entity_name.clone(),
+            field,
+            before,
+            after,
+        })
+    };
+
+    for field in fields {
+        match field {
+            ReplaceField::EntityName => {
+                if let Some(change) = entity
+                    .get_mut("name")
+                    .and_then(|v| replace_value(v, query, replacement, whole_value))
+                {
+                    push(*field, change);
+                }
+            }
+            ReplaceField::TexturePath => {
+                for (component, key) in [("sprite", "texture"), ("animation", "spriteSheet")] {
+                    if let Some(change) = entity
+                        .pointer_mut(&format!("/components/{}/{}", component, key))
+                        .and_then(|v| replace_value(v, query, replacement, whole_value))
+                    {
+                        push(*field, change);
+                    }
+                }
+            }
+            ReplaceField::Tag => {
+                if let Some(tags) = entity.get_mut("tags").and_then(Value::as_array_mut) {
+                    for tag in tags.iter_mut() {
+                        if let Some(change) = replace_value(tag, query, replacement, whole_value) {
+                            push(*field, change);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    matches
+}
+
+/// Replace text across every scene of a spec in place. Locked entities are only changed with
+/// `include_locked`; otherwise their matches come back separately and they stay as they are.
+pub fn replace_in_spec_value(
+    game_spec: &mut Value,
+    query: &str,
+    replacement: &str,
+    fields: &[ReplaceField],
+    whole_value: bool,
+    include_locked: bool,
+) -> (Vec<ReplaceMatch>, Vec<ReplaceMatch>) {
+    let mut matches = Vec::new();
+    let mut locked = Vec::new();
+    let mut replace = |entity: &mut Value, scene: Option<&str>| {
+        if include_locked || !entity_locks::is_locked(entity) {
+            matches.extend(replace_in_entity(entity, scene, query, replacement, fields, whole_value));
+        } else {
+            locked.extend(replace_in_entity(&mut entity.clone(), scene, query, replacement, fields, whole_value));
+        }
+    };
+
+    if let Some(entities) = game_spec.get_mut("entities").and_then(Value::as_array_mut) {
+        for entity in entities {
+            replace(entity, None);
+        }
+    }
+
+    if let Some(scenes) = game_spec.get_mut("scenes").and_then(Value::as_array_mut) {
+        for scene in scenes {
+            let scene_id = scene.get("id").and_then(Value::as_str).map(str::to_string);
+            if let Some(entities) = scene.get_mut("entities").and_then(Value::as_array_mut) {
+                for entity in entities {
+                    replace(entity, scene_id.as_deref());
+                }
+            }
+        }
+    }
+
+    (matches, locked)
+}
+
+/// Find-and-replace entity names, texture paths, and tags across all scenes.
+/// With `dry_run` the matches are returned without touching game.json; locked entities are
+/// skipped unless `include_locked` is set.
+#[tauri::command]
+pub async fn replace_in_spec(
+    project_path: String,
+    query: String,
+    replacement: String,
+    scope: Option<Vec<ReplaceField>>,
+    whole_value: Option<bool>,
+    dry_run: Option<bool>,
+    include_locked: Option<bool>,
+) -> Result<ReplaceResult, String> {
+    if query.is_empty() {
+        return Err("Search text must not be empty".to_string());
+    }
+
+    let fields = scope.filter(|s| !s.is_empty()).unwrap_or_else(|| ALL_FIELDS.to_vec());
+    let mut game_spec = spec::load_project_spec(&project_path)?;
+    let (matches, locked) = replace_in_spec_value(
+        &mut game_spec,
+        &query,
+        &replacement,
+        &fields,
+        whole_value.unwrap_or(false),
+        include_locked.unwrap_or(false),
+    );
+
+    if dry_run.unwrap_or(false) || matches.is_empty() {
+        return Ok(ReplaceResult {
+            matches,
+            locked,
+            applied: false,
+            history: None,
+        });
+    }
+
+    let label = format!("Replace '{}' with '{}'", query, replacement);
+    let entry = history::save_with_history(&project_path, &label, &game_spec)?;
+
+    Ok(ReplaceResult {
+        matches,
+        locked,
+        applied: true,
+        history: Some(entry),
+    })
+}
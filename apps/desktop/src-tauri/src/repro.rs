@@ -0,0 +1,301 @@
+use crate::assets::{AssetKind, AssetManifest};
+use crate::{health, hooks, paths, spec, templates};
+use image::{ImageFormat, Rgba, RgbaImage};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{BTreeSet, HashMap};
+use std::fs::{self, File};
+use std::io::{Cursor, Write};
+use std::path::{Path, PathBuf};
+use zip::write::SimpleFileOptions;
+
+/// Placeholder size when neither the asset nor the sprite gives one
+const FALLBACK_SIZE: (u32, u32) = (32, 32);
+/// Checker squares in placeholder images, in pixels
+const CHECKER: u32 = 8;
+/// Placeholder sounds are this much silence
+const SILENCE_SAMPLE_RATE: u32 = 8000;
+const SILENCE_SECONDS: u32 = 1;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReproBundle {
+    pub output_path: String,
+    /// Entries in the archive
+    pub files: Vec<String>,
+    pub assets_replaced: usize,
+    /// Spec strings and log lines that had a local path taken out
+    pub paths_redacted: usize,
+    /// Metadata fields removed from the spec, e.g. "metadata.author"
+    pub stripped_metadata: Vec<String>,
+    /// References whose file couldn't be read; they still get a placeholder
+    pub missing_assets: Vec<String>,
+}
+
+/// Swaps the project folder and home folder in text for neutral markers
+struct Redactor {
+    /// Path prefixes and their replacement, longest first
+    prefixes: Vec<(String, &'static str)>,
+}
+
+impl Redactor {
+    fn new(project: &Path) -> Self {
+        let mut prefixes: Vec<(String, &'static str)> = [paths::to_display(project), project.to_string_lossy().to_string()]
+            .into_iter()
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .map(|root| (root, "<project>"))
+            .collect();
+        if let Some(home) = templates::home_dir() {
+            prefixes.push((home.replace('\\', "/"), "~"));
+            prefixes.push((home, "~"));
+        }
+        prefixes.retain(|(prefix, _)| !prefix.is_empty());
+        prefixes.sort_by_key(|(prefix, _)| std::cmp::Reverse(prefix.len()));
+        prefixes.dedup();
+        Self { prefixes }
+    }
+
+    /// `text` with local paths replaced, and whether anything changed
+    fn text(&self, text: &str) -> (String, bool) {
+        let mut redacted = text.to_string();
+        for (prefix, marker) in &self.prefixes {
+            redacted = redacted.replace(prefix.as_str(), marker);
+        }
+        let changed = redacted != text;
+        (redacted, changed)
+    }
+
+    /// Redact every string in `value`; returns how many changed
+    fn value(&self, value: &mut Value) -> usize {
+        match value {
+            Value::String(s) => {
+                let (redacted, changed) = self.text(s);
+                *s = redacted;
+                changed as usize
+            }
+            Value::Array(items) => items.iter_mut().map(|item| self.value(item)).sum(),
+            Value::Object(map) => map.values_mut().map(|item| self.value(item)).sum(),
+            _ => 0,
+        }
+    }
+
+    /// Redact a log, counting changed lines
+    fn log(&self, text: &str) -> (String, usize) {
+        let mut changed = 0;
+        let lines: Vec<String> = text
+            .lines()
+            .map(|line| {
+                let (line, was_changed) = self.text(line);
+                changed += was_changed as usize;
+                line
+            })
+            .collect();
+        (lines.join("\n"), changed)
+    }
+}
+
+/// Gray and magenta checkerboard, so stand-ins are obvious in the running game
+fn placeholder_png((width, height): (u32, u32)) -> Result<Vec<u8>, String> {
+    let image = RgbaImage::from_fn(width.max(1), height.max(1), |x, y| {
+        if (x / CHECKER + y / CHECKER) % 2 == 0 {
+            Rgba([128, 128, 128, 255])
+        } else {
+            Rgba([255, 0, 255, 255])
+        }
+    });
+    let mut bytes = Vec::new();
+    image
+        .write_to(&mut Cursor::new(&mut bytes), ImageFormat::Png)
+        .map_err(|e| format!("Failed to encode placeholder: {}", e))?;
+    Ok(bytes)
+}
+
+/// Mono 16-bit WAV of silence
+fn placeholder_wav() -> Vec<u8> {
+    let data_len = SILENCE_SAMPLE_RATE * SILENCE_SECONDS * 2;
+    let mut wav = Vec::with_capacity(44 + data_len as usize);
+    wav.extend_from_slice(b"RIFF");
+    wav.extend_from_slice(&(36 + data_len).to_le_bytes());
+    wav.extend_from_slice(b"WAVEfmt ");
+    wav.extend_from_slice(&16u32.to_le_bytes());
+    wav.extend_from_slice(&1u16.to_le_bytes());
+    wav.extend_from_slice(&1u16.to_le_bytes());
+    wav.extend_from_slice(&SILENCE_SAMPLE_RATE.to_le_bytes());
+    wav.extend_from_slice(&(SILENCE_SAMPLE_RATE * 2).to_le_bytes());
+    wav.extend_from_slice(&2u16.to_le_bytes());
+    wav.extend_from_slice(&16u16.to_le_bytes());
+    wav.extend_from_slice(b"data");
+    wav.extend_from_slice(&data_len.to_le_bytes());
+    wav.resize(44 + data_len as usize, 0);
+    wav
+}
+
+/// Size from the sprite's own width and height, for assets that can't be read
+fn sprite_size(entity: &Value) -> Option<(u32, u32)> {
+    let sprite = entity.pointer("/components/sprite")?;
+    let width = sprite.get("width")?.as_f64()?;
+    let height = sprite.get("height")?.as_f64()?;
+    Some((width.round().max(1.0) as u32, height.round().max(1.0) as u32))
+}
+
+/// Point every asset field at a stand-in file; returns the stand-ins as archive entries and the
+/// references that couldn't be read
+fn replace_assets(
+    project_path: &str,
+    game_spec: &mut Value,
+) -> Result<(Vec<(String, Vec<u8>)>, Vec<String>), String> {
+    let manifest = AssetManifest::load(project_path).unwrap_or_default();
+    let root = PathBuf::from(project_path);
+    // Reference -> placeholder path, so an asset used by several entities becomes one file
+    let mut placeholders: HashMap<String, String> = HashMap::new();
+    let mut files = Vec::new();
+    let mut missing = BTreeSet::new();
+    let mut failure = None;
+
+    spec::for_each_entity_mut(game_spec, |entity| {
+        let fallback = sprite_size(entity).unwrap_or(FALLBACK_SIZE);
+        for (component, field) in spec::ASSET_FIELDS {
+            let Some(value) = entity.pointer_mut(&format!("/components/{}/{}", component, field)) else {
+                continue;
+            };
+            let Some(reference) = value.as_str().filter(|r| !r.is_empty()).map(str::to_string) else {
+                continue;
+            };
+            if let Some(placeholder) = placeholders.get(&reference) {
+                *value = Value::String(placeholder.clone());
+                continue;
+            }
+            let relative = manifest.resolve(&reference).map_or_else(|| reference.clone(), |entry| entry.path.clone());
+            let path = root.join(&relative);
+            let audio = *component == "audio" || AssetKind::from_path(&path) == AssetKind::Audio;
+            let index = placeholders.len() + 1;
+            let (name, bytes) = if audio {
+                if !path.is_file() {
+                    missing.insert(reference.clone());
+                }
+                (format!("assets/sound_{}.wav", index), placeholder_wav())
+            } else {
+                let size = image::image_dimensions(&path).unwrap_or_else(|_| {
+                    missing.insert(reference.clone());
+                    fallback
+                });
+                match placeholder_png(size) {
+                    Ok(bytes) => (format!("assets/image_{}.png", index), bytes),
+                    Err(e) => {
+                        failure.get_or_insert(e);
+                        continue;
+                    }
+                }
+            };
+            placeholders.insert(reference, name.clone());
+            files.push((name.clone(), bytes));
+            *value = Value::String(name);
+        }
+    });
+
+    match failure {
+        Some(e) => Err(e),
+        None => Ok((files, missing.into_iter().collect())),
+    }
+}
+
+fn readme(bundle: &ReproBundle, notes: &str) -> String {
+    let mut readme = format!(
+        "# PromptPlay reproduction bundle\n\nPromptPlay {} on {} ({})\n\n\
+         - `game.json`: the project spec, with local paths and author metadata removed\n\
+         - `assets/`: stand-ins the same size as the original images, and silent sounds, in place of {} asset(s)\n\
+         - `logs/`: project health report and hook runs, with local paths removed\n",
+        env!("CARGO_PKG_VERSION"),
+        std::env::consts::OS,
+        std::env::consts::ARCH,
+        bundle.assets_replaced,
+    );
+    if !bundle.missing_assets.is_empty() {
+        readme.push_str(&format!(
+            "\nThese references couldn't be read, so their stand-ins use the sprite size: {}\n",
+            bundle.missing_assets.join(", ")
+        ));
+    }
+    if !notes.trim().is_empty() {
+        readme.push_str(&format!("\n## Notes\n\n{}\n", notes.trim()));
+    }
+    readme
+}
+
+/// Zip in-memory entries into `target` via a temporary file, like backup archives
+fn write_bundle(entries: &[(String, Vec<u8>)], target: &Path) -> Result<(), String> {
+    let partial = target.with_extension("zip.part");
+    let write = || -> Result<(), String> {
+        let file = File::create(&partial).map_err(|e| format!("Failed to create bundle: {}", e))?;
+        let mut zip = zip::ZipWriter::new(file);
+        let options = SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+        for (name, bytes) in entries {
+            zip.start_file(name.as_str(), options)
+                .map_err(|e| format!("Failed to add {} to bundle: {}", name, e))?;
+            zip.write_all(bytes).map_err(|e| format!("Failed to add {} to bundle: {}", name, e))?;
+        }
+        zip.finish().map_err(|e| format!("Failed to finish bundle: {}", e))?;
+        fs::rename(&partial, target).map_err(|e| format!("Failed to finish bundle: {}", e))
+    };
+    write().inspect_err(|_| {
+        let _ = fs::remove_file(&partial);
+    })
+}
+
+/// Package the project into a small zip for a bug report: the spec with local paths and author
+/// metadata removed, same-size placeholder images (and silent sounds) in place of the real
+/// assets, and the project's logs. `logs` is extra text to include, such as the preview console;
+/// `notes` goes into the bundle's README.
+#[tauri::command]
+pub async fn export_repro_bundle(
+    project_path: String,
+    output_path: String,
+    logs: Option<String>,
+    notes: Option<String>,
+) -> Result<ReproBundle, String> {
+    let mut game_spec = spec::load_project_spec(&project_path)?;
+    let redactor = Redactor::new(&paths::project_root(&project_path));
+
+    let stripped_metadata = templates::strip_personal_metadata(&mut game_spec);
+    let (assets, missing_assets) = replace_assets(&project_path, &mut game_spec)?;
+    let mut paths_redacted = redactor.value(&mut game_spec);
+
+    let mut entries = vec![(
+        "game.json".to_string(),
+        serde_json::to_vec_pretty(&game_spec).map_err(|e| format!("Failed to serialize spec: {}", e))?,
+    )];
+    let assets_replaced = assets.len();
+    entries.extend(assets);
+
+    let mut report = serde_json::to_value(health::report_in_background(project_path.clone()).await?)
+        .map_err(|e| format!("Failed to serialize health report: {}", e))?;
+    paths_redacted += redactor.value(&mut report);
+    entries.push((
+        "logs/health.json".to_string(),
+        serde_json::to_vec_pretty(&report).map_err(|e| format!("Failed to serialize health report: {}", e))?,
+    ));
+    let hook_log = fs::read_to_string(PathBuf::from(&project_path).join(hooks::LOG_FILE)).ok();
+    for (name, text) in [("logs/hooks.log", hook_log), ("logs/app.log", logs)] {
+        if let Some(text) = text.filter(|t| !t.trim().is_empty()) {
+            let (text, changed) = redactor.log(&text);
+            paths_redacted += changed;
+            entries.push((name.to_string(), text.into_bytes()));
+        }
+    }
+
+    let mut bundle = ReproBundle {
+        output_path: output_path.clone(),
+        files: Vec::new(),
+        assets_replaced,
+        paths_redacted,
+        stripped_metadata,
+        missing_assets,
+    };
+    let (notes, _) = redactor.text(notes.as_deref().unwrap_or_default());
+    entries.insert(0, ("README.md".to_string(), readme(&bundle, &notes).into_bytes()));
+    bundle.files = entries.iter().map(|(name, _)| name.clone()).collect();
+
+    write_bundle(&entries, Path::new(&output_path))?;
+    Ok(bundle)
+}
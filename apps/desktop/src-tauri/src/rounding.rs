@@ -0,0 +1,103 @@
+use crate::formatter::FormatConfig;
+use crate::{json_guard, spec};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Component fields that accumulate float noise from dragging, physics and AI edits
+const ROUNDED_FIELDS: &[(&str, &[&str])] = &[
+    ("transform", &["x", "y"]),
+    ("transform3d", &["x", "y", "z"]),
+    ("sprite", &["width", "height"]),
+    ("collider", &["width", "height", "radius"]),
+    ("velocity", &["vx", "vy"]),
+];
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum RoundingPolicy {
+    /// Leave values as they are
+    #[default]
+    None,
+    /// Snap to whole numbers
+    Integer,
+    /// Snap to one decimal place
+    Tenth,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RoundingResult {
+    pub game_spec_json: String,
+    pub rounded: usize,
+}
+
+fn round_number(value: f64, policy: RoundingPolicy) -> Option<Value> {
+    match policy {
+        RoundingPolicy::None => None,
+        RoundingPolicy::Integer => Some(Value::from(value.round() as i64)),
+        RoundingPolicy::Tenth => {
+            let rounded = (value * 10.0).round() / 10.0;
+            Some(if rounded.fract() == 0.0 {
+                Value::from(rounded as i64)
+            } else {
+                Value::from(rounded)
+            })
+        }
+    }
+}
+
+/// Snap coordinate-like fields in place, returning how many values changed
+pub fn round_spec(game_spec: &mut Value, policy: RoundingPolicy) -> usize {
+    if policy == RoundingPolicy::None {
+        return 0;
+    }
+
+    let mut rounded = 0;
+    spec::for_each_entity_mut(game_spec, |entity| {
+        for (component, fields) in ROUNDED_FIELDS {
+            let Some(component) = entity.pointer_mut(&format!("/components/{}", component)) else {
+                continue;
+            };
+            for field in *fields {
+                let Some(value) = component.get_mut(*field) else {
+                    continue;
+                };
+                // Integers are already clean
+                let Some(number) = value.as_f64().filter(|_| value.is_f64()) else {
+                    continue;
+                };
+                if let Some(snapped) = round_number(number, policy) {
+                    if snapped.as_f64() != Some(number) {
+                        rounded += 1;
+                    }
+                    *value = snapped;
+                }
+            }
+        }
+    });
+    rounded
+}
+
+/// Apply the project's (or an explicit) rounding policy to a spec, e.g. an AI proposal before applying it
+#[tauri::command]
+pub async fn round_game_spec(
+    game_spec_json: String,
+    project_path: Option<String>,
+    policy: Option<RoundingPolicy>,
+) -> Result<RoundingResult, String> {
+    let mut game_spec: Value = json_guard::parse(game_spec_json.as_bytes(), "game spec", &json_guard::SPEC_LIMITS)?;
+
+    let policy = match (policy, project_path) {
+        (Some(policy), _) => policy,
+        (None, Some(path)) => FormatConfig::load(&path)?.rounding,
+        (None, None) => RoundingPolicy::None,
+    };
+
+    let rounded = round_spec(&mut game_spec, policy);
+    let game_spec_json = serde_json::to_string_pretty(&game_spec)
+        .map_err(|e| format!("Failed to serialize game spec: {}", e))?;
+
+    Ok(RoundingResult {
+        game_spec_json,
+        rounded,
+    })
+}
@@ -0,0 +1,225 @@
+use crate::project_settings::ProjectSettings;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+
+/// Folder under the app data directory holding downloaded runtimes: `runtimes/<channel>/<version>/`
+const RUNTIMES_DIR: &str = "runtimes";
+/// Descriptor every packaged runtime carries next to its runtime.js
+const RUNTIME_DESCRIPTOR: &str = "runtime.json";
+const RUNTIME_SCRIPT: &str = "runtime.js";
+/// Spec versions the runtime built into this app can play
+const BUILTIN_MIN_SPEC: &str = "1.0.0";
+const BUILTIN_MAX_SPEC: &str = "1.999.999";
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum RuntimeChannel {
+    #[default]
+    Stable,
+    Beta,
+    /// A runtime folder on disk, e.g. a local build of packages/runtime-2d
+    Local,
+}
+
+/// Which runtime a project's exports bundle (`runtime` in project.json)
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RuntimePin {
+    pub channel: RuntimeChannel,
+    /// Exact version; None means the newest available on the channel
+    pub version: Option<String>,
+    /// Runtime folder for the local channel
+    pub path: Option<String>,
+}
+
+/// Contents of a runtime's runtime.json
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct RuntimeDescriptor {
+    version: String,
+    min_spec_version: String,
+    max_spec_version: Option<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct RuntimeVersion {
+    pub channel: RuntimeChannel,
+    pub version: String,
+    pub min_spec_version: String,
+    pub max_spec_version: Option<String>,
+    /// Folder holding runtime.js; None for the runtime built into the app
+    pub path: Option<String>,
+    pub pinned: bool,
+}
+
+impl RuntimeVersion {
+    fn builtin() -> Self {
+        Self {
+            channel: RuntimeChannel::Stable,
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            min_spec_version: BUILTIN_MIN_SPEC.to_string(),
+            max_spec_version: Some(BUILTIN_MAX_SPEC.to_string()),
+            path: None,
+            pinned: false,
+        }
+    }
+
+    /// Whether this runtime can play a spec with the given `version` field
+    pub fn supports(&self, spec_version: &str) -> bool {
+        let Some(version) = parse_version(spec_version) else {
+            return false;
+        };
+        parse_version(&self.min_spec_version).is_some_and(|min| version >= min)
+            && self
+                .max_spec_version
+                .as_deref()
+                .and_then(parse_version)
+                .is_none_or(|max| version <= max)
+    }
+
+    /// The runtime's module script, or None for the built-in runtime
+    pub fn script(&self) -> Result<Option<String>, String> {
+        let Some(dir) = &self.path else {
+            return Ok(None);
+        };
+        fs::read_to_string(Path::new(dir).join(RUNTIME_SCRIPT))
+            .map(Some)
+            .map_err(|e| format!("Failed to read runtime {} from {}: {}", self.version, dir, e))
+    }
+}
+
+/// "1.2.3" (or "1.2", "1") as a comparable triple
+fn parse_version(text: &str) -> Option<(u64, u64, u64)> {
+    let mut parts = text.trim().trim_start_matches('v').split('.').map(|p| p.parse::<u64>().ok());
+    let major = parts.next()??;
+    let minor = parts.next().unwrap_or(Some(0))?;
+    let patch = parts.next().unwrap_or(Some(0))?;
+    Some((major, minor, patch))
+}
+
+fn read_runtime(dir: &Path, channel: RuntimeChannel) -> Result<RuntimeVersion, String> {
+    let content = fs::read_to_string(dir.join(RUNTIME_DESCRIPTOR))
+        .map_err(|e| format!("Failed to read {} in {}: {}", RUNTIME_DESCRIPTOR, dir.display(), e))?;
+    let descriptor: RuntimeDescriptor = serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse {} in {}: {}", RUNTIME_DESCRIPTOR, dir.display(), e))?;
+    if !dir.join(RUNTIME_SCRIPT).is_file() {
+        return Err(format!("{} has no {}", dir.display(), RUNTIME_SCRIPT));
+    }
+    Ok(RuntimeVersion {
+        channel,
+        version: descriptor.version,
+        min_spec_version: descriptor.min_spec_version,
+        max_spec_version: descriptor.max_spec_version,
+        path: Some(dir.to_string_lossy().to_string()),
+        pinned: false,
+    })
+}
+
+/// Runtimes installed under the app data directory for one channel
+fn installed(app: &AppHandle, channel: RuntimeChannel) -> Vec<RuntimeVersion> {
+    let name = match channel {
+        RuntimeChannel::Stable => "stable",
+        RuntimeChannel::Beta => "beta",
+        RuntimeChannel::Local => return Vec::new(),
+    };
+    let Ok(root) = app.path().app_data_dir() else {
+        return Vec::new();
+    };
+    let Ok(entries) = fs::read_dir(root.join(RUNTIMES_DIR).join(name)) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .filter_map(|entry| read_runtime(&entry.path(), channel).ok())
+        .collect()
+}
+
+/// Every runtime a pin can name: the built-in one plus installed stable and beta versions
+fn available(app: &AppHandle) -> Vec<RuntimeVersion> {
+    let mut versions = vec![RuntimeVersion::builtin()];
+    versions.extend(installed(app, RuntimeChannel::Stable));
+    versions.extend(installed(app, RuntimeChannel::Beta));
+    versions.sort_by(|a, b| {
+        (a.channel as u8, parse_version(&b.version)).cmp(&(b.channel as u8, parse_version(&a.version)))
+    });
+    versions
+}
+
+/// The runtime a pin refers to
+pub fn resolve(app: &AppHandle, pin: &RuntimePin) -> Result<RuntimeVersion, String> {
+    if pin.channel == RuntimeChannel::Local {
+        let path = pin.path.as_deref().ok_or("The local runtime channel needs a path")?;
+        return read_runtime(&PathBuf::from(path), RuntimeChannel::Local);
+    }
+
+    // available() lists each channel newest first
+    available(app)
+        .into_iter()
+        .filter(|v| v.channel == pin.channel)
+        .find(|v| pin.version.as_ref().is_none_or(|wanted| *wanted == v.version))
+        .ok_or_else(|| match &pin.version {
+            Some(version) => format!("Runtime {} is not installed on the {:?} channel", version, pin.channel),
+            None => format!("No runtime is installed on the {:?} channel", pin.channel),
+        })
+}
+
+/// Resolve a project's pinned runtime and check that it can play the spec
+pub fn runtime_for_export(app: &AppHandle, project_path: &str, spec: &Value) -> Result<RuntimeVersion, String> {
+    let runtime = resolve(app, &ProjectSettings::load(project_path)?.runtime)?;
+    let spec_version = spec.get("version").and_then(Value::as_str).unwrap_or(BUILTIN_MIN_SPEC);
+    if !runtime.supports(spec_version) {
+        return Err(format!(
+            "Runtime {} ({:?}) supports spec versions {} to {}, but this spec is version {}",
+            runtime.version,
+            runtime.channel,
+            runtime.min_spec_version,
+            runtime.max_spec_version.as_deref().unwrap_or("latest"),
+            spec_version
+        ));
+    }
+    Ok(runtime)
+}
+
+/// List the runtimes exports can bundle, marking the one the project pins
+#[tauri::command]
+pub async fn list_runtime_versions(app: AppHandle, project_path: Option<String>) -> Result<Vec<RuntimeVersion>, String> {
+    let pin = match &project_path {
+        Some(path) => Some(ProjectSettings::load(path)?.runtime),
+        None => None,
+    };
+    let pinned = pin.as_ref().and_then(|p| resolve(&app, p).ok());
+
+    let mut versions = available(&app);
+    if let Some(local) = pinned.as_ref().filter(|p| p.channel == RuntimeChannel::Local) {
+        versions.push(local.clone());
+    }
+    for version in &mut versions {
+        version.pinned = pinned
+            .as_ref()
+            .is_some_and(|p| p.channel == version.channel && p.version == version.version && p.path == version.path);
+    }
+    Ok(versions)
+}
+
+/// Pin the project's export runtime, checking that it resolves and supports the project's spec
+#[tauri::command]
+pub async fn set_runtime_pin(app: AppHandle, project_path: String, pin: RuntimePin) -> Result<RuntimeVersion, String> {
+    let mut settings = ProjectSettings::load(&project_path)?;
+    settings.runtime = pin;
+    let runtime = match crate::spec::load_project_spec(&project_path) {
+        Ok(game_spec) => {
+            let runtime = resolve(&app, &settings.runtime)?;
+            let spec_version = game_spec.get("version").and_then(Value::as_str).unwrap_or(BUILTIN_MIN_SPEC);
+            if !runtime.supports(spec_version) {
+                return Err(format!("Runtime {} cannot play spec version {}", runtime.version, spec_version));
+            }
+            runtime
+        }
+        Err(_) => resolve(&app, &settings.runtime)?,
+    };
+    settings.save(&project_path)?;
+    Ok(runtime)
+}
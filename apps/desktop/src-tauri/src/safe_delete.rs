@@ -0,0 +1,240 @@
+use crate::asset_usage;
+use crate::assets::AssetManifest;
+use crate::history::{self, HistoryEntry};
+use crate::references::{self, GraphEdge, NodeKind, Usages};
+use crate::{entity_locks, spec};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::cmp::Ordering;
+use std::fs;
+use std::path::PathBuf;
+
+/// What to do with references to the thing being deleted
+#[derive(Debug, Deserialize, Clone)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum DeleteStrategy {
+    /// Refuse while anything still uses it
+    Block,
+    /// Point every reference at another asset or prefab first
+    Remap { replacement: String },
+    /// Remove the referencing fields too; only runs once the user has confirmed
+    Cascade {
+        #[serde(default)]
+        confirmed: bool,
+    },
+}
+
+#[derive(Debug, Serialize)]
+pub struct SafeDeleteResult {
+    pub deleted: bool,
+    /// Graph node that was (or would have been) deleted
+    pub target: String,
+    pub usages: Usages,
+    pub remapped: usize,
+    pub removed_references: usize,
+    /// Updated game.json when references were rewritten
+    pub game_spec_json: Option<String>,
+    /// Undo entry for the spec edits, when there were any
+    pub history: Option<HistoryEntry>,
+    /// Locked entities holding references, when they kept a remap or cascade from running
+    pub locked_entities: Vec<String>,
+    /// Why nothing was deleted, for the confirmation or blocked dialog
+    pub message: Option<String>,
+}
+
+/// Tokens of a JSON pointer, numbers compared as numbers so later array items sort after earlier ones
+fn pointer_order(a: &str, b: &str) -> Ordering {
+    let tokens = |p: &str| p.split('/').map(|t| (t.parse::<usize>().ok(), t.to_string())).collect::<Vec<_>>();
+    tokens(a).cmp(&tokens(b))
+}
+
+/// Remove the value at `pointer`, from its parent object or array
+fn remove_at(spec: &mut Value, pointer: &str) -> bool {
+    let Some((parent, last)) = pointer.rsplit_once('/') else {
+        return false;
+    };
+    let last = last.replace("~1", "/").replace("~0", "~");
+    match spec.pointer_mut(parent) {
+        Some(Value::Object(map)) => map.remove(&last).is_some(),
+        Some(Value::Array(items)) => match last.parse::<usize>() {
+            Ok(index) if index < items.len() => {
+                items.remove(index);
+                true
+            }
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+/// Remove the prefab or dialogue definition itself from the spec
+fn remove_definition(spec: &mut Value, list: &str, key: &str) -> bool {
+    let Some(items) = spec.get_mut(list).and_then(Value::as_array_mut) else {
+        return false;
+    };
+    let before = items.len();
+    items.retain(|item| {
+        let id = item.get("id").and_then(Value::as_str);
+        let name = item.get("name").and_then(Value::as_str);
+        id.or(name) != Some(key)
+    });
+    items.len() != before
+}
+
+/// Spec text to write when remapping a reference to `replacement`
+fn replacement_text(graph: &references::ReferenceGraph, kind: NodeKind, replacement: &str) -> Result<String, String> {
+    let usages = references::usages(graph, replacement)?;
+    let (replacement_kind, key) = usages
+        .target
+        .split_once(':')
+        .ok_or_else(|| format!("Can't remap to '{}'", replacement))?;
+    let expected = match kind {
+        NodeKind::Asset => "asset",
+        NodeKind::Prefab => "prefab",
+        _ => "dialogue",
+    };
+    if replacement_kind != expected {
+        return Err(format!("Can't remap a {} to a {}", expected, replacement_kind));
+    }
+    Ok(key.to_string())
+}
+
+/// The locked entity an edge's reference sits on, if any
+fn locked_entity<'a>(spec: &'a Value, edge: &GraphEdge) -> Option<&'a Value> {
+    let id = edge.from.strip_prefix("entity:")?;
+    spec::all_entities(spec)
+        .find(|e| e.get("id").and_then(Value::as_str) == Some(id))
+        .filter(|e| entity_locks::is_locked(e))
+}
+
+/// Delete an asset, prefab or dialogue after checking what still uses it. `Block` refuses while it's
+/// in use, `Remap` rewrites references to a replacement, and `Cascade` removes them with confirmation.
+/// References on locked entities stop a remap or cascade unless `include_locked` is set.
+#[tauri::command]
+pub async fn safe_delete(
+    project_path: String,
+    path_or_id: String,
+    strategy: DeleteStrategy,
+    include_locked: Option<bool>,
+) -> Result<SafeDeleteResult, String> {
+    let before = spec::load_project_spec(&project_path)?;
+    let mut game_spec = before.clone();
+    let graph = references::build(&game_spec, &asset_usage::project_assets(&project_path));
+    let usages = references::usages(&graph, &path_or_id)?;
+    let (prefix, key) = usages.target.split_once(':').unwrap_or((usages.target.as_str(), ""));
+    let kind = match prefix {
+        "asset" => NodeKind::Asset,
+        "prefab" => NodeKind::Prefab,
+        "dialogue" => NodeKind::Dialogue,
+        _ => return Err(format!("Only assets, prefabs and dialogues can be deleted this way, not '{}'", path_or_id)),
+    };
+    let (prefix, key) = (prefix.to_string(), key.to_string());
+
+    let mut result = SafeDeleteResult {
+        deleted: false,
+        target: usages.target.clone(),
+        usages,
+        remapped: 0,
+        removed_references: 0,
+        game_spec_json: None,
+        history: None,
+        locked_entities: Vec::new(),
+        message: None,
+    };
+    let mut references: Vec<GraphEdge> = result.usages.references.clone();
+    // Rewrite deepest and last array items first so earlier pointers stay valid
+    references.sort_by(|a, b| pointer_order(&b.pointer, &a.pointer));
+
+    let edits_references = matches!(
+        strategy,
+        DeleteStrategy::Remap { .. } | DeleteStrategy::Cascade { confirmed: true }
+    );
+    if edits_references && !include_locked.unwrap_or(false) {
+        let mut locked: Vec<String> = references
+            .iter()
+            .filter_map(|edge| locked_entity(&game_spec, edge))
+            .map(|e| e.get("name").and_then(Value::as_str).unwrap_or_default().to_string())
+            .collect();
+        locked.sort();
+        locked.dedup();
+        if !locked.is_empty() {
+            result.message = Some(format!(
+                "Locked entities still reference it ({}); unlock them or allow editing locked entities",
+                locked.join(", ")
+            ));
+            result.locked_entities = locked;
+            return Ok(result);
+        }
+    }
+
+    let mut spec_changed = false;
+    if !references.is_empty() {
+        match &strategy {
+            DeleteStrategy::Block => {
+                result.message = Some(format!("{}; remap or remove those references first", result.usages.summary));
+                return Ok(result);
+            }
+            DeleteStrategy::Cascade { confirmed: false } => {
+                result.message = Some(format!(
+                    "{}; deleting it also removes {} reference(s). Confirm to continue.",
+                    result.usages.summary,
+                    references.len()
+                ));
+                return Ok(result);
+            }
+            DeleteStrategy::Remap { replacement } => {
+                let text = replacement_text(&graph, kind, replacement)?;
+                if text == key {
+                    return Err("The replacement is the item being deleted".to_string());
+                }
+                for edge in &references {
+                    if let Some(slot) = game_spec.pointer_mut(&edge.pointer) {
+                        *slot = Value::String(text.clone());
+                        result.remapped += 1;
+                    }
+                }
+            }
+            DeleteStrategy::Cascade { confirmed: true } => {
+                result.removed_references = references.iter().filter(|e| remove_at(&mut game_spec, &e.pointer)).count();
+            }
+        }
+        spec_changed = true;
+    }
+
+    match kind {
+        NodeKind::Prefab => spec_changed |= remove_definition(&mut game_spec, "prefabs", &key),
+        NodeKind::Dialogue => spec_changed |= remove_definition(&mut game_spec, "dialogues", &key),
+        _ => {}
+    }
+
+    // The spec is saved before the asset file goes, so a failed save never leaves references
+    // to a missing file
+    if spec_changed {
+        let label = format!("Delete {} '{}'", prefix, key);
+        result.history = Some(history::record(&project_path, &label, &before)?);
+        spec::save_project_spec(&project_path, &game_spec)?;
+        result.game_spec_json = Some(
+            serde_json::to_string_pretty(&game_spec).map_err(|e| format!("Failed to serialize game spec: {}", e))?,
+        );
+    }
+
+    if kind == NodeKind::Asset {
+        let file = PathBuf::from(&project_path).join(&key);
+        if file.exists() {
+            if let Err(e) = fs::remove_file(&file) {
+                if spec_changed {
+                    let _ = spec::save_project_spec(&project_path, &before);
+                }
+                return Err(format!("Failed to delete asset {}: {}", key, e));
+            }
+        }
+        let mut manifest = AssetManifest::load(&project_path)?;
+        let count = manifest.assets.len();
+        manifest.assets.retain(|a| a.path != key);
+        if manifest.assets.len() != count {
+            manifest.save(&project_path)?;
+        }
+    }
+    result.deleted = true;
+    Ok(result)
+}
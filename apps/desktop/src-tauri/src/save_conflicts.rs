@@ -0,0 +1,317 @@
+use crate::settings::AppSettings;
+use crate::{entity_ids, history, json_guard, spec};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, HashSet};
+use std::path::PathBuf;
+use std::time::UNIX_EPOCH;
+use tauri::AppHandle;
+
+/// What a save does when game.json changed on disk since the editor loaded or last saved it
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum ConflictPolicy {
+    /// Keep whichever side was edited last, the editor's changes or the file's
+    PreferNewest,
+    /// Write nothing and let the user choose
+    AlwaysPrompt,
+    /// Combine changes to different entities, components and settings; prompt when both sides
+    /// changed the same thing
+    #[default]
+    AutoMerge,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum SaveOrigin {
+    #[default]
+    Manual,
+    Autosave,
+}
+
+/// The user's answer to a prompted conflict
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ConflictResolution {
+    KeepMine,
+    KeepDisk,
+}
+
+/// What the editor knows about the spec it's saving, so the save can tell whether someone else
+/// wrote game.json in the meantime
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SaveContext {
+    /// The spec as the editor last loaded or saved it
+    pub base_spec_json: String,
+    /// When the editor's copy was last edited, in Unix milliseconds; now when absent
+    pub edited_at: Option<u64>,
+    #[serde(default)]
+    pub origin: SaveOrigin,
+    /// Answer to an earlier `conflict` outcome
+    pub resolution: Option<ConflictResolution>,
+}
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum SaveDecision {
+    /// No external change; the editor's spec was written
+    Saved,
+    /// External and editor changes were combined and written
+    Merged,
+    /// The editor's spec replaced an external change
+    KeptMine,
+    /// The external change was kept and the editor's spec discarded
+    KeptDisk,
+    /// Nothing was written; the user has to choose
+    Conflict,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SaveOutcome {
+    pub decision: SaveDecision,
+    pub policy: ConflictPolicy,
+    /// game.json as it now stands when that isn't what the editor sent (merged, kept disk, or the
+    /// disk side of a conflict), for the editor to load
+    pub spec_json: Option<String>,
+    /// Spec paths both sides changed differently, e.g. "/scenes/level-1/entities/player/components/transform"
+    pub conflicts: Vec<String>,
+    /// Undo entry holding the side that was replaced; undo_last_change brings it back
+    pub history_id: Option<String>,
+}
+
+impl SaveOutcome {
+    fn new(decision: SaveDecision, policy: ConflictPolicy) -> Self {
+        Self {
+            decision,
+            policy,
+            spec_json: None,
+            conflicts: Vec::new(),
+            history_id: None,
+        }
+    }
+}
+
+/// Array items paired with their "id", in order; None when any item has no string id
+fn keyed(items: &[Value]) -> Option<Vec<(&str, &Value)>> {
+    items.iter().map(|item| Some((item.get("id")?.as_str()?, item))).collect()
+}
+
+/// Three-way merge of one spec value. Objects merge key by key and arrays of items with ids
+/// (entities, scenes) item by item, so edits to different entities or components combine.
+/// Anything both sides changed differently is recorded in `conflicts` and keeps `ours`.
+fn merge(
+    base: Option<&Value>,
+    ours: Option<&Value>,
+    theirs: Option<&Value>,
+    path: &str,
+    conflicts: &mut Vec<String>,
+) -> Option<Value> {
+    if ours == theirs || theirs == base {
+        return ours.cloned();
+    }
+    if ours == base {
+        return theirs.cloned();
+    }
+    match (base, ours, theirs) {
+        (Some(Value::Object(b)), Some(Value::Object(o)), Some(Value::Object(t))) => {
+            Some(Value::Object(merge_objects(b, o, t, path, conflicts)))
+        }
+        (None, Some(Value::Object(o)), Some(Value::Object(t))) => {
+            Some(Value::Object(merge_objects(&Map::new(), o, t, path, conflicts)))
+        }
+        (Some(Value::Array(b)), Some(Value::Array(o)), Some(Value::Array(t))) => {
+            match (keyed(b), keyed(o), keyed(t)) {
+                (Some(b), Some(o), Some(t)) => Some(Value::Array(merge_keyed(&b, &o, &t, path, conflicts))),
+                _ => {
+                    conflicts.push(path.to_string());
+                    ours.cloned()
+                }
+            }
+        }
+        _ => {
+            conflicts.push(path.to_string());
+            ours.cloned()
+        }
+    }
+}
+
+fn merge_objects(
+    base: &Map<String, Value>,
+    ours: &Map<String, Value>,
+    theirs: &Map<String, Value>,
+    path: &str,
+    conflicts: &mut Vec<String>,
+) -> Map<String, Value> {
+    let mut merged = Map::new();
+    // Our key order first, then keys only the other side has, then keys both removed
+    let mut keys: Vec<&String> = Vec::new();
+    for key in ours.keys().chain(theirs.keys()).chain(base.keys()) {
+        if !keys.contains(&key) {
+            keys.push(key);
+        }
+    }
+    for key in keys {
+        let child = format!("{}/{}", path, key);
+        if let Some(value) = merge(base.get(key), ours.get(key), theirs.get(key), &child, conflicts) {
+            merged.insert(key.clone(), value);
+        }
+    }
+    merged
+}
+
+/// Merge arrays of items with ids item by item: our order, then items the other side added
+fn merge_keyed(
+    base: &[(&str, &Value)],
+    ours: &[(&str, &Value)],
+    theirs: &[(&str, &Value)],
+    path: &str,
+    conflicts: &mut Vec<String>,
+) -> Vec<Value> {
+    let base_by_id: BTreeMap<&str, &Value> = base.iter().copied().collect();
+    let ours_by_id: BTreeMap<&str, &Value> = ours.iter().copied().collect();
+    let theirs_by_id: BTreeMap<&str, &Value> = theirs.iter().copied().collect();
+    let mut seen: HashSet<&str> = HashSet::new();
+    let ids: Vec<&str> = ours
+        .iter()
+        .chain(theirs)
+        .chain(base)
+        .map(|(id, _)| *id)
+        .filter(|id| seen.insert(id))
+        .collect();
+    let mut merged = Vec::new();
+    for id in ids {
+        let child = format!("{}/{}", path, id);
+        let value = merge(
+            base_by_id.get(id).copied(),
+            ours_by_id.get(id).copied(),
+            theirs_by_id.get(id).copied(),
+            &child,
+            conflicts,
+        );
+        merged.extend(value);
+    }
+    merged
+}
+
+/// Merge `theirs` (game.json on disk) into `ours` (the editor's spec) relative to `base`
+pub fn merge_specs(base: &Value, ours: &Value, theirs: &Value) -> (Value, Vec<String>) {
+    let mut conflicts = Vec::new();
+    let merged = merge(Some(base), Some(ours), Some(theirs), "", &mut conflicts).unwrap_or(Value::Null);
+    (merged, conflicts)
+}
+
+fn modified_millis(project_path: &str) -> Option<u64> {
+    let modified = PathBuf::from(project_path).join("game.json").metadata().ok()?.modified().ok()?;
+    Some(modified.duration_since(UNIX_EPOCH).ok()?.as_millis() as u64)
+}
+
+fn now_millis() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+fn to_json(spec: &Value) -> Result<String, String> {
+    serde_json::to_string_pretty(spec).map_err(|e| format!("Failed to serialize spec: {}", e))
+}
+
+/// Decide what a save writes, applying the conflict policy when game.json changed since the
+/// context's base; without a context the spec is written as is. Every decision that replaces one
+/// side records it as an undo step labelled with the decision.
+pub fn resolve_save(
+    app: &AppHandle,
+    project_path: &str,
+    incoming: &Value,
+    context: Option<&SaveContext>,
+) -> Result<SaveOutcome, String> {
+    let policy = AppSettings::load(app).save_conflicts;
+    let Some(context) = context else {
+        spec::save_project_spec(project_path, incoming)?;
+        return Ok(SaveOutcome::new(SaveDecision::Saved, policy));
+    };
+    let mut base: Value = json_guard::parse(context.base_spec_json.as_bytes(), "base spec", &json_guard::SPEC_LIMITS)?;
+    // Legacy ids are derived deterministically, so every side ends up keyed the same way as the
+    // migrated disk copy and keyed arrays merge item by item
+    entity_ids::migrate(&mut base);
+    let disk_path = PathBuf::from(project_path).join("game.json");
+    let disk = if disk_path.exists() {
+        spec::load_project_spec(project_path)?
+    } else {
+        base.clone()
+    };
+
+    let unchanged = disk == base || disk == *incoming;
+    if unchanged {
+        spec::save_project_spec(project_path, incoming)?;
+        return Ok(SaveOutcome::new(SaveDecision::Saved, policy));
+    }
+
+    let action = match context.origin {
+        SaveOrigin::Manual => "Save",
+        SaveOrigin::Autosave => "Autosave",
+    };
+    let (merged, conflicts) = merge_specs(&base, incoming, &disk);
+    let keep_mine = |label: &str| -> Result<SaveOutcome, String> {
+        let entry = history::record(project_path, &format!("{}: {}", action, label), &disk)?;
+        spec::save_project_spec(project_path, incoming)?;
+        Ok(SaveOutcome {
+            history_id: Some(entry.id),
+            ..SaveOutcome::new(SaveDecision::KeptMine, policy)
+        })
+    };
+    // The discarded editor spec becomes the undo step, so undo brings those edits back
+    let keep_disk = |label: &str| -> Result<SaveOutcome, String> {
+        let entry = history::record(project_path, &format!("{}: {}", action, label), incoming)?;
+        Ok(SaveOutcome {
+            spec_json: Some(to_json(&disk)?),
+            history_id: Some(entry.id),
+            ..SaveOutcome::new(SaveDecision::KeptDisk, policy)
+        })
+    };
+
+    match (context.resolution, policy) {
+        (Some(ConflictResolution::KeepMine), _) => keep_mine("kept editor changes over the file's (chosen)"),
+        (Some(ConflictResolution::KeepDisk), _) => keep_disk("kept the file's changes over the editor's (chosen)"),
+        (None, ConflictPolicy::PreferNewest) => {
+            let edited_at = context.edited_at.unwrap_or_else(now_millis);
+            match modified_millis(project_path) {
+                Some(modified) if modified > edited_at => keep_disk("kept the newer file changes over the editor's"),
+                _ => keep_mine("kept the newer editor changes over the file's"),
+            }
+        }
+        (None, ConflictPolicy::AutoMerge) if conflicts.is_empty() => {
+            let entry = history::record(
+                project_path,
+                &format!("{}: merged changes made outside the editor", action),
+                &disk,
+            )?;
+            spec::save_project_spec(project_path, &merged)?;
+            Ok(SaveOutcome {
+                spec_json: Some(to_json(&merged)?),
+                history_id: Some(entry.id),
+                ..SaveOutcome::new(SaveDecision::Merged, policy)
+            })
+        }
+        (None, ConflictPolicy::AutoMerge | ConflictPolicy::AlwaysPrompt) => Ok(SaveOutcome {
+            spec_json: Some(to_json(&disk)?),
+            conflicts,
+            ..SaveOutcome::new(SaveDecision::Conflict, policy)
+        }),
+    }
+}
+
+#[tauri::command]
+pub async fn get_conflict_policy(app: AppHandle) -> Result<ConflictPolicy, String> {
+    Ok(AppSettings::load(&app).save_conflicts)
+}
+
+/// Choose how saves handle game.json changing underneath the editor
+#[tauri::command]
+pub async fn set_conflict_policy(app: AppHandle, policy: ConflictPolicy) -> Result<(), String> {
+    let mut settings = AppSettings::load(&app);
+    settings.save_conflicts = policy;
+    settings.save(&app)
+}
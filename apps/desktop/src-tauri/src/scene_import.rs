@@ -0,0 +1,981 @@
+use crate::entity_csv::target_entities;
+use crate::history::{self, HistoryEntry};
+use crate::{assets, entity_ids, spec};
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Subfolder of assets/ that converted scenes copy their textures into
+const IMPORTED_ASSETS_DIR: &str = "imported";
+/// Size used for sprites whose texture couldn't be read
+const FALLBACK_SPRITE_SIZE: f64 = 32.0;
+/// Unity's default; Unity units times this gives pixels
+const DEFAULT_PIXELS_PER_UNIT: f64 = 100.0;
+/// .meta files read while looking up Unity asset guids
+const MAX_META_FILES: usize = 20_000;
+
+/// Something in the source scene the importer left out or could only approximate
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Untranslated {
+    /// Node path (Godot) or GameObject name (Unity)
+    pub node: String,
+    /// Source node type or component, e.g. "Camera2D" or "MonoBehaviour"
+    pub kind: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SceneImportReport {
+    /// Names of the entities created
+    pub created: Vec<String>,
+    /// Project paths of textures copied into assets/imported
+    pub imported_assets: Vec<String>,
+    pub untranslated: Vec<Untranslated>,
+    pub applied: bool,
+    pub history: Option<HistoryEntry>,
+}
+
+/// 2D placement in pixels, y-down, rotation in radians
+#[derive(Debug, Clone, Copy)]
+struct Transform2D {
+    x: f64,
+    y: f64,
+    rotation: f64,
+    scale_x: f64,
+    scale_y: f64,
+}
+
+impl Default for Transform2D {
+    fn default() -> Self {
+        Self {
+            x: 0.0,
+            y: 0.0,
+            rotation: 0.0,
+            scale_x: 1.0,
+            scale_y: 1.0,
+        }
+    }
+}
+
+impl Transform2D {
+    /// `local` placed inside `self`
+    fn then(&self, local: &Transform2D) -> Transform2D {
+        let (lx, ly) = (local.x * self.scale_x, local.y * self.scale_y);
+        let (sin, cos) = self.rotation.sin_cos();
+        Transform2D {
+            x: self.x + lx * cos - ly * sin,
+            y: self.y + lx * sin + ly * cos,
+            rotation: self.rotation + local.rotation,
+            scale_x: self.scale_x * local.scale_x,
+            scale_y: self.scale_y * local.scale_y,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum BodyKind {
+    Static,
+    Dynamic,
+    Sensor,
+}
+
+#[derive(Debug, Clone)]
+struct SpriteSource {
+    /// Texture file in the source project
+    texture: Option<PathBuf>,
+    /// Size in source pixels when the scene states it; otherwise read from the texture
+    size: Option<(f64, f64)>,
+    /// Divides the texture's size (sprite sheets)
+    frames: (f64, f64),
+    /// Applied to the size before the entity's scale: a Godot sprite's own scale under its body,
+    /// or a Unity sprite's pixels-per-unit against the import's
+    scale: (f64, f64),
+    tint: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+enum Shape {
+    Box(f64, f64),
+    Circle(f64),
+}
+
+/// One entity-to-be, in output pixels
+#[derive(Debug, Clone)]
+struct ImportedNode {
+    name: String,
+    transform: Transform2D,
+    sprite: Option<SpriteSource>,
+    shape: Option<Shape>,
+    body: Option<BodyKind>,
+}
+
+/// Hex color for a 0..1 RGB tint, None for white
+fn tint_hex(r: f64, g: f64, b: f64) -> Option<String> {
+    let channel = |c: f64| (c.clamp(0.0, 1.0) * 255.0).round() as u8;
+    let (r, g, b) = (channel(r), channel(g), channel(b));
+    ((r, g, b) != (255, 255, 255)).then(|| format!("#{:02x}{:02x}{:02x}", r, g, b))
+}
+
+/// Copy textures into the project and turn the nodes into entities with unique names
+fn build_entities(
+    project_path: &str,
+    game_spec: &Value,
+    nodes: Vec<ImportedNode>,
+    dry_run: bool,
+    report: &mut SceneImportReport,
+) -> Result<Vec<Value>, String> {
+    let mut names: HashSet<String> = spec::all_entities(game_spec)
+        .filter_map(|e| Some(e.get("name")?.as_str()?.to_string()))
+        .collect();
+    let mut textures: HashMap<PathBuf, Option<String>> = HashMap::new();
+    let mut entities = Vec::new();
+
+    for node in nodes {
+        let t = node.transform;
+        let mut components = Map::new();
+        components.insert(
+            "transform".to_string(),
+            json!({ "x": t.x, "y": t.y, "rotation": t.rotation, "scaleX": 1, "scaleY": 1 }),
+        );
+
+        if let Some(sprite) = &node.sprite {
+            let texture = sprite.texture.as_ref().and_then(|source| {
+                textures
+                    .entry(source.clone())
+                    .or_insert_with(|| {
+                        if !source.is_file() {
+                            report.untranslated.push(Untranslated {
+                                node: node.name.clone(),
+                                kind: "texture".to_string(),
+                                reason: format!("{} was not found; the sprite has no texture", source.display()),
+                            });
+                            return None;
+                        }
+                        if dry_run {
+                            return Some(format!(
+                                "assets/{}/{}",
+                                IMPORTED_ASSETS_DIR,
+                                assets::normalized_file_name(&source.file_name().unwrap_or_default().to_string_lossy())
+                            ));
+                        }
+                        match assets::import_file(project_path, source, IMPORTED_ASSETS_DIR, None) {
+                            Ok(entry) => {
+                                report.imported_assets.push(entry.path.clone());
+                                Some(entry.path)
+                            }
+                            Err(e) => {
+                                report.untranslated.push(Untranslated {
+                                    node: node.name.clone(),
+                                    kind: "texture".to_string(),
+                                    reason: e,
+                                });
+                                None
+                            }
+                        }
+                    })
+                    .clone()
+            });
+            let size = sprite.size.or_else(|| {
+                let (w, h) = image::image_dimensions(sprite.texture.as_ref()?).ok()?;
+                Some((w as f64 / sprite.frames.0, h as f64 / sprite.frames.1))
+            });
+            let (width, height) = size
+                .map(|(w, h)| (w * sprite.scale.0, h * sprite.scale.1))
+                .unwrap_or((FALLBACK_SPRITE_SIZE, FALLBACK_SPRITE_SIZE));
+            let mut component = json!({
+                "texture": texture.unwrap_or_default(),
+                "width": (width * t.scale_x).abs(),
+                "height": (height * t.scale_y).abs(),
+            });
+            if let Some(tint) = &sprite.tint {
+                component["tint"] = Value::from(tint.as_str());
+            }
+            components.insert("sprite".to_string(), component);
+        }
+
+        if let Some(shape) = &node.shape {
+            let mut collider = match shape {
+                Shape::Box(w, h) => json!({
+                    "type": "box",
+                    "width": (w * t.scale_x).abs(),
+                    "height": (h * t.scale_y).abs(),
+                }),
+                Shape::Circle(r) => json!({ "type": "circle", "radius": r * t.scale_x.abs().max(t.scale_y.abs()) }),
+            };
+            match node.body {
+                Some(BodyKind::Static) | None => collider["isStatic"] = Value::Bool(true),
+                Some(BodyKind::Sensor) => collider["isSensor"] = Value::Bool(true),
+                Some(BodyKind::Dynamic) => {}
+            }
+            components.insert("collider".to_string(), collider);
+        }
+        if node.body == Some(BodyKind::Dynamic) {
+            components.insert("velocity".to_string(), json!({ "vx": 0, "vy": 0 }));
+        }
+
+        let base = if node.name.trim().is_empty() { "entity".to_string() } else { node.name.clone() };
+        let mut name = base.clone();
+        let mut suffix = 1;
+        while names.contains(&name) {
+            suffix += 1;
+            name = format!("{}_{}", base, suffix);
+        }
+        names.insert(name.clone());
+        report.created.push(name.clone());
+        entities.push(json!({
+            "id": entity_ids::new_entity_id(),
+            "name": name,
+            "components": components,
+            "tags": ["imported"],
+        }));
+    }
+    Ok(entities)
+}
+
+/// Convert, then add the entities to the project (or only report, for a dry run)
+fn import(
+    project_path: &str,
+    scene: Option<&str>,
+    dry_run: bool,
+    label: String,
+    nodes: Vec<ImportedNode>,
+    untranslated: Vec<Untranslated>,
+) -> Result<SceneImportReport, String> {
+    let mut game_spec = spec::load_project_spec(project_path)?;
+    let mut report = SceneImportReport {
+        created: Vec::new(),
+        imported_assets: Vec::new(),
+        untranslated,
+        applied: false,
+        history: None,
+    };
+    let entities = build_entities(project_path, &game_spec, nodes, dry_run, &mut report)?;
+    if dry_run || entities.is_empty() {
+        return Ok(report);
+    }
+    target_entities(&mut game_spec, scene)?.extend(entities);
+    report.history = Some(history::save_with_history(
+        project_path,
+        &format!("{}: {} entities", label, report.created.len()),
+        &game_spec,
+    )?);
+    report.applied = true;
+    Ok(report)
+}
+
+fn file_label(path: &Path) -> String {
+    path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default()
+}
+
+// ---------------------------------------------------------------------------------------------
+// Godot (.tscn, formats 2 and 3)
+
+#[derive(Debug, Default)]
+struct GodotSection {
+    tag: String,
+    attrs: HashMap<String, String>,
+    props: HashMap<String, String>,
+}
+
+const GODOT_BODIES: &[(&str, BodyKind)] = &[
+    ("StaticBody2D", BodyKind::Static),
+    ("AnimatableBody2D", BodyKind::Static),
+    ("RigidBody2D", BodyKind::Dynamic),
+    ("CharacterBody2D", BodyKind::Dynamic),
+    ("KinematicBody2D", BodyKind::Dynamic),
+    ("Area2D", BodyKind::Sensor),
+];
+const GODOT_SPRITES: &[&str] = &["Sprite2D", "Sprite", "AnimatedSprite2D", "AnimatedSprite"];
+/// Node types that only group and place their children
+const GODOT_CONTAINERS: &[&str] = &["Node", "Node2D"];
+
+/// Open minus close brackets, ignoring those in strings
+fn bracket_depth(text: &str) -> i32 {
+    let mut depth = 0;
+    let mut in_string = false;
+    let mut escaped = false;
+    for c in text.chars() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if in_string => escaped = true,
+            '"' => in_string = !in_string,
+            '(' | '[' | '{' if !in_string => depth += 1,
+            ')' | ']' | '}' if !in_string => depth -= 1,
+            _ => {}
+        }
+    }
+    depth
+}
+
+/// `tag key=value key="quoted value" key=Call( 1 )` from a section header
+fn parse_header(header: &str) -> (String, HashMap<String, String>) {
+    let mut chars = header.chars().peekable();
+    let tag: String = chars.by_ref().take_while(|c| !c.is_whitespace()).collect();
+    let mut attrs = HashMap::new();
+    loop {
+        while chars.peek().is_some_and(|c| c.is_whitespace()) {
+            chars.next();
+        }
+        let key: String = chars.by_ref().take_while(|c| *c != '=').collect();
+        if key.is_empty() {
+            break;
+        }
+        let mut value = String::new();
+        if chars.peek() == Some(&'"') {
+            chars.next();
+            let mut escaped = false;
+            for c in chars.by_ref() {
+                match c {
+                    _ if escaped => {
+                        value.push(c);
+                        escaped = false;
+                    }
+                    '\\' => escaped = true,
+                    '"' => break,
+                    _ => value.push(c),
+                }
+            }
+        } else {
+            let mut depth = 0;
+            while let Some(&c) = chars.peek() {
+                if c.is_whitespace() && depth == 0 {
+                    break;
+                }
+                depth += match c {
+                    '(' => 1,
+                    ')' => -1,
+                    _ => 0,
+                };
+                value.push(c);
+                chars.next();
+            }
+        }
+        attrs.insert(key.trim().to_string(), value);
+    }
+    (tag, attrs)
+}
+
+fn parse_tscn(text: &str) -> Vec<GodotSection> {
+    let mut sections: Vec<GodotSection> = Vec::new();
+    let mut open: Option<(String, String, i32)> = None;
+    for line in text.lines() {
+        if let Some((key, value, depth)) = open.as_mut() {
+            value.push('\n');
+            value.push_str(line);
+            *depth += bracket_depth(line);
+            if *depth <= 0 {
+                let (key, value, _) = open.take().unwrap_or_default();
+                if let Some(section) = sections.last_mut() {
+                    section.props.insert(key, value);
+                }
+            }
+            continue;
+        }
+        let trimmed = line.trim();
+        if trimmed.starts_with('[') && trimmed.ends_with(']') {
+            let (tag, attrs) = parse_header(&trimmed[1..trimmed.len() - 1]);
+            sections.push(GodotSection {
+                tag,
+                attrs,
+                props: HashMap::new(),
+            });
+        } else if let Some((key, value)) = trimmed.split_once('=') {
+            let (key, value) = (key.trim().to_string(), value.trim().to_string());
+            let depth = bracket_depth(&value);
+            if depth > 0 {
+                open = Some((key, value, depth));
+            } else if let Some(section) = sections.last_mut() {
+                section.props.insert(key, value);
+            }
+        }
+    }
+    sections
+}
+
+/// Numbers inside `Name(a, b, ...)`
+fn godot_numbers(value: &str, constructor: &str) -> Option<Vec<f64>> {
+    let inner = value.trim().strip_prefix(constructor)?.trim().strip_prefix('(')?.strip_suffix(')')?;
+    inner.split(',').map(|n| n.trim().parse().ok()).collect()
+}
+
+fn godot_vector(value: Option<&String>) -> Option<(f64, f64)> {
+    match godot_numbers(value?, "Vector2")?.as_slice() {
+        [x, y] => Some((*x, *y)),
+        _ => None,
+    }
+}
+
+/// Id inside `ExtResource("1_ab")`, `ExtResource( 1 )` or the SubResource equivalents
+fn godot_resource_id(value: Option<&String>, constructor: &str) -> Option<String> {
+    let inner = value?.trim().strip_prefix(constructor)?.trim().strip_prefix('(')?.strip_suffix(')')?;
+    Some(inner.trim().trim_matches('"').to_string())
+}
+
+/// Folder holding project.godot, which `res://` paths are relative to
+fn godot_root(scene_path: &Path) -> PathBuf {
+    scene_path
+        .ancestors()
+        .skip(1)
+        .find(|dir| dir.join("project.godot").is_file())
+        .or_else(|| scene_path.parent())
+        .map(Path::to_path_buf)
+        .unwrap_or_default()
+}
+
+fn godot_transform(props: &HashMap<String, String>) -> Transform2D {
+    let (x, y) = godot_vector(props.get("position")).unwrap_or((0.0, 0.0));
+    let rotation = props
+        .get("rotation")
+        .and_then(|r| r.parse::<f64>().ok())
+        .or_else(|| props.get("rotation_degrees").and_then(|r| r.parse::<f64>().ok()).map(f64::to_radians))
+        .unwrap_or(0.0);
+    let (scale_x, scale_y) = godot_vector(props.get("scale")).unwrap_or((1.0, 1.0));
+    Transform2D {
+        x,
+        y,
+        rotation,
+        scale_x,
+        scale_y,
+    }
+}
+
+/// The collision shape of a shape resource, with a note when it had to be approximated
+fn godot_shape(resource: &GodotSection) -> Result<(Shape, Option<String>), String> {
+    let kind = resource.attrs.get("type").map(String::as_str).unwrap_or_default();
+    let number = |key: &str, default: f64| resource.props.get(key).and_then(|v| v.parse().ok()).unwrap_or(default);
+    match kind {
+        "RectangleShape2D" => {
+            let (w, h) = godot_vector(resource.props.get("size"))
+                .or_else(|| godot_vector(resource.props.get("extents")).map(|(w, h)| (w * 2.0, h * 2.0)))
+                // Godot's default rectangle
+                .unwrap_or((20.0, 20.0));
+            Ok((Shape::Box(w, h), None))
+        }
+        "CircleShape2D" => Ok((Shape::Circle(number("radius", 10.0)), None)),
+        "CapsuleShape2D" => {
+            let (width, height) = (number("radius", 10.0) * 2.0, number("height", 30.0));
+            Ok((
+                Shape::Box(width, height),
+                Some(format!("Capsule approximated as a {} x {} box", width, height)),
+            ))
+        }
+        other => Err(format!("{} shapes aren't supported; add a box or circle collider by hand", other)),
+    }
+}
+
+/// Node path as used in `parent=` attributes: "." for the root, then "A", "A/B"
+fn godot_node_path(parent: Option<&str>, name: &str) -> String {
+    match parent {
+        None => ".".to_string(),
+        Some(".") => name.to_string(),
+        Some(parent) => format!("{}/{}", parent, name),
+    }
+}
+
+fn convert_godot(scene_path: &Path, text: &str) -> (Vec<ImportedNode>, Vec<Untranslated>) {
+    let sections = parse_tscn(text);
+    let root = godot_root(scene_path);
+    let resources = |tag: &str| -> HashMap<String, &GodotSection> {
+        sections
+            .iter()
+            .filter(|s| s.tag == tag)
+            .filter_map(|s| Some((s.attrs.get("id")?.clone(), s)))
+            .collect()
+    };
+    let external = resources("ext_resource");
+    let internal = resources("sub_resource");
+    let mut untranslated = Vec::new();
+    let mut skip = |node: &str, kind: &str, reason: String| {
+        untranslated.push(Untranslated {
+            node: node.to_string(),
+            kind: kind.to_string(),
+            reason,
+        })
+    };
+
+    let mut globals: HashMap<String, Transform2D> = HashMap::new();
+    // Node path -> index into `nodes` of the entity that node (or its body ancestor) became
+    let mut owners: HashMap<String, usize> = HashMap::new();
+    let mut nodes: Vec<ImportedNode> = Vec::new();
+
+    for section in sections.iter().filter(|s| s.tag == "node") {
+        let name = section.attrs.get("name").cloned().unwrap_or_default();
+        let parent = section.attrs.get("parent").map(String::as_str);
+        let path = godot_node_path(parent, &name);
+        let parent_global = parent.and_then(|p| globals.get(p)).copied().unwrap_or_default();
+        let global = parent_global.then(&godot_transform(&section.props));
+        globals.insert(path.clone(), global);
+        let owner = parent.and_then(|p| owners.get(p)).copied();
+
+        if section.props.contains_key("script") {
+            skip(&path, "script", "Scripts aren't converted; recreate the behavior with components".to_string());
+        }
+        let Some(kind) = section.attrs.get("type").map(String::as_str) else {
+            if section.attrs.contains_key("instance") {
+                skip(&path, "instance", "Instanced scene; import that scene separately".to_string());
+            }
+            continue;
+        };
+
+        if let Some((_, body)) = GODOT_BODIES.iter().find(|(k, _)| *k == kind) {
+            owners.insert(path.clone(), nodes.len());
+            nodes.push(ImportedNode {
+                name,
+                transform: global,
+                sprite: None,
+                shape: None,
+                body: Some(*body),
+            });
+        } else if GODOT_SPRITES.contains(&kind) {
+            let texture = godot_resource_id(section.props.get("texture"), "ExtResource")
+                .and_then(|id| external.get(&id)?.attrs.get("path").cloned())
+                .map(|res| root.join(res.trim_start_matches("res://")));
+            if texture.is_none() {
+                skip(
+                    &path,
+                    kind,
+                    "No texture could be resolved (animated sprites keep frames in a SpriteFrames resource)".to_string(),
+                );
+            }
+            let size = section
+                .props
+                .get("region_rect")
+                .filter(|_| section.props.get("region_enabled").is_some_and(|v| v == "true"))
+                .and_then(|rect| godot_numbers(rect, "Rect2"))
+                .and_then(|rect| Some((*rect.get(2)?, *rect.get(3)?)));
+            let frames = |key: &str| {
+                section.props.get(key).and_then(|v| v.parse::<f64>().ok()).unwrap_or(1.0).max(1.0)
+            };
+            let tint = section
+                .props
+                .get("modulate")
+                .and_then(|c| godot_numbers(c, "Color"))
+                .and_then(|c| tint_hex(*c.first()?, *c.get(1)?, *c.get(2)?));
+            let local = godot_transform(&section.props);
+            let mut sprite = SpriteSource {
+                texture,
+                size,
+                frames: (frames("hframes"), frames("vframes")),
+                scale: (1.0, 1.0),
+                tint,
+            };
+            match owner {
+                Some(index) if nodes[index].sprite.is_none() => {
+                    // Under a body the entity takes the body's transform, so the sprite keeps its own scale
+                    sprite.scale = (local.scale_x, local.scale_y);
+                    nodes[index].sprite = Some(sprite);
+                }
+                Some(_) => skip(&path, kind, "The body already has a sprite; only the first is kept".to_string()),
+                None => {
+                    owners.insert(path.clone(), nodes.len());
+                    nodes.push(ImportedNode {
+                        name,
+                        transform: global,
+                        sprite: Some(sprite),
+                        shape: None,
+                        body: None,
+                    });
+                }
+            }
+            if godot_vector(section.props.get("offset")).is_some_and(|o| o != (0.0, 0.0)) {
+                skip(&path, kind, "Sprite offset dropped; the sprite is centered on the entity".to_string());
+            }
+        } else if kind == "CollisionShape2D" {
+            let Some(index) = owner else {
+                skip(&path, kind, "Collision shape outside a physics body".to_string());
+                continue;
+            };
+            let resource = godot_resource_id(section.props.get("shape"), "SubResource").and_then(|id| internal.get(&id));
+            let Some(resource) = resource else {
+                skip(&path, kind, "Shape resource not found".to_string());
+                continue;
+            };
+            let shape = match godot_shape(resource) {
+                Ok((shape, note)) => {
+                    if let Some(note) = note {
+                        skip(&path, kind, note);
+                    }
+                    shape
+                }
+                Err(reason) => {
+                    skip(&path, kind, reason);
+                    continue;
+                }
+            };
+            if nodes[index].shape.is_some() {
+                skip(&path, kind, "Only one collider per entity; extra shapes are dropped".to_string());
+                continue;
+            }
+            // The shape node's own scale applies on top of the body's
+            let local = godot_transform(&section.props);
+            nodes[index].shape = Some(match shape {
+                Shape::Box(w, h) => Shape::Box(w * local.scale_x, h * local.scale_y),
+                Shape::Circle(r) => Shape::Circle(r * local.scale_x.abs().max(local.scale_y.abs())),
+            });
+            if (local.x, local.y) != (0.0, 0.0) {
+                skip(&path, kind, "Collider offset dropped; the collider is centered on the entity".to_string());
+            }
+        } else if !GODOT_CONTAINERS.contains(&kind) && parent.is_some() {
+            skip(&path, kind, format!("{} nodes have no PromptPlay equivalent", kind));
+        }
+    }
+
+    (nodes, untranslated)
+}
+
+// ---------------------------------------------------------------------------------------------
+// Unity (text-serialized .unity scenes and .prefab files)
+
+#[derive(Debug)]
+struct UnityObject {
+    class: String,
+    body: serde_yaml::Value,
+}
+
+/// Split a Unity YAML file into objects by file id. The `%TAG` directives and `!u!` tags aren't
+/// something serde_yaml reads, so documents are split on their `--- !u!<class> &<id>` lines.
+fn parse_unity(text: &str) -> BTreeMap<i64, UnityObject> {
+    let mut objects = BTreeMap::new();
+    let mut current: Option<(i64, String)> = None;
+    let flush = |current: &mut Option<(i64, String)>, objects: &mut BTreeMap<i64, UnityObject>| {
+        let Some((id, body)) = current.take() else {
+            return;
+        };
+        let Ok(serde_yaml::Value::Mapping(mapping)) = serde_yaml::from_str::<serde_yaml::Value>(&body) else {
+            return;
+        };
+        if let Some((class, body)) = mapping.into_iter().next() {
+            let class = class.as_str().unwrap_or_default().to_string();
+            objects.insert(id, UnityObject { class, body });
+        }
+    };
+    for line in text.lines() {
+        if let Some(header) = line.strip_prefix("--- !u!") {
+            flush(&mut current, &mut objects);
+            current = header
+                .split_whitespace()
+                .nth(1)
+                .and_then(|anchor| anchor.strip_prefix('&')?.parse().ok())
+                .map(|id| (id, String::new()));
+        } else if let Some((_, body)) = current.as_mut() {
+            body.push_str(line);
+            body.push('\n');
+        }
+    }
+    flush(&mut current, &mut objects);
+    objects
+}
+
+fn yaml_f64(value: &serde_yaml::Value, key: &str) -> Option<f64> {
+    value.get(key)?.as_f64().or_else(|| value.get(key)?.as_i64().map(|n| n as f64))
+}
+
+fn yaml_xy(value: &serde_yaml::Value, key: &str) -> Option<(f64, f64)> {
+    let v = value.get(key)?;
+    Some((yaml_f64(v, "x")?, yaml_f64(v, "y")?))
+}
+
+fn yaml_file_id(value: &serde_yaml::Value, key: &str) -> Option<i64> {
+    value.get(key)?.get("fileID")?.as_i64().filter(|id| *id != 0)
+}
+
+/// The Unity project folder (parent of Assets) the scene belongs to
+fn unity_root(scene_path: &Path) -> Option<PathBuf> {
+    scene_path
+        .ancestors()
+        .skip(1)
+        .find(|dir| dir.join("Assets").is_dir() && dir.join("ProjectSettings").is_dir())
+        .or_else(|| scene_path.ancestors().find(|dir| dir.file_name().is_some_and(|n| n == "Assets"))?.parent())
+        .map(Path::to_path_buf)
+}
+
+/// Asset paths of the given guids, found through the .meta files under Assets
+fn unity_assets(root: &Path, wanted: &HashSet<String>) -> HashMap<String, (PathBuf, f64)> {
+    let mut found = HashMap::new();
+    let mut pending = vec![root.join("Assets")];
+    let mut read = 0;
+    while let Some(dir) = pending.pop() {
+        for entry in fs::read_dir(&dir).into_iter().flatten().flatten() {
+            let path = entry.path();
+            if entry.file_type().is_ok_and(|t| t.is_dir()) {
+                pending.push(path);
+                continue;
+            }
+            if path.extension().is_none_or(|e| e != "meta") || read >= MAX_META_FILES {
+                continue;
+            }
+            read += 1;
+            let Ok(meta) = fs::read_to_string(&path) else {
+                continue;
+            };
+            let field = |name: &str| {
+                meta.lines()
+                    .find_map(|line| line.trim().strip_prefix(name)?.strip_prefix(':').map(|v| v.trim().to_string()))
+            };
+            let Some(guid) = field("guid").filter(|g| wanted.contains(g)) else {
+                continue;
+            };
+            let pixels_per_unit = field("spritePixelsToUnits")
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(DEFAULT_PIXELS_PER_UNIT);
+            found.insert(guid, (path.with_extension(""), pixels_per_unit));
+        }
+        if found.len() == wanted.len() {
+            break;
+        }
+    }
+    found
+}
+
+/// Angle about z of a Unity quaternion, in radians (counter-clockwise, y-up)
+fn quaternion_z(value: &serde_yaml::Value) -> f64 {
+    let q = |key| value.get("m_LocalRotation").and_then(|r| yaml_f64(r, key)).unwrap_or(0.0);
+    let (x, y, z, w) = (q("x"), q("y"), q("z"), q("w"));
+    (2.0 * (w * z + x * y)).atan2(1.0 - 2.0 * (y * y + z * z))
+}
+
+const UNITY_TRANSFORMS: &[&str] = &["Transform", "RectTransform"];
+/// Components that don't need a note: handled, or harmless to leave behind
+const UNITY_HANDLED: &[&str] = &[
+    "Transform",
+    "RectTransform",
+    "SpriteRenderer",
+    "BoxCollider2D",
+    "CircleCollider2D",
+    "CapsuleCollider2D",
+    "Rigidbody2D",
+];
+
+fn convert_unity(scene_path: &Path, text: &str, pixels_per_unit: f64) -> (Vec<ImportedNode>, Vec<Untranslated>) {
+    let objects = parse_unity(text);
+    let mut untranslated = Vec::new();
+
+    // GameObject id -> its components
+    let mut components: HashMap<i64, Vec<&UnityObject>> = HashMap::new();
+    for object in objects.values() {
+        if let Some(owner) = yaml_file_id(&object.body, "m_GameObject") {
+            components.entry(owner).or_default().push(object);
+        }
+    }
+    for object in objects.values().filter(|o| o.class == "PrefabInstance") {
+        let name = object
+            .body
+            .get("m_Modification")
+            .and_then(|m| m.get("m_Modifications"))
+            .and_then(serde_yaml::Value::as_sequence)
+            .and_then(|mods| {
+                mods.iter()
+                    .find(|m| m.get("propertyPath").and_then(serde_yaml::Value::as_str) == Some("m_Name"))
+            })
+            .and_then(|m| m.get("value")?.as_str().map(str::to_string))
+            .unwrap_or_else(|| "prefab".to_string());
+        untranslated.push(Untranslated {
+            node: name,
+            kind: "PrefabInstance".to_string(),
+            reason: "Nested prefab; import the prefab file separately".to_string(),
+        });
+    }
+
+    let transform_of = |game_object: i64| {
+        objects.iter().find_map(|(id, o)| {
+            (UNITY_TRANSFORMS.contains(&o.class.as_str()) && yaml_file_id(&o.body, "m_GameObject") == Some(game_object))
+                .then_some(*id)
+        })
+    };
+    let transforms: HashMap<i64, &serde_yaml::Value> = objects
+        .iter()
+        .filter(|(_, o)| UNITY_TRANSFORMS.contains(&o.class.as_str()))
+        .map(|(id, o)| (*id, &o.body))
+        .collect();
+    // World transform in Unity units, y-up
+    let world = |mut transform_id: i64| -> Transform2D {
+        let mut chain = Vec::new();
+        while let Some(body) = transforms.get(&transform_id) {
+            chain.push(*body);
+            match yaml_file_id(body, "m_Father") {
+                Some(father) if chain.len() < 256 => transform_id = father,
+                _ => break,
+            }
+        }
+        chain.iter().rev().fold(Transform2D::default(), |parent, body| {
+            let (x, y) = yaml_xy(body, "m_LocalPosition").unwrap_or((0.0, 0.0));
+            let (scale_x, scale_y) = yaml_xy(body, "m_LocalScale").unwrap_or((1.0, 1.0));
+            parent.then(&Transform2D {
+                x,
+                y,
+                rotation: quaternion_z(body),
+                scale_x,
+                scale_y,
+            })
+        })
+    };
+
+    let sprite_guids: HashSet<String> = objects
+        .values()
+        .filter(|o| o.class == "SpriteRenderer")
+        .filter_map(|o| Some(o.body.get("m_Sprite")?.get("guid")?.as_str()?.to_string()))
+        .collect();
+    let sprite_assets = match unity_root(scene_path) {
+        Some(root) if !sprite_guids.is_empty() => unity_assets(&root, &sprite_guids),
+        _ => HashMap::new(),
+    };
+
+    let mut nodes = Vec::new();
+    for (id, object) in objects.iter().filter(|(_, o)| o.class == "GameObject") {
+        let name = object.body.get("m_Name").and_then(serde_yaml::Value::as_str).unwrap_or_default().to_string();
+        let parts = components.get(id).cloned().unwrap_or_default();
+        let find = |class: &str| parts.iter().find(|c| c.class == class).map(|c| &c.body);
+        for part in parts.iter().filter(|c| !UNITY_HANDLED.contains(&c.class.as_str())) {
+            let reason = match part.class.as_str() {
+                "MonoBehaviour" => "Scripts aren't converted; recreate the behavior with components".to_string(),
+                "PolygonCollider2D" | "EdgeCollider2D" | "CompositeCollider2D" => {
+                    "Only box and circle colliders are converted".to_string()
+                }
+                other => format!("{} has no PromptPlay equivalent", other),
+            };
+            untranslated.push(Untranslated { node: name.clone(), kind: part.class.clone(), reason });
+        }
+
+        let sprite = find("SpriteRenderer").map(|renderer| {
+            let guid = renderer.get("m_Sprite").and_then(|s| s.get("guid")).and_then(serde_yaml::Value::as_str);
+            let asset = guid.and_then(|g| sprite_assets.get(g));
+            if asset.is_none() {
+                untranslated.push(Untranslated {
+                    node: name.clone(),
+                    kind: "SpriteRenderer".to_string(),
+                    reason: "Sprite asset not found next to the scene's Assets folder".to_string(),
+                });
+            }
+            let tint = renderer.get("m_Color").and_then(|c| tint_hex(yaml_f64(c, "r")?, yaml_f64(c, "g")?, yaml_f64(c, "b")?));
+            let sliced = renderer.get("m_DrawMode").and_then(serde_yaml::Value::as_i64).unwrap_or(0) != 0;
+            SpriteSource {
+                texture: asset.map(|(path, _)| path.clone()),
+                // Sliced and tiled sprites state their size in units
+                size: sliced
+                    .then(|| yaml_xy(renderer, "m_Size").map(|(w, h)| (w * pixels_per_unit, h * pixels_per_unit)))
+                    .flatten(),
+                frames: (1.0, 1.0),
+                scale: match asset {
+                    Some((_, sprite_ppu)) if !sliced => (pixels_per_unit / sprite_ppu, pixels_per_unit / sprite_ppu),
+                    _ => (1.0, 1.0),
+                },
+                tint,
+            }
+        });
+        let shape = find("BoxCollider2D")
+            .and_then(|c| yaml_xy(c, "m_Size").map(|(w, h)| (Shape::Box(w * pixels_per_unit, h * pixels_per_unit), c)))
+            .or_else(|| {
+                find("CircleCollider2D").map(|c| (Shape::Circle(yaml_f64(c, "m_Radius").unwrap_or(0.5) * pixels_per_unit), c))
+            })
+            .or_else(|| {
+                find("CapsuleCollider2D").and_then(|c| {
+                    let (w, h) = yaml_xy(c, "m_Size")?;
+                    untranslated.push(Untranslated {
+                        node: name.clone(),
+                        kind: "CapsuleCollider2D".to_string(),
+                        reason: format!("Capsule approximated as a {} x {} box", w * pixels_per_unit, h * pixels_per_unit),
+                    });
+                    Some((Shape::Box(w * pixels_per_unit, h * pixels_per_unit), c))
+                })
+            });
+        let rigidbody = find("Rigidbody2D");
+        if sprite.is_none() && shape.is_none() && rigidbody.is_none() {
+            continue;
+        }
+        if let Some((_, collider)) = &shape {
+            if yaml_xy(collider, "m_Offset").is_some_and(|o| o != (0.0, 0.0)) {
+                untranslated.push(Untranslated {
+                    node: name.clone(),
+                    kind: "Collider2D".to_string(),
+                    reason: "Collider offset dropped; the collider is centered on the entity".to_string(),
+                });
+            }
+        }
+        let trigger = shape
+            .as_ref()
+            .and_then(|(_, c)| c.get("m_IsTrigger")?.as_i64())
+            .is_some_and(|t| t != 0);
+        // m_BodyType: 0 dynamic, 1 kinematic, 2 static
+        let body = match (trigger, rigidbody.and_then(|r| r.get("m_BodyType")?.as_i64())) {
+            (true, _) => Some(BodyKind::Sensor),
+            (false, Some(0 | 1)) => Some(BodyKind::Dynamic),
+            (false, Some(_)) => Some(BodyKind::Static),
+            (false, None) => rigidbody.map(|_| BodyKind::Dynamic),
+        };
+
+        let unity = transform_of(*id).map(world).unwrap_or_default();
+        let transform = Transform2D {
+            x: unity.x * pixels_per_unit,
+            y: -unity.y * pixels_per_unit,
+            rotation: -unity.rotation,
+            scale_x: unity.scale_x,
+            scale_y: unity.scale_y,
+        };
+        nodes.push(ImportedNode {
+            name,
+            transform,
+            sprite,
+            shape: shape.map(|(shape, _)| shape),
+            body,
+        });
+    }
+    (nodes, untranslated)
+}
+
+/// Best-effort import of a Godot 2D scene (.tscn): node transforms, Sprite2D textures and
+/// rectangle or circle collision shapes become entities; everything else is listed in the report.
+/// Textures are copied into assets/imported.
+#[tauri::command]
+pub async fn import_godot_scene(
+    project_path: String,
+    path: String,
+    scene: Option<String>,
+    dry_run: Option<bool>,
+) -> Result<SceneImportReport, String> {
+    let scene_path = PathBuf::from(&path);
+    let text = fs::read_to_string(&scene_path).map_err(|e| format!("Failed to read file {}: {}", path, e))?;
+    if !text.trim_start().starts_with("[gd_scene") {
+        return Err(format!("{} is not a Godot text scene", path));
+    }
+    let (nodes, untranslated) = convert_godot(&scene_path, &text);
+    import(
+        &project_path,
+        scene.as_deref(),
+        dry_run.unwrap_or(false),
+        format!("Import Godot scene {}", file_label(&scene_path)),
+        nodes,
+        untranslated,
+    )
+}
+
+/// Best-effort import of a text-serialized Unity scene or prefab: GameObjects with a
+/// SpriteRenderer, 2D collider or Rigidbody2D become entities, converted from Unity units at
+/// `pixels_per_unit` (default 100) with y flipped to point down.
+#[tauri::command]
+pub async fn import_unity_scene_yaml(
+    project_path: String,
+    path: String,
+    scene: Option<String>,
+    pixels_per_unit: Option<f64>,
+    dry_run: Option<bool>,
+) -> Result<SceneImportReport, String> {
+    let scene_path = PathBuf::from(&path);
+    let text = fs::read_to_string(&scene_path).map_err(|e| format!("Failed to read file {}: {}", path, e))?;
+    if !text.contains("--- !u!") {
+        return Err(format!("{} is not a text-serialized Unity file (enable Force Text serialization)", path));
+    }
+    let pixels_per_unit = pixels_per_unit.filter(|p| *p > 0.0).unwrap_or(DEFAULT_PIXELS_PER_UNIT);
+    let (nodes, untranslated) = convert_unity(&scene_path, &text, pixels_per_unit);
+    import(
+        &project_path,
+        scene.as_deref(),
+        dry_run.unwrap_or(false),
+        format!("Import Unity scene {}", file_label(&scene_path)),
+        nodes,
+        untranslated,
+    )
+}
@@ -0,0 +1,248 @@
+use crate::{paths, spec};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+use tauri::State;
+
+const DEFAULT_PAGE_SIZE: usize = 500;
+const MAX_PAGE_SIZE: usize = 5000;
+
+/// A project's parsed game.json, kept between page requests so a 100k-entity scene is parsed once
+struct CachedSpec {
+    project_path: PathBuf,
+    modified: Option<SystemTime>,
+    /// Bumped on every reload; cursors from an older generation are rejected
+    generation: u64,
+    spec: Arc<Value>,
+}
+
+#[derive(Default)]
+pub struct SpecCacheState(Mutex<Option<CachedSpec>>);
+
+/// Everything about a scene except its entity list, which is fetched with `get_entities_page`
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SceneSummary {
+    /// None for the top-level entity list
+    pub scene: Option<String>,
+    /// The scene object (or for the top level, the spec) with entity lists left out
+    pub outline: Value,
+    pub entity_count: usize,
+    pub component_counts: BTreeMap<String, usize>,
+    pub tag_counts: BTreeMap<String, usize>,
+}
+
+/// Entity filter for paging; every set field must match
+#[derive(Debug, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct EntityFilter {
+    pub name_contains: Option<String>,
+    pub tag: Option<String>,
+    pub component: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EntityPage {
+    pub entities: Vec<Value>,
+    /// Pass back to fetch the next page; None on the last page
+    pub next_cursor: Option<String>,
+    /// Entities matching the filter across all pages
+    pub total: usize,
+}
+
+fn modified(project_path: &str) -> Option<SystemTime> {
+    fs::metadata(paths::for_io(&PathBuf::from(project_path).join("game.json")))
+        .and_then(|m| m.modified())
+        .ok()
+}
+
+/// The cached spec for `project_path` and its generation, reloading it when game.json changed
+fn cached_spec(state: &SpecCacheState, project_path: &str) -> Result<(Arc<Value>, u64), String> {
+    let mut guard = state.0.lock().map_err(|e| format!("Lock error: {}", e))?;
+    let modified = modified(project_path);
+    if let Some(cached) = guard
+        .as_ref()
+        .filter(|c| c.project_path == Path::new(project_path) && c.modified == modified)
+    {
+        return Ok((Arc::clone(&cached.spec), cached.generation));
+    }
+
+    let generation = guard.as_ref().map_or(0, |c| c.generation + 1);
+    let spec = Arc::new(spec::load_project_spec(project_path)?);
+    *guard = Some(CachedSpec {
+        project_path: PathBuf::from(project_path),
+        modified,
+        generation,
+        spec: Arc::clone(&spec),
+    });
+    Ok((spec, generation))
+}
+
+/// The scene object, or the spec itself for the top-level list
+fn scene_value<'a>(game_spec: &'a Value, scene: Option<&str>) -> Result<&'a Value, String> {
+    match scene {
+        None => Ok(game_spec),
+        Some(id) => game_spec
+            .get("scenes")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .find(|s| s.get("id").and_then(Value::as_str) == Some(id))
+            .ok_or_else(|| format!("Scene '{}' not found", id)),
+    }
+}
+
+fn scene_entities(scene: &Value) -> &[Value] {
+    scene.get("entities").and_then(Value::as_array).map(Vec::as_slice).unwrap_or_default()
+}
+
+/// `value` with every entity list removed, including those of nested scenes
+fn outline(value: &Value) -> Value {
+    let Some(object) = value.as_object() else {
+        return value.clone();
+    };
+    let mut outline: Map<String, Value> = object
+        .iter()
+        .filter(|(key, _)| *key != "entities" && *key != "scenes")
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect();
+    if let Some(scenes) = object.get("scenes").and_then(Value::as_array) {
+        outline.insert("scenes".to_string(), scenes.iter().map(outline_scene).collect());
+    }
+    Value::Object(outline)
+}
+
+fn outline_scene(scene: &Value) -> Value {
+    let mut outline = outline(scene);
+    if let Some(object) = outline.as_object_mut() {
+        object.insert("entityCount".to_string(), Value::from(scene_entities(scene).len()));
+    }
+    outline
+}
+
+fn entity_tags(entity: &Value) -> impl Iterator<Item = &str> {
+    entity.get("tags").and_then(Value::as_array).into_iter().flatten().filter_map(Value::as_str)
+}
+
+/// Counts and outline of one scene (None for the top level)
+pub fn summarize(game_spec: &Value, scene: Option<&str>) -> Result<SceneSummary, String> {
+    let value = scene_value(game_spec, scene)?;
+    let entities = scene_entities(value);
+    let mut component_counts = BTreeMap::new();
+    let mut tag_counts = BTreeMap::new();
+    for entity in entities {
+        for component in entity.get("components").and_then(Value::as_object).into_iter().flat_map(Map::keys) {
+            *component_counts.entry(component.clone()).or_insert(0) += 1;
+        }
+        for tag in entity_tags(entity) {
+            *tag_counts.entry(tag.to_string()).or_insert(0) += 1;
+        }
+    }
+    Ok(SceneSummary {
+        scene: scene.map(str::to_string),
+        outline: match scene {
+            None => outline(value),
+            Some(_) => outline_scene(value),
+        },
+        entity_count: entities.len(),
+        component_counts,
+        tag_counts,
+    })
+}
+
+impl EntityFilter {
+    fn matches(&self, entity: &Value, name_contains: Option<&str>) -> bool {
+        name_contains.is_none_or(|n| {
+            entity
+                .get("name")
+                .and_then(Value::as_str)
+                .is_some_and(|name| name.to_lowercase().contains(n))
+        }) && self.tag.as_deref().is_none_or(|t| entity_tags(entity).any(|tag| tag == t))
+            && self
+                .component
+                .as_deref()
+                .is_none_or(|c| entity.pointer(&format!("/components/{}", c)).is_some())
+    }
+}
+
+/// Cursors are "<generation>:<index of the next entity>"; opaque to the editor
+fn parse_cursor(cursor: &str, generation: u64) -> Result<usize, String> {
+    let (cursor_generation, index) = cursor
+        .split_once(':')
+        .and_then(|(g, i)| Some((g.parse::<u64>().ok()?, i.parse::<usize>().ok()?)))
+        .ok_or_else(|| format!("Invalid cursor '{}'", cursor))?;
+    if cursor_generation != generation {
+        return Err("game.json changed since this page was fetched; start paging again".to_string());
+    }
+    Ok(index)
+}
+
+/// Up to `limit` entities matching `filter`, starting at entity index `start`
+pub fn page(
+    game_spec: &Value,
+    scene: Option<&str>,
+    filter: &EntityFilter,
+    start: usize,
+    limit: usize,
+    generation: u64,
+) -> Result<EntityPage, String> {
+    let entities = scene_entities(scene_value(game_spec, scene)?);
+    let name_contains = filter.name_contains.as_ref().map(|n| n.to_lowercase());
+    let matching: Vec<usize> = entities
+        .iter()
+        .enumerate()
+        .filter(|(_, entity)| filter.matches(entity, name_contains.as_deref()))
+        .map(|(i, _)| i)
+        .collect();
+
+    let first = matching.partition_point(|&i| i < start);
+    let selected = &matching[first..(first + limit).min(matching.len())];
+    let next_cursor = matching
+        .get(first + limit)
+        .map(|&next| format!("{}:{}", generation, next));
+
+    Ok(EntityPage {
+        entities: selected.iter().map(|&i| entities[i].clone()).collect(),
+        next_cursor,
+        total: matching.len(),
+    })
+}
+
+/// Scene settings, entity counts and a spec outline without transferring any entity lists
+#[tauri::command]
+pub async fn get_scene_summary(
+    project_path: String,
+    scene: Option<String>,
+    state: State<'_, SpecCacheState>,
+) -> Result<SceneSummary, String> {
+    let (game_spec, _) = cached_spec(&state, &project_path)?;
+    summarize(&game_spec, scene.as_deref())
+}
+
+/// One page of a scene's entities, optionally filtered, in spec order
+#[tauri::command]
+pub async fn get_entities_page(
+    project_path: String,
+    scene: Option<String>,
+    filter: Option<EntityFilter>,
+    cursor: Option<String>,
+    limit: Option<usize>,
+    state: State<'_, SpecCacheState>,
+) -> Result<EntityPage, String> {
+    let (game_spec, generation) = cached_spec(&state, &project_path)?;
+    let start = cursor.as_deref().map(|c| parse_cursor(c, generation)).transpose()?.unwrap_or(0);
+    let limit = limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE);
+    page(
+        &game_spec,
+        scene.as_deref(),
+        &filter.unwrap_or_default(),
+        start,
+        limit,
+        generation,
+    )
+}
@@ -0,0 +1,292 @@
+use crate::assets::{AssetKind, AssetManifest};
+use crate::symlinks::{self, SymlinkPolicy};
+use crate::{paths, spec};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::State;
+
+/// Directories never worth indexing
+const SKIPPED_DIRS: &[&str] = &["node_modules", "target", "dist"];
+
+#[derive(Debug, Serialize, Clone)]
+pub struct IndexedEntity {
+    pub scene: Option<String>,
+    pub name: String,
+    pub tags: Vec<String>,
+    pub components: Vec<String>,
+    pub texture: Option<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct IndexedFile {
+    /// Project-relative path with forward slashes
+    pub path: String,
+    pub kind: AssetKind,
+    pub size: u64,
+}
+
+#[derive(Debug, Default)]
+pub struct SearchIndex {
+    pub project_path: PathBuf,
+    pub entities: Vec<IndexedEntity>,
+    pub files: Vec<IndexedFile>,
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SearchHitKind {
+    Entity,
+    File,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct SearchHit {
+    pub kind: SearchHitKind,
+    pub label: String,
+    pub scene: Option<String>,
+    pub path: Option<String>,
+    pub score: u32,
+}
+
+/// Filter for structured entity queries; every set field must match
+#[derive(Debug, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct EntityQuery {
+    pub name_contains: Option<String>,
+    pub tag: Option<String>,
+    pub component: Option<String>,
+    pub scene: Option<String>,
+}
+
+#[derive(Default)]
+pub struct SearchIndexState(pub Mutex<Option<SearchIndex>>);
+
+fn index_entities(game_spec: &Value) -> Vec<IndexedEntity> {
+    spec::entities_with_scene(game_spec)
+        .into_iter()
+        .map(|(scene, entity)| IndexedEntity {
+            scene: scene.map(str::to_string),
+            name: entity
+                .get("name")
+                .and_then(Value::as_str)
+                .unwrap_or_default()
+                .to_string(),
+            tags: entity
+                .get("tags")
+                .and_then(Value::as_array)
+                .map(|tags| tags.iter().filter_map(Value::as_str).map(str::to_string).collect())
+                .unwrap_or_default(),
+            components: entity
+                .get("components")
+                .and_then(Value::as_object)
+                .map(|c| c.keys().cloned().collect())
+                .unwrap_or_default(),
+            texture: entity
+                .pointer("/components/sprite/texture")
+                .and_then(Value::as_str)
+                .map(str::to_string),
+        })
+        .collect()
+}
+
+fn is_indexable(relative: &Path) -> bool {
+    !relative.components().any(|c| {
+        let part = c.as_os_str().to_string_lossy();
+        part.starts_with('.') || SKIPPED_DIRS.contains(&part.as_ref())
+    })
+}
+
+fn index_file(root: &Path, path: &Path) -> Option<IndexedFile> {
+    let relative = paths::relative_to(path, root)?;
+    if !is_indexable(&relative) {
+        return None;
+    }
+    let metadata = fs::metadata(paths::for_io(path)).ok()?;
+    if !metadata.is_file() {
+        return None;
+    }
+    Some(IndexedFile {
+        path: paths::to_spec_path(&relative),
+        kind: AssetKind::from_path(path),
+        size: metadata.len(),
+    })
+}
+
+fn walk(root: &Path, policy: SymlinkPolicy, files: &mut Vec<IndexedFile>) {
+    let mut descend = |dir: &Path| paths::relative_to(dir, root).is_some_and(|r| is_indexable(&r));
+    let mut visit = |path: &Path| files.extend(index_file(root, path));
+    symlinks::walk_files(root, policy, &mut descend, &mut visit);
+}
+
+impl SearchIndex {
+    /// Scan the project's game.json and files from scratch
+    pub fn build(project_path: &str) -> Self {
+        let root = paths::project_root(project_path);
+        let entities = spec::load_project_spec(project_path)
+            .map(|s| index_entities(&s))
+            .unwrap_or_default();
+
+        let policy = AssetManifest::load(project_path)
+            .map(|m| m.symlinks)
+            .unwrap_or_default();
+        let mut files = Vec::new();
+        walk(&root, policy, &mut files);
+        files.sort_by(|a, b| a.path.cmp(&b.path));
+
+        Self {
+            project_path: root,
+            entities,
+            files,
+        }
+    }
+
+    /// Apply a single file-system change reported by the watcher
+    pub fn update_path(&mut self, path: &Path) {
+        let Some(relative) = paths::relative_to(path, &self.project_path) else {
+            return;
+        };
+
+        if relative == Path::new("game.json") {
+            if let Ok(game_spec) = spec::load_project_spec(&self.project_path.to_string_lossy()) {
+                self.entities = index_entities(&game_spec);
+            }
+        }
+
+        let relative = paths::to_spec_path(&relative);
+        self.files.retain(|f| f.path != relative);
+        if let Some(file) = index_file(&self.project_path, path) {
+            self.files.push(file);
+            self.files.sort_by(|a, b| a.path.cmp(&b.path));
+        }
+    }
+
+    pub fn search(&self, query: &str, limit: usize) -> Vec<SearchHit> {
+        let query = query.to_lowercase();
+        let score = |text: &str| -> u32 {
+            let text = text.to_lowercase();
+            if text == query {
+                100
+            } else if text.starts_with(&query) {
+                75
+            } else if text.contains(&query) {
+                50
+            } else {
+                0
+            }
+        };
+
+        let mut hits: Vec<SearchHit> = Vec::new();
+
+        for entity in &self.entities {
+            let best = std::iter::once(score(&entity.name))
+                .chain(entity.tags.iter().map(|t| score(t) / 2))
+                .chain(entity.texture.iter().map(|t| score(t) / 2))
+                .max()
+                .unwrap_or(0);
+            if best > 0 {
+                hits.push(SearchHit {
+                    kind: SearchHitKind::Entity,
+                    label: entity.name.clone(),
+                    scene: entity.scene.clone(),
+                    path: None,
+                    score: best,
+                });
+            }
+        }
+
+        for file in &self.files {
+            let file_name = file.path.rsplit('/').next().unwrap_or(&file.path);
+            let best = score(file_name).max(score(&file.path) / 2);
+            if best > 0 {
+                hits.push(SearchHit {
+                    kind: SearchHitKind::File,
+                    label: file_name.to_string(),
+                    scene: None,
+                    path: Some(file.path.clone()),
+                    score: best,
+                });
+            }
+        }
+
+        hits.sort_by(|a, b| b.score.cmp(&a.score).then_with(|| a.label.cmp(&b.label)));
+        hits.truncate(limit);
+        hits
+    }
+
+    pub fn query_entities(&self, query: &EntityQuery) -> Vec<IndexedEntity> {
+        let name_contains = query.name_contains.as_ref().map(|n| n.to_lowercase());
+        self.entities
+            .iter()
+            .filter(|e| {
+                name_contains
+                    .as_ref()
+                    .is_none_or(|n| e.name.to_lowercase().contains(n))
+                    && query.tag.as_ref().is_none_or(|t| e.tags.contains(t))
+                    && query.component.as_ref().is_none_or(|c| e.components.contains(c))
+                    && query.scene.as_ref().is_none_or(|s| e.scene.as_ref() == Some(s))
+            })
+            .cloned()
+            .collect()
+    }
+}
+
+/// Run `f` against the index for `project_path`, building it first if needed
+fn with_index<T>(
+    state: &SearchIndexState,
+    project_path: &str,
+    f: impl FnOnce(&SearchIndex) -> T,
+) -> Result<T, String> {
+    let mut guard = state.0.lock().map_err(|e| format!("Lock error: {}", e))?;
+    let stale = guard
+        .as_ref()
+        .is_none_or(|index| index.project_path != Path::new(project_path));
+    if stale {
+        *guard = Some(SearchIndex::build(project_path));
+    }
+    Ok(f(guard.as_ref().expect("index was just built")))
+}
+
+/// Called by the file watcher for every changed path
+pub fn notify_changed(state: &SearchIndexState, path: &Path) {
+    if let Ok(mut guard) = state.0.lock() {
+        if let Some(index) = guard.as_mut() {
+            index.update_path(path);
+        }
+    }
+}
+
+/// Rebuild the search index for a project from disk
+#[tauri::command]
+pub async fn rebuild_search_index(
+    project_path: String,
+    state: State<'_, SearchIndexState>,
+) -> Result<usize, String> {
+    let index = SearchIndex::build(&project_path);
+    let count = index.entities.len() + index.files.len();
+    *state.0.lock().map_err(|e| format!("Lock error: {}", e))? = Some(index);
+    Ok(count)
+}
+
+/// Search entity names, tags, textures, and project files
+#[tauri::command]
+pub async fn search_project(
+    project_path: String,
+    query: String,
+    limit: Option<usize>,
+    state: State<'_, SearchIndexState>,
+) -> Result<Vec<SearchHit>, String> {
+    with_index(&state, &project_path, |index| index.search(&query, limit.unwrap_or(50)))
+}
+
+/// Query indexed entities by name, tag, component, or scene
+#[tauri::command]
+pub async fn query_entities(
+    project_path: String,
+    query: EntityQuery,
+    state: State<'_, SearchIndexState>,
+) -> Result<Vec<IndexedEntity>, String> {
+    with_index(&state, &project_path, |index| index.query_entities(&query))
+}
@@ -0,0 +1,65 @@
+use crate::spec;
+use serde_json::Value;
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+
+/// Read the gameplay seed from `config.seed`
+pub fn spec_seed(spec: &Value) -> Option<u32> {
+    spec.pointer("/config/seed")
+        .and_then(Value::as_u64)
+        .and_then(|s| u32::try_from(s).ok())
+}
+
+/// The seed a run plays with: `config.seed`, or 0 like the exported runtime when none is set
+pub fn get(spec: &Value) -> u32 {
+    spec_seed(spec).unwrap_or(0)
+}
+
+/// Store a seed in `config.seed`, creating the config object if needed
+pub fn set_spec_seed(spec: &mut Value, seed: u32) -> Result<(), String> {
+    let root = spec
+        .as_object_mut()
+        .ok_or("Game spec must be a JSON object")?;
+
+    let config = root
+        .entry("config")
+        .or_insert_with(|| Value::Object(Default::default()));
+
+    config
+        .as_object_mut()
+        .ok_or("config must be an object")?
+        .insert("seed".to_string(), Value::from(seed));
+
+    Ok(())
+}
+
+/// Fresh 32-bit seed; kept to u32 so the JS runtime's PRNG sees the exact value
+pub fn random_seed() -> u32 {
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u128(
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0),
+    );
+    hasher.finish() as u32
+}
+
+/// Get the project's gameplay seed, if one is configured
+#[tauri::command]
+pub async fn get_seed(project_path: String) -> Result<Option<u32>, String> {
+    let spec = spec::load_project_spec(&project_path)?;
+    Ok(spec_seed(&spec))
+}
+
+/// Pick a new gameplay seed (or set a specific one) and save it to game.json
+#[tauri::command]
+pub async fn reroll_seed(project_path: String, seed: Option<u32>) -> Result<u32, String> {
+    let mut spec = spec::load_project_spec(&project_path)?;
+    let seed = seed.unwrap_or_else(random_seed);
+
+    set_spec_seed(&mut spec, seed)?;
+    spec::save_project_spec(&project_path, &spec)?;
+
+    Ok(seed)
+}
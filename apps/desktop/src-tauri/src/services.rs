@@ -0,0 +1,189 @@
+use crate::project_settings::ProjectSettings;
+use crate::{cache, credentials};
+use reqwest::{Client, StatusCode, Url};
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+const TEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Score backend exported games report to (`services` in project.json). The API key ships inside
+/// every export, so it should be a submit-only key; it's kept in the keychain rather than
+/// project.json so it stays out of version control.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ServiceSettings {
+    /// REST endpoint scores are POSTed to as JSON
+    pub endpoint: String,
+}
+
+/// The project's service configuration as the editor shows it
+#[derive(Debug, Serialize)]
+pub struct ServiceConfig {
+    pub endpoint: String,
+    pub has_api_key: bool,
+}
+
+/// Outcome of `test_service_connection`
+#[derive(Debug, Serialize)]
+pub struct ServiceCheck {
+    pub ok: bool,
+    pub status: Option<u16>,
+    pub latency_ms: u64,
+    pub message: String,
+}
+
+fn keychain_account(project_path: &str) -> String {
+    format!("services-api-key:{}", cache::project_key(project_path))
+}
+
+/// Only https endpoints, except plain http on this machine for local testing
+fn parse_endpoint(endpoint: &str) -> Result<Url, String> {
+    let url = Url::parse(endpoint.trim()).map_err(|e| format!("Invalid service endpoint {}: {}", endpoint, e))?;
+    let local = matches!(url.host_str(), Some("localhost" | "127.0.0.1" | "[::1]"));
+    match url.scheme() {
+        "https" => Ok(url),
+        "http" if local => Ok(url),
+        _ => Err(format!("Service endpoint {} must use https", endpoint)),
+    }
+}
+
+fn configured(project_path: &str) -> Result<Option<(Url, Option<String>)>, String> {
+    let Some(services) = ProjectSettings::load(project_path)?.services else {
+        return Ok(None);
+    };
+    let url = parse_endpoint(&services.endpoint)?;
+    Ok(Some((url, credentials::load(&keychain_account(project_path))?)))
+}
+
+/// Appended to the runtime module of exports from projects with a score service. Each run's
+/// score is POSTed when the game resets; games can also call `promptplay.submitScore(score)`.
+/// Submissions are best-effort so an unreachable backend never interrupts play.
+pub fn export_script(project_path: &str, game_title: &str) -> Result<Option<String>, String> {
+    let Some((url, api_key)) = configured(project_path)? else {
+        return Ok(None);
+    };
+    let config = serde_json::json!({
+        "endpoint": url.as_str(),
+        "apiKey": api_key,
+        "game": game_title,
+    });
+    let config = serde_json::to_string(&config)
+        .map_err(|e| format!("Failed to serialize service config: {}", e))?
+        .replace("</", "<\\/");
+    Ok(Some(format!(
+        r#"
+
+        // Score service: runs are reported to the creator's backend
+        if (typeof runtime !== 'undefined') {{
+            const service = {config};
+            const submitScore = (score, extra) => {{
+                const headers = {{ 'Content-Type': 'application/json' }};
+                if (service.apiKey) headers['Authorization'] = 'Bearer ' + service.apiKey;
+                const body = Object.assign({{
+                    game: service.game,
+                    score,
+                    level: runtime.gameState.level,
+                    timeElapsed: runtime.gameState.timeElapsed,
+                    seed: typeof gameSeed !== 'undefined' ? gameSeed : undefined,
+                    submittedAt: new Date().toISOString()
+                }}, extra || {{}});
+                return fetch(service.endpoint, {{ method: 'POST', headers, body: JSON.stringify(body), keepalive: true }})
+                    .then(r => r.ok)
+                    .catch(() => false);
+            }};
+            window.promptplay = Object.assign(window.promptplay || {{}}, {{ submitScore }});
+            const baseReset = runtime.reset.bind(runtime);
+            runtime.reset = () => {{
+                if (runtime.gameState && runtime.gameState.score > 0) submitScore(runtime.gameState.score);
+                baseReset();
+            }};
+        }}"#,
+        config = config
+    )))
+}
+
+/// The project's score service, if one is configured
+#[tauri::command]
+pub async fn get_service_settings(project_path: String) -> Result<Option<ServiceConfig>, String> {
+    let Some(services) = ProjectSettings::load(&project_path)?.services else {
+        return Ok(None);
+    };
+    Ok(Some(ServiceConfig {
+        endpoint: services.endpoint,
+        has_api_key: credentials::load(&keychain_account(&project_path))?.is_some(),
+    }))
+}
+
+/// Set (or clear, with None) the score service exports report to. `api_key` None keeps the stored
+/// key; an empty string removes it.
+#[tauri::command]
+pub async fn set_service_settings(
+    project_path: String,
+    settings: Option<ServiceSettings>,
+    api_key: Option<String>,
+) -> Result<(), String> {
+    let mut project = ProjectSettings::load(&project_path)?;
+    let account = keychain_account(&project_path);
+    match settings {
+        Some(settings) => {
+            parse_endpoint(&settings.endpoint)?;
+            match api_key.as_deref().map(str::trim) {
+                Some("") => credentials::delete(&account)?,
+                Some(key) => credentials::store(&account, key)?,
+                None => {}
+            }
+            project.services = Some(ServiceSettings {
+                endpoint: settings.endpoint.trim().to_string(),
+            });
+        }
+        None => {
+            credentials::delete(&account)?;
+            project.services = None;
+        }
+    }
+    project.save(&project_path)
+}
+
+/// Reach the configured endpoint with the API key and report whether it accepts requests
+#[tauri::command]
+pub async fn test_service_connection(project_path: String) -> Result<ServiceCheck, String> {
+    let (url, api_key) = configured(&project_path)?.ok_or("This project has no score service configured")?;
+    let client = Client::builder()
+        .timeout(TEST_TIMEOUT)
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+    let mut request = client.get(url.clone());
+    if let Some(key) = &api_key {
+        request = request.bearer_auth(key);
+    }
+
+    let started = Instant::now();
+    let result = request.send().await;
+    let latency_ms = started.elapsed().as_millis() as u64;
+    let response = match result {
+        Ok(response) => response,
+        Err(e) => {
+            return Ok(ServiceCheck {
+                ok: false,
+                status: None,
+                latency_ms,
+                message: format!("Couldn't reach {}: {}", url, e),
+            });
+        }
+    };
+
+    let status = response.status();
+    // A POST-only endpoint answering GET with 405 is still reachable and authorized
+    let (ok, message) = match status {
+        s if s.is_success() || s == StatusCode::METHOD_NOT_ALLOWED => (true, "Connected".to_string()),
+        StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => (false, "The service rejected the API key".to_string()),
+        StatusCode::NOT_FOUND => (false, format!("Nothing answers at {}", url)),
+        s => (false, format!("The service answered {}", s)),
+    };
+    Ok(ServiceCheck {
+        ok,
+        status: Some(status.as_u16()),
+        latency_ms,
+        message,
+    })
+}
@@ -0,0 +1,80 @@
+use crate::ai_providers::ProviderSettings;
+use crate::backup::BackupSettings;
+use crate::notifications::NotificationSettings;
+use crate::permissions::{Operation, PermissionChoice, ProjectTrust};
+use crate::save_conflicts::ConflictPolicy;
+use crate::stt::SttSettings;
+use crate::sync::SyncSettings;
+use crate::tray::ContinuousExport;
+use crate::tts::TtsSettings;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use tauri::{AppHandle, Manager};
+
+/// App-wide settings file in the platform config directory
+const SETTINGS_FILE: &str = "settings.json";
+
+/// Editor preferences that live outside any one project
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AppSettings {
+    /// Last directory a picker was left in, keyed by purpose ("project", "images", "export")
+    pub last_directories: BTreeMap<String, String>,
+    /// Remembered answers to permission prompts
+    pub permissions: BTreeMap<Operation, PermissionChoice>,
+    /// Remembered answers to whether a project's own hooks may run, keyed by project path
+    pub trusted_projects: BTreeMap<String, ProjectTrust>,
+    /// Schedule and location of automatic project backups
+    pub backups: BackupSettings,
+    /// Remote storage per project, keyed by project path
+    pub sync: BTreeMap<String, SyncSettings>,
+    /// Speech-to-text provider for dictated prompts
+    pub stt: Option<SttSettings>,
+    /// Text-to-speech provider for dialogue previews
+    pub tts: Option<TtsSettings>,
+    /// Recent project folders from before they moved to recent-projects.json; read by
+    /// `projects` until that file exists
+    pub recent_projects: Vec<String>,
+    /// Last continuous export set up, which the tray can turn back on
+    pub continuous_export: Option<ContinuousExport>,
+    /// Per-category opt-outs for task notifications
+    pub notifications: NotificationSettings,
+    /// Global quick-capture shortcut; None uses the default
+    pub capture_shortcut: Option<String>,
+    /// Where caches live instead of the platform cache directory
+    pub cache_dir: Option<String>,
+    /// AI provider and model; None uses Anthropic's default model
+    pub ai_provider: Option<ProviderSettings>,
+    /// What saving does when game.json changed outside the editor
+    pub save_conflicts: ConflictPolicy,
+}
+
+fn settings_path(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_config_dir()
+        .map(|dir| dir.join(SETTINGS_FILE))
+        .map_err(|e| format!("Failed to resolve config directory: {}", e))
+}
+
+impl AppSettings {
+    /// Saved settings, or the defaults when none exist or the file is unreadable
+    pub fn load(app: &AppHandle) -> Self {
+        settings_path(app)
+            .ok()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, app: &AppHandle) -> Result<(), String> {
+        let path = settings_path(app)?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| format!("Failed to create config directory: {}", e))?;
+        }
+        let content = serde_json::to_string_pretty(self)
+            .map_err(|e| format!("Failed to serialize settings: {}", e))?;
+        fs::write(&path, content).map_err(|e| format!("Failed to write settings: {}", e))
+    }
+}
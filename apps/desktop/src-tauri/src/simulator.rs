@@ -0,0 +1,432 @@
+use crate::physics_materials::{self, PhysicsMaterial, SurfaceProperties};
+use crate::{collision_shapes, hud, seed, world};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::collections::{BTreeMap, HashSet};
+
+/// Fixed simulation step, matching a 60 fps runtime frame
+pub const FRAME_SECONDS: f64 = 1.0 / 60.0;
+/// Spec gravity is in g; one g in px/s²
+const GRAVITY_SCALE: f64 = 980.0;
+const SCORE_PER_COLLECTIBLE: f64 = 10.0;
+const ENEMY_CONTACT_DAMAGE: f64 = 10.0;
+const COLLECTIBLE_TAGS: &[&str] = &["collectible", "coin", "pickup"];
+/// Friction at which a released player stops within a frame; the default 0.1 does, ice slides
+const FRICTION_STOP: f64 = 0.1;
+/// Impacts slower than this in px/s settle instead of bouncing forever
+const MIN_BOUNCE_SPEED: f64 = 30.0;
+/// How far below a one-way platform's top a body may have been and still land on it
+const ONE_WAY_TOLERANCE: f64 = 2.0;
+/// Surfaces whose normal points at least this much upward are floors; slopes up to ~50° are walkable
+const FLOOR_NORMAL_Y: f64 = 0.64;
+
+/// Keys held for a span of simulated time
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct InputEvent {
+    /// Seconds from the start of the run
+    #[serde(default)]
+    pub at: f64,
+    /// Key codes as reported by the browser, e.g. "ArrowRight", "Space"
+    pub press: Vec<String>,
+    /// How long the keys stay down; defaults to a single frame
+    #[serde(rename = "for")]
+    pub duration: Option<f64>,
+}
+
+#[derive(Debug, Clone)]
+struct Body {
+    name: String,
+    tags: Vec<String>,
+    x: f64,
+    y: f64,
+    vx: f64,
+    vy: f64,
+    width: f64,
+    height: f64,
+    start_x: f64,
+    has_input: bool,
+    move_speed: f64,
+    jump_force: f64,
+    dynamic: bool,
+    solid: bool,
+    grounded: bool,
+    /// Friction of the surface the body stood on last frame
+    ground_friction: f64,
+    surface: SurfaceProperties,
+    /// Polygon or slope outline relative to the position; boxes and circles use width/height
+    outline: Option<Vec<(f64, f64)>>,
+    health: Option<(f64, f64)>,
+    ai: Option<(String, f64, f64, f64)>,
+    patrol_direction: f64,
+    collected: bool,
+}
+
+impl Body {
+    fn from_entity(entity: &Value, materials: &BTreeMap<String, PhysicsMaterial>) -> Self {
+        let number = |pointer: &str, default: f64| {
+            entity.pointer(pointer).and_then(Value::as_f64).unwrap_or(default)
+        };
+        let has = |component: &str| entity.pointer(&format!("/components/{}", component)).is_some();
+        let tags: Vec<String> = entity
+            .get("tags")
+            .and_then(Value::as_array)
+            .map(|t| t.iter().filter_map(Value::as_str).map(str::to_string).collect())
+            .unwrap_or_default();
+
+        let sprite_width = number("/components/sprite/width", 32.0);
+        let sprite_height = number("/components/sprite/height", 32.0);
+        let x = number("/components/transform/x", 0.0);
+        let collider = entity.pointer("/components/collider").unwrap_or(&Value::Null);
+        let outline = collision_shapes::outline(collider, sprite_width, sprite_height);
+        // Outlines are also tested as boxes first, so their bounding box is the broad phase
+        let (width, height) = match &outline {
+            Some(vertices) => collision_shapes::extent(vertices),
+            None => (
+                number("/components/collider/width", sprite_width),
+                number("/components/collider/height", sprite_height),
+            ),
+        };
+
+        Self {
+            name: entity.get("name").and_then(Value::as_str).unwrap_or_default().to_string(),
+            x,
+            y: number("/components/transform/y", 0.0),
+            vx: number("/components/velocity/vx", 0.0),
+            vy: number("/components/velocity/vy", 0.0),
+            width,
+            height,
+            start_x: x,
+            has_input: has("input"),
+            move_speed: number("/components/input/moveSpeed", 200.0),
+            jump_force: number("/components/input/jumpForce", -400.0),
+            // Same rule as the runtime: anything without velocity or input is static
+            dynamic: has("velocity") || has("input"),
+            solid: has("collider")
+                && entity.pointer("/components/collider/isSensor") != Some(&Value::Bool(true)),
+            grounded: false,
+            ground_friction: physics_materials::DEFAULT_FRICTION,
+            surface: physics_materials::resolve(materials, collider),
+            outline,
+            health: entity.pointer("/components/health").map(|_| {
+                (
+                    number("/components/health/current", 100.0),
+                    number("/components/health/max", 100.0),
+                )
+            }),
+            ai: entity.pointer("/components/aiBehavior").map(|_| {
+                (
+                    entity
+                        .pointer("/components/aiBehavior/type")
+                        .and_then(Value::as_str)
+                        .unwrap_or("idle")
+                        .to_string(),
+                    number("/components/aiBehavior/speed", 60.0),
+                    number("/components/aiBehavior/detectionRadius", 150.0),
+                    number("/components/aiBehavior/patrolRange", 100.0),
+                )
+            }),
+            patrol_direction: 1.0,
+            collected: false,
+            tags,
+        }
+    }
+
+    fn has_tag(&self, tags: &[&str]) -> bool {
+        self.tags.iter().any(|t| tags.contains(&t.as_str()))
+    }
+
+    fn overlaps(&self, other: &Body) -> bool {
+        (self.x - other.x).abs() * 2.0 < self.width + other.width
+            && (self.y - other.y).abs() * 2.0 < self.height + other.height
+    }
+}
+
+/// Deterministic, renderer-free approximation of the runtime for tests and tooling
+pub struct Simulation {
+    bodies: Vec<Body>,
+    gravity: (f64, f64),
+    bounds: world::WorldBounds,
+    game_state: Map<String, Value>,
+    contacts: HashSet<(usize, usize)>,
+    /// Seconds per step; FRAME_SECONDS unless overridden for frame-rate audits
+    frame_seconds: f64,
+    /// Gameplay seed from the spec, the same one the exported runtime seeds its PRNG with
+    pub seed: u32,
+    pub frame: u64,
+}
+
+impl Simulation {
+    /// Set up the entities of one scene (None for the top-level list)
+    pub fn new(game_spec: &Value, scene: Option<&str>) -> Self {
+        let materials = physics_materials::materials(game_spec);
+        let bodies = crate::thumbnails::scene_entities(game_spec, scene)
+            .into_iter()
+            .map(|entity| Body::from_entity(entity, &materials))
+            .collect();
+
+        let gravity = |axis: &str| {
+            game_spec
+                .pointer(&format!("/config/gravity/{}", axis))
+                .and_then(Value::as_f64)
+                .unwrap_or(0.0)
+                * GRAVITY_SCALE
+        };
+
+        // Defaults mirror the runtime's resetGameState
+        let mut game_state = json!({
+            "score": 0, "highScore": 0, "lives": 3, "maxLives": 3, "level": 1,
+            "timeRemaining": 0, "timeElapsed": 0, "combo": 0, "multiplier": 1
+        })
+        .as_object()
+        .cloned()
+        .unwrap_or_default();
+        if let Some(overrides) = game_spec.get("gameState").and_then(Value::as_object) {
+            game_state.extend(overrides.clone());
+        }
+
+        Self {
+            bodies,
+            gravity: (gravity("x"), gravity("y")),
+            bounds: world::world_bounds(game_spec),
+            game_state,
+            contacts: HashSet::new(),
+            frame_seconds: FRAME_SECONDS,
+            seed: seed::get(game_spec),
+            frame: 0,
+        }
+    }
+
+    /// Step at `fps` instead of 60; well-behaved specs play out the same at any rate. Rates that
+    /// aren't a positive finite number, or that would make a step zero-length, are ignored.
+    pub fn with_frame_rate(mut self, fps: f64) -> Self {
+        let frame_seconds = 1.0 / fps;
+        if fps.is_finite() && fps > 0.0 && frame_seconds > 0.0 {
+            self.frame_seconds = frame_seconds;
+        }
+        self
+    }
+
+    fn add_to_state(&mut self, field: &str, delta: f64) {
+        let current = self.game_state.get(field).and_then(Value::as_f64).unwrap_or(0.0);
+        self.game_state.insert(field.to_string(), Value::from(current + delta));
+    }
+
+    /// Advance one frame with the given keys held
+    pub fn step(&mut self, keys: &HashSet<String>) {
+        let dt = self.frame_seconds;
+        let held = |codes: &[&str]| codes.iter().any(|c| keys.contains(*c));
+        let player_x = self.bodies.iter().find(|b| b.has_input).map(|b| b.x);
+
+        for body in self.bodies.iter_mut().filter(|b| b.dynamic && !b.collected) {
+            if body.has_input {
+                // Released players keep sliding on low-friction ground, like ice
+                let friction = body.ground_friction.min(body.surface.friction);
+                body.vx = if body.grounded && friction < FRICTION_STOP {
+                    // Decay per 60 fps frame, scaled so other step sizes slide the same distance
+                    body.vx * (1.0 - (friction / FRICTION_STOP)).powf(dt / FRAME_SECONDS)
+                } else {
+                    0.0
+                };
+                if held(&["ArrowLeft", "KeyA"]) {
+                    body.vx = -body.move_speed;
+                }
+                if held(&["ArrowRight", "KeyD"]) {
+                    body.vx = body.move_speed;
+                }
+                if held(&["Space", "ArrowUp", "KeyW"]) && body.grounded {
+                    body.vy = body.jump_force;
+                    body.grounded = false;
+                }
+            }
+
+            if let Some((kind, speed, radius, range)) = &body.ai {
+                match kind.as_str() {
+                    "patrol" => {
+                        if (body.x - body.start_x).abs() >= *range {
+                            body.patrol_direction = -(body.x - body.start_x).signum();
+                        }
+                        body.vx = speed * body.patrol_direction;
+                    }
+                    "chase" => {
+                        body.vx = match player_x {
+                            Some(px) if (px - body.x).abs() <= *radius => speed * (px - body.x).signum(),
+                            _ => 0.0,
+                        };
+                    }
+                    _ => body.vx = 0.0,
+                }
+            }
+
+            body.vx += self.gravity.0 * dt;
+            body.vy += self.gravity.1 * dt;
+            body.x += body.vx * dt;
+            body.y += body.vy * dt;
+            body.grounded = false;
+        }
+
+        self.resolve_collisions(dt);
+        self.handle_contacts();
+
+        self.frame += 1;
+        self.add_to_state("timeElapsed", dt);
+    }
+
+    /// Push dynamic bodies out of static solids along the axis of least penetration, bouncing by
+    /// the larger restitution of the pair. Polygon and slope solids use a separating-axis test;
+    /// one-way solids only catch bodies falling onto them.
+    fn resolve_collisions(&mut self, dt: f64) {
+        let solids: Vec<Body> = self
+            .bodies
+            .iter()
+            .filter(|b| !b.dynamic && b.solid && !b.collected)
+            .cloned()
+            .collect();
+
+        for body in self.bodies.iter_mut().filter(|b| b.dynamic && b.solid && !b.collected) {
+            for solid in &solids {
+                if !body.overlaps(solid) {
+                    continue;
+                }
+                let overlap_x = (body.width + solid.width) / 2.0 - (body.x - solid.x).abs();
+                let overlap_y = (body.height + solid.height) / 2.0 - (body.y - solid.y).abs();
+                // Same combine rule as Matter.js: the bouncier surface wins
+                let restitution = body.surface.restitution.max(solid.surface.restitution);
+                let bounce = |speed: f64| if speed.abs() * restitution < MIN_BOUNCE_SPEED { 0.0 } else { -speed * restitution };
+                if let Some(outline) = &solid.outline {
+                    let polygon: Vec<(f64, f64)> = outline.iter().map(|(x, y)| (x + solid.x, y + solid.y)).collect();
+                    let half = (body.width / 2.0, body.height / 2.0);
+                    let Some(((nx, ny), depth)) = collision_shapes::separate_box((body.x, body.y), half, &polygon) else {
+                        continue;
+                    };
+                    if -ny >= FLOOR_NORMAL_Y {
+                        // Lift straight up so bodies stand on slopes instead of sliding down them
+                        body.y -= depth / -ny;
+                        body.grounded = true;
+                        body.ground_friction = solid.surface.friction;
+                        if body.vy > 0.0 {
+                            body.vy = bounce(body.vy);
+                        }
+                    } else {
+                        body.x += nx * depth;
+                        body.y += ny * depth;
+                        // Remove (or reflect) the velocity going into the surface
+                        let into = body.vx * nx + body.vy * ny;
+                        if into < 0.0 {
+                            let factor = if into.abs() * restitution < MIN_BOUNCE_SPEED { 1.0 } else { 1.0 + restitution };
+                            body.vx -= factor * into * nx;
+                            body.vy -= factor * into * ny;
+                        }
+                    }
+                } else if solid.surface.one_way {
+                    let previous_bottom = body.y - body.vy * dt + body.height / 2.0;
+                    if body.vy < 0.0 || previous_bottom > solid.y - solid.height / 2.0 + ONE_WAY_TOLERANCE {
+                        continue;
+                    }
+                    body.y -= overlap_y;
+                    body.grounded = true;
+                    body.ground_friction = solid.surface.friction;
+                    body.vy = bounce(body.vy);
+                } else if overlap_y <= overlap_x {
+                    if body.y < solid.y {
+                        body.y -= overlap_y;
+                        body.grounded = true;
+                        body.ground_friction = solid.surface.friction;
+                    } else {
+                        body.y += overlap_y;
+                    }
+                    body.vy = bounce(body.vy);
+                } else {
+                    body.x += if body.x < solid.x { -overlap_x } else { overlap_x };
+                    body.vx = bounce(body.vx);
+                }
+            }
+        }
+    }
+
+    /// Collectibles and enemy hits for the player, triggered when contact starts
+    fn handle_contacts(&mut self) {
+        let Some(player) = self.bodies.iter().position(|b| b.has_input) else {
+            return;
+        };
+
+        let mut current = HashSet::new();
+        for other in 0..self.bodies.len() {
+            if other != player
+                && !self.bodies[other].collected
+                && self.bodies[player].overlaps(&self.bodies[other])
+            {
+                current.insert((player, other));
+            }
+        }
+
+        let started: Vec<usize> = current
+            .difference(&self.contacts)
+            .map(|&(_, other)| other)
+            .collect();
+        for other in started {
+            if self.bodies[other].has_tag(COLLECTIBLE_TAGS) {
+                self.bodies[other].collected = true;
+                self.add_to_state("score", SCORE_PER_COLLECTIBLE);
+            } else if self.bodies[other].has_tag(&["enemy"]) {
+                match self.bodies[player].health.as_mut() {
+                    Some((current, _)) => *current = (*current - ENEMY_CONTACT_DAMAGE).max(0.0),
+                    None => self.add_to_state("lives", -1.0),
+                }
+            }
+        }
+        self.contacts = current;
+    }
+
+    /// Run for `seconds` of simulated time, holding keys according to `inputs`
+    pub fn run(&mut self, seconds: f64, inputs: &[InputEvent]) {
+        let frames = (seconds / self.frame_seconds).round() as u64;
+        for _ in 0..frames {
+            let now = self.frame as f64 * self.frame_seconds;
+            let keys: HashSet<String> = inputs
+                .iter()
+                .filter(|input| {
+                    let end = input.at + input.duration.unwrap_or(self.frame_seconds);
+                    now >= input.at && now < end
+                })
+                .flat_map(|input| input.press.iter().cloned())
+                .collect();
+            self.step(&keys);
+        }
+    }
+
+    /// Read a value with the HUD binding syntax: `gameState.<field>` or `entity.<name>.<property>`.
+    /// Entity properties are x, y, vx, vy, grounded, collected, health, inBounds.
+    pub fn read(&self, target: &str) -> Result<Value, String> {
+        if let Some(field) = target.strip_prefix("gameState.") {
+            if !hud::GAME_STATE_FIELDS.contains(&field) && !self.game_state.contains_key(field) {
+                return Err(format!("Unknown game state field '{}'", field));
+            }
+            return Ok(self.game_state.get(field).cloned().unwrap_or(Value::Null));
+        }
+
+        let rest = target
+            .strip_prefix("entity.")
+            .ok_or_else(|| format!("Target '{}' must be 'gameState.<field>' or 'entity.<name>.<property>'", target))?;
+        let (name, property) = rest
+            .rsplit_once('.')
+            .ok_or_else(|| format!("Target '{}' is missing an entity property", target))?;
+        let body = self
+            .bodies
+            .iter()
+            .find(|b| b.name == name)
+            .ok_or_else(|| format!("Unknown entity '{}'", name))?;
+
+        Ok(match property {
+            "x" => Value::from(body.x),
+            "y" => Value::from(body.y),
+            "vx" => Value::from(body.vx),
+            "vy" => Value::from(body.vy),
+            "grounded" => Value::from(body.grounded),
+            "collected" => Value::from(body.collected),
+            "health" => body.health.map(|(current, _)| Value::from(current)).unwrap_or(Value::Null),
+            "inBounds" => Value::from(self.bounds.contains(body.x, body.y)),
+            other => return Err(format!("Unknown entity property '{}'", other)),
+        })
+    }
+}
@@ -0,0 +1,277 @@
+use crate::{compression, encoding, encryption, entity_ids, json_guard, paths};
+use crate::formatter::{self, FormatConfig, SpecStorage};
+use crate::rounding::{self, RoundingPolicy};
+use memmap2::Mmap;
+use serde::Serialize;
+use serde_json::Value;
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+
+/// Iterate every entity in the spec: the legacy flat list plus each scene's entities
+pub fn all_entities(spec: &Value) -> impl Iterator<Item = &Value> {
+    let flat = spec
+        .get("entities")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten();
+
+    let scenes = spec
+        .get("scenes")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|scene| scene.get("entities").and_then(Value::as_array))
+        .flatten();
+
+    flat.chain(scenes)
+}
+
+/// Find an entity by name anywhere in the spec
+pub fn find_entity<'a>(spec: &'a Value, name: &str) -> Option<&'a Value> {
+    all_entities(spec).find(|e| e.get("name").and_then(Value::as_str) == Some(name))
+}
+
+/// Whether the spec declares a scene with this id
+pub fn scene_exists(spec: &Value, scene_id: &str) -> bool {
+    spec.get("scenes")
+        .and_then(Value::as_array)
+        .is_some_and(|scenes| {
+            scenes
+                .iter()
+                .any(|s| s.get("id").and_then(Value::as_str) == Some(scene_id))
+        })
+}
+
+/// Find an entity by its stable id anywhere in the spec, mutably
+pub fn find_entity_by_id_mut<'a>(spec: &'a mut Value, id: &str) -> Option<&'a mut Value> {
+    let is_match = |e: &&mut Value| e.get("id").and_then(Value::as_str) == Some(id);
+
+    for (key, value) in spec.as_object_mut()?.iter_mut() {
+        match key.as_str() {
+            "entities" => {
+                if let Some(found) = value.as_array_mut().and_then(|l| l.iter_mut().find(is_match)) {
+                    return Some(found);
+                }
+            }
+            "scenes" => {
+                for scene in value.as_array_mut().into_iter().flatten() {
+                    if let Some(found) = scene
+                        .get_mut("entities")
+                        .and_then(Value::as_array_mut)
+                        .and_then(|l| l.iter_mut().find(is_match))
+                    {
+                        return Some(found);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    None
+}
+
+/// Specs above this size are refused instead of risking an out-of-memory parse
+pub const MAX_SPEC_BYTES: u64 = 256 * 1024 * 1024;
+/// Files at least this large are memory-mapped rather than read into a buffer
+const MMAP_THRESHOLD_BYTES: u64 = 4 * 1024 * 1024;
+
+/// Turn a serde error into a message with line, column and the offending line's text
+fn describe_parse_error(bytes: &[u8], label: &str, e: &serde_json::Error) -> String {
+    if e.is_eof() {
+        return format!("Failed to parse {}: file ends unexpectedly ({})", label, e);
+    }
+
+    let line = bytes
+        .split(|b| *b == b'\n')
+        .nth(e.line().saturating_sub(1))
+        .map(|l| String::from_utf8_lossy(l).trim_end().to_string())
+        .unwrap_or_default();
+    // Generated tilemaps put huge arrays on one line; show only the region around the error
+    let start = e.column().saturating_sub(60);
+    let snippet: String = line.chars().skip(start).take(120).collect();
+
+    format!(
+        "Failed to parse {} at line {}, column {}: {}\n  {}",
+        label,
+        e.line(),
+        e.column(),
+        e,
+        snippet
+    )
+}
+
+/// Parse spec JSON from raw bytes with a located error message; BOMs and UTF-16 are transcoded
+/// first, and the [`json_guard::SPEC_LIMITS`] checks run before serde sees the bytes
+pub fn parse_spec_bytes(bytes: &[u8], label: &str) -> Result<Value, String> {
+    let bytes = encoding::to_utf8(bytes);
+    json_guard::check(&bytes, label, &json_guard::SPEC_LIMITS)?;
+    serde_json::from_slice(&bytes).map_err(|e| describe_parse_error(&bytes, label, &e))
+}
+
+/// Refuse spec files over [`MAX_SPEC_BYTES`], returning the file size otherwise
+pub fn check_spec_size(path: &Path) -> Result<u64, String> {
+    let size = fs::metadata(paths::for_io(path))
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?
+        .len();
+    if size > MAX_SPEC_BYTES {
+        return Err(format!(
+            "{} is {:.1} MB, over the {} MB limit; split large tilemaps into separate scenes",
+            path.display(),
+            size as f64 / (1024.0 * 1024.0),
+            MAX_SPEC_BYTES / (1024 * 1024)
+        ));
+    }
+    Ok(size)
+}
+
+/// Read and parse a spec file, memory-mapping large ones so they aren't copied into a String.
+/// Encrypted and zstd-compressed files are unwrapped first.
+pub fn read_spec_file(path: &Path) -> Result<Value, String> {
+    let size = check_spec_size(path)?;
+    let label = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.display().to_string());
+
+    if size < MMAP_THRESHOLD_BYTES {
+        let bytes = fs::read(paths::for_io(path)).map_err(|e| format!("Failed to read {}: {}", label, e))?;
+        return parse_spec_bytes(&compression::decompress(encryption::decrypt(path, bytes)?)?, &label);
+    }
+
+    let file = File::open(paths::for_io(path)).map_err(|e| format!("Failed to open {}: {}", label, e))?;
+    // SAFETY: the map is read-only and dropped before returning. Another process truncating
+    // the file mid-parse could fault, the same exposure any mmap-based reader accepts.
+    let map = unsafe { Mmap::map(&file) }.map_err(|e| format!("Failed to map {}: {}", label, e))?;
+    if encryption::is_encrypted(&map) || compression::is_compressed(&map) {
+        return parse_spec_bytes(&compression::decompress(encryption::decrypt(path, map.to_vec())?)?, &label);
+    }
+    parse_spec_bytes(&map, &label)
+}
+
+/// Read and parse a project's game.json
+pub fn load_project_spec(project_path: &str) -> Result<Value, String> {
+    let game_json_path = PathBuf::from(project_path).join("game.json");
+    if !game_json_path.exists() {
+        return Err(format!("game.json not found in {}", project_path));
+    }
+
+    let mut spec = read_spec_file(&game_json_path)?;
+    entity_ids::migrate(&mut spec);
+    Ok(spec)
+}
+
+/// Visit every entity mutably: the legacy flat list plus each scene's entities
+pub fn for_each_entity_mut<F: FnMut(&mut Value)>(spec: &mut Value, mut f: F) {
+    if let Some(entities) = spec.get_mut("entities").and_then(Value::as_array_mut) {
+        entities.iter_mut().for_each(&mut f);
+    }
+
+    if let Some(scenes) = spec.get_mut("scenes").and_then(Value::as_array_mut) {
+        for scene in scenes {
+            if let Some(entities) = scene.get_mut("entities").and_then(Value::as_array_mut) {
+                entities.iter_mut().for_each(&mut f);
+            }
+        }
+    }
+}
+
+fn stored_bytes(storage: SpecStorage, content: Vec<u8>) -> Result<Vec<u8>, String> {
+    match storage {
+        SpecStorage::Json => Ok(content),
+        SpecStorage::Zstd => compression::compress(&content),
+    }
+}
+
+/// game.json text as the project stores it: zstd-compressed when its format config says so.
+/// For writers that have the spec as text already and must not reformat it.
+pub fn encode_for_storage(project_path: &str, content: Vec<u8>) -> Result<Vec<u8>, String> {
+    stored_bytes(FormatConfig::load(project_path)?.storage, content)
+}
+
+/// Write a spec back to a project's game.json in the project's format, rounding style and storage
+pub fn save_project_spec(project_path: &str, spec: &Value) -> Result<(), String> {
+    let game_json_path = PathBuf::from(project_path).join("game.json");
+
+    let config = FormatConfig::load(project_path)?;
+    let content = if config.rounding == RoundingPolicy::None {
+        formatter::format_spec(spec, &config)
+    } else {
+        let mut rounded = spec.clone();
+        rounding::round_spec(&mut rounded, config.rounding);
+        formatter::format_spec(&rounded, &config)
+    };
+
+    let content = stored_bytes(config.storage, content.into_bytes())?;
+    let bytes = encryption::encrypt_for(&game_json_path, &content)?;
+    fs::write(&game_json_path, bytes)
+        .map_err(|e| format!("Failed to write game.json in {}: {}", project_path, e))
+}
+
+/// Component fields that hold an asset path
+pub const ASSET_FIELDS: &[(&str, &str)] = &[
+    ("sprite", "texture"),
+    ("animation", "spriteSheet"),
+    ("audio", "source"),
+];
+
+/// A spec location that points at an asset file
+#[derive(Debug, Serialize, Clone)]
+pub struct AssetReference {
+    pub scene: Option<String>,
+    pub entity: String,
+    pub component: String,
+    pub field: String,
+    pub asset: String,
+}
+
+/// Iterate every entity together with the id of the scene it belongs to (None for the flat list)
+pub fn entities_with_scene(spec: &Value) -> Vec<(Option<&str>, &Value)> {
+    let mut result: Vec<(Option<&str>, &Value)> = spec
+        .get("entities")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .map(|e| (None, e))
+        .collect();
+
+    for scene in spec.get("scenes").and_then(Value::as_array).into_iter().flatten() {
+        let scene_id = scene.get("id").and_then(Value::as_str);
+        for entity in scene.get("entities").and_then(Value::as_array).into_iter().flatten() {
+            result.push((scene_id, entity));
+        }
+    }
+
+    result
+}
+
+/// Collect every asset path referenced by entity components
+pub fn asset_references(spec: &Value) -> Vec<AssetReference> {
+    let mut references = Vec::new();
+
+    for (scene, entity) in entities_with_scene(spec) {
+        let name = entity.get("name").and_then(Value::as_str).unwrap_or_default();
+        for (component, field) in ASSET_FIELDS {
+            let Some(asset) = entity
+                .get("components")
+                .and_then(|c| c.get(component))
+                .and_then(|c| c.get(field))
+                .and_then(Value::as_str)
+            else {
+                continue;
+            };
+            if asset.is_empty() {
+                continue;
+            }
+            references.push(AssetReference {
+                scene: scene.map(str::to_string),
+                entity: name.to_string(),
+                component: component.to_string(),
+                field: field.to_string(),
+                asset: asset.to_string(),
+            });
+        }
+    }
+
+    references
+}
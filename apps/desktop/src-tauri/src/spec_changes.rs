@@ -0,0 +1,144 @@
+use crate::spec;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Event sent to the editor with the entities a game.json write touched
+pub const SPEC_CHANGED_EVENT: &str = "spec-changed";
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum EntityChangeKind {
+    Added,
+    Removed,
+    Modified,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct EntityChange {
+    pub id: String,
+    /// None for the top-level entity list; an entity moved between scenes is removed from one and
+    /// added to the other
+    pub scene: Option<String>,
+    pub kind: EntityChangeKind,
+    /// Components added, removed or edited; every component for added and removed entities
+    pub components: Vec<String>,
+    /// Entity keys outside `components` that changed, e.g. name or tags
+    pub fields: Vec<String>,
+}
+
+/// What changed between two versions of a spec, so the canvas repaints only those entities
+#[derive(Debug, Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct SpecChangeSet {
+    pub entities: Vec<EntityChange>,
+    /// Changed parts outside entity lists: top-level keys like "config" or "ui", and
+    /// "scenes/<id>" for a scene's own settings
+    pub sections: Vec<String>,
+    /// Set when the change can't be described per entity (entities without ids, scenes added or
+    /// removed); the editor should reload the whole spec
+    pub full_reload: bool,
+}
+
+impl SpecChangeSet {
+    pub fn is_empty(&self) -> bool {
+        self.entities.is_empty() && self.sections.is_empty() && !self.full_reload
+    }
+}
+
+type EntityKey<'a> = (Option<&'a str>, &'a str);
+
+/// Entities keyed by scene and id; None when one lacks an id
+fn keyed_entities(spec: &Value) -> Option<BTreeMap<EntityKey<'_>, &Value>> {
+    spec::entities_with_scene(spec)
+        .into_iter()
+        .map(|(scene, entity)| Some(((scene, entity.get("id")?.as_str()?), entity)))
+        .collect()
+}
+
+/// Keys whose values differ between two objects, including keys only one of them has;
+/// a missing or non-object value counts as an empty object
+fn changed_keys(before: Option<&Value>, after: Option<&Value>, skip: &[&str]) -> Vec<String> {
+    let keys = |value: Option<&Value>| value.and_then(Value::as_object).into_iter().flat_map(Map::keys);
+    keys(before)
+        .chain(keys(after))
+        .filter(|key| !skip.contains(&key.as_str()))
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .filter(|key| before.and_then(|v| v.get(key.as_str())) != after.and_then(|v| v.get(key.as_str())))
+        .cloned()
+        .collect()
+}
+
+fn component_names(entity: &Value) -> Vec<String> {
+    changed_keys(None, entity.get("components"), &[])
+}
+
+fn scene_settings(spec: &Value) -> BTreeMap<&str, &Value> {
+    spec.get("scenes")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|scene| Some((scene.get("id")?.as_str()?, scene)))
+        .collect()
+}
+
+/// Describe the edits that turn `before` into `after`
+pub fn diff(before: &Value, after: &Value) -> SpecChangeSet {
+    let mut changes = SpecChangeSet {
+        sections: changed_keys(Some(before), Some(after), &["entities", "scenes"]),
+        ..Default::default()
+    };
+
+    let (scenes_before, scenes_after) = (scene_settings(before), scene_settings(after));
+    if !scenes_before.keys().eq(scenes_after.keys()) {
+        changes.full_reload = true;
+    }
+    for (id, scene) in &scenes_after {
+        let Some(previous) = scenes_before.get(id) else {
+            continue;
+        };
+        if !changed_keys(Some(*previous), Some(*scene), &["entities"]).is_empty() {
+            changes.sections.push(format!("scenes/{}", id));
+        }
+    }
+
+    let (Some(entities_before), Some(entities_after)) = (keyed_entities(before), keyed_entities(after)) else {
+        changes.full_reload = true;
+        return changes;
+    };
+
+    let change = |(scene, id): &EntityKey, kind, components, fields| EntityChange {
+        id: id.to_string(),
+        scene: scene.map(str::to_string),
+        kind,
+        components,
+        fields,
+    };
+    for (key, entity) in &entities_before {
+        if !entities_after.contains_key(key) {
+            changes.entities.push(change(key, EntityChangeKind::Removed, component_names(entity), Vec::new()));
+        }
+    }
+    for (key, entity) in &entities_after {
+        match entities_before.get(key) {
+            None => changes.entities.push(change(key, EntityChangeKind::Added, component_names(entity), Vec::new())),
+            Some(previous) if previous != entity => {
+                let components = changed_keys(previous.get("components"), entity.get("components"), &[]);
+                let fields = changed_keys(Some(*previous), Some(*entity), &["components"]);
+                changes.entities.push(change(key, EntityChangeKind::Modified, components, fields));
+            }
+            Some(_) => {}
+        }
+    }
+
+    changes
+}
+
+/// Per-entity changes between two in-editor specs, for edits (like an AI patch) applied before
+/// they reach game.json
+#[tauri::command]
+pub async fn diff_spec_changes(before: Value, after: Value) -> Result<SpecChangeSet, String> {
+    Ok(diff(&before, &after))
+}
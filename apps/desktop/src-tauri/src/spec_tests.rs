@@ -0,0 +1,254 @@
+use crate::simulator::{InputEvent, Simulation};
+use crate::spec;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Project folder holding YAML spec test cases
+pub const TESTS_DIR: &str = "tests";
+/// Longest simulated run a case may ask for, ten minutes of play
+pub const MAX_TEST_SECONDS: f64 = 600.0;
+
+/// Assertion on a simulated value; every set comparison must hold
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Expectation {
+    /// `gameState.<field>` or `entity.<name>.<property>`
+    pub target: String,
+    pub eq: Option<Value>,
+    pub gt: Option<f64>,
+    pub lt: Option<f64>,
+    pub gte: Option<f64>,
+    pub lte: Option<f64>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SpecTestCase {
+    pub name: String,
+    /// Fixture spec relative to tests/; defaults to the project's game.json
+    pub spec: Option<String>,
+    pub scene: Option<String>,
+    /// Simulated seconds, at most MAX_TEST_SECONDS
+    #[serde(default = "default_duration")]
+    pub duration: f64,
+    #[serde(default)]
+    pub inputs: Vec<InputEvent>,
+    #[serde(default)]
+    pub expect: Vec<Expectation>,
+}
+
+fn default_duration() -> f64 {
+    1.0
+}
+
+/// A test file holds either one case or a `cases` list
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum SpecTestFile {
+    Many { cases: Vec<SpecTestCase> },
+    One(SpecTestCase),
+}
+
+#[derive(Debug, Serialize)]
+pub struct SpecTestResult {
+    pub file: String,
+    pub name: String,
+    pub passed: bool,
+    pub failures: Vec<String>,
+    pub frames: u64,
+    /// Gameplay seed the case ran with, so a failure can be replayed
+    pub seed: u32,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SpecTestReport {
+    pub passed: usize,
+    pub failed: usize,
+    pub results: Vec<SpecTestResult>,
+}
+
+/// Operator label, expected bound, and predicate over (actual, expected)
+type Comparison = (&'static str, Option<f64>, fn(f64, f64) -> bool);
+
+fn check(expectation: &Expectation, actual: &Value) -> Option<String> {
+    let number = actual.as_f64();
+    let failed = |op: &str, expected: &dyn std::fmt::Display| {
+        Some(format!("{}: expected {} {}, got {}", expectation.target, op, expected, actual))
+    };
+
+    if let Some(expected) = &expectation.eq {
+        let equal = match (expected.as_f64(), number) {
+            (Some(e), Some(a)) => (e - a).abs() < 1e-6,
+            _ => expected == actual,
+        };
+        if !equal {
+            return failed("==", expected);
+        }
+    }
+    let comparisons: [Comparison; 4] = [
+        (">", expectation.gt, |a, e| a > e),
+        ("<", expectation.lt, |a, e| a < e),
+        (">=", expectation.gte, |a, e| a >= e),
+        ("<=", expectation.lte, |a, e| a <= e),
+    ];
+    for (op, expected, holds) in comparisons {
+        let Some(expected) = expected else {
+            continue;
+        };
+        if !number.is_some_and(|a| holds(a, expected)) {
+            return failed(op, &expected);
+        }
+    }
+    None
+}
+
+fn run_case(project_path: &str, file: &str, case: &SpecTestCase) -> SpecTestResult {
+    let mut result = SpecTestResult {
+        file: file.to_string(),
+        name: case.name.clone(),
+        passed: false,
+        failures: Vec::new(),
+        frames: 0,
+        seed: 0,
+    };
+
+    if !(0.0..=MAX_TEST_SECONDS).contains(&case.duration) {
+        result.failures.push(format!(
+            "duration must be between 0 and {} seconds, got {}",
+            MAX_TEST_SECONDS, case.duration
+        ));
+        return result;
+    }
+
+    let game_spec = match &case.spec {
+        Some(fixture) => spec::read_spec_file(&PathBuf::from(project_path).join(TESTS_DIR).join(fixture)),
+        None => spec::load_project_spec(project_path),
+    };
+    let game_spec = match game_spec {
+        Ok(game_spec) => game_spec,
+        Err(e) => {
+            result.failures.push(e);
+            return result;
+        }
+    };
+
+    let mut simulation = Simulation::new(&game_spec, case.scene.as_deref());
+    simulation.run(case.duration, &case.inputs);
+    result.frames = simulation.frame;
+    result.seed = simulation.seed;
+
+    for expectation in &case.expect {
+        match simulation.read(&expectation.target) {
+            Ok(actual) => result.failures.extend(check(expectation, &actual)),
+            Err(e) => result.failures.push(e),
+        }
+    }
+    result.passed = result.failures.is_empty();
+    result
+}
+
+fn test_files(dir: &Path) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|e| e == "yaml" || e == "yml"))
+        .collect();
+    files.sort();
+    files
+}
+
+/// Run every case in the project's tests/ folder, optionally only files or cases matching `filter`
+pub fn run_all(project_path: &str, filter: Option<&str>) -> Result<SpecTestReport, String> {
+    let dir = PathBuf::from(project_path).join(TESTS_DIR);
+    if !dir.is_dir() {
+        return Err(format!("No {} folder in {}", TESTS_DIR, project_path));
+    }
+
+    let mut results = Vec::new();
+    for path in test_files(&dir) {
+        let file = path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let parsed = fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read {}: {}", file, e))
+            .and_then(|content| {
+                serde_yaml::from_str::<SpecTestFile>(&content)
+                    .map_err(|e| format!("Failed to parse {}: {}", file, e))
+            });
+
+        let cases = match parsed {
+            Ok(SpecTestFile::Many { cases }) => cases,
+            Ok(SpecTestFile::One(case)) => vec![case],
+            Err(e) => {
+                results.push(SpecTestResult {
+                    file: file.clone(),
+                    name: file.clone(),
+                    passed: false,
+                    failures: vec![e],
+                    frames: 0,
+                    seed: 0,
+                });
+                continue;
+            }
+        };
+
+        for case in cases {
+            if filter.is_some_and(|f| !file.contains(f) && !case.name.contains(f)) {
+                continue;
+            }
+            results.push(run_case(project_path, &file, &case));
+        }
+    }
+
+    let passed = results.iter().filter(|r| r.passed).count();
+    Ok(SpecTestReport {
+        passed,
+        failed: results.len() - passed,
+        results,
+    })
+}
+
+/// `promptplay-desktop test [project] [filter]`: run spec tests headlessly and return an exit code
+pub fn run_cli(args: &[String]) -> i32 {
+    let project_path = args.first().map(String::as_str).unwrap_or(".");
+    let report = match run_all(project_path, args.get(1).map(String::as_str)) {
+        Ok(report) => report,
+        Err(e) => {
+            eprintln!("{}", e);
+            return 2;
+        }
+    };
+
+    for result in &report.results {
+        println!(
+            "{} {} :: {}",
+            if result.passed { "PASS" } else { "FAIL" },
+            result.file,
+            result.name
+        );
+        for failure in &result.failures {
+            println!("    {}", failure);
+        }
+    }
+    println!("\n{} passed, {} failed", report.passed, report.failed);
+
+    if report.failed == 0 {
+        0
+    } else {
+        1
+    }
+}
+
+/// Run the project's YAML spec tests in the headless simulator
+#[tauri::command]
+pub async fn run_spec_tests(project_path: String, filter: Option<String>) -> Result<SpecTestReport, String> {
+    // Simulating long cases takes a while; keep it off the async runtime's workers
+    tauri::async_runtime::spawn_blocking(move || run_all(&project_path, filter.as_deref()))
+        .await
+        .map_err(|e| format!("Spec tests failed: {}", e))?
+}
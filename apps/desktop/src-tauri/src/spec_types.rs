@@ -0,0 +1,262 @@
+use crate::components::{ComponentDef, ComponentRegistry, FieldDef, FieldType};
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Written into the output folder; the runtime packages import these
+const TYPES_FILE: &str = "componentSpecs.ts";
+const SCHEMA_FILE: &str = "componentSpecs.schema.json";
+/// Where project types go when no output folder is given
+const PROJECT_TYPES_DIR: &str = ".promptplay/types";
+
+#[derive(Debug, Serialize)]
+pub struct GeneratedSpecTypes {
+    pub typescript_path: String,
+    pub schema_path: Option<String>,
+    pub components: usize,
+}
+
+/// `transform` -> `TransformComponentSpec`; custom names may hold characters TS won't take
+fn type_name(component: &str) -> String {
+    let mut name = String::new();
+    for part in component.split(|c: char| !c.is_ascii_alphanumeric()).filter(|p| !p.is_empty()) {
+        let mut chars = part.chars();
+        if let Some(first) = chars.next() {
+            name.push(first.to_ascii_uppercase());
+            name.extend(chars);
+        }
+    }
+    if name.starts_with(|c: char| c.is_ascii_digit()) {
+        name.insert(0, '_');
+    }
+    format!("{}ComponentSpec", name)
+}
+
+fn property_name(name: &str) -> String {
+    let identifier = name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_' || c == '$')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$');
+    if identifier {
+        name.to_string()
+    } else {
+        serde_json::to_string(name).unwrap_or_default()
+    }
+}
+
+fn ts_type(field: &FieldDef) -> String {
+    match field.field_type {
+        FieldType::Number | FieldType::Integer => "number".to_string(),
+        FieldType::String | FieldType::Asset => "string".to_string(),
+        FieldType::Boolean => "boolean".to_string(),
+        // "#rrggbb" strings or 0xRRGGBB numbers, as the runtime accepts both
+        FieldType::Color => "string | number".to_string(),
+        FieldType::Enum if !field.values.is_empty() => field
+            .values
+            .iter()
+            .map(|v| serde_json::to_string(v).unwrap_or_default())
+            .collect::<Vec<_>>()
+            .join(" | "),
+        FieldType::Enum => "string".to_string(),
+        FieldType::Object => "Record<string, unknown>".to_string(),
+        FieldType::Array => "unknown[]".to_string(),
+    }
+}
+
+/// JSDoc lines for a field: its description plus the constraints the validator enforces
+fn field_doc(field: &FieldDef) -> Vec<String> {
+    let mut lines: Vec<String> = field.description.iter().map(|d| d.replace("*/", "* /")).collect();
+    if field.field_type == FieldType::Integer {
+        lines.push("Integer".to_string());
+    }
+    if field.field_type == FieldType::Asset {
+        lines.push("Asset path or name from assets.json".to_string());
+    }
+    if let Some(min) = field.min {
+        lines.push(format!("@minimum {}", min));
+    }
+    if let Some(max) = field.max {
+        lines.push(format!("@maximum {}", max));
+    }
+    if let Some(default) = &field.default {
+        lines.push(format!("@default {}", default));
+    }
+    lines
+}
+
+fn push_doc(out: &mut String, indent: &str, lines: &[String]) {
+    match lines {
+        [] => {}
+        [line] => out.push_str(&format!("{}/** {} */\n", indent, line)),
+        lines => {
+            out.push_str(&format!("{}/**\n", indent));
+            for line in lines {
+                out.push_str(&format!("{} * {}\n", indent, line));
+            }
+            out.push_str(&format!("{} */\n", indent));
+        }
+    }
+}
+
+/// TypeScript interfaces for every component in the registry and for an entity's `components`
+pub fn typescript(registry: &ComponentRegistry) -> String {
+    let mut out = String::from(
+        "// Generated from the PromptPlay component registry by generate_spec_types; don't edit by hand.\n\n",
+    );
+    for (name, def) in &registry.components {
+        push_doc(&mut out, "", &def.description.iter().map(|d| d.replace("*/", "* /")).collect::<Vec<_>>());
+        out.push_str(&format!("export interface {} {{\n", type_name(name)));
+        for (field_name, field) in &def.fields {
+            push_doc(&mut out, "  ", &field_doc(field));
+            let optional = if def.required.contains(field_name) { "" } else { "?" };
+            out.push_str(&format!("  {}{}: {};\n", property_name(field_name), optional, ts_type(field)));
+        }
+        out.push_str("}\n\n");
+    }
+
+    out.push_str("/** The `components` object of an entity */\nexport interface ComponentsSpec {\n");
+    for name in registry.components.keys() {
+        out.push_str(&format!("  {}?: {};\n", property_name(name), type_name(name)));
+    }
+    out.push_str("}\n\n");
+    let names: Vec<String> = registry
+        .components
+        .keys()
+        .map(|name| serde_json::to_string(name).unwrap_or_default())
+        .collect();
+    let names = if names.is_empty() { "never".to_string() } else { names.join(" | ") };
+    out.push_str(&format!("export type ComponentName = {};\n", names));
+    out
+}
+
+fn field_schema(field: &FieldDef) -> Value {
+    let mut schema = match field.field_type {
+        FieldType::Number => json!({ "type": "number" }),
+        FieldType::Integer => json!({ "type": "integer" }),
+        FieldType::String | FieldType::Asset => json!({ "type": "string" }),
+        FieldType::Boolean => json!({ "type": "boolean" }),
+        FieldType::Color => json!({ "type": ["string", "integer"] }),
+        FieldType::Enum => json!({ "type": "string", "enum": field.values }),
+        FieldType::Object => json!({ "type": "object" }),
+        FieldType::Array => json!({ "type": "array" }),
+    };
+    if let Some(object) = schema.as_object_mut() {
+        if let Some(description) = &field.description {
+            object.insert("description".to_string(), json!(description));
+        }
+        if let Some(min) = field.min {
+            object.insert("minimum".to_string(), json!(min));
+        }
+        if let Some(max) = field.max {
+            object.insert("maximum".to_string(), json!(max));
+        }
+        if let Some(default) = &field.default {
+            object.insert("default".to_string(), default.clone());
+        }
+    }
+    schema
+}
+
+fn component_schema(def: &ComponentDef) -> Value {
+    let properties: Map<String, Value> = def
+        .fields
+        .iter()
+        .map(|(name, field)| (name.clone(), field_schema(field)))
+        .collect();
+    let mut schema = json!({
+        "type": "object",
+        "properties": properties,
+        "required": def.required,
+    });
+    if let (Some(object), Some(description)) = (schema.as_object_mut(), &def.description) {
+        object.insert("description".to_string(), json!(description));
+    }
+    schema
+}
+
+/// JSON Schema (draft-07) for an entity's `components`, one definition per component
+pub fn json_schema(registry: &ComponentRegistry) -> Value {
+    let definitions: Map<String, Value> = registry
+        .components
+        .iter()
+        .map(|(name, def)| (type_name(name), component_schema(def)))
+        .collect();
+    let properties: Map<String, Value> = registry
+        .components
+        .keys()
+        .map(|name| (name.clone(), json!({ "$ref": format!("#/definitions/{}", type_name(name)) })))
+        .collect();
+    json!({
+        "$schema": "http://json-schema.org/draft-07/schema#",
+        "title": "ComponentsSpec",
+        "type": "object",
+        "properties": properties,
+        "definitions": definitions,
+    })
+}
+
+fn write(registry: &ComponentRegistry, dir: &Path, with_schema: bool) -> Result<GeneratedSpecTypes, String> {
+    fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    let typescript_path = dir.join(TYPES_FILE);
+    fs::write(&typescript_path, typescript(registry)).map_err(|e| format!("Failed to write {}: {}", TYPES_FILE, e))?;
+
+    let schema_path = if with_schema {
+        let path = dir.join(SCHEMA_FILE);
+        let content = serde_json::to_string_pretty(&json_schema(registry))
+            .map_err(|e| format!("Failed to serialize {}: {}", SCHEMA_FILE, e))?;
+        fs::write(&path, content + "\n").map_err(|e| format!("Failed to write {}: {}", SCHEMA_FILE, e))?;
+        Some(path.to_string_lossy().to_string())
+    } else {
+        None
+    };
+
+    Ok(GeneratedSpecTypes {
+        typescript_path: typescript_path.to_string_lossy().to_string(),
+        schema_path,
+        components: registry.components.len(),
+    })
+}
+
+/// `promptplay-desktop types <dir> [--schema] [--project <path>]`, run by the packages' codegen
+/// script so the built-in types can't drift from the registry
+pub fn run_cli(args: &[String]) -> i32 {
+    let Some(dir) = args.first().filter(|a| !a.starts_with("--")) else {
+        eprintln!("usage: types <output dir> [--schema] [--project <path>]");
+        return 2;
+    };
+    let project = args.iter().position(|a| a == "--project").and_then(|i| args.get(i + 1));
+    let registry = match project {
+        Some(path) => ComponentRegistry::load(path),
+        None => Ok(ComponentRegistry::builtin()),
+    };
+    match registry.and_then(|registry| write(&registry, Path::new(dir), args.iter().any(|a| a == "--schema"))) {
+        Ok(generated) => {
+            println!("Wrote {} components to {}", generated.components, generated.typescript_path);
+            0
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            2
+        }
+    }
+}
+
+/// Write TypeScript definitions (and with `json_schema`, a JSON Schema) for the component
+/// registry. Project components from components.d.json are included when `project_path` is
+/// given; output goes to `output_dir`, or the project's .promptplay/types.
+#[tauri::command]
+pub async fn generate_spec_types(
+    project_path: Option<String>,
+    output_dir: Option<String>,
+    json_schema: Option<bool>,
+) -> Result<GeneratedSpecTypes, String> {
+    let registry = match &project_path {
+        Some(path) => ComponentRegistry::load(path)?,
+        None => ComponentRegistry::builtin(),
+    };
+    let dir = match (output_dir, &project_path) {
+        (Some(dir), _) => PathBuf::from(dir),
+        (None, Some(project)) => crate::paths::project_root(project).join(PROJECT_TYPES_DIR),
+        (None, None) => return Err("Choose an output folder or a project".to_string()),
+    };
+    write(&registry, &dir, json_schema.unwrap_or(false))
+}
@@ -0,0 +1,127 @@
+use serde::Serialize;
+use std::collections::BTreeSet;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Instant;
+use tauri::{AppHandle, Manager, State};
+
+/// One measured piece of startup work
+#[derive(Debug, Serialize, Clone)]
+pub struct StartupPhase {
+    pub name: String,
+    pub started_ms: u64,
+    pub duration_ms: u64,
+    /// Ran after the window showed instead of before it
+    pub background: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StartupReport {
+    /// From process start until the main window finished loading
+    pub window_shown_ms: Option<u64>,
+    pub phases: Vec<StartupPhase>,
+    /// Background phases still running
+    pub pending: Vec<String>,
+}
+
+/// Timings of this launch, counted from when the builder is set up in main
+pub struct StartupState {
+    started: Instant,
+    phases: Mutex<Vec<StartupPhase>>,
+    pending: Mutex<BTreeSet<String>>,
+    window_shown_ms: Mutex<Option<u64>>,
+    background_started: AtomicBool,
+}
+
+impl Default for StartupState {
+    fn default() -> Self {
+        Self {
+            started: Instant::now(),
+            phases: Mutex::new(Vec::new()),
+            pending: Mutex::new(BTreeSet::new()),
+            window_shown_ms: Mutex::new(None),
+            background_started: AtomicBool::new(false),
+        }
+    }
+}
+
+impl StartupState {
+    fn elapsed_ms(&self, since: Instant) -> u64 {
+        since.duration_since(self.started).as_millis() as u64
+    }
+
+    fn push(&self, name: &str, started: Instant, background: bool) {
+        let phase = StartupPhase {
+            name: name.to_string(),
+            started_ms: self.elapsed_ms(started),
+            duration_ms: started.elapsed().as_millis() as u64,
+            background,
+        };
+        if let Ok(mut phases) = self.phases.lock() {
+            phases.push(phase);
+        }
+    }
+
+    /// Record a step of setup that began at `started` and just finished
+    pub fn record(&self, name: &str, started: Instant) {
+        self.push(name, started, false);
+    }
+
+    /// Run a setup step and record how long it took
+    pub fn time<T>(&self, name: &str, step: impl FnOnce() -> T) -> T {
+        let started = Instant::now();
+        let result = step();
+        self.record(name, started);
+        result
+    }
+
+    fn report(&self) -> StartupReport {
+        StartupReport {
+            window_shown_ms: self.window_shown_ms.lock().ok().and_then(|shown| *shown),
+            phases: self.phases.lock().map(|p| p.clone()).unwrap_or_default(),
+            pending: self
+                .pending
+                .lock()
+                .map(|p| p.iter().cloned().collect())
+                .unwrap_or_default(),
+        }
+    }
+}
+
+/// Run a step on its own thread once the window is up, recording it as a background phase
+pub fn in_background(app: &AppHandle, name: &'static str, step: impl FnOnce(&AppHandle) + Send + 'static) {
+    if let Ok(mut pending) = app.state::<StartupState>().pending.lock() {
+        pending.insert(name.to_string());
+    }
+    let app = app.clone();
+    std::thread::spawn(move || {
+        let started = Instant::now();
+        step(&app);
+        let state = app.state::<StartupState>();
+        state.push(name, started, true);
+        let Ok(mut pending) = state.pending.lock() else {
+            return;
+        };
+        pending.remove(name);
+    });
+}
+
+/// Note that the main window finished loading. Returns true the first time, when deferred
+/// startup work should begin.
+pub fn window_shown(app: &AppHandle) -> bool {
+    let state = app.state::<StartupState>();
+    if state.background_started.swap(true, Ordering::Relaxed) {
+        return false;
+    }
+    let shown_ms = state.elapsed_ms(Instant::now());
+    if let Ok(mut shown) = state.window_shown_ms.lock() {
+        *shown = Some(shown_ms);
+    }
+    true
+}
+
+/// How long each part of this launch took, including work deferred until after the window showed
+#[tauri::command]
+pub async fn get_startup_report(state: State<'_, StartupState>) -> Result<StartupReport, String> {
+    Ok(state.report())
+}
@@ -0,0 +1,214 @@
+use crate::cache::{self, CacheCategory};
+use crate::credentials;
+use crate::settings::AppSettings;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use std::time::Instant;
+use tauri::AppHandle;
+
+/// Keychain account holding the transcription API key
+const API_KEY_ACCOUNT: &str = "stt-api-key";
+/// whisper.cpp only reads 16 kHz PCM
+const WHISPER_SAMPLE_RATE: u32 = 16_000;
+/// Longer dictation than this is almost certainly a stuck recorder
+const MAX_AUDIO_BYTES: usize = 50 * 1024 * 1024;
+
+/// Where dictated prompts are transcribed
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum SttProvider {
+    /// A local whisper.cpp binary (`whisper-cli`) run as a sidecar process
+    #[serde(rename_all = "camelCase")]
+    Whisper {
+        /// Binary path; `whisper-cli` on PATH when empty
+        #[serde(default)]
+        binary: Option<String>,
+        /// ggml model file, e.g. ggml-base.en.bin
+        model: String,
+    },
+    /// An OpenAI-compatible `/v1/audio/transcriptions` endpoint; the key lives in the keychain
+    #[serde(rename_all = "camelCase")]
+    Api { url: String, model: String },
+}
+
+/// Speech-to-text configuration (`stt` in the app settings)
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SttSettings {
+    pub provider: SttProvider,
+    /// ISO 639-1 code; auto-detected when unset
+    #[serde(default)]
+    pub language: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Transcription {
+    pub text: String,
+    pub provider: String,
+    pub duration_ms: u64,
+}
+
+/// Sample rate of a RIFF/WAVE file, read from its fmt chunk
+fn wav_sample_rate(bytes: &[u8]) -> Result<u32, String> {
+    if bytes.len() < 12 || &bytes[0..4] != b"RIFF" || &bytes[8..12] != b"WAVE" {
+        return Err("Audio is not a WAV file".to_string());
+    }
+    let mut offset = 12;
+    while offset + 8 <= bytes.len() {
+        let id = &bytes[offset..offset + 4];
+        let size = u32::from_le_bytes([bytes[offset + 4], bytes[offset + 5], bytes[offset + 6], bytes[offset + 7]]) as usize;
+        if id == b"fmt " && offset + 16 <= bytes.len() {
+            let rate = &bytes[offset + 12..offset + 16];
+            return Ok(u32::from_le_bytes([rate[0], rate[1], rate[2], rate[3]]));
+        }
+        // Chunks are padded to an even length
+        offset += 8 + size + (size & 1);
+    }
+    Err("WAV file has no fmt chunk".to_string())
+}
+
+fn transcribe_whisper(
+    work_dir: &Path,
+    binary: Option<&str>,
+    model: &str,
+    language: Option<&str>,
+    wav: &[u8],
+) -> Result<String, String> {
+    let rate = wav_sample_rate(wav)?;
+    if rate != WHISPER_SAMPLE_RATE {
+        return Err(format!("whisper.cpp needs 16 kHz audio, got {} Hz", rate));
+    }
+    let input = work_dir.join(format!("stt-{}.wav", uuid::Uuid::new_v4()));
+    fs::write(&input, wav).map_err(|e| format!("Failed to write audio: {}", e))?;
+
+    let binary = binary.filter(|b| !b.is_empty()).unwrap_or("whisper-cli");
+    let mut command = Command::new(binary);
+    command.arg("-m").arg(model).arg("-f").arg(&input).arg("--no-timestamps");
+    if let Some(language) = language {
+        command.arg("-l").arg(language);
+    }
+    let output = command.output();
+    let _ = fs::remove_file(&input);
+    let output = output.map_err(|e| match e.kind() {
+        std::io::ErrorKind::NotFound => format!("whisper.cpp binary not found: {}", binary),
+        _ => format!("Failed to run whisper.cpp: {}", e),
+    })?;
+    if !output.status.success() {
+        return Err(format!("whisper.cpp failed: {}", String::from_utf8_lossy(&output.stderr).trim()));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join(" "))
+}
+
+/// multipart/form-data body with text fields and the audio file
+fn multipart_body(boundary: &str, fields: &[(&str, &str)], wav: &[u8]) -> Vec<u8> {
+    let mut body = Vec::with_capacity(wav.len() + 512);
+    for (name, value) in fields {
+        body.extend_from_slice(
+            format!("--{}\r\nContent-Disposition: form-data; name=\"{}\"\r\n\r\n{}\r\n", boundary, name, value).as_bytes(),
+        );
+    }
+    body.extend_from_slice(
+        format!(
+            "--{}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"prompt.wav\"\r\nContent-Type: audio/wav\r\n\r\n",
+            boundary
+        )
+        .as_bytes(),
+    );
+    body.extend_from_slice(wav);
+    body.extend_from_slice(format!("\r\n--{}--\r\n", boundary).as_bytes());
+    body
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiTranscription {
+    text: String,
+}
+
+async fn transcribe_api(url: &str, model: &str, language: Option<&str>, wav: &[u8]) -> Result<String, String> {
+    wav_sample_rate(wav)?;
+    let api_key = credentials::load(API_KEY_ACCOUNT)?;
+    let boundary = format!("promptplay-{}", uuid::Uuid::new_v4().simple());
+    let mut fields = vec![("model", model), ("response_format", "json")];
+    if let Some(language) = language {
+        fields.push(("language", language));
+    }
+
+    let mut request = Client::new()
+        .post(url)
+        .header("Content-Type", format!("multipart/form-data; boundary={}", boundary))
+        .body(multipart_body(&boundary, &fields, wav));
+    if let Some(key) = api_key {
+        request = request.bearer_auth(key);
+    }
+    let response = request.send().await.map_err(|e| format!("Failed to reach transcription API: {}", e))?;
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        return Err(format!("Transcription API error {}: {}", status, body));
+    }
+    let result: ApiTranscription = response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse transcription: {}", e))?;
+    Ok(result.text.trim().to_string())
+}
+
+#[tauri::command]
+pub async fn get_stt_settings(app: AppHandle) -> Result<Option<SttSettings>, String> {
+    Ok(AppSettings::load(&app).stt)
+}
+
+/// Configure speech-to-text; `api_key` is stored in the keychain, an empty one removes it
+#[tauri::command]
+pub async fn set_stt_settings(app: AppHandle, settings: Option<SttSettings>, api_key: Option<String>) -> Result<(), String> {
+    match api_key.as_deref().map(str::trim) {
+        Some("") => credentials::delete(API_KEY_ACCOUNT)?,
+        Some(key) => credentials::store(API_KEY_ACCOUNT, key)?,
+        None => {}
+    }
+    let mut app_settings = AppSettings::load(&app);
+    app_settings.stt = settings;
+    app_settings.save(&app)
+}
+
+/// Transcribe a recorded WAV prompt with the configured provider
+#[tauri::command]
+pub async fn transcribe_audio(app: AppHandle, wav_bytes: Vec<u8>) -> Result<Transcription, String> {
+    if wav_bytes.len() > MAX_AUDIO_BYTES {
+        return Err("Recording is too long to transcribe".to_string());
+    }
+    let settings = AppSettings::load(&app)
+        .stt
+        .ok_or("Speech-to-text is not configured")?;
+    let language = settings.language.as_deref().filter(|l| !l.is_empty());
+    let started = Instant::now();
+
+    let (text, provider) = match &settings.provider {
+        SttProvider::Whisper { binary, model } => {
+            let (binary, model, language) = (binary.clone(), model.clone(), language.map(str::to_string));
+            let work_dir = cache::dir(&app, CacheCategory::AiTranscripts)?;
+            // whisper.cpp blocks for seconds on long recordings
+            let text = tokio::task::spawn_blocking(move || {
+                transcribe_whisper(&work_dir, binary.as_deref(), &model, language.as_deref(), &wav_bytes)
+            })
+            .await
+            .map_err(|e| format!("Transcription task failed: {}", e))??;
+            (text, "whisper")
+        }
+        SttProvider::Api { url, model } => (transcribe_api(url, model, language, &wav_bytes).await?, "api"),
+    };
+
+    Ok(Transcription {
+        text,
+        provider: provider.to_string(),
+        duration_ms: started.elapsed().as_millis() as u64,
+    })
+}
@@ -0,0 +1,181 @@
+use crate::assets::AssetManifest;
+use crate::{paths, spec};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// How project listing, watching and export treat symlinked folders and files
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum SymlinkPolicy {
+    /// Descend into symlinks, visiting each real directory once
+    #[default]
+    Follow,
+    /// Treat symlinks as absent
+    Skip,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SymlinkInfo {
+    /// Project-relative path of the link, with forward slashes
+    pub path: String,
+    pub target: String,
+    pub is_directory: bool,
+    /// Target lives outside the project folder, so copies of the project lose it
+    pub outside_project: bool,
+    pub broken: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SymlinkReport {
+    pub policy: SymlinkPolicy,
+    pub links: Vec<SymlinkInfo>,
+}
+
+/// Recursively visit files under `dir`, applying the policy to symlinks.
+/// `descend` decides per directory whether to enter it; cycles through links are cut off.
+pub fn walk_files(
+    dir: &Path,
+    policy: SymlinkPolicy,
+    descend: &mut dyn FnMut(&Path) -> bool,
+    visit: &mut dyn FnMut(&Path),
+) {
+    let mut visited = HashSet::new();
+    walk_inner(dir, policy, descend, visit, &mut visited);
+}
+
+fn walk_inner(
+    dir: &Path,
+    policy: SymlinkPolicy,
+    descend: &mut dyn FnMut(&Path) -> bool,
+    visit: &mut dyn FnMut(&Path),
+    visited: &mut HashSet<PathBuf>,
+) {
+    // Canonical paths identify a directory however it was reached
+    let real = fs::canonicalize(paths::for_io(dir)).unwrap_or_else(|_| dir.to_path_buf());
+    if !visited.insert(real) {
+        return;
+    }
+    let Ok(entries) = fs::read_dir(paths::for_io(dir)) else {
+        return;
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        if file_type.is_symlink() && policy == SymlinkPolicy::Skip {
+            continue;
+        }
+        // Follows the link, so a symlinked folder reports as a directory
+        let is_dir = if file_type.is_symlink() { path.is_dir() } else { file_type.is_dir() };
+        if is_dir {
+            if descend(&path) {
+                walk_inner(&path, policy, descend, visit, visited);
+            }
+        } else {
+            visit(&path);
+        }
+    }
+}
+
+fn describe(root: &Path, link: &Path) -> Option<SymlinkInfo> {
+    let target = fs::read_link(link).ok()?;
+    let resolved = fs::canonicalize(link).ok();
+    Some(SymlinkInfo {
+        path: paths::to_spec_path(&paths::relative_to(link, root)?),
+        target: paths::to_display(&target),
+        is_directory: link.is_dir(),
+        outside_project: resolved
+            .as_ref()
+            .is_some_and(|r| paths::relative_to(r, root).is_none()),
+        broken: resolved.is_none(),
+    })
+}
+
+/// Every symlink in the project, without descending into linked folders
+pub fn find_symlinks(project_path: &str) -> Vec<SymlinkInfo> {
+    let root = paths::project_root(project_path);
+    let mut links = Vec::new();
+    let mut pending = vec![root.clone()];
+
+    while let Some(dir) = pending.pop() {
+        let Ok(entries) = fs::read_dir(paths::for_io(&dir)) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            match entry.file_type() {
+                Ok(t) if t.is_symlink() => links.extend(describe(&root, &path)),
+                Ok(t) if t.is_dir() => pending.push(path),
+                _ => {}
+            }
+        }
+    }
+    links.sort_by(|a, b| a.path.cmp(&b.path));
+    links
+}
+
+/// Warnings for referenced assets that sit behind a symlink an exported bundle would not carry
+pub fn export_warnings(project_path: &str, manifest: &AssetManifest, game_spec: &Value) -> Vec<String> {
+    let links = find_symlinks(project_path);
+    if links.is_empty() {
+        return Vec::new();
+    }
+
+    let mut warnings = Vec::new();
+    let mut seen = HashSet::new();
+    for reference in spec::asset_references(game_spec) {
+        if !seen.insert(reference.asset.clone()) {
+            continue;
+        }
+        let path = manifest
+            .resolve(&reference.asset)
+            .map(|entry| entry.path.clone())
+            .unwrap_or_else(|| crate::assets::normalize_relative(&reference.asset));
+        let Some(link) = links
+            .iter()
+            .find(|l| path == l.path || path.starts_with(&format!("{}/", l.path)))
+        else {
+            continue;
+        };
+
+        if manifest.symlinks == SymlinkPolicy::Skip {
+            warnings.push(format!(
+                "Asset '{}' is behind symlink '{}', which the project skips; it will be missing from the export",
+                reference.asset, link.path
+            ));
+        } else if link.broken {
+            warnings.push(format!(
+                "Asset '{}' is behind broken symlink '{}' -> {}",
+                reference.asset, link.path, link.target
+            ));
+        } else if link.outside_project {
+            warnings.push(format!(
+                "Asset '{}' resolves outside the project through symlink '{}'; copy it in if the bundle is shared",
+                reference.asset, link.path
+            ));
+        }
+    }
+    warnings
+}
+
+/// List the project's symlinks and the policy applied to them
+#[tauri::command]
+pub async fn list_symlinks(project_path: String) -> Result<SymlinkReport, String> {
+    Ok(SymlinkReport {
+        policy: AssetManifest::load(&project_path)?.symlinks,
+        links: find_symlinks(&project_path),
+    })
+}
+
+/// Set whether the project's listing, watching and export follow symlinks (stored in assets.json)
+#[tauri::command]
+pub async fn set_symlink_policy(project_path: String, policy: SymlinkPolicy) -> Result<(), String> {
+    let mut manifest = AssetManifest::load(&project_path)?;
+    manifest.symlinks = policy;
+    manifest.save(&project_path)
+}
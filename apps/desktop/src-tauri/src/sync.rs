@@ -0,0 +1,562 @@
+use crate::backup;
+use crate::cache::{self, CacheCategory};
+use crate::json_guard;
+use crate::paths;
+use crate::settings::AppSettings;
+use reqwest::{Client, Method, StatusCode, Url};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::AppHandle;
+
+/// Which remote snapshot this copy of the project was last pushed or pulled as
+const SYNC_STATE_FILE: &str = ".promptplay/sync.json";
+const LINEAGE_FILE: &str = "lineage.json";
+const SNAPSHOTS_DIR: &str = "snapshots";
+
+/// Remote storage for project snapshots
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum SyncBackend {
+    /// A folder kept in sync by another tool: Dropbox, OneDrive, a network share
+    #[serde(rename_all = "camelCase")]
+    Folder { path: String },
+    #[serde(rename_all = "camelCase")]
+    WebDav {
+        url: String,
+        username: Option<String>,
+        password: Option<String>,
+    },
+    /// S3 or an S3-compatible store (MinIO, R2), addressed path-style
+    #[serde(rename_all = "camelCase")]
+    S3 {
+        endpoint: String,
+        region: String,
+        bucket: String,
+        #[serde(default)]
+        prefix: String,
+        access_key_id: String,
+        secret_access_key: String,
+    },
+}
+
+/// Sync configuration of one project (`sync` in the app settings, keyed by project path).
+/// Kept per machine because it holds credentials.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncSettings {
+    pub backend: SyncBackend,
+}
+
+/// One pushed version of the project
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Snapshot {
+    pub id: String,
+    /// Snapshot the pushing copy was based on
+    pub parent: Option<String>,
+    pub created_at: u64,
+    pub content_hash: String,
+    pub author: String,
+    pub size: u64,
+}
+
+/// The remote's history: every snapshot plus the current head
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+struct Lineage {
+    head: Option<String>,
+    snapshots: Vec<Snapshot>,
+}
+
+impl Lineage {
+    fn find(&self, id: &str) -> Option<&Snapshot> {
+        self.snapshots.iter().find(|s| s.id == id)
+    }
+
+    /// Whether `ancestor` is `id` or one of its parents. A remote lineage can have a cycle, so
+    /// each snapshot is visited once.
+    fn descends_from(&self, id: &str, ancestor: &str) -> bool {
+        let mut visited = HashSet::new();
+        let mut current = Some(id.to_string());
+        while let Some(id) = current {
+            if id == ancestor {
+                return true;
+            }
+            if !visited.insert(id.clone()) {
+                return false;
+            }
+            current = self.find(&id).and_then(|s| s.parent.clone());
+        }
+        false
+    }
+
+    /// Snapshot ids end up in remote and cache file names, so anything but a UUID is refused
+    fn check_ids(&self) -> Result<(), String> {
+        let ids = self.snapshots.iter().flat_map(|s| [Some(&s.id), s.parent.as_ref()]);
+        match ids.chain([self.head.as_ref()]).flatten().find(|id| !is_snapshot_id(id)) {
+            Some(bad) => Err(format!("The remote {} has an invalid snapshot id: {:.40}", LINEAGE_FILE, bad)),
+            None => Ok(()),
+        }
+    }
+}
+
+/// What a conditional write expects the remote file to be
+#[derive(Debug, Clone)]
+enum Expected {
+    /// The file didn't exist when it was read
+    Missing,
+    /// Its ETag, or the SHA-256 of a folder backend's file
+    Version(String),
+    /// The server sent no ETag, so the write can't be conditional
+    Unknown,
+}
+
+fn is_snapshot_id(id: &str) -> bool {
+    uuid::Uuid::parse_str(id).is_ok_and(|uuid| uuid.hyphenated().to_string() == id)
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+struct LocalState {
+    base: Option<String>,
+    base_content_hash: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SyncStatus {
+    pub head: Option<String>,
+    pub base: Option<String>,
+    /// Local files differ from the snapshot this copy is based on
+    pub local_changed: bool,
+    /// The remote has snapshots built on ours that haven't been pulled
+    pub remote_ahead: bool,
+    /// Both sides moved on from a common snapshot (or share none); pushing or pulling loses work
+    pub conflict: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SyncResult {
+    /// "pushed", "pulled", "upToDate" or "conflict"
+    pub action: String,
+    pub snapshot: Option<Snapshot>,
+    pub status: SyncStatus,
+    pub message: String,
+}
+
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// First characters of a snapshot id, for messages
+fn short_id(id: &str) -> &str {
+    &id[..id.len().min(8)]
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn hmac_sha256(key: &[u8], data: &[u8]) -> Vec<u8> {
+    const BLOCK: usize = 64;
+    let mut key = if key.len() > BLOCK { Sha256::digest(key).to_vec() } else { key.to_vec() };
+    key.resize(BLOCK, 0);
+    let inner: Vec<u8> = key.iter().map(|b| b ^ 0x36).collect();
+    let outer: Vec<u8> = key.iter().map(|b| b ^ 0x5c).collect();
+    let inner_hash = Sha256::new().chain_update(&inner).chain_update(data).finalize();
+    Sha256::new().chain_update(&outer).chain_update(inner_hash).finalize().to_vec()
+}
+
+/// (YYYYMMDD, YYYYMMDDTHHMMSSZ) in UTC for SigV4
+fn amz_dates(unix_seconds: u64) -> (String, String) {
+    let days = (unix_seconds / 86400) as i64;
+    let secs = unix_seconds % 86400;
+    // Civil-from-days (Howard Hinnant's algorithm)
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    let date = format!("{:04}{:02}{:02}", year, month, day);
+    let stamp = format!("{}T{:02}{:02}{:02}Z", date, secs / 3600, secs % 3600 / 60, secs % 60);
+    (date, stamp)
+}
+
+impl SyncBackend {
+    fn join_url(base: &str, name: &str) -> Result<Url, String> {
+        let base = if base.ends_with('/') { base.to_string() } else { format!("{}/", base) };
+        Url::parse(&base)
+            .and_then(|url| url.join(name))
+            .map_err(|e| format!("Invalid sync URL {}: {}", base, e))
+    }
+
+    /// Signed request for an object in the bucket
+    fn s3_request(&self, client: &Client, method: Method, name: &str, body: &[u8]) -> Result<reqwest::RequestBuilder, String> {
+        let SyncBackend::S3 { endpoint, region, bucket, prefix, access_key_id, secret_access_key } = self else {
+            return Err("Not an S3 backend".to_string());
+        };
+        let key = [prefix.trim_matches('/'), name].iter().filter(|p| !p.is_empty()).copied().collect::<Vec<_>>().join("/");
+        let url = Self::join_url(endpoint, &format!("{}/{}", bucket, key))?;
+        let host = match (url.host_str(), url.port()) {
+            (Some(host), Some(port)) => format!("{}:{}", host, port),
+            (Some(host), None) => host.to_string(),
+            _ => return Err(format!("Invalid S3 endpoint {}", endpoint)),
+        };
+
+        let payload_hash = hex(&Sha256::digest(body));
+        let (date, stamp) = amz_dates(now_millis() / 1000);
+        // Url already percent-encodes the path, which is the canonical URI SigV4 expects
+        let canonical_path = url.path().to_string();
+        let canonical = format!(
+            "{}\n{}\n\nhost:{}\nx-amz-content-sha256:{}\nx-amz-date:{}\n\nhost;x-amz-content-sha256;x-amz-date\n{}",
+            method, canonical_path, host, payload_hash, stamp, payload_hash
+        );
+        let scope = format!("{}/{}/s3/aws4_request", date, region);
+        let to_sign = format!("AWS4-HMAC-SHA256\n{}\n{}\n{}", stamp, scope, hex(&Sha256::digest(canonical.as_bytes())));
+        let mut key = hmac_sha256(format!("AWS4{}", secret_access_key).as_bytes(), date.as_bytes());
+        for part in [region.as_str(), "s3", "aws4_request"] {
+            key = hmac_sha256(&key, part.as_bytes());
+        }
+        let signature = hex(&hmac_sha256(&key, to_sign.as_bytes()));
+
+        Ok(client
+            .request(method, url)
+            .header("x-amz-content-sha256", payload_hash)
+            .header("x-amz-date", stamp)
+            .header(
+                "Authorization",
+                format!(
+                    "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders=host;x-amz-content-sha256;x-amz-date, Signature={}",
+                    access_key_id, scope, signature
+                ),
+            ))
+    }
+
+    fn webdav_request(&self, client: &Client, method: Method, name: &str) -> Result<reqwest::RequestBuilder, String> {
+        let SyncBackend::WebDav { url, username, password } = self else {
+            return Err("Not a WebDAV backend".to_string());
+        };
+        let request = client.request(method, Self::join_url(url, name)?);
+        Ok(match username {
+            Some(user) => request.basic_auth(user, password.as_ref()),
+            None => request,
+        })
+    }
+
+    /// Read a remote file; None when it doesn't exist yet
+    async fn read(&self, client: &Client, name: &str) -> Result<Option<Vec<u8>>, String> {
+        Ok(self.read_versioned(client, name).await?.map(|(bytes, _)| bytes))
+    }
+
+    /// Read a remote file along with the version a conditional write must match
+    async fn read_versioned(&self, client: &Client, name: &str) -> Result<Option<(Vec<u8>, Expected)>, String> {
+        let request = match self {
+            SyncBackend::Folder { path } => {
+                let file = PathBuf::from(path).join(name);
+                if !file.exists() {
+                    return Ok(None);
+                }
+                let bytes = fs::read(&file).map_err(|e| format!("Failed to read {}: {}", file.display(), e))?;
+                let version = Expected::Version(hex(&Sha256::digest(&bytes)));
+                return Ok(Some((bytes, version)));
+            }
+            SyncBackend::WebDav { .. } => self.webdav_request(client, Method::GET, name)?,
+            SyncBackend::S3 { .. } => self.s3_request(client, Method::GET, name, b"")?,
+        };
+        let response = request.send().await.map_err(|e| format!("Failed to fetch {}: {}", name, e))?;
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if !response.status().is_success() {
+            return Err(format!("Failed to fetch {}: HTTP {}", name, response.status()));
+        }
+        let version = response
+            .headers()
+            .get(reqwest::header::ETAG)
+            .and_then(|etag| etag.to_str().ok())
+            .map_or(Expected::Unknown, |etag| Expected::Version(etag.to_string()));
+        let bytes = response.bytes().await.map_err(|e| format!("Failed to fetch {}: {}", name, e))?;
+        Ok(Some((bytes.to_vec(), version)))
+    }
+
+    async fn write(&self, client: &Client, name: &str, bytes: Vec<u8>) -> Result<(), String> {
+        self.write_if(client, name, bytes, &Expected::Unknown).await.map(|_| ())
+    }
+
+    /// Write a remote file only if it is still what `expected` says; false when it changed
+    async fn write_if(&self, client: &Client, name: &str, bytes: Vec<u8>, expected: &Expected) -> Result<bool, String> {
+        let request = match self {
+            SyncBackend::Folder { path } => {
+                let file = PathBuf::from(path).join(name);
+                let unchanged = match expected {
+                    Expected::Missing => !file.exists(),
+                    Expected::Version(hash) => fs::read(&file).is_ok_and(|current| hex(&Sha256::digest(current)) == *hash),
+                    Expected::Unknown => true,
+                };
+                if !unchanged {
+                    return Ok(false);
+                }
+                if let Some(parent) = file.parent() {
+                    fs::create_dir_all(parent).map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+                }
+                return fs::write(&file, bytes)
+                    .map(|_| true)
+                    .map_err(|e| format!("Failed to write {}: {}", file.display(), e));
+            }
+            SyncBackend::WebDav { .. } => {
+                if let Some((dir, _)) = name.rsplit_once('/') {
+                    // MKCOL fails with 405 once the collection exists, which is fine
+                    let mkcol = Method::from_bytes(b"MKCOL").map_err(|e| e.to_string())?;
+                    let _ = self.webdav_request(client, mkcol, &format!("{}/", dir))?.send().await;
+                }
+                self.webdav_request(client, Method::PUT, name)?.body(bytes)
+            }
+            SyncBackend::S3 { .. } => self.s3_request(client, Method::PUT, name, &bytes)?.body(bytes),
+        };
+        let request = match expected {
+            Expected::Missing => request.header(reqwest::header::IF_NONE_MATCH, "*"),
+            Expected::Version(etag) => request.header(reqwest::header::IF_MATCH, etag.as_str()),
+            Expected::Unknown => request,
+        };
+        let response = request.send().await.map_err(|e| format!("Failed to upload {}: {}", name, e))?;
+        if response.status() == StatusCode::PRECONDITION_FAILED {
+            return Ok(false);
+        }
+        if !response.status().is_success() {
+            return Err(format!("Failed to upload {}: HTTP {}", name, response.status()));
+        }
+        Ok(true)
+    }
+
+    async fn lineage(&self, client: &Client) -> Result<Lineage, String> {
+        Ok(self.lineage_versioned(client).await?.0)
+    }
+
+    /// The remote lineage and the version a push must find when it writes the lineage back
+    async fn lineage_versioned(&self, client: &Client) -> Result<(Lineage, Expected), String> {
+        let Some((bytes, version)) = self.read_versioned(client, LINEAGE_FILE).await? else {
+            return Ok((Lineage::default(), Expected::Missing));
+        };
+        let lineage: Lineage = json_guard::parse(&bytes, &format!("remote {}", LINEAGE_FILE), &json_guard::METADATA_LIMITS)?;
+        lineage.check_ids()?;
+        Ok((lineage, version))
+    }
+}
+
+fn settings_key(project_path: &str) -> String {
+    paths::to_display(&paths::project_root(project_path))
+}
+
+fn sync_settings(app: &AppHandle, project_path: &str) -> Result<SyncSettings, String> {
+    AppSettings::load(app)
+        .sync
+        .get(&settings_key(project_path))
+        .cloned()
+        .ok_or_else(|| "Sync isn't set up for this project".to_string())
+}
+
+fn load_state(project: &Path) -> LocalState {
+    fs::read_to_string(project.join(SYNC_STATE_FILE))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_state(project: &Path, state: &LocalState) -> Result<(), String> {
+    let path = project.join(SYNC_STATE_FILE);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create sync state directory: {}", e))?;
+    }
+    let content = serde_json::to_string_pretty(state).map_err(|e| format!("Failed to serialize sync state: {}", e))?;
+    fs::write(&path, content).map_err(|e| format!("Failed to write sync state: {}", e))
+}
+
+/// Hash of every file a snapshot would contain, independent of zip timestamps
+fn content_hash(files: &[(PathBuf, String)]) -> Result<String, String> {
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; 64 * 1024];
+    for (path, name) in files {
+        hasher.update(name.as_bytes());
+        hasher.update([0]);
+        let mut file = fs::File::open(path).map_err(|e| format!("Failed to read {}: {}", name, e))?;
+        loop {
+            let read = file.read(&mut buffer).map_err(|e| format!("Failed to read {}: {}", name, e))?;
+            if read == 0 {
+                break;
+            }
+            hasher.update(&buffer[..read]);
+        }
+    }
+    Ok(hex(&hasher.finalize()))
+}
+
+fn status(lineage: &Lineage, state: &LocalState, local_hash: &str) -> SyncStatus {
+    let local_changed = state.base_content_hash.as_deref() != Some(local_hash);
+    let (head, base) = (lineage.head.clone(), state.base.clone());
+    let fast_forward = match (&head, &base) {
+        (Some(head), Some(base)) => head != base && lineage.descends_from(head, base),
+        (Some(_), None) => true,
+        _ => false,
+    };
+    let diverged = match (&head, &base) {
+        (Some(head), Some(base)) => head != base && !lineage.descends_from(head, base),
+        _ => false,
+    };
+    SyncStatus {
+        head,
+        base,
+        local_changed,
+        remote_ahead: fast_forward,
+        conflict: diverged || (fast_forward && local_changed),
+    }
+}
+
+fn result(action: &str, snapshot: Option<Snapshot>, status: SyncStatus, message: String) -> SyncResult {
+    SyncResult {
+        action: action.to_string(),
+        snapshot,
+        status,
+        message,
+    }
+}
+
+/// Sync configuration of a project, if any
+#[tauri::command]
+pub async fn get_sync_settings(app: AppHandle, project_path: String) -> Result<Option<SyncSettings>, String> {
+    Ok(AppSettings::load(&app).sync.get(&settings_key(&project_path)).cloned())
+}
+
+/// Set up (or turn off) sync for a project
+#[tauri::command]
+pub async fn set_sync_settings(app: AppHandle, project_path: String, settings: Option<SyncSettings>) -> Result<(), String> {
+    let mut app_settings = AppSettings::load(&app);
+    match settings {
+        Some(settings) => app_settings.sync.insert(settings_key(&project_path), settings),
+        None => app_settings.sync.remove(&settings_key(&project_path)),
+    };
+    app_settings.save(&app)
+}
+
+/// Compare the local project with the remote snapshot lineage
+#[tauri::command]
+pub async fn sync_status(app: AppHandle, project_path: String) -> Result<SyncStatus, String> {
+    let backend = sync_settings(&app, &project_path)?.backend;
+    let project = paths::project_root(&project_path);
+    let lineage = backend.lineage(&Client::new()).await?;
+    let local_hash = content_hash(&backup::project_files(&app, &project)?)?;
+    Ok(status(&lineage, &load_state(&project), &local_hash))
+}
+
+/// Upload the project as a new snapshot. Refused as a conflict when the remote has snapshots
+/// this copy hasn't pulled, unless `force`.
+#[tauri::command]
+pub async fn sync_push(app: AppHandle, project_path: String, force: Option<bool>) -> Result<SyncResult, String> {
+    let backend = sync_settings(&app, &project_path)?.backend;
+    let client = Client::new();
+    let project = paths::project_root(&project_path);
+    let (mut lineage, lineage_version) = backend.lineage_versioned(&client).await?;
+    let state = load_state(&project);
+    let files = backup::project_files(&app, &project)?;
+    let local_hash = content_hash(&files)?;
+    let current = status(&lineage, &state, &local_hash);
+
+    if lineage.head.is_some() && lineage.head == state.base && !current.local_changed {
+        return Ok(result("upToDate", None, current, "Nothing to push".to_string()));
+    }
+    if lineage.head.is_some() && lineage.head != state.base && !force.unwrap_or(false) {
+        let message = "The remote has changes from another machine; pull them first or push with force to overwrite".to_string();
+        return Ok(result("conflict", None, current, message));
+    }
+
+    let id = uuid::Uuid::new_v4().to_string();
+    let archive = cache::dir(&app, CacheCategory::Exports)?.join(format!("sync-{}.zip", id));
+    backup::write_archive(&files, &archive)?;
+    let bytes = fs::read(&archive).map_err(|e| format!("Failed to read snapshot archive: {}", e));
+    let _ = fs::remove_file(&archive);
+    let bytes = bytes?;
+
+    let snapshot = Snapshot {
+        id: id.clone(),
+        // A forced push still records what it was based on, so the lineage shows the fork
+        parent: state.base.clone(),
+        created_at: now_millis(),
+        content_hash: local_hash.clone(),
+        author: std::env::var("COMPUTERNAME")
+            .or_else(|_| std::env::var("HOSTNAME"))
+            .unwrap_or_else(|_| "unknown".to_string()),
+        size: bytes.len() as u64,
+    };
+    backend.write(&client, &format!("{}/{}.zip", SNAPSHOTS_DIR, id), bytes).await?;
+    lineage.snapshots.push(snapshot.clone());
+    lineage.head = Some(id.clone());
+    let lineage_json = serde_json::to_vec_pretty(&lineage).map_err(|e| format!("Failed to serialize lineage: {}", e))?;
+    // Another push that landed since the lineage was read would otherwise be dropped from it.
+    // The uploaded archive stays behind unreferenced.
+    if !backend.write_if(&client, LINEAGE_FILE, lineage_json, &lineage_version).await? {
+        let message = "Another machine pushed while this push was uploading; pull its changes, then push again".to_string();
+        return Ok(result("conflict", None, current, message));
+    }
+
+    let state = LocalState {
+        base: Some(id),
+        base_content_hash: Some(local_hash.clone()),
+    };
+    save_state(&project, &state)?;
+    let message = format!("Pushed snapshot {}", short_id(&snapshot.id));
+    Ok(result("pushed", Some(snapshot), status(&lineage, &state, &local_hash), message))
+}
+
+/// Replace the project with the remote head snapshot. Refused as a conflict when local changes
+/// would be lost, unless `force`; a backup is taken before restoring either way.
+#[tauri::command]
+pub async fn sync_pull(app: AppHandle, project_path: String, force: Option<bool>) -> Result<SyncResult, String> {
+    let backend = sync_settings(&app, &project_path)?.backend;
+    let client = Client::new();
+    let project = paths::project_root(&project_path);
+    let lineage = backend.lineage(&client).await?;
+    let state = load_state(&project);
+    let local_hash = content_hash(&backup::project_files(&app, &project)?)?;
+    let current = status(&lineage, &state, &local_hash);
+
+    let Some(head) = lineage.head.clone() else {
+        return Ok(result("upToDate", None, current, "The remote has no snapshots yet".to_string()));
+    };
+    if state.base.as_deref() == Some(head.as_str()) {
+        return Ok(result("upToDate", None, current, "Already up to date".to_string()));
+    }
+    if current.conflict && !force.unwrap_or(false) {
+        let message = "Local changes and remote changes both exist; push with force or pull with force to pick a side".to_string();
+        return Ok(result("conflict", None, current, message));
+    }
+
+    let snapshot = lineage.find(&head).cloned().ok_or("The remote lineage names a head snapshot it doesn't list")?;
+    let bytes = backend
+        .read(&client, &format!("{}/{}.zip", SNAPSHOTS_DIR, head))
+        .await?
+        .ok_or_else(|| format!("Snapshot {} is missing on the remote", head))?;
+    let archive = cache::dir(&app, CacheCategory::Exports)?.join(format!("sync-{}.zip", head));
+    fs::write(&archive, bytes).map_err(|e| format!("Failed to save snapshot archive: {}", e))?;
+    let restored = backup::restore(&app, &archive, &project_path);
+    let _ = fs::remove_file(&archive);
+    restored?;
+
+    let state = LocalState {
+        base: Some(head),
+        base_content_hash: Some(snapshot.content_hash.clone()),
+    };
+    save_state(&project, &state)?;
+    let message = format!("Pulled snapshot {} from {}", short_id(&snapshot.id), snapshot.author);
+    let local_hash = content_hash(&backup::project_files(&app, &project)?)?;
+    Ok(result("pulled", Some(snapshot), status(&lineage, &state, &local_hash), message))
+}
@@ -0,0 +1,461 @@
+use crate::validation::{self, Diagnostic, Severity};
+use crate::{backup, deep_link, json_guard, paths, spec};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Manager};
+
+/// Folder under the app data directory holding one folder per saved template
+const TEMPLATES_DIR: &str = "templates";
+/// Manifest at the root of every template folder
+pub const MANIFEST_FILE: &str = "template.json";
+/// Placeholders a template may use; the new-project flow fills them in
+const PARAMETERS: &[&str] = &["projectName"];
+/// Metadata fields that identify the author and are dropped from shared specs
+const PERSONAL_METADATA: &[&str] = &["author", "authorEmail", "email", "owner", "createdBy"];
+/// Files rewritten with placeholders; everything else is copied byte for byte
+const TEXT_EXTENSIONS: &[&str] = &["json", "js", "ts", "md", "txt", "html", "css", "yaml", "yml"];
+
+/// template.json
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TemplateManifest {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub genre: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Placeholders used by the files, e.g. `projectName` for `{{projectName}}`
+    #[serde(default)]
+    pub parameters: Vec<String>,
+    /// Every file in the template besides the manifest, as forward-slash relative paths
+    pub files: Vec<String>,
+    pub created_at: u64,
+    /// Bumped by the author on each release; projects that installed the template as a kit
+    /// compare it to tell whether an upgrade is available
+    #[serde(default = "default_version")]
+    pub version: String,
+}
+
+fn default_version() -> String {
+    "1.0.0".to_string()
+}
+
+/// What to call the template and what to put in it
+#[derive(Debug, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TemplateOptions {
+    /// Slug for the template folder and install links; derived from `name` when missing
+    pub id: Option<String>,
+    pub name: String,
+    pub description: String,
+    pub tags: Vec<String>,
+    /// Leave out everything but game.json
+    pub spec_only: bool,
+    /// Release version; defaults to 1.0.0
+    pub version: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CreatedTemplate {
+    pub path: String,
+    pub manifest: TemplateManifest,
+    /// Personal data and private files left out, for the user to review
+    pub stripped: Vec<String>,
+    /// Findings from validating the new template
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TemplateValidation {
+    pub valid: bool,
+    pub manifest: Option<TemplateManifest>,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+fn slug(name: &str) -> String {
+    let slug: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_lowercase() } else { '-' })
+        .collect();
+    slug.split('-').filter(|part| !part.is_empty()).collect::<Vec<_>>().join("-")
+}
+
+fn templates_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map(|dir| dir.join(TEMPLATES_DIR))
+        .map_err(|e| format!("Failed to resolve app data directory: {}", e))
+}
+
+/// Folder of a template in the registry by id, or a template folder given by path
+pub fn template_dir(app: &AppHandle, template: &str) -> Result<PathBuf, String> {
+    let dir = if deep_link::is_template_id(template) {
+        templates_dir(app)?.join(template)
+    } else {
+        PathBuf::from(template)
+    };
+    if !dir.is_dir() {
+        return Err(format!("Template not found: {}", template));
+    }
+    Ok(dir)
+}
+
+/// Keys, env files and OS clutter that never belong in a shared starter
+fn is_private_file(name: &str) -> bool {
+    let file = name.rsplit('/').next().unwrap_or(name).to_ascii_lowercase();
+    file == ".env"
+        || file.starts_with(".env.")
+        || file == ".ds_store"
+        || file == "thumbs.db"
+        || [".pem", ".key", ".p12", ".pfx", ".promptplay"].iter().any(|ext| file.ends_with(ext))
+}
+
+pub fn is_text_file(name: &str) -> bool {
+    Path::new(name)
+        .extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| TEXT_EXTENSIONS.contains(&e.to_ascii_lowercase().as_str()))
+}
+
+/// The user's home folder, which shows up in absolute paths a template shouldn't carry
+pub fn home_dir() -> Option<String> {
+    std::env::var("HOME")
+        .or_else(|_| std::env::var("USERPROFILE"))
+        .ok()
+        .filter(|home| home.len() > 1)
+}
+
+/// Swaps project-specific text for placeholders and project-relative paths
+struct Parameterizer {
+    project_name: String,
+    /// The project root as forward-slash and native strings, longest first
+    roots: Vec<String>,
+}
+
+impl Parameterizer {
+    fn new(project: &Path, project_name: &str) -> Self {
+        let mut roots: Vec<String> = [paths::to_display(project), project.to_string_lossy().to_string()]
+            .into_iter()
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect();
+        roots.sort_by_key(|root| std::cmp::Reverse(root.len()));
+        Self {
+            project_name: project_name.to_string(),
+            roots,
+        }
+    }
+
+    fn text(&self, text: &str) -> String {
+        let mut text = text.to_string();
+        for root in &self.roots {
+            text = text.replace(&format!("{}/", root), "").replace(&format!("{}\\", root), "").replace(root, ".");
+        }
+        // Very short names ("a", "go") would replace unrelated text
+        if self.project_name.chars().count() >= 3 {
+            text = text.replace(&self.project_name, "{{projectName}}");
+        }
+        text
+    }
+
+    fn value(&self, value: &mut Value) {
+        match value {
+            Value::String(s) => *s = self.text(s),
+            Value::Array(items) => items.iter_mut().for_each(|item| self.value(item)),
+            Value::Object(map) => map.values_mut().for_each(|item| self.value(item)),
+            _ => {}
+        }
+    }
+}
+
+/// Remove author fields from the spec's metadata, returning what was removed
+pub fn strip_personal_metadata(spec: &mut Value) -> Vec<String> {
+    let Some(metadata) = spec.get_mut("metadata").and_then(Value::as_object_mut) else {
+        return Vec::new();
+    };
+    PERSONAL_METADATA
+        .iter()
+        .filter(|key| metadata.remove(**key).is_some())
+        .map(|key| format!("metadata.{}", key))
+        .collect()
+}
+
+/// `{{name}}` placeholders in `text`
+fn placeholders(text: &str) -> Vec<String> {
+    text.split("{{")
+        .skip(1)
+        .filter_map(|rest| rest.split_once("}}").map(|(name, _)| name.trim().to_string()))
+        .collect()
+}
+
+/// Email addresses, which usually mean contact details were left in
+fn contains_email(text: &str) -> bool {
+    text.split(|c: char| c.is_whitespace() || "\"'<>(),;".contains(c)).any(|word| {
+        word.split_once('@').is_some_and(|(user, domain)| {
+            !user.is_empty() && domain.contains('.') && !domain.starts_with('.') && !domain.ends_with('.')
+        })
+    })
+}
+
+fn check_text(name: &str, text: &str, diagnostics: &mut Vec<Diagnostic>) {
+    for placeholder in placeholders(text) {
+        if !PARAMETERS.contains(&placeholder.as_str()) {
+            diagnostics.push(Diagnostic::error(
+                name,
+                format!("Unknown placeholder {{{{{}}}}}; templates may use {}", placeholder, PARAMETERS.join(", ")),
+            ));
+        }
+    }
+    if let Some(home) = home_dir().filter(|home| text.contains(home.as_str())) {
+        diagnostics.push(Diagnostic::error(
+            name,
+            format!("Contains a path inside your home folder ({}); use project-relative paths", home),
+        ));
+    }
+    if contains_email(text) {
+        diagnostics.push(Diagnostic::warning(name, "Contains what looks like an email address"));
+    }
+}
+
+/// Check a template folder: manifest, listed files, the spec, and leftover personal data
+pub fn validate(dir: &Path) -> TemplateValidation {
+    let mut diagnostics = Vec::new();
+    let manifest_path = dir.join(MANIFEST_FILE);
+    let manifest: Option<TemplateManifest> = match fs::read(&manifest_path) {
+        Ok(bytes) => match json_guard::parse(&bytes, MANIFEST_FILE, &json_guard::METADATA_LIMITS) {
+            Ok(manifest) => Some(manifest),
+            Err(e) => {
+                diagnostics.push(Diagnostic::error(MANIFEST_FILE, e));
+                None
+            }
+        },
+        Err(e) => {
+            diagnostics.push(Diagnostic::error(MANIFEST_FILE, format!("Failed to read {}: {}", MANIFEST_FILE, e)));
+            None
+        }
+    };
+
+    if let Some(manifest) = &manifest {
+        if !deep_link::is_template_id(&manifest.id) {
+            diagnostics.push(Diagnostic::error(
+                MANIFEST_FILE,
+                format!("'{}' is not a template id; use letters, digits, '-' and '_'", manifest.id),
+            ));
+        }
+        if manifest.name.trim().is_empty() {
+            diagnostics.push(Diagnostic::error(MANIFEST_FILE, "Template has no name"));
+        }
+        if !manifest.files.iter().any(|f| f == "game.json") {
+            diagnostics.push(Diagnostic::error(MANIFEST_FILE, "game.json is not listed in files"));
+        }
+        for parameter in manifest.parameters.iter().filter(|p| !PARAMETERS.contains(&p.as_str())) {
+            diagnostics.push(Diagnostic::error(MANIFEST_FILE, format!("Unknown parameter '{}'", parameter)));
+        }
+
+        for name in &manifest.files {
+            let relative = Path::new(name);
+            if relative.is_absolute() || relative.components().any(|c| matches!(c, std::path::Component::ParentDir)) {
+                diagnostics.push(Diagnostic::error(name, "File paths must stay inside the template"));
+                continue;
+            }
+            let path = dir.join(relative);
+            if !path.is_file() {
+                diagnostics.push(Diagnostic::error(name, "Listed in template.json but missing"));
+                continue;
+            }
+            if is_private_file(name) {
+                diagnostics.push(Diagnostic::error(name, "Private files such as keys and .env files can't be shared"));
+            }
+            if is_text_file(name) {
+                if let Ok(text) = fs::read_to_string(&path) {
+                    check_text(name, &text, &mut diagnostics);
+                }
+            }
+        }
+
+        let listed: BTreeSet<&str> = manifest.files.iter().map(String::as_str).collect();
+        for (_, name) in template_files(dir) {
+            if name != MANIFEST_FILE && !listed.contains(name.as_str()) {
+                diagnostics.push(Diagnostic::warning(&name, "Not listed in template.json, so it won't be installed"));
+            }
+        }
+    }
+
+    match spec::read_spec_file(&dir.join("game.json")) {
+        Ok(spec) => {
+            if let Some(metadata) = spec.get("metadata").and_then(Value::as_object) {
+                for key in PERSONAL_METADATA.iter().filter(|key| metadata.contains_key(**key)) {
+                    diagnostics.push(Diagnostic::warning(
+                        format!("game.json#/metadata/{}", key),
+                        "Author details are shared with everyone who installs the template",
+                    ));
+                }
+            }
+            diagnostics.extend(validation::validate_spec(&spec).into_iter().map(|d| Diagnostic {
+                path: format!("game.json#{}", d.path),
+                ..d
+            }));
+        }
+        Err(e) => diagnostics.push(Diagnostic::error("game.json", e)),
+    }
+
+    TemplateValidation {
+        valid: !diagnostics.iter().any(|d| d.severity == Severity::Error),
+        manifest,
+        diagnostics,
+    }
+}
+
+/// Every file under `dir` as (absolute path, forward-slash relative name)
+fn template_files(dir: &Path) -> Vec<(PathBuf, String)> {
+    let mut files = Vec::new();
+    let mut pending = vec![dir.to_path_buf()];
+    while let Some(current) = pending.pop() {
+        for entry in fs::read_dir(&current).into_iter().flatten().flatten() {
+            let path = entry.path();
+            match entry.file_type() {
+                Ok(kind) if kind.is_dir() => pending.push(path),
+                Ok(kind) if kind.is_file() => {
+                    if let Some(relative) = paths::relative_to(&path, dir) {
+                        files.push((path, paths::to_spec_path(&relative)));
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+    files.sort_by(|a, b| a.1.cmp(&b.1));
+    files
+}
+
+fn write_template(
+    app: &AppHandle,
+    project: &Path,
+    target: &Path,
+    options: &TemplateOptions,
+    id: String,
+) -> Result<(TemplateManifest, Vec<String>), String> {
+    let mut game_spec = spec::read_spec_file(&project.join("game.json"))?;
+    let project_name = game_spec
+        .pointer("/metadata/title")
+        .and_then(Value::as_str)
+        .map(str::to_string)
+        .or_else(|| project.file_name().map(|n| n.to_string_lossy().to_string()))
+        .unwrap_or_default();
+    let parameterizer = Parameterizer::new(project, &project_name);
+
+    let mut stripped = strip_personal_metadata(&mut game_spec);
+    parameterizer.value(&mut game_spec);
+    if let Some(metadata) = game_spec.get_mut("metadata").and_then(Value::as_object_mut) {
+        metadata.insert("title".to_string(), Value::String("{{projectName}}".to_string()));
+        if !options.description.is_empty() {
+            metadata.insert("description".to_string(), Value::String(options.description.clone()));
+        }
+    }
+    let genre = game_spec.pointer("/metadata/genre").and_then(Value::as_str).map(str::to_string);
+    let content = serde_json::to_string_pretty(&game_spec).map_err(|e| format!("Failed to serialize game spec: {}", e))?;
+    fs::write(target.join("game.json"), content).map_err(|e| format!("Failed to write game.json: {}", e))?;
+
+    let mut files = vec!["game.json".to_string()];
+    if !options.spec_only {
+        for (path, name) in backup::project_files(app, project)? {
+            if name == "game.json" {
+                continue;
+            }
+            if is_private_file(&name) {
+                stripped.push(name);
+                continue;
+            }
+            let destination = target.join(&name);
+            if let Some(parent) = destination.parent() {
+                fs::create_dir_all(parent).map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+            }
+            let text = is_text_file(&name).then(|| fs::read_to_string(&path).ok()).flatten();
+            match text {
+                Some(text) => fs::write(&destination, parameterizer.text(&text)),
+                None => fs::copy(&path, &destination).map(|_| ()),
+            }
+            .map_err(|e| format!("Failed to copy {}: {}", name, e))?;
+            files.push(name);
+        }
+    }
+
+    let manifest = TemplateManifest {
+        id,
+        name: options.name.trim().to_string(),
+        description: options.description.clone(),
+        genre,
+        tags: options.tags.clone(),
+        parameters: PARAMETERS.iter().map(|p| p.to_string()).collect(),
+        files,
+        created_at: now_millis(),
+        version: options.version.clone().filter(|v| !v.trim().is_empty()).unwrap_or_else(default_version),
+    };
+    let content = serde_json::to_string_pretty(&manifest).map_err(|e| format!("Failed to serialize manifest: {}", e))?;
+    fs::write(target.join(MANIFEST_FILE), content).map_err(|e| format!("Failed to write {}: {}", MANIFEST_FILE, e))?;
+    Ok((manifest, stripped))
+}
+
+/// Turn a project into a shareable template in the template registry: its name becomes
+/// `{{projectName}}`, absolute project paths become relative, and author details, keys and
+/// .env files are left out. The result is validated before it's returned.
+#[tauri::command]
+pub async fn create_template_from_project(
+    app: AppHandle,
+    project_path: String,
+    options: TemplateOptions,
+) -> Result<CreatedTemplate, String> {
+    if options.name.trim().is_empty() {
+        return Err("Templates need a name".to_string());
+    }
+    let id = options.id.clone().unwrap_or_else(|| slug(&options.name));
+    if !deep_link::is_template_id(&id) {
+        return Err(format!("'{}' is not a template id; use letters, digits, '-' and '_'", id));
+    }
+
+    let project = paths::project_root(&project_path);
+    let root = templates_dir(&app)?;
+    let target = root.join(&id);
+    if target.exists() {
+        return Err(format!("A template named '{}' already exists", id));
+    }
+    // Build next to the registry and rename, so a failure leaves no half-written template
+    let partial = root.join(format!(".{}.partial", id));
+    let _ = fs::remove_dir_all(&partial);
+    fs::create_dir_all(&partial).map_err(|e| format!("Failed to create template folder: {}", e))?;
+    let (manifest, stripped) = match write_template(&app, &project, &partial, &options, id) {
+        Ok(written) => written,
+        Err(e) => {
+            let _ = fs::remove_dir_all(&partial);
+            return Err(e);
+        }
+    };
+    fs::rename(&partial, &target).map_err(|e| format!("Failed to finish template: {}", e))?;
+
+    Ok(CreatedTemplate {
+        path: paths::to_display(&target),
+        manifest,
+        stripped,
+        diagnostics: validate(&target).diagnostics,
+    })
+}
+
+/// Check a template folder, or a template in the registry by id, before it's shared or installed
+#[tauri::command]
+pub async fn validate_template(app: AppHandle, template: String) -> Result<TemplateValidation, String> {
+    Ok(validate(&template_dir(&app, &template)?))
+}
@@ -0,0 +1,264 @@
+use crate::assets::{AssetKind, AssetManifest};
+use crate::cache::{self, CacheCategory};
+use crate::{spec, world};
+use base64::Engine;
+use image::imageops::{self, FilterType};
+use image::{ImageFormat, Rgba, RgbaImage};
+use serde::Serialize;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fs;
+use std::io::Cursor;
+use std::path::PathBuf;
+use tauri::AppHandle;
+
+/// Asset thumbnails, which the asset manifest references, stay in the project; scene thumbnails
+/// are rendered into the app cache
+pub const THUMBNAIL_DIR: &str = ".promptplay/thumbnails";
+pub const DEFAULT_THUMBNAIL_WIDTH: u32 = 320;
+/// Largest side of a rendered scene; tall worlds are scaled down to fit instead of growing the image
+pub const MAX_RENDER_SIZE: u32 = 2048;
+/// The runtime's canvas background
+pub const BACKGROUND: Rgba<u8> = Rgba([0x1a, 0x1a, 0x2e, 0xff]);
+const DEFAULT_TINT: u32 = 0x808080;
+
+#[derive(Debug, Serialize)]
+pub struct Thumbnail {
+    pub path: String,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// In-memory render of a spec that may not be saved yet
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpecSnapshot {
+    /// PNG as a data URL, ready for an <img> or diff view
+    pub data_url: String,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Sprite tint as RGBA; accepts 0xRRGGBB numbers and "#rrggbb"/"#rgb" strings like the runtime
+pub fn parse_tint(tint: Option<&Value>) -> Rgba<u8> {
+    let rgb = match tint {
+        Some(Value::Number(n)) => n.as_u64().map(|n| n as u32),
+        Some(Value::String(s)) => {
+            let hex = s.trim_start_matches('#');
+            match hex.len() {
+                6 => u32::from_str_radix(hex, 16).ok(),
+                3 => u32::from_str_radix(
+                    &hex.chars().flat_map(|c| [c, c]).collect::<String>(),
+                    16,
+                )
+                .ok(),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+    .unwrap_or(DEFAULT_TINT);
+
+    Rgba([(rgb >> 16) as u8, (rgb >> 8) as u8, rgb as u8, 0xff])
+}
+
+/// Fill an axis-aligned rectangle, clipped to the image
+pub fn fill_rect(image: &mut RgbaImage, x: i64, y: i64, width: i64, height: i64, color: Rgba<u8>) {
+    let x0 = x.max(0);
+    let y0 = y.max(0);
+    let x1 = x.saturating_add(width).min(image.width() as i64);
+    let y1 = y.saturating_add(height).min(image.height() as i64);
+    for py in y0..y1 {
+        for px in x0..x1 {
+            image.put_pixel(px as u32, py as u32, color);
+        }
+    }
+}
+
+/// Entities of one scene (None for the legacy top-level list)
+pub fn scene_entities<'a>(game_spec: &'a Value, scene: Option<&str>) -> Vec<&'a Value> {
+    spec::entities_with_scene(game_spec)
+        .into_iter()
+        .filter(|(entity_scene, _)| *entity_scene == scene)
+        .map(|(_, entity)| entity)
+        .collect()
+}
+
+/// Load a sprite texture from the project, resolving bare names through assets.json
+pub fn load_texture(project_path: &str, manifest: &AssetManifest, texture: &str) -> Option<RgbaImage> {
+    let relative = match manifest.resolve(texture) {
+        Some(entry) if entry.kind == AssetKind::Image => entry.path.clone(),
+        Some(_) => return None,
+        None => texture.to_string(),
+    };
+    let path = PathBuf::from(project_path).join(relative);
+    if AssetKind::from_path(&path) != AssetKind::Image || !path.is_file() {
+        return None;
+    }
+    image::open(&path).ok().map(|img| img.to_rgba8())
+}
+
+/// Rasterize a top-down composite of a scene's sprites at their transforms.
+/// Rotation is ignored; sprites without a loadable texture (or without a project to load it from)
+/// are drawn as tinted boxes, as are sprites too large to resample.
+/// Neither side of the image exceeds MAX_RENDER_SIZE, so a world taller than wide comes out narrower
+/// than `width`.
+pub fn render_scene(project_path: Option<&str>, game_spec: &Value, scene: Option<&str>, width: u32) -> RgbaImage {
+    let bounds = world::world_bounds(game_spec);
+    let max = MAX_RENDER_SIZE as f64;
+    let scale = (width.min(MAX_RENDER_SIZE) as f64 / bounds.width).min(max / bounds.height);
+    let width = ((bounds.width * scale).round() as u32).clamp(1, MAX_RENDER_SIZE);
+    let height = ((bounds.height * scale).round() as u32).clamp(1, MAX_RENDER_SIZE);
+    let mut image = RgbaImage::from_pixel(width, height, BACKGROUND);
+
+    let manifest = project_path
+        .and_then(|path| AssetManifest::load(path).ok())
+        .unwrap_or_default();
+    let mut textures: HashMap<String, Option<RgbaImage>> = HashMap::new();
+
+    let mut entities: Vec<&Value> = scene_entities(game_spec, scene)
+        .into_iter()
+        .filter(|e| e.pointer("/components/sprite").is_some())
+        .filter(|e| e.pointer("/components/sprite/visible") != Some(&Value::Bool(false)))
+        .collect();
+    entities.sort_by_key(|e| {
+        e.pointer("/components/sprite/zIndex")
+            .and_then(Value::as_i64)
+            .unwrap_or(0)
+    });
+
+    for entity in entities {
+        let number = |pointer: &str, default: f64| {
+            entity.pointer(pointer).and_then(Value::as_f64).unwrap_or(default)
+        };
+        let w = number("/components/sprite/width", 32.0) * number("/components/transform/scaleX", 1.0).abs();
+        let h = number("/components/sprite/height", 32.0) * number("/components/transform/scaleY", 1.0).abs();
+        // Transforms are sprite centers, as in the runtime
+        let left = ((number("/components/transform/x", 0.0) - w / 2.0 - bounds.x) * scale).round() as i64;
+        let top = ((number("/components/transform/y", 0.0) - h / 2.0 - bounds.y) * scale).round() as i64;
+        let w = ((w * scale).round() as i64).max(1);
+        let h = ((h * scale).round() as i64).max(1);
+
+        let texture = entity
+            .pointer("/components/sprite/texture")
+            .and_then(Value::as_str)
+            .filter(|t| !t.is_empty())
+            .zip(project_path)
+            .and_then(|(t, project_path)| {
+                textures
+                    .entry(t.to_string())
+                    .or_insert_with(|| load_texture(project_path, &manifest, t))
+                    .as_ref()
+            });
+
+        // Resampling allocates the whole sprite, most of which would fall outside the image
+        let resizable = w <= MAX_RENDER_SIZE as i64 * 2 && h <= MAX_RENDER_SIZE as i64 * 2;
+        match texture.filter(|_| resizable) {
+            Some(texture) => {
+                let sprite = imageops::resize(texture, w as u32, h as u32, FilterType::Triangle);
+                imageops::overlay(&mut image, &sprite, left, top);
+            }
+            None => fill_rect(
+                &mut image,
+                left,
+                top,
+                w,
+                h,
+                parse_tint(entity.pointer("/components/sprite/tint")),
+            ),
+        }
+    }
+
+    image
+}
+
+/// The scene a snapshot shows by default: the spec's active scene, else the top-level list
+pub fn default_scene(game_spec: &Value) -> Option<&str> {
+    game_spec
+        .get("activeScene")
+        .and_then(Value::as_str)
+        .filter(|id| spec::scene_exists(game_spec, id))
+}
+
+/// Encode an image as base64 PNG
+pub fn encode_png(image: &RgbaImage) -> Result<String, String> {
+    let mut bytes = Vec::new();
+    image
+        .write_to(&mut Cursor::new(&mut bytes), ImageFormat::Png)
+        .map_err(|e| format!("Failed to encode snapshot: {}", e))?;
+    Ok(base64::engine::general_purpose::STANDARD.encode(bytes))
+}
+
+/// File-safe stem for per-scene outputs; the legacy top-level list is "main". A scene id that had
+/// to be sanitized, or is itself "main", gets a hash suffix so it can't share a file with another
+/// scene or the top-level list.
+pub fn scene_file_stem(scene: Option<&str>) -> String {
+    let Some(id) = scene else {
+        return "main".to_string();
+    };
+    let stem: String = id
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+        .collect();
+    if stem == id && id != "main" {
+        return stem;
+    }
+    let digest = Sha256::digest(id.as_bytes());
+    format!("{}-{}", stem, digest[..4].iter().map(|b| format!("{:02x}", b)).collect::<String>())
+}
+
+/// Render a PNG thumbnail of a scene into the thumbnail cache for the scene picker and recent projects
+#[tauri::command]
+pub async fn render_scene_thumbnail(
+    app: AppHandle,
+    project_path: String,
+    scene: Option<String>,
+    width: Option<u32>,
+) -> Result<Thumbnail, String> {
+    let game_spec = spec::load_project_spec(&project_path)?;
+    if let Some(scene_id) = scene.as_deref().filter(|id| !spec::scene_exists(&game_spec, id)) {
+        return Err(format!("Scene '{}' not found", scene_id));
+    }
+
+    let width = width.unwrap_or(DEFAULT_THUMBNAIL_WIDTH).clamp(16, 2048);
+    let image = render_scene(Some(&project_path), &game_spec, scene.as_deref(), width);
+
+    let dir = cache::dir(&app, CacheCategory::Thumbnails)?.join(cache::project_key(&project_path));
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create thumbnail directory: {}", e))?;
+    let path = dir.join(format!("{}.png", scene_file_stem(scene.as_deref())));
+    image
+        .save_with_format(&path, ImageFormat::Png)
+        .map_err(|e| format!("Failed to write thumbnail: {}", e))?;
+
+    Ok(Thumbnail {
+        path: path.to_string_lossy().to_string(),
+        width: image.width(),
+        height: image.height(),
+    })
+}
+
+/// Render the level layout of an in-editor spec without touching disk, for AI request context and
+/// before/after images of AI patches. The scene defaults to the spec's active scene; without a
+/// project path sprites are drawn as tinted boxes.
+#[tauri::command]
+pub async fn render_spec_snapshot(
+    project_path: Option<String>,
+    spec: Value,
+    scene: Option<String>,
+    width: Option<u32>,
+) -> Result<SpecSnapshot, String> {
+    if let Some(scene_id) = scene.as_deref().filter(|id| !spec::scene_exists(&spec, id)) {
+        return Err(format!("Scene '{}' not found", scene_id));
+    }
+
+    let width = width.unwrap_or(DEFAULT_THUMBNAIL_WIDTH).clamp(16, 2048);
+    let scene = scene.as_deref().or_else(|| default_scene(&spec));
+    let image = render_scene(project_path.as_deref(), &spec, scene, width);
+
+    Ok(SpecSnapshot {
+        data_url: format!("data:image/png;base64,{}", encode_png(&image)?),
+        width: image.width(),
+        height: image.height(),
+    })
+}
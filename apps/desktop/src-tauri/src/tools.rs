@@ -0,0 +1,651 @@
+use crate::cache::{self, CacheCategory};
+use crate::paths;
+use crate::permissions::{self, Operation};
+use serde::{Deserialize, Serialize};
+use std::io::Read;
+use std::path::{Component, Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+use tauri::AppHandle;
+
+const MAX_ARGS: usize = 64;
+const MAX_ARG_CHARS: usize = 4096;
+/// Output kept per stream; the rest is dropped and the run is marked truncated
+const MAX_OUTPUT_BYTES: u64 = 1024 * 1024;
+/// Uploads can be slow, but nothing should run unattended forever
+const TOOL_TIMEOUT: Duration = Duration::from_secs(15 * 60);
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// External programs the backend will run; anything else is refused
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ToolId {
+    /// itch.io's uploader
+    Butler,
+    Git,
+    /// oxipng, for shrinking PNG assets
+    ImageOptimizer,
+}
+
+/// What an argument must look like
+#[derive(Clone, Copy)]
+enum Value {
+    Number,
+    /// Free text such as a commit message
+    Text,
+    /// A branch, remote, tag or revision
+    Ref,
+    /// A file or folder inside the project
+    ProjectPath,
+    /// An https:// remote URL; other transports can run local commands
+    RemoteUrl,
+    /// A folder inside the project or the export cache
+    UploadDir,
+    PngPath,
+    /// `user/game:channel`
+    ButlerTarget,
+    Choice(&'static [&'static str]),
+}
+
+/// A subcommand and the only options and operands it accepts
+struct Subcommand {
+    /// Empty for tools without subcommands
+    name: &'static str,
+    /// Always passed right after the subcommand
+    fixed: &'static [&'static str],
+    flags: &'static [&'static str],
+    /// Options that take a value, as `--opt value` or `--opt=value`
+    valued: &'static [(&'static str, Value)],
+    /// Positional operands in order; the last kind repeats when `variadic`
+    operands: &'static [Value],
+    variadic: bool,
+    /// Changes something outside the project (pushes, uploads), so the user is asked first
+    needs_consent: bool,
+}
+
+impl Subcommand {
+    const fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            fixed: &[],
+            flags: &[],
+            valued: &[],
+            operands: &[],
+            variadic: false,
+            needs_consent: false,
+        }
+    }
+}
+
+struct Tool {
+    binary: &'static str,
+    /// Passed before the user's arguments
+    fixed_args: &'static [&'static str],
+    subcommands: &'static [Subcommand],
+}
+
+const GIT_HOOKS_OFF: &str = if cfg!(windows) { "core.hooksPath=NUL" } else { "core.hooksPath=/dev/null" };
+
+/// Diff drivers and textconv filters come from the project's own config and attributes
+const NO_DIFF_PROGRAMS: &[&str] = &["--no-ext-diff", "--no-textconv"];
+
+const GIT: Tool = Tool {
+    binary: "git",
+    // A project from someone else can carry config that makes git run its commands: hooks, an
+    // fsmonitor, an ssh, askpass, gpg or credential program, an editor, or ext:: and local
+    // remotes that start a process. Filters and merge drivers have project-chosen names and are
+    // switched off per run.
+    fixed_args: &[
+        "-c",
+        GIT_HOOKS_OFF,
+        "-c",
+        "core.fsmonitor=false",
+        "-c",
+        "core.sshCommand=ssh",
+        "-c",
+        "core.askPass=",
+        "-c",
+        "protocol.ext.allow=never",
+        "-c",
+        "protocol.file.allow=never",
+        "-c",
+        "commit.gpgSign=false",
+        "-c",
+        "tag.gpgSign=false",
+        "-c",
+        "log.showSignature=false",
+        "-c",
+        "credential.helper=",
+        "-c",
+        "core.editor=true",
+        "-c",
+        "sequence.editor=true",
+    ],
+    subcommands: &[
+        Subcommand {
+            flags: &["--short", "--branch", "--porcelain", "--ignore-submodules"],
+            ..Subcommand::new("status")
+        },
+        Subcommand {
+            fixed: NO_DIFF_PROGRAMS,
+            flags: &["--oneline", "--stat", "--no-ext-diff", "--no-textconv"],
+            valued: &[("-n", Value::Number), ("--max-count", Value::Number)],
+            operands: &[Value::Ref],
+            ..Subcommand::new("log")
+        },
+        Subcommand {
+            fixed: NO_DIFF_PROGRAMS,
+            flags: &["--stat", "--cached", "--name-only", "--no-ext-diff", "--no-textconv"],
+            operands: &[Value::ProjectPath],
+            variadic: true,
+            ..Subcommand::new("diff")
+        },
+        Subcommand {
+            flags: &["-A", "--all"],
+            operands: &[Value::ProjectPath],
+            variadic: true,
+            needs_consent: true,
+            ..Subcommand::new("add")
+        },
+        Subcommand {
+            flags: &["--allow-empty"],
+            valued: &[("-m", Value::Text), ("--message", Value::Text)],
+            ..Subcommand::new("commit")
+        },
+        Subcommand {
+            flags: &["--prune"],
+            operands: &[Value::Ref],
+            needs_consent: true,
+            ..Subcommand::new("fetch")
+        },
+        Subcommand {
+            flags: &["--ff-only", "--rebase"],
+            operands: &[Value::Ref, Value::Ref],
+            needs_consent: true,
+            ..Subcommand::new("pull")
+        },
+        Subcommand {
+            flags: &["-u", "--set-upstream", "--tags"],
+            operands: &[Value::Ref, Value::Ref],
+            needs_consent: true,
+            ..Subcommand::new("push")
+        },
+        Subcommand::new("init"),
+        Subcommand {
+            flags: &["--verify"],
+            operands: &[Value::Ref],
+            ..Subcommand::new("rev-parse")
+        },
+        Subcommand {
+            flags: &["-M"],
+            operands: &[Value::Ref],
+            ..Subcommand::new("branch")
+        },
+        Subcommand {
+            operands: &[Value::Choice(&["user.name", "user.email"])],
+            ..Subcommand::new("config")
+        },
+        Subcommand {
+            operands: &[Value::Choice(&["get-url", "set-url", "add"]), Value::Ref, Value::RemoteUrl],
+            needs_consent: true,
+            ..Subcommand::new("remote")
+        },
+    ],
+};
+
+const BUTLER: Tool = Tool {
+    binary: "butler",
+    fixed_args: &[],
+    subcommands: &[
+        Subcommand {
+            flags: &["--if-changed"],
+            valued: &[("--userversion", Value::Ref)],
+            operands: &[Value::UploadDir, Value::ButlerTarget],
+            needs_consent: true,
+            ..Subcommand::new("push")
+        },
+        Subcommand {
+            operands: &[Value::ButlerTarget],
+            ..Subcommand::new("status")
+        },
+        Subcommand::new("version"),
+    ],
+};
+
+const IMAGE_OPTIMIZER: Tool = Tool {
+    binary: "oxipng",
+    fixed_args: &[],
+    subcommands: &[Subcommand {
+        flags: &["--quiet"],
+        valued: &[
+            ("-o", Value::Choice(&["0", "1", "2", "3", "4", "5", "6", "max"])),
+            ("--strip", Value::Choice(&["safe", "all"])),
+        ],
+        operands: &[Value::PngPath],
+        variadic: true,
+        ..Subcommand::new("")
+    }],
+};
+
+impl ToolId {
+    fn tool(self) -> &'static Tool {
+        match self {
+            ToolId::Butler => &BUTLER,
+            ToolId::Git => &GIT,
+            ToolId::ImageOptimizer => &IMAGE_OPTIMIZER,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ToolRun {
+    pub tool: ToolId,
+    pub exit_code: Option<i32>,
+    pub stdout: String,
+    pub stderr: String,
+    pub duration_ms: u64,
+    /// Output went past the limit and was cut off
+    pub truncated: bool,
+}
+
+/// Arguments that passed validation, ready to hand to the program
+struct Validated {
+    args: Vec<String>,
+    needs_consent: bool,
+}
+
+/// Whether `path` resolves inside `root`, following symlinks for the parts that exist
+fn inside(root: &Path, path: &Path) -> bool {
+    if path.components().any(|c| matches!(c, Component::ParentDir)) {
+        return false;
+    }
+    let candidate = if path.is_absolute() { path.to_path_buf() } else { root.join(path) };
+    paths::relative_to(&paths::canonical(&candidate), &paths::canonical(root)).is_some()
+}
+
+fn check_value(exports: Option<&Path>, project: &Path, kind: Value, value: &str) -> Result<(), String> {
+    let ok = match kind {
+        Value::Number => !value.is_empty() && value.len() <= 10 && value.chars().all(|c| c.is_ascii_digit()),
+        Value::Text => true,
+        Value::Ref => {
+            !value.is_empty()
+                && !value.starts_with('-')
+                && !value.contains("..")
+                && value.chars().all(|c| c.is_ascii_alphanumeric() || "._/-~^@".contains(c))
+        }
+        Value::ProjectPath => !value.starts_with('-') && inside(project, Path::new(value)),
+        Value::RemoteUrl => {
+            value.starts_with("https://") && !value.chars().any(|c| c.is_whitespace() || c.is_control())
+        }
+        Value::UploadDir => {
+            let path = Path::new(value);
+            !value.starts_with('-')
+                && (inside(project, path) || exports.is_some_and(|exports| inside(exports, path)))
+        }
+        Value::PngPath => {
+            !value.starts_with('-')
+                && value.to_ascii_lowercase().ends_with(".png")
+                && inside(project, Path::new(value))
+        }
+        Value::ButlerTarget => value.split_once('/').is_some_and(|(user, rest)| {
+            let slug = |s: &str| !s.is_empty() && s.chars().all(|c| c.is_ascii_alphanumeric() || "-_.".contains(c));
+            rest.split_once(':').is_some_and(|(game, channel)| slug(user) && slug(game) && slug(channel))
+        }),
+        Value::Choice(options) => options.contains(&value),
+    };
+    if ok {
+        Ok(())
+    } else {
+        Err(format!("'{}' isn't an allowed argument here", value))
+    }
+}
+
+/// Check `args` against the tool's allowlist; `exports` is the export cache uploads may come from
+fn validate(exports: Option<&Path>, project: &Path, tool_id: ToolId, args: &[String]) -> Result<Validated, String> {
+    let tool = tool_id.tool();
+    if args.len() > MAX_ARGS {
+        return Err(format!("At most {} arguments may be passed to {}", MAX_ARGS, tool.binary));
+    }
+    if let Some(bad) = args.iter().find(|a| a.contains('\0') || a.chars().count() > MAX_ARG_CHARS) {
+        return Err(format!("Argument is too long or contains a NUL byte: {:.40}", bad));
+    }
+
+    let mut rest = args.iter();
+    let subcommand = match tool.subcommands {
+        [only] if only.name.is_empty() => only,
+        subcommands => {
+            let name = rest.next().ok_or_else(|| format!("{} needs a subcommand", tool.binary))?;
+            subcommands.iter().find(|s| s.name == name).ok_or_else(|| {
+                let allowed: Vec<&str> = subcommands.iter().map(|s| s.name).collect();
+                format!("{} {} isn't allowed; allowed: {}", tool.binary, name, allowed.join(", "))
+            })?
+        }
+    };
+    let command = if subcommand.name.is_empty() {
+        tool.binary.to_string()
+    } else {
+        format!("{} {}", tool.binary, subcommand.name)
+    };
+
+    let mut operands = 0;
+    let mut options_done = false;
+    while let Some(arg) = rest.next() {
+        if !options_done && arg == "--" {
+            options_done = true;
+            continue;
+        }
+        if !options_done && arg.starts_with('-') {
+            if subcommand.flags.contains(&arg.as_str()) {
+                continue;
+            }
+            let (name, inline) = match arg.split_once('=') {
+                Some((name, value)) if name.starts_with("--") => (name, Some(value)),
+                _ => (arg.as_str(), None),
+            };
+            let (_, kind) = subcommand
+                .valued
+                .iter()
+                .find(|(option, _)| *option == name)
+                .ok_or_else(|| format!("Option {} isn't allowed for {}", name, command))?;
+            let value = match inline {
+                Some(value) => value,
+                None => rest.next().ok_or_else(|| format!("Option {} needs a value", name))?,
+            };
+            check_value(exports, project, *kind, value)?;
+            continue;
+        }
+        let kind = match subcommand.operands.get(operands) {
+            Some(kind) => *kind,
+            None if subcommand.variadic && !subcommand.operands.is_empty() => {
+                subcommand.operands[subcommand.operands.len() - 1]
+            }
+            None => return Err(format!("Too many arguments for {}", command)),
+        };
+        check_value(exports, project, kind, arg)?;
+        operands += 1;
+    }
+    if subcommand.name == "push" && tool_id == ToolId::Butler && operands < 2 {
+        return Err("butler push needs a folder and a user/game:channel target".to_string());
+    }
+
+    let named = usize::from(!subcommand.name.is_empty());
+    let full = tool
+        .fixed_args
+        .iter()
+        .map(|a| a.to_string())
+        .chain(args[..named].iter().cloned())
+        .chain(subcommand.fixed.iter().map(|a| a.to_string()))
+        .chain(args[named..].iter().cloned())
+        .collect();
+    Ok(Validated {
+        args: full,
+        needs_consent: subcommand.needs_consent,
+    })
+}
+
+/// Read a stream to the output limit on its own thread, so a chatty tool can't block on a full pipe
+fn collect(stream: Option<impl Read + Send + 'static>) -> std::thread::JoinHandle<(String, bool)> {
+    std::thread::spawn(move || {
+        let Some(stream) = stream else {
+            return (String::new(), false);
+        };
+        let mut bytes = Vec::new();
+        let mut limited = stream.take(MAX_OUTPUT_BYTES + 1);
+        let _ = limited.read_to_end(&mut bytes);
+        // Drain the remainder so the child doesn't stall writing to a closed reader
+        let _ = std::io::copy(&mut limited.into_inner(), &mut std::io::sink());
+        let truncated = bytes.len() as u64 > MAX_OUTPUT_BYTES;
+        bytes.truncate(MAX_OUTPUT_BYTES as usize);
+        (String::from_utf8_lossy(&bytes).to_string(), truncated)
+    })
+}
+
+/// Settings that replace a project-defined filter or merge driver with one that runs nothing.
+/// A `false` merge driver just leaves the file as a conflict.
+const GIT_DRIVERS_OFF: &[(&str, &[&str])] = &[
+    ("filter", &["clean=", "smudge=", "process=", "required=false"]),
+    ("merge", &["driver=false"]),
+];
+
+/// `-c` overrides that turn off every clean/smudge filter and merge driver the project's git
+/// config defines. They are named by the project, so they can't be listed in the tool's fixed
+/// arguments. Reading config runs nothing from it.
+fn git_drivers_off(project: &Path) -> Vec<String> {
+    let Ok(output) = Command::new("git")
+        .args(["config", "--name-only", "--get-regexp", r"^(filter|merge)\."])
+        .current_dir(project)
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .stdin(Stdio::null())
+        .output()
+    else {
+        return Vec::new();
+    };
+    let mut overrides: Vec<String> = Vec::new();
+    for key in String::from_utf8_lossy(&output.stdout).lines() {
+        let Some((section, name)) = key
+            .split_once('.')
+            .and_then(|(section, rest)| Some((section, rest.rsplit_once('.')?.0)))
+        else {
+            continue;
+        };
+        let Some((section, settings)) = GIT_DRIVERS_OFF.iter().find(|(s, _)| s.eq_ignore_ascii_case(section)) else {
+            continue;
+        };
+        for setting in settings.iter().map(|setting| format!("{}.{}.{}", section, name, setting)) {
+            if !overrides.contains(&setting) {
+                overrides.push("-c".to_string());
+                overrides.push(setting);
+            }
+        }
+    }
+    overrides
+}
+
+fn run(project: &Path, tool_id: ToolId, args: &[String], env: &[(&str, String)]) -> Result<ToolRun, String> {
+    let tool = tool_id.tool();
+    let started = Instant::now();
+    let mut command = Command::new(tool.binary);
+    if tool_id == ToolId::Git {
+        command.env("GIT_TERMINAL_PROMPT", "0").args(git_drivers_off(project));
+    }
+    let mut child = command
+        .args(args)
+        .envs(env.iter().map(|(key, value)| (*key, value.as_str())))
+        .current_dir(project)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => format!("{} is not installed or not on PATH", tool.binary),
+            _ => format!("Failed to run {}: {}", tool.binary, e),
+        })?;
+    let stdout = collect(child.stdout.take());
+    let stderr = collect(child.stderr.take());
+
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) if started.elapsed() > TOOL_TIMEOUT => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(format!("{} was stopped after {} minutes", tool.binary, TOOL_TIMEOUT.as_secs() / 60));
+            }
+            Ok(None) => std::thread::sleep(POLL_INTERVAL),
+            Err(e) => return Err(format!("Failed to wait for {}: {}", tool.binary, e)),
+        }
+    };
+
+    let (stdout, stdout_truncated) = stdout.join().unwrap_or_default();
+    let (stderr, stderr_truncated) = stderr.join().unwrap_or_default();
+    Ok(ToolRun {
+        tool: tool_id,
+        exit_code: status.code(),
+        stdout,
+        stderr,
+        duration_ms: started.elapsed().as_millis() as u64,
+        truncated: stdout_truncated || stderr_truncated,
+    })
+}
+
+fn exports_dir(app: &AppHandle) -> Option<PathBuf> {
+    cache::dir(app, CacheCategory::Exports).ok()
+}
+
+/// Run a registered tool in the project with allowlisted arguments. Nothing goes through a
+/// shell; pushes and uploads ask the user first, since AI suggestions may trigger runs.
+#[tauri::command]
+pub async fn run_tool(
+    app: AppHandle,
+    project_path: String,
+    tool_id: ToolId,
+    args: Vec<String>,
+) -> Result<ToolRun, String> {
+    let project = paths::project_root(&project_path);
+    let exports = exports_dir(&app);
+    let validated = validate(exports.as_deref(), &project, tool_id, &args)?;
+    if validated.needs_consent {
+        permissions::require(
+            &app,
+            Operation::RunTool,
+            &format!("{} {}", tool_id.tool().binary, args.join(" ")),
+        )?;
+    }
+    run(&project, tool_id, &validated.args, &[])
+}
+
+/// Run a registered tool for an automation hook. Nobody is there to confirm, so subcommands that
+/// would ask for consent are refused rather than prompting.
+pub fn run_unattended(app: &AppHandle, project_path: &str, tool_id: ToolId, args: &[String]) -> Result<ToolRun, String> {
+    let project = paths::project_root(project_path);
+    let exports = exports_dir(app);
+    let validated = validate(exports.as_deref(), &project, tool_id, args)?;
+    if validated.needs_consent {
+        return Err(format!(
+            "{} {} changes things outside the project and can't run from a hook",
+            tool_id.tool().binary,
+            args.join(" ")
+        ));
+    }
+    run(&project, tool_id, &validated.args, &[])
+}
+
+/// Run a registered tool for one of the app's own features, such as publishing to GitHub or the
+/// health check's git status. Arguments go through the same allowlist as `run_tool`; the
+/// feature's command is the user's request, so no consent prompt is shown. `env` passes
+/// credentials without putting them on the command line.
+pub fn run_for_app(project: &Path, tool_id: ToolId, args: &[&str], env: &[(&str, String)]) -> Result<ToolRun, String> {
+    let args: Vec<String> = args.iter().map(|a| a.to_string()).collect();
+    let validated = validate(None, project, tool_id, &args)?;
+    run(project, tool_id, &validated.args, env)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn project() -> PathBuf {
+        let dir = std::env::temp_dir().join("promptplay-tools-validate");
+        std::fs::create_dir_all(&dir).unwrap();
+        paths::canonical(&dir)
+    }
+
+    fn git(args: &[&str]) -> Result<Validated, String> {
+        let args: Vec<String> = args.iter().map(|a| a.to_string()).collect();
+        validate(None, &project(), ToolId::Git, &args)
+    }
+
+    fn has_override(args: &[String], setting: &str) -> bool {
+        args.windows(2).any(|pair| pair[0] == "-c" && pair[1] == setting)
+    }
+
+    #[test]
+    fn git_runs_with_project_programs_off() {
+        let validated = git(&["commit", "-m", "Save"]).unwrap();
+        for setting in [
+            "commit.gpgSign=false",
+            "tag.gpgSign=false",
+            "log.showSignature=false",
+            "credential.helper=",
+            "core.editor=true",
+            "sequence.editor=true",
+            "core.sshCommand=ssh",
+            "protocol.ext.allow=never",
+        ] {
+            assert!(has_override(&validated.args, setting), "missing {}", setting);
+        }
+        assert!(!validated.needs_consent);
+    }
+
+    #[test]
+    fn log_and_diff_never_run_diff_programs() {
+        for subcommand in ["log", "diff"] {
+            let validated = git(&[subcommand]).unwrap();
+            let at = validated.args.iter().position(|a| a == subcommand).unwrap();
+            assert_eq!(&validated.args[at + 1..at + 3], NO_DIFF_PROGRAMS);
+        }
+    }
+
+    #[test]
+    fn network_and_staging_subcommands_need_consent() {
+        for args in [&["push", "origin", "main"][..], &["pull"], &["fetch"], &["add", "-A"]] {
+            assert!(git(args).unwrap().needs_consent, "{:?}", args);
+        }
+    }
+
+    #[test]
+    fn refuses_unlisted_subcommands_and_options() {
+        assert!(git(&["clone", "https://example.com/repo.git"]).is_err());
+        assert!(git(&["-c", "core.pager=sh", "status"]).is_err());
+        assert!(git(&["log", "--output=/tmp/x"]).is_err());
+        assert!(git(&["status", "--untracked-files"]).is_err());
+        assert!(git(&[]).is_err());
+    }
+
+    #[test]
+    fn refuses_bad_operands() {
+        assert!(git(&["log", "--upload-pack=touch"]).is_err());
+        assert!(git(&["log", "main..evil"]).is_err());
+        assert!(git(&["diff", "../outside"]).is_err());
+        assert!(git(&["diff", "/etc/passwd"]).is_err());
+        assert!(git(&["remote", "add", "origin", "ext::sh -c touch"]).is_err());
+        assert!(git(&["remote", "add", "origin", "file:///tmp/repo"]).is_err());
+        assert!(git(&["remote", "add", "origin", "https://example.com/repo.git"]).is_ok());
+        assert!(git(&["config", "core.hooksPath", "hooks"]).is_err());
+        assert!(git(&["log", "-n", "ten"]).is_err());
+    }
+
+    #[test]
+    fn options_before_the_separator_only() {
+        assert!(git(&["diff", "--", "assets"]).is_ok());
+        assert!(git(&["diff", "--", "--output=x"]).is_err());
+        assert!(git(&["commit", "-m"]).is_err());
+        assert!(git(&["commit", "--message=Save"]).is_ok());
+    }
+
+    #[test]
+    fn butler_uploads_need_a_target_and_consent() {
+        let project = project();
+        let push = |args: &[&str]| {
+            let args: Vec<String> = args.iter().map(|a| a.to_string()).collect();
+            validate(None, &project, ToolId::Butler, &args)
+        };
+        assert!(push(&["push", "build"]).is_err());
+        assert!(push(&["push", "build", "user/game"]).is_err());
+        assert!(push(&["push", "build", "user/game:html5"]).unwrap().needs_consent);
+        assert!(push(&["push", "/elsewhere", "user/game:html5"]).is_err());
+    }
+
+    #[test]
+    fn image_optimizer_only_takes_project_pngs() {
+        let project = project();
+        let optimize = |args: &[&str]| {
+            let args: Vec<String> = args.iter().map(|a| a.to_string()).collect();
+            validate(None, &project, ToolId::ImageOptimizer, &args)
+        };
+        assert!(optimize(&["-o", "4", "assets/hero.png"]).is_ok());
+        assert!(optimize(&["assets/hero.jpg"]).is_err());
+        assert!(optimize(&["-o", "9", "assets/hero.png"]).is_err());
+        assert!(optimize(&["../hero.png"]).is_err());
+    }
+}
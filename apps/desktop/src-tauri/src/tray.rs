@@ -0,0 +1,307 @@
+use crate::export::{self, ExportOptions};
+use crate::notifications::{self, NotificationCategory};
+use crate::settings::AppSettings;
+use crate::{deep_link, health, projects, workspace};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tauri::menu::{CheckMenuItem, Menu, MenuEvent, MenuItem, PredefinedMenuItem, Submenu};
+use tauri::tray::TrayIconBuilder;
+use tauri::{AppHandle, Manager, State};
+
+const TRAY_ID: &str = "promptplay";
+/// How often continuous export looks for changes
+const EXPORT_POLL_SECONDS: u64 = 2;
+
+/// Re-export a project to `output_path` whenever it changes (`continuousExport` in the app
+/// settings, so the tray can resume it after a restart)
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ContinuousExport {
+    pub project_path: String,
+    pub output_path: String,
+    #[serde(default)]
+    pub options: ExportOptions,
+}
+
+/// What is running in the background, shown in the tray tooltip and reported to the editor
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct BackgroundStatus {
+    pub continuous_export: bool,
+    pub watching_paused: bool,
+    pub last_export_at: Option<u64>,
+    pub last_export_path: Option<String>,
+    pub last_error: Option<String>,
+}
+
+/// Background work that outlives the main window
+#[derive(Default)]
+pub struct TrayState {
+    watching_paused: AtomicBool,
+    /// Stop flag of the running continuous export
+    export: Mutex<Option<Arc<AtomicBool>>>,
+    status: Mutex<BackgroundStatus>,
+}
+
+impl TrayState {
+    /// File-change events are held back while paused; the search index still updates
+    pub fn watching_paused(&self) -> bool {
+        self.watching_paused.load(Ordering::Relaxed)
+    }
+
+    /// Whether closing the main window should hide it instead, so background work continues
+    pub fn keeps_running(&self) -> bool {
+        self.export.lock().is_ok_and(|export| export.is_some())
+    }
+
+    fn status(&self) -> BackgroundStatus {
+        let mut status = self.status.lock().map(|s| s.clone()).unwrap_or_default();
+        status.continuous_export = self.keeps_running();
+        status.watching_paused = self.watching_paused();
+        status
+    }
+
+    fn update_status(&self, update: impl FnOnce(&mut BackgroundStatus)) {
+        if let Ok(mut status) = self.status.lock() {
+            update(&mut status);
+        }
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+fn project_name(project_path: &str) -> String {
+    Path::new(project_path)
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| project_path.to_string())
+}
+
+fn tooltip(status: &BackgroundStatus) -> String {
+    let mut parts = vec!["PromptPlay".to_string()];
+    if status.continuous_export {
+        parts.push("continuous export on".to_string());
+    }
+    if status.watching_paused {
+        parts.push("watching paused".to_string());
+    }
+    if let Some(error) = &status.last_error {
+        parts.push(format!("last export failed: {}", error));
+    }
+    parts.join(" - ")
+}
+
+fn build_menu(app: &AppHandle) -> tauri::Result<Menu> {
+    let settings = AppSettings::load(app);
+    let status = app.state::<TrayState>().status();
+
+    let recent_items = projects::recent_paths(app)
+        .iter()
+        .enumerate()
+        .map(|(i, path)| MenuItem::with_id(app, format!("tray_recent_{}", i), project_name(path), true, None::<&str>))
+        .collect::<tauri::Result<Vec<_>>>()?;
+    let recent_refs: Vec<&dyn tauri::menu::IsMenuItem> =
+        recent_items.iter().map(|item| item as &dyn tauri::menu::IsMenuItem).collect();
+    let recent = Submenu::with_items(app, "Recent Projects", !recent_items.is_empty(), &recent_refs)?;
+
+    let show = MenuItem::with_id(app, "tray_show", "Show PromptPlay", true, None::<&str>)?;
+    let export_label = match &settings.continuous_export {
+        Some(config) => format!("Continuous Export ({})", project_name(&config.project_path)),
+        None => "Continuous Export".to_string(),
+    };
+    let continuous_export = CheckMenuItem::with_id(
+        app,
+        "tray_continuous_export",
+        export_label,
+        settings.continuous_export.is_some(),
+        status.continuous_export,
+        None::<&str>,
+    )?;
+    let pause_watching =
+        CheckMenuItem::with_id(app, "tray_pause_watching", "Pause Watching", true, status.watching_paused, None::<&str>)?;
+    let quit = MenuItem::with_id(app, "tray_quit", "Quit PromptPlay", true, None::<&str>)?;
+
+    Menu::with_items(
+        app,
+        &[
+            &show,
+            &PredefinedMenuItem::separator(app)?,
+            &recent,
+            &continuous_export,
+            &pause_watching,
+            &PredefinedMenuItem::separator(app)?,
+            &quit,
+        ],
+    )
+}
+
+/// Rebuild the tray menu and tooltip after background state changes
+pub fn refresh(app: &AppHandle) {
+    let Some(tray) = app.tray_by_id(TRAY_ID) else {
+        return;
+    };
+    if let Ok(menu) = build_menu(app) {
+        let _ = tray.set_menu(Some(menu));
+    }
+    let _ = tray.set_tooltip(Some(tooltip(&app.state::<TrayState>().status())));
+}
+
+fn show_main_window(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.show();
+        let _ = window.unminimize();
+        let _ = window.set_focus();
+    }
+}
+
+fn on_menu_event(app: &AppHandle, event: MenuEvent) {
+    let state = app.state::<TrayState>();
+    match event.id().as_ref() {
+        "tray_show" => show_main_window(app),
+        "tray_continuous_export" => {
+            let result = if state.keeps_running() {
+                stop(&state)
+            } else {
+                match AppSettings::load(app).continuous_export {
+                    Some(config) => start(app, config),
+                    None => Ok(()),
+                }
+            };
+            if let Err(error) = result {
+                state.update_status(|status| status.last_error = Some(error));
+            }
+        }
+        "tray_pause_watching" => {
+            state.watching_paused.fetch_xor(true, Ordering::Relaxed);
+        }
+        "tray_quit" => app.exit(0),
+        id => {
+            // Recent projects open through the same queue as "open with" and promptplay:// links
+            if let Some(index) = id.strip_prefix("tray_recent_").and_then(|i| i.parse::<usize>().ok()) {
+                if let Some(path) = projects::recent_paths(app).get(index).cloned() {
+                    show_main_window(app);
+                    deep_link::dispatch(app, [path]);
+                }
+            }
+        }
+    }
+    refresh(app);
+}
+
+/// Create the tray icon; called once from setup
+pub fn setup(app: &AppHandle) -> tauri::Result<()> {
+    let mut builder = TrayIconBuilder::with_id(TRAY_ID)
+        .menu(&build_menu(app)?)
+        .tooltip(tooltip(&app.state::<TrayState>().status()))
+        .on_menu_event(on_menu_event);
+    if let Some(icon) = app.default_window_icon() {
+        builder = builder.icon(icon.clone());
+    }
+    builder.build(app)?;
+    Ok(())
+}
+
+fn export_once(app: &AppHandle, config: &ContinuousExport) -> Result<String, String> {
+    let game_spec_json = crate::spec::load_project_spec(&config.project_path)
+        .and_then(|spec| serde_json::to_string(&spec).map_err(|e| format!("Failed to serialize game spec: {}", e)))?;
+    let spec: Value = serde_json::from_str(&game_spec_json).unwrap_or(Value::Null);
+    let title = spec
+        .pointer("/metadata/title")
+        .and_then(Value::as_str)
+        .map(str::to_string)
+        .unwrap_or_else(|| project_name(&config.project_path));
+    let mut options = config.options.clone();
+    options.project_path.get_or_insert_with(|| config.project_path.clone());
+    tauri::async_runtime::block_on(export::export_html(app, &game_spec_json, config.output_path.clone(), &title, options))
+    .map(|report| report.output_path)
+}
+
+/// Start re-exporting the project whenever it changes, replacing any running continuous export
+fn start(app: &AppHandle, config: ContinuousExport) -> Result<(), String> {
+    let state = app.state::<TrayState>();
+    stop(&state)?;
+    let stop_flag = Arc::new(AtomicBool::new(false));
+    *state.export.lock().map_err(|e| format!("Lock error: {}", e))? = Some(stop_flag.clone());
+
+    let app = app.clone();
+    std::thread::spawn(move || {
+        let project = PathBuf::from(&config.project_path);
+        let mut exported = None;
+        while !stop_flag.load(Ordering::Relaxed) {
+            let stamps = health::fingerprint(&project);
+            if exported.as_ref() != Some(&stamps) && !health::recently_modified(&stamps) {
+                let result = export_once(&app, &config);
+                let state = app.state::<TrayState>();
+                // Notify when exports start failing, not on every retry
+                if let Err(error) = &result {
+                    if state.status().last_error.is_none() {
+                        notifications::task_finished(&app, NotificationCategory::Export, "Continuous export failed", error);
+                    }
+                }
+                state.update_status(|status| match &result {
+                    Ok(path) => {
+                        status.last_export_at = Some(now());
+                        status.last_export_path = Some(path.clone());
+                        status.last_error = None;
+                    }
+                    Err(error) => status.last_error = Some(error.clone()),
+                });
+                workspace::emit_to_project(&app, &project, "background-status", state.status());
+                refresh(&app);
+                exported = Some(stamps);
+            }
+            std::thread::sleep(Duration::from_secs(EXPORT_POLL_SECONDS));
+        }
+    });
+    Ok(())
+}
+
+fn stop(state: &TrayState) -> Result<(), String> {
+    if let Some(stop) = state.export.lock().map_err(|e| format!("Lock error: {}", e))?.take() {
+        stop.store(true, Ordering::Relaxed);
+    }
+    Ok(())
+}
+
+/// Keep an HTML export of the project up to date in the background; it continues, with the
+/// tray icon as its control, after the main window is closed
+#[tauri::command]
+pub async fn start_continuous_export(
+    app: AppHandle,
+    project_path: String,
+    output_path: String,
+    options: Option<ExportOptions>,
+) -> Result<BackgroundStatus, String> {
+    let config = ContinuousExport {
+        project_path,
+        output_path,
+        options: options.unwrap_or_default(),
+    };
+    let mut settings = AppSettings::load(&app);
+    settings.continuous_export = Some(config.clone());
+    settings.save(&app)?;
+    start(&app, config)?;
+    refresh(&app);
+    Ok(app.state::<TrayState>().status())
+}
+
+#[tauri::command]
+pub async fn stop_continuous_export(app: AppHandle, state: State<'_, TrayState>) -> Result<BackgroundStatus, String> {
+    stop(&state)?;
+    refresh(&app);
+    Ok(state.status())
+}
+
+/// Continuous export and watcher status for the editor's status bar
+#[tauri::command]
+pub async fn get_background_status(state: State<'_, TrayState>) -> Result<BackgroundStatus, String> {
+    Ok(state.status())
+}
@@ -0,0 +1,251 @@
+use crate::assets::{AssetEntry, AssetKind, AssetManifest, ASSETS_DIR};
+use crate::credentials;
+use crate::history::{self, HistoryEntry};
+use crate::settings::AppSettings;
+use crate::spec;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use tauri::AppHandle;
+
+/// Keychain account holding the TTS API key
+const API_KEY_ACCOUNT: &str = "tts-api-key";
+/// Voice lines go under assets/voice/<dialogue id>/
+const VOICE_DIR: &str = "voice";
+/// Lines synthesized outside a dialogue, for auditioning voices
+const PREVIEW_DIR: &str = "preview";
+const MAX_LINE_CHARS: usize = 4096;
+
+/// Where dialogue lines are synthesized
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum TtsProvider {
+    /// A local Piper binary; `voice` picks `<modelDir>/<voice>.onnx`
+    #[serde(rename_all = "camelCase")]
+    Piper {
+        #[serde(default)]
+        binary: Option<String>,
+        model_dir: String,
+    },
+    /// An OpenAI-compatible `/v1/audio/speech` endpoint; the key lives in the keychain
+    #[serde(rename_all = "camelCase")]
+    Api { url: String, model: String },
+}
+
+/// Text-to-speech configuration (`tts` in the app settings)
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TtsSettings {
+    pub provider: TtsProvider,
+    /// Voice used when a line doesn't name one
+    #[serde(default)]
+    pub default_voice: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SynthesizedLine {
+    pub asset: AssetEntry,
+    /// Whether the dialogue node's `voice` now points at the file
+    pub linked: bool,
+    pub history: Option<HistoryEntry>,
+    /// Updated game.json for the editor, when a node was linked
+    pub game_spec_json: Option<String>,
+}
+
+fn synthesize_piper(binary: Option<&str>, model_dir: &str, voice: &str, text: &str, output: &Path) -> Result<(), String> {
+    let model = PathBuf::from(model_dir).join(format!("{}.onnx", voice));
+    if !model.exists() {
+        return Err(format!("Piper voice not found: {}", model.display()));
+    }
+    let binary = binary.filter(|b| !b.is_empty()).unwrap_or("piper");
+    let mut child = Command::new(binary)
+        .arg("--model")
+        .arg(&model)
+        .arg("--output_file")
+        .arg(output)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => format!("Piper binary not found: {}", binary),
+            _ => format!("Failed to run Piper: {}", e),
+        })?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(text.as_bytes())
+            .map_err(|e| format!("Failed to send text to Piper: {}", e))?;
+    }
+    let result = child.wait_with_output().map_err(|e| format!("Failed to run Piper: {}", e))?;
+    if !result.status.success() {
+        return Err(format!("Piper failed: {}", String::from_utf8_lossy(&result.stderr).trim()));
+    }
+    Ok(())
+}
+
+async fn synthesize_api(url: &str, model: &str, voice: &str, text: &str) -> Result<Vec<u8>, String> {
+    let mut request = Client::new().post(url).json(&json!({
+        "model": model,
+        "input": text,
+        "voice": voice,
+        "response_format": "mp3",
+    }));
+    if let Some(key) = credentials::load(API_KEY_ACCOUNT)? {
+        request = request.bearer_auth(key);
+    }
+    let response = request.send().await.map_err(|e| format!("Failed to reach TTS API: {}", e))?;
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        return Err(format!("TTS API error {}: {}", status, body));
+    }
+    response
+        .bytes()
+        .await
+        .map(|bytes| bytes.to_vec())
+        .map_err(|e| format!("Failed to read TTS audio: {}", e))
+}
+
+/// Path-safe form of a dialogue or node id
+fn file_stem(id: &str) -> String {
+    id.chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+        .collect()
+}
+
+/// The node with `node_id` in dialogue `dialogue_id`, mutably
+fn find_node<'a>(game_spec: &'a mut Value, dialogue_id: &str, node_id: &str) -> Option<&'a mut Value> {
+    game_spec
+        .get_mut("dialogues")?
+        .as_array_mut()?
+        .iter_mut()
+        .find(|d| d.get("id").and_then(Value::as_str) == Some(dialogue_id))?
+        .get_mut("nodes")?
+        .as_array_mut()?
+        .iter_mut()
+        .find(|n| n.get("id").and_then(Value::as_str) == Some(node_id))
+}
+
+#[tauri::command]
+pub async fn get_tts_settings(app: AppHandle) -> Result<Option<TtsSettings>, String> {
+    Ok(AppSettings::load(&app).tts)
+}
+
+/// Configure text-to-speech; `api_key` is stored in the keychain, an empty one removes it
+#[tauri::command]
+pub async fn set_tts_settings(app: AppHandle, settings: Option<TtsSettings>, api_key: Option<String>) -> Result<(), String> {
+    match api_key.as_deref().map(str::trim) {
+        Some("") => credentials::delete(API_KEY_ACCOUNT)?,
+        Some(key) => credentials::store(API_KEY_ACCOUNT, key)?,
+        None => {}
+    }
+    let mut app_settings = AppSettings::load(&app);
+    app_settings.tts = settings;
+    app_settings.save(&app)
+}
+
+/// Synthesize one line into assets/voice/ and register it. With `dialogue_id` and `node_id` the
+/// file is named after the node and linked from its `voice` field as an undoable change.
+#[tauri::command]
+pub async fn synthesize_dialogue_line(
+    app: AppHandle,
+    project_path: String,
+    text: String,
+    voice: Option<String>,
+    dialogue_id: Option<String>,
+    node_id: Option<String>,
+) -> Result<SynthesizedLine, String> {
+    let text = text.trim().to_string();
+    if text.is_empty() {
+        return Err("Nothing to synthesize".to_string());
+    }
+    if text.chars().count() > MAX_LINE_CHARS {
+        return Err(format!("Dialogue lines are limited to {} characters", MAX_LINE_CHARS));
+    }
+    let settings = AppSettings::load(&app).tts.ok_or("Text-to-speech is not configured")?;
+    let voice = voice
+        .or(settings.default_voice.clone())
+        .filter(|v| !v.trim().is_empty())
+        .ok_or("No voice selected and no default voice configured")?;
+
+    let before = spec::load_project_spec(&project_path)?;
+    let mut game_spec = before.clone();
+    let target = match (&dialogue_id, &node_id) {
+        (Some(dialogue), Some(node)) => {
+            if find_node(&mut game_spec, dialogue, node).is_none() {
+                return Err(format!("Dialogue node {}/{} not found", dialogue, node));
+            }
+            Some((dialogue.clone(), node.clone()))
+        }
+        _ => None,
+    };
+
+    // Node lines are named after the node so re-synthesizing replaces them; previews by content
+    let (subdir, stem) = match &target {
+        Some((dialogue, node)) => (file_stem(dialogue), file_stem(node)),
+        None => {
+            let digest = Sha256::digest(format!("{}\n{}", voice, text).as_bytes());
+            let hash: String = digest.iter().take(6).map(|b| format!("{:02x}", b)).collect();
+            (PREVIEW_DIR.to_string(), format!("{}-{}", file_stem(&voice), hash))
+        }
+    };
+    let extension = match settings.provider {
+        TtsProvider::Piper { .. } => "wav",
+        TtsProvider::Api { .. } => "mp3",
+    };
+    let relative = format!("{}/{}/{}/{}.{}", ASSETS_DIR, VOICE_DIR, subdir, stem, extension);
+    let destination = PathBuf::from(&project_path).join(&relative);
+    if let Some(parent) = destination.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create voice directory: {}", e))?;
+    }
+
+    match &settings.provider {
+        TtsProvider::Piper { binary, model_dir } => {
+            let (binary, model_dir, voice, text, output) =
+                (binary.clone(), model_dir.clone(), voice.clone(), text.clone(), destination.clone());
+            tokio::task::spawn_blocking(move || synthesize_piper(binary.as_deref(), &model_dir, &voice, &text, &output))
+                .await
+                .map_err(|e| format!("Synthesis task failed: {}", e))??;
+        }
+        TtsProvider::Api { url, model } => {
+            let audio = synthesize_api(url, model, &voice, &text).await?;
+            fs::write(&destination, audio).map_err(|e| format!("Failed to write voice line: {}", e))?;
+        }
+    }
+
+    let asset = AssetEntry {
+        kind: AssetKind::Audio,
+        ..AssetEntry::new(relative.clone(), None)
+    };
+    let mut manifest = AssetManifest::load(&project_path)?;
+    manifest.upsert(asset.clone());
+    manifest.save(&project_path)?;
+
+    let Some((dialogue, node)) = target else {
+        return Ok(SynthesizedLine {
+            asset,
+            linked: false,
+            history: None,
+            game_spec_json: None,
+        });
+    };
+    if let Some(node) = find_node(&mut game_spec, &dialogue, &node) {
+        node["voice"] = Value::String(relative);
+    }
+    let entry = history::record(&project_path, &format!("Voice line for {}/{}", dialogue, node), &before)?;
+    spec::save_project_spec(&project_path, &game_spec)?;
+
+    Ok(SynthesizedLine {
+        asset,
+        linked: true,
+        history: Some(entry),
+        game_spec_json: Some(
+            serde_json::to_string_pretty(&game_spec).map_err(|e| format!("Failed to serialize game spec: {}", e))?,
+        ),
+    })
+}
@@ -0,0 +1,268 @@
+use crate::export::ExportManifest;
+use crate::prompt_history::{self, PromptOutcome};
+use crate::{paths, spec};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::AppHandle;
+
+/// Tutorial progress for the project, kept with its other editor state
+const TUTORIAL_FILE: &str = ".promptplay/tutorial.json";
+
+/// Guided onboarding steps, in the order the tutorial walks through them
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum TutorialStep {
+    OpenProject,
+    AddEntity,
+    EditProperty,
+    UseAi,
+    Export,
+}
+
+impl TutorialStep {
+    pub const ALL: [TutorialStep; 5] = [
+        TutorialStep::OpenProject,
+        TutorialStep::AddEntity,
+        TutorialStep::EditProperty,
+        TutorialStep::UseAi,
+        TutorialStep::Export,
+    ];
+
+    fn title(self) -> &'static str {
+        match self {
+            TutorialStep::OpenProject => "Open a project",
+            TutorialStep::AddEntity => "Add an entity",
+            TutorialStep::EditProperty => "Change a property",
+            TutorialStep::UseAi => "Ask the AI for a change",
+            TutorialStep::Export => "Export your game",
+        }
+    }
+
+    fn instructions(self) -> &'static str {
+        match self {
+            TutorialStep::OpenProject => "Open or create a project folder with a game.json.",
+            TutorialStep::AddEntity => "Add an entity from the scene tree or the entity palette, then save.",
+            TutorialStep::EditProperty => "Select an entity, change one of its properties in the inspector, then save.",
+            TutorialStep::UseAi => "Describe a change in the AI panel and apply the result.",
+            TutorialStep::Export => "Export the game to HTML from the export dialog.",
+        }
+    }
+}
+
+/// `.promptplay/tutorial.json`: what the project looked like when the tutorial started and which
+/// steps have been verified since
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct TutorialProgress {
+    started_at: u64,
+    baseline_entities: usize,
+    /// Hash of the spec when the tutorial started, to tell whether anything was edited
+    baseline_hash: String,
+    #[serde(default)]
+    completed: Vec<CompletedStep>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct CompletedStep {
+    step: TutorialStep,
+    completed_at: u64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StepStatus {
+    pub step: TutorialStep,
+    pub title: &'static str,
+    pub instructions: &'static str,
+    pub completed_at: Option<u64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TutorialState {
+    pub started_at: u64,
+    pub steps: Vec<StepStatus>,
+    /// First step not yet completed; None once the tutorial is finished
+    pub current: Option<TutorialStep>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StepVerification {
+    pub step: TutorialStep,
+    pub completed: bool,
+    /// Why the step isn't done yet, phrased as what's still missing
+    pub reason: Option<String>,
+    pub state: TutorialState,
+}
+
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+fn progress_path(project_path: &str) -> PathBuf {
+    paths::project_root(project_path).join(TUTORIAL_FILE)
+}
+
+fn spec_hash(spec: &Value) -> String {
+    Sha256::digest(spec.to_string().as_bytes()).iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn save_progress(project_path: &str, progress: &TutorialProgress) -> Result<(), String> {
+    let path = progress_path(project_path);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create .promptplay directory: {}", e))?;
+    }
+    let content =
+        serde_json::to_string_pretty(progress).map_err(|e| format!("Failed to serialize tutorial progress: {}", e))?;
+    fs::write(&path, content).map_err(|e| format!("Failed to write {}: {}", TUTORIAL_FILE, e))
+}
+
+/// The saved progress, or a fresh start with the project's current spec as the baseline
+fn load_progress(project_path: &str) -> Result<TutorialProgress, String> {
+    match fs::read_to_string(progress_path(project_path)) {
+        Ok(content) => {
+            return serde_json::from_str(&content).map_err(|e| format!("Failed to parse {}: {}", TUTORIAL_FILE, e));
+        }
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+            return Err(format!("Failed to read {}: {}", TUTORIAL_FILE, e));
+        }
+        Err(_) => {}
+    }
+    let game_spec = spec::load_project_spec(project_path)?;
+    let progress = TutorialProgress {
+        started_at: now_millis(),
+        baseline_entities: spec::all_entities(&game_spec).count(),
+        baseline_hash: spec_hash(&game_spec),
+        completed: Vec::new(),
+    };
+    save_progress(project_path, &progress)?;
+    Ok(progress)
+}
+
+fn state(progress: &TutorialProgress) -> TutorialState {
+    let steps: Vec<StepStatus> = TutorialStep::ALL
+        .iter()
+        .map(|&step| StepStatus {
+            step,
+            title: step.title(),
+            instructions: step.instructions(),
+            completed_at: progress.completed.iter().find(|c| c.step == step).map(|c| c.completed_at),
+        })
+        .collect();
+    TutorialState {
+        started_at: progress.started_at,
+        current: steps.iter().find(|s| s.completed_at.is_none()).map(|s| s.step),
+        steps,
+    }
+}
+
+/// Check an export's manifest: it must exist and be newer than the tutorial
+fn verify_export(output_path: &Path, started_at: u64) -> Result<(), String> {
+    if !output_path.is_file() {
+        return Err(format!("{} doesn't exist yet", output_path.display()));
+    }
+    let manifest_path = ExportManifest::path_for(output_path);
+    let manifest: ExportManifest = fs::read_to_string(&manifest_path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .ok_or_else(|| format!("{} wasn't exported by PromptPlay", output_path.display()))?;
+    if manifest.exported_at < started_at {
+        return Err("That export is older than the tutorial; export again".to_string());
+    }
+    Ok(())
+}
+
+/// Inspect the project for evidence that `step` was done; Err holds what's still missing
+fn verify(
+    app: &AppHandle,
+    project_path: &str,
+    progress: &TutorialProgress,
+    step: TutorialStep,
+    evidence: Option<&str>,
+) -> Result<(), String> {
+    match step {
+        TutorialStep::OpenProject => spec::load_project_spec(project_path).map(|_| ()),
+        TutorialStep::AddEntity => {
+            let game_spec = spec::load_project_spec(project_path)?;
+            let count = spec::all_entities(&game_spec).count();
+            if count > progress.baseline_entities {
+                Ok(())
+            } else {
+                Err(format!("The saved project still has {} entities; add one and save", count))
+            }
+        }
+        TutorialStep::EditProperty => {
+            let game_spec = spec::load_project_spec(project_path)?;
+            if spec_hash(&game_spec) != progress.baseline_hash {
+                Ok(())
+            } else {
+                Err("The saved project hasn't changed since the tutorial started".to_string())
+            }
+        }
+        TutorialStep::UseAi => {
+            let prompts: Vec<_> = prompt_history::for_project(app, project_path)?
+                .into_iter()
+                .filter(|entry| entry.created_at >= progress.started_at)
+                .collect();
+            if prompts.iter().any(|entry| entry.outcome == PromptOutcome::Applied) {
+                Ok(())
+            } else if prompts.is_empty() {
+                Err("No prompt has been sent from this project yet".to_string())
+            } else {
+                Err("Apply one of the AI's results to finish this step".to_string())
+            }
+        }
+        TutorialStep::Export => {
+            let output_path = evidence.ok_or("Export the game, then verify with the exported file's path")?;
+            verify_export(Path::new(output_path), progress.started_at)
+        }
+    }
+}
+
+/// Tutorial steps and which are done; starts the tutorial on first call, with the project as
+/// it is now as the baseline
+#[tauri::command]
+pub async fn get_tutorial_state(project_path: String) -> Result<TutorialState, String> {
+    Ok(state(&load_progress(&project_path)?))
+}
+
+/// Check the project for evidence that a step was done and record it if so. `evidence` is the
+/// exported file's path for the export step.
+#[tauri::command]
+pub async fn verify_tutorial_step(
+    app: AppHandle,
+    project_path: String,
+    step: TutorialStep,
+    evidence: Option<String>,
+) -> Result<StepVerification, String> {
+    let mut progress = load_progress(&project_path)?;
+    if progress.completed.iter().any(|c| c.step == step) {
+        return Ok(StepVerification {
+            step,
+            completed: true,
+            reason: None,
+            state: state(&progress),
+        });
+    }
+
+    let reason = verify(&app, &project_path, &progress, step, evidence.as_deref()).err();
+    if reason.is_none() {
+        progress.completed.push(CompletedStep {
+            step,
+            completed_at: now_millis(),
+        });
+        save_progress(&project_path, &progress)?;
+    }
+    Ok(StepVerification {
+        step,
+        completed: reason.is_none(),
+        reason,
+        state: state(&progress),
+    })
+}
@@ -0,0 +1,123 @@
+use crate::components::ComponentRegistry;
+use crate::{
+    collision_shapes, components, difficulty, entity_ids, frame_rate, game_spec, hud, json_guard, physics_materials, pooling, progression, world,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Error,
+    Warning,
+    Info,
+}
+
+/// A single validation finding, addressed by a JSON-pointer-like path into the spec
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Diagnostic {
+    pub path: String,
+    pub severity: Severity,
+    pub message: String,
+    /// Suggested change that would resolve the finding
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fix: Option<String>,
+}
+
+impl Diagnostic {
+    pub fn error(path: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            path: path.into(),
+            severity: Severity::Error,
+            message: message.into(),
+            fix: None,
+        }
+    }
+
+    pub fn warning(path: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            path: path.into(),
+            severity: Severity::Warning,
+            message: message.into(),
+            fix: None,
+        }
+    }
+
+    pub fn with_fix(mut self, fix: impl Into<String>) -> Self {
+        self.fix = Some(fix.into());
+        self
+    }
+}
+
+/// Run every spec-level check and collect the findings
+pub fn validate_spec(spec: &Value) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+
+    let Some(root) = spec.as_object() else {
+        diagnostics.push(Diagnostic::error("", "Game spec must be a JSON object"));
+        return diagnostics;
+    };
+
+    match root.get("entities") {
+        Some(Value::Array(entities)) => {
+            for (i, entity) in entities.iter().enumerate() {
+                let path = format!("/entities/{}", i);
+                if entity.get("name").and_then(Value::as_str).is_none() {
+                    diagnostics.push(Diagnostic::error(&path, "Entity is missing a name"));
+                }
+                if !entity.get("components").is_some_and(Value::is_object) {
+                    diagnostics.push(Diagnostic::error(
+                        format!("{}/components", path),
+                        "Entity components must be an object",
+                    ));
+                }
+            }
+        }
+        Some(_) => diagnostics.push(Diagnostic::error("/entities", "entities must be an array")),
+        None => diagnostics.push(Diagnostic::error("/entities", "Game spec has no entities array")),
+    }
+
+    diagnostics.extend(game_spec::validate_entities(spec));
+    diagnostics.extend(hud::validate_ui(spec));
+    diagnostics.extend(progression::validate_levels(spec));
+    diagnostics.extend(difficulty::validate_difficulty(spec));
+    diagnostics.extend(entity_ids::validate_references(spec));
+    diagnostics.extend(world::validate_world(spec));
+    diagnostics.extend(physics_materials::validate_materials(spec));
+    diagnostics.extend(collision_shapes::validate_shapes(spec));
+    diagnostics.extend(pooling::validate_pooling(spec));
+    diagnostics.extend(frame_rate::validate_frame_rate(spec));
+
+    diagnostics
+}
+
+/// Run the spec checks plus component checks against a project's registry
+pub fn validate_spec_with_registry(spec: &Value, registry: &ComponentRegistry) -> Vec<Diagnostic> {
+    let mut diagnostics = validate_spec(spec);
+    diagnostics.extend(components::validate_components(spec, registry));
+    diagnostics
+}
+
+/// Validate a game spec JSON string and return structured diagnostics.
+/// When `project_path` is given, custom components from components.d.json are honored.
+/// JSON that doesn't parse comes back as a single error rather than a failed call.
+#[tauri::command]
+pub async fn validate_game_spec(
+    game_spec_json: String,
+    project_path: Option<String>,
+) -> Result<Vec<Diagnostic>, String> {
+    let spec: Value = match json_guard::parse(game_spec_json.as_bytes(), "game spec", &json_guard::SPEC_LIMITS) {
+        Ok(spec) => spec,
+        Err(e) => {
+            return Ok(vec![Diagnostic::error("", e)
+                .with_fix("Look for a missing comma, bracket or quote at that line and column")])
+        }
+    };
+
+    let registry = match project_path {
+        Some(path) => ComponentRegistry::load(&path)?,
+        None => ComponentRegistry::builtin(),
+    };
+
+    Ok(validate_spec_with_registry(&spec, &registry))
+}
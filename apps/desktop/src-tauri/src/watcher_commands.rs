@@ -1,22 +1,25 @@
-use crate::file_watcher::{start_watching, stop_watching, FileWatcherState};
-use std::path::PathBuf;
-use std::sync::Mutex;
+use crate::error::AppError;
+use crate::file_watcher::{start_watching, stop_watching};
+use crate::paths;
+use crate::workspace::WorkspaceManager;
 use tauri::{AppHandle, State};
 
-/// Start watching a directory for file changes
+/// Start watching a directory for file changes in the calling window
 #[tauri::command]
 pub async fn start_file_watcher(
     app_handle: AppHandle,
+    window: tauri::Window,
     path: String,
-    state: State<'_, Mutex<FileWatcherState>>,
-) -> Result<(), String> {
-    let mut watcher_state = state.lock().map_err(|e| format!("Lock error: {}", e))?;
+    workspace: State<'_, WorkspaceManager>,
+) -> Result<(), AppError> {
+    let services = workspace.services(window.label())?;
+    let mut watcher_state = services.watcher.lock()?;
 
     // Stop existing watcher if any
     stop_watching(&mut watcher_state.watcher);
 
-    // Start new watcher
-    let path_buf = PathBuf::from(&path);
+    // Start new watcher; the canonical root keeps event paths comparable on network drives
+    let path_buf = paths::project_root(&path);
     let watcher = start_watching(app_handle, path_buf.clone())?;
 
     watcher_state.watcher = Some(watcher);
@@ -27,8 +30,9 @@ pub async fn start_file_watcher(
 
 /// Stop watching the current directory
 #[tauri::command]
-pub async fn stop_file_watcher(state: State<'_, Mutex<FileWatcherState>>) -> Result<(), String> {
-    let mut watcher_state = state.lock().map_err(|e| format!("Lock error: {}", e))?;
+pub async fn stop_file_watcher(window: tauri::Window, workspace: State<'_, WorkspaceManager>) -> Result<(), AppError> {
+    let services = workspace.services(window.label())?;
+    let mut watcher_state = services.watcher.lock()?;
 
     stop_watching(&mut watcher_state.watcher);
     watcher_state.watched_path = None;
@@ -36,15 +40,17 @@ pub async fn stop_file_watcher(state: State<'_, Mutex<FileWatcherState>>) -> Res
     Ok(())
 }
 
-/// Get the currently watched path
+/// Get the path the calling window is watching
 #[tauri::command]
 pub async fn get_watched_path(
-    state: State<'_, Mutex<FileWatcherState>>,
-) -> Result<Option<String>, String> {
-    let watcher_state = state.lock().map_err(|e| format!("Lock error: {}", e))?;
+    window: tauri::Window,
+    workspace: State<'_, WorkspaceManager>,
+) -> Result<Option<String>, AppError> {
+    let services = workspace.services(window.label())?;
+    let watcher_state = services.watcher.lock()?;
 
     Ok(watcher_state
         .watched_path
         .as_ref()
-        .map(|p| p.to_string_lossy().to_string()))
+        .map(|p| paths::to_display(p)))
 }
@@ -0,0 +1,33 @@
+use futures::stream::{self, StreamExt};
+use std::sync::Arc;
+use std::thread;
+
+/// Upper bound on concurrent file jobs; disk-bound work gains little past this
+const MAX_WORKERS: usize = 8;
+
+/// Concurrent file jobs for this machine
+pub fn worker_count() -> usize {
+    thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1)
+        .clamp(1, MAX_WORKERS)
+}
+
+/// Run `job` over `items` on the blocking pool, at most `worker_count()` at a time.
+/// Results keep the order of `items`; a job that panics fails only its own item.
+pub async fn run_blocking<T, R, F>(items: Vec<T>, job: F) -> Vec<Result<R, String>>
+where
+    T: Send + 'static,
+    R: Send + 'static,
+    F: Fn(T) -> R + Send + Sync + 'static,
+{
+    let job = Arc::new(job);
+    stream::iter(items.into_iter().map(|item| {
+        let job = Arc::clone(&job);
+        tokio::task::spawn_blocking(move || job(item))
+    }))
+    .buffered(worker_count())
+    .map(|outcome| outcome.map_err(|e| format!("Worker failed: {}", e)))
+    .collect()
+    .await
+}
@@ -0,0 +1,200 @@
+use crate::backup::BackupSchedulerState;
+use crate::file_watcher::{stop_watching, FileWatcherState};
+use crate::health::HealthMonitorState;
+use crate::inbox::InboxState;
+use crate::paths;
+use crate::project_lock::ProjectLockState;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Emitter, Manager, State, WebviewUrl, WebviewWindowBuilder};
+
+/// Labels of windows opened with open_project_window; the capability file grants them the
+/// same permissions as the main window
+const PROJECT_WINDOW_PREFIX: &str = "project-";
+
+/// Background services of one window's project: its session lock, monitors and file watcher
+#[derive(Default)]
+pub struct ProjectServices {
+    pub lock: ProjectLockState,
+    pub health: HealthMonitorState,
+    pub inbox: InboxState,
+    pub backups: BackupSchedulerState,
+    pub watcher: Mutex<FileWatcherState>,
+}
+
+impl ProjectServices {
+    /// Stop everything and release the lock
+    pub fn stop(&self) -> Result<(), String> {
+        self.health.stop()?;
+        self.inbox.stop()?;
+        self.backups.stop()?;
+        if let Ok(mut watcher) = self.watcher.lock() {
+            stop_watching(&mut watcher.watcher);
+            watcher.watched_path = None;
+        }
+        self.lock.release()
+    }
+}
+
+#[derive(Default)]
+struct WindowContext {
+    /// Canonical root of the project the window shows
+    project: Option<PathBuf>,
+    /// Opened with open_project_readonly: commands may not write into the project
+    read_only: bool,
+    services: Arc<ProjectServices>,
+}
+
+/// Project context per window, keyed by window label
+#[derive(Default)]
+pub struct WorkspaceManager(Mutex<HashMap<String, WindowContext>>);
+
+impl WorkspaceManager {
+    /// The window's services, created on first use
+    pub fn services(&self, label: &str) -> Result<Arc<ProjectServices>, String> {
+        let mut windows = self.0.lock().map_err(|e| format!("Lock error: {}", e))?;
+        Ok(windows.entry(label.to_string()).or_default().services.clone())
+    }
+
+    /// Record which project a window shows, so project events are addressed to it
+    pub fn bind(&self, label: &str, project_path: Option<&str>) -> Result<(), String> {
+        self.bind_with_mode(label, project_path, false)
+    }
+
+    /// Like `bind`, marking the project read-only for the window
+    pub fn bind_read_only(&self, label: &str, project_path: &str) -> Result<(), String> {
+        self.bind_with_mode(label, Some(project_path), true)
+    }
+
+    fn bind_with_mode(&self, label: &str, project_path: Option<&str>, read_only: bool) -> Result<(), String> {
+        let mut windows = self.0.lock().map_err(|e| format!("Lock error: {}", e))?;
+        let context = windows.entry(label.to_string()).or_default();
+        context.project = project_path.map(paths::project_root);
+        context.read_only = read_only && context.project.is_some();
+        Ok(())
+    }
+
+    pub fn project(&self, label: &str) -> Option<PathBuf> {
+        self.0.lock().ok()?.get(label)?.project.clone()
+    }
+
+    /// The window's project when it was opened read-only
+    pub fn read_only_project(&self, label: &str) -> Option<PathBuf> {
+        let windows = self.0.lock().ok()?;
+        let context = windows.get(label)?;
+        context.project.clone().filter(|_| context.read_only)
+    }
+
+    /// Whether any window shows the project read-only, for work that runs without a window
+    pub fn is_read_only(&self, project: &Path) -> bool {
+        let root = paths::canonical(project);
+        self.0.lock().is_ok_and(|windows| {
+            windows
+                .values()
+                .any(|context| context.read_only && context.project.as_deref() == Some(root.as_path()))
+        })
+    }
+
+    /// Labels of the windows showing the project
+    pub fn owners(&self, project: &Path) -> Vec<String> {
+        let root = paths::canonical(project);
+        let Ok(windows) = self.0.lock() else {
+            return Vec::new();
+        };
+        windows
+            .iter()
+            .filter(|(_, context)| context.project.as_deref() == Some(root.as_path()))
+            .map(|(label, _)| label.clone())
+            .collect()
+    }
+
+    /// Drop a window's context, stopping its services
+    pub fn close(&self, label: &str) -> Result<(), String> {
+        let context = self.0.lock().map_err(|e| format!("Lock error: {}", e))?.remove(label);
+        match context {
+            Some(context) => context.services.stop(),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Send a project event (watcher, health, backup, inbox) to the windows showing that project.
+/// Broadcasts when no window claims it, e.g. an import triggered before the project is bound.
+pub fn emit_to_project<S: Serialize + Clone>(app: &AppHandle, project: &Path, event: &str, payload: S) {
+    let owners = app.state::<WorkspaceManager>().owners(project);
+    if owners.is_empty() {
+        let _ = app.emit(event, payload);
+        return;
+    }
+    for label in owners {
+        let _ = app.emit_to(label.as_str(), event, payload.clone());
+    }
+}
+
+/// The window app-wide input such as the menu bar applies to: the focused one, else "main",
+/// else any open window. None once every window is closed.
+pub fn focused_window(app: &AppHandle) -> Option<String> {
+    let windows = app.webview_windows();
+    windows
+        .iter()
+        .find(|(_, window)| window.is_focused().unwrap_or(false))
+        .map(|(label, _)| label.clone())
+        .or_else(|| windows.contains_key("main").then(|| "main".to_string()))
+        .or_else(|| windows.keys().next().cloned())
+}
+
+fn query_escape(text: &str) -> String {
+    text.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => (b as char).to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+/// Open a project in an additional window with its own lock, monitors and watcher; focuses the
+/// window that already shows it instead. Returns the window label.
+#[tauri::command]
+pub async fn open_project_window(
+    app: AppHandle,
+    project_path: String,
+    workspace: State<'_, WorkspaceManager>,
+) -> Result<String, String> {
+    // Fail here rather than in a fresh window if the folder isn't a project
+    crate::commands::load_game_spec(project_path.clone()).await?;
+
+    let root = paths::project_root(&project_path);
+    if let Some(window) = workspace.owners(&root).iter().find_map(|label| app.get_webview_window(label)) {
+        window.set_focus().map_err(|e| format!("Failed to focus window: {}", e))?;
+        return Ok(window.label().to_string());
+    }
+
+    let label = format!("{}{}", PROJECT_WINDOW_PREFIX, uuid::Uuid::new_v4().simple());
+    workspace.bind(&label, Some(&project_path))?;
+    let name = root
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| project_path.clone());
+    let url = format!("index.html?project={}", query_escape(&project_path));
+    let built = WebviewWindowBuilder::new(&app, &label, WebviewUrl::App(url.into()))
+        .title(format!("PromptPlay - {}", name))
+        .inner_size(1400.0, 900.0)
+        .min_inner_size(1200.0, 700.0)
+        .build();
+    if let Err(e) = built {
+        let _ = workspace.close(&label);
+        return Err(format!("Failed to open project window: {}", e));
+    }
+    Ok(label)
+}
+
+/// The project the calling window was opened for, if any
+#[tauri::command]
+pub async fn get_window_project(
+    window: tauri::Window,
+    workspace: State<'_, WorkspaceManager>,
+) -> Result<Option<String>, String> {
+    Ok(workspace.project(window.label()).map(|p| paths::to_display(&p)))
+}
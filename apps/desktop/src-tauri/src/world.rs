@@ -0,0 +1,196 @@
+use crate::history::{self, HistoryEntry};
+use crate::spec;
+use crate::validation::Diagnostic;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Canvas size used by the runtime when the spec doesn't set one
+pub const DEFAULT_CANVAS: CanvasSize = CanvasSize {
+    width: 800.0,
+    height: 600.0,
+};
+
+/// Visible viewport in pixels (`config.canvas`)
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub struct CanvasSize {
+    pub width: f64,
+    pub height: f64,
+}
+
+/// Playable area in world units (`config.worldBounds`); defaults to the canvas
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub struct WorldBounds {
+    #[serde(default)]
+    pub x: f64,
+    #[serde(default)]
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+impl WorldBounds {
+    pub fn contains(&self, x: f64, y: f64) -> bool {
+        x >= self.x && x <= self.x + self.width && y >= self.y && y <= self.y + self.height
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct WorldSettings {
+    pub canvas: CanvasSize,
+    pub world_bounds: WorldBounds,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SetWorldBoundsResult {
+    pub settings: WorldSettings,
+    /// Entities whose position falls outside the new bounds
+    pub out_of_bounds: Vec<String>,
+    pub history: HistoryEntry,
+}
+
+fn parse<T: serde::de::DeserializeOwned>(spec: &Value, pointer: &str) -> Option<T> {
+    spec.pointer(pointer)
+        .and_then(|v| serde_json::from_value(v.clone()).ok())
+}
+
+pub fn canvas(spec: &Value) -> CanvasSize {
+    parse::<CanvasSize>(spec, "/config/canvas")
+        .filter(|c| c.width > 0.0 && c.height > 0.0)
+        .unwrap_or(DEFAULT_CANVAS)
+}
+
+pub fn world_bounds(spec: &Value) -> WorldBounds {
+    parse::<WorldBounds>(spec, "/config/worldBounds")
+        .filter(|b| b.width > 0.0 && b.height > 0.0)
+        .unwrap_or_else(|| {
+            let canvas = canvas(spec);
+            WorldBounds {
+                x: 0.0,
+                y: 0.0,
+                width: canvas.width,
+                height: canvas.height,
+            }
+        })
+}
+
+/// Names of entities positioned outside the world bounds, with their spec paths
+fn out_of_bounds(spec: &Value) -> Vec<(String, String)> {
+    let bounds = world_bounds(spec);
+    let mut lists: Vec<(String, &Vec<Value>)> = Vec::new();
+    if let Some(entities) = spec.get("entities").and_then(Value::as_array) {
+        lists.push(("/entities".to_string(), entities));
+    }
+    for (i, scene) in spec.get("scenes").and_then(Value::as_array).into_iter().flatten().enumerate() {
+        if let Some(entities) = scene.get("entities").and_then(Value::as_array) {
+            lists.push((format!("/scenes/{}/entities", i), entities));
+        }
+    }
+
+    let mut result = Vec::new();
+    for (prefix, entities) in lists {
+        for (i, entity) in entities.iter().enumerate() {
+            let position = entity.pointer("/components/transform/x").and_then(Value::as_f64).zip(
+                entity.pointer("/components/transform/y").and_then(Value::as_f64),
+            );
+            if let Some((x, y)) = position {
+                if !bounds.contains(x, y) {
+                    let name = entity.get("name").and_then(Value::as_str).unwrap_or("<unnamed>");
+                    result.push((format!("{}/{}/components/transform", prefix, i), name.to_string()));
+                }
+            }
+        }
+    }
+    result
+}
+
+/// Check canvas/world bounds values and flag entities placed outside the world
+pub fn validate_world(spec: &Value) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+
+    for (pointer, label) in [("/config/canvas", "Canvas"), ("/config/worldBounds", "World bounds")] {
+        let Some(value) = spec.pointer(pointer) else {
+            continue;
+        };
+        let size_ok = ["width", "height"]
+            .iter()
+            .all(|k| value.get(k).and_then(Value::as_f64).is_some_and(|v| v > 0.0));
+        if !size_ok {
+            diagnostics.push(Diagnostic::error(
+                pointer,
+                format!("{} needs a positive width and height", label),
+            ));
+        }
+    }
+
+    let bounds = world_bounds(spec);
+    for (path, name) in out_of_bounds(spec) {
+        diagnostics.push(
+            Diagnostic::warning(
+                path,
+                format!(
+                    "Entity '{}' lies outside the world bounds ({}, {}, {}x{})",
+                    name, bounds.x, bounds.y, bounds.width, bounds.height
+                ),
+            )
+            .with_fix("Move it inside the world bounds, or enlarge config.worldBounds"),
+        );
+    }
+
+    diagnostics
+}
+
+/// Get the effective canvas size and world bounds of a project
+#[tauri::command]
+pub async fn get_world_bounds(project_path: String) -> Result<WorldSettings, String> {
+    let game_spec = spec::load_project_spec(&project_path)?;
+    Ok(WorldSettings {
+        canvas: canvas(&game_spec),
+        world_bounds: world_bounds(&game_spec),
+    })
+}
+
+/// Set the world bounds (and optionally the canvas size), reporting entities left outside
+#[tauri::command]
+pub async fn set_world_bounds(
+    project_path: String,
+    bounds: WorldBounds,
+    canvas_size: Option<CanvasSize>,
+) -> Result<SetWorldBoundsResult, String> {
+    if bounds.width <= 0.0 || bounds.height <= 0.0 {
+        return Err("World bounds need a positive width and height".to_string());
+    }
+    if canvas_size.is_some_and(|c| c.width <= 0.0 || c.height <= 0.0) {
+        return Err("Canvas needs a positive width and height".to_string());
+    }
+
+    let mut game_spec = spec::load_project_spec(&project_path)?;
+    let config = game_spec
+        .as_object_mut()
+        .ok_or("Game spec must be a JSON object")?
+        .entry("config")
+        .or_insert_with(|| Value::Object(Default::default()));
+    let config = config.as_object_mut().ok_or("config must be an object")?;
+
+    let serialize_error = |e: serde_json::Error| format!("Failed to serialize world settings: {}", e);
+    config.insert(
+        "worldBounds".to_string(),
+        serde_json::to_value(bounds).map_err(serialize_error)?,
+    );
+    if let Some(canvas_size) = canvas_size {
+        config.insert(
+            "canvas".to_string(),
+            serde_json::to_value(canvas_size).map_err(serialize_error)?,
+        );
+    }
+
+    let history = history::save_with_history(&project_path, "Set world bounds", &game_spec)?;
+
+    Ok(SetWorldBoundsResult {
+        settings: WorldSettings {
+            canvas: canvas(&game_spec),
+            world_bounds: world_bounds(&game_spec),
+        },
+        out_of_bounds: out_of_bounds(&game_spec).into_iter().map(|(_, name)| name).collect(),
+        history,
+    })
+}
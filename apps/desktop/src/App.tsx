@@ -1,6 +1,8 @@
 import { useState, useCallback, useEffect, useMemo, useRef, lazy, Suspense } from 'react';
 import { invoke } from '@tauri-apps/api/core';
+import { openProjectWithLock, releaseProjectLock } from './utils/projectLock';
 import { listen } from '@tauri-apps/api/event';
+import { getCurrentWebviewWindow } from '@tauri-apps/api/webviewWindow';
 import { save } from '@tauri-apps/plugin-dialog';
 import type { GameSpec, SceneSpec, EntitySpec, Game3DSpec } from '@promptplay/shared-types';
 import GameCanvas from './components/GameCanvas';
@@ -40,6 +42,7 @@ import { useHistoryState } from './hooks/useHistoryState';
 import { useEntityOperations } from './hooks/useEntityOperations';
 import { useKeyboardShortcuts } from './hooks/useKeyboardShortcuts';
 import { addRecentProject } from './services/RecentProjectsService';
+import { getTemplate, getCustomTemplates } from './services/TemplateService';
 import { screenCapture } from './services/ScreenCaptureService';
 import { publishService } from './services/PublishService';
 import { CodeIcon, CheckIcon, FolderIcon, SceneIcon, EntityIcon, LayersIcon, ImageIcon, PhysicsIcon, GridIcon } from './components/Icons';
@@ -81,6 +84,24 @@ type ViewMode = 'game' | 'code' | 'nodes' | 'shaders' | 'behavior' | 'states';
 type LeftPanelMode = 'files' | 'scenes' | 'entities' | 'prefabs' | 'assets' | 'tilemap';
 type RightPanelMode = 'inspector' | 'json' | 'physics' | 'scripts';
 
+type SaveDecision = 'saved' | 'merged' | 'keptMine' | 'keptDisk' | 'conflict';
+
+interface SaveOutcome {
+  decision: SaveDecision;
+  policy: 'preferNewest' | 'alwaysPrompt' | 'autoMerge';
+  specJson: string | null;
+  conflicts: string[];
+  historyId: string | null;
+}
+
+const SAVE_NOTICES: Record<SaveDecision, string> = {
+  saved: 'Saved',
+  merged: 'Saved, merged with changes made outside the editor',
+  keptMine: 'Saved over changes made outside the editor',
+  keptDisk: 'Kept the version on disk',
+  conflict: 'Not saved: game.json changed outside the editor',
+};
+
 function App() {
   const [gameSpec, setGameSpec] = useState<GameSpec | null>(null);
   const [isPlaying, setIsPlaying] = useState(false);
@@ -157,6 +178,9 @@ function App() {
 
   // Notification timeout ref for auto-dismiss
   const notificationTimeoutRef = useRef<NodeJS.Timeout | null>(null);
+  // game.json as last loaded or saved, so saves can spot changes made outside the editor
+  const savedSpecRef = useRef<string | null>(null);
+  const lastEditRef = useRef<number | null>(null);
 
   // Helper function to show notification with auto-dismiss
   const showNotification = useCallback((message: string, duration: number = 2000) => {
@@ -485,13 +509,17 @@ function App() {
   const handleFileChanged = useCallback(async (filePath: string) => {
     const fileName = filePath.split('/').pop() || '';
 
-    // Auto-reload game.json
-    if (fileName === 'game.json' && projectPath) {
+    // Auto-reload game.json; with unsaved edits the save conflict policy reconciles it on save
+    if (fileName === 'game.json' && projectPath && hasUnsavedChanges) {
+      setNotification('game.json changed on disk; it will be reconciled when you save');
+      setTimeout(() => setNotification(null), 3000);
+    } else if (fileName === 'game.json' && projectPath) {
       try {
         const gameJsonStr = await invoke<string>('load_game_spec', {
           projectPath,
         });
         const spec = JSON.parse(gameJsonStr) as GameSpec;
+        savedSpecRef.current = gameJsonStr;
         setGameSpec(spec);
         setNotification('Game reloaded');
         setTimeout(() => setNotification(null), 2000);
@@ -503,7 +531,11 @@ function App() {
       setNotification(`${fileName} changed`);
       setTimeout(() => setNotification(null), 2000);
     }
-  }, [projectPath]);
+  }, [projectPath, hasUnsavedChanges]);
+
+  useEffect(() => {
+    if (hasUnsavedChanges) lastEditRef.current = Date.now();
+  }, [gameSpec, hasUnsavedChanges]);
 
   // Set up file watcher
   useFileWatcher({
@@ -523,10 +555,8 @@ function App() {
       if (!selected) {
         try {
           // Use our custom Rust command instead of the dialog plugin
-          // to avoid cyclic structure serialization issues
-          const dialogResult = await invoke<string | null>('pick_directory', {
-            title: 'Open Game Project',
-          });
+          // to avoid cyclic structure serialization issues; it also rejects folders without game.json
+          const dialogResult = await invoke<string | null>('pick_project_folder');
 
           // Handle null (cancelled)
           if (!dialogResult) {
@@ -539,7 +569,7 @@ function App() {
           // Dialog error - don't set projectPath, just show error
           logError('Failed to open file dialog', dialogErr);
           setLoading(false);
-          setError('Failed to open file dialog. Please try again.');
+          setError(typeof dialogErr === 'string' ? dialogErr : 'Failed to open file dialog. Please try again.');
           return;
         }
       }
@@ -547,7 +577,12 @@ function App() {
       // Load the game spec BEFORE setting projectPath to avoid triggering
       // dependent effects (FileTree, useFileWatcher) if the load fails
       try {
-        gameJsonStr = await invoke<string>('load_game_spec', { projectPath: selected });
+        const lockedSpec = await openProjectWithLock(selected);
+        if (lockedSpec === null) {
+          setLoading(false);
+          return;
+        }
+        gameJsonStr = lockedSpec;
       } catch (loadErr) {
         logError('Failed to load game.json', loadErr);
         setLoading(false);
@@ -559,6 +594,7 @@ function App() {
 
       // Only set projectPath AFTER successful load
       setProjectPath(selected);
+      savedSpecRef.current = gameJsonStr;
       setGameSpec(spec);
       // Load tilemap from gameSpec if present
       setCurrentTilemap(spec.tilemap as Tilemap | undefined || null);
@@ -655,6 +691,7 @@ function App() {
       const parentDir = pathParts.join('/');
 
       setProjectPath(parentDir);
+      savedSpecRef.current = selected.endsWith('/game.json') ? fileContent : null;
       setGameSpec(spec);
       setIsPlaying(false);
       setLoading(false);
@@ -1010,7 +1047,6 @@ function App() {
     if (!gameSpec || !projectPath) return;
 
     try {
-      const gameJsonPath = `${projectPath}/game.json`;
       // Include tilemap in gameSpec when saving
       const specToSave: GameSpec = {
         ...gameSpec,
@@ -1018,10 +1054,35 @@ function App() {
       };
       const gameJsonContent = JSON.stringify(specToSave, null, 2);
 
-      await invoke('write_file', {
-        path: gameJsonPath,
-        content: gameJsonContent,
-      });
+      // game.json goes through the save conflict policy in case it changed outside the editor
+      const saveGameJson = (resolution?: 'keepMine' | 'keepDisk') =>
+        invoke<SaveOutcome>('save_game_spec', {
+          projectPath,
+          gameSpecJson: gameJsonContent,
+          conflict: savedSpecRef.current === null ? null : {
+            baseSpecJson: savedSpecRef.current,
+            editedAt: lastEditRef.current,
+            origin: 'manual',
+            resolution: resolution ?? null,
+          },
+        });
+      let outcome = await saveGameJson();
+      if (outcome.decision === 'conflict') {
+        const changed = outcome.conflicts.length > 0
+          ? `\n\nBoth changed: ${outcome.conflicts.slice(0, 5).join(', ')}`
+          : '';
+        const keepMine = window.confirm(
+          `game.json was changed outside the editor since it was opened.${changed}\n\n` +
+          'OK keeps your changes; Cancel keeps the version on disk. Either way, Undo brings the other back.'
+        );
+        outcome = await saveGameJson(keepMine ? 'keepMine' : 'keepDisk');
+      }
+      if (outcome.specJson) {
+        const diskSpec = JSON.parse(outcome.specJson) as GameSpec;
+        setGameSpec(diskSpec);
+        setCurrentTilemap(diskSpec.tilemap as Tilemap | undefined || null);
+      }
+      savedSpecRef.current = outcome.specJson ?? gameJsonContent;
 
       // Ensure scripts directory exists for visual scripting data
       try {
@@ -1063,7 +1124,7 @@ function App() {
       }
 
       setHasUnsavedChanges(false);
-      setNotification('Saved');
+      setNotification(SAVE_NOTICES[outcome.decision]);
       setTimeout(() => setNotification(null), 2000);
     } catch (err) {
       logError('Failed to save project', err);
@@ -1098,6 +1159,7 @@ function App() {
 
       // Update project path to new location
       setProjectPath(selectedPath);
+      savedSpecRef.current = gameJsonContent;
       setHasUnsavedChanges(false);
       setNotification('Saved to ' + selectedPath.split('/').pop());
       setTimeout(() => setNotification(null), 2000);
@@ -1120,62 +1182,15 @@ function App() {
 
       if (!selectedPath) return;
 
-      // Create project directory
-      await invoke('create_directory', { path: selectedPath });
-
-      // Create default game.json
-      const defaultSpec: GameSpec = {
-        version: '1.0.0',
-        metadata: {
-          title: newProjectName,
-          genre: 'platformer',
-          description: 'A new game created with PromptPlay',
-        },
-        config: {
-          gravity: { x: 0, y: 1 },
-          worldBounds: { width: 800, height: 600 },
-        },
-        entities: [
-          {
-            name: 'player',
-            components: {
-              transform: { x: 400, y: 300, rotation: 0, scaleX: 1, scaleY: 1 },
-              sprite: { texture: 'default', width: 32, height: 32, tint: 0x4488ff },
-              velocity: { vx: 0, vy: 0 },
-              collider: { type: 'box', width: 32, height: 32 },
-              input: { moveSpeed: 150, jumpForce: -300 },
-            },
-            tags: ['player'],
-          },
-          {
-            name: 'ground',
-            components: {
-              transform: { x: 400, y: 580, rotation: 0, scaleX: 1, scaleY: 1 },
-              sprite: { texture: 'default', width: 800, height: 40, tint: 0x664422 },
-              collider: { type: 'box', width: 800, height: 40 },
-            },
-            tags: ['ground', 'platform'],
-          },
-        ],
-        systems: ['input', 'physics', 'collision', 'render'],
-      };
-
-      const gameJsonPath = `${selectedPath}/game.json`;
-      await invoke('write_file', {
-        path: gameJsonPath,
-        content: JSON.stringify(defaultSpec, null, 2),
+      // Scaffold game.json, assets/ and scripts/ from the platformer starter
+      await invoke('create_project', {
+        path: selectedPath,
+        template: 'platformer',
+        name: newProjectName,
       });
-
-      // Load the new project
-      setProjectPath(selectedPath);
-      setGameSpec(defaultSpec);
-      setIsPlaying(false);
-      setHasUnsavedChanges(false);
       setShowNewProjectModal(false);
       setNewProjectName('');
-      setActiveSceneId(null); // New projects start without scenes
-      setSelectedEntities(new Set());
-      initializeHistory(defaultSpec);
+      await openProject(selectedPath);
 
       setNotification('Project created');
       setTimeout(() => setNotification(null), 2000);
@@ -1438,6 +1453,7 @@ function App() {
 
       // Load the new project
       setProjectPath(selectedPath);
+      savedSpecRef.current = JSON.stringify(templateSpec, null, 2);
       setGameSpec(templateSpec);
       setIsPlaying(false);
       setHasUnsavedChanges(false);
@@ -1453,6 +1469,76 @@ function App() {
     }
   };
 
+  // promptplay:// links and "open with" files, queued by the backend until we ask for them
+  const launchHandlers = useRef({ openProject, createFromTemplate });
+  launchHandlers.current = { openProject, createFromTemplate };
+  useEffect(() => {
+    type LaunchRequest =
+      | { action: 'openProject'; path: string; fromLink: boolean }
+      | { action: 'installTemplate'; id: string }
+      | { action: 'rejected'; target: string; reason: string };
+
+    const handleLaunchRequests = async () => {
+      const requests = await invoke<LaunchRequest[]>('take_launch_requests');
+      for (const request of requests) {
+        switch (request.action) {
+          case 'openProject':
+            if (!request.fromLink || window.confirm(`A link wants to open the project at ${request.path}. Open it?`)) {
+              await launchHandlers.current.openProject(request.path);
+            }
+            break;
+          case 'installTemplate':
+            if (getTemplate(request.id) || getCustomTemplates().some(t => t.id === request.id)) {
+              await launchHandlers.current.createFromTemplate(request.id);
+            } else {
+              setError(`Unknown template '${request.id}'`);
+            }
+            break;
+          case 'rejected':
+            setError(`Could not open ${request.target}: ${request.reason}`);
+            break;
+        }
+      }
+    };
+
+    handleLaunchRequests().catch((err) => logError('Failed to handle launch requests', err));
+    const unlisten = listen('launch-requests', () => {
+      handleLaunchRequests().catch((err) => logError('Failed to handle launch requests', err));
+    });
+
+    return () => {
+      unlisten.then(fn => fn());
+    };
+  }, []);
+
+  // Global quick-capture shortcut: append a note to the project's idea backlog
+  const projectPathRef = useRef(projectPath);
+  projectPathRef.current = projectPath;
+  useEffect(() => {
+    const unlisten = listen('quick-capture', async () => {
+      const currentPath = projectPathRef.current;
+      if (!currentPath) {
+        setError('Open a project to capture ideas');
+        return;
+      }
+      const text = window.prompt('Capture an idea');
+      if (!text?.trim()) return;
+      const propose = window.confirm('Ask the AI to draft a spec change for this idea?');
+      try {
+        await invoke('capture_idea', { projectPath: currentPath, text, propose });
+        setNotification(propose ? 'Idea captured, drafting proposal' : 'Idea captured');
+        setTimeout(() => setNotification(null), 2000);
+      } catch (err) {
+        logError('Failed to capture idea', err);
+        setError('Failed to capture idea: ' + getErrorMessage(err));
+      }
+    });
+
+    return () => {
+      unlisten.then(fn => fn());
+    };
+  }, []);
+
   // Convert 2D GameSpec to 3D Game3DSpec for 3D preview
   const convertTo3DSpec = useCallback((spec: GameSpec): Game3DSpec => {
     const worldHeight = spec.config?.worldBounds?.height || 600;
@@ -1813,6 +1899,7 @@ function App() {
       const confirmed = window.confirm('You have unsaved changes. Are you sure you want to close?');
       if (!confirmed) return;
     }
+    releaseProjectLock();
     setProjectPath(null);
     setGameSpec(null);
     setSelectedEntities(new Set());
@@ -2071,6 +2158,73 @@ function App() {
     };
   }, [openProject, closeProject, saveProject, exportGame, handleUndo, handleRedo, selectedEntity, selectedEntities, handleDuplicateEntity, handleDeleteEntity, handleDuplicateSelected, handleDeleteSelected, handleSelectAll, gameSpec, projectPath, resetGame]);
 
+  // Windows opened with open_project_window come up bound to a project; load it right away
+  useEffect(() => {
+    invoke<string | null>('get_window_project')
+      .then((path) => {
+        if (path) openProject(path);
+      })
+      .catch((err) => logError('Failed to read window project', err));
+  }, [openProject]);
+
+  // Another window force-opened this project and took over its session lock
+  useEffect(() => {
+    const unlisten = getCurrentWebviewWindow().listen('project-lock-lost', () => {
+      setError('This project was opened in another PromptPlay window. Saving here may overwrite its changes; reopen the project to continue editing safely.');
+    });
+
+    return () => {
+      unlisten.then(fn => fn());
+    };
+  }, []);
+
+  // Background integrity checks catch problems from external edits before an export fails
+  useEffect(() => {
+    const unlisten = getCurrentWebviewWindow().listen<{ errors: number; warnings: number }>('project-health', (event) => {
+      const { errors, warnings } = event.payload;
+      if (errors > 0) {
+        setNotification(`Project check found ${errors} error(s) and ${warnings} warning(s)`);
+        setTimeout(() => setNotification(null), 5000);
+      }
+    });
+
+    return () => {
+      unlisten.then(fn => fn());
+    };
+  }, []);
+
+  // Art dropped into the project's inbox folder is filed into assets/ by the backend
+  useEffect(() => {
+    const unlisten = getCurrentWebviewWindow().listen<{ imported: { path: string }[]; failed: string[] }>('inbox-imported', (event) => {
+      const { imported, failed } = event.payload;
+      if (failed.length > 0) {
+        console.warn('Inbox import problems:', failed);
+      }
+      setNotification(
+        imported.length > 0
+          ? `Imported ${imported.length} asset(s) from the inbox${failed.length > 0 ? `, ${failed.length} failed` : ''}`
+          : `Inbox import failed: ${failed[0]}`
+      );
+      setTimeout(() => setNotification(null), 5000);
+    });
+
+    return () => {
+      unlisten.then(fn => fn());
+    };
+  }, []);
+
+  // Scheduled backups run in the backend; only failures need the user's attention
+  useEffect(() => {
+    const unlisten = getCurrentWebviewWindow().listen<{ projectPath: string; error: string }>('backup-failed', (event) => {
+      setNotification(`Backup failed: ${event.payload.error}`);
+      setTimeout(() => setNotification(null), 8000);
+    });
+
+    return () => {
+      unlisten.then(fn => fn());
+    };
+  }, []);
+
   return (
     <div className="flex h-screen bg-canvas text-text-primary overflow-hidden font-sans">
       {/* File Change Notification */}
@@ -1,5 +1,6 @@
 import { useState, useCallback, useRef, useEffect } from 'react';
 import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
 import ReactMarkdown from 'react-markdown';
 import remarkGfm from 'remark-gfm';
 import type { GameSpec } from '@promptplay/shared-types';
@@ -8,7 +9,7 @@ import DiffPreview from './DiffPreview';
 import { simulateAIResponse } from '../services/aiDemoSimulator';
 import { chatHistoryService, ChatSession } from '../services/ChatHistoryService';
 import { useVoiceInput } from '../hooks/useVoiceInput';
-import { logError } from '../utils/errorUtils';
+import { logError, type AppError } from '../utils/errorUtils';
 
 interface SceneContext {
   selectedEntityId?: string | null;
@@ -36,7 +37,24 @@ interface Message {
 interface AIResponse {
   content: string;
   success: boolean;
-  error: string | null;
+  error: AppError | null;
+  prompt_id: string | null;
+}
+
+/** A finding from validate_game_spec */
+interface SpecDiagnostic {
+  path: string;
+  severity: 'error' | 'warning' | 'info';
+  message: string;
+  fix?: string;
+}
+
+/** A change to a locked entity, from check_entity_locks */
+interface LockedEdit {
+  id: string;
+  name: string;
+  scene: string | null;
+  kind: 'added' | 'removed' | 'modified';
 }
 
 export default function AIPromptPanel({
@@ -50,7 +68,14 @@ export default function AIPromptPanel({
   const [prompt, setPrompt] = useState('');
   const [messages, setMessages] = useState<Message[]>([]);
   const [isLoading, setIsLoading] = useState(false);
+  // Reply text received so far while a streamed request is running
+  const [streamingText, setStreamingText] = useState('');
+  const streamIdRef = useRef<string | null>(null);
   const [pendingChanges, setPendingChanges] = useState<GameSpec | null>(null);
+  // Validator findings for the pending changes; errors block applying them
+  const [pendingDiagnostics, setPendingDiagnostics] = useState<SpecDiagnostic[]>([]);
+  const [lockedEdits, setLockedEdits] = useState<LockedEdit[]>([]);
+  const [allowLockedEdits, setAllowLockedEdits] = useState(false);
   const [showSettings, setShowSettings] = useState(false);
   const [showHistory, setShowHistory] = useState(false);
   const [apiKey, setApiKey] = useState('');
@@ -60,6 +85,8 @@ export default function AIPromptPanel({
   const [showEntitySuggestions, setShowEntitySuggestions] = useState(false);
   const [entitySuggestions, setEntitySuggestions] = useState<string[]>([]);
   const [suggestionIndex, setSuggestionIndex] = useState(0);
+  const [pendingPromptId, setPendingPromptId] = useState<string | null>(null);
+  const [contextScopeMode, setContextScopeMode] = useState<'auto' | 'scene' | 'project'>('auto');
   const messagesEndRef = useRef<HTMLDivElement>(null);
   const inputRef = useRef<HTMLTextAreaElement>(null);
 
@@ -81,6 +108,44 @@ export default function AIPromptPanel({
     },
   });
 
+  // Run proposed changes through the same validator as saved specs before they can be applied
+  useEffect(() => {
+    if (!pendingChanges) {
+      setPendingDiagnostics([]);
+      return;
+    }
+    let cancelled = false;
+    invoke<SpecDiagnostic[]>('validate_game_spec', {
+      gameSpecJson: JSON.stringify(pendingChanges),
+      projectPath,
+    })
+      .then((diagnostics) => {
+        if (!cancelled) setPendingDiagnostics(diagnostics.filter((d) => d.severity !== 'info'));
+      })
+      .catch((e) => console.error('Failed to validate changes:', e));
+    return () => {
+      cancelled = true;
+    };
+  }, [pendingChanges, projectPath]);
+
+  // Flag proposed changes to locked entities; they're undone on apply unless explicitly allowed
+  useEffect(() => {
+    setAllowLockedEdits(false);
+    if (!pendingChanges || !gameSpec) {
+      setLockedEdits([]);
+      return;
+    }
+    let cancelled = false;
+    invoke<LockedEdit[]>('check_entity_locks', { before: gameSpec, after: pendingChanges })
+      .then((edits) => {
+        if (!cancelled) setLockedEdits(edits);
+      })
+      .catch((e) => console.error('Failed to check entity locks:', e));
+    return () => {
+      cancelled = true;
+    };
+  }, [pendingChanges, gameSpec]);
+
   // Check if API key is configured
   useEffect(() => {
     const checkApiKey = async () => {
@@ -134,7 +199,7 @@ export default function AIPromptPanel({
   // Auto-scroll to bottom when messages change
   useEffect(() => {
     messagesEndRef.current?.scrollIntoView({ behavior: 'smooth' });
-  }, [messages]);
+  }, [messages, streamingText]);
 
   // Focus input when panel becomes visible
   useEffect(() => {
@@ -212,9 +277,11 @@ export default function AIPromptPanel({
       contextParts.push('');
     }
 
-    // Add entity hierarchy
-    contextParts.push(generateEntityHierarchy());
-    contextParts.push('');
+    // The full hierarchy is only worth its tokens when the whole project is in scope
+    if (contextScopeMode === 'project') {
+      contextParts.push(generateEntityHierarchy());
+      contextParts.push('');
+    }
 
     // Add game metadata
     if (gameSpec?.metadata) {
@@ -236,12 +303,19 @@ export default function AIPromptPanel({
       contextParts.push('');
     }
 
-    // Add full game spec JSON at the end
-    contextParts.push('=== Full Game Spec (JSON) ===');
-    contextParts.push(JSON.stringify(gameSpec, null, 2));
+    // The backend appends a budgeted spec summary and serves entity detail on demand
 
     return contextParts.join('\n');
-  }, [gameSpec, sceneContext, generateEntityHierarchy]);
+  }, [gameSpec, sceneContext, generateEntityHierarchy, contextScopeMode]);
+
+  // Auto narrows to the selection when there is one, otherwise the active scene
+  const buildContextScope = useCallback(() => {
+    if (contextScopeMode === 'project') return { type: 'project' };
+    if (contextScopeMode === 'auto' && sceneContext?.selectedEntityId) {
+      return { type: 'selection', entities: [sceneContext.selectedEntityId] };
+    }
+    return { type: 'activeScene', scene: sceneContext?.activeScene };
+  }, [contextScopeMode, sceneContext?.selectedEntityId, sceneContext?.activeScene]);
 
   // Handle entity reference autocomplete
   const handlePromptChange = useCallback((value: string) => {
@@ -323,15 +397,40 @@ export default function AIPromptPanel({
         .map(m => ({ role: m.role, content: m.content }));
       apiMessages.push({ role: 'user', content: prompt });
 
-      // Send to Anthropic API via Rust backend with enhanced context
-      const response = await invoke<AIResponse>('ai_send_message', {
-        messages: apiMessages,
-        gameContext: generateEnhancedContext(),
+      // Send to Anthropic API via Rust backend with enhanced context, showing the reply as it streams
+      const streamId = crypto.randomUUID();
+      streamIdRef.current = streamId;
+      setStreamingText('');
+      const unlisten = await listen<{ streamId: string; text: string }>('ai-stream-chunk', (event) => {
+        if (event.payload.streamId === streamId) {
+          setStreamingText(prev => prev + event.payload.text);
+        }
       });
+      let response: AIResponse;
+      try {
+        response = await invoke<AIResponse>('ai_send_message', {
+          messages: apiMessages,
+          gameContext: generateEnhancedContext(),
+          projectPath,
+          specContext: { gameSpec, contextScope: buildContextScope() },
+          streamId,
+        });
+      } finally {
+        unlisten();
+        streamIdRef.current = null;
+        setStreamingText('');
+      }
 
-      if (!response.success) {
+      if (response.error?.code === 'cancelled') {
+        const stoppedMessage: Message = {
+          role: 'system',
+          content: 'Response stopped.',
+          timestamp: new Date(),
+        };
+        setMessages(prev => [...prev, stoppedMessage]);
+      } else if (!response.success) {
         // If API key not set, fall back to simulated response
-        if (response.error?.includes('API key')) {
+        if (response.error?.code === 'apiKeyMissing') {
           const simResponse = await simulateAIResponse(prompt, gameSpec);
           const assistantMessage: Message = {
             role: 'assistant',
@@ -346,7 +445,7 @@ export default function AIPromptPanel({
             setPendingChanges(simResponse.updatedSpec);
           }
         } else {
-          throw new Error(response.error || 'Unknown error');
+          throw new Error(response.error?.message || 'Unknown error');
         }
       } else {
         const assistantMessage: Message = {
@@ -364,6 +463,7 @@ export default function AIPromptPanel({
         });
         if (parsedSpec) {
           setPendingChanges(parsedSpec);
+          setPendingPromptId(response.prompt_id);
         }
       }
     } catch (error) {
@@ -377,24 +477,88 @@ export default function AIPromptPanel({
     } finally {
       setIsLoading(false);
     }
-  }, [prompt, gameSpec, isLoading, messages]);
+  }, [prompt, gameSpec, isLoading, messages, projectPath, generateEnhancedContext, buildContextScope]);
 
-  const handleApplyChanges = useCallback(() => {
-    if (pendingChanges) {
-      onApplyChanges(pendingChanges);
+  const handleStop = useCallback(() => {
+    if (!streamIdRef.current) return;
+    invoke('ai_cancel_message', { streamId: streamIdRef.current }).catch((e) =>
+      console.error('Failed to stop the response:', e)
+    );
+  }, []);
+
+  // Outcomes feed the prompt history so effective prompts can be found and re-run later
+  const recordPromptOutcome = useCallback((outcome: 'applied' | 'discarded') => {
+    if (!pendingPromptId) return;
+    invoke('set_prompt_outcome', { id: pendingPromptId, outcome }).catch((e) =>
+      console.error('Failed to record prompt outcome:', e)
+    );
+    setPendingPromptId(null);
+  }, [pendingPromptId]);
+
+  const pendingErrors = pendingDiagnostics.filter((d) => d.severity === 'error').length;
+
+  const handleApplyChanges = useCallback(async () => {
+    if (pendingChanges && pendingErrors === 0) {
+      // Snap AI-proposed coordinates with the project's rounding policy before applying
+      let changes = pendingChanges;
+      let reverted: LockedEdit[] = [];
+      if (gameSpec && lockedEdits.length > 0 && !allowLockedEdits) {
+        try {
+          const result = await invoke<{ spec: GameSpec; reverted: LockedEdit[] }>('protect_locked_entities', {
+            before: gameSpec,
+            after: pendingChanges,
+          });
+          changes = result.spec;
+          reverted = result.reverted;
+        } catch (e) {
+          console.error('Failed to protect locked entities:', e);
+          return;
+        }
+      }
+      if (projectPath) {
+        try {
+          const result = await invoke<{ game_spec_json: string; rounded: number }>('round_game_spec', {
+            gameSpecJson: JSON.stringify(changes),
+            projectPath,
+          });
+          changes = JSON.parse(result.game_spec_json);
+        } catch (e) {
+          console.error('Failed to apply rounding policy:', e);
+        }
+      }
+      onApplyChanges(changes);
       setPendingChanges(null);
+      recordPromptOutcome('applied');
+      if (projectPath) {
+        invoke('run_project_hooks', { projectPath, event: 'on-ai-apply' }).catch((e) =>
+          console.error('Failed to run on-ai-apply hooks:', e)
+        );
+      }
 
       const systemMessage: Message = {
         role: 'system',
-        content: 'Changes applied successfully!',
+        content:
+          reverted.length > 0
+            ? `Changes applied. Locked entities kept unchanged: ${reverted.map((e) => e.name || e.id).join(', ')}`
+            : 'Changes applied successfully!',
         timestamp: new Date(),
       };
       setMessages(prev => [...prev, systemMessage]);
     }
-  }, [pendingChanges, onApplyChanges]);
+  }, [
+    pendingChanges,
+    pendingErrors,
+    gameSpec,
+    lockedEdits,
+    allowLockedEdits,
+    onApplyChanges,
+    projectPath,
+    recordPromptOutcome,
+  ]);
 
   const handleRejectChanges = useCallback(() => {
     setPendingChanges(null);
+    recordPromptOutcome('discarded');
 
     const systemMessage: Message = {
       role: 'system',
@@ -402,18 +566,18 @@ export default function AIPromptPanel({
       timestamp: new Date(),
     };
     setMessages(prev => [...prev, systemMessage]);
-  }, []);
+  }, [recordPromptOutcome]);
 
   const handleSaveApiKey = useCallback(async () => {
     try {
-      await invoke('ai_set_api_key', { apiKey });
+      await invoke('ai_save_api_key', { apiKey });
       setHasApiKey(true);
       setShowSettings(false);
       setApiKey('');
 
       const systemMessage: Message = {
         role: 'system',
-        content: 'API key configured successfully!',
+        content: 'API key saved to your system keychain.',
         timestamp: new Date(),
       };
       setMessages(prev => [...prev, systemMessage]);
@@ -422,6 +586,24 @@ export default function AIPromptPanel({
     }
   }, [apiKey]);
 
+  const handleDeleteApiKey = useCallback(async () => {
+    try {
+      const stillHasKey = await invoke<boolean>('ai_delete_api_key');
+      setHasApiKey(stillHasKey);
+
+      const systemMessage: Message = {
+        role: 'system',
+        content: stillHasKey
+          ? 'Saved API key removed. The key from the environment is still in use.'
+          : 'Saved API key removed.',
+        timestamp: new Date(),
+      };
+      setMessages(prev => [...prev, systemMessage]);
+    } catch (err) {
+      logError('Failed to remove API key', err);
+    }
+  }, []);
+
   const handleKeyDown = useCallback((e: React.KeyboardEvent) => {
     // Handle entity suggestion navigation
     if (showEntitySuggestions && entitySuggestions.length > 0) {
@@ -605,10 +787,28 @@ export default function AIPromptPanel({
             >
               Save
             </button>
+            {hasApiKey && (
+              <button
+                onClick={handleDeleteApiKey}
+                className="px-3 py-1.5 bg-white/10 text-white rounded text-sm hover:bg-white/20 transition-colors"
+              >
+                Remove
+              </button>
+            )}
           </div>
           <p className="text-xs text-gray-500 mt-1">
-            Or set ANTHROPIC_API_KEY environment variable
+            Stored in your system keychain. Or set the ANTHROPIC_API_KEY environment variable
           </p>
+          <h4 className="text-sm font-medium text-white mt-3 mb-2">Context Sent to AI</h4>
+          <select
+            value={contextScopeMode}
+            onChange={(e) => setContextScopeMode(e.target.value as 'auto' | 'scene' | 'project')}
+            className="w-full px-3 py-1.5 bg-white/5 border border-white/20 rounded text-sm text-white focus:outline-none focus:ring-2 focus:ring-violet-500"
+          >
+            <option value="auto">Selection, or active scene</option>
+            <option value="scene">Active scene</option>
+            <option value="project">Whole project</option>
+          </select>
         </div>
       )}
 
@@ -650,7 +850,19 @@ export default function AIPromptPanel({
           </div>
         ))}
 
-        {isLoading && (
+        {isLoading && streamingText && (
+          <div className="flex justify-start">
+            <div className="max-w-[90%] px-3 py-2 rounded-lg text-sm shadow-sm bg-[#252542] border border-white/10 text-gray-200">
+              <div className="prose prose-sm max-w-none prose-invert prose-pre:bg-black/30 prose-pre:border prose-pre:border-white/10 prose-code:text-violet-400 prose-code:bg-white/10 prose-code:px-1 prose-code:rounded">
+                <ReactMarkdown remarkPlugins={[remarkGfm]}>
+                  {streamingText}
+                </ReactMarkdown>
+              </div>
+            </div>
+          </div>
+        )}
+
+        {isLoading && !streamingText && (
           <div className="flex justify-start">
             <div className="bg-[#252542] px-3 py-2 rounded-lg border border-white/10">
               <div className="flex gap-1">
@@ -672,10 +884,42 @@ export default function AIPromptPanel({
           <div className="bg-black/30 rounded border border-white/10 overflow-hidden">
             <DiffPreview currentSpec={gameSpec} pendingSpec={pendingChanges} />
           </div>
+          {pendingDiagnostics.length > 0 && (
+            <ul className="mt-2 space-y-1 text-xs max-h-32 overflow-y-auto">
+              {pendingDiagnostics.map((d, i) => (
+                <li key={i} className={d.severity === 'error' ? 'text-red-300' : 'text-yellow-300'}>
+                  <span className="font-mono text-gray-400">{d.path || '/'}</span> {d.message}
+                  {d.fix && <span className="block text-gray-400">Fix: {d.fix}</span>}
+                </li>
+              ))}
+            </ul>
+          )}
+          {lockedEdits.length > 0 && (
+            <div className="mt-2 text-xs text-yellow-300">
+              <p>These changes touch locked entities:</p>
+              <ul className="ml-3 list-disc max-h-24 overflow-y-auto">
+                {lockedEdits.map((e) => (
+                  <li key={`${e.scene ?? ''}/${e.id}`}>
+                    {e.name || e.id} ({e.kind === 'removed' ? 'removed' : 'edited'})
+                  </li>
+                ))}
+              </ul>
+              <label className="flex items-center gap-1.5 mt-1 text-gray-300">
+                <input
+                  type="checkbox"
+                  checked={allowLockedEdits}
+                  onChange={(e) => setAllowLockedEdits(e.target.checked)}
+                />
+                Apply to locked entities too
+              </label>
+            </div>
+          )}
           <div className="flex gap-2 mt-3">
             <button
               onClick={handleApplyChanges}
-              className="flex-1 px-3 py-1.5 bg-green-600 text-white rounded text-sm font-medium hover:bg-green-500 shadow-lg shadow-green-900/20 transition-colors"
+              disabled={pendingErrors > 0}
+              title={pendingErrors > 0 ? 'Fix the errors above, or ask the AI to correct them' : undefined}
+              className="flex-1 px-3 py-1.5 bg-green-600 text-white rounded text-sm font-medium hover:bg-green-500 disabled:opacity-50 disabled:hover:bg-green-600 shadow-lg shadow-green-900/20 transition-colors"
             >
               Apply Changes
             </button>
@@ -770,13 +1014,23 @@ export default function AIPromptPanel({
                   {isListening ? <MicrophoneOffIcon size={16} /> : <MicrophoneIcon size={16} />}
                 </button>
               )}
-              <button
-                onClick={handleSubmit}
-                disabled={!prompt.trim() || isLoading}
-                className="px-4 py-2 bg-gradient-to-r from-violet-600 to-indigo-600 text-white rounded-lg text-sm font-medium hover:from-violet-500 hover:to-indigo-500 disabled:opacity-50 disabled:cursor-not-allowed shadow-lg shadow-violet-500/20 transition-all"
-              >
-                Send
-              </button>
+              {isLoading ? (
+                <button
+                  onClick={handleStop}
+                  className="px-4 py-2 bg-white/10 text-gray-200 rounded-lg text-sm font-medium hover:bg-white/20 transition-all"
+                  title="Stop generating"
+                >
+                  Stop
+                </button>
+              ) : (
+                <button
+                  onClick={handleSubmit}
+                  disabled={!prompt.trim()}
+                  className="px-4 py-2 bg-gradient-to-r from-violet-600 to-indigo-600 text-white rounded-lg text-sm font-medium hover:from-violet-500 hover:to-indigo-500 disabled:opacity-50 disabled:cursor-not-allowed shadow-lg shadow-violet-500/20 transition-all"
+                >
+                  Send
+                </button>
+              )}
             </div>
             {messages.length > 0 && (
               <button
@@ -2,6 +2,7 @@ import { useState, useEffect, useCallback } from 'react';
 import { open } from '@tauri-apps/plugin-dialog';
 import { fileSystem } from '../services/FileSystem';
 import { ImageIcon, SoundIcon, FolderIcon, RefreshIcon, PlusIcon } from './Icons';
+import { getErrorMessage } from '../utils/errorUtils';
 
 interface AssetBrowserProps {
   projectPath: string | null;
@@ -74,7 +75,7 @@ export default function AssetBrowser({ projectPath, onAssetSelect }: AssetBrowse
 
       setAssets(assetList);
     } catch (err) {
-      setError(err instanceof Error ? err.message : String(err));
+      setError(getErrorMessage(err));
       setAssets([]);
     } finally {
       setLoading(false);
@@ -120,7 +121,7 @@ export default function update(entity, dt) {
       await fileSystem.writeTextFile(fullPath, template);
       await loadAssets(currentPath);
     } catch (err) {
-      setError(`Failed to create script: ${err}`);
+      setError(`Failed to create script: ${getErrorMessage(err)}`);
       setLoading(false);
     }
   };
@@ -154,7 +155,7 @@ export default function update(entity, dt) {
 
       await loadAssets(currentPath);
     } catch (err) {
-      setError(`Failed to import sounds: ${err}`);
+      setError(`Failed to import sounds: ${getErrorMessage(err)}`);
       setLoading(false);
     }
   };
@@ -188,7 +189,7 @@ export default function update(entity, dt) {
 
       await loadAssets(currentPath);
     } catch (err) {
-      setError(`Failed to import images: ${err}`);
+      setError(`Failed to import images: ${getErrorMessage(err)}`);
       setLoading(false);
     }
   };
@@ -2,6 +2,7 @@ import { useState, useEffect, useRef, useCallback } from 'react';
 import Editor from '@monaco-editor/react';
 import { invoke } from '@tauri-apps/api/core';
 import { compilationService, CompilationError } from '../services/CompilationService';
+import { getErrorMessage } from '../utils/errorUtils';
 
 interface CodeEditorProps {
   filePath: string | null;
@@ -102,7 +103,7 @@ export default function CodeEditor({ filePath, onSave, onCompilationErrors }: Co
       setContent(fileContent);
       setIsDirty(false);
     } catch (err) {
-      setError(err instanceof Error ? err.message : String(err));
+      setError(getErrorMessage(err));
       setContent('');
     } finally {
       setLoading(false);
@@ -119,7 +120,7 @@ export default function CodeEditor({ filePath, onSave, onCompilationErrors }: Co
         onSave(filePath, content);
       }
     } catch (err) {
-      setError(err instanceof Error ? err.message : String(err));
+      setError(getErrorMessage(err));
     }
   };
 
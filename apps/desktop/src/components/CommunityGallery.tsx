@@ -1,4 +1,5 @@
 import { useState, useEffect, useCallback } from 'react';
+import { invoke } from '@tauri-apps/api/core';
 import {
   gameSharing,
   type PublishedGame,
@@ -164,8 +165,10 @@ export function CommunityGallery({ isOpen, onClose, onPlayGame }: CommunityGalle
     if (onPlayGame) {
       onPlayGame(game);
     } else {
-      // Open in new tab
-      window.open(game.embedUrl, '_blank');
+      // Open in the system browser; the backend asks for permission first
+      invoke('open_external', { target: game.embedUrl }).catch((err) => {
+        console.warn('Failed to open game:', err);
+      });
     }
   }, [onPlayGame]);
 
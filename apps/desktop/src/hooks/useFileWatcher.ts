@@ -1,6 +1,6 @@
 import { useEffect } from 'react';
 import { invoke } from '@tauri-apps/api/core';
-import { listen } from '@tauri-apps/api/event';
+import { getCurrentWebviewWindow } from '@tauri-apps/api/webviewWindow';
 import { logError } from '../utils/errorUtils';
 
 interface UseFileWatcherOptions {
@@ -21,8 +21,8 @@ export function useFileWatcher({ projectPath, onFileChanged }: UseFileWatcherOpt
       }
     };
 
-    // Listen for file change events
-    const unlisten = listen<string>('file-changed', (event) => {
+    // Listen for file change events; the backend only addresses them to the window showing the project
+    const unlisten = getCurrentWebviewWindow().listen<string>('file-changed', (event) => {
       const changedPath = event.payload;
       onFileChanged(changedPath);
     });
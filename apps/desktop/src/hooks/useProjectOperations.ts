@@ -8,6 +8,7 @@ import {
   type ExportOptions,
 } from '../services/GamePackageService';
 import { logError, getErrorMessage } from '../utils/errorUtils';
+import { openProjectWithLock } from '../utils/projectLock';
 
 interface UseProjectOperationsOptions {
   gameSpec: GameSpec | null;
@@ -280,18 +281,21 @@ export function useProjectOperations(options: UseProjectOperationsOptions) {
       onLoadingChange(true);
       onErrorChange(null);
 
-      // Use custom Rust command to avoid cyclic structure serialization issues
-      const selected = await invoke<string | null>('pick_directory', {
-        title: 'Open Game Project',
-      });
+      // Rust-side picker remembers the last location and checks for game.json
+      const selected = await invoke<string | null>('pick_project_folder');
 
       if (!selected) {
         onLoadingChange(false);
         return;
       }
 
+      const gameJsonStr = await openProjectWithLock(selected);
+      if (gameJsonStr === null) {
+        onLoadingChange(false);
+        return;
+      }
+
       onProjectPathChange(selected);
-      const gameJsonStr = await invoke<string>('load_game_spec', { projectPath: selected });
       const spec = JSON.parse(gameJsonStr) as GameSpec;
 
       onGameSpecChange(spec);
@@ -2,6 +2,9 @@ import React from 'react';
 import ReactDOM from 'react-dom/client';
 import App from './App';
 import './index.css';
+import { installCommandTiming } from './services/CommandTiming';
+
+installCommandTiming();
 
 ReactDOM.createRoot(document.getElementById('root')!).render(
   <React.StrictMode>
@@ -0,0 +1,52 @@
+/**
+ * Command Timing - Time every backend command round trip and report the durations in batches,
+ * feeding get_slow_commands_report
+ */
+import { logError } from '../utils/errorUtils';
+
+interface CommandTimingSample {
+  command: string;
+  durationMs: number;
+}
+
+type InvokeFn = (cmd: string, args?: unknown, options?: unknown) => Promise<unknown>;
+
+const REPORT_COMMAND = 'record_command_timings';
+const FLUSH_INTERVAL_MS = 5000;
+const MAX_PENDING = 500;
+
+let pending: CommandTimingSample[] = [];
+let installed = false;
+
+/**
+ * Wrap the Tauri IPC entry point that every `invoke` goes through. Plugin commands are skipped;
+ * their handlers run outside the app's invoke handler.
+ */
+export function installCommandTiming(): void {
+  const internals = (window as unknown as { __TAURI_INTERNALS__?: { invoke: InvokeFn } }).__TAURI_INTERNALS__;
+  if (installed || !internals) return;
+  installed = true;
+
+  const invoke = internals.invoke.bind(internals);
+  internals.invoke = (cmd, args, options) => {
+    if (cmd === REPORT_COMMAND || cmd.startsWith('plugin:')) {
+      return invoke(cmd, args, options);
+    }
+    const started = performance.now();
+    const record = () => {
+      if (pending.length < MAX_PENDING) {
+        pending.push({ command: cmd, durationMs: Math.round(performance.now() - started) });
+      }
+    };
+    const result = invoke(cmd, args, options);
+    result.then(record, record);
+    return result;
+  };
+
+  setInterval(() => {
+    if (pending.length === 0) return;
+    const timings = pending;
+    pending = [];
+    invoke(REPORT_COMMAND, { timings }).catch((err) => logError('Failed to report command timings', err));
+  }, FLUSH_INTERVAL_MS);
+}
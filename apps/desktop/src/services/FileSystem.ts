@@ -1,5 +1,6 @@
 import { invoke } from '@tauri-apps/api/core';
 import { copyFile } from '@tauri-apps/plugin-fs';
+import { getErrorCode, getErrorMessage } from '../utils/errorUtils';
 
 export interface FileStat {
     name: string;
@@ -40,9 +41,17 @@ export class FileSystemError extends Error {
     }
 
     static fromError(error: unknown, path?: string): FileSystemError {
-        const message = error instanceof Error ? error.message : String(error);
+        const message = getErrorMessage(error);
 
-        // Parse error message to determine error type
+        switch (getErrorCode(error)) {
+            case 'notFound':
+            case 'notAProject':
+                return new FileSystemError(message, 'NOT_FOUND', path);
+            case 'permissionDenied':
+                return new FileSystemError(message, 'PERMISSION_DENIED', path);
+        }
+
+        // Errors from the plugins and older commands only carry text
         if (message.includes('not found') || message.includes('No such file')) {
             return new FileSystemError(message, 'NOT_FOUND', path);
         }
@@ -1,3 +1,4 @@
+import { invoke } from '@tauri-apps/api/core';
 import type { GameSpec } from '@promptplay/shared-types';
 
 /**
@@ -225,6 +226,15 @@ export class GameSharingService {
           return { success: false, error: 'Authentication required' };
         }
 
+        // Uploads are public, so confirm with a native prompt (remembered if the user chooses)
+        const allowed = await invoke<boolean>('request_permission', {
+          operation: 'publishUpload',
+          detail: `"${options.title}" to ${this.config.apiUrl}`,
+        });
+        if (!allowed) {
+          return { success: false, error: 'Publishing was not permitted' };
+        }
+
         const response = await this.apiRequest<ApiResponse<{
           game: PublishedGame;
           url: string;
@@ -33,6 +33,7 @@ export type PluginPermission =
   | 'ui:menu'
   | 'ui:contextMenu'
   | 'command:register'
+  | 'lint:register'
   | 'event:subscribe'
   | 'network:fetch';
 
@@ -53,6 +54,9 @@ export interface PluginContext {
   registerMenuItem: (item: PluginMenuItem) => () => void;
   registerContextMenuItem: (item: PluginContextMenuItem) => () => void;
 
+  // Lint rules
+  registerLintRule: (rule: PluginLintRule) => () => void;
+
   // Commands
   registerCommand: (command: PluginCommand) => () => void;
   executeCommand: (commandId: string, ...args: unknown[]) => Promise<unknown>;
@@ -115,6 +119,15 @@ export interface PluginCommand {
   handler: (...args: unknown[]) => unknown | Promise<unknown>;
 }
 
+/**
+ * A Rhai lint rule run by the backend alongside the project's lint-rules/*.rhai.
+ * The script defines `fn lint(spec)` returning an array of `#{ message, path, severity }`.
+ */
+export interface PluginLintRule {
+  id: string;
+  source: string;
+}
+
 export type PluginEvent =
   | 'gameSpec:changed'
   | 'entity:created'
@@ -164,6 +177,7 @@ class PluginService {
   private menuItems: Map<string, PluginMenuItem> = new Map();
   private contextMenuItems: Map<string, PluginContextMenuItem> = new Map();
   private commands: Map<string, PluginCommand> = new Map();
+  private lintRules: Map<string, PluginLintRule> = new Map();
   private eventHandlers: Map<PluginEvent, Set<(...args: unknown[]) => void>> = new Map();
   private listeners: Set<() => void> = new Set();
 
@@ -328,6 +342,17 @@ class PluginService {
         };
       },
 
+      registerLintRule: (rule: PluginLintRule) => {
+        if (!hasPermission('lint:register')) {
+          throw new Error('Plugin does not have lint:register permission');
+        }
+        const id = `${pluginId}:${rule.id}`;
+        this.lintRules.set(id, { ...rule, id });
+        return () => {
+          this.lintRules.delete(id);
+        };
+      },
+
       registerCommand: (command: PluginCommand) => {
         if (!hasPermission('command:register')) {
           throw new Error('Plugin does not have command:register permission');
@@ -519,6 +544,11 @@ class PluginService {
         this.commands.delete(id);
       }
     }
+    for (const [id] of this.lintRules) {
+      if (id.startsWith(`${pluginId}:`)) {
+        this.lintRules.delete(id);
+      }
+    }
 
     this.notifyListeners();
   }
@@ -551,6 +581,11 @@ class PluginService {
     return Array.from(this.commands.values());
   }
 
+  /** Rules to pass as `pluginRules` to the `run_lint_rules` command */
+  getLintRules(): PluginLintRule[] {
+    return Array.from(this.lintRules.values());
+  }
+
   subscribe(listener: () => void): () => void {
     this.listeners.add(listener);
     return () => this.listeners.delete(listener);
@@ -1,3 +1,39 @@
+/**
+ * Typed error returned by backend commands (AppError in src-tauri/src/error.rs).
+ */
+export interface AppError {
+  code:
+    | 'notFound'
+    | 'permissionDenied'
+    | 'io'
+    | 'notAProject'
+    | 'invalidSpec'
+    | 'dialog'
+    | 'lock'
+    | 'apiKeyMissing'
+    | 'network'
+    | 'apiUnauthorized'
+    | 'rateLimited'
+    | 'api'
+    | 'toolLoop'
+    | 'cancelled'
+    | 'other';
+  message: string;
+  hint: string | null;
+}
+
+export function isAppError(err: unknown): err is AppError {
+  return !!err && typeof err === 'object' && 'code' in err && 'message' in err
+    && typeof err.code === 'string' && typeof err.message === 'string';
+}
+
+/**
+ * Backend error code, or null for errors that didn't come from a typed command.
+ */
+export function getErrorCode(err: unknown): AppError['code'] | null {
+  return isAppError(err) ? err.code : null;
+}
+
 /**
  * Safely extract error message from various error types.
  * Handles Tauri errors which may have cyclic references.
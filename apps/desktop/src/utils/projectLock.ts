@@ -0,0 +1,61 @@
+/**
+ * Project Session Lock
+ * Opens projects through the backend lock so two windows never save over each other
+ */
+
+import { invoke } from '@tauri-apps/api/core';
+
+interface LockInfo {
+  pid: number;
+  instance: string;
+  acquiredAt: number;
+  heartbeat: number;
+}
+
+interface OpenProjectResult {
+  game_spec_json: string | null;
+  conflict: LockInfo | null;
+}
+
+/**
+ * Open a project and take its lock. If another instance holds it, ask whether to open anyway.
+ * Returns the game.json contents, or null when the user backs out.
+ */
+export async function openProjectWithLock(projectPath: string): Promise<string | null> {
+  const result = await invoke<OpenProjectResult>('open_project', { projectPath });
+  if (!result.conflict) {
+    return result.game_spec_json;
+  }
+
+  const lastActive = new Date(result.conflict.heartbeat * 1000).toLocaleTimeString();
+  const openAnyway = window.confirm(
+    `This project is already open in another PromptPlay window (process ${result.conflict.pid}, active at ${lastActive}).\n\n` +
+      'Opening it here too can overwrite that window\'s saves. Open anyway?'
+  );
+  if (!openAnyway) {
+    return null;
+  }
+
+  const forced = await invoke<OpenProjectResult>('open_project', { projectPath, force: true });
+  return forced.game_spec_json;
+}
+
+/**
+ * Open a project for review only: no lock is taken and the backend refuses any write into it.
+ * Returns the game.json contents.
+ */
+export async function openProjectReadOnly(projectPath: string): Promise<string | null> {
+  const result = await invoke<OpenProjectResult>('open_project_readonly', { projectPath });
+  return result.game_spec_json;
+}
+
+/**
+ * Release the lock of the currently open project
+ */
+export async function releaseProjectLock(): Promise<void> {
+  try {
+    await invoke('close_project');
+  } catch (error) {
+    console.warn('Failed to release project lock:', error);
+  }
+}
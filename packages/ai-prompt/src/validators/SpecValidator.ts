@@ -22,12 +22,20 @@ const SpriteSchema = z.object({
 });
 
 const ColliderSchema = z.object({
-  type: z.enum(['box', 'circle']),
+  type: z.enum(['box', 'circle', 'polygon', 'slope']),
   width: z.number().optional(),
   height: z.number().optional(),
   radius: z.number().optional(),
   isSensor: z.boolean().optional(),
   layer: z.number().optional(),
+  offsetX: z.number().optional(),
+  offsetY: z.number().optional(),
+  vertices: z.array(z.object({ x: z.number(), y: z.number() })).min(3).optional(),
+  rise: z.enum(['left', 'right']).optional(),
+  material: z.string().optional(),
+  restitution: z.number().min(0).max(1).optional(),
+  friction: z.number().min(0).max(1).optional(),
+  oneWay: z.boolean().optional(),
 });
 
 const InputSchema = z.object({
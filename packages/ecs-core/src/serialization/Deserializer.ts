@@ -93,7 +93,8 @@ export class Deserializer {
     // Deserialize Collider
     if (components.collider) {
       addComponent(w, Collider, eid);
-      Collider.type[eid] = components.collider.type === 'box' ? 0 : 1;
+      // Polygons and slopes fall back to their bounding box here
+      Collider.type[eid] = components.collider.type === 'circle' ? 1 : 0;
       Collider.width[eid] = components.collider.width ?? 0;
       Collider.height[eid] = components.collider.height ?? 0;
       Collider.radius[eid] = components.collider.radius ?? 0;
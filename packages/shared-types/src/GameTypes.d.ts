@@ -4,12 +4,24 @@ export interface GameSpec {
     config: GameConfig;
     entities: EntitySpec[];
     systems: string[];
+    /** Object pools by name, used by the exported runtime for spawned entities */
+    pooling?: Record<string, PoolConfig>;
+}
+export interface PoolConfig {
+    /** Entity id of the pooled template */
+    template: string;
+    size: number;
+    maxSize?: number;
 }
 export interface GameMetadata {
     title: string;
     genre: 'platformer' | 'shooter' | 'puzzle';
     description: string;
 }
+export interface PhysicsMaterial {
+    restitution?: number;
+    friction?: number;
+}
 export interface GameConfig {
     gravity: {
         x: number;
@@ -19,6 +31,10 @@ export interface GameConfig {
         width: number;
         height: number;
     };
+    /** Project materials for collider.material, alongside default, ice, bouncy and sticky */
+    physicsMaterials?: Record<string, PhysicsMaterial>;
+    /** Frame-rate independent movement at the spec's px/s; set by the delta-time migration */
+    deltaTime?: boolean;
 }
 export interface EntitySpec {
     name: string;
@@ -36,6 +52,7 @@ export interface EntityComponents {
     animation?: AnimationComponent;
     camera?: CameraComponent;
     particleEmitter?: ParticleEmitterComponent;
+    spawner?: SpawnerComponent;
     audio?: AudioComponent;
     [key: string]: unknown;
 }
@@ -57,13 +74,31 @@ export interface SpriteComponent {
     tint?: string | number;
 }
 export interface ColliderComponent {
-    type: 'box' | 'circle';
+    type: 'box' | 'circle' | 'polygon' | 'slope';
     width?: number;
     height?: number;
     radius?: number;
     isSensor?: boolean;
     isStatic?: boolean;
     layer?: number;
+    /** Shape center relative to the sprite center */
+    offsetX?: number;
+    offsetY?: number;
+    /** Convex outline of a polygon collider relative to the shape center, clockwise on screen */
+    vertices?: {
+        x: number;
+        y: number;
+    }[];
+    /** High side of a slope collider */
+    rise?: 'left' | 'right';
+    /** Bundled or project physics material; restitution and friction override it */
+    material?: string;
+    /** Bounciness, 0..1 */
+    restitution?: number;
+    /** Surface friction, 0..1 */
+    friction?: number;
+    /** Only blocks bodies landing from above */
+    oneWay?: boolean;
 }
 export interface InputComponent {
     moveSpeed: number;
@@ -93,6 +128,16 @@ export interface CameraComponent {
     zoom?: number;
     bounds?: { x: number; y: number; width: number; height: number };
 }
+export interface SpawnerComponent {
+    /** Entity id of the template to copy; templates aren't placed in the world */
+    template: string;
+    /** Seconds between spawns */
+    interval: number;
+    lifetime?: number;
+    vx?: number;
+    vy?: number;
+    maxAlive?: number;
+}
 export interface ParticleEmitterComponent {
     preset?: string;
     emitRate?: number;
@@ -26,6 +26,15 @@ export interface GameSpec {
   settings?: GameSettings;
   // Tilemap support
   tilemap?: TilemapSpec;
+  /** Object pools by name, used by the exported runtime for spawned entities */
+  pooling?: Record<string, PoolConfig>;
+}
+
+export interface PoolConfig {
+  /** Entity id of the pooled template */
+  template: string;
+  size: number;
+  maxSize?: number;
 }
 
 export interface GameMetadata {
@@ -35,9 +44,18 @@ export interface GameMetadata {
   description: string;
 }
 
+export interface PhysicsMaterial {
+  restitution?: number;
+  friction?: number;
+}
+
 export interface GameConfig {
   gravity: { x: number; y: number };
   worldBounds: { width: number; height: number };
+  /** Project materials for collider.material, alongside default, ice, bouncy and sticky */
+  physicsMaterials?: Record<string, PhysicsMaterial>;
+  /** Frame-rate independent movement at the spec's px/s; set by the delta-time migration */
+  deltaTime?: boolean;
 }
 
 export interface GameSettings {
@@ -64,6 +82,7 @@ export interface EntityComponents {
   animation?: AnimationComponent;
   camera?: CameraComponent;
   particleEmitter?: ParticleEmitterComponent;
+  spawner?: SpawnerComponent;
   audio?: AudioComponent;
   // 3D components (allow any for compatibility with 3D entities)
   [key: string]: unknown;
@@ -104,13 +123,28 @@ export interface SpriteComponent {
 }
 
 export interface ColliderComponent {
-  type: 'box' | 'circle';
+  type: 'box' | 'circle' | 'polygon' | 'slope';
   width?: number;
   height?: number;
   radius?: number;
   isSensor?: boolean;
   isStatic?: boolean;
   layer?: number;
+  /** Shape center relative to the sprite center */
+  offsetX?: number;
+  offsetY?: number;
+  /** Convex outline of a polygon collider relative to the shape center, clockwise on screen */
+  vertices?: { x: number; y: number }[];
+  /** High side of a slope collider */
+  rise?: 'left' | 'right';
+  /** Bundled or project physics material; restitution and friction override it */
+  material?: string;
+  /** Bounciness, 0..1 */
+  restitution?: number;
+  /** Surface friction, 0..1 */
+  friction?: number;
+  /** Only blocks bodies landing from above */
+  oneWay?: boolean;
 }
 
 export interface InputComponent {
@@ -122,6 +156,8 @@ export interface InputComponent {
     right?: string;
     jump?: string;
   };
+  /** Co-op seat controlling this entity (1 by default); other seats' entities follow the LAN relay */
+  player?: number;
 }
 
 export interface HealthComponent {
@@ -166,6 +202,17 @@ export interface CameraComponent {
   isActive?: boolean;
 }
 
+export interface SpawnerComponent {
+  /** Entity id of the template to copy; templates aren't placed in the world */
+  template: string;
+  /** Seconds between spawns */
+  interval: number;
+  lifetime?: number;
+  vx?: number;
+  vy?: number;
+  maxAlive?: number;
+}
+
 export interface ParticleEmitterComponent {
   emitRate?: number;
   maxParticles?: number;